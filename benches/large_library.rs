@@ -0,0 +1,108 @@
+use kicad_component_importer::kicad_sym::{KicadSymbolLib, SymbolStream};
+use kicad_component_importer::mapped::MappedFile;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+const DEFAULT_SIZE_MB: usize = 200;
+
+struct PeakAlloc;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for PeakAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(current, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOC: PeakAlloc = PeakAlloc;
+
+fn write_library(path: &Path, size_mb: usize) -> usize {
+    let mut out = BufWriter::new(fs::File::create(path).unwrap());
+    out.write_all(b"(kicad_symbol_lib (version 20231120) (generator kci)\n")
+        .unwrap();
+    let mut written = 0;
+    let mut count = 0;
+    while written < size_mb * 1024 * 1024 {
+        let symbol = format!(
+            "\t(symbol \"R_{count}\" (pin_names (offset 0)) (in_bom yes) (on_board yes)\n\
+             \t\t(property \"Reference\" \"R\" (at 2.032 0 90) (effects (font (size 1.27 1.27))))\n\
+             \t\t(property \"Value\" \"{count}R\" (at 0 0 90) (effects (font (size 1.27 1.27))))\n\
+             \t\t(property \"Footprint\" \"Resistor_SMD:R_0603_1608Metric\" (at -1.778 0 90) (effects (font (size 1.27 1.27)) hide))\n\
+             \t\t(property \"Datasheet\" \"~\" (at 0 0 0) (effects (font (size 1.27 1.27)) hide))\n\
+             \t\t(symbol \"R_{count}_0_1\" (rectangle (start -1.016 -2.54) (end 1.016 2.54) (stroke (width 0.254) (type default)) (fill (type none))))\n\
+             \t\t(symbol \"R_{count}_1_1\"\n\
+             \t\t\t(pin passive line (at 0 3.81 270) (length 1.27) (name \"~\" (effects (font (size 1.27 1.27)))) (number \"1\" (effects (font (size 1.27 1.27)))))\n\
+             \t\t\t(pin passive line (at 0 -3.81 90) (length 1.27) (name \"~\" (effects (font (size 1.27 1.27)))) (number \"2\" (effects (font (size 1.27 1.27)))))))\n"
+        );
+        out.write_all(symbol.as_bytes()).unwrap();
+        written += symbol.len();
+        count += 1;
+    }
+    out.write_all(b")\n").unwrap();
+    count
+}
+
+fn measure(label: &str, run: impl FnOnce() -> usize) -> usize {
+    CURRENT.store(0, Ordering::Relaxed);
+    PEAK.store(0, Ordering::Relaxed);
+    let start = Instant::now();
+    let symbols = run();
+    println!(
+        "{:<24} {:>8} symbols {:>8.2}s {:>10.1} MB peak heap",
+        label,
+        symbols,
+        start.elapsed().as_secs_f64(),
+        PEAK.load(Ordering::Relaxed) as f64 / (1024.0 * 1024.0)
+    );
+    symbols
+}
+
+fn main() {
+    let size_mb = std::env::var("KCI_BENCH_MB")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_SIZE_MB);
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("large.kicad_sym");
+    let expected = write_library(&path, size_mb);
+    println!(
+        "library: {} MB, {} symbols",
+        fs::metadata(&path).unwrap().len() / (1024 * 1024),
+        expected
+    );
+
+    let read = measure("read + full parse", || {
+        let content = fs::read_to_string(&path).unwrap();
+        KicadSymbolLib::parse(&content)
+            .unwrap()
+            .symbols_iter()
+            .filter(|symbol| symbol.property_value("Footprint").is_some())
+            .count()
+    });
+    let mapped = measure("mmap + stream", || {
+        let file = MappedFile::open(&path).unwrap();
+        SymbolStream::new(file.text().unwrap())
+            .map(Result::unwrap)
+            .filter(|symbol| symbol.property_value("Footprint").is_some())
+            .count()
+    });
+    assert_eq!(read, expected);
+    assert_eq!(mapped, expected);
+}
@@ -0,0 +1,17 @@
+#![no_main]
+
+use kicad_component_importer::kicad_sym::{KicadSymbolLib, parse_sexps};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(input) = std::str::from_utf8(data) else {
+        return;
+    };
+    if let Ok(sexps) = parse_sexps(input) {
+        for sexp in sexps {
+            let text = sexp.to_string_pretty();
+            parse_sexps(&text).expect("pretty-printed output should parse again");
+        }
+    }
+    let _ = KicadSymbolLib::parse(input);
+});
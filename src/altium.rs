@@ -0,0 +1,909 @@
+use crate::kicad_sym::{
+    AddPolicy, BOARD_PRECISION, KicadSymError, KicadSymbolLib, SCHEMATIC_PRECISION, Symbol,
+    format_number,
+};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+const CFB_MAGIC: [u8; 8] = [0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+const SCH_MM_PER_UNIT: f64 = 0.254;
+const PCB_MM_PER_UNIT: f64 = 0.0000254;
+const SYMBOL_FONT: &str = "(effects (font (size 1.27 1.27)))";
+const HIDDEN_SYMBOL_FONT: &str = "(effects (font (size 1.27 1.27)) hide)";
+
+#[derive(Debug)]
+pub enum AltiumError {
+    Io(io::Error),
+    Symbol(KicadSymError),
+    Invalid(String),
+}
+
+impl fmt::Display for AltiumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AltiumError::Io(err) => write!(f, "io error: {}", err),
+            AltiumError::Symbol(err) => write!(f, "Altium symbol conversion error: {}", err),
+            AltiumError::Invalid(msg) => write!(f, "invalid Altium library: {}", msg),
+        }
+    }
+}
+
+impl Error for AltiumError {}
+
+impl From<io::Error> for AltiumError {
+    fn from(value: io::Error) -> Self {
+        AltiumError::Io(value)
+    }
+}
+
+impl From<KicadSymError> for AltiumError {
+    fn from(value: KicadSymError) -> Self {
+        AltiumError::Symbol(value)
+    }
+}
+
+type Record = BTreeMap<String, String>;
+type Footprint = (String, String);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AltiumConversion {
+    symbol_libs: Vec<PathBuf>,
+    footprints: Vec<PathBuf>,
+    warnings: Vec<String>,
+}
+
+impl AltiumConversion {
+    pub fn symbol_libs(&self) -> &[PathBuf] {
+        &self.symbol_libs
+    }
+
+    pub fn footprints(&self) -> &[PathBuf] {
+        &self.footprints
+    }
+
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+}
+
+pub fn is_altium_library(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("schlib") || ext.eq_ignore_ascii_case("pcblib"))
+}
+
+pub fn find_libraries(root: &Path) -> Vec<PathBuf> {
+    WalkDir::new(root)
+        .into_iter()
+        .flatten()
+        .filter(|entry| entry.file_type().is_file() && is_altium_library(entry.path()))
+        .map(|entry| entry.path().to_path_buf())
+        .collect()
+}
+
+pub fn convert_libraries(
+    libraries: &[PathBuf],
+    out_dir: &Path,
+) -> Result<AltiumConversion, AltiumError> {
+    fs::create_dir_all(out_dir)?;
+    let mut conversion = AltiumConversion::default();
+    for library in libraries {
+        let file_name = library
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let is_schlib = library
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("schlib"));
+        if is_schlib {
+            let (lib, warnings) = convert_schlib(library)?;
+            let stem = library
+                .file_stem()
+                .map(|stem| sanitize_name(&stem.to_string_lossy()))
+                .unwrap_or_else(|| "altium".to_string());
+            let path = out_dir.join(format!("{}.kicad_sym", stem));
+            fs::write(&path, lib.to_string_pretty())?;
+            conversion.symbol_libs.push(path);
+            for warning in warnings {
+                conversion
+                    .warnings
+                    .push(format!("{}: {}", file_name, warning));
+            }
+        } else {
+            let (footprints, warnings) = convert_pcblib(library)?;
+            for (name, content) in footprints {
+                let path = out_dir.join(format!("{}.kicad_mod", name));
+                fs::write(&path, content)?;
+                conversion.footprints.push(path);
+            }
+            for warning in warnings {
+                conversion
+                    .warnings
+                    .push(format!("{}: {}", file_name, warning));
+            }
+        }
+    }
+    Ok(conversion)
+}
+
+pub fn convert_schlib(path: &Path) -> Result<(KicadSymbolLib, Vec<String>), AltiumError> {
+    let mut lib = KicadSymbolLib::parse("(kicad_symbol_lib (version 20231120) (generator kci))")?;
+    let mut warnings = Vec::new();
+    for records in schlib_components(path)? {
+        match component_symbol(&records) {
+            Some(symbol) => {
+                let symbol = Symbol::parse(&symbol)?;
+                if lib.symbol(symbol.name()).is_some() {
+                    warnings.push(format!(
+                        "duplicate symbol {}, keeping the first",
+                        symbol.name()
+                    ));
+                    continue;
+                }
+                lib.add_symbol(symbol, AddPolicy::ErrorOnConflict)?;
+            }
+            None => warnings.push("skipped a component without a name".to_string()),
+        }
+    }
+    Ok((lib, warnings))
+}
+
+fn convert_pcblib(path: &Path) -> Result<(Vec<Footprint>, Vec<String>), AltiumError> {
+    let mut file = fs::File::open(path)?;
+    let mut magic = [0u8; 8];
+    if file.read(&mut magic)? < magic.len() || magic != CFB_MAGIC {
+        return Err(AltiumError::Invalid(format!(
+            "{} is not a binary PcbLib; save it as binary in Altium and try again",
+            path.display()
+        )));
+    }
+    let mut compound = cfb::open(path)?;
+    let storages: Vec<PathBuf> = compound
+        .read_root_storage()
+        .filter(|entry| entry.is_storage())
+        .map(|entry| entry.path().to_path_buf())
+        .collect();
+    let mut footprints = Vec::new();
+    let mut warnings = Vec::new();
+    for storage in storages {
+        let data_path = storage.join("Data");
+        if !compound.is_stream(&data_path) {
+            continue;
+        }
+        let mut data = Vec::new();
+        compound.open_stream(&data_path)?.read_to_end(&mut data)?;
+        if let Some((name, content)) = footprint(&data, &mut warnings) {
+            footprints.push((name, content));
+        }
+    }
+    Ok((footprints, warnings))
+}
+
+fn schlib_components(path: &Path) -> Result<Vec<Vec<Record>>, AltiumError> {
+    let bytes = fs::read(path)?;
+    if !bytes.starts_with(&CFB_MAGIC) {
+        let mut components: Vec<Vec<Record>> = Vec::new();
+        for line in String::from_utf8_lossy(&bytes).lines() {
+            let record = parse_properties(line);
+            if record.is_empty() {
+                continue;
+            }
+            if record.get("RECORD").map(String::as_str) == Some("1") {
+                components.push(Vec::new());
+            }
+            if let Some(component) = components.last_mut() {
+                component.push(record);
+            }
+        }
+        return Ok(components);
+    }
+
+    let mut compound = cfb::CompoundFile::open(io::Cursor::new(bytes))?;
+    let storages: Vec<PathBuf> = compound
+        .read_root_storage()
+        .filter(|entry| entry.is_storage())
+        .map(|entry| entry.path().to_path_buf())
+        .collect();
+    let mut components = Vec::new();
+    for storage in storages {
+        let data_path = storage.join("Data");
+        if !compound.is_stream(&data_path) {
+            continue;
+        }
+        let mut data = Vec::new();
+        compound.open_stream(&data_path)?.read_to_end(&mut data)?;
+        components.push(sch_records(&data));
+    }
+    Ok(components)
+}
+
+fn sch_records(data: &[u8]) -> Vec<Record> {
+    let mut records = Vec::new();
+    let mut reader = Reader::new(data);
+    while let Some(header) = reader.u32() {
+        let length = (header & 0x00ff_ffff) as usize;
+        let binary = header >> 24 != 0;
+        let Some(payload) = reader.bytes(length) else {
+            break;
+        };
+        let record = if binary {
+            binary_pin(payload)
+        } else {
+            Some(parse_properties(&String::from_utf8_lossy(payload)))
+        };
+        if let Some(record) = record.filter(|record| !record.is_empty()) {
+            records.push(record);
+        }
+    }
+    records
+}
+
+fn binary_pin(payload: &[u8]) -> Option<Record> {
+    let mut reader = Reader::new(payload);
+    let mut record = Record::new();
+    record.insert("RECORD".to_string(), reader.i32()?.to_string());
+    reader.skip(1)?;
+    record.insert("OWNERPARTID".to_string(), reader.i16()?.to_string());
+    record.insert("OWNERPARTDISPLAYMODE".to_string(), reader.u8()?.to_string());
+    reader.skip(4)?;
+    reader.pascal()?;
+    reader.skip(1)?;
+    record.insert("ELECTRICAL".to_string(), reader.u8()?.to_string());
+    record.insert("PINCONGLOMERATE".to_string(), reader.u8()?.to_string());
+    record.insert("PINLENGTH".to_string(), reader.i16()?.to_string());
+    record.insert("LOCATION.X".to_string(), reader.i16()?.to_string());
+    record.insert("LOCATION.Y".to_string(), reader.i16()?.to_string());
+    reader.skip(4)?;
+    record.insert("NAME".to_string(), reader.pascal()?);
+    record.insert("DESIGNATOR".to_string(), reader.pascal()?);
+    Some(record)
+}
+
+fn parse_properties(text: &str) -> Record {
+    let mut record = Record::new();
+    for field in text.trim_end_matches('\0').split('|') {
+        let Some((key, value)) = field.split_once('=') else {
+            continue;
+        };
+        let key = key.trim().to_ascii_uppercase();
+        match key.strip_prefix("%UTF8%") {
+            Some(key) => {
+                record.insert(key.to_string(), value.to_string());
+            }
+            None => {
+                record.entry(key).or_insert_with(|| value.to_string());
+            }
+        }
+    }
+    record
+}
+
+fn component_symbol(records: &[Record]) -> Option<String> {
+    let field = |record: &Record, key: &str| {
+        record
+            .get(key)
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    let component = records.iter().find(|record| record_kind(record) == "1")?;
+    let name = sanitize_name(
+        &field(component, "LIBREFERENCE").or_else(|| field(component, "DESIGNITEMID"))?,
+    );
+
+    let reference = records
+        .iter()
+        .find(|record| record_kind(record) == "34")
+        .and_then(|record| field(record, "TEXT"))
+        .map(|text| text.trim_end_matches('?').to_string())
+        .filter(|text| !text.is_empty())
+        .unwrap_or_else(|| "U".to_string());
+    let implementations: Vec<&Record> = records
+        .iter()
+        .filter(|record| {
+            record_kind(record) == "45"
+                && record
+                    .get("MODELTYPE")
+                    .is_some_and(|model| model.eq_ignore_ascii_case("PCBLIB"))
+        })
+        .collect();
+    let footprint = implementations
+        .iter()
+        .find(|record| record.get("ISCURRENT").map(String::as_str) == Some("T"))
+        .or(implementations.first())
+        .and_then(|record| field(record, "MODELNAME"))
+        .map(|name| sanitize_name(&name))
+        .unwrap_or_default();
+
+    let mut value = name.clone();
+    let mut datasheet = String::new();
+    let mut extra = Vec::new();
+    if let Some(description) = field(component, "COMPONENTDESCRIPTION") {
+        extra.push(("Description".to_string(), description));
+    }
+    for record in records.iter().filter(|record| record_kind(record) == "41") {
+        let (Some(key), Some(text)) = (field(record, "NAME"), field(record, "TEXT")) else {
+            continue;
+        };
+        if text.starts_with('=') || text == "*" {
+            continue;
+        }
+        match key.to_ascii_lowercase().as_str() {
+            "comment" | "value" => value = text,
+            "datasheet" | "componentlink1url" => datasheet = text,
+            "reference" | "footprint" | "description" => {}
+            _ => extra.push((key, text)),
+        }
+    }
+
+    let mut properties = vec![
+        ("Reference".to_string(), reference, false),
+        ("Value".to_string(), value, false),
+        ("Footprint".to_string(), footprint, true),
+        ("Datasheet".to_string(), datasheet, true),
+    ];
+    properties.extend(extra.into_iter().map(|(key, text)| (key, text, true)));
+    let mut out = format!("(symbol {} (in_bom yes) (on_board yes)", quote(&name));
+    for (idx, (key, value, hidden)) in properties.iter().enumerate() {
+        let y = if idx == 0 { 2.54 } else { -2.54 * idx as f64 };
+        out.push_str(&format!(
+            " (property {} {} (at 0 {} 0) {})",
+            quote(key),
+            quote(value),
+            mm(y),
+            if *hidden {
+                HIDDEN_SYMBOL_FONT
+            } else {
+                SYMBOL_FONT
+            }
+        ));
+    }
+
+    let mut units: BTreeMap<i64, Vec<String>> = BTreeMap::new();
+    for record in records {
+        if record
+            .get("OWNERPARTDISPLAYMODE")
+            .is_some_and(|mode| mode.trim() != "0")
+        {
+            continue;
+        }
+        let unit = record
+            .get("OWNERPARTID")
+            .and_then(|id| id.trim().parse::<i64>().ok())
+            .filter(|id| *id > 0)
+            .unwrap_or(0);
+        if let Some(item) = sch_item(record) {
+            units.entry(unit).or_default().push(item);
+        }
+    }
+    for (unit, items) in units {
+        out.push_str(&format!(
+            " (symbol {} {})",
+            quote(&format!("{}_{}_1", name, unit)),
+            items.join(" ")
+        ));
+    }
+    out.push(')');
+    Some(out)
+}
+
+fn record_kind(record: &Record) -> &str {
+    record.get("RECORD").map(String::as_str).unwrap_or_default()
+}
+
+fn sch_item(record: &Record) -> Option<String> {
+    let point =
+        |x: &str, y: &str| format!("{} {}", mm(sch_coord(record, x)), mm(sch_coord(record, y)));
+    let stroke = "(stroke (width 0) (type default))";
+    let fill = if record.get("ISSOLID").map(String::as_str) == Some("T") {
+        "(fill (type background))"
+    } else {
+        "(fill (type none))"
+    };
+    match record.get("RECORD")?.as_str() {
+        "2" => sch_pin(record),
+        "14" => Some(format!(
+            "(rectangle (start {}) (end {}) {} {})",
+            point("LOCATION.X", "LOCATION.Y"),
+            point("CORNER.X", "CORNER.Y"),
+            stroke,
+            fill
+        )),
+        "13" => Some(format!(
+            "(polyline (pts (xy {}) (xy {})) {} (fill (type none)))",
+            point("LOCATION.X", "LOCATION.Y"),
+            point("CORNER.X", "CORNER.Y"),
+            stroke
+        )),
+        kind @ ("6" | "7") => {
+            let count: usize = record.get("LOCATIONCOUNT")?.trim().parse().ok()?;
+            let mut pts: Vec<String> = (1..=count)
+                .map(|idx| format!("(xy {})", point(&format!("X{}", idx), &format!("Y{}", idx))))
+                .collect();
+            if kind == "7"
+                && let Some(first) = pts.first().cloned()
+            {
+                pts.push(first);
+            }
+            Some(format!(
+                "(polyline (pts {}) {} {})",
+                pts.join(" "),
+                stroke,
+                if kind == "7" {
+                    fill
+                } else {
+                    "(fill (type none))"
+                }
+            ))
+        }
+        "8" => Some(format!(
+            "(circle (center {}) (radius {}) {} {})",
+            point("LOCATION.X", "LOCATION.Y"),
+            mm(sch_coord(record, "RADIUS")),
+            stroke,
+            fill
+        )),
+        "12" => {
+            let (cx, cy) = (
+                sch_coord(record, "LOCATION.X"),
+                sch_coord(record, "LOCATION.Y"),
+            );
+            let radius = sch_coord(record, "RADIUS");
+            let start = angle(record, "STARTANGLE");
+            let mut end = angle(record, "ENDANGLE");
+            if end <= start {
+                end += 360.0;
+            }
+            let at = |degrees: f64| {
+                let radians = degrees.to_radians();
+                format!(
+                    "{} {}",
+                    mm(cx + radius * radians.cos()),
+                    mm(cy + radius * radians.sin())
+                )
+            };
+            Some(format!(
+                "(arc (start {}) (mid {}) (end {}) {} (fill (type none)))",
+                at(start),
+                at((start + end) / 2.0),
+                at(end),
+                stroke
+            ))
+        }
+        _ => None,
+    }
+}
+
+fn sch_pin(record: &Record) -> Option<String> {
+    let conglomerate: u32 = record
+        .get("PINCONGLOMERATE")
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(0);
+    let length = sch_coord(record, "PINLENGTH");
+    let (x, y) = (
+        sch_coord(record, "LOCATION.X"),
+        sch_coord(record, "LOCATION.Y"),
+    );
+    let (x, y, angle) = match conglomerate & 0x03 {
+        0 => (x + length, y, 180),
+        1 => (x, y + length, 270),
+        2 => (x - length, y, 0),
+        _ => (x, y - length, 90),
+    };
+    let electric = match record.get("ELECTRICAL").map(|value| value.trim()) {
+        Some("0") => "input",
+        Some("1") => "bidirectional",
+        Some("2") => "output",
+        Some("3") => "open_collector",
+        Some("5") => "tri_state",
+        Some("6") => "open_emitter",
+        Some("7") => "power_in",
+        _ => "passive",
+    };
+    let designator = record
+        .get("DESIGNATOR")
+        .map(|value| value.trim())
+        .unwrap_or_default();
+    let name = record
+        .get("NAME")
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+        .unwrap_or(designator);
+    Some(format!(
+        "(pin {} line (at {} {} {}) (length {}){} (name {} {}) (number {} {}))",
+        electric,
+        mm(x),
+        mm(y),
+        angle,
+        mm(length),
+        if conglomerate & 0x04 != 0 {
+            " hide"
+        } else {
+            ""
+        },
+        quote(name),
+        SYMBOL_FONT,
+        quote(designator),
+        SYMBOL_FONT
+    ))
+}
+
+fn sch_coord(record: &Record, key: &str) -> f64 {
+    let whole = record
+        .get(key)
+        .and_then(|value| value.trim().parse::<f64>().ok())
+        .unwrap_or(0.0);
+    let frac = record
+        .get(&format!("{}_FRAC", key))
+        .and_then(|value| value.trim().parse::<f64>().ok())
+        .unwrap_or(0.0);
+    (whole + frac / 100000.0) * SCH_MM_PER_UNIT
+}
+
+fn angle(record: &Record, key: &str) -> f64 {
+    record
+        .get(key)
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(0.0)
+}
+
+fn footprint(data: &[u8], warnings: &mut Vec<String>) -> Option<Footprint> {
+    let mut reader = Reader::new(data);
+    let header = reader.subrecord()?;
+    let name = sanitize_name(&Reader::new(header).pascal()?);
+    if name.is_empty() {
+        return None;
+    }
+
+    let mut items = Vec::new();
+    let mut through_hole = false;
+    while let Some(kind) = reader.u8() {
+        let count = match kind {
+            1 | 3 | 4 | 6 | 11 | 12 => 1,
+            2 => 6,
+            5 => 2,
+            _ => {
+                warnings.push(format!("{}: stopped at unknown record type {}", name, kind));
+                break;
+            }
+        };
+        let mut subrecords = Vec::new();
+        for _ in 0..count {
+            match reader.subrecord() {
+                Some(subrecord) => subrecords.push(subrecord),
+                None => break,
+            }
+        }
+        if subrecords.len() != count {
+            warnings.push(format!("{}: truncated record type {}", name, kind));
+            break;
+        }
+        let item = match kind {
+            1 => pcb_arc(subrecords[0]),
+            2 => pcb_pad(subrecords[0], subrecords[4], &mut through_hole),
+            4 => pcb_track(subrecords[0]),
+            _ => None,
+        };
+        items.extend(item);
+    }
+
+    let mut out = format!(
+        "(footprint {} (version 20221018) (generator kci) (layer \"F.Cu\") (attr {})",
+        quote(&name),
+        if through_hole { "through_hole" } else { "smd" }
+    );
+    out.push_str(" (fp_text reference \"REF**\" (at 0 -2.54) (layer \"F.SilkS\") (effects (font (size 1 1) (thickness 0.15))))");
+    out.push_str(&format!(
+        " (fp_text value {} (at 0 2.54) (layer \"F.Fab\") (effects (font (size 1 1) (thickness 0.15))))",
+        quote(&name)
+    ));
+    for item in items {
+        out.push(' ');
+        out.push_str(&item);
+    }
+    out.push(')');
+    Some((name, out))
+}
+
+fn pcb_pad(name: &[u8], data: &[u8], through_hole: &mut bool) -> Option<String> {
+    let number = Reader::new(name).pascal().unwrap_or_default();
+    let mut reader = Reader::new(data);
+    let layer = reader.u8()?;
+    reader.skip(12)?;
+    let (x, y) = (reader.i32()?, reader.i32()?);
+    let (width, height) = (reader.i32()?, reader.i32()?);
+    reader.skip(16)?;
+    let hole = reader.i32()?;
+    let shape = reader.u8()?;
+    reader.skip(2)?;
+    let direction = reader.f64()?;
+    let plated = reader.u8()? != 0;
+
+    let pad_shape = match shape {
+        1 if width == height => "circle",
+        1 => "oval",
+        _ => "rect",
+    };
+    let rotation = if direction.rem_euclid(360.0) != 0.0 {
+        format!(
+            " {}",
+            (direction.rem_euclid(360.0) * 1000.0).round() / 1000.0
+        )
+    } else {
+        String::new()
+    };
+    let at = format!(
+        "(at {} {}{})",
+        pcb_mm(x as f64),
+        pcb_mm(-(y as f64)),
+        rotation
+    );
+    let size = format!("(size {} {})", pcb_mm(width as f64), pcb_mm(height as f64));
+    Some(match layer {
+        1 | 32 if hole == 0 => {
+            let side = if layer == 32 { "B" } else { "F" };
+            format!(
+                "(pad {} smd {} {} {} (layers \"{side}.Cu\" \"{side}.Paste\" \"{side}.Mask\"))",
+                quote(&number),
+                pad_shape,
+                at,
+                size
+            )
+        }
+        _ => {
+            *through_hole = true;
+            format!(
+                "(pad {} {} {} {} {} (drill {}) (layers \"*.Cu\" \"*.Mask\"))",
+                quote(&number),
+                if plated { "thru_hole" } else { "np_thru_hole" },
+                pad_shape,
+                at,
+                size,
+                pcb_mm(hole as f64)
+            )
+        }
+    })
+}
+
+fn pcb_track(data: &[u8]) -> Option<String> {
+    let mut reader = Reader::new(data);
+    let layer = pcb_layer(reader.u8()?)?;
+    reader.skip(12)?;
+    let (x1, y1, x2, y2) = (reader.i32()?, reader.i32()?, reader.i32()?, reader.i32()?);
+    let width = reader.i32()?;
+    Some(format!(
+        "(fp_line (start {} {}) (end {} {}) (stroke (width {}) (type solid)) (layer \"{}\"))",
+        pcb_mm(x1 as f64),
+        pcb_mm(-(y1 as f64)),
+        pcb_mm(x2 as f64),
+        pcb_mm(-(y2 as f64)),
+        pcb_mm(width as f64),
+        layer
+    ))
+}
+
+fn pcb_arc(data: &[u8]) -> Option<String> {
+    let mut reader = Reader::new(data);
+    let layer = pcb_layer(reader.u8()?)?;
+    reader.skip(12)?;
+    let (cx, cy) = (reader.i32()? as f64, -(reader.i32()? as f64));
+    let radius = reader.i32()? as f64;
+    let start = reader.f64()?;
+    let mut end = reader.f64()?;
+    let width = pcb_mm(reader.i32()? as f64);
+    if end <= start {
+        end += 360.0;
+    }
+    if end - start >= 360.0 {
+        return Some(format!(
+            "(fp_circle (center {} {}) (end {} {}) (stroke (width {}) (type solid)) (fill none) (layer \"{}\"))",
+            pcb_mm(cx),
+            pcb_mm(cy),
+            pcb_mm(cx + radius),
+            pcb_mm(cy),
+            width,
+            layer
+        ));
+    }
+    let at = |degrees: f64| {
+        let radians = degrees.to_radians();
+        format!(
+            "{} {}",
+            pcb_mm(cx + radius * radians.cos()),
+            pcb_mm(cy - radius * radians.sin())
+        )
+    };
+    Some(format!(
+        "(fp_arc (start {}) (mid {}) (end {}) (stroke (width {}) (type solid)) (layer \"{}\"))",
+        at(start),
+        at((start + end) / 2.0),
+        at(end),
+        width,
+        layer
+    ))
+}
+
+fn pcb_layer(id: u8) -> Option<&'static str> {
+    match id {
+        1 => Some("F.Cu"),
+        32 => Some("B.Cu"),
+        33 => Some("F.SilkS"),
+        34 => Some("B.SilkS"),
+        35 => Some("F.Paste"),
+        36 => Some("B.Paste"),
+        37 => Some("F.Mask"),
+        38 => Some("B.Mask"),
+        57..=72 => Some("F.Fab"),
+        _ => None,
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn bytes(&mut self, count: usize) -> Option<&'a [u8]> {
+        let bytes = self.data.get(self.pos..self.pos.checked_add(count)?)?;
+        self.pos += count;
+        Some(bytes)
+    }
+
+    fn skip(&mut self, count: usize) -> Option<()> {
+        self.bytes(count).map(|_| ())
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.bytes(1).map(|bytes| bytes[0])
+    }
+
+    fn i16(&mut self) -> Option<i16> {
+        self.bytes(2)?.try_into().ok().map(i16::from_le_bytes)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.bytes(4)?.try_into().ok().map(u32::from_le_bytes)
+    }
+
+    fn i32(&mut self) -> Option<i32> {
+        self.bytes(4)?.try_into().ok().map(i32::from_le_bytes)
+    }
+
+    fn f64(&mut self) -> Option<f64> {
+        self.bytes(8)?.try_into().ok().map(f64::from_le_bytes)
+    }
+
+    fn pascal(&mut self) -> Option<String> {
+        let length = self.u8()? as usize;
+        let bytes = self.bytes(length)?;
+        Some(
+            String::from_utf8_lossy(bytes)
+                .trim_end_matches('\0')
+                .to_string(),
+        )
+    }
+
+    fn subrecord(&mut self) -> Option<&'a [u8]> {
+        let length = self.u32()? as usize;
+        self.bytes(length)
+    }
+}
+
+fn mm(value: f64) -> String {
+    format_number(value, SCHEMATIC_PRECISION)
+}
+
+fn pcb_mm(value: f64) -> String {
+    format_number(value * PCB_MM_PER_UNIT, BOARD_PRECISION)
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn sanitize_name(name: &str) -> String {
+    name.trim()
+        .chars()
+        .map(|ch| {
+            if matches!(ch, '/' | '\\' | ':') {
+                '_'
+            } else {
+                ch
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    fn text_record(text: &str) -> Vec<u8> {
+        let mut payload = text.as_bytes().to_vec();
+        payload.push(0);
+        let mut out = (payload.len() as u32).to_le_bytes().to_vec();
+        out.extend(payload);
+        out
+    }
+
+    fn pascal(text: &str) -> Vec<u8> {
+        let mut out = vec![text.len() as u8];
+        out.extend(text.as_bytes());
+        out
+    }
+
+    fn binary_pin_record(designator: &str, name: &str, x: i16, conglomerate: u8) -> Vec<u8> {
+        let mut payload = 2i32.to_le_bytes().to_vec();
+        payload.push(0);
+        payload.extend(1i16.to_le_bytes());
+        payload.extend([0, 0, 0, 0, 0]);
+        payload.extend(pascal(""));
+        payload.extend([0, 2, conglomerate]);
+        payload.extend(10i16.to_le_bytes());
+        payload.extend(x.to_le_bytes());
+        payload.extend(0i16.to_le_bytes());
+        payload.extend(0u32.to_le_bytes());
+        payload.extend(pascal(name));
+        payload.extend(pascal(designator));
+        let mut out = (payload.len() as u32 | 0x0100_0000).to_le_bytes().to_vec();
+        out.extend(payload);
+        out
+    }
+
+    #[test]
+    fn converts_binary_schlib_components() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("parts.SchLib");
+        let mut data = Vec::new();
+        data.extend(text_record(
+            "|RECORD=1|LIBREFERENCE=NE555|COMPONENTDESCRIPTION=Timer|PARTCOUNT=2",
+        ));
+        data.extend(text_record("|RECORD=34|OWNERINDEX=0|TEXT=U?"));
+        data.extend(text_record(
+            "|RECORD=14|OWNERPARTID=1|LOCATION.X=0|LOCATION.Y=-20|CORNER.X=40|CORNER.Y=10|ISSOLID=T",
+        ));
+        data.extend(binary_pin_record("1", "TRIG", 0, 2));
+        data.extend(text_record(
+            "|RECORD=2|OWNERPARTID=1|LOCATION.X=40|LOCATION.Y=0|PINLENGTH=20|PINCONGLOMERATE=0|ELECTRICAL=7|NAME=VCC|DESIGNATOR=8",
+        ));
+        data.extend(text_record("|RECORD=41|NAME=Manufacturer|TEXT=TI"));
+        data.extend(text_record("|RECORD=41|NAME=Comment|TEXT==Value"));
+        data.extend(text_record(
+            "|RECORD=45|MODELNAME=SOIC8|MODELTYPE=PCBLIB|ISCURRENT=T",
+        ));
+
+        let mut compound = cfb::create(&path).unwrap();
+        compound.create_storage("/NE555").unwrap();
+        compound
+            .create_stream("/NE555/Data")
+            .unwrap()
+            .write_all(&data)
+            .unwrap();
+        compound.flush().unwrap();
+        drop(compound);
+
+        let (lib, warnings) = convert_schlib(&path).unwrap();
+        assert!(warnings.is_empty());
+        let symbol = lib.symbol("NE555").unwrap();
+        assert_eq!(symbol.property_value("Reference").as_deref(), Some("U"));
+        assert_eq!(symbol.property_value("Value").as_deref(), Some("NE555"));
+        assert_eq!(symbol.property_value("Footprint").as_deref(), Some("SOIC8"));
+        assert_eq!(symbol.property_value("Manufacturer").as_deref(), Some("TI"));
+        let text = lib.to_string_pretty();
+        assert!(text.contains("\"NE555_1_1\""));
+        assert!(text.contains("(at -2.54 0 0)"));
+        assert!(text.contains("(at 15.24 0 180)"));
+        assert!(text.contains("power_in"));
+        assert!(text.contains("(start 0 -5.08)"));
+    }
+}
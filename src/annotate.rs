@@ -0,0 +1,198 @@
+use crate::importer::{ImportError, SourceInspection, inspect_source};
+use crate::kicad_sym::{KicadSymError, Sexp, Symbol, parse_one};
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolSummary {
+    name: String,
+    pins: usize,
+    properties: Vec<(String, String)>,
+}
+
+impl SymbolSummary {
+    pub fn from_symbol(symbol: &Symbol) -> Self {
+        Self {
+            name: symbol.name().to_string(),
+            pins: symbol.pin_count(),
+            properties: symbol
+                .properties()
+                .into_iter()
+                .filter(|(_, value)| !value.is_empty())
+                .collect(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn pins(&self) -> usize {
+        self.pins
+    }
+
+    pub fn properties(&self) -> &[(String, String)] {
+        &self.properties
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FootprintSummary {
+    name: String,
+    pads: usize,
+    models: Vec<String>,
+    description: Option<String>,
+}
+
+impl FootprintSummary {
+    pub fn parse(name: &str, content: &str) -> Result<Self, KicadSymError> {
+        let sexp = parse_one(content)?;
+        let mut summary = Self {
+            name: name.to_string(),
+            pads: 0,
+            models: Vec::new(),
+            description: None,
+        };
+        let Sexp::List(items) = &sexp else {
+            return Ok(summary);
+        };
+        for item in items {
+            let Sexp::List(child) = item else {
+                continue;
+            };
+            match child.as_slice() {
+                [Sexp::Atom(head), ..] if head.value() == "pad" => summary.pads += 1,
+                [Sexp::Atom(head), Sexp::Atom(path), ..] if head.value() == "model" => {
+                    summary.models.push(path.value().to_string())
+                }
+                [Sexp::Atom(head), Sexp::Atom(text), ..] if head.value() == "descr" => {
+                    summary.description = Some(text.value().to_string())
+                }
+                _ => {}
+            }
+        }
+        Ok(summary)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn pads(&self) -> usize {
+        self.pads
+    }
+
+    pub fn models(&self) -> &[String] {
+        &self.models
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+}
+
+pub fn annotate_source(source: &Path) -> Result<String, ImportError> {
+    let inspection = inspect_source(source)?;
+    let title = source
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| source.display().to_string());
+    Ok(render_markdown(&title, &inspection))
+}
+
+pub fn render_markdown(title: &str, inspection: &SourceInspection) -> String {
+    let mut out = format!("# {}\n\nVendor: {}\n", title, inspection.vendor());
+
+    out.push_str(&format!(
+        "\n## Symbols ({})\n",
+        inspection.symbol_summaries().len()
+    ));
+    for symbol in inspection.symbol_summaries() {
+        out.push_str(&format!(
+            "\n### {}\n\n- Pins: {}\n",
+            symbol.name(),
+            symbol.pins()
+        ));
+        for (name, value) in symbol.properties() {
+            out.push_str(&format!("- {}: {}\n", name, value));
+        }
+    }
+
+    out.push_str(&format!(
+        "\n## Footprints ({})\n",
+        inspection.footprint_summaries().len()
+    ));
+    if !inspection.footprint_summaries().is_empty() {
+        out.push_str("\n| Footprint | Pads | 3D models | Description |\n");
+        out.push_str("| --- | --- | --- | --- |\n");
+        for footprint in inspection.footprint_summaries() {
+            out.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                table_cell(footprint.name()),
+                footprint.pads(),
+                table_cell(&footprint.models().join(", ")),
+                table_cell(footprint.description().unwrap_or_default())
+            ));
+        }
+    }
+
+    out.push_str(&format!(
+        "\n## 3D models ({})\n",
+        inspection.step_files().len()
+    ));
+    if !inspection.step_files().is_empty() {
+        out.push('\n');
+        for path in inspection.step_files() {
+            out.push_str(&format!("- {}\n", path.display()));
+        }
+    }
+
+    if !inspection.problems().is_empty() {
+        out.push_str("\n## Problems\n\n");
+        for problem in inspection.problems() {
+            out.push_str(&format!("- {}\n", problem));
+        }
+    }
+    out
+}
+
+fn table_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarizes_symbols_and_footprints() {
+        let symbol = Symbol::parse(
+            r#"(symbol "NE555" (property "Reference" "U") (property "Datasheet" "")
+                (property "MPN" "NE555DR")
+                (symbol "NE555_1_1" (pin input line (at 0 0 0) (length 2.54) (name "TR") (number "2"))
+                    (pin output line (at 0 0 0) (length 2.54) (name "Q") (number "3"))))"#,
+        )
+        .unwrap();
+        let summary = SymbolSummary::from_symbol(&symbol);
+        assert_eq!(summary.pins(), 2);
+        assert_eq!(
+            summary.properties(),
+            [
+                ("Reference".to_string(), "U".to_string()),
+                ("MPN".to_string(), "NE555DR".to_string()),
+            ]
+        );
+
+        let footprint = FootprintSummary::parse(
+            "SOIC-8",
+            r#"(footprint "SOIC-8" (descr "SOIC, 8 | 1.27mm") (pad "1" smd rect) (pad "2" smd rect)
+                (model "${KIPRJMOD}/3d/soic8.step"))"#,
+        )
+        .unwrap();
+        assert_eq!(footprint.pads(), 2);
+        assert_eq!(footprint.models(), ["${KIPRJMOD}/3d/soic8.step"]);
+        assert_eq!(
+            table_cell(footprint.description().unwrap()),
+            "SOIC, 8 \\| 1.27mm"
+        );
+    }
+}
@@ -0,0 +1,248 @@
+use crate::importer::SymbolOverride;
+use serde::Deserialize;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug)]
+pub enum BatchError {
+    Io(io::Error),
+    Parse(toml::de::Error),
+    Invalid(String),
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BatchError::Io(err) => write!(f, "io error: {}", err),
+            BatchError::Parse(err) => write!(f, "parts manifest parse error: {}", err),
+            BatchError::Invalid(msg) => write!(f, "parts manifest error: {}", msg),
+        }
+    }
+}
+
+impl Error for BatchError {}
+
+impl From<io::Error> for BatchError {
+    fn from(value: io::Error) -> Self {
+        BatchError::Io(value)
+    }
+}
+
+impl From<toml::de::Error> for BatchError {
+    fn from(value: toml::de::Error) -> Self {
+        BatchError::Parse(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PartSource {
+    Path(PathBuf),
+    Url(String),
+    Lcsc(String),
+    Digikey(String),
+    Mouser(String),
+}
+
+impl fmt::Display for PartSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PartSource::Path(path) => write!(f, "{}", path.display()),
+            PartSource::Url(url) => write!(f, "{}", url),
+            PartSource::Lcsc(part) => write!(f, "LCSC {}", part),
+            PartSource::Digikey(part) => write!(f, "Digi-Key {}", part),
+            PartSource::Mouser(part) => write!(f, "Mouser {}", part),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestPart {
+    source: PartSource,
+    symbol_override: Option<SymbolOverride>,
+}
+
+impl ManifestPart {
+    pub fn source(&self) -> &PartSource {
+        &self.source
+    }
+
+    pub fn symbol_override(&self) -> Option<&SymbolOverride> {
+        self.symbol_override.as_ref()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartsManifest {
+    parts: Vec<ManifestPart>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawManifest {
+    #[serde(default)]
+    part: Vec<RawPart>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawPart {
+    #[serde(default)]
+    path: Option<PathBuf>,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    lcsc: Option<String>,
+    #[serde(default)]
+    digikey: Option<String>,
+    #[serde(default)]
+    mouser: Option<String>,
+    #[serde(default)]
+    symbol: Option<String>,
+    #[serde(default)]
+    rename: Option<String>,
+    #[serde(default)]
+    footprint: Option<String>,
+}
+
+impl PartsManifest {
+    pub fn load(path: &Path) -> Result<Self, BatchError> {
+        let content = fs::read_to_string(path)?;
+        Self::parse(&content, path.parent().unwrap_or(Path::new("")))
+    }
+
+    pub fn parse(input: &str, base_dir: &Path) -> Result<Self, BatchError> {
+        let raw: RawManifest = toml::from_str(input)?;
+        if raw.part.is_empty() {
+            return Err(BatchError::Invalid("no [[part]] entries".to_string()));
+        }
+        let parts = raw
+            .part
+            .into_iter()
+            .enumerate()
+            .map(|(index, part)| part.resolve(base_dir, index + 1))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { parts })
+    }
+
+    pub fn parts(&self) -> &[ManifestPart] {
+        &self.parts
+    }
+}
+
+impl RawPart {
+    fn resolve(self, base_dir: &Path, number: usize) -> Result<ManifestPart, BatchError> {
+        let mut sources = Vec::new();
+        if let Some(path) = self.path {
+            sources.push(PartSource::Path(base_dir.join(path)));
+        }
+        let text_sources = [
+            (self.url, PartSource::Url as fn(String) -> PartSource),
+            (self.lcsc, PartSource::Lcsc),
+            (self.digikey, PartSource::Digikey),
+            (self.mouser, PartSource::Mouser),
+        ];
+        for (value, source) in text_sources {
+            if let Some(value) = value {
+                sources.push(source(value.trim().to_string()));
+            }
+        }
+        let source = match <[PartSource; 1]>::try_from(sources) {
+            Ok([source]) => source,
+            Err(_) => {
+                return Err(BatchError::Invalid(format!(
+                    "part {} needs exactly one of path, url, lcsc, digikey or mouser",
+                    number
+                )));
+            }
+        };
+        if [&self.symbol, &self.rename, &self.footprint]
+            .into_iter()
+            .flatten()
+            .any(|value| value.trim().is_empty())
+        {
+            return Err(BatchError::Invalid(format!(
+                "part {} has an empty symbol, rename or footprint",
+                number
+            )));
+        }
+        let symbol_override = (self.rename.is_some() || self.footprint.is_some()).then(|| {
+            SymbolOverride::new(self.symbol.as_deref())
+                .with_rename(self.rename.as_deref())
+                .with_footprint(self.footprint.as_deref())
+        });
+        Ok(ManifestPart {
+            source,
+            symbol_override,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sources_and_overrides() {
+        let manifest = PartsManifest::parse(
+            r#"
+                [[part]]
+                path = "vendor/LM358.zip"
+
+                [[part]]
+                url = "https://example.com/NE555.zip"
+                rename = "NE555_TI"
+
+                [[part]]
+                lcsc = "C7593"
+                footprint = "SOIC-8_3.9x4.9mm_P1.27mm"
+
+                [[part]]
+                digikey = "296-1411-5-ND"
+                symbol = "NE555P"
+                rename = "NE555"
+            "#,
+            Path::new("libs"),
+        )
+        .unwrap();
+        let parts = manifest.parts();
+        assert_eq!(
+            parts[0].source(),
+            &PartSource::Path(PathBuf::from("libs/vendor/LM358.zip"))
+        );
+        assert!(parts[0].symbol_override().is_none());
+        assert_eq!(
+            parts[1].symbol_override().unwrap().rename(),
+            Some("NE555_TI")
+        );
+        assert_eq!(parts[2].source().to_string(), "LCSC C7593");
+        assert_eq!(
+            parts[2].symbol_override().unwrap().footprint(),
+            Some("SOIC-8_3.9x4.9mm_P1.27mm")
+        );
+        assert_eq!(parts[3].symbol_override().unwrap().symbol(), Some("NE555P"));
+    }
+
+    #[test]
+    fn rejects_ambiguous_or_unknown_entries() {
+        let base = Path::new(".");
+        assert!(matches!(
+            PartsManifest::parse("[[part]]\npath = \"a.zip\"\nlcsc = \"C1\"\n", base),
+            Err(BatchError::Invalid(_))
+        ));
+        assert!(matches!(
+            PartsManifest::parse("[[part]]\nrename = \"X\"\n", base),
+            Err(BatchError::Invalid(_))
+        ));
+        assert!(matches!(
+            PartsManifest::parse("[[part]]\npath = \"a.zip\"\nfootprnt = \"X\"\n", base),
+            Err(BatchError::Parse(_))
+        ));
+        assert!(matches!(
+            PartsManifest::parse("", base),
+            Err(BatchError::Invalid(_))
+        ));
+    }
+}
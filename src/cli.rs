@@ -1,16 +1,112 @@
-use crate::importer::{import_source, ImportConfig, ImportError};
-use crate::kicad_table::ensure_project_tables;
+use crate::annotate::annotate_source;
+use crate::batch::{BatchError, PartSource, PartsManifest};
+use crate::consolidate::{consolidate_footprint_libs, ConsolidateError, MergeKind};
+use crate::generate::{
+    generate_connector, generate_passive, ChipSize, ConnectorSpec, GenerateError, PassiveKind,
+};
+use crate::descriptions::DescriptionLanguage;
+use crate::enrich::{enrich_symbols, EnrichError};
+use crate::export::{export_symbol, ExportError};
+use crate::fetch::{
+    clear_cache, DigikeyCredentials, DigikeyFetcher, FetchError, LcscFetcher, LcscPart,
+    MouserCredentials, MouserFetcher, NexarClient, NexarCredentials, UrlFetcher,
+    DEFAULT_CACHE_TTL_HOURS,
+};
+use crate::importer::{
+    add_symbols_to_library, footprint_lib_name, import_sources, inspect_source, parallel_map, ImportConfig, ImportError, ImportReport,
+    ModelOverride, OversizePolicy, StepLayout, StepNaming, SymbolOverride,
+    DEFAULT_ASSOCIATION_THRESHOLD,
+    DEFAULT_COPY_JOBS, DEFAULT_MODEL_SIZE_LIMIT_MB, STDIN_SOURCE,
+};
+use crate::journal::{ImportJournal, JournalError};
+use crate::kicad_table::{
+    diagnose_project_tables, ensure_existing_project_tables, ensure_project_tables,
+    ensure_symbol_table, find_shadowed_global_libs, kicad_global_config_dir, lib_name_from_path,
+    remove_project_lib,
+    set_project_lib_disabled, TableKind,
+};
 use crate::kicad_sym::AddPolicy;
-use clap::{Args, Parser, Subcommand};
+use crate::ledger::{Ledger, LedgerError};
+use crate::lfs::{check_lfs, LfsError, LfsPolicy};
+use crate::lifecycle::{
+    deprecate, deprecation_notice, find_unapproved_uses, set_lifecycle, substitute_references,
+    Lifecycle, LifecycleError, LIFECYCLE_TAG,
+};
+use crate::lookup::{LibraryIndex, LookupError, LookupTarget};
+use crate::lsp::{serve, LspError};
+use crate::manifest::{Manifest, ManifestError};
+use crate::merge::{merge_symbol_lib, MergeError, MergeOutcome};
+use crate::model_store::{ModelStore, ModelStoreError};
+use crate::naming::{LibraryNaming, NamingError};
+use crate::pad_checks::PadPolicy;
+use crate::remote::{run_sync_command, RemoteError, RemoteTarget};
+use crate::remove::{remove_symbol, Removal, RemoveError};
+use crate::rename::{rename_footprint, rename_project, rename_symbol, RenameError};
+use crate::review::{review_interactive, ReviewError, ReviewQueue};
+use crate::signing::{
+    sign_file, signature_path, verify_file, Provenance, SigningConfig, SigningError, SigningFormat,
+};
+use crate::shell::{Session, ShellCommand, ShellError, SHELL_HELP};
+use crate::simplify::SimplifyCommand;
+use crate::staging::{Staging, StagingError, SymbolChange};
+use crate::stats::{ImportStats, StatsError};
+use crate::symbol_cache::{SymbolCache, SymbolCacheError};
+use crate::validate::{validate_libraries, ValidateError};
+use crate::tags::{load_tags, update_tags, Tag, TagError, TagStore, Tags, TAG_PROPERTY_PREFIX};
+use crate::value_rules::ValueDecoder;
+use crate::watch::{FolderWatch, WatchError};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::error::Error;
 use std::fmt;
-use std::io;
+use std::io::{self, BufRead, IsTerminal, Write};
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 const DEFAULT_SYMBOL_LIB: &str = "project_symbols.kicad_sym";
 const DEFAULT_FOOTPRINT_LIB: &str = "project_footprints.pretty";
 const DEFAULT_STEP_DIR: &str = "project_3d";
+const DEFAULT_PROJECT_NAME: &str = "project";
+const GLOBAL_CONFIG_FILE: &str = "config.toml";
+const WATCH_POLL: Duration = Duration::from_millis(500);
+const CONFIG_KEYS: &[&str] = &[
+    "symbol_lib",
+    "footprint_lib",
+    "step_dir",
+    "value_decoders",
+    "description_language",
+    "keep_description_translations",
+    "kicad_version",
+    "association_threshold",
+    "step_naming",
+    "step_layout",
+    "lfs",
+    "max_model_size_mb",
+    "oversize_models",
+    "simplify_command",
+    "tag_store",
+    "existing_symbols",
+    "pad_checks",
+    "operator",
+    "sync_command",
+    "lib_descr",
+    "lib_options",
+    "cache_ttl_hours",
+    "copy_jobs",
+    "stats",
+    "temp_dir",
+    "signing.format",
+    "signing.key",
+    "signing.verify_key",
+    "signing.identity",
+    "model_store.url",
+    "model_store.endpoint",
+    "model_store.variable",
+    "library_naming.symbol_lib",
+    "library_naming.footprint_lib",
+    "library_naming.step_dir",
+];
 
 #[derive(Parser, Debug)]
 #[command(name = "kci", version, about = "KiCad component importer")]
@@ -21,19 +117,397 @@ pub struct Cli {
 
 #[derive(Subcommand, Debug)]
 pub enum Command {
-    Import(ImportArgs),
+    Import(Box<ImportArgs>),
+    Generate(GenerateArgs),
+    Doctor(DoctorArgs),
+    Table(TableArgs),
+    Review(ReviewArgs),
+    VerifyArchive(VerifyArchiveArgs),
+    AnnotateSource(AnnotateSourceArgs),
+    Freeze,
+    Verify,
+    FetchModels,
+    Tag(TagArgs),
+    List(ListArgs),
+    Approve(ApproveArgs),
+    Deprecate(DeprecateArgs),
+    Validate,
+    Where(WhereArgs),
+    Usages(UsagesArgs),
+    Index(IndexArgs),
+    Cache(CacheArgs),
+    Fetch(FetchArgs),
+    Shell,
+    Lsp,
+    Watch(WatchArgs),
+    RenameProject(RenameProjectArgs),
+    Consolidate(ConsolidateArgs),
+    Remove(RemoveArgs),
+    Rename(RenameArgs),
+    RenameFootprint(RenameArgs),
+    Search(SearchArgs),
+    Init(InitArgs),
+    Config(ConfigArgs),
+    Export(ExportArgs),
+    Diff(DiffArgs),
+    Merge(MergeArgs),
 }
 
-#[derive(Args, Debug)]
+#[derive(Args, Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ImportArgs {
-    #[arg(value_name = "SOURCE")]
-    pub source: PathBuf,
+    #[arg(value_name = "SOURCE", required_unless_present_any = ["lcsc", "manifest", "resume"])]
+    pub sources: Vec<PathBuf>,
+    #[arg(long = "lcsc", value_name = "PART")]
+    pub lcsc: Vec<String>,
     #[arg(long, value_name = "SYMBOL_LIB")]
     pub symbol_lib: Option<PathBuf>,
     #[arg(long, value_name = "FOOTPRINT_LIB")]
     pub footprint_lib: Option<PathBuf>,
     #[arg(long, value_name = "STEP_DIR")]
     pub step_dir: Option<PathBuf>,
+    #[arg(long = "value-decoder", value_name = "DECODER")]
+    pub value_decoders: Vec<String>,
+    #[arg(long, value_name = "MAJOR")]
+    pub kicad_version: Option<u32>,
+    #[arg(long, alias = "allow-missing-footprints")]
+    pub allow_partial: bool,
+    #[arg(long)]
+    pub footprints_only: bool,
+    #[arg(long, conflicts_with = "footprints_only")]
+    pub symbols_only: bool,
+    #[arg(long)]
+    pub drop_local_properties: bool,
+    #[arg(long)]
+    pub raw_pin_text: bool,
+    #[arg(long)]
+    pub from_kicad_plugin: bool,
+    #[arg(long, value_name = "SCORE")]
+    pub association_threshold: Option<f64>,
+    #[arg(long = "model", value_name = "[FOOTPRINT=]STEP")]
+    pub models: Vec<String>,
+    #[arg(long, value_name = "SCHEME")]
+    pub step_naming: Option<String>,
+    #[arg(long, value_name = "LAYOUT")]
+    pub step_layout: Option<String>,
+    #[arg(long, value_name = "POLICY")]
+    pub lfs: Option<String>,
+    #[arg(long, value_name = "MB")]
+    pub max_model_size: Option<f64>,
+    #[arg(long, value_name = "POLICY")]
+    pub oversize: Option<String>,
+    #[arg(long, value_name = "POLICY")]
+    pub existing: Option<String>,
+    #[arg(long, value_name = "POLICY")]
+    pub pad_checks: Option<String>,
+    #[arg(long)]
+    pub dry_run: bool,
+    #[arg(long, value_name = "FILE")]
+    pub emit_patch: Option<PathBuf>,
+    #[arg(long, conflicts_with_all = ["dry_run", "emit_patch"])]
+    pub enrich: bool,
+    #[arg(long, value_name = "DIR")]
+    pub project: Option<PathBuf>,
+    #[arg(long, value_name = "NAME")]
+    pub kicad_project: Option<String>,
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["sources", "lcsc"])]
+    pub manifest: Option<PathBuf>,
+    #[arg(long)]
+    pub stats: bool,
+    #[arg(long, value_name = "DIR")]
+    pub temp_dir: Option<PathBuf>,
+    #[arg(long)]
+    pub trust_project_commands: bool,
+    #[arg(long, conflicts_with_all = ["sources", "lcsc", "manifest", "dry_run", "emit_patch"])]
+    #[serde(skip)]
+    pub resume: bool,
+    #[arg(skip)]
+    pub symbol_overrides: Vec<SymbolOverride>,
+}
+
+#[derive(Args, Debug)]
+pub struct GenerateArgs {
+    #[command(subcommand)]
+    pub target: GenerateTarget,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum GenerateTarget {
+    Passive(PassiveArgs),
+    Connector(ConnectorArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct PassiveArgs {
+    #[arg(value_name = "KIND")]
+    pub kind: String,
+    #[arg(value_name = "VALUE")]
+    pub value: String,
+    #[arg(value_name = "SIZE")]
+    pub size: String,
+    #[arg(long, value_name = "SYMBOL_LIB")]
+    pub symbol_lib: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct ConnectorArgs {
+    #[arg(long, value_name = "COUNT", required_unless_present = "csv")]
+    pub pins: Option<usize>,
+    #[arg(long, value_name = "MM", default_value_t = 2.54)]
+    pub pitch: f64,
+    #[arg(long, value_name = "ROWS", default_value_t = 1)]
+    pub rows: usize,
+    #[arg(long, value_name = "CSV", conflicts_with = "pins")]
+    pub csv: Option<PathBuf>,
+    #[arg(long, value_name = "NAME")]
+    pub name: Option<String>,
+    #[arg(long, value_name = "SYMBOL_LIB")]
+    pub symbol_lib: Option<PathBuf>,
+    #[arg(long, value_name = "FOOTPRINT_LIB")]
+    pub footprint_lib: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct DoctorArgs {
+    #[arg(long)]
+    pub fix: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct VerifyArchiveArgs {
+    #[arg(value_name = "SOURCE")]
+    pub source: PathBuf,
+}
+
+#[derive(Args, Debug)]
+pub struct AnnotateSourceArgs {
+    #[arg(value_name = "SOURCE")]
+    pub source: PathBuf,
+    #[arg(long, short, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct TagArgs {
+    #[arg(value_name = "SYMBOL")]
+    pub symbol: String,
+    #[arg(value_name = "KEY=VALUE")]
+    pub tags: Vec<String>,
+    #[arg(long = "remove", value_name = "KEY")]
+    pub remove: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct ListArgs {
+    #[arg(long = "tag", value_name = "KEY=VALUE")]
+    pub tags: Vec<String>,
+    #[arg(long, value_name = "STATE")]
+    pub lifecycle: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct WhereArgs {
+    #[arg(value_name = "FOOTPRINT|MODEL")]
+    pub target: String,
+}
+
+#[derive(Args, Debug)]
+pub struct UsagesArgs {
+    #[arg(value_name = "SYMBOL")]
+    pub symbol: String,
+}
+
+#[derive(Args, Debug)]
+pub struct IndexArgs {
+    #[arg(long)]
+    pub rebuild: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct DeprecateArgs {
+    #[arg(value_name = "SYMBOL")]
+    pub symbol: String,
+    #[arg(long, value_name = "SYMBOL")]
+    pub replacement: Option<String>,
+    #[arg(long, requires = "replacement")]
+    pub substitute: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct ApproveArgs {
+    #[arg(value_name = "SYMBOL", required = true)]
+    pub symbols: Vec<String>,
+    #[arg(long, value_name = "STATE")]
+    pub state: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct ReviewArgs {
+    #[arg(long)]
+    pub list: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct CacheArgs {
+    #[command(subcommand)]
+    pub action: CacheAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CacheAction {
+    Clear,
+}
+
+#[derive(Args, Debug)]
+pub struct WatchArgs {
+    #[arg(value_name = "DIR")]
+    pub dir: PathBuf,
+}
+
+#[derive(Args, Debug)]
+pub struct RenameProjectArgs {
+    #[arg(value_name = "NAME")]
+    pub name: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct RenameArgs {
+    #[arg(value_name = "OLD")]
+    pub old: String,
+    #[arg(value_name = "NEW")]
+    pub new: String,
+}
+
+#[derive(Args, Debug)]
+pub struct SearchArgs {
+    #[arg(value_name = "TEXT")]
+    pub text: String,
+}
+
+#[derive(Args, Debug)]
+pub struct InitArgs {
+    #[arg(long, value_name = "NAME")]
+    pub name: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub action: ConfigAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigAction {
+    Get(ConfigGetArgs),
+    Set(ConfigSetArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct ConfigGetArgs {
+    #[arg(value_name = "KEY")]
+    pub key: String,
+}
+
+#[derive(Args, Debug)]
+pub struct ConfigSetArgs {
+    #[arg(value_name = "KEY")]
+    pub key: String,
+    #[arg(value_name = "VALUE")]
+    pub value: String,
+}
+
+#[derive(Args, Debug)]
+pub struct RemoveArgs {
+    #[arg(value_name = "SYMBOL")]
+    pub symbol: String,
+    #[arg(long)]
+    pub with_footprint: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct ExportArgs {
+    #[arg(value_name = "SYMBOL")]
+    pub symbol: String,
+    #[arg(short, long, value_name = "ZIP")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct DiffArgs {
+    #[arg(value_name = "SOURCE", required = true)]
+    pub sources: Vec<PathBuf>,
+    #[arg(long, value_name = "POLICY")]
+    pub existing: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct MergeArgs {
+    #[arg(value_name = "SYMBOL_LIB")]
+    pub lib: PathBuf,
+    #[arg(long, value_name = "POLICY")]
+    pub existing: Option<String>,
+    #[arg(long = "policy", value_name = "SYMBOL=POLICY")]
+    pub policies: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct ConsolidateArgs {
+    #[arg(value_name = "LIB")]
+    pub libs: Vec<String>,
+    #[arg(long, value_name = "FOOTPRINT_LIB")]
+    pub into: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct FetchArgs {
+    #[arg(long = "digikey", value_name = "PN", required_unless_present = "mouser")]
+    pub digikey: Vec<String>,
+    #[arg(long = "mouser", value_name = "PN")]
+    pub mouser: Vec<String>,
+    #[arg(long, value_name = "ID", requires = "digikey_client_secret")]
+    pub digikey_client_id: Option<String>,
+    #[arg(long, value_name = "SECRET", requires = "digikey_client_id")]
+    pub digikey_client_secret: Option<String>,
+    #[arg(long, value_name = "KEY")]
+    pub mouser_api_key: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct TableArgs {
+    #[command(subcommand)]
+    pub action: TableAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TableAction {
+    Remove(TableEntryArgs),
+    Disable(TableEntryArgs),
+    Enable(TableEntryArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct TableEntryArgs {
+    #[arg(value_name = "NICKNAME")]
+    pub name: String,
+    #[arg(long, value_enum, default_value_t = TableSelection::Both)]
+    pub table: TableSelection,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum TableSelection {
+    Symbol,
+    Footprint,
+    Both,
+}
+
+impl TableSelection {
+    fn kinds(self) -> &'static [TableKind] {
+        match self {
+            TableSelection::Symbol => &[TableKind::Symbol],
+            TableSelection::Footprint => &[TableKind::Footprint],
+            TableSelection::Both => &[TableKind::Symbol, TableKind::Footprint],
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -44,6 +518,154 @@ pub struct ConfigFile {
     footprint_lib: Option<PathBuf>,
     #[serde(default)]
     step_dir: Option<PathBuf>,
+    #[serde(default)]
+    value_decoders: Option<Vec<String>>,
+    #[serde(default)]
+    description_language: Option<String>,
+    #[serde(default)]
+    keep_description_translations: Option<bool>,
+    #[serde(default)]
+    kicad_version: Option<u32>,
+    #[serde(default)]
+    association_threshold: Option<f64>,
+    #[serde(default)]
+    step_naming: Option<String>,
+    #[serde(default)]
+    step_layout: Option<String>,
+    #[serde(default)]
+    lfs: Option<String>,
+    #[serde(default)]
+    max_model_size_mb: Option<f64>,
+    #[serde(default)]
+    oversize_models: Option<String>,
+    #[serde(default)]
+    simplify_command: Option<String>,
+    #[serde(default)]
+    tag_store: Option<String>,
+    #[serde(default)]
+    existing_symbols: Option<String>,
+    #[serde(default)]
+    pad_checks: Option<String>,
+    #[serde(default)]
+    operator: Option<String>,
+    #[serde(default)]
+    sync_command: Option<String>,
+    #[serde(default)]
+    lib_descr: Option<String>,
+    #[serde(default)]
+    lib_options: Option<String>,
+    #[serde(default)]
+    cache_ttl_hours: Option<u64>,
+    #[serde(default)]
+    copy_jobs: Option<usize>,
+    #[serde(default)]
+    stats: Option<bool>,
+    #[serde(default)]
+    temp_dir: Option<PathBuf>,
+    #[serde(default)]
+    signing: Option<SigningSection>,
+    #[serde(default)]
+    model_store: Option<ModelStoreSection>,
+    #[serde(default)]
+    library_naming: Option<LibraryNaming>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SigningSection {
+    format: String,
+    key: PathBuf,
+    #[serde(default)]
+    verify_key: Option<PathBuf>,
+    #[serde(default)]
+    identity: Option<String>,
+}
+
+impl SigningSection {
+    fn resolve(&self) -> Result<SigningConfig, ConfigError> {
+        let format = self
+            .format
+            .parse::<SigningFormat>()
+            .map_err(ConfigError::Invalid)?;
+        Ok(SigningConfig::new(format, self.key.clone())
+            .with_verify_key(self.verify_key.clone())
+            .with_identity(self.identity.clone()))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelStoreSection {
+    url: String,
+    #[serde(default)]
+    endpoint: Option<String>,
+    #[serde(default)]
+    variable: Option<String>,
+}
+
+impl ModelStoreSection {
+    fn resolve(&self) -> Result<ModelStore, ConfigError> {
+        let store =
+            ModelStore::new(&self.url).map_err(|err| ConfigError::Invalid(err.to_string()))?;
+        Ok(store
+            .with_endpoint(self.endpoint.clone())
+            .with_variable(self.variable.clone()))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct GlobalConfig {
+    #[serde(default)]
+    pub digikey: Option<DigikeyCredentials>,
+    #[serde(default)]
+    pub mouser: Option<MouserCredentials>,
+    #[serde(default)]
+    pub nexar: Option<NexarCredentials>,
+    #[serde(default)]
+    pub library_naming: Option<LibraryNaming>,
+    #[serde(default)]
+    pub sync_command: Option<String>,
+    #[serde(default)]
+    pub simplify_command: Option<String>,
+}
+
+impl GlobalConfig {
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let raw = std::fs::read_to_string(path)?;
+        Ok(toml::from_str(&raw)?)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), ConfigError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let data = toml::to_string_pretty(self)?;
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        io::Write::write_all(&mut options.open(path)?, data.as_bytes())?;
+        Ok(())
+    }
+}
+
+pub fn global_config_path() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("KCI_CONFIG_HOME") {
+        return Some(PathBuf::from(dir).join(GLOBAL_CONFIG_FILE));
+    }
+    let base = if cfg!(target_os = "windows") {
+        PathBuf::from(std::env::var_os("APPDATA")?)
+    } else if cfg!(target_os = "macos") {
+        PathBuf::from(std::env::var_os("HOME")?)
+            .join("Library")
+            .join("Application Support")
+    } else if let Some(dir) = std::env::var_os("XDG_CONFIG_HOME") {
+        PathBuf::from(dir)
+    } else {
+        PathBuf::from(std::env::var_os("HOME")?).join(".config")
+    };
+    Some(base.join("kci").join(GLOBAL_CONFIG_FILE))
 }
 
 impl ConfigFile {
@@ -58,26 +680,182 @@ impl ConfigFile {
         Ok(())
     }
 
+    fn get(&self, key: &str) -> Result<Option<toml::Value>, ConfigError> {
+        check_config_key(key)?;
+        let mut value = toml::Value::try_from(self)?;
+        for part in key.split('.') {
+            match value.get(part) {
+                Some(inner) => value = inner.clone(),
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(value))
+    }
+
+    fn set(&self, key: &str, raw: &str) -> Result<Self, ConfigError> {
+        check_config_key(key)?;
+        let parsed = toml::from_str::<toml::Table>(&format!("value = {}", raw))
+            .ok()
+            .and_then(|mut table| table.remove("value"));
+        let mut last_error = None;
+        for candidate in parsed.into_iter().chain([toml::Value::String(raw.to_string())]) {
+            let mut root = toml::Value::try_from(self)?;
+            let mut table = &mut root;
+            let (parents, leaf) = key.rsplit_once('.').map_or((None, key), |(parents, leaf)| {
+                (Some(parents), leaf)
+            });
+            for part in parents.into_iter().flat_map(|parents| parents.split('.')) {
+                let toml::Value::Table(inner) = table else {
+                    break;
+                };
+                table = inner
+                    .entry(part)
+                    .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+            }
+            if let toml::Value::Table(inner) = table {
+                inner.insert(leaf.to_string(), candidate);
+            }
+            match root.try_into::<ConfigFile>() {
+                Ok(file) => return Ok(file),
+                Err(err) => last_error = Some(err),
+            }
+        }
+        Err(ConfigError::Invalid(format!(
+            "{} = {}: {}",
+            key,
+            raw,
+            last_error.map(|err| err.message().to_string()).unwrap_or_default()
+        )))
+    }
+
     fn from_import_config(config: &ImportConfig) -> Self {
         Self {
             symbol_lib: Some(config.symbol_lib().to_path_buf()),
             footprint_lib: Some(config.footprint_lib().to_path_buf()),
             step_dir: Some(config.step_dir().to_path_buf()),
+            value_decoders: if config.value_decoders().is_empty() {
+                None
+            } else {
+                Some(
+                    config
+                        .value_decoders()
+                        .iter()
+                        .map(|decoder| decoder.name().to_string())
+                        .collect(),
+                )
+            },
+            description_language: config
+                .description_language()
+                .map(|choice| choice.language().to_string()),
+            keep_description_translations: config
+                .description_language()
+                .filter(|choice| choice.keep_translations())
+                .map(|_| true),
+            kicad_version: config.kicad_version(),
+            association_threshold: if config.association_threshold()
+                == DEFAULT_ASSOCIATION_THRESHOLD
+            {
+                None
+            } else {
+                Some(config.association_threshold())
+            },
+            step_naming: if config.step_naming() == StepNaming::Original {
+                None
+            } else {
+                Some(config.step_naming().name().to_string())
+            },
+            step_layout: if config.step_layout() == StepLayout::Flat {
+                None
+            } else {
+                Some(config.step_layout().name().to_string())
+            },
+            lfs: if config.lfs_policy() == LfsPolicy::Warn {
+                None
+            } else {
+                Some(config.lfs_policy().name().to_string())
+            },
+            max_model_size_mb: if config.model_size_limit_mb() == DEFAULT_MODEL_SIZE_LIMIT_MB {
+                None
+            } else {
+                Some(config.model_size_limit_mb())
+            },
+            oversize_models: if config.oversize_policy() == OversizePolicy::Warn {
+                None
+            } else {
+                Some(config.oversize_policy().name().to_string())
+            },
+            simplify_command: None,
+            tag_store: if config.tag_store() == TagStore::Property {
+                None
+            } else {
+                Some(config.tag_store().name().to_string())
+            },
+            existing_symbols: if config.existing_symbols() == AddPolicy::ReplaceExisting {
+                None
+            } else {
+                Some(config.existing_symbols().name().to_string())
+            },
+            pad_checks: if config.pad_policy() == PadPolicy::Warn {
+                None
+            } else {
+                Some(config.pad_policy().name().to_string())
+            },
+            operator: config.operator().map(str::to_string),
+            sync_command: None,
+            lib_descr: config.lib_descr().map(str::to_string),
+            lib_options: config.lib_options().map(str::to_string),
+            cache_ttl_hours: if config.cache_ttl_hours() == DEFAULT_CACHE_TTL_HOURS {
+                None
+            } else {
+                Some(config.cache_ttl_hours())
+            },
+            copy_jobs: if config.copy_jobs() == DEFAULT_COPY_JOBS {
+                None
+            } else {
+                Some(config.copy_jobs())
+            },
+            stats: config.stats().then_some(true),
+            temp_dir: config.temp_dir().map(Path::to_path_buf),
+            signing: None,
+            model_store: None,
+            library_naming: None,
         }
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct ImportPlan {
-    source: PathBuf,
+    sources: Vec<PathBuf>,
+    lcsc_parts: Vec<LcscPart>,
     config: ImportConfig,
     config_path: PathBuf,
     created_config: bool,
+    dry_run: bool,
+    emit_patch: Option<PathBuf>,
+    enrich: bool,
+    kicad_project: Option<String>,
+    lcsc_cache_hits: usize,
 }
 
 impl ImportPlan {
-    pub fn source(&self) -> &Path {
-        &self.source
+    pub fn sources(&self) -> &[PathBuf] {
+        &self.sources
+    }
+
+    pub fn lcsc_parts(&self) -> &[LcscPart] {
+        &self.lcsc_parts
+    }
+
+    pub fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    pub fn emit_patch(&self) -> Option<&Path> {
+        self.emit_patch.as_deref()
+    }
+
+    pub fn enrich(&self) -> bool {
+        self.enrich
     }
 
     pub fn config(&self) -> &ImportConfig {
@@ -91,6 +869,10 @@ impl ImportPlan {
     pub fn created_config(&self) -> bool {
         self.created_config
     }
+
+    pub fn kicad_project(&self) -> Option<&str> {
+        self.kicad_project.as_deref()
+    }
 }
 
 #[derive(Debug)]
@@ -98,6 +880,7 @@ pub enum ConfigError {
     Io(io::Error),
     Parse(toml::de::Error),
     Write(toml::ser::Error),
+    Invalid(String),
 }
 
 impl fmt::Display for ConfigError {
@@ -106,6 +889,7 @@ impl fmt::Display for ConfigError {
             ConfigError::Io(err) => write!(f, "io error: {}", err),
             ConfigError::Parse(err) => write!(f, "config parse error: {}", err),
             ConfigError::Write(err) => write!(f, "config write error: {}", err),
+            ConfigError::Invalid(msg) => write!(f, "invalid config: {}", msg),
         }
     }
 }
@@ -135,6 +919,36 @@ pub enum CliError {
     Config(ConfigError),
     Import(ImportError),
     Table(crate::kicad_table::TableError),
+    Generate(GenerateError),
+    Review(ReviewError),
+    Ledger(LedgerError),
+    Manifest(ManifestError),
+    Signing(SigningError),
+    Staging(StagingError),
+    Remote(RemoteError),
+    ModelStore(ModelStoreError),
+    Lfs(LfsError),
+    Tag(TagError),
+    Lifecycle(LifecycleError),
+    Lookup(LookupError),
+    Fetch(FetchError),
+    Shell(ShellError),
+    Lsp(LspError),
+    Enrich(EnrichError),
+    Watch(WatchError),
+    Batch(BatchError),
+    Rename(RenameError),
+    Consolidate(ConsolidateError),
+    Remove(RemoveError),
+    SymbolCache(SymbolCacheError),
+    Stats(StatsError),
+    Validate(ValidateError),
+    Journal(JournalError),
+    Export(ExportError),
+    Merge(MergeError),
+    Tampered(usize),
+    Unapproved(usize),
+    LibraryProblems(usize),
 }
 
 impl fmt::Display for CliError {
@@ -143,6 +957,42 @@ impl fmt::Display for CliError {
             CliError::Config(err) => write!(f, "{}", err),
             CliError::Import(err) => write!(f, "{}", err),
             CliError::Table(err) => write!(f, "{}", err),
+            CliError::Generate(err) => write!(f, "{}", err),
+            CliError::Review(err) => write!(f, "{}", err),
+            CliError::Ledger(err) => write!(f, "{}", err),
+            CliError::Manifest(err) => write!(f, "{}", err),
+            CliError::Signing(err) => write!(f, "{}", err),
+            CliError::Staging(err) => write!(f, "{}", err),
+            CliError::Remote(err) => write!(f, "{}", err),
+            CliError::ModelStore(err) => write!(f, "{}", err),
+            CliError::Lfs(err) => write!(f, "{}", err),
+            CliError::Tag(err) => write!(f, "{}", err),
+            CliError::Lifecycle(err) => write!(f, "{}", err),
+            CliError::Lookup(err) => write!(f, "{}", err),
+            CliError::Fetch(err) => write!(f, "{}", err),
+            CliError::Shell(err) => write!(f, "{}", err),
+            CliError::Lsp(err) => write!(f, "{}", err),
+            CliError::Enrich(err) => write!(f, "{}", err),
+            CliError::Watch(err) => write!(f, "{}", err),
+            CliError::Batch(err) => write!(f, "{}", err),
+            CliError::Rename(err) => write!(f, "{}", err),
+            CliError::Consolidate(err) => write!(f, "{}", err),
+            CliError::Remove(err) => write!(f, "{}", err),
+            CliError::SymbolCache(err) => write!(f, "{}", err),
+            CliError::Stats(err) => write!(f, "{}", err),
+            CliError::Validate(err) => write!(f, "{}", err),
+            CliError::Journal(err) => write!(f, "{}", err),
+            CliError::Export(err) => write!(f, "{}", err),
+            CliError::Merge(err) => write!(f, "{}", err),
+            CliError::Tampered(count) => {
+                write!(f, "{} library files changed since the last freeze", count)
+            }
+            CliError::Unapproved(count) => {
+                write!(f, "{} unapproved parts used in schematics", count)
+            }
+            CliError::LibraryProblems(count) => {
+                write!(f, "{} problems found in the project libraries", count)
+            }
         }
     }
 }
@@ -167,144 +1017,2494 @@ impl From<crate::kicad_table::TableError> for CliError {
     }
 }
 
-pub fn resolve_import(args: ImportArgs, cwd: &Path) -> Result<ImportPlan, ConfigError> {
-    let config_path = cwd.join(".kci_config");
-    let config_file = if config_path.exists() {
-        Some(ConfigFile::load(&config_path)?)
-    } else {
-        None
-    };
+impl From<GenerateError> for CliError {
+    fn from(value: GenerateError) -> Self {
+        CliError::Generate(value)
+    }
+}
 
-    let defaults = default_config(cwd);
+impl From<ReviewError> for CliError {
+    fn from(value: ReviewError) -> Self {
+        CliError::Review(value)
+    }
+}
 
-    let symbol_lib = resolve_path(
-        &args.symbol_lib,
-        config_file
-            .as_ref()
-            .and_then(|config| config.symbol_lib.as_ref()),
-        defaults.symbol_lib(),
-    );
-    let footprint_lib = resolve_path(
-        &args.footprint_lib,
-        config_file
-            .as_ref()
-            .and_then(|config| config.footprint_lib.as_ref()),
-        defaults.footprint_lib(),
-    );
-    let step_dir = resolve_path(
-        &args.step_dir,
-        config_file.as_ref().and_then(|config| config.step_dir.as_ref()),
-        defaults.step_dir(),
-    );
+impl From<LedgerError> for CliError {
+    fn from(value: LedgerError) -> Self {
+        CliError::Ledger(value)
+    }
+}
 
-    let config = ImportConfig::new(symbol_lib, footprint_lib, step_dir);
+impl From<ManifestError> for CliError {
+    fn from(value: ManifestError) -> Self {
+        CliError::Manifest(value)
+    }
+}
 
-    let mut created_config = false;
-    if config_file.is_none() {
-        let file = ConfigFile::from_import_config(&config);
-        file.write(&config_path)?;
-        created_config = true;
+impl From<SigningError> for CliError {
+    fn from(value: SigningError) -> Self {
+        CliError::Signing(value)
     }
+}
 
-    Ok(ImportPlan {
-        source: args.source,
-        config,
-        config_path,
+impl From<StagingError> for CliError {
+    fn from(value: StagingError) -> Self {
+        CliError::Staging(value)
+    }
+}
+
+impl From<RemoteError> for CliError {
+    fn from(value: RemoteError) -> Self {
+        CliError::Remote(value)
+    }
+}
+
+impl From<ModelStoreError> for CliError {
+    fn from(value: ModelStoreError) -> Self {
+        CliError::ModelStore(value)
+    }
+}
+
+impl From<LfsError> for CliError {
+    fn from(value: LfsError) -> Self {
+        CliError::Lfs(value)
+    }
+}
+
+impl From<TagError> for CliError {
+    fn from(value: TagError) -> Self {
+        CliError::Tag(value)
+    }
+}
+
+impl From<LifecycleError> for CliError {
+    fn from(value: LifecycleError) -> Self {
+        CliError::Lifecycle(value)
+    }
+}
+
+impl From<LookupError> for CliError {
+    fn from(value: LookupError) -> Self {
+        CliError::Lookup(value)
+    }
+}
+
+impl From<FetchError> for CliError {
+    fn from(value: FetchError) -> Self {
+        CliError::Fetch(value)
+    }
+}
+
+impl From<ShellError> for CliError {
+    fn from(value: ShellError) -> Self {
+        CliError::Shell(value)
+    }
+}
+
+impl From<LspError> for CliError {
+    fn from(value: LspError) -> Self {
+        CliError::Lsp(value)
+    }
+}
+
+impl From<EnrichError> for CliError {
+    fn from(value: EnrichError) -> Self {
+        CliError::Enrich(value)
+    }
+}
+
+impl From<WatchError> for CliError {
+    fn from(value: WatchError) -> Self {
+        CliError::Watch(value)
+    }
+}
+
+impl From<BatchError> for CliError {
+    fn from(value: BatchError) -> Self {
+        CliError::Batch(value)
+    }
+}
+
+impl From<RenameError> for CliError {
+    fn from(value: RenameError) -> Self {
+        CliError::Rename(value)
+    }
+}
+
+impl From<ConsolidateError> for CliError {
+    fn from(value: ConsolidateError) -> Self {
+        CliError::Consolidate(value)
+    }
+}
+
+impl From<RemoveError> for CliError {
+    fn from(value: RemoveError) -> Self {
+        CliError::Remove(value)
+    }
+}
+
+impl From<SymbolCacheError> for CliError {
+    fn from(value: SymbolCacheError) -> Self {
+        CliError::SymbolCache(value)
+    }
+}
+
+impl From<StatsError> for CliError {
+    fn from(value: StatsError) -> Self {
+        CliError::Stats(value)
+    }
+}
+
+impl From<ValidateError> for CliError {
+    fn from(value: ValidateError) -> Self {
+        CliError::Validate(value)
+    }
+}
+
+impl From<JournalError> for CliError {
+    fn from(value: JournalError) -> Self {
+        CliError::Journal(value)
+    }
+}
+
+impl From<ExportError> for CliError {
+    fn from(value: ExportError) -> Self {
+        CliError::Export(value)
+    }
+}
+
+impl From<MergeError> for CliError {
+    fn from(value: MergeError) -> Self {
+        CliError::Merge(value)
+    }
+}
+
+pub fn resolve_import(args: ImportArgs, cwd: &Path) -> Result<ImportPlan, ConfigError> {
+    let stdin_sources = args
+        .sources
+        .iter()
+        .filter(|source| source.as_os_str() == STDIN_SOURCE)
+        .count();
+    if stdin_sources > 1 {
+        return Err(ConfigError::Invalid(format!(
+            "{} (stdin) can only be given once",
+            STDIN_SOURCE
+        )));
+    }
+    let overrides = ConfigOverrides {
+        symbol_lib: args.symbol_lib,
+        footprint_lib: args.footprint_lib,
+        step_dir: args.step_dir,
+        value_decoders: args.value_decoders,
+        kicad_version: args.kicad_version,
+        association_threshold: args.association_threshold,
+        step_naming: args.step_naming,
+        step_layout: args.step_layout,
+        lfs: args.lfs,
+        max_model_size: args.max_model_size,
+        oversize: args.oversize,
+        existing: args.existing,
+        pad_checks: args.pad_checks,
+        kicad_project: args.kicad_project,
+        temp_dir: args.temp_dir,
+        trust_project_commands: args.trust_project_commands,
+    };
+    let staged = args.dry_run || args.emit_patch.is_some();
+    let resolved = load_config(overrides, cwd, !staged)?;
+    for key in &resolved.untrusted_commands {
+        eprintln!(
+            "warning: ignoring {} from .kci_config, pass --trust-project-commands to run it",
+            key
+        );
+    }
+    if resolved.config.oversize_policy() == OversizePolicy::Simplify
+        && resolved.config.simplify_command().is_none()
+    {
+        return Err(ConfigError::Invalid(
+            "oversize policy simplify needs a trusted simplify_command, pass --trust-project-commands"
+                .to_string(),
+        ));
+    }
+    let models = args
+        .models
+        .iter()
+        .map(|model| model.parse::<ModelOverride>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(ConfigError::Invalid)?;
+    let lcsc_parts = args
+        .lcsc
+        .iter()
+        .map(|part| part.parse::<LcscPart>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(ConfigError::Invalid)?;
+    let stats = args.stats || resolved.config.stats();
+    Ok(ImportPlan {
+        sources: args.sources,
+        lcsc_parts,
+        config: resolved
+            .config
+            .with_stats(stats)
+            .with_allow_partial(args.allow_partial)
+            .with_footprints_only(args.footprints_only)
+            .with_symbols_only(args.symbols_only)
+            .with_keep_local_properties(!args.drop_local_properties)
+            .with_clean_pin_text(!args.raw_pin_text)
+            .with_from_kicad_plugin(args.from_kicad_plugin)
+            .with_model_overrides(models)
+            .with_symbol_overrides(args.symbol_overrides),
+        config_path: resolved.config_path,
+        created_config: resolved.created_config,
+        dry_run: args.dry_run,
+        emit_patch: args.emit_patch,
+        enrich: args.enrich,
+        kicad_project: resolved.kicad_project,
+        lcsc_cache_hits: 0,
+    })
+}
+
+#[derive(Debug, Default)]
+struct ConfigOverrides {
+    symbol_lib: Option<PathBuf>,
+    footprint_lib: Option<PathBuf>,
+    step_dir: Option<PathBuf>,
+    value_decoders: Vec<String>,
+    kicad_version: Option<u32>,
+    association_threshold: Option<f64>,
+    step_naming: Option<String>,
+    step_layout: Option<String>,
+    lfs: Option<String>,
+    max_model_size: Option<f64>,
+    oversize: Option<String>,
+    existing: Option<String>,
+    pad_checks: Option<String>,
+    kicad_project: Option<String>,
+    temp_dir: Option<PathBuf>,
+    trust_project_commands: bool,
+}
+
+struct ResolvedConfig {
+    config: ImportConfig,
+    config_path: PathBuf,
+    created_config: bool,
+    kicad_project: Option<String>,
+    untrusted_commands: Vec<&'static str>,
+}
+
+fn resolve_config(overrides: ConfigOverrides, cwd: &Path) -> Result<ResolvedConfig, ConfigError> {
+    load_config(overrides, cwd, true)
+}
+
+fn load_config(
+    overrides: ConfigOverrides,
+    cwd: &Path,
+    write_missing: bool,
+) -> Result<ResolvedConfig, ConfigError> {
+    let config_path = cwd.join(".kci_config");
+    let config_file = if config_path.exists() {
+        Some(ConfigFile::load(&config_path)?)
+    } else {
+        None
+    };
+
+    let kicad_project = choose_kicad_project(cwd, overrides.kicad_project.as_deref())?;
+    let naming = library_naming(config_file.as_ref())?;
+    let defaults = default_config(kicad_project.as_ref().map(|(name, _)| name.as_str()), &naming)?;
+
+    let symbol_lib = resolve_path(
+        &overrides.symbol_lib,
+        config_file
+            .as_ref()
+            .and_then(|config| config.symbol_lib.as_ref()),
+        defaults.symbol_lib(),
+    );
+    let footprint_lib = resolve_path(
+        &overrides.footprint_lib,
+        config_file
+            .as_ref()
+            .and_then(|config| config.footprint_lib.as_ref()),
+        defaults.footprint_lib(),
+    );
+    let step_dir = resolve_path(
+        &overrides.step_dir,
+        config_file.as_ref().and_then(|config| config.step_dir.as_ref()),
+        defaults.step_dir(),
+    );
+
+    let decoder_names = if overrides.value_decoders.is_empty() {
+        config_file
+            .as_ref()
+            .and_then(|config| config.value_decoders.clone())
+            .unwrap_or_default()
+    } else {
+        overrides.value_decoders
+    };
+    let value_decoders = decoder_names
+        .iter()
+        .map(|name| name.parse::<ValueDecoder>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(ConfigError::Invalid)?;
+
+    let description_language = match config_file
+        .as_ref()
+        .and_then(|config| config.description_language.as_deref())
+    {
+        Some(language) => Some(
+            DescriptionLanguage::new(language)
+                .map_err(ConfigError::Invalid)?
+                .with_keep_translations(
+                    config_file
+                        .as_ref()
+                        .and_then(|config| config.keep_description_translations)
+                        .unwrap_or(false),
+                ),
+        ),
+        None => None,
+    };
+
+    let kicad_version = overrides
+        .kicad_version
+        .or_else(|| config_file.as_ref().and_then(|config| config.kicad_version));
+
+    let association_threshold = overrides
+        .association_threshold
+        .or_else(|| {
+            config_file
+                .as_ref()
+                .and_then(|config| config.association_threshold)
+        })
+        .unwrap_or(DEFAULT_ASSOCIATION_THRESHOLD);
+    if !(0.0..=1.0).contains(&association_threshold) {
+        return Err(ConfigError::Invalid(format!(
+            "association threshold must be between 0 and 1: {}",
+            association_threshold
+        )));
+    }
+
+    let step_naming = match overrides
+        .step_naming
+        .or_else(|| config_file.as_ref().and_then(|config| config.step_naming.clone()))
+    {
+        Some(name) => name.parse::<StepNaming>().map_err(ConfigError::Invalid)?,
+        None => StepNaming::Original,
+    };
+    let step_layout = match overrides
+        .step_layout
+        .or_else(|| config_file.as_ref().and_then(|config| config.step_layout.clone()))
+    {
+        Some(name) => name.parse::<StepLayout>().map_err(ConfigError::Invalid)?,
+        None => StepLayout::Flat,
+    };
+    let lfs_policy = match overrides
+        .lfs
+        .or_else(|| config_file.as_ref().and_then(|config| config.lfs.clone()))
+    {
+        Some(name) => name.parse::<LfsPolicy>().map_err(ConfigError::Invalid)?,
+        None => LfsPolicy::Warn,
+    };
+    let model_size_limit_mb = overrides
+        .max_model_size
+        .or_else(|| config_file.as_ref().and_then(|config| config.max_model_size_mb))
+        .unwrap_or(DEFAULT_MODEL_SIZE_LIMIT_MB);
+    if model_size_limit_mb < 0.0 {
+        return Err(ConfigError::Invalid(format!(
+            "max model size must not be negative: {}",
+            model_size_limit_mb
+        )));
+    }
+    let oversize_policy = match overrides.oversize.or_else(|| {
+        config_file
+            .as_ref()
+            .and_then(|config| config.oversize_models.clone())
+    }) {
+        Some(name) => name.parse::<OversizePolicy>().map_err(ConfigError::Invalid)?,
+        None => OversizePolicy::Warn,
+    };
+    let mut untrusted_commands = Vec::new();
+    let global = match global_config_path() {
+        Some(path) => GlobalConfig::load(&path)?,
+        None => GlobalConfig::default(),
+    };
+    let project_simplify = config_file
+        .as_ref()
+        .and_then(|config| config.simplify_command.clone());
+    if oversize_policy == OversizePolicy::Simplify
+        && project_simplify.is_none()
+        && global.simplify_command.is_none()
+    {
+        return Err(ConfigError::Invalid(
+            "oversize policy simplify needs a simplify_command".to_string(),
+        ));
+    }
+    let simplify_command = match project_simplify {
+        Some(_) if !overrides.trust_project_commands => {
+            untrusted_commands.push("simplify_command");
+            global.simplify_command
+        }
+        Some(template) => Some(template),
+        None => global.simplify_command,
+    };
+    let simplify_command = match simplify_command {
+        Some(template) => Some(
+            SimplifyCommand::new(&template).map_err(|err| ConfigError::Invalid(err.to_string()))?,
+        ),
+        None => None,
+    };
+
+    let signing = match config_file
+        .as_ref()
+        .and_then(|config| config.signing.as_ref())
+    {
+        Some(section) => Some(section.resolve()?),
+        None => None,
+    };
+    let model_store = match config_file
+        .as_ref()
+        .and_then(|config| config.model_store.as_ref())
+    {
+        Some(section) => Some(section.resolve()?),
+        None => None,
+    };
+    if model_store.is_some() && RemoteTarget::from_path(&step_dir).is_some() {
+        return Err(ConfigError::Invalid(
+            "model_store needs a local step_dir".to_string(),
+        ));
+    }
+    let tag_store = match config_file
+        .as_ref()
+        .and_then(|config| config.tag_store.as_deref())
+    {
+        Some(name) => name.parse::<TagStore>().map_err(ConfigError::Invalid)?,
+        None => TagStore::Property,
+    };
+    let existing_symbols = match overrides.existing.or_else(|| {
+        config_file
+            .as_ref()
+            .and_then(|config| config.existing_symbols.clone())
+    }) {
+        Some(name) => name.parse::<AddPolicy>().map_err(ConfigError::Invalid)?,
+        None => AddPolicy::ReplaceExisting,
+    };
+    let pad_policy = match overrides.pad_checks.or_else(|| {
+        config_file
+            .as_ref()
+            .and_then(|config| config.pad_checks.clone())
+    }) {
+        Some(name) => name.parse::<PadPolicy>().map_err(ConfigError::Invalid)?,
+        None => PadPolicy::Warn,
+    };
+    let operator = config_file
+        .as_ref()
+        .and_then(|config| config.operator.clone());
+    let sync_command = match config_file
+        .as_ref()
+        .and_then(|config| config.sync_command.clone())
+    {
+        Some(_) if !overrides.trust_project_commands => {
+            untrusted_commands.push("sync_command");
+            global.sync_command
+        }
+        Some(command) => Some(command),
+        None => global.sync_command,
+    };
+    let lib_descr = config_file
+        .as_ref()
+        .and_then(|config| config.lib_descr.clone());
+    let lib_options = config_file
+        .as_ref()
+        .and_then(|config| config.lib_options.clone());
+    let cache_ttl_hours = config_file
+        .as_ref()
+        .and_then(|config| config.cache_ttl_hours)
+        .unwrap_or(DEFAULT_CACHE_TTL_HOURS);
+    let copy_jobs = config_file
+        .as_ref()
+        .and_then(|config| config.copy_jobs)
+        .unwrap_or(DEFAULT_COPY_JOBS);
+    let stats = config_file
+        .as_ref()
+        .and_then(|config| config.stats)
+        .unwrap_or(false);
+    let temp_dir = overrides
+        .temp_dir
+        .or_else(|| config_file.as_ref().and_then(|config| config.temp_dir.clone()));
+
+    let config = ImportConfig::new(symbol_lib, footprint_lib, step_dir)
+        .with_value_decoders(value_decoders)
+        .with_description_language(description_language)
+        .with_kicad_version(kicad_version)
+        .with_association_threshold(association_threshold)
+        .with_step_naming(step_naming)
+        .with_step_layout(step_layout)
+        .with_signing(signing)
+        .with_operator(operator)
+        .with_sync_command(sync_command)
+        .with_lib_descr(lib_descr)
+        .with_lib_options(lib_options)
+        .with_model_store(model_store)
+        .with_lfs_policy(lfs_policy)
+        .with_model_size_limit_mb(model_size_limit_mb)
+        .with_oversize_policy(oversize_policy)
+        .with_simplify_command(simplify_command)
+        .with_tag_store(tag_store)
+        .with_existing_symbols(existing_symbols)
+        .with_pad_policy(pad_policy)
+        .with_cache_ttl_hours(cache_ttl_hours)
+        .with_copy_jobs(copy_jobs)
+        .with_stats(stats)
+        .with_temp_dir(temp_dir);
+
+    let mut created_config = false;
+    if config_file.is_none() && write_missing {
+        let file = ConfigFile::from_import_config(&config);
+        file.write(&config_path)?;
+        created_config = true;
+    }
+
+    Ok(ResolvedConfig {
+        config,
+        config_path,
         created_config,
+        kicad_project: kicad_project
+            .filter(|(_, ambiguous)| *ambiguous)
+            .map(|(name, _)| name),
+        untrusted_commands,
+    })
+}
+
+fn default_config(
+    kicad_project: Option<&str>,
+    naming: &LibraryNaming,
+) -> Result<ImportConfig, ConfigError> {
+    let builtin = match kicad_project {
+        Some(project_name) => ImportConfig::new(
+            PathBuf::from(format!("{}_symbols.kicad_sym", project_name)),
+            PathBuf::from(format!("{}_footprints.pretty", project_name)),
+            PathBuf::from(format!("{}_step", project_name)),
+        ),
+        None => ImportConfig::new(
+            PathBuf::from(DEFAULT_SYMBOL_LIB),
+            PathBuf::from(DEFAULT_FOOTPRINT_LIB),
+            PathBuf::from(DEFAULT_STEP_DIR),
+        ),
+    };
+    let project = kicad_project.unwrap_or(DEFAULT_PROJECT_NAME);
+    let invalid = |err: NamingError| ConfigError::Invalid(err.to_string());
+    let symbol_lib = naming
+        .symbol_lib(project)
+        .map_err(invalid)?
+        .unwrap_or_else(|| builtin.symbol_lib().to_path_buf());
+    let footprint_lib = naming
+        .footprint_lib(project)
+        .map_err(invalid)?
+        .unwrap_or_else(|| builtin.footprint_lib().to_path_buf());
+    let step_dir = naming
+        .step_dir(project)
+        .map_err(invalid)?
+        .unwrap_or_else(|| builtin.step_dir().to_path_buf());
+    Ok(builtin.with_paths(symbol_lib, footprint_lib, step_dir))
+}
+
+fn library_naming(config_file: Option<&ConfigFile>) -> Result<LibraryNaming, ConfigError> {
+    if let Some(naming) = config_file.and_then(|config| config.library_naming.clone()) {
+        return Ok(naming);
+    }
+    let Some(global_path) = global_config_path() else {
+        return Ok(LibraryNaming::default());
+    };
+    Ok(GlobalConfig::load(&global_path)?
+        .library_naming
+        .unwrap_or_default())
+}
+
+fn resolve_path(
+    cli_value: &Option<PathBuf>,
+    config_value: Option<&PathBuf>,
+    default: &Path,
+) -> PathBuf {
+    if let Some(value) = cli_value {
+        return value.clone();
+    }
+    if let Some(value) = config_value {
+        return value.clone();
+    }
+    default.to_path_buf()
+}
+
+fn project_root() -> Result<PathBuf, CliError> {
+    let current = std::env::current_dir().map_err(ConfigError::from)?;
+    Ok(find_project_root(&current))
+}
+
+fn import_root(current: &Path, project: Option<&Path>) -> PathBuf {
+    find_project_root(&project.map_or_else(|| current.to_path_buf(), |project| current.join(project)))
+}
+
+pub fn find_project_root(start: &Path) -> PathBuf {
+    if start.join(".kci_config").exists() || has_kicad_pro(start) {
+        return start.to_path_buf();
+    }
+    if let Some(ancestor) = start.ancestors().skip(1).find(|dir| has_kicad_pro(dir)) {
+        return ancestor.to_path_buf();
+    }
+    let children: Vec<PathBuf> = std::fs::read_dir(start)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir() && has_kicad_pro(path))
+        .collect();
+    match children.as_slice() {
+        [child] => child.clone(),
+        _ => start.to_path_buf(),
+    }
+}
+
+fn has_kicad_pro(dir: &Path) -> bool {
+    std::fs::read_dir(dir).into_iter().flatten().flatten().any(|entry| {
+        let path = entry.path();
+        path.is_file() && path.extension().and_then(|value| value.to_str()) == Some("kicad_pro")
     })
 }
 
-fn default_config(cwd: &Path) -> ImportConfig {
-    if let Some(project_name) = project_name_from_kicad_pro(cwd) {
-        return ImportConfig::new(
-            PathBuf::from(format!("{}_symbols.kicad_sym", project_name)),
-            PathBuf::from(format!("{}_footprints.pretty", project_name)),
-            PathBuf::from(format!("{}_step", project_name)),
-        );
+fn kicad_pro_names(cwd: &Path) -> Vec<String> {
+    let mut names = Vec::new();
+    for entry in std::fs::read_dir(cwd).into_iter().flatten().flatten() {
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+        if path.extension().and_then(|value| value.to_str()) != Some("kicad_pro") {
+            continue;
+        }
+        if let Some(stem) = path.file_stem().and_then(|value| value.to_str()) {
+            names.push(stem.to_string());
+        }
+    }
+    names.sort();
+    names
+}
+
+fn choose_kicad_project(
+    cwd: &Path,
+    requested: Option<&str>,
+) -> Result<Option<(String, bool)>, ConfigError> {
+    let names = kicad_pro_names(cwd);
+    let ambiguous = names.len() > 1;
+    if let Some(requested) = requested {
+        let requested = requested.strip_suffix(".kicad_pro").unwrap_or(requested);
+        if !names.iter().any(|name| name == requested) {
+            return Err(ConfigError::Invalid(format!(
+                "no {}.kicad_pro in {} (found: {})",
+                requested,
+                cwd.display(),
+                if names.is_empty() { "none".to_string() } else { names.join(", ") }
+            )));
+        }
+        return Ok(Some((requested.to_string(), ambiguous)));
+    }
+    if !ambiguous {
+        return Ok(names.into_iter().next().map(|name| (name, false)));
+    }
+    let dir_name = cwd.file_name().and_then(|value| value.to_str());
+    if let Some(dir_name) = dir_name
+        && names.iter().any(|name| name == dir_name)
+    {
+        return Ok(Some((dir_name.to_string(), true)));
+    }
+    if io::stdin().is_terminal() {
+        let chosen = prompt_kicad_project(&names, &mut io::stdin().lock(), &mut io::stderr())?;
+        return match chosen {
+            Some(name) => Ok(Some((name, true))),
+            None => Err(ConfigError::Invalid(format!(
+                "several .kicad_pro files in {}; pass --kicad-project <NAME>",
+                cwd.display()
+            ))),
+        };
+    }
+    Ok(names.into_iter().next().map(|name| (name, true)))
+}
+
+fn prompt_kicad_project<R: BufRead, W: Write>(
+    names: &[String],
+    input: &mut R,
+    output: &mut W,
+) -> io::Result<Option<String>> {
+    writeln!(output, "several KiCad projects found:")?;
+    for (index, name) in names.iter().enumerate() {
+        writeln!(output, "  {}) {}.kicad_pro", index + 1, name)?;
+    }
+    loop {
+        write!(output, "project [1-{}]: ", names.len())?;
+        output.flush()?;
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let answer = line.trim();
+        let answer = answer.strip_suffix(".kicad_pro").unwrap_or(answer);
+        let chosen = match answer.parse::<usize>() {
+            Ok(number) => number.checked_sub(1).and_then(|index| names.get(index)),
+            Err(_) => names.iter().find(|name| *name == answer),
+        };
+        if let Some(name) = chosen {
+            return Ok(Some(name.clone()));
+        }
+    }
+}
+
+pub fn run(cli: Cli) -> Result<(), CliError> {
+    match cli.command {
+        Command::Import(args) => run_import(*args),
+        Command::Generate(args) => match args.target {
+            GenerateTarget::Passive(args) => run_generate_passive(args),
+            GenerateTarget::Connector(args) => run_generate_connector(args),
+        },
+        Command::Doctor(args) => run_doctor(args),
+        Command::Review(args) => run_review(args),
+        Command::VerifyArchive(args) => run_verify_archive(args),
+        Command::AnnotateSource(args) => run_annotate_source(args),
+        Command::Freeze => run_freeze(),
+        Command::Verify => run_verify(),
+        Command::FetchModels => run_fetch_models(),
+        Command::Tag(args) => run_tag(args),
+        Command::List(args) => run_list(args),
+        Command::Approve(args) => run_approve(args),
+        Command::Deprecate(args) => run_deprecate(args),
+        Command::Validate => run_validate(),
+        Command::Where(args) => run_where(args),
+        Command::Usages(args) => run_usages(args),
+        Command::Index(args) => run_index(args),
+        Command::Cache(args) => match args.action {
+            CacheAction::Clear => run_cache_clear(),
+        },
+        Command::Fetch(args) => run_fetch(args),
+        Command::Shell => run_shell(),
+        Command::Lsp => run_lsp(),
+        Command::Watch(args) => run_watch(args),
+        Command::RenameProject(args) => run_rename_project(args),
+        Command::Consolidate(args) => run_consolidate(args),
+        Command::Remove(args) => run_remove(args),
+        Command::Rename(args) => run_rename(args),
+        Command::RenameFootprint(args) => run_rename_footprint(args),
+        Command::Search(args) => run_search(args),
+        Command::Init(args) => run_init(args),
+        Command::Config(args) => match args.action {
+            ConfigAction::Get(args) => run_config_get(args),
+            ConfigAction::Set(args) => run_config_set(args),
+        },
+        Command::Export(args) => run_export(args),
+        Command::Diff(args) => run_diff(args),
+        Command::Merge(args) => run_merge(args),
+        Command::Table(args) => match args.action {
+            TableAction::Remove(args) => run_table_remove(args),
+            TableAction::Disable(args) => run_table_set_disabled(args, true),
+            TableAction::Enable(args) => run_table_set_disabled(args, false),
+        },
+    }
+}
+
+fn run_import(mut args: ImportArgs) -> Result<(), CliError> {
+    if let Some(manifest) = args.manifest.take() {
+        return run_manifest(&manifest, args);
+    }
+    let current = std::env::current_dir().map_err(ConfigError::from)?;
+    let cwd = import_root(&current, args.project.as_deref());
+    if cwd != current {
+        println!("using KiCad project in {}", cwd.display());
+    }
+    let journal_path = ImportJournal::path(&cwd);
+    let resuming = args.resume;
+    let journal = if resuming {
+        let journal = ImportJournal::load(&journal_path)?.ok_or_else(|| {
+            ConfigError::Invalid(format!(
+                "no interrupted import to resume in {}",
+                cwd.display()
+            ))
+        })?;
+        args = journal.args().clone().try_into().map_err(JournalError::from)?;
+        println!("resuming interrupted import");
+        journal
+    } else {
+        ImportJournal::new(toml::Value::try_from(&args).map_err(JournalError::from)?)
+    };
+    let started = Instant::now();
+    let mut plan = resolve_import(args, &cwd)?;
+    if let Some(kicad_project) = plan.kicad_project() {
+        println!("using KiCad project {}.kicad_pro", kicad_project);
+    }
+    fetch_lcsc_parts(&mut plan, &cwd)?;
+    let remotes = remote_targets(plan.config())?;
+    if plan.dry_run() || plan.emit_patch().is_some() {
+        if !remotes.is_empty() {
+            return Err(ConfigError::Invalid(
+                "--dry-run and --emit-patch need local library paths".to_string(),
+            )
+            .into());
+        }
+        return run_import_staged(&plan, &cwd);
+    }
+    if plan.enrich() && !remotes.is_empty() {
+        return Err(ConfigError::Invalid("--enrich needs local library paths".to_string()).into());
+    }
+    let nexar = if plan.enrich() {
+        Some(nexar_client(&cwd)?.with_ttl(Duration::from_secs(
+            plan.config().cache_ttl_hours() * 3600,
+        )))
+    } else {
+        None
+    };
+    let journaled = remotes.is_empty()
+        && !plan
+            .sources()
+            .iter()
+            .any(|source| source.as_os_str() == STDIN_SOURCE);
+    if journaled {
+        if !resuming && journal_path.exists() {
+            eprintln!(
+                "warning: replacing the journal of an interrupted import, \
+                 run `kci import --resume` to finish it instead"
+            );
+        }
+        plan.config = plan.config.clone().with_journal(Some(journal));
+    }
+    let report = if remotes.is_empty() {
+        let report = import_local(&plan, &cwd)?;
+        warn_global_shadowing(plan.config());
+        ensure_existing_project_tables(&cwd, plan.config())?;
+        report
+    } else {
+        import_remote(&plan, &cwd, &remotes)?
+    };
+    for warning in report.warnings() {
+        eprintln!("warning: {}", warning);
+    }
+    if plan.created_config() {
+        println!("wrote config to {}", plan.config_path().display());
+    }
+    println!(
+        "imported {} symbols, {} footprints, {} step files",
+        report.symbols_added(),
+        report.footprints_added(),
+        report.step_files_added()
+    );
+    if report.models_associated() > 0 {
+        println!("attached {} 3D models", report.models_associated());
+    }
+    if let Some(throughput) = report.step_throughput_mb_s() {
+        println!(
+            "copied {:.1} MB of 3D models in {:.1}s ({:.1} MB/s, {} jobs)",
+            report.step_bytes_copied() as f64 / (1024.0 * 1024.0),
+            report.step_copy_time().as_secs_f64(),
+            throughput,
+            plan.config().copy_jobs()
+        );
+    }
+    for simplification in report.simplifications() {
+        println!("  {}", simplification);
+    }
+    for association in report.associations() {
+        println!("  {}", association);
+    }
+    for (symbol, properties) in report.kept_properties() {
+        println!("  kept local properties on {}: {}", symbol, properties.join(", "));
+    }
+    let needs_review: Vec<_> = report.needs_review().collect();
+    if !needs_review.is_empty() {
+        println!(
+            "needs review (confidence below {:.0}%):",
+            plan.config().association_threshold() * 100.0
+        );
+        for association in needs_review {
+            println!("  {}", association);
+        }
+    }
+    if report.values_derived() > 0 {
+        println!(
+            "derived {} values from part numbers",
+            report.values_derived()
+        );
+    }
+    if report.pins_cleaned() > 0 {
+        println!("cleaned up {} pin names/numbers", report.pins_cleaned());
+    }
+    for (footprint, problem) in report.pads_fixed() {
+        println!("fixed {}: {}", footprint, problem);
+    }
+    if let Some(signing) = plan.config().signing()
+        && !report.footprint_models().is_empty()
+    {
+        sign_file(&Ledger::path(&cwd), signing)?;
+    }
+    if let Some(mut nexar) = nexar {
+        let names: Vec<String> = report
+            .associations()
+            .iter()
+            .map(|association| association.symbol().to_string())
+            .collect();
+        let enrichments = enrich_symbols(&cwd.join(plan.config().symbol_lib()), &names, |query| {
+            nexar.lookup(query)
+        })?;
+        for enrichment in enrichments {
+            println!("  {}", enrichment);
+        }
+    }
+    let mut queue = ReviewQueue::load(&cwd)?;
+    let queued = queue.update_from_report(&report);
+    queue.save(&cwd)?;
+    if queued > 0 {
+        println!("queued {} symbols for review, run `kci review`", queued);
+    }
+    if remotes.is_empty() && plan.config().model_store().is_none() {
+        report_lfs(&cwd, plan.config(), report.copied_steps())?;
+    }
+    if let Some(store) = plan.config().model_store() {
+        for name in report.copied_steps() {
+            store.upload(&plan.config().step_dir().join(name), name)?;
+        }
+        if !report.copied_steps().is_empty() {
+            println!(
+                "uploaded {} 3D models to {}",
+                report.copied_steps().len(),
+                store.url()
+            );
+        }
+    }
+    if let Some(command) = plan.config().sync_command() {
+        run_sync_command(command, &cwd)?;
+        println!("ran sync command: {}", command);
+    }
+    if plan.config().stats() {
+        ImportStats::new(&report, plan.sources(), started.elapsed())
+            .with_fetches(plan.lcsc_parts().len(), plan.lcsc_cache_hits)
+            .append(&cwd)?;
+        println!("recorded import timings in {}", ImportStats::path(&cwd).display());
+    }
+    if journaled {
+        ImportJournal::remove(&journal_path)?;
+    }
+    Ok(())
+}
+
+fn report_lfs(cwd: &Path, config: &ImportConfig, copied: &[String]) -> Result<(), CliError> {
+    let files: Vec<PathBuf> = copied
+        .iter()
+        .map(|name| config.step_dir().join(name))
+        .collect();
+    let report = check_lfs(cwd, &files, config.lfs_policy())?;
+    for finding in report.untracked() {
+        eprintln!(
+            "warning: {}, set `lfs = \"track\"` to add it to .gitattributes",
+            finding
+        );
+    }
+    for pattern in report.added_patterns() {
+        println!("tracking {} with Git LFS in .gitattributes", pattern);
+    }
+    Ok(())
+}
+
+fn remote_targets(config: &ImportConfig) -> Result<Vec<RemoteTarget>, CliError> {
+    let mut targets = Vec::new();
+    for path in [config.symbol_lib(), config.footprint_lib(), config.step_dir()] {
+        if let Some(target) = RemoteTarget::from_path(path) {
+            targets.push(target?);
+        }
+    }
+    Ok(targets)
+}
+
+fn import_remote(
+    plan: &ImportPlan,
+    cwd: &Path,
+    remotes: &[RemoteTarget],
+) -> Result<ImportReport, CliError> {
+    let config = plan.config();
+    let remote_path = |path: &Path| match RemoteTarget::from_path(path) {
+        Some(Ok(target)) => target.path().to_path_buf(),
+        _ => path.to_path_buf(),
+    };
+    let local = config.clone().with_paths(
+        remote_path(config.symbol_lib()),
+        remote_path(config.footprint_lib()),
+        remote_path(config.step_dir()),
+    );
+    let mirror = tempfile::tempdir().map_err(ConfigError::from)?;
+    let mut sources = HashMap::new();
+    for (idx, target) in remotes.iter().enumerate() {
+        let file_name = target.path().file_name().unwrap_or_default();
+        let dest = mirror.path().join(idx.to_string()).join(file_name);
+        if target.fetch(&dest)? {
+            sources.insert(target.path().to_path_buf(), dest);
+        }
+    }
+    let staging = Staging::with_sources(cwd, &local, sources)?;
+    let report = import_sources(
+        plan.sources(),
+        staging.config(),
+        plan.config().existing_symbols(),
+    )?;
+    stage_ledger(&staging, config, &report)?;
+    let step_dir = cwd.join(local.step_dir());
+    let mut steps = Vec::new();
+    for change in staging.changes()? {
+        let staged = staging.staged_path(change.path());
+        match remotes
+            .iter()
+            .find(|target| change.path().starts_with(target.path()))
+        {
+            Some(target) => target.push(&staged, change.path())?,
+            None if cwd.join(change.path()).starts_with(&step_dir) => {
+                steps.push(change.path().to_path_buf())
+            }
+            None => apply_staged_change(&staging, cwd, change.path())?,
+        }
+    }
+    let report = apply_staged_steps(&staging, cwd, &steps, config.copy_jobs(), report)?;
+    for target in remotes {
+        println!("updated {}", target);
+    }
+    ensure_project_tables(cwd, &local)?;
+    Ok(report)
+}
+
+fn import_local(plan: &ImportPlan, cwd: &Path) -> Result<ImportReport, CliError> {
+    let journal_path = ImportJournal::path(cwd);
+    let mut journal = plan.config().journal().cloned();
+    if let Some(journal) = &journal {
+        journal.save(&journal_path)?;
+    }
+    let staging = Staging::new(cwd, plan.config())?;
+    let report = import_sources(
+        plan.sources(),
+        staging.config(),
+        plan.config().existing_symbols(),
+    )?;
+    stage_ledger(&staging, plan.config(), &report)?;
+    stage_lifecycle(&staging, cwd, plan.config())?;
+    let changes = staging.changes()?;
+    if let Some(journal) = &mut journal {
+        journal.set_step_files(report.copied_steps().to_vec());
+        journal.save(&journal_path)?;
+    }
+    let symbol_lib = cwd.join(plan.config().symbol_lib());
+    let step_dir = cwd.join(plan.config().step_dir());
+    let mut steps = Vec::new();
+    for change in changes {
+        if cwd.join(change.path()).starts_with(&step_dir) {
+            steps.push(change.path().to_path_buf());
+            continue;
+        }
+        apply_staged_change(&staging, cwd, change.path())?;
+        if let Some(journal) = &mut journal
+            && cwd.join(change.path()) == symbol_lib
+        {
+            journal.set_symbols_added(report.symbols_added());
+            journal.save(&journal_path)?;
+        }
+    }
+    apply_staged_steps(&staging, cwd, &steps, plan.config().copy_jobs(), report)
+}
+
+fn apply_staged_steps(
+    staging: &Staging,
+    cwd: &Path,
+    paths: &[PathBuf],
+    jobs: usize,
+    report: ImportReport,
+) -> Result<ImportReport, CliError> {
+    let copies: Vec<(PathBuf, PathBuf)> = paths
+        .iter()
+        .map(|path| (staging.staged_path(path), cwd.join(path)))
+        .collect();
+    let started = Instant::now();
+    let mut bytes = 0;
+    for copied in parallel_map(&copies, jobs, |(staged, dest)| copy_staged_file(staged, dest)) {
+        bytes += copied.map_err(ConfigError::from)?;
+    }
+    Ok(report.with_step_copy(bytes, started.elapsed()))
+}
+
+fn apply_staged_change(staging: &Staging, cwd: &Path, path: &Path) -> Result<(), CliError> {
+    copy_staged_file(&staging.staged_path(path), &cwd.join(path)).map_err(ConfigError::from)?;
+    Ok(())
+}
+
+fn copy_staged_file(staged: &Path, dest: &Path) -> io::Result<u64> {
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::copy(staged, dest)
+}
+
+fn run_cache_clear() -> Result<(), CliError> {
+    let cwd = project_root()?;
+    let removed = clear_cache(&cwd)?;
+    println!("removed {} cached files", removed);
+    Ok(())
+}
+
+fn run_fetch(args: FetchArgs) -> Result<(), CliError> {
+    let cwd = project_root()?;
+    let config_path = global_config_path().ok_or_else(|| {
+        ConfigError::Invalid("cannot locate the global config directory".to_string())
+    })?;
+    let mut global = GlobalConfig::load(&config_path)?;
+    if let (Some(id), Some(secret)) = (&args.digikey_client_id, &args.digikey_client_secret) {
+        global.digikey = Some(DigikeyCredentials::new(id, secret));
+    }
+    if let Some(key) = &args.mouser_api_key {
+        global.mouser = Some(MouserCredentials::new(key));
+    }
+    if args.digikey_client_id.is_some() || args.mouser_api_key.is_some() {
+        global.write(&config_path)?;
+        println!("saved API credentials to {}", config_path.display());
+    }
+    let ttl = cache_ttl(&cwd)?;
+    let sources = fetch_vendor_archives(&cwd, ttl, &args.digikey, &args.mouser)?;
+    run_import(ImportArgs {
+        sources,
+        ..Default::default()
+    })
+}
+
+fn cache_ttl(cwd: &Path) -> Result<Duration, CliError> {
+    let config = load_config(ConfigOverrides::default(), cwd, false)?.config;
+    Ok(Duration::from_secs(config.cache_ttl_hours() * 3600))
+}
+
+fn fetch_vendor_archives(
+    cwd: &Path,
+    ttl: Duration,
+    digikey: &[String],
+    mouser: &[String],
+) -> Result<Vec<PathBuf>, CliError> {
+    let config_path = global_config_path().ok_or_else(|| {
+        ConfigError::Invalid("cannot locate the global config directory".to_string())
+    })?;
+    let global = GlobalConfig::load(&config_path)?;
+    let missing = |provider: &str, flags: &str| {
+        ConfigError::Invalid(format!(
+            "no {} credentials in {}, pass {} to `kci fetch` once",
+            provider,
+            config_path.display(),
+            flags
+        ))
+    };
+    let mut sources = Vec::new();
+    if !digikey.is_empty() {
+        let credentials = global.digikey.ok_or_else(|| {
+            missing("Digi-Key", "--digikey-client-id and --digikey-client-secret")
+        })?;
+        let fetcher = DigikeyFetcher::new(cwd, credentials).with_ttl(ttl);
+        for part in digikey {
+            let fetched = fetcher.fetch(part)?;
+            println!("{}", fetched);
+            sources.push(fetched.archive().to_path_buf());
+        }
+    }
+    if !mouser.is_empty() {
+        let credentials = global
+            .mouser
+            .ok_or_else(|| missing("Mouser", "--mouser-api-key"))?;
+        let fetcher = MouserFetcher::new(cwd, credentials).with_ttl(ttl);
+        for part in mouser {
+            let fetched = fetcher.fetch(part)?;
+            println!("{}", fetched);
+            sources.push(fetched.archive().to_path_buf());
+        }
+    }
+    Ok(sources)
+}
+
+fn run_manifest(manifest_path: &Path, args: ImportArgs) -> Result<(), CliError> {
+    let current = std::env::current_dir().map_err(ConfigError::from)?;
+    let cwd = import_root(&current, args.project.as_deref());
+    let manifest = PartsManifest::load(manifest_path)?;
+    let ttl = cache_ttl(&cwd)?;
+    let total = manifest.parts().len();
+    for (index, part) in manifest.parts().iter().enumerate() {
+        println!("[{}/{}] {}", index + 1, total, part.source());
+        let mut part_args = args.clone();
+        part_args.symbol_overrides = part.symbol_override().cloned().into_iter().collect();
+        match part.source() {
+            PartSource::Path(path) => part_args.sources = vec![path.clone()],
+            PartSource::Lcsc(code) => part_args.lcsc = vec![code.clone()],
+            PartSource::Url(url) => {
+                let fetched = UrlFetcher::new(&cwd).with_ttl(ttl).fetch(url)?;
+                println!("{}", fetched);
+                part_args.sources = vec![fetched.archive().to_path_buf()];
+            }
+            PartSource::Digikey(part) => {
+                part_args.sources = fetch_vendor_archives(&cwd, ttl, std::slice::from_ref(part), &[])?;
+            }
+            PartSource::Mouser(part) => {
+                part_args.sources = fetch_vendor_archives(&cwd, ttl, &[], std::slice::from_ref(part))?;
+            }
+        }
+        run_import(part_args)?;
+    }
+    println!("imported {} parts from {}", total, manifest_path.display());
+    Ok(())
+}
+
+fn nexar_client(cwd: &Path) -> Result<NexarClient, CliError> {
+    let config_path = global_config_path().ok_or_else(|| {
+        ConfigError::Invalid("cannot locate the global config directory".to_string())
+    })?;
+    let credentials = GlobalConfig::load(&config_path)?.nexar.ok_or_else(|| {
+        ConfigError::Invalid(format!(
+            "--enrich needs Nexar API credentials, add a [nexar] table with client_id and client_secret to {}",
+            config_path.display()
+        ))
+    })?;
+    Ok(NexarClient::new(cwd, credentials))
+}
+
+fn run_shell() -> Result<(), CliError> {
+    let cwd = project_root()?;
+    let resolved = resolve_config(ConfigOverrides::default(), &cwd)?;
+    let mut session = Session::new(&cwd.join(resolved.config.symbol_lib()));
+    println!("kci shell for {}, type `help` for commands", cwd.display());
+    let stdin = io::stdin();
+    loop {
+        print!("kci> ");
+        io::Write::flush(&mut io::stdout()).map_err(ConfigError::from)?;
+        let mut line = String::new();
+        if stdin.read_line(&mut line).map_err(ConfigError::from)? == 0 {
+            println!();
+            return Ok(());
+        }
+        let result = match ShellCommand::parse(&line) {
+            Ok(ShellCommand::Exit) => return Ok(()),
+            Ok(command) => run_shell_command(&mut session, command),
+            Err(err) => Err(err.into()),
+        };
+        if let Err(err) = result {
+            eprintln!("error: {}", err);
+        }
+    }
+}
+
+fn run_shell_command(session: &mut Session, command: ShellCommand) -> Result<(), CliError> {
+    match command {
+        ShellCommand::List => {
+            for name in session.list()? {
+                println!("{}", name);
+            }
+        }
+        ShellCommand::Search(text) => {
+            let found = session.search(&text)?;
+            if found.is_empty() {
+                println!("no symbols match {}", text);
+            }
+            for name in found {
+                println!("{}", name);
+            }
+        }
+        ShellCommand::Show(name) => {
+            let summary = session.show(&name)?;
+            println!("{} ({} pins)", summary.name(), summary.pins());
+            for (property, value) in summary.properties() {
+                println!("  {}: {}", property, value);
+            }
+        }
+        ShellCommand::Import(sources) => run_import(ImportArgs {
+            sources,
+            ..Default::default()
+        })?,
+        ShellCommand::Help => println!("{}", SHELL_HELP),
+        ShellCommand::Exit | ShellCommand::Empty => {}
+    }
+    Ok(())
+}
+
+fn run_lsp() -> Result<(), CliError> {
+    serve(io::stdin().lock(), io::stdout().lock())?;
+    Ok(())
+}
+
+fn run_watch(args: WatchArgs) -> Result<(), CliError> {
+    let mut watch = FolderWatch::new(&args.dir)?;
+    println!(
+        "watching {} for component archives, press Ctrl-C to stop",
+        watch.dir().display()
+    );
+    loop {
+        for archive in watch.wait(WATCH_POLL)? {
+            println!("new archive: {}", archive.display());
+            let result = run_import(ImportArgs {
+                sources: vec![archive.clone()],
+                ..Default::default()
+            });
+            match result {
+                Ok(()) => println!("imported {}", archive.display()),
+                Err(err) => eprintln!("error: {}: {}", archive.display(), err),
+            }
+        }
+    }
+}
+
+fn run_rename_project(args: RenameProjectArgs) -> Result<(), CliError> {
+    let cwd = project_root()?;
+    let config_path = cwd.join(".kci_config");
+    if !config_path.exists() {
+        return Err(ConfigError::Invalid(format!(
+            "no .kci_config in {}, nothing to rename",
+            cwd.display()
+        ))
+        .into());
+    }
+    let name = match args.name {
+        Some(name) => name,
+        None => choose_kicad_project(&cwd, None)?
+            .map(|(name, _)| name)
+            .ok_or_else(|| {
+                ConfigError::Invalid("no .kicad_pro found; pass the new project name".to_string())
+            })?,
+    };
+    let resolved = resolve_config(ConfigOverrides::default(), &cwd)?;
+    let mut file = ConfigFile::load(&config_path)?;
+    let naming = library_naming(Some(&file))?;
+    let rename = rename_project(&cwd, &resolved.config, &naming, &name)?;
+    file.symbol_lib = Some(rename.symbol_lib().to_path_buf());
+    file.footprint_lib = Some(rename.footprint_lib().to_path_buf());
+    file.step_dir = Some(rename.step_dir().to_path_buf());
+    file.write(&config_path)?;
+    for (old, new) in rename.moved() {
+        println!("renamed {} -> {}", old.display(), new.display());
+    }
+    for path in rename.updated() {
+        println!("updated {}", path.display());
+    }
+    println!("updated {}", config_path.display());
+    if Manifest::path(&cwd).exists() {
+        println!("libraries changed; run `kci freeze` to refresh .kci/lock.toml");
+    }
+    Ok(())
+}
+
+fn run_remove(args: RemoveArgs) -> Result<(), CliError> {
+    let cwd = project_root()?;
+    let resolved = resolve_config(ConfigOverrides::default(), &cwd)?;
+    let config = &resolved.config;
+    let removal = remove_symbol(&cwd, config, &args.symbol, args.with_footprint)?;
+    println!("removed {} from {}", removal.symbol(), config.symbol_lib().display());
+    if removal.removed_footprint()
+        && let Some(footprint) = removal.footprint()
+    {
+        println!("removed footprint {}", footprint);
+    }
+    forget_removed(&cwd, config, &removal)?;
+    for model in removal.removed_models() {
+        println!("removed 3D model {}", model.display());
+    }
+    for kept in removal.kept() {
+        println!("kept: {}", kept);
+    }
+    if removal.placed() > 0 {
+        eprintln!(
+            "warning: {} is still placed {} times in schematics",
+            removal.symbol(),
+            removal.placed()
+        );
+    }
+    Ok(())
+}
+
+fn forget_removed(cwd: &Path, config: &ImportConfig, removal: &Removal) -> Result<(), CliError> {
+    let mut queue = ReviewQueue::load(cwd)?;
+    if queue.resolve(removal.symbol()) {
+        queue.save(cwd)?;
+    }
+    if removal.removed_footprint()
+        && let Some(footprint) = removal.footprint()
+    {
+        let mut ledger = Ledger::load(cwd)?;
+        let name = footprint.rsplit(':').next().unwrap_or(footprint);
+        if ledger.remove_footprint(name).is_some() {
+            save_ledger(&mut ledger, cwd, config)?;
+        }
+    }
+    Ok(())
+}
+
+fn run_rename(args: RenameArgs) -> Result<(), CliError> {
+    let cwd = project_root()?;
+    let resolved = resolve_config(ConfigOverrides::default(), &cwd)?;
+    let placed = rename_symbol(&cwd, &resolved.config, &args.old, &args.new)?;
+    println!(
+        "renamed {} to {} in {}",
+        args.old,
+        args.new,
+        resolved.config.symbol_lib().display()
+    );
+    if placed > 0 {
+        eprintln!(
+            "warning: {} is still placed {} times in schematics; update them with Change Symbol",
+            args.old, placed
+        );
+    }
+    Ok(())
+}
+
+fn run_rename_footprint(args: RenameArgs) -> Result<(), CliError> {
+    let cwd = project_root()?;
+    let resolved = resolve_config(ConfigOverrides::default(), &cwd)?;
+    let config = &resolved.config;
+    let updated = rename_footprint(&cwd, config, &args.old, &args.new)?;
+    println!(
+        "renamed footprint {} to {} in {}",
+        args.old,
+        args.new,
+        config.footprint_lib().display()
+    );
+    for path in &updated {
+        println!("updated {}", path.display());
+    }
+    let mut ledger = Ledger::load(&cwd)?;
+    if let Some(record) = ledger.remove_footprint(&args.old) {
+        ledger.record_footprint(&args.new, record.models().to_vec());
+        save_ledger(&mut ledger, &cwd, config)?;
+    }
+    Ok(())
+}
+
+fn run_consolidate(args: ConsolidateArgs) -> Result<(), CliError> {
+    let cwd = project_root()?;
+    let overrides = ConfigOverrides {
+        footprint_lib: args.into,
+        ..Default::default()
+    };
+    let resolved = resolve_config(overrides, &cwd)?;
+    let consolidation =
+        consolidate_footprint_libs(&cwd, resolved.config.footprint_lib(), &args.libs)?;
+    ensure_project_tables(&cwd, &resolved.config)?;
+    for footprint in consolidation.footprints() {
+        println!("  {}", footprint);
+    }
+    for path in consolidation.updated() {
+        println!("updated {}", path.display());
+    }
+    if resolved.created_config {
+        println!("wrote config to {}", resolved.config_path.display());
+    }
+    println!(
+        "merged {} into {}: {} moved, {} duplicates dropped, {} renamed",
+        consolidation.merged_libs().join(", "),
+        resolved.config.footprint_lib().display(),
+        consolidation.count(MergeKind::Moved),
+        consolidation.count(MergeKind::Duplicate),
+        consolidation.count(MergeKind::Renamed)
+    );
+    Ok(())
+}
+
+fn run_merge(args: MergeArgs) -> Result<(), CliError> {
+    let cwd = project_root()?;
+    let resolved = resolve_config(ConfigOverrides::default(), &cwd)?;
+    let default = args
+        .existing
+        .as_deref()
+        .unwrap_or(AddPolicy::ErrorOnConflict.name())
+        .parse::<AddPolicy>()
+        .map_err(ConfigError::Invalid)?;
+    let mut policies = HashMap::new();
+    for value in &args.policies {
+        let Some((symbol, policy)) = value.split_once('=') else {
+            return Err(ConfigError::Invalid(format!(
+                "expected SYMBOL=POLICY, got {}",
+                value
+            ))
+            .into());
+        };
+        policies.insert(
+            symbol.trim().to_string(),
+            policy.parse::<AddPolicy>().map_err(ConfigError::Invalid)?,
+        );
+    }
+    let target = cwd.join(resolved.config.symbol_lib());
+    let merged = merge_symbol_lib(&target, &args.lib, default, &policies)?;
+    ensure_project_tables(&cwd, &resolved.config)?;
+    for symbol in &merged {
+        if symbol.outcome() != MergeOutcome::Unchanged {
+            println!("  {}", symbol);
+        }
+    }
+    if resolved.created_config {
+        println!("wrote config to {}", resolved.config_path.display());
+    }
+    let counts: Vec<String> = MergeOutcome::all()
+        .iter()
+        .map(|outcome| {
+            let count = merged
+                .iter()
+                .filter(|symbol| symbol.outcome() == *outcome)
+                .count();
+            (outcome, count)
+        })
+        .filter(|(_, count)| *count > 0)
+        .map(|(outcome, count)| format!("{} {}", count, outcome.name()))
+        .collect();
+    println!(
+        "merged {} into {}: {}",
+        args.lib.display(),
+        resolved.config.symbol_lib().display(),
+        if counts.is_empty() {
+            "no symbols".to_string()
+        } else {
+            counts.join(", ")
+        }
+    );
+    Ok(())
+}
+
+fn fetch_lcsc_parts(plan: &mut ImportPlan, cwd: &Path) -> Result<(), CliError> {
+    let fetcher = LcscFetcher::new(cwd)
+        .with_ttl(Duration::from_secs(plan.config().cache_ttl_hours() * 3600));
+    for part in &plan.lcsc_parts {
+        let fetched = fetcher.fetch(part)?;
+        for warning in fetched.warnings() {
+            eprintln!("warning: {}", warning);
+        }
+        println!("{}", fetched);
+        plan.sources.push(fetched.component().to_path_buf());
+        if fetched.cached() {
+            plan.lcsc_cache_hits += 1;
+        }
+    }
+    Ok(())
+}
+
+fn run_import_staged(plan: &ImportPlan, cwd: &Path) -> Result<(), CliError> {
+    let staging = Staging::new(cwd, plan.config())?;
+    let report = import_sources(
+        plan.sources(),
+        staging.config(),
+        plan.config().existing_symbols(),
+    )?;
+    stage_ledger(&staging, plan.config(), &report)?;
+    stage_lifecycle(&staging, cwd, plan.config())?;
+    ensure_existing_project_tables(staging.root(), plan.config())?;
+    for warning in report.warnings() {
+        eprintln!("warning: {}", warning);
+    }
+    println!(
+        "would import {} symbols, {} footprints, {} step files",
+        report.symbols_added(),
+        report.footprints_added(),
+        report.step_files_added()
+    );
+    for association in report.associations() {
+        println!("  {}", association);
+    }
+    let changes = staging.changes()?;
+    if let Some(patch_path) = plan.emit_patch() {
+        let mut patch = String::new();
+        for change in &changes {
+            patch.push_str(&change.git_diff()?);
+        }
+        std::fs::write(patch_path, patch).map_err(ConfigError::from)?;
+        println!(
+            "wrote {} file changes to {}, apply with `git apply`",
+            changes.len(),
+            patch_path.display()
+        );
+        return Ok(());
+    }
+    for change in changes.iter().filter(|change| change.shows_diff()) {
+        print!("{}", change.unified_diff());
+    }
+    let copies: Vec<_> = changes
+        .iter()
+        .filter(|change| !change.shows_diff())
+        .collect();
+    if !copies.is_empty() {
+        println!("files that would be written:");
+        for change in copies {
+            let status = if change.is_new() { "new" } else { "modified" };
+            println!("  {}: {}", status, change.path().display());
+        }
+    }
+    println!("dry run, no files were changed");
+    Ok(())
+}
+
+fn warn_global_shadowing(config: &ImportConfig) {
+    let global_dir = match kicad_global_config_dir() {
+        Some(dir) => dir,
+        None => return,
+    };
+    match find_shadowed_global_libs(&global_dir, config) {
+        Ok(shadowed) => {
+            for lib in shadowed {
+                eprintln!("warning: {}", lib);
+            }
+        }
+        Err(err) => eprintln!("warning: could not read global library tables: {}", err),
+    }
+}
+
+fn run_doctor(args: DoctorArgs) -> Result<(), CliError> {
+    let cwd = project_root()?;
+    let findings = diagnose_project_tables(&cwd, args.fix)?;
+    if findings.is_empty() {
+        println!("library tables look healthy");
+        return Ok(());
+    }
+    for finding in &findings {
+        let table = finding
+            .table()
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let status = if finding.fixed() { "fixed" } else { "found" };
+        println!("{}: {} ({})", table, finding.issue(), status);
+    }
+    if args.fix {
+        println!("repaired {} table issues", findings.len());
+    } else {
+        println!(
+            "found {} table issues, rerun with --fix to repair",
+            findings.len()
+        );
+    }
+    Ok(())
+}
+
+fn run_freeze() -> Result<(), CliError> {
+    let cwd = project_root()?;
+    let resolved = resolve_config(ConfigOverrides::default(), &cwd)?;
+    let mut manifest = Manifest::compute(&cwd, &resolved.config)?;
+    manifest.set_provenance(Provenance::current(resolved.config.operator()));
+    manifest.save(&cwd)?;
+    println!(
+        "recorded checksums for {} files in {}",
+        manifest.files().len(),
+        Manifest::path(&cwd).display()
+    );
+    if let Some(signing) = resolved.config.signing() {
+        let signature = sign_file(&Manifest::path(&cwd), signing)?;
+        println!("signed manifest: {}", signature.display());
+    }
+    Ok(())
+}
+
+fn save_ledger(ledger: &mut Ledger, cwd: &Path, config: &ImportConfig) -> Result<(), CliError> {
+    ledger.set_provenance(Provenance::current(config.operator()));
+    ledger.save(cwd)?;
+    if let Some(signing) = config.signing() {
+        sign_file(&Ledger::path(cwd), signing)?;
+    }
+    Ok(())
+}
+
+fn run_verify() -> Result<(), CliError> {
+    let cwd = project_root()?;
+    let resolved = resolve_config(ConfigOverrides::default(), &cwd)?;
+    let frozen = Manifest::load(&cwd)?;
+    if let Some(signing) = resolved.config.signing() {
+        let verified = verify_signatures(&cwd, signing)?;
+        if verified.contains(&Manifest::path(&cwd))
+            && let Some(provenance) = frozen.provenance()
+        {
+            println!(
+                "manifest signature ok ({} by {})",
+                provenance.tool(),
+                provenance.operator()
+            );
+        }
+        if verified.contains(&Ledger::path(&cwd)) {
+            println!("ledger signature ok");
+        }
+    }
+    let current = Manifest::compute(&cwd, &resolved.config)?;
+    let changes = frozen.diff(&current);
+    if changes.is_empty() {
+        println!("{} library files match the manifest", frozen.files().len());
+        return Ok(());
+    }
+    for change in &changes {
+        println!("{}", change);
+    }
+    Err(CliError::Tampered(changes.len()))
+}
+
+fn verify_signatures(cwd: &Path, signing: &SigningConfig) -> Result<Vec<PathBuf>, CliError> {
+    let mut signed = vec![Manifest::path(cwd)];
+    if Ledger::path(cwd).exists() {
+        signed.push(Ledger::path(cwd));
+    }
+    let mut verified = Vec::new();
+    for path in signed {
+        if signing.verify_key().is_some() {
+            verify_file(&path, signing)?;
+            verified.push(path);
+        } else if !signature_path(&path, signing.format()).exists() {
+            return Err(SigningError::Unsigned(path).into());
+        }
+    }
+    Ok(verified)
+}
+
+fn run_fetch_models() -> Result<(), CliError> {
+    let cwd = project_root()?;
+    let resolved = resolve_config(ConfigOverrides::default(), &cwd)?;
+    let config = &resolved.config;
+    let store = config
+        .model_store()
+        .ok_or_else(|| ConfigError::Invalid("no model_store configured".to_string()))?;
+    let ledger = Ledger::load(&cwd)?;
+    let mut models: Vec<&str> = ledger
+        .footprints()
+        .iter()
+        .flat_map(|record| record.models().iter().map(String::as_str))
+        .collect();
+    models.sort();
+    models.dedup();
+    let mut fetched = 0;
+    for model in models {
+        let name = match Path::new(model).strip_prefix(config.step_dir()) {
+            Ok(name) => name.to_string_lossy().replace('\\', "/"),
+            Err(_) => continue,
+        };
+        let dest = cwd.join(config.step_dir()).join(&name);
+        if dest.exists() {
+            continue;
+        }
+        store.download(&name, &dest)?;
+        fetched += 1;
+    }
+    println!(
+        "fetched {} 3D models from {} into {}",
+        fetched,
+        store.url(),
+        config.step_dir().display()
+    );
+    Ok(())
+}
+
+fn run_tag(args: TagArgs) -> Result<(), CliError> {
+    let cwd = project_root()?;
+    let resolved = resolve_config(ConfigOverrides::default(), &cwd)?;
+    let config = &resolved.config;
+    let set = parse_tags(&args.tags)?;
+    let tags = update_tags(
+        config.tag_store(),
+        config.symbol_lib(),
+        &cwd,
+        &args.symbol,
+        &set,
+        &args.remove,
+    )?;
+    if tags.is_empty() {
+        println!("{}: no tags", args.symbol);
+    } else {
+        println!("{}: {}", args.symbol, format_tags(&tags));
+    }
+    Ok(())
+}
+
+fn run_list(args: ListArgs) -> Result<(), CliError> {
+    let cwd = project_root()?;
+    let resolved = resolve_config(ConfigOverrides::default(), &cwd)?;
+    let config = &resolved.config;
+    let filters = parse_tags(&args.tags)?;
+    let lifecycle = match &args.lifecycle {
+        Some(name) => Some(name.parse::<Lifecycle>().map_err(ConfigError::Invalid)?),
+        None => None,
+    };
+    let symbols = load_tags(config.tag_store(), config.symbol_lib(), &cwd)?;
+    for (symbol, tags) in &symbols {
+        if !filters.iter().all(|filter| filter.matches(tags))
+            || lifecycle.is_some_and(|state| Lifecycle::of(tags) != state)
+        {
+            continue;
+        }
+        if tags.is_empty() {
+            println!("{}", symbol);
+        } else {
+            println!("{} [{}]", symbol, format_tags(tags));
+        }
+        if let Some(notice) = deprecation_notice(symbol, tags) {
+            eprintln!("warning: {}", notice);
+        }
+    }
+    Ok(())
+}
+
+fn run_search(args: SearchArgs) -> Result<(), CliError> {
+    let cwd = project_root()?;
+    let resolved = resolve_config(ConfigOverrides::default(), &cwd)?;
+    let symbol_lib = resolved.config.symbol_lib();
+    if !symbol_lib.is_file() {
+        return Ok(());
+    }
+    let needle = args.text.to_lowercase();
+    let mut cache = SymbolCache::load(&cwd);
+    for symbol in cache.symbols(symbol_lib)? {
+        if symbol.name().to_lowercase().contains(&needle)
+            || symbol
+                .properties()
+                .iter()
+                .any(|(_, value)| value.to_lowercase().contains(&needle))
+        {
+            println!("{}", symbol.name());
+        }
+    }
+    cache.save()?;
+    Ok(())
+}
+
+fn run_init(args: InitArgs) -> Result<(), CliError> {
+    let cwd = project_root()?;
+    let config_path = cwd.join(".kci_config");
+    let overrides = match args.name.as_deref() {
+        Some(name) => {
+            if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+                return Err(ConfigError::Invalid(format!("invalid library name: {:?}", name)).into());
+            }
+            if config_path.exists() {
+                return Err(ConfigError::Invalid(format!(
+                    "{} already exists, edit it to change the library paths",
+                    config_path.display()
+                ))
+                .into());
+            }
+            let defaults = default_config(Some(name), &library_naming(None)?)?;
+            ConfigOverrides {
+                symbol_lib: Some(defaults.symbol_lib().to_path_buf()),
+                footprint_lib: Some(defaults.footprint_lib().to_path_buf()),
+                step_dir: Some(defaults.step_dir().to_path_buf()),
+                ..Default::default()
+            }
+        }
+        None => ConfigOverrides::default(),
+    };
+    let resolved = resolve_config(overrides, &cwd)?;
+    let config = &resolved.config;
+    if !remote_targets(config)?.is_empty() {
+        return Err(ConfigError::Invalid("kci init needs local library paths".to_string()).into());
+    }
+    if resolved.created_config {
+        println!("wrote config to {}", resolved.config_path.display());
+    }
+    let symbol_lib = cwd.join(config.symbol_lib());
+    if !symbol_lib.exists() {
+        add_symbols_to_library(&symbol_lib, Vec::new(), AddPolicy::ReplaceExisting)?;
+        println!("created {}", config.symbol_lib().display());
+    }
+    for dir in [config.footprint_lib(), config.step_dir()] {
+        if !cwd.join(dir).is_dir() {
+            std::fs::create_dir_all(cwd.join(dir)).map_err(ConfigError::from)?;
+            println!("created {}", dir.display());
+        }
+    }
+    warn_global_shadowing(config);
+    ensure_project_tables(&cwd, config)?;
+    println!(
+        "registered {} and {} in the project lib tables",
+        lib_name_from_path(TableKind::Symbol, config.symbol_lib())?,
+        lib_name_from_path(TableKind::Footprint, config.footprint_lib())?
+    );
+    Ok(())
+}
+
+fn check_config_key(key: &str) -> Result<(), ConfigError> {
+    if CONFIG_KEYS.contains(&key) {
+        return Ok(());
+    }
+    Err(ConfigError::Invalid(format!(
+        "unknown key {}, expected one of {}",
+        key,
+        CONFIG_KEYS.join(", ")
+    )))
+}
+
+fn run_config_get(args: ConfigGetArgs) -> Result<(), CliError> {
+    let cwd = project_root()?;
+    let config_path = cwd.join(".kci_config");
+    let file = if config_path.exists() {
+        ConfigFile::load(&config_path)?
+    } else {
+        ConfigFile::default()
+    };
+    match file.get(&args.key)? {
+        Some(toml::Value::String(value)) => println!("{}", value),
+        Some(value) => println!("{}", value),
+        None => eprintln!("{} is not set in {}", args.key, config_path.display()),
+    }
+    Ok(())
+}
+
+fn run_config_set(args: ConfigSetArgs) -> Result<(), CliError> {
+    let cwd = project_root()?;
+    let config_path = cwd.join(".kci_config");
+    let previous = if config_path.exists() {
+        Some(std::fs::read_to_string(&config_path).map_err(ConfigError::from)?)
+    } else {
+        None
+    };
+    let file = match &previous {
+        Some(raw) => toml::from_str::<ConfigFile>(raw).map_err(ConfigError::from)?,
+        None => ConfigFile::default(),
+    };
+    let updated = file.set(&args.key, &args.value)?;
+    updated.write(&config_path)?;
+    if let Err(err) = load_config(ConfigOverrides::default(), &cwd, false) {
+        match previous {
+            Some(raw) => std::fs::write(&config_path, raw).map_err(ConfigError::from)?,
+            None => std::fs::remove_file(&config_path).map_err(ConfigError::from)?,
+        }
+        return Err(err.into());
+    }
+    if let Some(value) = updated.get(&args.key)? {
+        println!("set {} = {} in {}", args.key, value, config_path.display());
+    }
+    Ok(())
+}
+
+fn run_export(args: ExportArgs) -> Result<(), CliError> {
+    let cwd = project_root()?;
+    let resolved = resolve_config(ConfigOverrides::default(), &cwd)?;
+    let output = args
+        .output
+        .unwrap_or_else(|| PathBuf::from(format!("{}.zip", args.symbol)));
+    let export = export_symbol(&cwd, &resolved.config, &args.symbol, &output)?;
+    println!("exported {} to {}", args.symbol, output.display());
+    for entry in export.entries() {
+        println!("  {}", entry);
+    }
+    for skipped in export.skipped() {
+        eprintln!("warning: not bundled: {}", skipped);
+    }
+    Ok(())
+}
+
+fn run_diff(args: DiffArgs) -> Result<(), CliError> {
+    let cwd = project_root()?;
+    let overrides = ConfigOverrides {
+        existing: args.existing,
+        ..ConfigOverrides::default()
+    };
+    let config = load_config(overrides, &cwd, false)?.config;
+    if !remote_targets(&config)?.is_empty() {
+        return Err(ConfigError::Invalid("kci diff needs local library paths".to_string()).into());
+    }
+    let staging = Staging::new(&cwd, &config)?;
+    let report = import_sources(&args.sources, staging.config(), config.existing_symbols())?;
+    for warning in report.warnings() {
+        eprintln!("warning: {}", warning);
+    }
+    let mut symbols = Vec::new();
+    let mut footprints = Vec::new();
+    let mut steps = Vec::new();
+    for change in staging.changes()? {
+        let status = if change.is_new() { "+" } else { "~" };
+        let name = change
+            .path()
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if change.path() == config.symbol_lib() {
+            for symbol in change.symbol_changes()? {
+                let SymbolChange::Replaced {
+                    name,
+                    properties,
+                    body_changed,
+                } = symbol
+                else {
+                    symbols.push(symbol);
+                    continue;
+                };
+                let properties: Vec<_> = properties
+                    .into_iter()
+                    .filter(|property| !property.name().starts_with(TAG_PROPERTY_PREFIX))
+                    .collect();
+                if !properties.is_empty() || body_changed {
+                    symbols.push(SymbolChange::Replaced {
+                        name,
+                        properties,
+                        body_changed,
+                    });
+                }
+            }
+        } else if change.path().starts_with(config.footprint_lib()) {
+            footprints.push(format!("{} {}", status, name.trim_end_matches(".kicad_mod")));
+        } else if change.path().starts_with(config.step_dir()) {
+            steps.push(format!("{} {}", status, name));
+        }
+    }
+    if symbols.is_empty() && footprints.is_empty() && steps.is_empty() {
+        println!("no changes");
+        return Ok(());
+    }
+    if !symbols.is_empty() {
+        println!("symbols ({}):", config.symbol_lib().display());
+        for change in symbols {
+            match change {
+                SymbolChange::Added(name) => println!("  + {}", name),
+                SymbolChange::Replaced {
+                    name,
+                    properties,
+                    body_changed,
+                } => {
+                    println!("  ~ {}", name);
+                    for property in properties {
+                        println!("      {}", property);
+                    }
+                    if body_changed {
+                        println!("      pins or graphics changed");
+                    }
+                }
+            }
+        }
+    }
+    for (title, path, lines) in [
+        ("footprints", config.footprint_lib(), footprints),
+        ("STEP files", config.step_dir(), steps),
+    ] {
+        if lines.is_empty() {
+            continue;
+        }
+        println!("{} ({}):", title, path.display());
+        for line in lines {
+            println!("  {}", line);
+        }
+    }
+    Ok(())
+}
+
+fn project_tags(cwd: &Path, config: &ImportConfig) -> Result<BTreeMap<String, Tags>, CliError> {
+    let symbol_lib = cwd.join(config.symbol_lib());
+    if !symbol_lib.is_file() {
+        return Ok(BTreeMap::new());
+    }
+    Ok(load_tags(config.tag_store(), &symbol_lib, cwd)?)
+}
+
+fn stage_ledger(
+    staging: &Staging,
+    config: &ImportConfig,
+    report: &ImportReport,
+) -> Result<(), CliError> {
+    if report.footprint_models().is_empty() {
+        return Ok(());
+    }
+    let mut ledger = Ledger::load(staging.root())?;
+    for (footprint, model) in report.footprint_models() {
+        ledger.record_footprint(footprint, model.iter().cloned().collect());
+    }
+    ledger.set_provenance(Provenance::current(config.operator()));
+    ledger.save(staging.root())?;
+    Ok(())
+}
+
+fn stage_lifecycle(staging: &Staging, cwd: &Path, config: &ImportConfig) -> Result<(), CliError> {
+    let Some(change) = staging.file_change(config.symbol_lib())? else {
+        return Ok(());
+    };
+    let previous = project_tags(cwd, config)?;
+    let symbol_lib = staging.staged_path(config.symbol_lib());
+    for symbol_change in change.symbol_changes()? {
+        let (symbol, body_changed) = match symbol_change {
+            SymbolChange::Added(name) => (name, true),
+            SymbolChange::Replaced {
+                name, body_changed, ..
+            } => (name, body_changed),
+        };
+        let tags = previous.get(&symbol).cloned().unwrap_or_default();
+        let mut keep: Vec<Tag> = tags.iter().map(|(key, value)| Tag::new(key, value)).collect();
+        if body_changed {
+            match deprecation_notice(&symbol, &tags) {
+                Some(notice) => eprintln!("warning: re-imported {}", notice),
+                None => {
+                    keep.retain(|tag| tag.key() != LIFECYCLE_TAG);
+                    keep.push(Lifecycle::Draft.tag());
+                }
+            }
+        }
+        if !keep.is_empty() {
+            update_tags(config.tag_store(), &symbol_lib, staging.root(), &symbol, &keep, &[])?;
+        }
+    }
+    Ok(())
+}
+
+fn run_approve(args: ApproveArgs) -> Result<(), CliError> {
+    let cwd = project_root()?;
+    let resolved = resolve_config(ConfigOverrides::default(), &cwd)?;
+    let config = &resolved.config;
+    let state = match &args.state {
+        Some(name) => name.parse::<Lifecycle>().map_err(ConfigError::Invalid)?,
+        None => Lifecycle::Approved,
+    };
+    set_lifecycle(
+        config.tag_store(),
+        config.symbol_lib(),
+        &cwd,
+        &args.symbols,
+        state,
+    )?;
+    for symbol in &args.symbols {
+        println!("{}: {}", symbol, state);
+    }
+    Ok(())
+}
+
+fn run_deprecate(args: DeprecateArgs) -> Result<(), CliError> {
+    let cwd = project_root()?;
+    let resolved = resolve_config(ConfigOverrides::default(), &cwd)?;
+    let config = &resolved.config;
+    deprecate(
+        config.tag_store(),
+        config.symbol_lib(),
+        &cwd,
+        &args.symbol,
+        args.replacement.as_deref(),
+    )?;
+    let Some(replacement) = &args.replacement else {
+        println!("{}: deprecated", args.symbol);
+        return Ok(());
+    };
+    println!("{}: deprecated, replaced by {}", args.symbol, replacement);
+    let references = substitute_references(
+        config.symbol_lib(),
+        &cwd,
+        &args.symbol,
+        replacement,
+        args.substitute,
+    )?;
+    let total: usize = references.iter().map(|(_, count)| count).sum();
+    if args.substitute {
+        for (schematic, count) in &references {
+            println!("  replaced {} references in {}", count, schematic.display());
+        }
+        if total > 0 {
+            println!("run Update Symbols from Library and ERC in KiCad to check the new pins");
+        }
+    } else if total > 0 {
+        println!(
+            "{} schematic references still use {}, rerun with --substitute to replace them",
+            total, args.symbol
+        );
+    }
+    Ok(())
+}
+
+fn run_where(args: WhereArgs) -> Result<(), CliError> {
+    let cwd = project_root()?;
+    let resolved = resolve_config(ConfigOverrides::default(), &cwd)?;
+    let target = args
+        .target
+        .parse::<LookupTarget>()
+        .map_err(ConfigError::Invalid)?;
+    let index = LibraryIndex::refresh(&cwd, &resolved.config)?;
+    let report = index.lookup(&target);
+    if report.is_empty() {
+        println!("nothing in the project libraries uses {}", args.target);
+        return Ok(());
+    }
+    if !report.footprints().is_empty() {
+        println!("footprints:");
+        for footprint in report.footprints() {
+            let path = footprint.path().strip_prefix(&cwd).unwrap_or(footprint.path());
+            println!("  {} ({})", footprint.lib_id(), path.display());
+            for model in footprint.models() {
+                println!("    model {}", model);
+            }
+        }
+    }
+    if !report.symbols().is_empty() {
+        println!("symbols:");
+        for symbol in report.symbols() {
+            println!("  {}", symbol);
+        }
+    }
+    if !report.usages().is_empty() {
+        println!("schematic usages:");
+        for usage in report.usages() {
+            println!("  {}", usage);
+        }
+    }
+    if !report.tables().is_empty() {
+        println!("table entries:");
+        for entry in report.tables() {
+            println!("  {}", entry);
+        }
+    }
+    Ok(())
+}
+
+fn run_usages(args: UsagesArgs) -> Result<(), CliError> {
+    let cwd = project_root()?;
+    let resolved = resolve_config(ConfigOverrides::default(), &cwd)?;
+    let index = LibraryIndex::refresh(&cwd, &resolved.config)?;
+    let usages = index.usages_of(&args.symbol);
+    if usages.is_empty() {
+        println!("no schematic places {}", args.symbol);
+        return Ok(());
+    }
+    for usage in &usages {
+        println!("{}", usage);
+    }
+    Ok(())
+}
+
+fn run_index(args: IndexArgs) -> Result<(), CliError> {
+    let cwd = project_root()?;
+    let resolved = resolve_config(ConfigOverrides::default(), &cwd)?;
+    if args.rebuild {
+        LibraryIndex::clear(&cwd)?;
     }
-    ImportConfig::new(
-        PathBuf::from(DEFAULT_SYMBOL_LIB),
-        PathBuf::from(DEFAULT_FOOTPRINT_LIB),
-        PathBuf::from(DEFAULT_STEP_DIR),
-    )
+    let index = LibraryIndex::refresh(&cwd, &resolved.config)?;
+    println!(
+        "indexed {} files ({} re-read): {} footprints, {} symbols, {} schematic usages",
+        index.files(),
+        index.reparsed(),
+        index.footprints().len(),
+        index.symbols().len(),
+        index.usages().len()
+    );
+    Ok(())
 }
 
-fn resolve_path(
-    cli_value: &Option<PathBuf>,
-    config_value: Option<&PathBuf>,
-    default: &Path,
-) -> PathBuf {
-    if let Some(value) = cli_value {
-        return value.clone();
+fn run_validate() -> Result<(), CliError> {
+    let cwd = project_root()?;
+    let resolved = resolve_config(ConfigOverrides::default(), &cwd)?;
+    let config = &resolved.config;
+    let problems = validate_libraries(&cwd, config)?;
+    if problems.is_empty() {
+        println!("project libraries and lib tables look consistent");
     }
-    if let Some(value) = config_value {
-        return value.clone();
+    for problem in &problems {
+        println!("{}", problem);
     }
-    default.to_path_buf()
+    let found = find_unapproved_uses(config.tag_store(), config.symbol_lib(), &cwd)?;
+    if found.is_empty() {
+        println!("all project parts used in schematics are approved");
+    }
+    for unapproved in &found {
+        println!("{}", unapproved);
+    }
+    if !problems.is_empty() {
+        return Err(CliError::LibraryProblems(problems.len()));
+    }
+    if !found.is_empty() {
+        return Err(CliError::Unapproved(found.len()));
+    }
+    Ok(())
 }
 
-fn project_name_from_kicad_pro(cwd: &Path) -> Option<String> {
-    let mut names = Vec::new();
-    let dir_name = cwd.file_name().and_then(|value| value.to_str());
-    let entries = std::fs::read_dir(cwd).ok()?;
-    for entry in entries.flatten() {
-        let path = entry.path();
-        if !path.is_file() {
-            continue;
+fn parse_tags(values: &[String]) -> Result<Vec<Tag>, ConfigError> {
+    values
+        .iter()
+        .map(|value| value.parse::<Tag>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(ConfigError::Invalid)
+}
+
+fn format_tags(tags: &Tags) -> String {
+    tags.iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn run_verify_archive(args: VerifyArchiveArgs) -> Result<(), CliError> {
+    let inspection = inspect_source(&args.source)?;
+    println!("vendor: {}", inspection.vendor());
+    let categories = [
+        ("symbol libraries", inspection.symbol_files()),
+        ("footprints", inspection.footprint_files()),
+        ("3D models", inspection.step_files()),
+        ("other files", inspection.other_files()),
+    ];
+    for (label, files) in categories {
+        println!("{}: {}", label, files.len());
+        for file in files {
+            println!("  {}", file.display());
         }
-        if path.extension().and_then(|value| value.to_str()) != Some("kicad_pro") {
-            continue;
+    }
+    println!("symbols: {}", inspection.symbols().join(", "));
+    println!("footprints: {}", inspection.footprints().join(", "));
+    if inspection.problems().is_empty() {
+        println!("no problems found");
+    } else {
+        println!("problems:");
+        for problem in inspection.problems() {
+            println!("  {}", problem);
         }
-        if let Some(stem) = path.file_stem().and_then(|value| value.to_str()) {
-            names.push(stem.to_string());
+    }
+    Ok(())
+}
+
+fn run_annotate_source(args: AnnotateSourceArgs) -> Result<(), CliError> {
+    let manifest = annotate_source(&args.source)?;
+    match &args.output {
+        Some(path) => {
+            std::fs::write(path, manifest).map_err(ConfigError::from)?;
+            println!("wrote {}", path.display());
         }
+        None => print!("{}", manifest),
     }
-    if names.is_empty() {
-        return None;
+    Ok(())
+}
+
+fn run_review(args: ReviewArgs) -> Result<(), CliError> {
+    let cwd = project_root()?;
+    let mut queue = ReviewQueue::load(&cwd)?;
+    if queue.is_empty() {
+        println!("nothing to review");
+        return Ok(());
     }
-    if let Some(dir_name) = dir_name {
-        if names.iter().any(|name| name == dir_name) {
-            return Some(dir_name.to_string());
+    if args.list {
+        for item in queue.items() {
+            println!("{}", item);
         }
+        return Ok(());
     }
-    names.sort();
-    names.first().cloned()
+    let resolved = resolve_config(ConfigOverrides::default(), &cwd)?;
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let mut output = io::stdout();
+    let pending: Vec<String> = queue
+        .items()
+        .iter()
+        .map(|item| item.symbol().to_string())
+        .collect();
+    let count = review_interactive(&mut queue, &resolved.config, &mut input, &mut output)?;
+    queue.save(&cwd)?;
+    let reviewed: Vec<String> = pending
+        .into_iter()
+        .filter(|symbol| queue.items().iter().all(|item| item.symbol() != symbol))
+        .collect();
+    set_lifecycle(
+        resolved.config.tag_store(),
+        resolved.config.symbol_lib(),
+        &cwd,
+        &reviewed,
+        Lifecycle::Reviewed,
+    )?;
+    println!(
+        "resolved {} symbols, {} left to review",
+        count,
+        queue.items().len()
+    );
+    Ok(())
 }
 
-pub fn run(cli: Cli) -> Result<(), CliError> {
-    match cli.command {
-        Command::Import(args) => {
-            let cwd = std::env::current_dir().map_err(ConfigError::from)?;
-            let plan = resolve_import(args, &cwd)?;
-            let report = import_source(plan.source(), plan.config(), AddPolicy::ReplaceExisting)?;
-            ensure_project_tables(&cwd, plan.config())?;
-            if plan.created_config() {
-                println!("wrote config to {}", plan.config_path().display());
-            }
-            println!(
-                "imported {} symbols, {} footprints, {} step files",
-                report.symbols_added(),
-                report.footprints_added(),
-                report.step_files_added()
-            );
-            Ok(())
+fn run_table_remove(args: TableEntryArgs) -> Result<(), CliError> {
+    let cwd = project_root()?;
+    let mut removed = 0;
+    for kind in args.table.kinds() {
+        if remove_project_lib(&cwd, *kind, &args.name)? {
+            println!("removed {} from {}", args.name, kind.file_name());
+            removed += 1;
         }
     }
+    if removed == 0 {
+        println!("no table entry named {}", args.name);
+    }
+    Ok(())
+}
+
+fn run_table_set_disabled(args: TableEntryArgs, disabled: bool) -> Result<(), CliError> {
+    let cwd = project_root()?;
+    let action = if disabled { "disabled" } else { "enabled" };
+    let mut changed = 0;
+    for kind in args.table.kinds() {
+        if set_project_lib_disabled(&cwd, *kind, &args.name, disabled)? {
+            println!("{} {} in {}", action, args.name, kind.file_name());
+            changed += 1;
+        }
+    }
+    if changed == 0 {
+        println!("no table entry named {}", args.name);
+    }
+    Ok(())
+}
+
+fn run_generate_passive(args: PassiveArgs) -> Result<(), CliError> {
+    let kind: PassiveKind = args.kind.parse()?;
+    let size: ChipSize = args.size.parse()?;
+    let symbol = generate_passive(kind, &args.value, size)?;
+    let cwd = project_root()?;
+    let overrides = ConfigOverrides {
+        symbol_lib: args.symbol_lib,
+        ..Default::default()
+    };
+    let resolved = resolve_config(overrides, &cwd)?;
+    let name = symbol.name().to_string();
+    let footprint = symbol.property_value("Footprint").unwrap_or_default();
+    add_symbols_to_library(
+        resolved.config.symbol_lib(),
+        vec![symbol],
+        AddPolicy::ReplaceExisting,
+    )?;
+    warn_global_shadowing(&resolved.config);
+    ensure_symbol_table(&cwd, &resolved.config)?;
+    if resolved.created_config {
+        println!("wrote config to {}", resolved.config_path.display());
+    }
+    println!("generated symbol {} ({})", name, footprint);
+    Ok(())
+}
+
+fn run_generate_connector(args: ConnectorArgs) -> Result<(), CliError> {
+    let spec = match &args.csv {
+        Some(path) => {
+            let content = std::fs::read_to_string(path).map_err(ConfigError::from)?;
+            ConnectorSpec::from_csv(&content, args.pitch, args.rows)?
+        }
+        None => ConnectorSpec::new(args.pins.unwrap_or_default(), args.pitch, args.rows)?,
+    };
+    let cwd = project_root()?;
+    let overrides = ConfigOverrides {
+        symbol_lib: args.symbol_lib,
+        footprint_lib: args.footprint_lib,
+        ..Default::default()
+    };
+    let resolved = resolve_config(overrides, &cwd)?;
+    let config = &resolved.config;
+    let lib_name = footprint_lib_name(config.footprint_lib())?;
+    let generated = generate_connector(&spec, args.name.as_deref(), &lib_name)?;
+
+    std::fs::create_dir_all(config.footprint_lib()).map_err(ConfigError::from)?;
+    let footprint_path = config
+        .footprint_lib()
+        .join(format!("{}.kicad_mod", generated.footprint_name()));
+    std::fs::write(&footprint_path, generated.footprint()).map_err(ConfigError::from)?;
+    let name = generated.symbol().name().to_string();
+    let footprint = format!("{}:{}", lib_name, generated.footprint_name());
+    let mut ledger = Ledger::load(&cwd)?;
+    ledger.record_footprint(generated.footprint_name(), Vec::new());
+    save_ledger(&mut ledger, &cwd, config)?;
+    add_symbols_to_library(
+        config.symbol_lib(),
+        vec![generated.into_symbol()],
+        AddPolicy::ReplaceExisting,
+    )?;
+    warn_global_shadowing(config);
+    ensure_project_tables(&cwd, config)?;
+    if resolved.created_config {
+        println!("wrote config to {}", resolved.config_path.display());
+    }
+    println!("generated symbol {} ({})", name, footprint);
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::review::ReviewItem;
     use tempfile::tempdir;
 
     #[test]
     fn resolve_import_creates_default_config() {
         let dir = tempdir().unwrap();
         let args = ImportArgs {
-            source: dir.path().join("source.zip"),
-            symbol_lib: None,
-            footprint_lib: None,
-            step_dir: None,
+            sources: vec![dir.path().join("source.zip")],
+            ..Default::default()
         };
         let plan = resolve_import(args, dir.path()).unwrap();
         assert!(plan.created_config());
@@ -317,16 +3517,38 @@ mod tests {
         assert_eq!(stored.step_dir.as_ref().unwrap(), Path::new(DEFAULT_STEP_DIR));
     }
 
+    #[test]
+    fn find_project_root_locates_the_kicad_pro() {
+        let dir = tempdir().unwrap();
+        let board = dir.path().join("hardware").join("board");
+        std::fs::create_dir_all(board.join("libs")).unwrap();
+        std::fs::write(board.join("board.kicad_pro"), "{}").unwrap();
+        assert_eq!(find_project_root(&board), board);
+        assert_eq!(find_project_root(&board.join("libs")), board);
+        assert_eq!(find_project_root(&dir.path().join("hardware")), board);
+
+        for name in ["first", "second"] {
+            std::fs::create_dir_all(dir.path().join(name)).unwrap();
+            std::fs::write(dir.path().join(name).join(format!("{}.kicad_pro", name)), "{}")
+                .unwrap();
+        }
+        std::fs::create_dir_all(dir.path().join("plain")).unwrap();
+        std::fs::write(dir.path().join("plain").join(".kci_config"), "").unwrap();
+        assert_eq!(find_project_root(dir.path()), dir.path());
+        assert_eq!(
+            find_project_root(&dir.path().join("plain")),
+            dir.path().join("plain")
+        );
+    }
+
     #[test]
     fn resolve_import_uses_kicad_pro_name_for_defaults() {
         let dir = tempdir().unwrap();
         let pro_path = dir.path().join("my_project.kicad_pro");
         std::fs::write(&pro_path, "dummy").unwrap();
         let args = ImportArgs {
-            source: dir.path().join("source.zip"),
-            symbol_lib: None,
-            footprint_lib: None,
-            step_dir: None,
+            sources: vec![dir.path().join("source.zip")],
+            ..Default::default()
         };
         let plan = resolve_import(args, dir.path()).unwrap();
         assert!(plan.created_config());
@@ -341,16 +3563,123 @@ mod tests {
         assert_eq!(plan.config().step_dir(), Path::new("my_project_step"));
     }
 
+    #[test]
+    fn config_file_set_parses_and_checks_values() {
+        let file = ConfigFile::default()
+            .set("step_dir", "models3d")
+            .unwrap()
+            .set("copy_jobs", "8")
+            .unwrap()
+            .set("operator", "true")
+            .unwrap()
+            .set("library_naming.step_dir", "libs/{project}/3d")
+            .unwrap();
+        assert_eq!(file.step_dir.as_deref(), Some(Path::new("models3d")));
+        assert_eq!(file.copy_jobs, Some(8));
+        assert_eq!(file.operator.as_deref(), Some("true"));
+        assert_eq!(
+            file.get("library_naming.step_dir").unwrap(),
+            Some(toml::Value::String("libs/{project}/3d".to_string()))
+        );
+        assert_eq!(file.get("lfs").unwrap(), None);
+        assert!(matches!(
+            file.set("copy_jobs", "lots"),
+            Err(ConfigError::Invalid(_))
+        ));
+        assert!(matches!(
+            file.get("symbol-lib"),
+            Err(ConfigError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn resolve_import_applies_library_naming_template() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("board.kicad_pro"), "{}").unwrap();
+        std::fs::write(
+            dir.path().join(".kci_config"),
+            r#"[library_naming]
+symbol_lib = "libs/{project}/{category}.kicad_sym"
+step_dir = "libs/{project}/{category}"
+"#,
+        )
+        .unwrap();
+        let args = ImportArgs {
+            sources: vec![dir.path().join("source.zip")],
+            ..Default::default()
+        };
+        let plan = resolve_import(args, dir.path()).unwrap();
+        assert_eq!(
+            plan.config().symbol_lib(),
+            Path::new("libs/board/symbols.kicad_sym")
+        );
+        assert_eq!(
+            plan.config().footprint_lib(),
+            Path::new("board_footprints.pretty")
+        );
+        assert_eq!(plan.config().step_dir(), Path::new("libs/board/3d"));
+
+        std::fs::write(
+            dir.path().join(".kci_config"),
+            "[library_naming]\nsymbol_lib = \"{vendor}.kicad_sym\"\n",
+        )
+        .unwrap();
+        let args = ImportArgs {
+            sources: vec![dir.path().join("source.zip")],
+            ..Default::default()
+        };
+        assert!(matches!(
+            resolve_import(args, dir.path()),
+            Err(ConfigError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn resolve_import_uses_requested_kicad_project() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("rev_a.kicad_pro"), "{}").unwrap();
+        std::fs::write(dir.path().join("rev_b.kicad_pro"), "{}").unwrap();
+        let args = ImportArgs {
+            sources: vec![dir.path().join("source.zip")],
+            kicad_project: Some("rev_b.kicad_pro".to_string()),
+            ..Default::default()
+        };
+        let plan = resolve_import(args, dir.path()).unwrap();
+        assert_eq!(plan.kicad_project(), Some("rev_b"));
+        assert_eq!(plan.config().symbol_lib(), Path::new("rev_b_symbols.kicad_sym"));
+
+        let args = ImportArgs {
+            sources: vec![dir.path().join("source.zip")],
+            kicad_project: Some("rev_c".to_string()),
+            ..Default::default()
+        };
+        let err = resolve_import(args, dir.path()).unwrap_err();
+        assert!(err.to_string().contains("found: rev_a, rev_b"));
+    }
+
+    #[test]
+    fn prompt_kicad_project_accepts_number_or_name() {
+        let names = ["rev_a".to_string(), "rev_b".to_string()];
+        let mut output = Vec::new();
+        let chosen =
+            prompt_kicad_project(&names, &mut io::Cursor::new("3\n2\n"), &mut output).unwrap();
+        assert_eq!(chosen.as_deref(), Some("rev_b"));
+        assert!(String::from_utf8(output).unwrap().contains("1) rev_a.kicad_pro"));
+        let chosen =
+            prompt_kicad_project(&names, &mut io::Cursor::new("rev_a\n"), &mut Vec::new()).unwrap();
+        assert_eq!(chosen.as_deref(), Some("rev_a"));
+        let chosen = prompt_kicad_project(&names, &mut io::Cursor::new(""), &mut Vec::new()).unwrap();
+        assert_eq!(chosen, None);
+    }
+
     #[test]
     fn resolve_import_uses_partial_config() {
         let dir = tempdir().unwrap();
         let config_path = dir.path().join(".kci_config");
         std::fs::write(&config_path, "symbol_lib = \"sym.kicad_sym\"\n").unwrap();
         let args = ImportArgs {
-            source: dir.path().join("source.zip"),
-            symbol_lib: None,
-            footprint_lib: None,
-            step_dir: None,
+            sources: vec![dir.path().join("source.zip")],
+            ..Default::default()
         };
         let plan = resolve_import(args, dir.path()).unwrap();
         assert!(!plan.created_config());
@@ -369,14 +3698,416 @@ mod tests {
         )
         .unwrap();
         let args = ImportArgs {
-            source: dir.path().join("source.zip"),
+            sources: vec![dir.path().join("source.zip")],
             symbol_lib: Some(PathBuf::from("override.kicad_sym")),
-            footprint_lib: None,
             step_dir: Some(PathBuf::from("override_steps")),
+            ..Default::default()
         };
         let plan = resolve_import(args, dir.path()).unwrap();
         assert_eq!(plan.config().symbol_lib(), Path::new("override.kicad_sym"));
         assert_eq!(plan.config().footprint_lib(), Path::new("foot.pretty"));
         assert_eq!(plan.config().step_dir(), Path::new("override_steps"));
     }
+
+    #[test]
+    fn resolve_import_reads_value_decoders() {
+        let dir = tempdir().unwrap();
+        let config_path = dir.path().join(".kci_config");
+        std::fs::write(&config_path, "value_decoders = [\"murata-grm\"]\n").unwrap();
+        let args = ImportArgs {
+            sources: vec![dir.path().join("source.zip")],
+            ..Default::default()
+        };
+        let plan = resolve_import(args, dir.path()).unwrap();
+        assert_eq!(plan.config().value_decoders(), &[ValueDecoder::MurataGrm]);
+
+        let args = ImportArgs {
+            sources: vec![dir.path().join("source.zip")],
+            value_decoders: vec!["bogus".to_string()],
+            ..Default::default()
+        };
+        assert!(matches!(
+            resolve_import(args, dir.path()),
+            Err(ConfigError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn global_config_stores_digikey_credentials() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("kci").join(GLOBAL_CONFIG_FILE);
+        assert!(GlobalConfig::load(&path).unwrap().digikey.is_none());
+        let credentials = DigikeyCredentials::new("id", "secret");
+        GlobalConfig {
+            digikey: Some(credentials.clone()),
+            mouser: Some(MouserCredentials::new("key")),
+            nexar: None,
+            library_naming: None,
+            sync_command: None,
+            simplify_command: None,
+        }
+        .write(&path)
+        .unwrap();
+        assert_eq!(GlobalConfig::load(&path).unwrap().digikey, Some(credentials));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn resolve_import_reads_description_language() {
+        let dir = tempdir().unwrap();
+        let config_path = dir.path().join(".kci_config");
+        std::fs::write(
+            &config_path,
+            "description_language = \"de_DE\"\nkeep_description_translations = true\n",
+        )
+        .unwrap();
+        let args = ImportArgs {
+            sources: vec![dir.path().join("source.zip")],
+            ..Default::default()
+        };
+        let plan = resolve_import(args, dir.path()).unwrap();
+        let choice = plan.config().description_language().unwrap();
+        assert_eq!(choice.language(), "de-de");
+        assert!(choice.keep_translations());
+
+        std::fs::write(&config_path, "description_language = \"German\"\n").unwrap();
+        let args = ImportArgs {
+            sources: vec![dir.path().join("source.zip")],
+            ..Default::default()
+        };
+        assert!(matches!(
+            resolve_import(args, dir.path()),
+            Err(ConfigError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn resolve_import_reads_signing_section() {
+        let dir = tempdir().unwrap();
+        let config_path = dir.path().join(".kci_config");
+        std::fs::write(
+            &config_path,
+            "operator = \"ci\"\n[signing]\nformat = \"ssh\"\nkey = \"id_ed25519\"\nidentity = \"ci@example.com\"\n",
+        )
+        .unwrap();
+        let args = ImportArgs {
+            sources: vec![dir.path().join("source.zip")],
+            ..Default::default()
+        };
+        let plan = resolve_import(args, dir.path()).unwrap();
+        let signing = plan.config().signing().unwrap();
+        assert_eq!(signing.format(), SigningFormat::Ssh);
+        assert_eq!(signing.key(), Path::new("id_ed25519"));
+        assert_eq!(signing.identity(), Some("ci@example.com"));
+        assert_eq!(plan.config().operator(), Some("ci"));
+
+        std::fs::write(&config_path, "[signing]\nformat = \"gpg\"\nkey = \"k\"\n").unwrap();
+        let args = ImportArgs {
+            sources: vec![dir.path().join("source.zip")],
+            ..Default::default()
+        };
+        assert!(matches!(
+            resolve_import(args, dir.path()),
+            Err(ConfigError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn import_local_applies_all_sources_or_nothing() {
+        let dir = tempdir().unwrap();
+        let write_source = |name: &str, part: &str, footprint: &str| {
+            let source = dir.path().join(name);
+            std::fs::create_dir_all(&source).unwrap();
+            std::fs::write(
+                source.join("lib.kicad_sym"),
+                format!(
+                    "(kicad_symbol_lib (version 20231120) (symbol \"{}\" (property \"Footprint\" \"Vendor:{}\")))",
+                    part, footprint
+                ),
+            )
+            .unwrap();
+            std::fs::write(
+                source.join(format!("{}.kicad_mod", footprint)),
+                format!("(footprint \"{}\")", footprint),
+            )
+            .unwrap();
+            source
+        };
+        let first = write_source("first", "PartA", "FpA");
+        let second = write_source("second", "PartB", "FpB");
+        let args = ImportArgs {
+            sources: vec![first, second],
+            ..Default::default()
+        };
+        let plan = resolve_import(args, dir.path()).unwrap();
+        let report = import_local(&plan, dir.path()).unwrap();
+        assert_eq!(report.symbols_added(), 2);
+        assert_eq!(report.footprints_added(), 2);
+        let symbol_lib = dir.path().join(plan.config().symbol_lib());
+        let footprint_lib = dir.path().join(plan.config().footprint_lib());
+        assert!(footprint_lib.join("FpA.kicad_mod").exists());
+        assert!(footprint_lib.join("FpB.kicad_mod").exists());
+        let before = std::fs::read_to_string(&symbol_lib).unwrap();
+        assert!(before.contains("PartA") && before.contains("PartB"));
+
+        let third = write_source("third", "PartC", "FpC");
+        std::fs::write(third.join("FpC.step"), "ISO-10303-21;").unwrap();
+        std::fs::write(dir.path().join(plan.config().step_dir()), "not a directory").unwrap();
+        let args = ImportArgs {
+            sources: vec![third],
+            ..Default::default()
+        };
+        let plan = resolve_import(args, dir.path()).unwrap();
+        assert!(import_local(&plan, dir.path()).is_err());
+        assert_eq!(std::fs::read_to_string(&symbol_lib).unwrap(), before);
+        assert!(!footprint_lib.join("FpC.kicad_mod").exists());
+    }
+
+    #[test]
+    fn interrupted_import_leaves_a_journal_to_resume() {
+        let dir = tempdir().unwrap();
+        let source = dir.path().join("vendor");
+        std::fs::create_dir_all(&source).unwrap();
+        std::fs::write(
+            source.join("lib.kicad_sym"),
+            "(kicad_symbol_lib (version 20231120) (symbol \"PartA\" (property \"Footprint\" \"Vendor:FpA\")))",
+        )
+        .unwrap();
+        std::fs::write(source.join("FpA.kicad_mod"), "(footprint \"FpA\")").unwrap();
+        std::fs::write(source.join("FpA.step"), "ISO-10303-21;").unwrap();
+        let args = ImportArgs {
+            sources: vec![source],
+            ..Default::default()
+        };
+        let journal = ImportJournal::new(toml::Value::try_from(&args).unwrap());
+        let mut plan = resolve_import(args, dir.path()).unwrap();
+        plan.config = plan.config.clone().with_journal(Some(journal));
+        let step_dir = dir.path().join(plan.config().step_dir());
+        std::fs::write(&step_dir, "not a directory").unwrap();
+        assert!(import_local(&plan, dir.path()).is_err());
+        let journal_path = ImportJournal::path(dir.path());
+        let mut journal = ImportJournal::load(&journal_path).unwrap().unwrap();
+
+        std::fs::remove_file(&step_dir).unwrap();
+        std::fs::create_dir_all(&step_dir).unwrap();
+        std::fs::write(step_dir.join("FpA.step"), "ISO-10303-21;").unwrap();
+        journal.set_step_files(vec!["FpA.step".to_string()]);
+        plan.config = plan.config.clone().with_journal(Some(journal));
+        let report = import_local(&plan, dir.path()).unwrap();
+        assert_eq!(report.symbols_added(), 1);
+        assert_eq!(report.step_files_added(), 0);
+        assert_eq!(report.step_bytes_copied(), 0);
+        assert_eq!(
+            ImportJournal::load(&journal_path)
+                .unwrap()
+                .unwrap()
+                .step_files(),
+            ["FpA.step"]
+        );
+    }
+
+    #[test]
+    fn import_local_measures_copying_models_into_the_project() {
+        let dir = tempdir().unwrap();
+        let source = dir.path().join("vendor");
+        std::fs::create_dir_all(&source).unwrap();
+        std::fs::write(
+            source.join("lib.kicad_sym"),
+            "(kicad_symbol_lib (version 20231120) (symbol \"PartA\" (property \"Footprint\" \"Vendor:FpA\")))",
+        )
+        .unwrap();
+        std::fs::write(source.join("FpA.kicad_mod"), "(footprint \"FpA\")").unwrap();
+        std::fs::write(source.join("FpB.kicad_mod"), "(footprint \"FpB\")").unwrap();
+        std::fs::write(source.join("FpA.step"), vec![b'a'; 3000]).unwrap();
+        std::fs::write(source.join("FpB.step"), vec![b'b'; 5000]).unwrap();
+        std::fs::write(dir.path().join(".kci_config"), "copy_jobs = 2\n").unwrap();
+        let args = ImportArgs {
+            sources: vec![source],
+            ..Default::default()
+        };
+        let plan = resolve_import(args, dir.path()).unwrap();
+        assert_eq!(plan.config().copy_jobs(), 2);
+        let report = import_local(&plan, dir.path()).unwrap();
+        assert_eq!(report.step_files_added(), 2);
+        assert_eq!(report.step_bytes_copied(), 8000);
+        let step_dir = dir.path().join(plan.config().step_dir());
+        assert_eq!(std::fs::read(step_dir.join("FpA.step")).unwrap().len(), 3000);
+        assert_eq!(std::fs::read(step_dir.join("FpB.step")).unwrap().len(), 5000);
+    }
+
+    #[test]
+    fn lifecycle_and_ledger_are_part_of_the_staged_import() {
+        let dir = tempdir().unwrap();
+        let source = dir.path().join("vendor");
+        std::fs::create_dir_all(&source).unwrap();
+        let write_symbol = |pins: &str| {
+            std::fs::write(
+                source.join("lib.kicad_sym"),
+                format!(
+                    "(kicad_symbol_lib (version 20231120) (symbol \"PartA\" \
+                     (property \"Footprint\" \"Vendor:FpA\"){}))",
+                    pins
+                ),
+            )
+            .unwrap();
+        };
+        write_symbol("");
+        std::fs::write(source.join("FpA.kicad_mod"), "(footprint \"FpA\")").unwrap();
+        let patch_path = dir.path().join("import.patch");
+        let args = ImportArgs {
+            sources: vec![source.clone()],
+            emit_patch: Some(patch_path.clone()),
+            ..Default::default()
+        };
+        let plan = resolve_import(args, dir.path()).unwrap();
+        run_import_staged(&plan, dir.path()).unwrap();
+        let patch = std::fs::read_to_string(&patch_path).unwrap();
+        assert!(patch.contains("kci_tag:lifecycle"), "{}", patch);
+        assert!(patch.contains("b/.kci/ledger.toml"), "{}", patch);
+
+        let args = ImportArgs {
+            sources: vec![source.clone()],
+            ..Default::default()
+        };
+        let plan = resolve_import(args, dir.path()).unwrap();
+        import_local(&plan, dir.path()).unwrap();
+        let symbol_lib = dir.path().join(plan.config().symbol_lib());
+        let lifecycle = || {
+            let tags = load_tags(TagStore::Property, &symbol_lib, dir.path()).unwrap();
+            Lifecycle::of(&tags["PartA"])
+        };
+        assert_eq!(lifecycle(), Lifecycle::Draft);
+        assert!(Ledger::path(dir.path()).is_file());
+
+        let approved = ["PartA".to_string()];
+        set_lifecycle(TagStore::Property, &symbol_lib, dir.path(), &approved, Lifecycle::Approved)
+            .unwrap();
+        import_local(&plan, dir.path()).unwrap();
+        assert_eq!(lifecycle(), Lifecycle::Approved);
+
+        write_symbol(" (pin input line (at 0 0 0) (length 2.54) (number \"1\"))");
+        import_local(&plan, dir.path()).unwrap();
+        assert_eq!(lifecycle(), Lifecycle::Draft);
+    }
+
+    #[test]
+    fn removing_a_part_drops_its_review_item_and_ledger_record() {
+        let dir = tempdir().unwrap();
+        let config = ImportConfig::new(
+            PathBuf::from("lib.kicad_sym"),
+            PathBuf::from("lib.pretty"),
+            PathBuf::from("lib_3d"),
+        );
+        std::fs::write(
+            dir.path().join("lib.kicad_sym"),
+            r#"(kicad_symbol_lib
+                (symbol "NE555" (property "Footprint" "lib:DIP-8"))
+                (symbol "LM358" (property "Footprint" "")))"#,
+        )
+        .unwrap();
+        std::fs::create_dir_all(dir.path().join("lib.pretty")).unwrap();
+        std::fs::write(dir.path().join("lib.pretty/DIP-8.kicad_mod"), "(footprint \"DIP-8\")")
+            .unwrap();
+        let mut ledger = Ledger::default();
+        ledger.record_footprint("DIP-8", Vec::new());
+        ledger.save(dir.path()).unwrap();
+        let mut queue = ReviewQueue::default();
+        queue.push(ReviewItem::new("NE555", Some("lib:DIP-8"), "low-confidence match"));
+        queue.push(ReviewItem::new("LM358", None, "no footprint assigned"));
+        queue.save(dir.path()).unwrap();
+
+        let removal = remove_symbol(dir.path(), &config, "NE555", true).unwrap();
+        forget_removed(dir.path(), &config, &removal).unwrap();
+
+        let queue = ReviewQueue::load(dir.path()).unwrap();
+        let symbols: Vec<&str> = queue.items().iter().map(ReviewItem::symbol).collect();
+        assert_eq!(symbols, ["LM358"]);
+        assert!(Ledger::load(dir.path()).unwrap().footprints().is_empty());
+    }
+
+    #[test]
+    fn project_commands_need_explicit_trust() {
+        let dir = tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(".kci_config"),
+            "sync_command = \"rsync -a libs/ libserver:/srv/kicad/\"\n",
+        )
+        .unwrap();
+        let args = ImportArgs {
+            sources: vec![dir.path().join("source.zip")],
+            ..Default::default()
+        };
+        let plan = resolve_import(args.clone(), dir.path()).unwrap();
+        assert_eq!(plan.config().sync_command(), None);
+
+        let args = ImportArgs {
+            trust_project_commands: true,
+            ..args
+        };
+        let plan = resolve_import(args.clone(), dir.path()).unwrap();
+        assert_eq!(
+            plan.config().sync_command(),
+            Some("rsync -a libs/ libserver:/srv/kicad/")
+        );
+
+        std::fs::write(
+            dir.path().join(".kci_config"),
+            "oversize_models = \"simplify\"\nsimplify_command = \"shrink {input} {output}\"\n",
+        )
+        .unwrap();
+        assert!(load_config(ConfigOverrides::default(), dir.path(), false).is_ok());
+        let untrusted = ImportArgs {
+            trust_project_commands: false,
+            ..args.clone()
+        };
+        assert!(matches!(
+            resolve_import(untrusted, dir.path()),
+            Err(ConfigError::Invalid(_))
+        ));
+        let plan = resolve_import(args, dir.path()).unwrap();
+        assert_eq!(
+            plan.config().simplify_command().map(SimplifyCommand::template),
+            Some("shrink {input} {output}")
+        );
+    }
+
+    #[test]
+    fn verify_requires_manifest_and_ledger_signatures() {
+        let dir = tempdir().unwrap();
+        let manifest = Manifest::path(dir.path());
+        let ledger = Ledger::path(dir.path());
+        std::fs::create_dir_all(manifest.parent().unwrap()).unwrap();
+        std::fs::write(&manifest, "").unwrap();
+        std::fs::write(&ledger, "").unwrap();
+        let signing = SigningConfig::new(SigningFormat::Minisign, PathBuf::from("kci.key"));
+
+        let unsigned = |path: &Path| match verify_signatures(dir.path(), &signing) {
+            Err(CliError::Signing(SigningError::Unsigned(missing))) => missing == path,
+            _ => false,
+        };
+        assert!(unsigned(&manifest));
+        std::fs::write(signature_path(&manifest, SigningFormat::Minisign), "").unwrap();
+        assert!(unsigned(&ledger));
+        std::fs::write(signature_path(&ledger, SigningFormat::Minisign), "").unwrap();
+        assert!(verify_signatures(dir.path(), &signing).unwrap().is_empty());
+    }
+
+    #[test]
+    fn dry_run_does_not_write_config() {
+        let dir = tempdir().unwrap();
+        let args = ImportArgs {
+            sources: vec![dir.path().join("source.zip")],
+            dry_run: true,
+            ..Default::default()
+        };
+        let plan = resolve_import(args, dir.path()).unwrap();
+        assert!(plan.dry_run());
+        assert!(!plan.created_config());
+        assert!(!dir.path().join(".kci_config").exists());
+    }
 }
@@ -0,0 +1,557 @@
+use crate::kicad_sym::{Atom, KicadSymError, Sexp, parse_one};
+use crate::kicad_table::{
+    TableError, TableKind, lib_name_from_path, project_table_entries, remove_project_lib,
+};
+use crate::lookup::LibraryIndex;
+use crate::rename::{
+    RenameError, project_files, rewrite_references, rewrite_symbol_footprints, set_footprint_name,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+#[derive(Debug)]
+pub enum ConsolidateError {
+    Io(io::Error),
+    Symbol(KicadSymError),
+    Table(TableError),
+    Rename(RenameError),
+    Invalid(String),
+}
+
+impl fmt::Display for ConsolidateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConsolidateError::Io(err) => write!(f, "io error: {}", err),
+            ConsolidateError::Symbol(err) => write!(f, "footprint parse error: {}", err),
+            ConsolidateError::Table(err) => write!(f, "{}", err),
+            ConsolidateError::Rename(err) => write!(f, "{}", err),
+            ConsolidateError::Invalid(msg) => write!(f, "cannot consolidate: {}", msg),
+        }
+    }
+}
+
+impl Error for ConsolidateError {}
+
+impl From<io::Error> for ConsolidateError {
+    fn from(value: io::Error) -> Self {
+        ConsolidateError::Io(value)
+    }
+}
+
+impl From<KicadSymError> for ConsolidateError {
+    fn from(value: KicadSymError) -> Self {
+        ConsolidateError::Symbol(value)
+    }
+}
+
+impl From<TableError> for ConsolidateError {
+    fn from(value: TableError) -> Self {
+        ConsolidateError::Table(value)
+    }
+}
+
+impl From<RenameError> for ConsolidateError {
+    fn from(value: RenameError) -> Self {
+        ConsolidateError::Rename(value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeKind {
+    Moved,
+    Duplicate,
+    Renamed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergedFootprint {
+    from: String,
+    to: String,
+    kind: MergeKind,
+}
+
+impl MergedFootprint {
+    pub fn from(&self) -> &str {
+        &self.from
+    }
+
+    pub fn to(&self) -> &str {
+        &self.to
+    }
+
+    pub fn kind(&self) -> MergeKind {
+        self.kind
+    }
+}
+
+impl fmt::Display for MergedFootprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            MergeKind::Moved => write!(f, "{} -> {}", self.from, self.to),
+            MergeKind::Duplicate => write!(f, "{} -> {} (duplicate)", self.from, self.to),
+            MergeKind::Renamed => write!(f, "{} -> {} (renamed, name taken)", self.from, self.to),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Consolidation {
+    merged_libs: Vec<String>,
+    footprints: Vec<MergedFootprint>,
+    updated: Vec<PathBuf>,
+}
+
+impl Consolidation {
+    pub fn merged_libs(&self) -> &[String] {
+        &self.merged_libs
+    }
+
+    pub fn footprints(&self) -> &[MergedFootprint] {
+        &self.footprints
+    }
+
+    pub fn updated(&self) -> &[PathBuf] {
+        &self.updated
+    }
+
+    pub fn count(&self, kind: MergeKind) -> usize {
+        self.footprints
+            .iter()
+            .filter(|footprint| footprint.kind == kind)
+            .count()
+    }
+}
+
+pub fn consolidate_footprint_libs(
+    project_root: &Path,
+    target: &Path,
+    libs: &[String],
+) -> Result<Consolidation, ConsolidateError> {
+    let target_name = lib_name_from_path(TableKind::Footprint, target)?;
+    let entries = project_table_entries(project_root, TableKind::Footprint)?;
+    for lib in libs {
+        if !entries.iter().any(|entry| entry.name() == lib) {
+            return Err(ConsolidateError::Invalid(format!(
+                "fp-lib-table has no library named {}",
+                lib
+            )));
+        }
+        if *lib == target_name {
+            return Err(ConsolidateError::Invalid(format!(
+                "{} is the library being merged into",
+                lib
+            )));
+        }
+    }
+    let mut sources = Vec::new();
+    for entry in entries {
+        if entry.name() == target_name
+            || !(libs.is_empty() || libs.iter().any(|lib| lib == entry.name()))
+        {
+            continue;
+        }
+        match entry.path() {
+            Some(path) if path.is_dir() => {
+                sources.push((entry.name().to_string(), path.to_path_buf()))
+            }
+            _ if libs.is_empty() => continue,
+            _ => {
+                return Err(ConsolidateError::Invalid(format!(
+                    "{} is not a local footprint library ({})",
+                    entry.name(),
+                    entry.uri()
+                )));
+            }
+        }
+    }
+    if sources.is_empty() {
+        return Err(ConsolidateError::Invalid(format!(
+            "no other footprint libraries to merge into {}",
+            target_name
+        )));
+    }
+
+    let target_dir = project_root.join(target);
+    let mut extras = Vec::new();
+    let mut planned: HashMap<PathBuf, PathBuf> = HashMap::new();
+    for (_, dir) in sources
+        .iter()
+        .filter(|(_, dir)| dir.starts_with(project_root))
+    {
+        for entry in WalkDir::new(dir).min_depth(1) {
+            let entry = entry.map_err(io::Error::from)?;
+            if entry.file_type().is_dir() || (entry.depth() == 1 && is_footprint(entry.path())) {
+                continue;
+            }
+            let dest = target_dir.join(entry.path().strip_prefix(dir).unwrap_or(entry.path()));
+            let clash = match planned.get(&dest) {
+                Some(other) => !same_content(other, entry.path())?,
+                None => dest.exists() && !same_content(&dest, entry.path())?,
+            };
+            if clash {
+                return Err(ConsolidateError::Invalid(format!(
+                    "{} would overwrite {}",
+                    entry.path().display(),
+                    dest.display()
+                )));
+            }
+            planned.insert(dest.clone(), entry.path().to_path_buf());
+            extras.push((entry.path().to_path_buf(), dest));
+        }
+    }
+    fs::create_dir_all(&target_dir)?;
+    let mut existing = BTreeMap::new();
+    for (name, path) in footprint_files(&target_dir)? {
+        existing.insert(name, normalized(&parse_one(&fs::read_to_string(path)?)?));
+    }
+
+    let mut footprints = Vec::new();
+    let mut lib_ids = HashMap::new();
+    for (lib, dir) in &sources {
+        let relocate = |model: &str| {
+            dir.starts_with(project_root)
+                .then(|| relocated_model(model, project_root, dir, &target_dir))
+                .flatten()
+        };
+        for (name, path) in footprint_files(dir)? {
+            let mut sexp = parse_one(&fs::read_to_string(&path)?)?;
+            let content = normalized(&sexp);
+            let relocated = relocate_models(&mut sexp, relocate);
+            let duplicate = existing
+                .iter()
+                .find(|(_, other)| **other == content)
+                .map(|(other, _)| other.clone());
+            let (new_name, kind) = match duplicate {
+                Some(other) => (other, MergeKind::Duplicate),
+                None if existing.contains_key(&name) => {
+                    let new_name = free_name(&existing, &format!("{}_{}", name, lib));
+                    set_footprint_name(&mut sexp, &new_name);
+                    fs::write(
+                        target_dir.join(format!("{}.kicad_mod", new_name)),
+                        sexp.to_string_pretty(),
+                    )?;
+                    (new_name, MergeKind::Renamed)
+                }
+                None => {
+                    let dest = target_dir.join(format!("{}.kicad_mod", name));
+                    if relocated {
+                        fs::write(dest, sexp.to_string_pretty())?;
+                    } else {
+                        fs::copy(&path, dest)?;
+                    }
+                    (name.clone(), MergeKind::Moved)
+                }
+            };
+            existing.entry(new_name.clone()).or_insert(content);
+            let from = format!("{}:{}", lib, name);
+            let to = format!("{}:{}", target_name, new_name);
+            lib_ids.insert(from.clone(), to.clone());
+            footprints.push(MergedFootprint { from, to, kind });
+        }
+    }
+
+    let rewrite = |value: &str| lib_ids.get(value).cloned();
+    let mut updated = Vec::new();
+    for entry in project_table_entries(project_root, TableKind::Symbol)? {
+        if let Some(path) = entry.path()
+            && path.is_file()
+            && rewrite_symbol_footprints(path, &rewrite)?
+        {
+            updated.push(
+                path.strip_prefix(project_root)
+                    .unwrap_or(path)
+                    .to_path_buf(),
+            );
+        }
+    }
+    for path in project_files(project_root).map_err(RenameError::from)? {
+        if rewrite_references(&path, &rewrite)? {
+            updated.push(
+                path.strip_prefix(project_root)
+                    .unwrap_or(&path)
+                    .to_path_buf(),
+            );
+        }
+    }
+
+    for (source, dest) in &extras {
+        if dest.exists() {
+            fs::remove_file(source)?;
+            continue;
+        }
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        if fs::rename(source, dest).is_err() {
+            fs::copy(source, dest)?;
+            fs::remove_file(source)?;
+        }
+    }
+    let mut merged_libs = Vec::new();
+    for (lib, dir) in sources {
+        remove_project_lib(project_root, TableKind::Footprint, &lib)?;
+        if dir.starts_with(project_root) {
+            for (_, path) in footprint_files(&dir)? {
+                fs::remove_file(path)?;
+            }
+            remove_empty_dirs(&dir)?;
+        }
+        merged_libs.push(lib);
+    }
+    let index = LibraryIndex::path(project_root);
+    if index.exists() {
+        fs::remove_file(index)?;
+    }
+
+    Ok(Consolidation {
+        merged_libs,
+        footprints,
+        updated,
+    })
+}
+
+fn footprint_files(dir: &Path) -> Result<Vec<(String, PathBuf)>, ConsolidateError> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if !is_footprint(&path) {
+            continue;
+        }
+        if let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) {
+            files.push((name.to_string(), path.clone()));
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn is_footprint(path: &Path) -> bool {
+    path.extension().and_then(|ext| ext.to_str()) == Some("kicad_mod")
+}
+
+fn same_content(a: &Path, b: &Path) -> io::Result<bool> {
+    Ok(fs::read(a)? == fs::read(b)?)
+}
+
+fn remove_empty_dirs(dir: &Path) -> io::Result<()> {
+    for entry in WalkDir::new(dir).contents_first(true) {
+        let entry = entry?;
+        if entry.file_type().is_dir() && fs::read_dir(entry.path())?.next().is_none() {
+            fs::remove_dir(entry.path())?;
+        }
+    }
+    Ok(())
+}
+
+fn relocated_model(
+    model: &str,
+    project_root: &Path,
+    source_dir: &Path,
+    target_dir: &Path,
+) -> Option<String> {
+    let (path, prefix) = match model.strip_prefix("${KIPRJMOD}") {
+        Some(rest) => (
+            project_root.join(rest.trim_start_matches(['/', '\\'])),
+            Some("${KIPRJMOD}/"),
+        ),
+        None if model.contains("${") => return None,
+        None if Path::new(model).is_absolute() => (PathBuf::from(model), None),
+        None => (project_root.join(model), Some("")),
+    };
+    let inner = path.strip_prefix(source_dir).ok()?;
+    let dest = target_dir.join(inner);
+    let dest = match prefix {
+        Some(prefix) => format!(
+            "{}{}",
+            prefix,
+            dest.strip_prefix(project_root).ok()?.to_string_lossy()
+        ),
+        None => dest.to_string_lossy().to_string(),
+    };
+    Some(dest.replace('\\', "/"))
+}
+
+fn relocate_models(footprint: &mut Sexp, relocate: impl Fn(&str) -> Option<String>) -> bool {
+    let Sexp::List(items) = footprint else {
+        return false;
+    };
+    let mut changed = false;
+    for item in items {
+        let Sexp::List(fields) = item else {
+            continue;
+        };
+        let [Sexp::Atom(head), Sexp::Atom(path), ..] = fields.as_mut_slice() else {
+            continue;
+        };
+        if head.value() != "model" {
+            continue;
+        }
+        if let Some(new_path) = relocate(path.value()) {
+            *path = Atom::new_quoted(new_path);
+            changed = true;
+        }
+    }
+    changed
+}
+
+fn normalized(footprint: &Sexp) -> String {
+    let mut footprint = footprint.clone();
+    set_footprint_name(&mut footprint, "");
+    footprint.to_string_pretty()
+}
+
+fn free_name(existing: &BTreeMap<String, String>, base: &str) -> String {
+    let mut name = base.to_string();
+    let mut counter = 2;
+    while existing.contains_key(&name) {
+        name = format!("{}_{}", base, counter);
+        counter += 1;
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::importer::ImportConfig;
+    use crate::kicad_sym::KicadSymbolLib;
+    use crate::kicad_table::ensure_project_tables;
+    use tempfile::tempdir;
+
+    fn write_footprint(dir: &Path, name: &str, body: &str) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(
+            dir.join(format!("{}.kicad_mod", name)),
+            format!("(footprint \"{}\" {})", name, body),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn merges_libraries_and_rewrites_references() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let config = ImportConfig::new(
+            PathBuf::from("board_symbols.kicad_sym"),
+            PathBuf::from("board_footprints.pretty"),
+            PathBuf::from("board_step"),
+        );
+        ensure_project_tables(root, &config).unwrap();
+        for lib in ["vendor_a", "vendor_b"] {
+            let other = ImportConfig::new(
+                PathBuf::from("board_symbols.kicad_sym"),
+                PathBuf::from(format!("{}.pretty", lib)),
+                PathBuf::from("board_step"),
+            );
+            ensure_project_tables(root, &other).unwrap();
+        }
+        let target = root.join("board_footprints.pretty");
+        write_footprint(&target, "SOIC-8", "(pad 1 smd rect)");
+        write_footprint(&root.join("vendor_a.pretty"), "SOIC8", "(pad 1 smd rect)");
+        write_footprint(&root.join("vendor_a.pretty"), "SOT-23", "(pad 1 smd oval)");
+        write_footprint(
+            &root.join("vendor_b.pretty"),
+            "SOIC-8",
+            "(pad 1 thru_hole circle)",
+        );
+        fs::write(
+            root.join("board_symbols.kicad_sym"),
+            r#"(kicad_symbol_lib
+                (symbol "A" (property "Footprint" "vendor_a:SOIC8"))
+                (symbol "B" (property "Footprint" "vendor_b:SOIC-8")))"#,
+        )
+        .unwrap();
+        fs::write(
+            root.join("board.kicad_pcb"),
+            r#"(kicad_pcb (footprint "vendor_a:SOT-23") (footprint "other:SOT-23"))"#,
+        )
+        .unwrap();
+
+        let consolidation = consolidate_footprint_libs(root, config.footprint_lib(), &[]).unwrap();
+        assert_eq!(consolidation.merged_libs(), ["vendor_a", "vendor_b"]);
+        assert_eq!(
+            consolidation
+                .footprints()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            [
+                "vendor_a:SOIC8 -> board_footprints:SOIC-8 (duplicate)",
+                "vendor_a:SOT-23 -> board_footprints:SOT-23",
+                "vendor_b:SOIC-8 -> board_footprints:SOIC-8_vendor_b (renamed, name taken)",
+            ]
+        );
+        assert!(target.join("SOIC-8_vendor_b.kicad_mod").exists());
+        assert!(!root.join("vendor_a.pretty").exists());
+
+        let lib = KicadSymbolLib::parse(
+            &fs::read_to_string(root.join("board_symbols.kicad_sym")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            lib.symbol("B")
+                .unwrap()
+                .property_value("Footprint")
+                .as_deref(),
+            Some("board_footprints:SOIC-8_vendor_b")
+        );
+        let board = fs::read_to_string(root.join("board.kicad_pcb")).unwrap();
+        assert!(board.contains("\"board_footprints:SOT-23\""));
+        assert!(board.contains("\"other:SOT-23\""));
+        let table = fs::read_to_string(root.join("fp-lib-table")).unwrap();
+        assert!(!table.contains("vendor_"));
+    }
+
+    #[test]
+    fn moves_models_stored_inside_merged_libraries() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let config = ImportConfig::new(
+            PathBuf::from("board_symbols.kicad_sym"),
+            PathBuf::from("board_footprints.pretty"),
+            PathBuf::from("board_step"),
+        );
+        ensure_project_tables(root, &config).unwrap();
+        let vendor = ImportConfig::new(
+            PathBuf::from("board_symbols.kicad_sym"),
+            PathBuf::from("Vendor.pretty"),
+            PathBuf::from("board_step"),
+        );
+        ensure_project_tables(root, &vendor).unwrap();
+        let source = root.join("Vendor.pretty");
+        write_footprint(
+            &source,
+            "SOT-23",
+            "(pad 1 smd rect) (model \"${KIPRJMOD}/Vendor.pretty/3d/SOT-23.step\")",
+        );
+        fs::create_dir_all(source.join("3d")).unwrap();
+        fs::write(source.join("3d/SOT-23.step"), "vendor").unwrap();
+        let target = root.join("board_footprints.pretty");
+        fs::create_dir_all(target.join("3d")).unwrap();
+        fs::write(target.join("3d/SOT-23.step"), "ours").unwrap();
+
+        let err = consolidate_footprint_libs(root, config.footprint_lib(), &[]).unwrap_err();
+        assert!(matches!(err, ConsolidateError::Invalid(_)), "{}", err);
+        assert!(source.join("3d/SOT-23.step").exists());
+        assert!(!target.join("SOT-23.kicad_mod").exists());
+
+        fs::remove_file(target.join("3d/SOT-23.step")).unwrap();
+        consolidate_footprint_libs(root, config.footprint_lib(), &[]).unwrap();
+        assert_eq!(
+            fs::read_to_string(target.join("3d/SOT-23.step")).unwrap(),
+            "vendor"
+        );
+        let footprint = fs::read_to_string(target.join("SOT-23.kicad_mod")).unwrap();
+        assert!(
+            footprint.contains("\"${KIPRJMOD}/board_footprints.pretty/3d/SOT-23.step\""),
+            "{}",
+            footprint
+        );
+        assert!(!source.exists());
+    }
+}
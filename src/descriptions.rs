@@ -0,0 +1,185 @@
+use crate::kicad_sym::Symbol;
+
+const DESCRIPTION_PROPERTIES: &[&str] = &["Description", "ki_description"];
+const FIRST_DESCRIPTION_PROPERTY_VERSION: u32 = 8;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DescriptionLanguage {
+    language: String,
+    keep_translations: bool,
+}
+
+impl DescriptionLanguage {
+    pub fn new(language: &str) -> Result<Self, String> {
+        let language = normalize_language(language)
+            .ok_or_else(|| format!("invalid description language: {}", language))?;
+        Ok(Self {
+            language,
+            keep_translations: false,
+        })
+    }
+
+    pub fn with_keep_translations(mut self, keep: bool) -> Self {
+        self.keep_translations = keep;
+        self
+    }
+
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    pub fn keep_translations(&self) -> bool {
+        self.keep_translations
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DescriptionChoice {
+    NoTranslations,
+    Selected(String),
+    Unavailable,
+}
+
+pub fn apply_description_language(
+    symbol: &mut Symbol,
+    choice: &DescriptionLanguage,
+    kicad_version: Option<u32>,
+) -> DescriptionChoice {
+    let translations: Vec<(String, String, String)> = symbol
+        .properties()
+        .into_iter()
+        .filter_map(|(name, value)| Some((localized_language(&name)?, name, value)))
+        .collect();
+    if translations.is_empty() {
+        return DescriptionChoice::NoTranslations;
+    }
+    let primary = |tag: &str| tag.split('-').next().unwrap_or_default().to_string();
+    let selected = translations
+        .iter()
+        .find(|(language, _, _)| *language == choice.language)
+        .or_else(|| {
+            translations
+                .iter()
+                .find(|(language, _, _)| primary(language) == primary(&choice.language))
+        });
+    let Some((language, _, value)) = selected.cloned() else {
+        return DescriptionChoice::Unavailable;
+    };
+
+    let target = DESCRIPTION_PROPERTIES
+        .iter()
+        .find(|name| symbol.property_value(name).is_some())
+        .copied()
+        .unwrap_or(match kicad_version {
+            Some(version) if version < FIRST_DESCRIPTION_PROPERTY_VERSION => "ki_description",
+            _ => "Description",
+        });
+    symbol.set_or_add_hidden_property(target, &value);
+    if !choice.keep_translations {
+        for (_, name, _) in &translations {
+            symbol.remove_property(name);
+        }
+    }
+    DescriptionChoice::Selected(language)
+}
+
+pub fn localized_language(property: &str) -> Option<String> {
+    let lower = property.to_ascii_lowercase();
+    let rest = DESCRIPTION_PROPERTIES
+        .iter()
+        .find_map(|base| lower.strip_prefix(&base.to_ascii_lowercase()))?;
+    let tag = rest
+        .trim_start_matches([' ', '_', '-', ':', '.', '(', '['])
+        .trim_end_matches([')', ']']);
+    if tag.len() == rest.len() {
+        return None;
+    }
+    normalize_language(tag)
+}
+
+fn normalize_language(value: &str) -> Option<String> {
+    let tag = value.trim().to_ascii_lowercase().replace('_', "-");
+    let mut parts = tag.split('-');
+    let primary = parts.next()?;
+    if !(2..=3).contains(&primary.len()) || !primary.chars().all(|ch| ch.is_ascii_alphabetic()) {
+        return None;
+    }
+    let region_ok = match parts.next() {
+        None => true,
+        Some(region) => {
+            (2..=4).contains(&region.len()) && region.chars().all(|ch| ch.is_ascii_alphanumeric())
+        }
+    };
+    (region_ok && parts.next().is_none()).then_some(tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbol() -> Symbol {
+        Symbol::parse(
+            r#"(symbol "R" (property "Value" "R") (property "Description" "Resistor")
+                (property "Description_DE" "Widerstand") (property "ki_description (zh-CN)" "电阻"))"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn recognizes_localized_description_properties() {
+        assert_eq!(localized_language("Description_DE").as_deref(), Some("de"));
+        assert_eq!(
+            localized_language("ki_description (zh_CN)").as_deref(),
+            Some("zh-cn")
+        );
+        assert_eq!(localized_language("Description:fr").as_deref(), Some("fr"));
+        assert_eq!(localized_language("Description"), None);
+        assert_eq!(localized_language("Descriptions"), None);
+        assert_eq!(localized_language("Description_long_text"), None);
+        assert!(DescriptionLanguage::new("not a language").is_err());
+    }
+
+    #[test]
+    fn selects_configured_language() {
+        let mut german = symbol();
+        let choice = DescriptionLanguage::new("de-AT").unwrap();
+        assert_eq!(
+            apply_description_language(&mut german, &choice, None),
+            DescriptionChoice::Selected("de".to_string())
+        );
+        assert_eq!(german.property_value("Description").unwrap(), "Widerstand");
+        assert!(german.property_value("Description_DE").is_none());
+        assert!(german.property_value("ki_description (zh-CN)").is_none());
+
+        let mut chinese = symbol();
+        let choice = DescriptionLanguage::new("zh-CN")
+            .unwrap()
+            .with_keep_translations(true);
+        apply_description_language(&mut chinese, &choice, None);
+        assert_eq!(chinese.property_value("Description").unwrap(), "电阻");
+        assert_eq!(
+            chinese.property_value("Description_DE").unwrap(),
+            "Widerstand"
+        );
+
+        let mut legacy =
+            Symbol::parse(r#"(symbol "R" (property "Description_fr" "Résistance"))"#).unwrap();
+        let choice = DescriptionLanguage::new("fr").unwrap();
+        apply_description_language(&mut legacy, &choice, Some(7));
+        assert_eq!(
+            legacy.property_value("ki_description").unwrap(),
+            "Résistance"
+        );
+
+        let mut missing = symbol();
+        let choice = DescriptionLanguage::new("ja").unwrap();
+        assert_eq!(
+            apply_description_language(&mut missing, &choice, None),
+            DescriptionChoice::Unavailable
+        );
+        assert_eq!(
+            missing.property_value("Description_DE").unwrap(),
+            "Widerstand"
+        );
+    }
+}
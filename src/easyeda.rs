@@ -0,0 +1,695 @@
+use crate::kicad_sym::{
+    AddPolicy, KicadSymError, KicadSymbolLib, SCHEMATIC_PRECISION, Symbol, format_number,
+};
+use serde_json::Value;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+const MM_PER_UNIT: f64 = 0.254;
+const SYMBOL_FONT: &str = "(effects (font (size 1.27 1.27)))";
+const HIDDEN_SYMBOL_FONT: &str = "(effects (font (size 1.27 1.27)) hide)";
+
+#[derive(Debug)]
+pub enum EasyEdaError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Symbol(KicadSymError),
+    Invalid(String),
+}
+
+impl fmt::Display for EasyEdaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EasyEdaError::Io(err) => write!(f, "io error: {}", err),
+            EasyEdaError::Json(err) => write!(f, "EasyEDA json error: {}", err),
+            EasyEdaError::Symbol(err) => write!(f, "EasyEDA symbol conversion error: {}", err),
+            EasyEdaError::Invalid(msg) => write!(f, "invalid EasyEDA component: {}", msg),
+        }
+    }
+}
+
+impl Error for EasyEdaError {}
+
+impl From<io::Error> for EasyEdaError {
+    fn from(value: io::Error) -> Self {
+        EasyEdaError::Io(value)
+    }
+}
+
+impl From<serde_json::Error> for EasyEdaError {
+    fn from(value: serde_json::Error) -> Self {
+        EasyEdaError::Json(value)
+    }
+}
+
+impl From<KicadSymError> for EasyEdaError {
+    fn from(value: KicadSymError) -> Self {
+        EasyEdaError::Symbol(value)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Drawing {
+    origin: (f64, f64),
+    shapes: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct EasyEdaComponent {
+    name: String,
+    reference: String,
+    lcsc: Option<String>,
+    datasheet: String,
+    properties: Vec<(String, String)>,
+    footprint_name: String,
+    model_uuid: Option<String>,
+    symbol: Drawing,
+    footprint: Drawing,
+}
+
+impl EasyEdaComponent {
+    pub fn parse(input: &str) -> Result<Self, EasyEdaError> {
+        let value: Value = serde_json::from_str(input)?;
+        let result = value.get("result").unwrap_or(&value);
+        let symbol_data = data_str(result)
+            .ok_or_else(|| EasyEdaError::Invalid("missing symbol dataStr".to_string()))?;
+        let package = result
+            .get("packageDetail")
+            .ok_or_else(|| EasyEdaError::Invalid("missing packageDetail".to_string()))?;
+        let footprint_data = data_str(package)
+            .ok_or_else(|| EasyEdaError::Invalid("missing footprint dataStr".to_string()))?;
+
+        let symbol_head = symbol_data.get("head").unwrap_or(&Value::Null);
+        let footprint_head = footprint_data.get("head").unwrap_or(&Value::Null);
+        let c_para = symbol_head.get("c_para").unwrap_or(&Value::Null);
+        let text = |value: &Value, key: &str| {
+            value
+                .get(key)
+                .and_then(Value::as_str)
+                .map(str::trim)
+                .filter(|text| !text.is_empty())
+                .map(str::to_string)
+        };
+
+        let name = text(c_para, "name")
+            .or_else(|| text(result, "title"))
+            .ok_or_else(|| EasyEdaError::Invalid("component has no name".to_string()))?;
+        let footprint_name = text(package, "title")
+            .or_else(|| text(c_para, "package"))
+            .ok_or_else(|| EasyEdaError::Invalid("footprint has no name".to_string()))?;
+        let reference = text(c_para, "pre")
+            .map(|pre| pre.trim_end_matches('?').to_string())
+            .filter(|pre| !pre.is_empty())
+            .unwrap_or_else(|| "U".to_string());
+        let lcsc = result
+            .get("lcsc")
+            .and_then(|lcsc| text(lcsc, "number"))
+            .or_else(|| text(c_para, "Supplier Part"));
+        let datasheet = result
+            .get("lcsc")
+            .and_then(|lcsc| text(lcsc, "url"))
+            .or_else(|| text(c_para, "link"))
+            .unwrap_or_default();
+        let properties = ["Manufacturer", "Manufacturer Part"]
+            .into_iter()
+            .filter_map(|key| text(c_para, key).map(|value| (key.to_string(), value)))
+            .collect();
+        let footprint_shapes = shapes(&footprint_data);
+        let model_uuid = text(footprint_head, "uuid_3d").or_else(|| {
+            footprint_shapes.iter().find_map(|shape| {
+                let json = shape.strip_prefix("SVGNODE~")?;
+                let node: Value = serde_json::from_str(json).ok()?;
+                text(node.get("attrs")?, "uuid")
+            })
+        });
+
+        Ok(Self {
+            name: sanitize_name(&name),
+            reference,
+            lcsc,
+            datasheet,
+            properties,
+            footprint_name: sanitize_name(&footprint_name),
+            model_uuid,
+            symbol: Drawing {
+                origin: origin(symbol_head),
+                shapes: shapes(&symbol_data),
+            },
+            footprint: Drawing {
+                origin: origin(footprint_head),
+                shapes: footprint_shapes,
+            },
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn footprint_name(&self) -> &str {
+        &self.footprint_name
+    }
+
+    pub fn lcsc(&self) -> Option<&str> {
+        self.lcsc.as_deref()
+    }
+
+    pub fn model_uuid(&self) -> Option<&str> {
+        self.model_uuid.as_deref()
+    }
+
+    pub fn to_symbol(&self) -> Result<Symbol, EasyEdaError> {
+        let mut properties = vec![
+            ("Reference".to_string(), self.reference.clone(), false),
+            ("Value".to_string(), self.name.clone(), false),
+            ("Footprint".to_string(), self.footprint_name.clone(), true),
+            ("Datasheet".to_string(), self.datasheet.clone(), true),
+        ];
+        if let Some(lcsc) = &self.lcsc {
+            properties.push(("LCSC".to_string(), lcsc.clone(), true));
+        }
+        for (key, value) in &self.properties {
+            properties.push((key.clone(), value.clone(), true));
+        }
+
+        let mut out = format!("(symbol {} (in_bom yes) (on_board yes)", quote(&self.name));
+        for (idx, (key, value, hidden)) in properties.iter().enumerate() {
+            let y = if idx == 0 { 2.54 } else { -2.54 * idx as f64 };
+            out.push_str(&format!(
+                " (property {} {} (at 0 {} 0) {})",
+                quote(key),
+                quote(value),
+                mm(y),
+                if *hidden {
+                    HIDDEN_SYMBOL_FONT
+                } else {
+                    SYMBOL_FONT
+                }
+            ));
+        }
+
+        let (ox, oy) = self.symbol.origin;
+        let point = |x: f64, y: f64| {
+            format!(
+                "{} {}",
+                mm((x - ox) * MM_PER_UNIT),
+                mm(-(y - oy) * MM_PER_UNIT)
+            )
+        };
+        let mut graphics = Vec::new();
+        let mut pins = Vec::new();
+        for shape in &self.symbol.shapes {
+            let fields: Vec<&str> = shape.split('~').collect();
+            match fields[0] {
+                "R" if fields.len() > 6 => {
+                    let (x, y) = (number(fields[1]), number(fields[2]));
+                    let (w, h) = (number(fields[5]), number(fields[6]));
+                    graphics.push(format!(
+                        "(rectangle (start {}) (end {}) (stroke (width 0) (type default)) (fill (type background)))",
+                        point(x, y),
+                        point(x + w, y + h)
+                    ));
+                }
+                "PL" | "PG" if fields.len() > 1 => {
+                    let mut points = coordinate_pairs(fields[1]);
+                    if fields[0] == "PG"
+                        && let Some(first) = points.first().copied()
+                    {
+                        points.push(first);
+                    }
+                    let pts: Vec<String> = points
+                        .iter()
+                        .map(|(x, y)| format!("(xy {})", point(*x, *y)))
+                        .collect();
+                    let fill = if fields[0] == "PG" {
+                        "background"
+                    } else {
+                        "none"
+                    };
+                    graphics.push(format!(
+                        "(polyline (pts {}) (stroke (width 0) (type default)) (fill (type {})))",
+                        pts.join(" "),
+                        fill
+                    ));
+                }
+                "E" if fields.len() > 3 => {
+                    graphics.push(format!(
+                        "(circle (center {}) (radius {}) (stroke (width 0) (type default)) (fill (type none)))",
+                        point(number(fields[1]), number(fields[2])),
+                        mm(number(fields[3]) * MM_PER_UNIT)
+                    ));
+                }
+                "P" => {
+                    if let Some(pin) = pin(shape, &point) {
+                        pins.push(pin);
+                    }
+                }
+                _ => {}
+            }
+        }
+        out.push_str(&format!(
+            " (symbol {} {})",
+            quote(&format!("{}_0_1", self.name)),
+            graphics.join(" ")
+        ));
+        out.push_str(&format!(
+            " (symbol {} {})",
+            quote(&format!("{}_1_1", self.name)),
+            pins.join(" ")
+        ));
+        out.push(')');
+        Ok(Symbol::parse(&out)?)
+    }
+
+    pub fn to_footprint(&self) -> String {
+        let (ox, oy) = self.footprint.origin;
+        let point = |x: f64, y: f64| {
+            format!(
+                "{} {}",
+                mm((x - ox) * MM_PER_UNIT),
+                mm((y - oy) * MM_PER_UNIT)
+            )
+        };
+        let mut items = Vec::new();
+        let mut through_hole = false;
+        for shape in &self.footprint.shapes {
+            let fields: Vec<&str> = shape.split('~').collect();
+            match fields[0] {
+                "PAD" if fields.len() > 9 => {
+                    let (w, h) = (
+                        number(fields[4]) * MM_PER_UNIT,
+                        number(fields[5]) * MM_PER_UNIT,
+                    );
+                    let pad_shape = match fields[1] {
+                        "ELLIPSE" if (w - h).abs() < f64::EPSILON => "circle",
+                        "ELLIPSE" | "OVAL" => "oval",
+                        _ => "rect",
+                    };
+                    let rotation = fields
+                        .get(11)
+                        .map(|value| (360.0 - number(value)).rem_euclid(360.0))
+                        .filter(|rotation| *rotation != 0.0)
+                        .map(|rotation| format!(" {}", mm(rotation)))
+                        .unwrap_or_default();
+                    let at = format!(
+                        "(at {}{})",
+                        point(number(fields[2]), number(fields[3])),
+                        rotation
+                    );
+                    let size = format!("(size {} {})", mm(w), mm(h));
+                    let pad = match fields[6] {
+                        "11" => {
+                            through_hole = true;
+                            let drill = number(fields[9]) * 2.0 * MM_PER_UNIT;
+                            format!(
+                                "(pad {} thru_hole {} {} {} (drill {}) (layers \"*.Cu\" \"*.Mask\"))",
+                                quote(fields[8]),
+                                pad_shape,
+                                at,
+                                size,
+                                mm(drill)
+                            )
+                        }
+                        layer => {
+                            let side = if layer == "2" { "B" } else { "F" };
+                            format!(
+                                "(pad {} smd {} {} {} (layers \"{side}.Cu\" \"{side}.Paste\" \"{side}.Mask\"))",
+                                quote(fields[8]),
+                                pad_shape,
+                                at,
+                                size
+                            )
+                        }
+                    };
+                    items.push(pad);
+                }
+                "TRACK" if fields.len() > 4 => {
+                    let Some(layer) = footprint_layer(fields[2]) else {
+                        continue;
+                    };
+                    let width = mm(number(fields[1]) * MM_PER_UNIT);
+                    let points = coordinate_pairs(fields[4]);
+                    for pair in points.windows(2) {
+                        items.push(format!(
+                            "(fp_line (start {}) (end {}) (stroke (width {}) (type solid)) (layer \"{}\"))",
+                            point(pair[0].0, pair[0].1),
+                            point(pair[1].0, pair[1].1),
+                            width,
+                            layer
+                        ));
+                    }
+                }
+                "CIRCLE" if fields.len() > 5 => {
+                    let Some(layer) = footprint_layer(fields[5]) else {
+                        continue;
+                    };
+                    let (cx, cy, r) = (number(fields[1]), number(fields[2]), number(fields[3]));
+                    items.push(format!(
+                        "(fp_circle (center {}) (end {}) (stroke (width {}) (type solid)) (fill none) (layer \"{}\"))",
+                        point(cx, cy),
+                        point(cx + r, cy),
+                        mm(number(fields[4]) * MM_PER_UNIT),
+                        layer
+                    ));
+                }
+                "HOLE" if fields.len() > 3 => {
+                    let diameter = mm(number(fields[3]) * 2.0 * MM_PER_UNIT);
+                    items.push(format!(
+                        "(pad \"\" np_thru_hole circle (at {}) (size {diameter} {diameter}) (drill {diameter}) (layers \"*.Cu\" \"*.Mask\"))",
+                        point(number(fields[1]), number(fields[2]))
+                    ));
+                }
+                _ => {}
+            }
+        }
+
+        let mut out = format!(
+            "(footprint {} (version 20221018) (generator kci) (layer \"F.Cu\") (attr {})",
+            quote(&self.footprint_name),
+            if through_hole { "through_hole" } else { "smd" }
+        );
+        out.push_str(" (fp_text reference \"REF**\" (at 0 -2.54) (layer \"F.SilkS\") (effects (font (size 1 1) (thickness 0.15))))");
+        out.push_str(&format!(
+            " (fp_text value {} (at 0 2.54) (layer \"F.Fab\") (effects (font (size 1 1) (thickness 0.15))))",
+            quote(&self.footprint_name)
+        ));
+        for item in items {
+            out.push(' ');
+            out.push_str(&item);
+        }
+        out.push(')');
+        out
+    }
+}
+
+pub fn is_component(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+        && fs::read_to_string(path).is_ok_and(|content| EasyEdaComponent::parse(&content).is_ok())
+}
+
+pub fn find_components(root: &Path) -> Vec<PathBuf> {
+    WalkDir::new(root)
+        .into_iter()
+        .flatten()
+        .filter(|entry| entry.file_type().is_file() && is_component(entry.path()))
+        .map(|entry| entry.path().to_path_buf())
+        .collect()
+}
+
+pub fn convert_component(
+    json_path: &Path,
+    out_dir: &Path,
+) -> Result<EasyEdaComponent, EasyEdaError> {
+    let component = EasyEdaComponent::parse(&fs::read_to_string(json_path)?)?;
+    fs::create_dir_all(out_dir)?;
+    let mut lib = KicadSymbolLib::parse("(kicad_symbol_lib (version 20231120) (generator kci))")?;
+    lib.add_symbol(component.to_symbol()?, AddPolicy::ReplaceExisting)?;
+    fs::write(
+        out_dir.join(format!("{}.kicad_sym", component.name)),
+        lib.to_string_pretty(),
+    )?;
+    fs::write(
+        out_dir.join(format!("{}.kicad_mod", component.footprint_name)),
+        component.to_footprint(),
+    )?;
+    if let Some(step) = json_path
+        .parent()
+        .and_then(|dir| sibling_model(dir, &component))
+    {
+        let extension = step
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .unwrap_or_else(|| "step".to_string());
+        fs::copy(
+            &step,
+            out_dir.join(format!("{}.{}", component.footprint_name, extension)),
+        )?;
+    }
+    Ok(component)
+}
+
+fn sibling_model(dir: &Path, component: &EasyEdaComponent) -> Option<PathBuf> {
+    let steps: Vec<PathBuf> = fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path.extension().is_some_and(|ext| {
+                    ext.eq_ignore_ascii_case("step") || ext.eq_ignore_ascii_case("stp")
+                })
+        })
+        .collect();
+    let wanted: Vec<&str> = [
+        component.model_uuid(),
+        Some(component.name()),
+        Some(component.footprint_name()),
+        component.lcsc(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    steps
+        .iter()
+        .find(|step| {
+            step.file_stem()
+                .and_then(|stem| stem.to_str())
+                .is_some_and(|stem| wanted.iter().any(|name| name.eq_ignore_ascii_case(stem)))
+        })
+        .cloned()
+        .or_else(|| (steps.len() == 1).then(|| steps[0].clone()))
+}
+
+fn pin(shape: &str, point: &dyn Fn(f64, f64) -> String) -> Option<String> {
+    let segments: Vec<&str> = shape.split("^^").collect();
+    let head: Vec<&str> = segments.first()?.split('~').collect();
+    if head.len() < 6 {
+        return None;
+    }
+    let (x, y) = (number(head[4]), number(head[5]));
+    let electric = match head[2] {
+        "1" => "input",
+        "2" => "output",
+        "3" => "bidirectional",
+        "4" => "power_in",
+        _ => "passive",
+    };
+    let path = segments.get(2)?.split('~').next()?;
+    let tokens = path_tokens(path);
+    let (angle, length) = match tokens.iter().position(|token| token == "h" || token == "v") {
+        Some(idx) => {
+            let length = tokens
+                .get(idx + 1)
+                .map(|value| number(value))
+                .unwrap_or(0.0);
+            let angle = match (tokens[idx].as_str(), length >= 0.0) {
+                ("h", true) => 0,
+                ("h", false) => 180,
+                ("v", true) => 270,
+                _ => 90,
+            };
+            (angle, length.abs())
+        }
+        None => (0, 0.0),
+    };
+    let label = |idx: usize| {
+        segments
+            .get(idx)
+            .and_then(|segment| segment.split('~').nth(4))
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+    };
+    let number_text = label(4).unwrap_or(head[3]);
+    let name = label(3).unwrap_or(number_text);
+    Some(format!(
+        "(pin {} line (at {} {}) (length {}) (name {} {}) (number {} {}))",
+        electric,
+        point(x, y),
+        angle,
+        mm(length * MM_PER_UNIT),
+        quote(name),
+        SYMBOL_FONT,
+        quote(number_text),
+        SYMBOL_FONT
+    ))
+}
+
+fn data_str(value: &Value) -> Option<Value> {
+    match value.get("dataStr")? {
+        Value::String(raw) => serde_json::from_str(raw).ok(),
+        data => Some(data.clone()),
+    }
+}
+
+fn shapes(data: &Value) -> Vec<String> {
+    data.get("shape")
+        .and_then(Value::as_array)
+        .map(|shapes| {
+            shapes
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn origin(head: &Value) -> (f64, f64) {
+    let coordinate = |key: &str| match head.get(key) {
+        Some(Value::String(value)) => number(value),
+        Some(value) => value.as_f64().unwrap_or(0.0),
+        None => 0.0,
+    };
+    (coordinate("x"), coordinate("y"))
+}
+
+fn footprint_layer(id: &str) -> Option<&'static str> {
+    match id {
+        "1" => Some("F.Cu"),
+        "2" => Some("B.Cu"),
+        "3" => Some("F.SilkS"),
+        "4" => Some("B.SilkS"),
+        "5" => Some("F.Paste"),
+        "6" => Some("B.Paste"),
+        "7" => Some("F.Mask"),
+        "8" => Some("B.Mask"),
+        "10" => Some("Edge.Cuts"),
+        "12" => Some("Cmts.User"),
+        "13" | "100" | "101" => Some("F.Fab"),
+        "14" => Some("B.Fab"),
+        "15" => Some("Dwgs.User"),
+        "99" => Some("F.CrtYd"),
+        _ => None,
+    }
+}
+
+fn coordinate_pairs(points: &str) -> Vec<(f64, f64)> {
+    let values: Vec<f64> = points
+        .split([' ', ','])
+        .filter(|value| !value.is_empty())
+        .map(number)
+        .collect();
+    values
+        .chunks_exact(2)
+        .map(|pair| (pair[0], pair[1]))
+        .collect()
+}
+
+fn path_tokens(path: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    for ch in path.chars() {
+        if ch.is_ascii_alphabetic() {
+            if !current.is_empty() {
+                tokens.push(std::mem::take(&mut current));
+            }
+            tokens.push(ch.to_ascii_lowercase().to_string());
+        } else if ch == ' ' || ch == ',' {
+            if !current.is_empty() {
+                tokens.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(ch);
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+fn number(value: &str) -> f64 {
+    value.trim().parse().unwrap_or(0.0)
+}
+
+fn mm(value: f64) -> String {
+    format_number(value, SCHEMATIC_PRECISION)
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn sanitize_name(name: &str) -> String {
+    name.chars()
+        .map(|ch| {
+            if matches!(ch, '/' | '\\' | ':') {
+                '_'
+            } else {
+                ch
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMPONENT: &str = r#"{
+        "result": {
+            "title": "NE555DR",
+            "dataStr": {
+                "head": {"x": "400", "y": "300", "c_para": {"pre": "U?", "name": "NE555DR", "Manufacturer": "TI"}},
+                "shape": [
+                    "R~380~280~~~40~40~#880000~1~0~none~gge1~0",
+                    "P~show~1~1~370~290~180~gge2~0^^370~290^^M 370 290 h 10~#880000^^1~383~294~0~TRIG~start~~~#0000FF^^1~375~289~0~2~end~~~#0000FF",
+                    "P~show~2~3~430~290~0~gge3~0^^430~290^^M 430 290 h -10~#880000^^1~417~294~0~OUT~end~~~#0000FF^^1~425~289~0~3~start~~~#0000FF"
+                ]
+            },
+            "packageDetail": {
+                "title": "SOIC-8_L4.9-W3.9-P1.27",
+                "dataStr": {
+                    "head": {"x": "4000", "y": "3000", "uuid_3d": "abc123"},
+                    "shape": [
+                        "PAD~RECT~3985~3000~6~2~1~~1~0~3982 2999 3988 2999 3988 3001 3982 3001~90~gge4~0~~Y~0",
+                        "PAD~ELLIPSE~4015~3000~6~6~11~~2~1.5~~0~gge5~0~~Y~0",
+                        "TRACK~1~3~~3980 2990 4020 2990~gge6~0",
+                        "TEXT~L~4000~2980~0.8~0~0~3~~4.5~NE555DR~M 0 0~~gge7~~0"
+                    ]
+                }
+            },
+            "lcsc": {"number": "C7593"}
+        }
+    }"#;
+
+    #[test]
+    fn converts_symbol_and_footprint() {
+        let component = EasyEdaComponent::parse(COMPONENT).unwrap();
+        assert_eq!(component.name(), "NE555DR");
+        assert_eq!(component.footprint_name(), "SOIC-8_L4.9-W3.9-P1.27");
+        assert_eq!(component.lcsc(), Some("C7593"));
+        assert_eq!(component.model_uuid(), Some("abc123"));
+
+        let symbol = component.to_symbol().unwrap();
+        assert_eq!(symbol.property_value("Reference").as_deref(), Some("U"));
+        assert_eq!(
+            symbol.property_value("Footprint").as_deref(),
+            Some("SOIC-8_L4.9-W3.9-P1.27")
+        );
+        assert_eq!(symbol.property_value("LCSC").as_deref(), Some("C7593"));
+        let text = symbol.into_sexp().to_string_pretty();
+        assert!(text.contains("(at -7.62 2.54 0)"));
+        assert!(text.contains("(at 7.62 2.54 180)"));
+        assert!(text.contains("input") && text.contains("output"));
+        assert!(text.contains("(start -5.08 5.08)"));
+
+        let footprint = component.to_footprint();
+        assert!(footprint.contains("(pad \"1\" smd rect (at -3.81 0 270) (size 1.524 0.508)"));
+        assert!(
+            footprint.contains(
+                "(pad \"2\" thru_hole circle (at 3.81 0) (size 1.524 1.524) (drill 0.762)"
+            )
+        );
+        assert!(footprint.contains("(attr through_hole)"));
+        assert!(footprint.contains("(layer \"F.SilkS\")"));
+        assert!(crate::kicad_sym::parse_one(&footprint).is_ok());
+
+        assert!(EasyEdaComponent::parse("{\"result\": {}}").is_err());
+    }
+}
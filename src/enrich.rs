@@ -0,0 +1,198 @@
+use crate::fetch::{FetchError, PartMetadata};
+use crate::kicad_sym::{KicadSymError, KicadSymbolLib, Symbol};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const QUERY_PROPERTIES: &[&str] = &["MPN", "Manufacturer Part Number", "Value"];
+
+#[derive(Debug)]
+pub enum EnrichError {
+    Io(io::Error),
+    Symbol(KicadSymError),
+    Fetch(FetchError),
+}
+
+impl fmt::Display for EnrichError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnrichError::Io(err) => write!(f, "io error: {}", err),
+            EnrichError::Symbol(err) => write!(f, "symbol library error: {}", err),
+            EnrichError::Fetch(err) => write!(f, "part lookup failed: {}", err),
+        }
+    }
+}
+
+impl Error for EnrichError {}
+
+impl From<io::Error> for EnrichError {
+    fn from(value: io::Error) -> Self {
+        EnrichError::Io(value)
+    }
+}
+
+impl From<KicadSymError> for EnrichError {
+    fn from(value: KicadSymError) -> Self {
+        EnrichError::Symbol(value)
+    }
+}
+
+impl From<FetchError> for EnrichError {
+    fn from(value: FetchError) -> Self {
+        EnrichError::Fetch(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Enrichment {
+    symbol: String,
+    query: String,
+    matched: bool,
+    filled: Vec<String>,
+}
+
+impl Enrichment {
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn matched(&self) -> bool {
+        self.matched
+    }
+
+    pub fn filled(&self) -> &[String] {
+        &self.filled
+    }
+}
+
+impl fmt::Display for Enrichment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.matched {
+            write!(f, "{}: no Octopart match for {}", self.symbol, self.query)
+        } else if self.filled.is_empty() {
+            write!(f, "{}: already has Octopart metadata", self.symbol)
+        } else {
+            write!(f, "{}: filled {}", self.symbol, self.filled.join(", "))
+        }
+    }
+}
+
+pub fn enrich_symbols(
+    symbol_lib: &Path,
+    names: &[String],
+    mut lookup: impl FnMut(&str) -> Result<Option<PartMetadata>, FetchError>,
+) -> Result<Vec<Enrichment>, EnrichError> {
+    let content = fs::read_to_string(symbol_lib)?;
+    let mut lib = KicadSymbolLib::parse(&content)?;
+    let mut enrichments = Vec::new();
+    for name in names {
+        let Some(symbol) = lib.symbol_mut(name) else {
+            continue;
+        };
+        let query = part_query(symbol);
+        let Some(metadata) = lookup(&query)? else {
+            enrichments.push(Enrichment {
+                symbol: name.clone(),
+                query,
+                matched: false,
+                filled: Vec::new(),
+            });
+            continue;
+        };
+        let mut filled = Vec::new();
+        for (property, value) in metadata.properties() {
+            if is_blank(symbol.property_value(property).as_deref()) {
+                symbol.set_or_add_hidden_property(property, value);
+                filled.push(property.to_string());
+            }
+        }
+        enrichments.push(Enrichment {
+            symbol: name.clone(),
+            query,
+            matched: true,
+            filled,
+        });
+    }
+    if enrichments
+        .iter()
+        .any(|enrichment| !enrichment.filled.is_empty())
+    {
+        fs::write(symbol_lib, lib.to_string_pretty())?;
+    }
+    Ok(enrichments)
+}
+
+fn part_query(symbol: &Symbol) -> String {
+    QUERY_PROPERTIES
+        .iter()
+        .filter_map(|property| symbol.property_value(property))
+        .find(|value| !is_blank(Some(value)))
+        .unwrap_or_else(|| symbol.name().to_string())
+}
+
+fn is_blank(value: Option<&str>) -> bool {
+    value.is_none_or(|value| matches!(value.trim(), "" | "~"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn fills_only_missing_properties() {
+        let dir = tempdir().unwrap();
+        let symbol_lib = dir.path().join("lib.kicad_sym");
+        fs::write(
+            &symbol_lib,
+            r#"(kicad_symbol_lib
+                (symbol "NE555" (property "Reference" "U") (property "Value" "NE555P")
+                    (property "Datasheet" "~") (property "Manufacturer" "TI"))
+                (symbol "MYSTERY" (property "Value" "~")))"#,
+        )
+        .unwrap();
+        let mut queries = Vec::new();
+        let enrichments = enrich_symbols(
+            &symbol_lib,
+            &[
+                "NE555".to_string(),
+                "MYSTERY".to_string(),
+                "GONE".to_string(),
+            ],
+            |query| {
+                queries.push(query.to_string());
+                Ok((query == "NE555P").then(|| {
+                    PartMetadata::new(
+                        Some("Texas Instruments"),
+                        Some("NE555P"),
+                        Some("Precision timer"),
+                        Some("https://www.ti.com/lit/ds/symlink/ne555.pdf"),
+                    )
+                }))
+            },
+        )
+        .unwrap();
+        assert_eq!(queries, ["NE555P", "MYSTERY"]);
+        assert_eq!(enrichments.len(), 2);
+        assert_eq!(enrichments[0].filled(), ["MPN", "Description", "Datasheet"]);
+        assert_eq!(
+            enrichments[1].to_string(),
+            "MYSTERY: no Octopart match for MYSTERY"
+        );
+
+        let lib = KicadSymbolLib::parse(&fs::read_to_string(&symbol_lib).unwrap()).unwrap();
+        let symbol = lib.symbol("NE555").unwrap();
+        assert_eq!(symbol.property_value("Manufacturer").as_deref(), Some("TI"));
+        assert_eq!(symbol.property_value("MPN").as_deref(), Some("NE555P"));
+        assert_eq!(
+            symbol.property_value("Datasheet").as_deref(),
+            Some("https://www.ti.com/lit/ds/symlink/ne555.pdf")
+        );
+    }
+}
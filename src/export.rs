@@ -0,0 +1,269 @@
+use crate::importer::{ImportConfig, model_reference};
+use crate::kicad_sym::{AddPolicy, KicadSymError, KicadSymbolLib, parse_one};
+use crate::kicad_table::{TableError, TableKind, lib_name_from_path};
+use crate::model_store::ModelStoreError;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{self, Cursor, Write};
+use std::path::Path;
+use zip::ZipWriter;
+use zip::result::ZipError;
+use zip::write::FileOptions;
+
+const MODEL_DIR: &str = "3d";
+
+#[derive(Debug)]
+pub enum ExportError {
+    Io(io::Error),
+    Zip(ZipError),
+    Symbol(KicadSymError),
+    Table(TableError),
+    ModelStore(ModelStoreError),
+    UnknownSymbol(String),
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::Io(err) => write!(f, "io error: {}", err),
+            ExportError::Zip(err) => write!(f, "zip error: {}", err),
+            ExportError::Symbol(err) => write!(f, "cannot export symbol: {}", err),
+            ExportError::Table(err) => write!(f, "{}", err),
+            ExportError::ModelStore(err) => write!(f, "{}", err),
+            ExportError::UnknownSymbol(name) => write!(f, "symbol not found: {}", name),
+        }
+    }
+}
+
+impl Error for ExportError {}
+
+impl From<io::Error> for ExportError {
+    fn from(value: io::Error) -> Self {
+        ExportError::Io(value)
+    }
+}
+
+impl From<ZipError> for ExportError {
+    fn from(value: ZipError) -> Self {
+        ExportError::Zip(value)
+    }
+}
+
+impl From<KicadSymError> for ExportError {
+    fn from(value: KicadSymError) -> Self {
+        ExportError::Symbol(value)
+    }
+}
+
+impl From<TableError> for ExportError {
+    fn from(value: TableError) -> Self {
+        ExportError::Table(value)
+    }
+}
+
+impl From<ModelStoreError> for ExportError {
+    fn from(value: ModelStoreError) -> Self {
+        ExportError::ModelStore(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Export {
+    entries: Vec<String>,
+    skipped: Vec<String>,
+}
+
+impl Export {
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    pub fn skipped(&self) -> &[String] {
+        &self.skipped
+    }
+}
+
+pub fn export_symbol(
+    project_root: &Path,
+    config: &ImportConfig,
+    name: &str,
+    output: &Path,
+) -> Result<Export, ExportError> {
+    let lib = KicadSymbolLib::parse(&fs::read_to_string(project_root.join(config.symbol_lib()))?)?;
+    let symbol = lib
+        .symbol(name)
+        .ok_or_else(|| ExportError::UnknownSymbol(name.to_string()))?;
+    let parent = symbol.extends().and_then(|parent| lib.symbol(parent));
+    let mut bundle =
+        KicadSymbolLib::parse("(kicad_symbol_lib (version 20231120) (generator kci))")?;
+    if let Some(parent) = parent {
+        bundle.add_symbol(parent.clone(), AddPolicy::ErrorOnConflict)?;
+    }
+    bundle.add_symbol(symbol.clone(), AddPolicy::ErrorOnConflict)?;
+
+    let mut files = vec![(
+        format!("{}.kicad_sym", name),
+        bundle.to_string_pretty().into_bytes(),
+    )];
+    let mut skipped = Vec::new();
+    let footprint = symbol
+        .property_value("Footprint")
+        .or_else(|| parent.and_then(|parent| parent.property_value("Footprint")))
+        .filter(|value| !value.is_empty());
+    if let Some(footprint) = footprint {
+        bundle_footprint(project_root, config, &footprint, &mut files, &mut skipped)?;
+    }
+
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    for (entry, bytes) in &files {
+        writer.start_file(entry.as_str(), FileOptions::default())?;
+        writer.write_all(bytes)?;
+    }
+    let bytes = writer.finish()?.into_inner();
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(output, bytes)?;
+    Ok(Export {
+        entries: files.into_iter().map(|(entry, _)| entry).collect(),
+        skipped,
+    })
+}
+
+fn bundle_footprint(
+    project_root: &Path,
+    config: &ImportConfig,
+    footprint: &str,
+    files: &mut Vec<(String, Vec<u8>)>,
+    skipped: &mut Vec<String>,
+) -> Result<(), ExportError> {
+    let footprint_lib = lib_name_from_path(TableKind::Footprint, config.footprint_lib())?;
+    let Some(footprint_name) = footprint.strip_prefix(&format!("{}:", footprint_lib)) else {
+        skipped.push(format!("{} is not in {}", footprint, footprint_lib));
+        return Ok(());
+    };
+    let footprint_path = project_root
+        .join(config.footprint_lib())
+        .join(format!("{}.kicad_mod", footprint_name));
+    if !footprint_path.exists() {
+        skipped.push(format!("{} not found", footprint_path.display()));
+        return Ok(());
+    }
+    let content = fs::read_to_string(&footprint_path)?;
+    let model = model_reference(&parse_one(&content)?).map(str::to_string);
+    files.push((
+        format!("{}.pretty/{}.kicad_mod", footprint_lib, footprint_name),
+        content.into_bytes(),
+    ));
+
+    let Some(model) = model else {
+        return Ok(());
+    };
+    let Some(file_name) = model
+        .rsplit(['/', '\\'])
+        .next()
+        .filter(|name| !name.is_empty())
+    else {
+        return Ok(());
+    };
+    let store_prefix = config
+        .model_store()
+        .map(|store| format!("${{{}}}/", store.variable()));
+    let bytes = if let Some(relative) = model.strip_prefix("${KIPRJMOD}/") {
+        read_model(&project_root.join(relative))?
+    } else if let (Some(store), Some(name)) = (
+        config.model_store(),
+        store_prefix
+            .as_deref()
+            .and_then(|prefix| model.strip_prefix(prefix)),
+    ) {
+        let temp = tempfile::tempdir()?;
+        let local = temp.path().join(file_name);
+        store.download(name, &local)?;
+        read_model(&local)?
+    } else if model.starts_with("${") {
+        skipped.push(format!("{} uses an unknown path variable", model));
+        return Ok(());
+    } else {
+        read_model(&project_root.join(&model))?
+    };
+    match bytes {
+        Some(bytes) => files.push((format!("{}/{}", MODEL_DIR, file_name), bytes)),
+        None => skipped.push(format!("{} not found", model)),
+    }
+    Ok(())
+}
+
+fn read_model(path: &Path) -> Result<Option<Vec<u8>>, ExportError> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    Ok(Some(fs::read(path)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use std::path::PathBuf;
+    use tempfile::tempdir;
+    use zip::ZipArchive;
+
+    #[test]
+    fn bundles_symbol_footprint_and_model() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let config = ImportConfig::new(
+            PathBuf::from("lib.kicad_sym"),
+            PathBuf::from("lib.pretty"),
+            PathBuf::from("lib_3d"),
+        );
+        fs::write(
+            root.join("lib.kicad_sym"),
+            r#"(kicad_symbol_lib
+                (symbol "NE555" (property "Footprint" "lib:DIP-8"))
+                (symbol "LM555" (extends "NE555"))
+                (symbol "LM358" (property "Footprint" "Package_SO:SOIC-8")))"#,
+        )
+        .unwrap();
+        fs::create_dir_all(root.join("lib.pretty")).unwrap();
+        fs::create_dir_all(root.join("lib_3d")).unwrap();
+        fs::write(
+            root.join("lib.pretty/DIP-8.kicad_mod"),
+            "(footprint \"DIP-8\" (model \"${KIPRJMOD}/lib_3d/DIP-8.step\"))",
+        )
+        .unwrap();
+        fs::write(root.join("lib_3d/DIP-8.step"), "ISO-10303-21;").unwrap();
+
+        let output = root.join("out/LM555.zip");
+        let export = export_symbol(root, &config, "LM555", &output).unwrap();
+        assert_eq!(
+            export.entries(),
+            [
+                "LM555.kicad_sym",
+                "lib.pretty/DIP-8.kicad_mod",
+                "3d/DIP-8.step"
+            ]
+        );
+        assert!(export.skipped().is_empty());
+        let mut archive = ZipArchive::new(fs::File::open(&output).unwrap()).unwrap();
+        let mut symbols = String::new();
+        archive
+            .by_name("LM555.kicad_sym")
+            .unwrap()
+            .read_to_string(&mut symbols)
+            .unwrap();
+        let lib = KicadSymbolLib::parse(&symbols).unwrap();
+        let names: Vec<&str> = lib.symbols_iter().map(|symbol| symbol.name()).collect();
+        assert_eq!(names, ["NE555", "LM555"]);
+
+        let export = export_symbol(root, &config, "LM358", &root.join("LM358.zip")).unwrap();
+        assert_eq!(export.entries(), ["LM358.kicad_sym"]);
+        assert_eq!(export.skipped(), ["Package_SO:SOIC-8 is not in lib"]);
+        assert!(matches!(
+            export_symbol(root, &config, "TL072", &root.join("TL072.zip")),
+            Err(ExportError::UnknownSymbol(_))
+        ));
+    }
+}
@@ -0,0 +1,1255 @@
+use crate::easyeda::{EasyEdaComponent, EasyEdaError};
+use crate::ledger::STATE_DIR;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+use walkdir::WalkDir;
+
+pub const LCSC_COMPONENT_URL: &str =
+    "https://easyeda.com/api/products/{part}/components?version=6.4.19.5";
+pub const EASYEDA_MODEL_URL: &str = "https://modules.easyeda.com/qAxj6KHrDKw4blvCG8QJPs7Y/{uuid}";
+pub const DIGIKEY_TOKEN_URL: &str = "https://api.digikey.com/v1/oauth2/token";
+pub const DIGIKEY_PRODUCT_URL: &str =
+    "https://api.digikey.com/products/v4/search/{part}/productdetails";
+pub const MOUSER_SEARCH_URL: &str =
+    "https://api.mouser.com/api/v1/search/partnumber?apiKey={key}";
+pub const NEXAR_TOKEN_URL: &str = "https://identity.nexar.com/connect/token";
+pub const NEXAR_API_URL: &str = "https://api.nexar.com/graphql";
+pub const DEFAULT_CACHE_TTL_HOURS: u64 = 24;
+pub const CACHE_DIR: &str = "cache";
+const LCSC_CACHE_DIR: &str = "lcsc";
+const DIGIKEY_CACHE_DIR: &str = "digikey";
+const MOUSER_CACHE_DIR: &str = "mouser";
+const URL_CACHE_DIR: &str = "url";
+const NEXAR_CACHE_DIR: &str = "nexar";
+const NEXAR_PART_QUERY: &str = "query Part($q: String!) { supSearchMpn(q: $q, limit: 1) { results { part { mpn manufacturer { name } shortDescription bestDatasheet { url } } } } }";
+const SNAPEDA: &str = "SnapEDA";
+const SAMACSYS: &str = "SamacSys";
+const ULTRA_LIBRARIAN: &str = "Ultra Librarian";
+const ECAD_HOSTS: &[(&str, &str)] = &[
+    ("snapeda.com", SNAPEDA),
+    ("componentsearchengine.com", SAMACSYS),
+    ("samacsys.com", SAMACSYS),
+    ("ultralibrarian.com", ULTRA_LIBRARIAN),
+];
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+const CONNECT_TIMEOUT_SECS: &str = "15";
+const MAX_TIME_SECS: &str = "300";
+
+#[derive(Debug)]
+pub enum FetchError {
+    Io(io::Error),
+    Download(String),
+    NotFound(String),
+    NoCadModel(String),
+    Component(EasyEdaError),
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::Io(err) => write!(f, "io error: {}", err),
+            FetchError::Download(msg) => write!(f, "download failed: {}", msg),
+            FetchError::NotFound(part) => write!(f, "LCSC part not found: {}", part),
+            FetchError::NoCadModel(msg) => write!(f, "no CAD model: {}", msg),
+            FetchError::Component(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Error for FetchError {}
+
+impl From<io::Error> for FetchError {
+    fn from(value: io::Error) -> Self {
+        FetchError::Io(value)
+    }
+}
+
+impl From<EasyEdaError> for FetchError {
+    fn from(value: EasyEdaError) -> Self {
+        FetchError::Component(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LcscPart {
+    number: String,
+}
+
+impl LcscPart {
+    pub fn number(&self) -> &str {
+        &self.number
+    }
+}
+
+impl fmt::Display for LcscPart {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.number)
+    }
+}
+
+impl FromStr for LcscPart {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let trimmed = value.trim();
+        let digits = trimmed.strip_prefix(['C', 'c']).unwrap_or(trimmed);
+        if digits.is_empty() || !digits.chars().all(|ch| ch.is_ascii_digit()) {
+            return Err(format!("invalid LCSC part number: {}", value));
+        }
+        Ok(Self {
+            number: format!("C{}", digits),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchedPart {
+    part: LcscPart,
+    component: PathBuf,
+    model: Option<PathBuf>,
+    cached: bool,
+    warnings: Vec<String>,
+}
+
+impl FetchedPart {
+    pub fn component(&self) -> &Path {
+        &self.component
+    }
+
+    pub fn model(&self) -> Option<&Path> {
+        self.model.as_deref()
+    }
+
+    pub fn cached(&self) -> bool {
+        self.cached
+    }
+
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+}
+
+impl fmt::Display for FetchedPart {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let source = if self.cached { "cached" } else { "downloaded" };
+        let model = if self.model.is_some() {
+            "with 3D model"
+        } else {
+            "without 3D model"
+        };
+        write!(f, "{}: {} {}", self.part, source, model)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LcscFetcher {
+    cache_dir: PathBuf,
+    component_url: String,
+    model_url: String,
+    ttl: Duration,
+    transport: Transport,
+}
+
+impl LcscFetcher {
+    pub fn new(project_root: &Path) -> Self {
+        Self {
+            cache_dir: project_root
+                .join(STATE_DIR)
+                .join(CACHE_DIR)
+                .join(LCSC_CACHE_DIR),
+            component_url: LCSC_COMPONENT_URL.to_string(),
+            model_url: EASYEDA_MODEL_URL.to_string(),
+            ttl: Duration::from_secs(DEFAULT_CACHE_TTL_HOURS * 3600),
+            transport: Transport::Curl,
+        }
+    }
+
+    pub fn with_urls(mut self, component_url: &str, model_url: &str) -> Self {
+        self.component_url = component_url.to_string();
+        self.model_url = model_url.to_string();
+        self
+    }
+
+    #[cfg(test)]
+    fn with_mirror(mut self, root: &Path) -> Self {
+        self.transport = Transport::Mirror(root.to_path_buf());
+        self
+    }
+
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn cache_dir(&self) -> &Path {
+        &self.cache_dir
+    }
+
+    pub fn fetch(&self, part: &LcscPart) -> Result<FetchedPart, FetchError> {
+        let dir = self.cache_dir.join(part.number());
+        let component_path = dir.join(format!("{}.json", part.number()));
+        let mut warnings = Vec::new();
+        let mut cached = is_fresh(&component_path, self.ttl);
+        let content = if cached {
+            fs::read_to_string(&component_path)?
+        } else {
+            match self.download_component(part, &dir, &component_path) {
+                Ok(content) => content,
+                Err(FetchError::Download(err)) if component_path.exists() => {
+                    warnings.push(format!("{}: using expired cache entry: {}", part, err));
+                    cached = true;
+                    fs::read_to_string(&component_path)?
+                }
+                Err(err) => return Err(err),
+            }
+        };
+        let component = EasyEdaComponent::parse(&content)?;
+
+        let model = match component.model_uuid() {
+            Some(uuid) => {
+                let model_path = dir.join(format!("{}.step", uuid));
+                if model_path.exists() {
+                    Some(model_path)
+                } else {
+                    match self
+                        .transport
+                        .download(&self.model_url.replace("{uuid}", uuid))
+                    {
+                        Ok(bytes) => {
+                            fs::write(&model_path, bytes)?;
+                            Some(model_path)
+                        }
+                        Err(err) => {
+                            warnings.push(format!("{}: no 3D model: {}", part, err));
+                            None
+                        }
+                    }
+                }
+            }
+            None => None,
+        };
+        Ok(FetchedPart {
+            part: part.clone(),
+            component: component_path,
+            model,
+            cached,
+            warnings,
+        })
+    }
+
+    fn download_component(
+        &self,
+        part: &LcscPart,
+        dir: &Path,
+        component_path: &Path,
+    ) -> Result<String, FetchError> {
+        fs::create_dir_all(dir)?;
+        let url = self.component_url.replace("{part}", part.number());
+        let content = String::from_utf8_lossy(&self.transport.download(&url)?).to_string();
+        let response: Value = serde_json::from_str(&content).map_err(EasyEdaError::from)?;
+        if response.get("success").and_then(Value::as_bool) == Some(false)
+            || response.get("result").is_some_and(Value::is_null)
+        {
+            return Err(FetchError::NotFound(part.to_string()));
+        }
+        EasyEdaComponent::parse(&content)?;
+        fs::write(component_path, &content)?;
+        Ok(content)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DigikeyCredentials {
+    client_id: String,
+    client_secret: String,
+}
+
+impl DigikeyCredentials {
+    pub fn new(client_id: &str, client_secret: &str) -> Self {
+        Self {
+            client_id: client_id.to_string(),
+            client_secret: client_secret.to_string(),
+        }
+    }
+
+    pub fn client_id(&self) -> &str {
+        &self.client_id
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchedArchive {
+    part: String,
+    archive: PathBuf,
+    provider: Option<&'static str>,
+    cached: bool,
+}
+
+impl FetchedArchive {
+    pub fn archive(&self) -> &Path {
+        &self.archive
+    }
+
+    pub fn cached(&self) -> bool {
+        self.cached
+    }
+}
+
+impl fmt::Display for FetchedArchive {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let source = if self.cached { "cached" } else { "downloaded" };
+        match self.provider {
+            Some(provider) => write!(f, "{}: {} {} CAD model", self.part, source, provider),
+            None => write!(f, "{}: {} CAD model", self.part, source),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DigikeyFetcher {
+    credentials: DigikeyCredentials,
+    cache_dir: PathBuf,
+    token_url: String,
+    product_url: String,
+    ttl: Duration,
+    transport: Transport,
+}
+
+impl DigikeyFetcher {
+    pub fn new(project_root: &Path, credentials: DigikeyCredentials) -> Self {
+        Self {
+            credentials,
+            cache_dir: project_root
+                .join(STATE_DIR)
+                .join(CACHE_DIR)
+                .join(DIGIKEY_CACHE_DIR),
+            token_url: DIGIKEY_TOKEN_URL.to_string(),
+            product_url: DIGIKEY_PRODUCT_URL.to_string(),
+            ttl: Duration::from_secs(DEFAULT_CACHE_TTL_HOURS * 3600),
+            transport: Transport::Curl,
+        }
+    }
+
+    pub fn with_urls(mut self, token_url: &str, product_url: &str) -> Self {
+        self.token_url = token_url.to_string();
+        self.product_url = product_url.to_string();
+        self
+    }
+
+    #[cfg(test)]
+    fn with_mirror(mut self, root: &Path) -> Self {
+        self.transport = Transport::Mirror(root.to_path_buf());
+        self
+    }
+
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn cache_dir(&self) -> &Path {
+        &self.cache_dir
+    }
+
+    pub fn fetch(&self, part: &str) -> Result<FetchedArchive, FetchError> {
+        let part = part.trim();
+        if part.is_empty() {
+            return Err(FetchError::NoCadModel("empty Digi-Key part number".to_string()));
+        }
+        if let Some(cached) = cached_archive(&self.cache_dir, part, Some(SNAPEDA), self.ttl) {
+            return Ok(cached);
+        }
+
+        let token = self.access_token()?;
+        let headers = [
+            format!("X-DIGIKEY-Client-Id: {}", self.credentials.client_id),
+            format!("Authorization: Bearer {}", token),
+        ];
+        let url = self.product_url.replace("{part}", &percent_encode(part));
+        let product = self.transport.request(&url, &url, &headers, None)?;
+        let product: Value = serde_json::from_slice(&product)
+            .map_err(|err| FetchError::Download(format!("{}: {}", url, err)))?;
+        let mut links = Vec::new();
+        ecad_links(&product, &[SNAPEDA], &mut links);
+        if links.is_empty() {
+            return Err(FetchError::NoCadModel(format!(
+                "Digi-Key lists no SnapEDA model for {}",
+                part
+            )));
+        }
+        download_archive(&self.transport, &self.cache_dir, part, links)
+    }
+
+    fn access_token(&self) -> Result<String, FetchError> {
+        client_credentials_token(
+            &self.transport,
+            &self.token_url,
+            &self.credentials.client_id,
+            &self.credentials.client_secret,
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MouserCredentials {
+    api_key: String,
+}
+
+impl MouserCredentials {
+    pub fn new(api_key: &str) -> Self {
+        Self {
+            api_key: api_key.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MouserFetcher {
+    credentials: MouserCredentials,
+    cache_dir: PathBuf,
+    search_url: String,
+    ttl: Duration,
+    transport: Transport,
+}
+
+impl MouserFetcher {
+    pub fn new(project_root: &Path, credentials: MouserCredentials) -> Self {
+        Self {
+            credentials,
+            cache_dir: project_root
+                .join(STATE_DIR)
+                .join(CACHE_DIR)
+                .join(MOUSER_CACHE_DIR),
+            search_url: MOUSER_SEARCH_URL.to_string(),
+            ttl: Duration::from_secs(DEFAULT_CACHE_TTL_HOURS * 3600),
+            transport: Transport::Curl,
+        }
+    }
+
+    pub fn with_url(mut self, search_url: &str) -> Self {
+        self.search_url = search_url.to_string();
+        self
+    }
+
+    #[cfg(test)]
+    fn with_mirror(mut self, root: &Path) -> Self {
+        self.transport = Transport::Mirror(root.to_path_buf());
+        self
+    }
+
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn cache_dir(&self) -> &Path {
+        &self.cache_dir
+    }
+
+    pub fn fetch(&self, part: &str) -> Result<FetchedArchive, FetchError> {
+        let part = part.trim();
+        if part.is_empty() {
+            return Err(FetchError::NoCadModel("empty Mouser part number".to_string()));
+        }
+        if let Some(cached) = cached_archive(&self.cache_dir, part, None, self.ttl) {
+            return Ok(cached);
+        }
+
+        let label = self.search_url.replace("{key}", "***");
+        let url = self
+            .search_url
+            .replace("{key}", &percent_encode(&self.credentials.api_key));
+        let request = json!({
+            "SearchByPartRequest": {"mouserPartNumber": part, "partSearchOptions": "Exact"}
+        });
+        let headers = ["Content-Type: application/json".to_string()];
+        let response = self
+            .transport
+            .request(&label, &url, &headers, Some(&request.to_string()))?;
+        let response: Value = serde_json::from_slice(&response)
+            .map_err(|err| FetchError::Download(format!("{}: {}", label, err)))?;
+        let errors: Vec<&str> = response
+            .get("Errors")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|error| error.get("Message").and_then(Value::as_str))
+            .collect();
+        if !errors.is_empty() {
+            return Err(FetchError::Download(format!("{}: {}", label, errors.join("; "))));
+        }
+        let parts = response
+            .pointer("/SearchResults/Parts")
+            .and_then(Value::as_array)
+            .filter(|parts| !parts.is_empty())
+            .ok_or_else(|| FetchError::NoCadModel(format!("Mouser has no part {}", part)))?;
+        let mut links = Vec::new();
+        for found in parts {
+            ecad_links(found, &[SNAPEDA, SAMACSYS, ULTRA_LIBRARIAN], &mut links);
+        }
+        if links.is_empty() {
+            return Err(FetchError::NoCadModel(format!(
+                "Mouser lists no ECAD model for {}",
+                part
+            )));
+        }
+        download_archive(&self.transport, &self.cache_dir, part, links)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NexarCredentials {
+    client_id: String,
+    client_secret: String,
+}
+
+impl NexarCredentials {
+    pub fn new(client_id: &str, client_secret: &str) -> Self {
+        Self {
+            client_id: client_id.to_string(),
+            client_secret: client_secret.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PartMetadata {
+    manufacturer: Option<String>,
+    mpn: Option<String>,
+    description: Option<String>,
+    datasheet: Option<String>,
+}
+
+impl PartMetadata {
+    pub fn new(
+        manufacturer: Option<&str>,
+        mpn: Option<&str>,
+        description: Option<&str>,
+        datasheet: Option<&str>,
+    ) -> Self {
+        let owned = |value: Option<&str>| {
+            value
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        Self {
+            manufacturer: owned(manufacturer),
+            mpn: owned(mpn),
+            description: owned(description),
+            datasheet: owned(datasheet),
+        }
+    }
+
+    pub fn manufacturer(&self) -> Option<&str> {
+        self.manufacturer.as_deref()
+    }
+
+    pub fn mpn(&self) -> Option<&str> {
+        self.mpn.as_deref()
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn datasheet(&self) -> Option<&str> {
+        self.datasheet.as_deref()
+    }
+
+    pub fn properties(&self) -> Vec<(&'static str, &str)> {
+        [
+            ("Manufacturer", self.manufacturer()),
+            ("MPN", self.mpn()),
+            ("Description", self.description()),
+            ("Datasheet", self.datasheet()),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| value.map(|value| (name, value)))
+        .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NexarClient {
+    credentials: NexarCredentials,
+    cache_dir: PathBuf,
+    token_url: String,
+    api_url: String,
+    token: Option<String>,
+    ttl: Duration,
+    transport: Transport,
+}
+
+impl NexarClient {
+    pub fn new(project_root: &Path, credentials: NexarCredentials) -> Self {
+        Self {
+            credentials,
+            cache_dir: project_root
+                .join(STATE_DIR)
+                .join(CACHE_DIR)
+                .join(NEXAR_CACHE_DIR),
+            token_url: NEXAR_TOKEN_URL.to_string(),
+            api_url: NEXAR_API_URL.to_string(),
+            token: None,
+            ttl: Duration::from_secs(DEFAULT_CACHE_TTL_HOURS * 3600),
+            transport: Transport::Curl,
+        }
+    }
+
+    pub fn with_urls(mut self, token_url: &str, api_url: &str) -> Self {
+        self.token_url = token_url.to_string();
+        self.api_url = api_url.to_string();
+        self
+    }
+
+    #[cfg(test)]
+    fn with_mirror(mut self, root: &Path) -> Self {
+        self.transport = Transport::Mirror(root.to_path_buf());
+        self
+    }
+
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn cache_dir(&self) -> &Path {
+        &self.cache_dir
+    }
+
+    pub fn lookup(&mut self, query: &str) -> Result<Option<PartMetadata>, FetchError> {
+        let query = query.trim();
+        let cache_path = self.cache_dir.join(format!("{}.json", cache_name(query)));
+        let response = if is_fresh(&cache_path, self.ttl) {
+            fs::read(&cache_path)?
+        } else {
+            let response = self.query(query)?;
+            fs::create_dir_all(&self.cache_dir)?;
+            fs::write(&cache_path, &response)?;
+            response
+        };
+        let response: Value = serde_json::from_slice(&response)
+            .map_err(|err| FetchError::Download(format!("{}: {}", cache_path.display(), err)))?;
+        let Some(part) = response.pointer("/data/supSearchMpn/results/0/part") else {
+            return Ok(None);
+        };
+        let text = |pointer: &str| part.pointer(pointer).and_then(Value::as_str);
+        Ok(Some(PartMetadata::new(
+            text("/manufacturer/name"),
+            text("/mpn"),
+            text("/shortDescription"),
+            text("/bestDatasheet/url"),
+        )))
+    }
+
+    fn query(&mut self, query: &str) -> Result<Vec<u8>, FetchError> {
+        let token = match &self.token {
+            Some(token) => token.clone(),
+            None => {
+                let token = client_credentials_token(
+                    &self.transport,
+                    &self.token_url,
+                    &self.credentials.client_id,
+                    &self.credentials.client_secret,
+                )?;
+                self.token.insert(token).clone()
+            }
+        };
+        let request = json!({"query": NEXAR_PART_QUERY, "variables": {"q": query}});
+        let headers = [
+            format!("Authorization: Bearer {}", token),
+            "Content-Type: application/json".to_string(),
+        ];
+        let raw = self.transport.request(
+            &self.api_url,
+            &self.api_url,
+            &headers,
+            Some(&request.to_string()),
+        )?;
+        let response: Value = serde_json::from_slice(&raw)
+            .map_err(|err| FetchError::Download(format!("{}: {}", self.api_url, err)))?;
+        let errors: Vec<&str> = response
+            .get("errors")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|error| error.get("message").and_then(Value::as_str))
+            .collect();
+        if !errors.is_empty() {
+            return Err(FetchError::Download(format!(
+                "{}: {}",
+                self.api_url,
+                errors.join("; ")
+            )));
+        }
+        Ok(raw)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UrlFetcher {
+    cache_dir: PathBuf,
+    ttl: Duration,
+    transport: Transport,
+}
+
+impl UrlFetcher {
+    pub fn new(project_root: &Path) -> Self {
+        Self {
+            cache_dir: project_root
+                .join(STATE_DIR)
+                .join(CACHE_DIR)
+                .join(URL_CACHE_DIR),
+            ttl: Duration::from_secs(DEFAULT_CACHE_TTL_HOURS * 3600),
+            transport: Transport::Curl,
+        }
+    }
+
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn cache_dir(&self) -> &Path {
+        &self.cache_dir
+    }
+
+    pub fn fetch(&self, url: &str) -> Result<FetchedArchive, FetchError> {
+        let url = url.trim();
+        let path = url.split(['?', '#']).next().unwrap_or(url);
+        let file_name: String = path
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+            .chars()
+            .map(|ch| {
+                if ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.') {
+                    ch
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let file_name = if file_name.trim_matches('.').is_empty() {
+            "source.zip".to_string()
+        } else {
+            file_name
+        };
+        let dir = self.cache_dir.join(cache_name(url));
+        let archive = dir.join(file_name);
+        if is_fresh(&archive, self.ttl) {
+            return Ok(FetchedArchive {
+                part: url.to_string(),
+                archive,
+                provider: None,
+                cached: true,
+            });
+        }
+        let bytes = self.transport.download(url)?;
+        fs::create_dir_all(&dir)?;
+        fs::write(&archive, bytes)?;
+        Ok(FetchedArchive {
+            part: url.to_string(),
+            archive,
+            provider: None,
+            cached: false,
+        })
+    }
+}
+
+pub fn clear_cache(project_root: &Path) -> Result<usize, FetchError> {
+    let dir = project_root.join(STATE_DIR).join(CACHE_DIR);
+    if !dir.exists() {
+        return Ok(0);
+    }
+    let removed = WalkDir::new(&dir)
+        .into_iter()
+        .flatten()
+        .filter(|entry| entry.file_type().is_file())
+        .count();
+    fs::remove_dir_all(&dir)?;
+    Ok(removed)
+}
+
+fn is_fresh(path: &Path, ttl: Duration) -> bool {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age < ttl)
+}
+
+fn client_credentials_token(
+    transport: &Transport,
+    token_url: &str,
+    client_id: &str,
+    client_secret: &str,
+) -> Result<String, FetchError> {
+    let form = format!(
+        "client_id={}&client_secret={}&grant_type=client_credentials",
+        percent_encode(client_id),
+        percent_encode(client_secret)
+    );
+    let response = transport.request(token_url, token_url, &[], Some(&form))?;
+    let response: Value = serde_json::from_slice(&response)
+        .map_err(|err| FetchError::Download(format!("{}: {}", token_url, err)))?;
+    response
+        .get("access_token")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| FetchError::Download(format!("{}: no access token in response", token_url)))
+}
+
+fn cached_archive(
+    cache_dir: &Path,
+    part: &str,
+    provider: Option<&'static str>,
+    ttl: Duration,
+) -> Option<FetchedArchive> {
+    let archive = cache_dir
+        .join(cache_name(part))
+        .join(format!("{}.zip", cache_name(part)));
+    is_fresh(&archive, ttl).then(|| FetchedArchive {
+        part: part.to_string(),
+        archive,
+        provider,
+        cached: true,
+    })
+}
+
+fn download_archive(
+    transport: &Transport,
+    cache_dir: &Path,
+    part: &str,
+    mut links: Vec<String>,
+) -> Result<FetchedArchive, FetchError> {
+    links.sort_by_key(|link| !link.to_ascii_lowercase().contains("kicad"));
+    links.dedup();
+    let dir = cache_dir.join(cache_name(part));
+    let mut failures = Vec::new();
+    for link in &links {
+        match transport.download(link) {
+            Ok(bytes) if bytes.starts_with(ZIP_MAGIC) => {
+                fs::create_dir_all(&dir)?;
+                let archive = dir.join(format!("{}.zip", cache_name(part)));
+                fs::write(&archive, bytes)?;
+                return Ok(FetchedArchive {
+                    part: part.to_string(),
+                    archive,
+                    provider: ecad_provider(link),
+                    cached: false,
+                });
+            }
+            Ok(_) => failures.push(format!("{} is not a zip archive", link)),
+            Err(err) => failures.push(err.to_string()),
+        }
+    }
+    Err(FetchError::NoCadModel(format!(
+        "{}: {}",
+        part,
+        failures.join("; ")
+    )))
+}
+
+#[derive(Debug, Clone)]
+enum Transport {
+    Curl,
+    #[cfg(test)]
+    Mirror(PathBuf),
+}
+
+impl Transport {
+    fn download(&self, url: &str) -> Result<Vec<u8>, FetchError> {
+        self.request(url, url, &[], None)
+    }
+
+    fn request(
+        &self,
+        label: &str,
+        url: &str,
+        headers: &[String],
+        data: Option<&str>,
+    ) -> Result<Vec<u8>, FetchError> {
+        match self {
+            Transport::Curl => curl(label, url, headers, data),
+            #[cfg(test)]
+            Transport::Mirror(root) => {
+                let path = url
+                    .strip_prefix("https://")
+                    .and_then(|rest| rest.split(['?', '#']).next())
+                    .ok_or_else(|| FetchError::Download(format!("{}: not an https URL", label)))?;
+                fs::read(root.join(path))
+                    .map_err(|err| FetchError::Download(format!("{}: {}", label, err)))
+            }
+        }
+    }
+}
+
+fn curl(
+    label: &str,
+    url: &str,
+    headers: &[String],
+    data: Option<&str>,
+) -> Result<Vec<u8>, FetchError> {
+    let mut config = format!("url = {}\n", curl_config_value(url));
+    for header in headers {
+        config.push_str(&format!("header = {}\n", curl_config_value(header)));
+    }
+    if let Some(data) = data {
+        config.push_str(&format!("data = {}\n", curl_config_value(data)));
+    }
+    let mut child = Command::new("curl")
+        .args(["-fsSL", "--retry", "2"])
+        .args(["--proto", "=https", "--proto-redir", "=https"])
+        .args(["--connect-timeout", CONNECT_TIMEOUT_SECS])
+        .args(["--max-time", MAX_TIME_SECS])
+        .args(["-K", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| FetchError::Download(format!("curl: {}", err)))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(config.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(FetchError::Download(format!(
+            "{}: {}",
+            label,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(output.stdout)
+}
+
+fn ecad_links(value: &Value, providers: &[&str], out: &mut Vec<String>) {
+    match value {
+        Value::String(text)
+            if ecad_provider(text).is_some_and(|provider| providers.contains(&provider)) =>
+        {
+            out.push(text.clone())
+        }
+        Value::Array(items) => items
+            .iter()
+            .for_each(|item| ecad_links(item, providers, out)),
+        Value::Object(fields) => fields
+            .values()
+            .for_each(|field| ecad_links(field, providers, out)),
+        _ => {}
+    }
+}
+
+fn ecad_provider(url: &str) -> Option<&'static str> {
+    let host = url_host(url)?;
+    ECAD_HOSTS
+        .iter()
+        .find(|(domain, _)| {
+            host == *domain
+                || host
+                    .strip_suffix(domain)
+                    .is_some_and(|subdomain| subdomain.ends_with('.'))
+        })
+        .map(|(_, provider)| *provider)
+}
+
+fn url_host(url: &str) -> Option<String> {
+    let (_, rest) = url.trim().split_once("://")?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?;
+    let host = host.split(':').next()?.trim_end_matches('.');
+    (!host.is_empty()).then(|| host.to_ascii_lowercase())
+}
+
+fn curl_config_value(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn percent_encode(value: &str) -> String {
+    let mut out = String::new();
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    out
+}
+
+fn cache_name(part: &str) -> String {
+    part.chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_') {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn fetches_component_and_model_into_cache() {
+        assert_eq!("c7593".parse::<LcscPart>().unwrap().number(), "C7593");
+        assert_eq!("7593".parse::<LcscPart>().unwrap().number(), "C7593");
+        assert!("NE555".parse::<LcscPart>().is_err());
+        let dir = tempdir().unwrap();
+        let remote = dir.path().join("easyeda.com");
+        fs::create_dir_all(&remote).unwrap();
+        fs::write(
+            remote.join("C7593.json"),
+            r#"{"success": true, "result": {
+                "dataStr": {"head": {"c_para": {"name": "NE555DR"}}, "shape": []},
+                "packageDetail": {"title": "SOIC-8", "dataStr": {"head": {"uuid_3d": "abc"}, "shape": []}}
+            }}"#,
+        )
+        .unwrap();
+        fs::write(remote.join("abc"), "ISO-10303-21;").unwrap();
+        let fetcher = LcscFetcher::new(dir.path())
+            .with_urls("https://easyeda.com/{part}.json", "https://easyeda.com/{uuid}")
+            .with_mirror(dir.path());
+
+        let part: LcscPart = "C7593".parse().unwrap();
+        let fetched = fetcher.fetch(&part).unwrap();
+        assert!(!fetched.cached());
+        assert!(fetched.warnings().is_empty());
+        assert!(fetched.component().starts_with(fetcher.cache_dir()));
+        assert_eq!(
+            fs::read_to_string(fetched.model().unwrap()).unwrap(),
+            "ISO-10303-21;"
+        );
+
+        fs::remove_dir_all(&remote).unwrap();
+        let fetched = fetcher.fetch(&part).unwrap();
+        assert!(fetched.cached());
+        assert_eq!(fetched.to_string(), "C7593: cached with 3D model");
+        assert!(matches!(
+            fetcher.fetch(&"C1".parse().unwrap()),
+            Err(FetchError::Download(_))
+        ));
+    }
+
+    #[test]
+    fn expired_cache_entries_are_refetched_or_reused() {
+        let dir = tempdir().unwrap();
+        let fetcher = LcscFetcher::new(dir.path())
+            .with_urls("https://easyeda.com/{part}.json", "https://easyeda.com/{uuid}")
+            .with_mirror(dir.path())
+            .with_ttl(Duration::ZERO);
+        let part: LcscPart = "C7593".parse().unwrap();
+        let entry = fetcher.cache_dir().join("C7593");
+        fs::create_dir_all(&entry).unwrap();
+        fs::write(
+            entry.join("C7593.json"),
+            r#"{"success": true, "result": {
+                "dataStr": {"head": {"c_para": {"name": "NE555DR"}}, "shape": []},
+                "packageDetail": {"title": "SOIC-8", "dataStr": {"head": {}, "shape": []}}
+            }}"#,
+        )
+        .unwrap();
+
+        let fetched = fetcher.fetch(&part).unwrap();
+        assert!(fetched.cached());
+        assert_eq!(fetched.warnings().len(), 1);
+        assert!(fetched.warnings()[0].starts_with("C7593: using expired cache entry"));
+        let fresh = fetcher.with_ttl(Duration::from_secs(3600)).fetch(&part).unwrap();
+        assert!(fresh.warnings().is_empty());
+
+        assert_eq!(clear_cache(dir.path()).unwrap(), 1);
+        assert!(!entry.exists());
+        assert_eq!(clear_cache(dir.path()).unwrap(), 0);
+    }
+
+    #[test]
+    #[ignore = "needs curl"]
+    fn curl_refuses_urls_that_are_not_https() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("part.json");
+        fs::write(&path, "{}").unwrap();
+        for url in [
+            format!("file://{}", path.display()),
+            "http://127.0.0.1:9/part.json".to_string(),
+        ] {
+            let err = Transport::Curl.download(&url).unwrap_err().to_string();
+            assert!(err.contains("Protocol"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn matches_ecad_providers_by_url_host() {
+        assert_eq!(ecad_provider("https://www.snapeda.com/parts/x.zip"), Some(SNAPEDA));
+        assert_eq!(ecad_provider("https://user@SnapEDA.com:443/x"), Some(SNAPEDA));
+        assert_eq!(
+            ecad_provider("https://componentsearchengine.com/x?y=z"),
+            Some(SAMACSYS)
+        );
+        assert_eq!(ecad_provider("https://snapeda.com.example.net/x"), None);
+        assert_eq!(ecad_provider("https://example.net/snapeda.com/x"), None);
+        assert_eq!(ecad_provider("https://notsnapeda.com/x"), None);
+        assert_eq!(ecad_provider("snapeda.com"), None);
+    }
+
+    #[test]
+    fn fetches_snapeda_archive_for_digikey_part() {
+        assert_eq!(percent_encode("296-1411-5/ND"), "296-1411-5%2FND");
+        assert_eq!(cache_name("../NE555 P"), "___NE555_P");
+        let dir = tempdir().unwrap();
+        let remote = dir.path().join("snapeda.com");
+        fs::create_dir_all(&remote).unwrap();
+        fs::write(remote.join("token.json"), r#"{"access_token": "abc"}"#).unwrap();
+        fs::write(remote.join("page.html"), "<html></html>").unwrap();
+        fs::write(remote.join("kicad.zip"), b"PK\x03\x04rest").unwrap();
+        let base = "https://snapeda.com";
+        fs::write(
+            remote.join("296-1411-5-ND.json"),
+            format!(
+                r#"{{"Product": {{"ManufacturerProductNumber": "NE555P",
+                    "MediaLinks": [{{"Url": "{base}/page.html"}}, {{"Url": "https://example.com/ds.pdf"}}],
+                    "OtherLinks": {{"CadModel": "{base}/kicad.zip"}}}}}}"#
+            ),
+        )
+        .unwrap();
+        let fetcher = DigikeyFetcher::new(dir.path(), DigikeyCredentials::new("id", "secret"))
+            .with_urls(
+                &format!("{}/token.json", base),
+                &format!("{}/{{part}}.json", base),
+            )
+            .with_mirror(dir.path());
+
+        let fetched = fetcher.fetch("296-1411-5-ND").unwrap();
+        assert!(!fetched.cached());
+        assert!(fetched.archive().starts_with(fetcher.cache_dir()));
+        assert_eq!(fs::read(fetched.archive()).unwrap(), b"PK\x03\x04rest");
+        assert_eq!(
+            fetcher.fetch("296-1411-5-ND").unwrap().to_string(),
+            "296-1411-5-ND: cached SnapEDA CAD model"
+        );
+
+        fs::write(remote.join("NE556.json"), r#"{"Product": {}}"#).unwrap();
+        assert!(matches!(
+            fetcher.fetch("NE556"),
+            Err(FetchError::NoCadModel(_))
+        ));
+    }
+
+    #[test]
+    fn fetches_ecad_archive_for_mouser_part() {
+        let dir = tempdir().unwrap();
+        let remote = dir.path().join("componentsearchengine.com");
+        fs::create_dir_all(&remote).unwrap();
+        fs::write(remote.join("model.zip"), b"PK\x03\x04rest").unwrap();
+        let base = "https://componentsearchengine.com";
+        fs::write(
+            remote.join("good.json"),
+            format!(
+                r#"{{"Errors": [], "SearchResults": {{"NumberOfResult": 1, "Parts": [
+                    {{"MouserPartNumber": "595-NE555P", "DataSheetUrl": "https://example.com/ds.pdf",
+                      "ProductDetailUrl": "{base}/model.zip"}}]}}}}"#
+            ),
+        )
+        .unwrap();
+        let fetcher = MouserFetcher::new(dir.path(), MouserCredentials::new("good"))
+            .with_url(&format!("{}/{{key}}.json", base))
+            .with_mirror(dir.path());
+
+        let fetched = fetcher.fetch("595-NE555P").unwrap();
+        assert!(!fetched.cached());
+        assert!(fetched.archive().starts_with(fetcher.cache_dir()));
+        assert_eq!(
+            fetched.to_string(),
+            "595-NE555P: downloaded SamacSys CAD model"
+        );
+        assert!(fetcher.fetch("595-NE555P").unwrap().cached());
+        let expired = fetcher.clone().with_ttl(Duration::ZERO);
+        assert!(!expired.fetch("595-NE555P").unwrap().cached());
+
+        fs::write(
+            remote.join("empty.json"),
+            r#"{"Errors": [], "SearchResults": {"NumberOfResult": 0, "Parts": []}}"#,
+        )
+        .unwrap();
+        let fetcher = MouserFetcher::new(dir.path(), MouserCredentials::new("empty"))
+            .with_url(&format!("{}/{{key}}.json", base))
+            .with_mirror(dir.path());
+        assert!(matches!(
+            fetcher.fetch("595-NE556N"),
+            Err(FetchError::NoCadModel(_))
+        ));
+
+        fs::write(
+            remote.join("bad.json"),
+            r#"{"Errors": [{"Code": "Invalid", "Message": "Invalid unique identifier."}]}"#,
+        )
+        .unwrap();
+        let fetcher = MouserFetcher::new(dir.path(), MouserCredentials::new("bad"))
+            .with_url(&format!("{}/{{key}}.json", base))
+            .with_mirror(dir.path());
+        let err = fetcher.fetch("595-NE556N").unwrap_err().to_string();
+        assert!(err.contains("Invalid unique identifier."), "{}", err);
+        assert!(!err.contains("bad.json"), "{}", err);
+    }
+
+    #[test]
+    fn looks_up_part_metadata_on_nexar() {
+        let dir = tempdir().unwrap();
+        let remote = dir.path().join("nexar.com");
+        fs::create_dir_all(&remote).unwrap();
+        fs::write(remote.join("token.json"), r#"{"access_token": "abc"}"#).unwrap();
+        fs::write(
+            remote.join("part.json"),
+            r#"{"data": {"supSearchMpn": {"results": [{"part": {"mpn": "NE555P",
+                "manufacturer": {"name": "Texas Instruments"}, "shortDescription": "Precision timer",
+                "bestDatasheet": null}}]}}}"#,
+        )
+        .unwrap();
+        fs::write(
+            remote.join("none.json"),
+            r#"{"data": {"supSearchMpn": {"results": []}}}"#,
+        )
+        .unwrap();
+        let base = "https://nexar.com";
+        let client = |api: &str| {
+            NexarClient::new(dir.path(), NexarCredentials::new("id", "secret"))
+                .with_urls(&format!("{}/token.json", base), &format!("{}/{}", base, api))
+                .with_mirror(dir.path())
+        };
+
+        let metadata = client("part.json").lookup("NE555P").unwrap().unwrap();
+        assert_eq!(metadata.manufacturer(), Some("Texas Instruments"));
+        assert_eq!(metadata.datasheet(), None);
+        assert_eq!(
+            metadata.properties(),
+            [
+                ("Manufacturer", "Texas Instruments"),
+                ("MPN", "NE555P"),
+                ("Description", "Precision timer")
+            ]
+        );
+        assert_eq!(client("none.json").lookup("XYZ").unwrap(), None);
+
+        fs::remove_file(remote.join("token.json")).unwrap();
+        let cached = client("part.json").lookup(" NE555P ").unwrap().unwrap();
+        assert_eq!(cached, metadata);
+        assert!(
+            client("part.json")
+                .with_ttl(Duration::ZERO)
+                .lookup("NE555P")
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,686 @@
+use crate::kicad_sym::{format_number, KicadSymError, Symbol, SCHEMATIC_PRECISION};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+const CHIP_SIZES: &[(&str, &str)] = &[
+    ("0201", "0603Metric"),
+    ("0402", "1005Metric"),
+    ("0603", "1608Metric"),
+    ("0805", "2012Metric"),
+    ("1206", "3216Metric"),
+    ("1210", "3225Metric"),
+    ("1812", "4532Metric"),
+    ("2010", "5025Metric"),
+    ("2512", "6332Metric"),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PassiveKind {
+    Resistor,
+    Capacitor,
+}
+
+impl PassiveKind {
+    fn prefix(self) -> &'static str {
+        match self {
+            PassiveKind::Resistor => "R",
+            PassiveKind::Capacitor => "C",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            PassiveKind::Resistor => "Resistor",
+            PassiveKind::Capacitor => "Unpolarized capacitor",
+        }
+    }
+
+    fn keywords(self) -> &'static str {
+        match self {
+            PassiveKind::Resistor => "R res resistor",
+            PassiveKind::Capacitor => "cap capacitor",
+        }
+    }
+
+    fn footprint_lib(self) -> &'static str {
+        match self {
+            PassiveKind::Resistor => "Resistor_SMD",
+            PassiveKind::Capacitor => "Capacitor_SMD",
+        }
+    }
+}
+
+impl FromStr for PassiveKind {
+    type Err = GenerateError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "r" | "res" | "resistor" => Ok(PassiveKind::Resistor),
+            "c" | "cap" | "capacitor" => Ok(PassiveKind::Capacitor),
+            _ => Err(GenerateError::UnknownKind(value.to_string())),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChipSize {
+    imperial: &'static str,
+    metric: &'static str,
+}
+
+impl ChipSize {
+    pub fn imperial(&self) -> &str {
+        self.imperial
+    }
+
+    pub fn metric(&self) -> &str {
+        self.metric
+    }
+}
+
+impl FromStr for ChipSize {
+    type Err = GenerateError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let trimmed = value.trim();
+        CHIP_SIZES
+            .iter()
+            .find(|(imperial, _)| *imperial == trimmed)
+            .map(|(imperial, metric)| ChipSize { imperial, metric })
+            .ok_or_else(|| GenerateError::UnknownSize(value.to_string()))
+    }
+}
+
+#[derive(Debug)]
+pub enum GenerateError {
+    UnknownKind(String),
+    UnknownSize(String),
+    InvalidValue(String),
+    Symbol(KicadSymError),
+}
+
+impl fmt::Display for GenerateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GenerateError::UnknownKind(kind) => write!(f, "unknown passive kind: {}", kind),
+            GenerateError::UnknownSize(size) => write!(f, "unknown chip size: {}", size),
+            GenerateError::InvalidValue(msg) => write!(f, "invalid value: {}", msg),
+            GenerateError::Symbol(err) => write!(f, "symbol error: {}", err),
+        }
+    }
+}
+
+impl Error for GenerateError {}
+
+impl From<KicadSymError> for GenerateError {
+    fn from(value: KicadSymError) -> Self {
+        GenerateError::Symbol(value)
+    }
+}
+
+pub fn passive_footprint(kind: PassiveKind, size: ChipSize) -> String {
+    format!(
+        "{}:{}_{}_{}",
+        kind.footprint_lib(),
+        kind.prefix(),
+        size.imperial(),
+        size.metric()
+    )
+}
+
+pub fn generate_passive(
+    kind: PassiveKind,
+    value: &str,
+    size: ChipSize,
+) -> Result<Symbol, GenerateError> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(GenerateError::InvalidValue("value is empty".to_string()));
+    }
+    let name = format!(
+        "{}_{}_{}",
+        kind.prefix(),
+        sanitize_name(value),
+        size.imperial()
+    );
+    let body = match kind {
+        PassiveKind::Resistor => resistor_body(&name),
+        PassiveKind::Capacitor => capacitor_body(&name),
+    };
+    let description = format!("{} {} {}", kind.description(), value, size.imperial());
+    let input = format!(
+        "(symbol {name} (pin_numbers hide) (pin_names (offset 0)) (exclude_from_sim no) (in_bom yes) (on_board yes)\
+         (property \"Reference\" {reference} (at 2.032 0 90) (effects (font (size 1.27 1.27))))\
+         (property \"Value\" {value} (at 0 0 90) (effects (font (size 1.27 1.27))))\
+         (property \"Footprint\" {footprint} (at -1.778 0 90) (effects (font (size 1.27 1.27)) hide))\
+         (property \"Datasheet\" \"~\" (at 0 0 0) (effects (font (size 1.27 1.27)) hide))\
+         (property \"Description\" {description} (at 0 0 0) (effects (font (size 1.27 1.27)) hide))\
+         (property \"ki_keywords\" {keywords} (at 0 0 0) (effects (font (size 1.27 1.27)) hide))\
+         {body})",
+        name = quote(&name),
+        reference = quote(kind.prefix()),
+        value = quote(value),
+        footprint = quote(&passive_footprint(kind, size)),
+        description = quote(&description),
+        keywords = quote(kind.keywords()),
+        body = body,
+    );
+    Ok(Symbol::parse(&input)?)
+}
+
+fn resistor_body(name: &str) -> String {
+    format!(
+        "(symbol {graphics} (rectangle (start -1.016 -2.54) (end 1.016 2.54) (stroke (width 0.254) (type default)) (fill (type none))))\
+         (symbol {pins} {pin1} {pin2})",
+        graphics = quote(&format!("{}_0_1", name)),
+        pins = quote(&format!("{}_1_1", name)),
+        pin1 = passive_pin("1", 3.81, 270, 1.27),
+        pin2 = passive_pin("2", -3.81, 90, 1.27),
+    )
+}
+
+fn capacitor_body(name: &str) -> String {
+    format!(
+        "(symbol {graphics} \
+         (polyline (pts (xy -2.032 -0.762) (xy 2.032 -0.762)) (stroke (width 0.508) (type default)) (fill (type none)))\
+         (polyline (pts (xy -2.032 0.762) (xy 2.032 0.762)) (stroke (width 0.508) (type default)) (fill (type none))))\
+         (symbol {pins} {pin1} {pin2})",
+        graphics = quote(&format!("{}_0_1", name)),
+        pins = quote(&format!("{}_1_1", name)),
+        pin1 = passive_pin("1", 3.81, 270, 2.794),
+        pin2 = passive_pin("2", -3.81, 90, 2.794),
+    )
+}
+
+fn passive_pin(number: &str, y: f64, angle: u32, length: f64) -> String {
+    format!(
+        "(pin passive line (at 0 {} {}) (length {}) \
+         (name \"~\" (effects (font (size 1.27 1.27)))) \
+         (number {} (effects (font (size 1.27 1.27)))))",
+        y,
+        angle,
+        length,
+        quote(number)
+    )
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConnectorPin {
+    number: String,
+    name: String,
+}
+
+impl ConnectorPin {
+    pub fn new(number: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            number: number.into(),
+            name: name.into(),
+        }
+    }
+
+    pub fn number(&self) -> &str {
+        &self.number
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ConnectorSpec {
+    pins: Vec<ConnectorPin>,
+    pitch: f64,
+    rows: usize,
+}
+
+impl ConnectorSpec {
+    pub fn new(pin_count: usize, pitch: f64, rows: usize) -> Result<Self, GenerateError> {
+        let pins = (1..=pin_count)
+            .map(|number| ConnectorPin::new(number.to_string(), format!("Pin_{}", number)))
+            .collect();
+        Self::from_pins(pins, pitch, rows)
+    }
+
+    pub fn from_csv(input: &str, pitch: f64, rows: usize) -> Result<Self, GenerateError> {
+        let mut pins = Vec::new();
+        let mut seen = HashMap::new();
+        for (idx, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (number, name) = match line.split_once(',') {
+                Some((number, name)) => (number.trim(), name.trim()),
+                None => (line, ""),
+            };
+            if idx == 0 && number.eq_ignore_ascii_case("number") {
+                continue;
+            }
+            if number.is_empty() {
+                return Err(GenerateError::InvalidValue(format!(
+                    "missing pin number on line {}",
+                    idx + 1
+                )));
+            }
+            if let Some(first) = seen.insert(number.to_string(), idx + 1) {
+                return Err(GenerateError::InvalidValue(format!(
+                    "duplicate pin number {} on line {} (first used on line {})",
+                    number,
+                    idx + 1,
+                    first
+                )));
+            }
+            let name = if name.is_empty() {
+                format!("Pin_{}", number)
+            } else {
+                name.to_string()
+            };
+            pins.push(ConnectorPin::new(number, name));
+        }
+        Self::from_pins(pins, pitch, rows)
+    }
+
+    fn from_pins(pins: Vec<ConnectorPin>, pitch: f64, rows: usize) -> Result<Self, GenerateError> {
+        if pins.is_empty() {
+            return Err(GenerateError::InvalidValue(
+                "connector needs at least one pin".to_string(),
+            ));
+        }
+        if !(1..=2).contains(&rows) {
+            return Err(GenerateError::InvalidValue(format!(
+                "unsupported row count: {}",
+                rows
+            )));
+        }
+        if rows == 2 && !pins.len().is_multiple_of(2) {
+            return Err(GenerateError::InvalidValue(
+                "dual-row connectors need an even pin count".to_string(),
+            ));
+        }
+        if pad_dimensions(pitch).is_none() {
+            return Err(GenerateError::InvalidValue(format!(
+                "unsupported pitch: {}mm",
+                pitch
+            )));
+        }
+        Ok(Self { pins, pitch, rows })
+    }
+
+    pub fn pins(&self) -> &[ConnectorPin] {
+        &self.pins
+    }
+
+    pub fn pitch(&self) -> f64 {
+        self.pitch
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    fn per_row(&self) -> usize {
+        self.pins.len() / self.rows
+    }
+
+    pub fn symbol_name(&self) -> String {
+        format!(
+            "Conn_{:02}x{:02}_P{}mm",
+            self.rows,
+            self.per_row(),
+            format_mm(self.pitch)
+        )
+    }
+
+    pub fn footprint_name(&self) -> String {
+        format!(
+            "PinHeader_{}x{:02}_P{}mm_Vertical",
+            self.rows,
+            self.per_row(),
+            format_mm(self.pitch)
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct GeneratedConnector {
+    symbol: Symbol,
+    footprint_name: String,
+    footprint: String,
+}
+
+impl GeneratedConnector {
+    pub fn symbol(&self) -> &Symbol {
+        &self.symbol
+    }
+
+    pub fn into_symbol(self) -> Symbol {
+        self.symbol
+    }
+
+    pub fn footprint_name(&self) -> &str {
+        &self.footprint_name
+    }
+
+    pub fn footprint(&self) -> &str {
+        &self.footprint
+    }
+}
+
+pub fn generate_connector(
+    spec: &ConnectorSpec,
+    name: Option<&str>,
+    footprint_lib_name: &str,
+) -> Result<GeneratedConnector, GenerateError> {
+    let symbol_name = match name {
+        Some(name) => sanitize_name(name),
+        None => spec.symbol_name(),
+    };
+    let footprint_name = spec.footprint_name();
+    let symbol = connector_symbol(spec, &symbol_name, footprint_lib_name, &footprint_name)?;
+    let footprint = connector_footprint(spec, &footprint_name);
+    Ok(GeneratedConnector {
+        symbol,
+        footprint_name,
+        footprint,
+    })
+}
+
+fn connector_symbol(
+    spec: &ConnectorSpec,
+    name: &str,
+    footprint_lib_name: &str,
+    footprint_name: &str,
+) -> Result<Symbol, GenerateError> {
+    let per_row = spec.per_row();
+    let bottom = -(per_row as f64 - 1.0) * 2.54;
+    let right = if spec.rows() == 2 { 3.81 } else { 1.27 };
+    let mut pins = String::new();
+    for (idx, pin) in spec.pins().iter().enumerate() {
+        let (row, column) = if spec.rows() == 2 {
+            (idx / 2, idx % 2)
+        } else {
+            (idx, 0)
+        };
+        let y = -(row as f64) * 2.54;
+        let (x, angle) = if column == 0 {
+            (-5.08, 0)
+        } else {
+            (right + 3.81, 180)
+        };
+        pins.push_str(&format!(
+            "(pin passive line (at {} {} {}) (length 3.81) \
+             (name {} (effects (font (size 1.27 1.27)))) \
+             (number {} (effects (font (size 1.27 1.27)))))",
+            format_mm(x),
+            format_mm(y),
+            angle,
+            quote(pin.name()),
+            quote(pin.number())
+        ));
+    }
+    let description = format!(
+        "Generic connector, {} row(s), {} pins, {}mm pitch",
+        spec.rows(),
+        spec.pins().len(),
+        format_mm(spec.pitch())
+    );
+    let input = format!(
+        "(symbol {name} (pin_names (offset 1.016) hide) (exclude_from_sim no) (in_bom yes) (on_board yes)\
+         (property \"Reference\" \"J\" (at 0 2.54 0) (effects (font (size 1.27 1.27))))\
+         (property \"Value\" {name} (at 0 {value_y} 0) (effects (font (size 1.27 1.27))))\
+         (property \"Footprint\" {footprint} (at 0 0 0) (effects (font (size 1.27 1.27)) hide))\
+         (property \"Datasheet\" \"~\" (at 0 0 0) (effects (font (size 1.27 1.27)) hide))\
+         (property \"Description\" {description} (at 0 0 0) (effects (font (size 1.27 1.27)) hide))\
+         (property \"ki_keywords\" \"connector\" (at 0 0 0) (effects (font (size 1.27 1.27)) hide))\
+         (symbol {graphics} (rectangle (start -1.27 1.27) (end {right} {bottom}) \
+         (stroke (width 0.254) (type default)) (fill (type background))))\
+         (symbol {units} {pins}))",
+        name = quote(name),
+        value_y = format_mm(bottom - 2.54),
+        footprint = quote(&format!("{}:{}", footprint_lib_name, footprint_name)),
+        description = quote(&description),
+        graphics = quote(&format!("{}_0_1", name)),
+        right = format_mm(right),
+        bottom = format_mm(bottom - 1.27),
+        units = quote(&format!("{}_1_1", name)),
+        pins = pins,
+    );
+    Ok(Symbol::parse(&input)?)
+}
+
+fn connector_footprint(spec: &ConnectorSpec, name: &str) -> String {
+    let pitch = spec.pitch();
+    let (pad, drill) = pad_dimensions(pitch).unwrap_or((1.7, 1.0));
+    let per_row = spec.per_row();
+    let left = -pitch / 2.0;
+    let top = -pitch / 2.0;
+    let right = (spec.rows() as f64 - 1.0) * pitch + pitch / 2.0;
+    let bottom = (per_row as f64 - 1.0) * pitch + pitch / 2.0;
+
+    let mut lines = vec![
+        format!("(footprint {}", quote(name)),
+        "\t(version 20221018)".to_string(),
+        "\t(generator \"kci\")".to_string(),
+        "\t(layer \"F.Cu\")".to_string(),
+        format!(
+            "\t(descr {})",
+            quote(&format!(
+                "Through hole straight pin header, {}x{:02}, {}mm pitch",
+                spec.rows(),
+                per_row,
+                format_mm(pitch)
+            ))
+        ),
+        "\t(tags \"connector pin header THT\")".to_string(),
+        "\t(attr through_hole)".to_string(),
+        format!(
+            "\t(fp_text reference \"REF**\" (at {} {}) (layer \"F.SilkS\") (effects (font (size 1 1) (thickness 0.15))))",
+            format_mm((left + right) / 2.0),
+            format_mm(top - 1.5)
+        ),
+        format!(
+            "\t(fp_text value {} (at {} {}) (layer \"F.Fab\") (effects (font (size 1 1) (thickness 0.15))))",
+            quote(name),
+            format_mm((left + right) / 2.0),
+            format_mm(bottom + 1.5)
+        ),
+    ];
+    lines.extend(rectangle_lines(left, top, right, bottom, 0.1, "F.Fab"));
+    lines.extend(rectangle_lines(
+        left - 0.11,
+        top - 0.11,
+        right + 0.11,
+        bottom + 0.11,
+        0.12,
+        "F.SilkS",
+    ));
+    lines.extend(rectangle_lines(
+        snap_courtyard(left - 0.5, false),
+        snap_courtyard(top - 0.5, false),
+        snap_courtyard(right + 0.5, true),
+        snap_courtyard(bottom + 0.5, true),
+        0.05,
+        "F.CrtYd",
+    ));
+    for (idx, pin) in spec.pins().iter().enumerate() {
+        let (row, column) = if spec.rows() == 2 {
+            (idx / 2, idx % 2)
+        } else {
+            (idx, 0)
+        };
+        let shape = if idx == 0 { "rect" } else { "oval" };
+        lines.push(format!(
+            "\t(pad {} thru_hole {} (at {} {}) (size {} {}) (drill {}) (layers \"*.Cu\" \"*.Mask\"))",
+            quote(pin.number()),
+            shape,
+            format_mm(column as f64 * pitch),
+            format_mm(row as f64 * pitch),
+            format_mm(pad),
+            format_mm(pad),
+            format_mm(drill)
+        ));
+    }
+    lines.push(")".to_string());
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
+
+fn rectangle_lines(
+    left: f64,
+    top: f64,
+    right: f64,
+    bottom: f64,
+    width: f64,
+    layer: &str,
+) -> Vec<String> {
+    let corners = [
+        (left, top),
+        (right, top),
+        (right, bottom),
+        (left, bottom),
+        (left, top),
+    ];
+    corners
+        .windows(2)
+        .map(|pair| {
+            format!(
+                "\t(fp_line (start {} {}) (end {} {}) (stroke (width {}) (type solid)) (layer {}))",
+                format_mm(pair[0].0),
+                format_mm(pair[0].1),
+                format_mm(pair[1].0),
+                format_mm(pair[1].1),
+                format_mm(width),
+                quote(layer)
+            )
+        })
+        .collect()
+}
+
+fn pad_dimensions(pitch: f64) -> Option<(f64, f64)> {
+    if !pitch.is_finite() || pitch < 1.27 {
+        None
+    } else if pitch >= 2.54 {
+        Some((1.7, 1.0))
+    } else if pitch >= 2.0 {
+        Some((1.35, 0.8))
+    } else {
+        Some((1.0, 0.65))
+    }
+}
+
+fn snap_courtyard(value: f64, up: bool) -> f64 {
+    let steps = value / 0.05;
+    let steps = if up { steps.ceil() } else { steps.floor() };
+    steps * 0.05
+}
+
+fn format_mm(value: f64) -> String {
+    format_number(value, SCHEMATIC_PRECISION)
+}
+
+fn sanitize_name(value: &str) -> String {
+    value
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || matches!(ch, '.' | '-' | '+') {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generates_resistor_with_standard_footprint() {
+        let size: ChipSize = "0402".parse().unwrap();
+        let symbol = generate_passive(PassiveKind::Resistor, "10k", size).unwrap();
+        assert_eq!(symbol.name(), "R_10k_0402");
+        assert_eq!(symbol.property_value("Reference").unwrap(), "R");
+        assert_eq!(symbol.property_value("Value").unwrap(), "10k");
+        assert_eq!(
+            symbol.property_value("Footprint").unwrap(),
+            "Resistor_SMD:R_0402_1005Metric"
+        );
+    }
+
+    #[test]
+    fn generates_capacitor_and_sanitizes_name() {
+        let size: ChipSize = "0603".parse().unwrap();
+        let kind: PassiveKind = "cap".parse().unwrap();
+        let symbol = generate_passive(kind, "100nF 16V", size).unwrap();
+        assert_eq!(symbol.name(), "C_100nF_16V_0603");
+        assert_eq!(symbol.property_value("Value").unwrap(), "100nF 16V");
+        assert_eq!(
+            symbol.property_value("Footprint").unwrap(),
+            "Capacitor_SMD:C_0603_1608Metric"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_size_and_kind() {
+        assert!(matches!(
+            "0404".parse::<ChipSize>(),
+            Err(GenerateError::UnknownSize(_))
+        ));
+        assert!(matches!(
+            "Q".parse::<PassiveKind>(),
+            Err(GenerateError::UnknownKind(_))
+        ));
+    }
+
+    #[test]
+    fn generates_single_row_connector_and_footprint() {
+        let spec = ConnectorSpec::new(4, 2.54, 1).unwrap();
+        let generated = generate_connector(&spec, None, "Project").unwrap();
+        assert_eq!(generated.symbol().name(), "Conn_01x04_P2.54mm");
+        assert_eq!(
+            generated.symbol().property_value("Footprint").unwrap(),
+            "Project:PinHeader_1x04_P2.54mm_Vertical"
+        );
+        let footprint = crate::kicad_sym::parse_one(generated.footprint()).unwrap();
+        let text = footprint.to_string_pretty();
+        assert!(text.contains("(at 0 7.62)"));
+        assert_eq!(generated.footprint().matches("(pad ").count(), 4);
+        assert!(generated.footprint().contains("(pad \"1\" thru_hole rect"));
+    }
+
+    #[test]
+    fn connector_from_csv_uses_pin_names() {
+        let csv = "number,name\n1,VCC\n2,GND\n3,SDA\n4,SCL\n";
+        let spec = ConnectorSpec::from_csv(csv, 2.0, 2).unwrap();
+        assert_eq!(spec.pins()[2].name(), "SDA");
+        let generated = generate_connector(&spec, Some("I2C Header"), "Project").unwrap();
+        assert_eq!(generated.symbol().name(), "I2C_Header");
+        assert_eq!(generated.footprint_name(), "PinHeader_2x02_P2mm_Vertical");
+        assert!(generated.footprint().contains("(drill 0.8)"));
+    }
+
+    #[test]
+    fn connector_from_csv_rejects_duplicate_pin_numbers() {
+        let csv = "number,name\n1,VCC\n2,GND\n\n2,SDA\n4,SCL\n";
+        let err = ConnectorSpec::from_csv(csv, 2.54, 2).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "invalid value: duplicate pin number 2 on line 5 (first used on line 3)"
+        );
+    }
+
+    #[test]
+    fn connector_rejects_odd_dual_row() {
+        assert!(ConnectorSpec::new(5, 2.54, 2).is_err());
+        assert!(ConnectorSpec::new(5, 0.5, 1).is_err());
+    }
+}
@@ -0,0 +1,97 @@
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+pub const GIT_IGNORE_FILE: &str = ".gitignore";
+pub const KCI_IGNORE_FILE: &str = ".kciignore";
+
+#[derive(Debug, Default)]
+pub struct IgnoreRules {
+    matchers: Vec<Gitignore>,
+}
+
+impl IgnoreRules {
+    pub fn load(root: &Path, warnings: &mut Vec<String>) -> Self {
+        let mut files = WalkDir::new(root)
+            .into_iter()
+            .filter_entry(|entry| entry.file_name() != ".git")
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_file())
+            .filter_map(|entry| {
+                let rank = match entry.file_name().to_str()? {
+                    KCI_IGNORE_FILE => 0,
+                    GIT_IGNORE_FILE => 1,
+                    _ => return None,
+                };
+                Some((entry.depth(), rank, entry.into_path()))
+            })
+            .collect::<Vec<_>>();
+        files.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)).then(a.2.cmp(&b.2)));
+        let mut matchers = Vec::new();
+        for (_, _, path) in files {
+            let dir = path.parent().unwrap_or(root);
+            let mut builder = GitignoreBuilder::new(dir);
+            if let Some(err) = builder.add(&path) {
+                warnings.push(format!("{}: {}", path.display(), err));
+            }
+            match builder.build() {
+                Ok(matcher) if !matcher.is_empty() => matchers.push(matcher),
+                Ok(_) => {}
+                Err(err) => warnings.push(format!("{}: {}", path.display(), err)),
+            }
+        }
+        Self { matchers }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.matchers.is_empty()
+    }
+
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        for matcher in &self.matchers {
+            if !path.starts_with(matcher.path()) {
+                continue;
+            }
+            let matched = matcher.matched_path_or_any_parents(path, path.is_dir());
+            if matched.is_ignore() {
+                return true;
+            }
+            if matched.is_whitelist() {
+                return false;
+            }
+        }
+        false
+    }
+
+    pub fn retain(&self, paths: &mut Vec<PathBuf>) -> usize {
+        let before = paths.len();
+        paths.retain(|path| !self.is_ignored(path));
+        before - paths.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn nested_and_kci_rules_take_precedence() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join("build")).unwrap();
+        fs::create_dir_all(root.join("vendor/build")).unwrap();
+        fs::write(root.join(GIT_IGNORE_FILE), "build/\n*.kicad_mod\n").unwrap();
+        fs::write(root.join(KCI_IGNORE_FILE), "!keep.kicad_mod\n").unwrap();
+        fs::write(root.join("vendor").join(GIT_IGNORE_FILE), "!build/\n").unwrap();
+
+        let mut warnings = Vec::new();
+        let rules = IgnoreRules::load(root, &mut warnings);
+        assert!(warnings.is_empty());
+        assert!(rules.is_ignored(&root.join("build/part.kicad_sym")));
+        assert!(rules.is_ignored(&root.join("stray.kicad_mod")));
+        assert!(!rules.is_ignored(&root.join("keep.kicad_mod")));
+        assert!(!rules.is_ignored(&root.join("vendor/build/part.kicad_sym")));
+        assert!(!rules.is_ignored(&root.join("part.kicad_sym")));
+    }
+}
@@ -1,19 +1,85 @@
-use crate::kicad_sym::{AddPolicy, KicadSymError, KicadSymbolLib, Symbol};
-use std::collections::HashMap;
+use crate::altium::{self, AltiumError};
+use crate::annotate::{FootprintSummary, SymbolSummary};
+use crate::descriptions::{apply_description_language, DescriptionChoice, DescriptionLanguage};
+use crate::easyeda::{EasyEdaError, convert_component, find_components};
+use crate::fetch::DEFAULT_CACHE_TTL_HOURS;
+use crate::ignore_rules::{IgnoreRules, GIT_IGNORE_FILE, KCI_IGNORE_FILE};
+use crate::journal::ImportJournal;
+use crate::kicad_sym::{parse_one, AddPolicy, Atom, KicadSymError, KicadSymbolLib, Sexp, Symbol};
+use crate::kicad_table::{TableKind, project_table_entries};
+use crate::legacy::{self, LegacyError};
+use crate::lfs::LfsPolicy;
+use crate::manifest::sha256_file;
+use crate::model_store::ModelStore;
+use crate::pad_checks::{check_pads, fix_pads, PadPolicy, PadProblem};
+use crate::pin_text::clean_pin_text;
+use crate::signing::SigningConfig;
+use crate::simplify::{Simplification, SimplifyCommand, SimplifyError};
+use crate::tags::{TagStore, TAG_PROPERTY_PREFIX};
+use crate::value_rules::{apply_value_rules, ValueDecoder};
+use crate::vendor::{detect_vendor, Vendor};
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::error::Error;
 use std::fmt;
 use std::fs;
-use std::io;
-use std::path::{Path, PathBuf};
+use std::io::{self, Cursor, Read, Seek};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
 use tempfile::TempDir;
 use walkdir::WalkDir;
 use zip::ZipArchive;
 
+pub const DEFAULT_ASSOCIATION_THRESHOLD: f64 = 0.8;
+pub const DEFAULT_MODEL_SIZE_LIMIT_MB: f64 = 20.0;
+pub const DEFAULT_COPY_JOBS: usize = 4;
+pub const STDIN_SOURCE: &str = "-";
+const FUZZY_MATCH_FLOOR: f64 = 0.5;
+const TEMP_DIR_PREFIX: &str = ".kci-";
+
 #[derive(Debug, Clone)]
 pub struct ImportConfig {
     symbol_lib: PathBuf,
     footprint_lib: PathBuf,
     step_dir: PathBuf,
+    value_decoders: Vec<ValueDecoder>,
+    description_language: Option<DescriptionLanguage>,
+    kicad_version: Option<u32>,
+    allow_partial: bool,
+    footprints_only: bool,
+    symbols_only: bool,
+    from_kicad_plugin: bool,
+    association_threshold: f64,
+    model_overrides: Vec<ModelOverride>,
+    symbol_overrides: Vec<SymbolOverride>,
+    step_naming: StepNaming,
+    step_layout: StepLayout,
+    signing: Option<SigningConfig>,
+    operator: Option<String>,
+    output_root: Option<PathBuf>,
+    existing_paths: HashMap<PathBuf, PathBuf>,
+    sync_command: Option<String>,
+    lib_descr: Option<String>,
+    lib_options: Option<String>,
+    model_store: Option<ModelStore>,
+    lfs_policy: LfsPolicy,
+    model_size_limit_mb: f64,
+    oversize_policy: OversizePolicy,
+    simplify_command: Option<SimplifyCommand>,
+    tag_store: TagStore,
+    existing_symbols: AddPolicy,
+    keep_local_properties: bool,
+    clean_pin_text: bool,
+    pad_policy: PadPolicy,
+    cache_ttl_hours: u64,
+    copy_jobs: usize,
+    stats: bool,
+    temp_dir: Option<PathBuf>,
+    journal: Option<ImportJournal>,
 }
 
 impl ImportConfig {
@@ -22,9 +88,225 @@ impl ImportConfig {
             symbol_lib,
             footprint_lib,
             step_dir,
+            value_decoders: Vec::new(),
+            description_language: None,
+            kicad_version: None,
+            allow_partial: false,
+            footprints_only: false,
+            symbols_only: false,
+            from_kicad_plugin: false,
+            association_threshold: DEFAULT_ASSOCIATION_THRESHOLD,
+            model_overrides: Vec::new(),
+            symbol_overrides: Vec::new(),
+            step_naming: StepNaming::Original,
+            step_layout: StepLayout::Flat,
+            signing: None,
+            operator: None,
+            output_root: None,
+            existing_paths: HashMap::new(),
+            sync_command: None,
+            lib_descr: None,
+            lib_options: None,
+            model_store: None,
+            lfs_policy: LfsPolicy::Warn,
+            model_size_limit_mb: DEFAULT_MODEL_SIZE_LIMIT_MB,
+            oversize_policy: OversizePolicy::Warn,
+            simplify_command: None,
+            tag_store: TagStore::Property,
+            existing_symbols: AddPolicy::ReplaceExisting,
+            keep_local_properties: true,
+            clean_pin_text: true,
+            pad_policy: PadPolicy::Warn,
+            cache_ttl_hours: DEFAULT_CACHE_TTL_HOURS,
+            copy_jobs: DEFAULT_COPY_JOBS,
+            stats: false,
+            temp_dir: None,
+            journal: None,
         }
     }
 
+    pub fn with_paths(
+        mut self,
+        symbol_lib: PathBuf,
+        footprint_lib: PathBuf,
+        step_dir: PathBuf,
+    ) -> Self {
+        self.symbol_lib = symbol_lib;
+        self.footprint_lib = footprint_lib;
+        self.step_dir = step_dir;
+        self
+    }
+
+    pub fn with_value_decoders(mut self, decoders: Vec<ValueDecoder>) -> Self {
+        self.value_decoders = decoders;
+        self
+    }
+
+    pub fn with_description_language(mut self, language: Option<DescriptionLanguage>) -> Self {
+        self.description_language = language;
+        self
+    }
+
+    pub fn with_kicad_version(mut self, version: Option<u32>) -> Self {
+        self.kicad_version = version;
+        self
+    }
+
+    pub fn with_allow_partial(mut self, allow_partial: bool) -> Self {
+        self.allow_partial = allow_partial;
+        self
+    }
+
+    pub fn with_footprints_only(mut self, footprints_only: bool) -> Self {
+        self.footprints_only = footprints_only;
+        self
+    }
+
+    pub fn with_symbols_only(mut self, symbols_only: bool) -> Self {
+        self.symbols_only = symbols_only;
+        self
+    }
+
+    pub fn with_from_kicad_plugin(mut self, from_kicad_plugin: bool) -> Self {
+        self.from_kicad_plugin = from_kicad_plugin;
+        self
+    }
+
+    pub fn with_association_threshold(mut self, threshold: f64) -> Self {
+        self.association_threshold = threshold;
+        self
+    }
+
+    pub fn with_model_overrides(mut self, overrides: Vec<ModelOverride>) -> Self {
+        self.model_overrides = overrides;
+        self
+    }
+
+    pub fn with_symbol_overrides(mut self, overrides: Vec<SymbolOverride>) -> Self {
+        self.symbol_overrides = overrides;
+        self
+    }
+
+    pub fn with_step_naming(mut self, naming: StepNaming) -> Self {
+        self.step_naming = naming;
+        self
+    }
+
+    pub fn with_step_layout(mut self, layout: StepLayout) -> Self {
+        self.step_layout = layout;
+        self
+    }
+
+    pub fn with_signing(mut self, signing: Option<SigningConfig>) -> Self {
+        self.signing = signing;
+        self
+    }
+
+    pub fn with_operator(mut self, operator: Option<String>) -> Self {
+        self.operator = operator;
+        self
+    }
+
+    pub fn with_output_root(mut self, root: Option<PathBuf>) -> Self {
+        self.output_root = root;
+        self
+    }
+
+    pub fn with_existing_path(mut self, path: &Path, existing: PathBuf) -> Self {
+        self.existing_paths.insert(path.to_path_buf(), existing);
+        self
+    }
+
+    pub fn with_sync_command(mut self, command: Option<String>) -> Self {
+        self.sync_command = command;
+        self
+    }
+
+    pub fn with_lib_descr(mut self, template: Option<String>) -> Self {
+        self.lib_descr = template;
+        self
+    }
+
+    pub fn with_lib_options(mut self, template: Option<String>) -> Self {
+        self.lib_options = template;
+        self
+    }
+
+    pub fn with_model_store(mut self, store: Option<ModelStore>) -> Self {
+        self.model_store = store;
+        self
+    }
+
+    pub fn with_lfs_policy(mut self, policy: LfsPolicy) -> Self {
+        self.lfs_policy = policy;
+        self
+    }
+
+    pub fn with_model_size_limit_mb(mut self, limit: f64) -> Self {
+        self.model_size_limit_mb = limit;
+        self
+    }
+
+    pub fn with_oversize_policy(mut self, policy: OversizePolicy) -> Self {
+        self.oversize_policy = policy;
+        self
+    }
+
+    pub fn with_simplify_command(mut self, command: Option<SimplifyCommand>) -> Self {
+        self.simplify_command = command;
+        self
+    }
+
+    pub fn with_tag_store(mut self, store: TagStore) -> Self {
+        self.tag_store = store;
+        self
+    }
+
+    pub fn with_existing_symbols(mut self, policy: AddPolicy) -> Self {
+        self.existing_symbols = policy;
+        self
+    }
+
+    pub fn with_keep_local_properties(mut self, keep: bool) -> Self {
+        self.keep_local_properties = keep;
+        self
+    }
+
+    pub fn with_clean_pin_text(mut self, clean: bool) -> Self {
+        self.clean_pin_text = clean;
+        self
+    }
+
+    pub fn with_pad_policy(mut self, policy: PadPolicy) -> Self {
+        self.pad_policy = policy;
+        self
+    }
+
+    pub fn with_cache_ttl_hours(mut self, hours: u64) -> Self {
+        self.cache_ttl_hours = hours;
+        self
+    }
+
+    pub fn with_copy_jobs(mut self, jobs: usize) -> Self {
+        self.copy_jobs = jobs.max(1);
+        self
+    }
+
+    pub fn with_stats(mut self, stats: bool) -> Self {
+        self.stats = stats;
+        self
+    }
+
+    pub fn with_temp_dir(mut self, temp_dir: Option<PathBuf>) -> Self {
+        self.temp_dir = temp_dir;
+        self
+    }
+
+    pub fn with_journal(mut self, journal: Option<ImportJournal>) -> Self {
+        self.journal = journal;
+        self
+    }
+
     pub fn symbol_lib(&self) -> &Path {
         &self.symbol_lib
     }
@@ -36,174 +318,1705 @@ impl ImportConfig {
     pub fn step_dir(&self) -> &Path {
         &self.step_dir
     }
+
+    pub fn value_decoders(&self) -> &[ValueDecoder] {
+        &self.value_decoders
+    }
+
+    pub fn description_language(&self) -> Option<&DescriptionLanguage> {
+        self.description_language.as_ref()
+    }
+
+    pub fn kicad_version(&self) -> Option<u32> {
+        self.kicad_version
+    }
+
+    pub fn allow_partial(&self) -> bool {
+        self.allow_partial
+    }
+
+    pub fn footprints_only(&self) -> bool {
+        self.footprints_only
+    }
+
+    pub fn symbols_only(&self) -> bool {
+        self.symbols_only
+    }
+
+    pub fn from_kicad_plugin(&self) -> bool {
+        self.from_kicad_plugin
+    }
+
+    pub fn association_threshold(&self) -> f64 {
+        self.association_threshold
+    }
+
+    pub fn model_overrides(&self) -> &[ModelOverride] {
+        &self.model_overrides
+    }
+
+    pub fn symbol_overrides(&self) -> &[SymbolOverride] {
+        &self.symbol_overrides
+    }
+
+    pub fn step_naming(&self) -> StepNaming {
+        self.step_naming
+    }
+
+    pub fn step_layout(&self) -> StepLayout {
+        self.step_layout
+    }
+
+    pub fn signing(&self) -> Option<&SigningConfig> {
+        self.signing.as_ref()
+    }
+
+    pub fn operator(&self) -> Option<&str> {
+        self.operator.as_deref()
+    }
+
+    pub fn output_root(&self) -> Option<&Path> {
+        self.output_root.as_deref()
+    }
+
+    pub fn sync_command(&self) -> Option<&str> {
+        self.sync_command.as_deref()
+    }
+
+    pub fn lib_descr(&self) -> Option<&str> {
+        self.lib_descr.as_deref()
+    }
+
+    pub fn lib_options(&self) -> Option<&str> {
+        self.lib_options.as_deref()
+    }
+
+    pub fn model_store(&self) -> Option<&ModelStore> {
+        self.model_store.as_ref()
+    }
+
+    pub fn lfs_policy(&self) -> LfsPolicy {
+        self.lfs_policy
+    }
+
+    pub fn model_size_limit_mb(&self) -> f64 {
+        self.model_size_limit_mb
+    }
+
+    pub fn oversize_policy(&self) -> OversizePolicy {
+        self.oversize_policy
+    }
+
+    pub fn simplify_command(&self) -> Option<&SimplifyCommand> {
+        self.simplify_command.as_ref()
+    }
+
+    pub fn tag_store(&self) -> TagStore {
+        self.tag_store
+    }
+
+    pub fn existing_symbols(&self) -> AddPolicy {
+        self.existing_symbols
+    }
+
+    pub fn keep_local_properties(&self) -> bool {
+        self.keep_local_properties
+    }
+
+    pub fn clean_pin_text(&self) -> bool {
+        self.clean_pin_text
+    }
+
+    pub fn pad_policy(&self) -> PadPolicy {
+        self.pad_policy
+    }
+
+    pub fn cache_ttl_hours(&self) -> u64 {
+        self.cache_ttl_hours
+    }
+
+    pub fn copy_jobs(&self) -> usize {
+        self.copy_jobs
+    }
+
+    pub fn stats(&self) -> bool {
+        self.stats
+    }
+
+    pub fn temp_dir(&self) -> Option<&Path> {
+        self.temp_dir.as_deref()
+    }
+
+    pub fn journal(&self) -> Option<&ImportJournal> {
+        self.journal.as_ref()
+    }
+
+    pub fn output_path(&self, path: &Path) -> PathBuf {
+        let root = match &self.output_root {
+            Some(root) => root,
+            None => return path.to_path_buf(),
+        };
+        let mut staged = root.clone();
+        for component in path.components() {
+            if let Component::Normal(part) = component {
+                staged.push(part);
+            }
+        }
+        staged
+    }
+
+    pub fn existing_path(&self, path: &Path) -> PathBuf {
+        self.existing_paths
+            .get(path)
+            .cloned()
+            .unwrap_or_else(|| self.output_path(path))
+    }
 }
 
-#[derive(Debug, Clone)]
-pub struct ImportReport {
-    symbols_added: usize,
-    footprints_added: usize,
-    step_files_added: usize,
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StepNaming {
+    Original,
+    Sanitized,
+    Footprint,
 }
 
-impl ImportReport {
-    pub fn symbols_added(&self) -> usize {
-        self.symbols_added
+impl StepNaming {
+    pub fn all() -> &'static [StepNaming] {
+        &[
+            StepNaming::Original,
+            StepNaming::Sanitized,
+            StepNaming::Footprint,
+        ]
     }
 
-    pub fn footprints_added(&self) -> usize {
-        self.footprints_added
+    pub fn name(self) -> &'static str {
+        match self {
+            StepNaming::Original => "original",
+            StepNaming::Sanitized => "sanitized",
+            StepNaming::Footprint => "footprint",
+        }
     }
+}
 
-    pub fn step_files_added(&self) -> usize {
-        self.step_files_added
+impl fmt::Display for StepNaming {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
     }
 }
 
-#[derive(Debug)]
-pub enum ImportError {
-    Io(io::Error),
-    Symbol(KicadSymError),
-    Zip(zip::result::ZipError),
-    Walkdir(walkdir::Error),
-    InvalidSource(String),
-    MissingSymbols,
-    MissingFootprints,
-    Association(String),
+impl std::str::FromStr for StepNaming {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        StepNaming::all()
+            .iter()
+            .copied()
+            .find(|naming| naming.name().eq_ignore_ascii_case(value.trim()))
+            .ok_or_else(|| format!("unknown step naming: {}", value))
+    }
 }
 
-impl fmt::Display for ImportError {
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StepLayout {
+    Flat,
+    Component,
+}
+
+impl StepLayout {
+    pub fn all() -> &'static [StepLayout] {
+        &[StepLayout::Flat, StepLayout::Component]
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            StepLayout::Flat => "flat",
+            StepLayout::Component => "component",
+        }
+    }
+}
+
+impl fmt::Display for StepLayout {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl std::str::FromStr for StepLayout {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        StepLayout::all()
+            .iter()
+            .copied()
+            .find(|layout| layout.name().eq_ignore_ascii_case(value.trim()))
+            .ok_or_else(|| format!("unknown step layout: {}", value))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OversizePolicy {
+    Warn,
+    Skip,
+    Simplify,
+}
+
+impl OversizePolicy {
+    pub fn all() -> &'static [OversizePolicy] {
+        &[
+            OversizePolicy::Warn,
+            OversizePolicy::Skip,
+            OversizePolicy::Simplify,
+        ]
+    }
+
+    pub fn name(self) -> &'static str {
         match self {
-            ImportError::Io(err) => write!(f, "io error: {}", err),
-            ImportError::Symbol(err) => write!(f, "symbol parse error: {}", err),
-            ImportError::Zip(err) => write!(f, "zip error: {}", err),
-            ImportError::Walkdir(err) => write!(f, "walk error: {}", err),
-            ImportError::InvalidSource(msg) => write!(f, "invalid source: {}", msg),
-            ImportError::MissingSymbols => write!(f, "no symbols found in source"),
-            ImportError::MissingFootprints => write!(f, "no footprints found in source"),
-            ImportError::Association(msg) => write!(f, "association error: {}", msg),
+            OversizePolicy::Warn => "warn",
+            OversizePolicy::Skip => "skip",
+            OversizePolicy::Simplify => "simplify",
         }
     }
 }
 
-impl Error for ImportError {}
+impl fmt::Display for OversizePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
 
-impl From<io::Error> for ImportError {
-    fn from(value: io::Error) -> Self {
-        ImportError::Io(value)
+impl std::str::FromStr for OversizePolicy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        OversizePolicy::all()
+            .iter()
+            .copied()
+            .find(|policy| policy.name().eq_ignore_ascii_case(value.trim()))
+            .ok_or_else(|| format!("unknown oversize policy: {}", value))
     }
 }
 
-impl From<KicadSymError> for ImportError {
-    fn from(value: KicadSymError) -> Self {
-        ImportError::Symbol(value)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelOverride {
+    footprint: Option<String>,
+    path: PathBuf,
+}
+
+impl ModelOverride {
+    pub fn new(footprint: Option<&str>, path: PathBuf) -> Self {
+        Self {
+            footprint: footprint.map(str::to_string),
+            path,
+        }
+    }
+
+    pub fn footprint(&self) -> Option<&str> {
+        self.footprint.as_deref()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
     }
 }
 
-impl From<zip::result::ZipError> for ImportError {
-    fn from(value: zip::result::ZipError) -> Self {
-        ImportError::Zip(value)
+impl std::str::FromStr for ModelOverride {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (footprint, path) = match value.split_once('=') {
+            Some((footprint, path))
+                if !footprint.contains(['/', '\\']) && !Path::new(value.trim()).is_file() =>
+            {
+                (Some(footprint.trim()), path.trim())
+            }
+            _ => (None, value.trim()),
+        };
+        if path.is_empty() || footprint.is_some_and(str::is_empty) {
+            return Err(format!("invalid model: {}", value));
+        }
+        Ok(Self::new(footprint, PathBuf::from(path)))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolOverride {
+    symbol: Option<String>,
+    rename: Option<String>,
+    footprint: Option<String>,
+}
+
+impl SymbolOverride {
+    pub fn new(symbol: Option<&str>) -> Self {
+        Self {
+            symbol: symbol.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_rename(mut self, rename: Option<&str>) -> Self {
+        self.rename = rename.map(str::to_string);
+        self
+    }
+
+    pub fn with_footprint(mut self, footprint: Option<&str>) -> Self {
+        self.footprint = footprint.map(str::to_string);
+        self
+    }
+
+    pub fn symbol(&self) -> Option<&str> {
+        self.symbol.as_deref()
+    }
+
+    pub fn rename(&self) -> Option<&str> {
+        self.rename.as_deref()
+    }
+
+    pub fn footprint(&self) -> Option<&str> {
+        self.footprint.as_deref()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AssociationRule {
+    ExplicitProperty,
+    SingleFootprint,
+    NameMatch,
+    Fuzzy,
+    ExternalLibrary,
+    VendorConvention,
+}
+
+impl AssociationRule {
+    pub fn name(self) -> &'static str {
+        match self {
+            AssociationRule::ExplicitProperty => "explicit property",
+            AssociationRule::SingleFootprint => "single footprint",
+            AssociationRule::NameMatch => "name match",
+            AssociationRule::Fuzzy => "fuzzy",
+            AssociationRule::ExternalLibrary => "external library",
+            AssociationRule::VendorConvention => "vendor convention",
+        }
+    }
+}
+
+impl fmt::Display for AssociationRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SymbolAssociation {
+    symbol: String,
+    footprint: Option<String>,
+    rule: Option<AssociationRule>,
+    confidence: f64,
+    needs_review: bool,
+}
+
+impl SymbolAssociation {
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn footprint(&self) -> Option<&str> {
+        self.footprint.as_deref()
+    }
+
+    pub fn rule(&self) -> Option<AssociationRule> {
+        self.rule
+    }
+
+    pub fn confidence(&self) -> f64 {
+        self.confidence
+    }
+
+    pub fn needs_review(&self) -> bool {
+        self.needs_review
+    }
+}
+
+impl fmt::Display for SymbolAssociation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.footprint, self.rule) {
+            (Some(footprint), Some(rule)) => {
+                write!(
+                    f,
+                    "{} -> {} ({}, {:.0}%)",
+                    self.symbol,
+                    footprint,
+                    rule,
+                    self.confidence * 100.0
+                )
+            }
+            _ => write!(f, "{} -> no footprint", self.symbol),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportReport {
+    symbols_added: usize,
+    footprints_added: usize,
+    step_files_added: usize,
+    values_derived: usize,
+    pins_cleaned: usize,
+    pads_fixed: Vec<(String, PadProblem)>,
+    models_associated: usize,
+    footprint_models: Vec<(String, Option<String>)>,
+    copied_steps: Vec<String>,
+    simplifications: Vec<Simplification>,
+    warnings: Vec<String>,
+    associations: Vec<SymbolAssociation>,
+    kept_properties: Vec<(String, Vec<String>)>,
+    step_bytes_copied: u64,
+    step_copy_time: Duration,
+    stage_times: Vec<(&'static str, Duration)>,
+}
+
+impl ImportReport {
+    pub fn symbols_added(&self) -> usize {
+        self.symbols_added
+    }
+
+    pub fn footprints_added(&self) -> usize {
+        self.footprints_added
+    }
+
+    pub fn step_files_added(&self) -> usize {
+        self.step_files_added
+    }
+
+    pub fn values_derived(&self) -> usize {
+        self.values_derived
+    }
+
+    pub fn pins_cleaned(&self) -> usize {
+        self.pins_cleaned
+    }
+
+    pub fn pads_fixed(&self) -> &[(String, PadProblem)] {
+        &self.pads_fixed
+    }
+
+    pub fn models_associated(&self) -> usize {
+        self.models_associated
+    }
+
+    pub fn footprint_models(&self) -> &[(String, Option<String>)] {
+        &self.footprint_models
+    }
+
+    pub fn copied_steps(&self) -> &[String] {
+        &self.copied_steps
+    }
+
+    pub fn simplifications(&self) -> &[Simplification] {
+        &self.simplifications
+    }
+
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    pub fn associations(&self) -> &[SymbolAssociation] {
+        &self.associations
+    }
+
+    pub fn kept_properties(&self) -> &[(String, Vec<String>)] {
+        &self.kept_properties
+    }
+
+    pub fn step_bytes_copied(&self) -> u64 {
+        self.step_bytes_copied
+    }
+
+    pub fn step_copy_time(&self) -> Duration {
+        self.step_copy_time
+    }
+
+    pub fn stage_times(&self) -> &[(&'static str, Duration)] {
+        &self.stage_times
+    }
+
+    pub fn with_step_copy(mut self, bytes: u64, time: Duration) -> Self {
+        self.step_bytes_copied = bytes;
+        self.step_copy_time = time;
+        self
+    }
+
+    pub fn step_throughput_mb_s(&self) -> Option<f64> {
+        let seconds = self.step_copy_time.as_secs_f64();
+        (self.step_bytes_copied > 0 && seconds > 0.0)
+            .then(|| megabytes(self.step_bytes_copied) / seconds)
+    }
+
+    pub fn needs_review(&self) -> impl Iterator<Item = &SymbolAssociation> {
+        self.associations
+            .iter()
+            .filter(|association| association.needs_review)
+    }
+}
+
+#[derive(Debug)]
+pub enum ImportError {
+    Io(io::Error),
+    Symbol(KicadSymError),
+    Zip(zip::result::ZipError),
+    SevenZ(sevenz_rust::Error),
+    Walkdir(walkdir::Error),
+    InvalidSource(String),
+    MissingSymbols,
+    MissingFootprints,
+    Association(String),
+    Simplify(SimplifyError),
+    EasyEda(EasyEdaError),
+    Altium(AltiumError),
+    Legacy(LegacyError),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Io(err) => write!(f, "io error: {}", err),
+            ImportError::Symbol(err) => write!(f, "symbol parse error: {}", err),
+            ImportError::Zip(err) => write!(f, "zip error: {}", err),
+            ImportError::SevenZ(err) => write!(f, "7z error: {}", err),
+            ImportError::Walkdir(err) => write!(f, "walk error: {}", err),
+            ImportError::InvalidSource(msg) => write!(f, "invalid source: {}", msg),
+            ImportError::MissingSymbols => write!(f, "no symbols found in source"),
+            ImportError::MissingFootprints => write!(f, "no footprints found in source"),
+            ImportError::Association(msg) => write!(f, "association error: {}", msg),
+            ImportError::Simplify(err) => write!(f, "{}", err),
+            ImportError::EasyEda(err) => write!(f, "{}", err),
+            ImportError::Altium(err) => write!(f, "{}", err),
+            ImportError::Legacy(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Error for ImportError {}
+
+impl From<io::Error> for ImportError {
+    fn from(value: io::Error) -> Self {
+        ImportError::Io(value)
+    }
+}
+
+impl From<KicadSymError> for ImportError {
+    fn from(value: KicadSymError) -> Self {
+        ImportError::Symbol(value)
+    }
+}
+
+impl From<zip::result::ZipError> for ImportError {
+    fn from(value: zip::result::ZipError) -> Self {
+        ImportError::Zip(value)
+    }
+}
+
+impl From<sevenz_rust::Error> for ImportError {
+    fn from(value: sevenz_rust::Error) -> Self {
+        ImportError::SevenZ(value)
+    }
+}
+
+impl From<walkdir::Error> for ImportError {
+    fn from(value: walkdir::Error) -> Self {
+        ImportError::Walkdir(value)
+    }
+}
+
+impl From<SimplifyError> for ImportError {
+    fn from(value: SimplifyError) -> Self {
+        ImportError::Simplify(value)
+    }
+}
+
+impl From<EasyEdaError> for ImportError {
+    fn from(value: EasyEdaError) -> Self {
+        ImportError::EasyEda(value)
+    }
+}
+
+impl From<AltiumError> for ImportError {
+    fn from(value: AltiumError) -> Self {
+        ImportError::Altium(value)
+    }
+}
+
+impl From<LegacyError> for ImportError {
+    fn from(value: LegacyError) -> Self {
+        ImportError::Legacy(value)
+    }
+}
+
+pub fn import_source(
+    source: &Path,
+    config: &ImportConfig,
+    policy: AddPolicy,
+) -> Result<ImportReport, ImportError> {
+    import_sources(&[source.to_path_buf()], config, policy)
+}
+
+pub fn import_sources(
+    sources: &[PathBuf],
+    config: &ImportConfig,
+    policy: AddPolicy,
+) -> Result<ImportReport, ImportError> {
+    if sources.is_empty() {
+        return Err(ImportError::InvalidSource("no sources given".to_string()));
+    }
+    let mut clock = StageClock::start();
+    let contexts = sources
+        .iter()
+        .map(|source| SourceContext::open(source, config.temp_dir(), ArchiveFilter::new(config)))
+        .collect::<Result<Vec<_>, _>>()?;
+    clock.lap("extract");
+    let mut symbol_files = Vec::new();
+    let mut footprint_files = Vec::new();
+    let mut step_files = Vec::new();
+    let mut archive_footprint_libs = None;
+    let mut footprint_hints = HashMap::new();
+    let mut warnings = Vec::new();
+    let standalone_symbols = contexts.iter().all(|context| context.symbols_only);
+    for (source, context) in sources.iter().zip(&contexts) {
+        let layout = if config.from_kicad_plugin() {
+            SourceLayout::KicadProject
+        } else {
+            SourceLayout::detect(&context.root)
+        };
+        if layout == SourceLayout::KicadProject && !config.from_kicad_plugin() {
+            warnings.push(format!(
+                "{} is a KiCad project; importing only the libraries in its lib tables",
+                source.display()
+            ));
+        }
+        warnings.extend(context.warnings.iter().cloned());
+        let mut files = layout.files(&context.root)?;
+        if context.root == *source {
+            let rules = IgnoreRules::load(source, &mut warnings);
+            if !rules.is_empty() {
+                let skipped = rules.retain(&mut files.symbol_files)
+                    + rules.retain(&mut files.footprint_files)
+                    + rules.retain(&mut files.step_files);
+                if skipped > 0 {
+                    warnings.push(format!(
+                        "skipped {} file(s) in {} matched by {} or {}",
+                        skipped,
+                        source.display(),
+                        GIT_IGNORE_FILE,
+                        KCI_IGNORE_FILE
+                    ));
+                }
+            }
+        }
+        symbol_files.extend(files.symbol_files);
+        footprint_files.extend(files.footprint_files);
+        step_files.extend(files.step_files);
+        footprint_hints.extend(files.footprint_hints);
+        if let Some(libs) = files.footprint_libs {
+            archive_footprint_libs
+                .get_or_insert_with(BTreeSet::new)
+                .extend(libs);
+        }
+    }
+    for model in config.model_overrides() {
+        if !model.path().is_file() {
+            return Err(ImportError::InvalidSource(format!(
+                "model not found: {}",
+                model.path().display()
+            )));
+        }
+        step_files.push(model.path().to_path_buf());
+    }
+    if config.symbols_only() {
+        footprint_files.clear();
+        step_files.clear();
+    }
+    clock.lap("scan");
+    let simplify_dir = new_temp_dir(config.temp_dir())?;
+    let (step_contents, simplifications) =
+        apply_size_budget(&mut step_files, config, simplify_dir.path(), &mut warnings)?;
+    clock.lap("size budget");
+    if config.footprints_only() {
+        symbol_files.clear();
+        if footprint_files.is_empty() {
+            if step_files.is_empty() {
+                return Err(ImportError::MissingFootprints);
+            }
+            warnings.push("no footprints found in source; importing 3D models only".to_string());
+        }
+    } else if config.symbols_only() {
+        if symbol_files.is_empty() {
+            return Err(ImportError::MissingSymbols);
+        }
+    } else if symbol_files.is_empty() {
+        if !config.allow_partial() {
+            return Err(ImportError::MissingSymbols);
+        }
+        if !footprint_files.is_empty() {
+            warnings.push("no symbols found in source; importing footprints only".to_string());
+        } else if !step_files.is_empty() {
+            warnings.push(
+                "no symbols or footprints found in source; importing 3D models only".to_string(),
+            );
+        } else {
+            return Err(ImportError::MissingSymbols);
+        }
+    } else if footprint_files.is_empty() && !standalone_symbols {
+        if !config.allow_partial() {
+            return Err(ImportError::MissingFootprints);
+        }
+        warnings.push(
+            "no footprints found in source; symbols imported without footprint association"
+                .to_string(),
+        );
+    }
+
+    let mut symbols = Vec::new();
+    for path in &symbol_files {
+        let content = fs::read_to_string(path)?;
+        let lib = KicadSymbolLib::parse(&content)?;
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        for warning in lib.warnings() {
+            warnings.push(format!("{}: {}", file_name, warning));
+        }
+        for symbol in lib.symbols()? {
+            symbols.push(symbol);
+        }
+    }
+
+    let footprint_infos = collect_footprints(&footprint_files)?;
+    clock.lap("parse");
+    apply_symbol_overrides(&mut symbols, &footprint_infos, config.symbol_overrides())?;
+    let (mut symbols, associations) = if footprint_infos.is_empty() {
+        let associations = symbols
+            .iter()
+            .map(|symbol| SymbolAssociation {
+                symbol: symbol.name().to_string(),
+                footprint: None,
+                rule: None,
+                confidence: 0.0,
+                needs_review: false,
+            })
+            .collect();
+        (symbols, associations)
+    } else {
+        let footprint_lib_name = footprint_lib_name(config.footprint_lib())?;
+        associate_footprints(
+            symbols,
+            &footprint_infos,
+            &footprint_lib_name,
+            archive_footprint_libs.as_ref(),
+            &footprint_hints,
+            config.association_threshold(),
+        )?
+    };
+    let mut values_derived = 0;
+    let mut pins_cleaned = 0;
+    for symbol in symbols.iter_mut() {
+        if config.clean_pin_text() {
+            pins_cleaned += clean_pin_text(symbol);
+        }
+        if apply_value_rules(symbol, config.value_decoders()) {
+            values_derived += 1;
+        }
+        if let Some(choice) = config.description_language()
+            && apply_description_language(symbol, choice, config.kicad_version())
+                == DescriptionChoice::Unavailable
+        {
+            warnings.push(format!(
+                "{} has no {} description; keeping its translations",
+                symbol.name(),
+                choice.language()
+            ));
+        }
+    }
+
+    let mut models = associate_models(&footprint_infos, &step_files);
+    apply_model_overrides(&mut models, &footprint_infos, config.model_overrides())?;
+    let models_associated = models.iter().filter(|model| model.is_some()).count();
+    let mut step_names = step_file_names(
+        &step_files,
+        &footprint_infos,
+        &models,
+        config.step_naming(),
+        config.step_layout(),
+    )?;
+    clock.lap("associate");
+    let hash_started = Instant::now();
+    let resumed_steps: HashSet<&str> = config
+        .journal()
+        .map(|journal| journal.step_files().iter().map(String::as_str).collect())
+        .unwrap_or_default();
+    let completed_steps = resolve_step_collisions(
+        &step_files,
+        &step_contents,
+        &mut step_names,
+        &config.existing_path(config.step_dir()),
+        &resumed_steps,
+        config.copy_jobs(),
+        &mut warnings,
+    )?;
+    let hash_time = hash_started.elapsed();
+    clock.lap("hash models");
+
+    let kept_properties =
+        if policy == AddPolicy::ReplaceExisting && config.keep_local_properties() {
+            keep_local_properties(&config.output_path(config.symbol_lib()), &mut symbols)?
+        } else {
+            Vec::new()
+        };
+    let symbols_added = match config.journal().and_then(ImportJournal::symbols_added) {
+        Some(added) => added,
+        None if symbols.is_empty() => 0,
+        None => add_symbols_to_library(&config.output_path(config.symbol_lib()), symbols, policy)?,
+    };
+    clock.lap("write symbols");
+
+    let footprint_models = footprint_infos
+        .iter()
+        .zip(&models)
+        .map(|(footprint, model)| {
+            let model = model
+                .and_then(|step| step_names.get(step))
+                .map(|name| relative_model_path(config.step_dir(), name));
+            (footprint.name.clone(), model)
+        })
+        .collect();
+    let mut pads_fixed = Vec::new();
+    let footprints_added = if footprint_infos.is_empty() {
+        0
+    } else {
+        copy_footprints(
+            &footprint_infos,
+            &models,
+            &step_names,
+            config,
+            &mut pads_fixed,
+            &mut warnings,
+        )?
+    };
+    clock.lap("write footprints");
+    let copied_steps = step_files
+        .iter()
+        .filter_map(|step| step_names.get(step).cloned())
+        .collect();
+    let copy_started = Instant::now();
+    let (step_files_added, step_bytes_copied) = copy_steps(
+        &step_files,
+        &completed_steps,
+        &step_contents,
+        &step_names,
+        &config.output_path(config.step_dir()),
+        config.copy_jobs(),
+    )?;
+    let step_copy_time = hash_time + copy_started.elapsed();
+    clock.lap("copy models");
+
+    Ok(ImportReport {
+        symbols_added,
+        footprints_added,
+        step_files_added,
+        values_derived,
+        pins_cleaned,
+        pads_fixed,
+        models_associated,
+        footprint_models,
+        copied_steps,
+        simplifications,
+        warnings,
+        associations,
+        kept_properties,
+        step_bytes_copied,
+        step_copy_time,
+        stage_times: clock.stages,
+    })
+}
+
+struct StageClock {
+    started: Instant,
+    stages: Vec<(&'static str, Duration)>,
+}
+
+impl StageClock {
+    fn start() -> Self {
+        Self {
+            started: Instant::now(),
+            stages: Vec::new(),
+        }
+    }
+
+    fn lap(&mut self, stage: &'static str) {
+        let now = Instant::now();
+        self.stages.push((stage, now - self.started));
+        self.started = now;
+    }
+}
+
+fn apply_size_budget(
+    step_files: &mut Vec<PathBuf>,
+    config: &ImportConfig,
+    work_dir: &Path,
+    warnings: &mut Vec<String>,
+) -> Result<(HashMap<PathBuf, PathBuf>, Vec<Simplification>), ImportError> {
+    let mut contents = HashMap::new();
+    let mut simplifications = Vec::new();
+    let limit = config.model_size_limit_mb();
+    if limit <= 0.0 {
+        return Ok((contents, simplifications));
+    }
+    let mut kept = Vec::new();
+    for step in step_files.drain(..) {
+        let size_mb = megabytes(fs::metadata(&step)?.len());
+        let name = step
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let is_override = config
+            .model_overrides()
+            .iter()
+            .any(|model| model.path() == step);
+        let simplify = match config.oversize_policy() {
+            OversizePolicy::Simplify => config.simplify_command(),
+            _ => None,
+        };
+        if size_mb <= limit {
+            kept.push(step);
+        } else if let Some(command) = simplify {
+            let output = work_dir.join(contents.len().to_string()).join(&name);
+            let simplification = command.run(&step, &output)?;
+            let reduced_mb = megabytes(simplification.reduced_size());
+            if reduced_mb > limit {
+                warnings.push(format!(
+                    "model {} is still {:.1} MB after simplification, above the {} MB budget",
+                    name, reduced_mb, limit
+                ));
+            }
+            simplifications.push(simplification);
+            contents.insert(step.clone(), output);
+            kept.push(step);
+        } else if config.oversize_policy() == OversizePolicy::Skip && !is_override {
+            warnings.push(format!(
+                "skipped model {} ({:.1} MB, budget {} MB)",
+                name, size_mb, limit
+            ));
+        } else {
+            warnings.push(format!(
+                "model {} is {:.1} MB, above the {} MB budget",
+                name, size_mb, limit
+            ));
+            kept.push(step);
+        }
+    }
+    *step_files = kept;
+    Ok((contents, simplifications))
+}
+
+fn megabytes(bytes: u64) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0)
+}
+
+#[derive(Debug, Clone)]
+pub struct SourceInspection {
+    vendor: Vendor,
+    symbol_files: Vec<PathBuf>,
+    footprint_files: Vec<PathBuf>,
+    step_files: Vec<PathBuf>,
+    other_files: Vec<PathBuf>,
+    symbols: Vec<String>,
+    footprints: Vec<String>,
+    symbol_summaries: Vec<SymbolSummary>,
+    footprint_summaries: Vec<FootprintSummary>,
+    problems: Vec<String>,
+}
+
+impl SourceInspection {
+    pub fn vendor(&self) -> Vendor {
+        self.vendor
+    }
+
+    pub fn symbol_files(&self) -> &[PathBuf] {
+        &self.symbol_files
+    }
+
+    pub fn footprint_files(&self) -> &[PathBuf] {
+        &self.footprint_files
+    }
+
+    pub fn step_files(&self) -> &[PathBuf] {
+        &self.step_files
+    }
+
+    pub fn other_files(&self) -> &[PathBuf] {
+        &self.other_files
+    }
+
+    pub fn symbols(&self) -> &[String] {
+        &self.symbols
+    }
+
+    pub fn footprints(&self) -> &[String] {
+        &self.footprints
+    }
+
+    pub fn symbol_summaries(&self) -> &[SymbolSummary] {
+        &self.symbol_summaries
+    }
+
+    pub fn footprint_summaries(&self) -> &[FootprintSummary] {
+        &self.footprint_summaries
+    }
+
+    pub fn problems(&self) -> &[String] {
+        &self.problems
+    }
+}
+
+pub fn inspect_source(source: &Path) -> Result<SourceInspection, ImportError> {
+    let context = SourceContext::open(source, None, ArchiveFilter::All)?;
+    let root = &context.root;
+    let vendor = detect_vendor(root);
+    let mut symbol_files = Vec::new();
+    let mut footprint_files = Vec::new();
+    let mut step_files = Vec::new();
+    let mut other_files = Vec::new();
+    for entry in WalkDir::new(root) {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let path = entry.path();
+        let bucket = if has_extension(path, "kicad_sym") {
+            &mut symbol_files
+        } else if has_extension(path, "kicad_mod") {
+            &mut footprint_files
+        } else if has_extension(path, "step") || has_extension(path, "stp") {
+            &mut step_files
+        } else {
+            &mut other_files
+        };
+        bucket.push(path.to_path_buf());
+    }
+
+    let mut problems = context.warnings.clone();
+    let layout = SourceLayout::detect(root);
+    let mut footprint_hints = HashMap::new();
+    if layout != SourceLayout::Loose {
+        match layout.files(root) {
+            Ok(selected) => {
+                footprint_hints = selected.footprint_hints;
+                for (bucket, keep) in [
+                    (&mut symbol_files, &selected.symbol_files),
+                    (&mut footprint_files, &selected.footprint_files),
+                    (&mut step_files, &selected.step_files),
+                ] {
+                    let (kept, skipped): (Vec<PathBuf>, Vec<PathBuf>) =
+                        bucket.drain(..).partition(|path| keep.contains(path));
+                    *bucket = kept;
+                    other_files.extend(skipped);
+                }
+            }
+            Err(err) => problems.push(err.to_string()),
+        }
+    }
+    if symbol_files.is_empty() {
+        problems.push("no symbols found".to_string());
+    }
+    if footprint_files.is_empty() {
+        problems.push("no footprints found".to_string());
+    }
+    if step_files.is_empty() {
+        problems.push("no 3D models found".to_string());
+    }
+
+    let mut symbols = Vec::new();
+    for path in &symbol_files {
+        let parsed = fs::read_to_string(path)
+            .map_err(ImportError::from)
+            .and_then(|content| Ok(KicadSymbolLib::parse(&content)?))
+            .and_then(|lib| Ok((lib.symbols()?, lib.warnings().to_vec())));
+        match parsed {
+            Ok((found, warnings)) => {
+                symbols.extend(found);
+                for warning in warnings {
+                    problems.push(format!("{}: {}", relative_display(path, root), warning));
+                }
+            }
+            Err(err) => problems.push(format!(
+                "cannot read {}: {}",
+                relative_display(path, root),
+                err
+            )),
+        }
+    }
+    let footprint_infos = collect_footprints(&footprint_files)?;
+    let mut footprint_summaries = Vec::new();
+    for footprint in &footprint_infos {
+        let parsed = fs::read_to_string(&footprint.path)
+            .map_err(ImportError::from)
+            .and_then(|content| Ok(FootprintSummary::parse(&footprint.name, &content)?));
+        match parsed {
+            Ok(summary) => footprint_summaries.push(summary),
+            Err(err) => problems.push(format!(
+                "cannot read {}: {}",
+                relative_display(&footprint.path, root),
+                err
+            )),
+        }
+    }
+
+    let mut seen = HashMap::new();
+    for symbol in &symbols {
+        *seen.entry(symbol.name()).or_insert(0) += 1;
+    }
+    let mut duplicates: Vec<_> = seen
+        .into_iter()
+        .filter(|(_, count)| *count > 1)
+        .map(|(name, _)| name)
+        .collect();
+    duplicates.sort();
+    for name in duplicates {
+        problems.push(format!("symbol {} is defined more than once", name));
+    }
+
+    if !footprint_infos.is_empty() {
+        let mut footprints_by_name = HashMap::new();
+        for footprint in &footprint_infos {
+            footprints_by_name.insert(footprint.name.as_str(), footprint);
+        }
+        for symbol in &symbols {
+            let hint = footprint_hints
+                .get(&symbol.name().to_lowercase())
+                .map(String::as_str);
+            let selected = select_footprint_for_symbol(
+                symbol,
+                &footprints_by_name,
+                footprint_infos.len(),
+                hint,
+            );
+            match selected {
+                Ok((footprint, rule, confidence))
+                    if confidence < DEFAULT_ASSOCIATION_THRESHOLD =>
+                {
+                    problems.push(format!(
+                        "symbol {} would get footprint {} by {} ({:.0}%)",
+                        symbol.name(),
+                        footprint,
+                        rule,
+                        confidence * 100.0
+                    ))
+                }
+                Ok(_) => {}
+                Err(_) => problems.push(format!(
+                    "no footprint can be chosen for symbol {}",
+                    symbol.name()
+                )),
+            }
+        }
+    }
+
+    if !step_files.is_empty() {
+        let models = associate_models(&footprint_infos, &step_files);
+        for (footprint, model) in footprint_infos.iter().zip(&models) {
+            if model.is_none() {
+                problems.push(format!("footprint {} has no 3D model", footprint.name));
+            }
+        }
+    }
+
+    let relative = |paths: Vec<PathBuf>| -> Vec<PathBuf> {
+        paths
+            .into_iter()
+            .map(|path| path.strip_prefix(root).map(Path::to_path_buf).unwrap_or(path))
+            .collect()
+    };
+    Ok(SourceInspection {
+        vendor,
+        symbols: symbols.iter().map(|symbol| symbol.name().to_string()).collect(),
+        footprints: footprint_infos
+            .iter()
+            .map(|footprint| footprint.name.clone())
+            .collect(),
+        symbol_summaries: symbols.iter().map(SymbolSummary::from_symbol).collect(),
+        footprint_summaries,
+        symbol_files: relative(symbol_files),
+        footprint_files: relative(footprint_files),
+        step_files: relative(step_files),
+        other_files: relative(other_files),
+        problems,
+    })
+}
+
+fn relative_display(path: &Path, root: &Path) -> String {
+    path.strip_prefix(root).unwrap_or(path).display().to_string()
+}
+
+pub fn add_symbols_to_library(
+    path: &Path,
+    symbols: Vec<Symbol>,
+    policy: AddPolicy,
+) -> Result<usize, ImportError> {
+    let count = symbols.len();
+    let mut target_lib = load_or_create_symbol_lib(path)?;
+    for symbol in symbols {
+        target_lib.add_symbol(symbol, policy)?;
+    }
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, target_lib.to_string_pretty())?;
+    Ok(count)
+}
+
+fn keep_local_properties(
+    path: &Path,
+    symbols: &mut [Symbol],
+) -> Result<Vec<(String, Vec<String>)>, ImportError> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let existing = load_or_create_symbol_lib(path)?;
+    let mut kept = Vec::new();
+    for symbol in symbols.iter_mut() {
+        let Some(previous) = existing.symbol(symbol.name()) else {
+            continue;
+        };
+        let names = symbol.keep_properties_from(previous, TAG_PROPERTY_PREFIX);
+        if !names.is_empty() {
+            kept.push((symbol.name().to_string(), names));
+        }
+    }
+    Ok(kept)
+}
+
+fn load_or_create_symbol_lib(path: &Path) -> Result<KicadSymbolLib, ImportError> {
+    if path.exists() {
+        let content = fs::read_to_string(path)?;
+        Ok(KicadSymbolLib::parse(&content)?)
+    } else {
+        let content = "(kicad_symbol_lib (version 20231120))";
+        Ok(KicadSymbolLib::parse(content)?)
+    }
+}
+
+struct SourceContext {
+    root: PathBuf,
+    warnings: Vec<String>,
+    symbols_only: bool,
+    _temp: Option<TempDir>,
+}
+
+impl SourceContext {
+    fn open(
+        path: &Path,
+        temp_dir: Option<&Path>,
+        filter: ArchiveFilter,
+    ) -> Result<Self, ImportError> {
+        let context = if path == Path::new(STDIN_SOURCE) {
+            let mut bytes = Vec::new();
+            io::stdin().lock().read_to_end(&mut bytes)?;
+            let temp = new_temp_dir(temp_dir)?;
+            extract_zip(Cursor::new(bytes), temp.path(), filter)?;
+            Self {
+                root: temp.path().to_path_buf(),
+                warnings: Vec::new(),
+                symbols_only: false,
+                _temp: Some(temp),
+            }
+        } else if path.is_dir() {
+            Self {
+                root: path.to_path_buf(),
+                warnings: Vec::new(),
+                symbols_only: false,
+                _temp: None,
+            }
+        } else if let Some(kind) = ArchiveKind::detect(path) {
+            let temp = new_temp_dir(temp_dir)?;
+            kind.extract(path, temp.path(), filter)?;
+            Self {
+                root: temp.path().to_path_buf(),
+                warnings: Vec::new(),
+                symbols_only: false,
+                _temp: Some(temp),
+            }
+        } else if has_extension(path, "json") {
+            return Self::convert_easyeda(&[path.to_path_buf()], temp_dir);
+        } else if has_extension(path, "kicad_sym") && path.is_file() {
+            let temp = new_temp_dir(temp_dir)?;
+            fs::copy(path, temp.path().join(path.file_name().unwrap_or_default()))?;
+            Self {
+                root: temp.path().to_path_buf(),
+                warnings: Vec::new(),
+                symbols_only: true,
+                _temp: Some(temp),
+            }
+        } else {
+            return Err(ImportError::InvalidSource(format!(
+                "expected directory, .zip, .7z, .tar, .tar.gz/.tgz, .kicad_sym, EasyEDA .json or - for a zip on stdin: {}",
+                path.display()
+            )));
+        };
+        let components = find_components(&context.root);
+        if !components.is_empty() {
+            return Self::convert_easyeda(&components, temp_dir);
+        }
+        let context = context.upgrade_legacy(temp_dir)?;
+        let libraries = altium::find_libraries(&context.root);
+        if libraries.is_empty()
+            || !find_files(&context.root, "kicad_sym")?.is_empty()
+            || !find_files(&context.root, "kicad_mod")?.is_empty()
+        {
+            return Ok(context);
+        }
+        Self::convert_altium(&context.root, &libraries, temp_dir)
+    }
+
+    fn convert_easyeda(
+        components: &[PathBuf],
+        temp_dir: Option<&Path>,
+    ) -> Result<Self, ImportError> {
+        let temp = new_temp_dir(temp_dir)?;
+        for component in components {
+            convert_component(component, temp.path())?;
+        }
+        Ok(Self {
+            root: temp.path().to_path_buf(),
+            warnings: Vec::new(),
+            symbols_only: false,
+            _temp: Some(temp),
+        })
+    }
+
+    fn upgrade_legacy(self, temp_dir: Option<&Path>) -> Result<Self, ImportError> {
+        let symbol_libs = if find_files(&self.root, "kicad_sym")?.is_empty() {
+            legacy::find_symbol_libraries(&self.root)
+        } else {
+            Vec::new()
+        };
+        let footprint_libs = if find_files(&self.root, "kicad_mod")?.is_empty() {
+            legacy::find_footprint_libraries(&self.root)
+        } else {
+            Vec::new()
+        };
+        if symbol_libs.is_empty() && footprint_libs.is_empty() {
+            return Ok(self);
+        }
+
+        let temp = new_temp_dir(temp_dir)?;
+        copy_tree(&self.root, temp.path())?;
+        let target = |path: &Path| temp.path().join(path.strip_prefix(&self.root).unwrap_or(path));
+        let mut warnings = self.warnings.clone();
+        for path in &symbol_libs {
+            let (lib, lib_warnings) = legacy::convert_symbol_library(path)?;
+            fs::write(target(path).with_extension("kicad_sym"), lib.to_string_pretty())?;
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+            for warning in lib_warnings {
+                warnings.push(format!("{}: {}", file_name, warning));
+            }
+        }
+        for path in &footprint_libs {
+            let (footprints, lib_warnings) = legacy::convert_footprint_library(path)?;
+            let dir = target(path).with_extension("pretty");
+            fs::create_dir_all(&dir)?;
+            for (name, content) in footprints {
+                fs::write(dir.join(format!("{}.kicad_mod", name)), content)?;
+            }
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+            for warning in lib_warnings {
+                warnings.push(format!("{}: {}", file_name, warning));
+            }
+        }
+        Ok(Self {
+            root: temp.path().to_path_buf(),
+            warnings,
+            symbols_only: false,
+            _temp: Some(temp),
+        })
+    }
+
+    fn convert_altium(
+        root: &Path,
+        libraries: &[PathBuf],
+        temp_dir: Option<&Path>,
+    ) -> Result<Self, ImportError> {
+        let temp = new_temp_dir(temp_dir)?;
+        let conversion = altium::convert_libraries(libraries, temp.path())?;
+        for step in find_step_files(root)? {
+            if let Some(name) = step.file_name() {
+                fs::copy(&step, temp.path().join(name))?;
+            }
+        }
+        Ok(Self {
+            root: temp.path().to_path_buf(),
+            warnings: conversion.warnings().to_vec(),
+            symbols_only: false,
+            _temp: Some(temp),
+        })
+    }
+}
+
+pub fn new_temp_dir(temp_dir: Option<&Path>) -> io::Result<TempDir> {
+    let mut builder = tempfile::Builder::new();
+    builder.prefix(TEMP_DIR_PREFIX);
+    match temp_dir {
+        Some(dir) => {
+            fs::create_dir_all(dir)?;
+            builder.tempdir_in(dir)
+        }
+        None => builder.tempdir(),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SourceLayout {
+    Loose,
+    SnapEda,
+    UltraLibrarian,
+    SamacSys,
+    KicadProject,
+}
+
+struct SourceFiles {
+    symbol_files: Vec<PathBuf>,
+    footprint_files: Vec<PathBuf>,
+    step_files: Vec<PathBuf>,
+    footprint_libs: Option<BTreeSet<String>>,
+    footprint_hints: HashMap<String, String>,
+}
+
+impl SourceLayout {
+    fn detect(root: &Path) -> Self {
+        match detect_vendor(root) {
+            Vendor::SnapEda => SourceLayout::SnapEda,
+            Vendor::UltraLibrarian => SourceLayout::UltraLibrarian,
+            Vendor::SamacSys => SourceLayout::SamacSys,
+            Vendor::KicadProject => SourceLayout::KicadProject,
+            _ => SourceLayout::Loose,
+        }
+    }
+
+    fn files(self, root: &Path) -> Result<SourceFiles, ImportError> {
+        match self {
+            SourceLayout::Loose => Ok(SourceFiles {
+                symbol_files: find_files(root, "kicad_sym")?,
+                footprint_files: find_files(root, "kicad_mod")?,
+                step_files: find_step_files(root)?,
+                footprint_libs: None,
+                footprint_hints: HashMap::new(),
+            }),
+            SourceLayout::SnapEda => snapeda_files(root),
+            SourceLayout::UltraLibrarian => ultra_librarian_files(root),
+            SourceLayout::SamacSys => samacsys_files(root),
+            SourceLayout::KicadProject => kicad_project_files(root),
+        }
     }
 }
 
-impl From<walkdir::Error> for ImportError {
-    fn from(value: walkdir::Error) -> Self {
-        ImportError::Walkdir(value)
+fn snapeda_files(root: &Path) -> Result<SourceFiles, ImportError> {
+    let mut files = SourceFiles {
+        symbol_files: Vec::new(),
+        footprint_files: Vec::new(),
+        step_files: Vec::new(),
+        footprint_libs: None,
+        footprint_hints: HashMap::new(),
+    };
+    let mut seen = BTreeSet::new();
+    let entries = WalkDir::new(root)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            let name = entry.file_name().to_string_lossy();
+            name != "__MACOSX" && !name.starts_with("._")
+        });
+    for entry in entries {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let path = entry.path();
+        let bucket = if has_extension(path, "kicad_sym") {
+            &mut files.symbol_files
+        } else if has_extension(path, "kicad_mod") {
+            &mut files.footprint_files
+        } else if has_extension(path, "step") || has_extension(path, "stp") {
+            &mut files.step_files
+        } else {
+            continue;
+        };
+        if seen.insert(entry.file_name().to_string_lossy().to_lowercase()) {
+            bucket.push(path.to_path_buf());
+        }
     }
+    Ok(files)
 }
 
-pub fn import_source(
-    source: &Path,
-    config: &ImportConfig,
-    policy: AddPolicy,
-) -> Result<ImportReport, ImportError> {
-    let source_ctx = SourceContext::open(source)?;
-    let symbol_files = find_files(&source_ctx.root, "kicad_sym")?;
-    if symbol_files.is_empty() {
-        return Err(ImportError::MissingSymbols);
-    }
-    let footprint_files = find_files(&source_ctx.root, "kicad_mod")?;
-    if footprint_files.is_empty() {
-        return Err(ImportError::MissingFootprints);
+fn ultra_librarian_files(root: &Path) -> Result<SourceFiles, ImportError> {
+    let mut modern: Option<(u32, PathBuf)> = None;
+    for entry in WalkDir::new(root).min_depth(1) {
+        let entry = entry?;
+        if !entry.file_type().is_dir() {
+            continue;
+        }
+        let name: String = entry
+            .file_name()
+            .to_string_lossy()
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .collect::<String>()
+            .to_ascii_lowercase();
+        let Some(version) = name
+            .strip_prefix("kicadv")
+            .and_then(|version| version.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        if modern.as_ref().is_none_or(|(best, _)| version > *best) {
+            modern = Some((version, entry.into_path()));
+        }
     }
-    let step_files = find_step_files(&source_ctx.root)?;
+    let Some((_, dir)) = modern else {
+        return SourceLayout::Loose.files(root);
+    };
+    Ok(SourceFiles {
+        symbol_files: find_files(&dir, "kicad_sym")?,
+        footprint_files: find_files(&dir, "kicad_mod")?,
+        step_files: find_step_files(root)?,
+        footprint_libs: None,
+        footprint_hints: HashMap::new(),
+    })
+}
 
-    let mut symbols = Vec::new();
-    for path in &symbol_files {
-        let content = fs::read_to_string(path)?;
-        let lib = KicadSymbolLib::parse(&content)?;
-        for symbol in lib.symbols()? {
-            symbols.push(symbol);
+fn samacsys_files(root: &Path) -> Result<SourceFiles, ImportError> {
+    let mut files = SourceLayout::Loose.files(root)?;
+    for entry in WalkDir::new(root).min_depth(1) {
+        let entry = entry?;
+        if !entry.file_type().is_dir() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy();
+        let Some(part) = name
+            .get(..4)
+            .filter(|prefix| prefix.eq_ignore_ascii_case("lib_"))
+            .map(|_| &name[4..])
+        else {
+            continue;
+        };
+        let footprints = find_files(entry.path(), "kicad_mod")?;
+        if let [footprint] = footprints.as_slice()
+            && let Some(stem) = footprint.file_stem()
+        {
+            files.footprint_hints.insert(
+                part.to_lowercase(),
+                stem.to_string_lossy().to_string(),
+            );
         }
     }
+    Ok(files)
+}
 
-    let footprint_infos = collect_footprints(&footprint_files)?;
-    let footprint_lib_name = footprint_lib_name(config.footprint_lib())?;
-    let symbols = associate_footprints(symbols, &footprint_infos, &footprint_lib_name)?;
-
-    let symbols_added = symbols.len();
-    let mut target_lib = load_or_create_symbol_lib(config.symbol_lib())?;
-    for symbol in symbols {
-        target_lib.add_symbol(symbol, policy)?;
-    }
-    if let Some(parent) = config.symbol_lib().parent() {
-        if !parent.as_os_str().is_empty() {
-            fs::create_dir_all(parent)?;
+fn kicad_project_files(root: &Path) -> Result<SourceFiles, ImportError> {
+    let project_root = kicad_project_root(root)?.ok_or_else(|| {
+        ImportError::InvalidSource("no KiCad project or library table found".to_string())
+    })?;
+    let mut files = SourceFiles {
+        symbol_files: Vec::new(),
+        footprint_files: Vec::new(),
+        step_files: find_step_files(root)?,
+        footprint_libs: Some(BTreeSet::new()),
+        footprint_hints: HashMap::new(),
+    };
+    for kind in [TableKind::Symbol, TableKind::Footprint] {
+        let entries = project_table_entries(&project_root, kind)
+            .map_err(|err| ImportError::InvalidSource(err.to_string()))?;
+        for entry in entries {
+            let Some(path) = entry.path().filter(|path| path.starts_with(root)) else {
+                continue;
+            };
+            match kind {
+                TableKind::Symbol if path.is_file() => {
+                    files.symbol_files.push(path.to_path_buf());
+                }
+                TableKind::Footprint if path.is_dir() => {
+                    files.footprint_files.extend(find_files(path, "kicad_mod")?);
+                    if let Some(libs) = &mut files.footprint_libs {
+                        libs.insert(entry.name().to_string());
+                    }
+                }
+                _ => {}
+            }
         }
     }
-    fs::write(config.symbol_lib(), target_lib.to_string_pretty())?;
-
-    let footprints_added = copy_footprints(&footprint_infos, config.footprint_lib())?;
-    let step_files_added = copy_steps(&step_files, config.step_dir())?;
-
-    Ok(ImportReport {
-        symbols_added,
-        footprints_added,
-        step_files_added,
-    })
+    Ok(files)
 }
 
-fn load_or_create_symbol_lib(path: &Path) -> Result<KicadSymbolLib, ImportError> {
-    if path.exists() {
-        let content = fs::read_to_string(path)?;
-        Ok(KicadSymbolLib::parse(&content)?)
-    } else {
-        let content = "(kicad_symbol_lib (version 20231120))";
-        Ok(KicadSymbolLib::parse(content)?)
+fn kicad_project_root(root: &Path) -> Result<Option<PathBuf>, ImportError> {
+    let mut found: Option<(usize, PathBuf)> = None;
+    for entry in WalkDir::new(root).sort_by_file_name() {
+        let entry = entry?;
+        let path = entry.path();
+        let is_marker = entry.file_type().is_file()
+            && (has_extension(path, "kicad_pro")
+                || [TableKind::Symbol, TableKind::Footprint]
+                    .iter()
+                    .any(|kind| entry.file_name() == kind.file_name()));
+        if is_marker
+            && found.as_ref().is_none_or(|(depth, _)| entry.depth() < *depth)
+            && let Some(parent) = path.parent()
+        {
+            found = Some((entry.depth(), parent.to_path_buf()));
+        }
     }
+    Ok(found.map(|(_, path)| path))
 }
 
-struct SourceContext {
-    root: PathBuf,
-    _temp: Option<TempDir>,
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ArchiveFilter {
+    All,
+    SymbolsOnly,
+    FootprintsOnly,
 }
 
-impl SourceContext {
-    fn open(path: &Path) -> Result<Self, ImportError> {
-        if path.is_dir() {
-            return Ok(Self {
-                root: path.to_path_buf(),
-                _temp: None,
-            });
+impl ArchiveFilter {
+    fn new(config: &ImportConfig) -> Self {
+        if config.symbols_only() {
+            ArchiveFilter::SymbolsOnly
+        } else if config.footprints_only() {
+            ArchiveFilter::FootprintsOnly
+        } else {
+            ArchiveFilter::All
         }
-        if is_zip(path) {
-            let temp = TempDir::new()?;
-            extract_zip(path, temp.path())?;
-            return Ok(Self {
-                root: temp.path().to_path_buf(),
-                _temp: Some(temp),
-            });
+    }
+
+    fn skips_dirs(self) -> bool {
+        self != ArchiveFilter::All
+    }
+
+    fn skips(self, path: &Path) -> bool {
+        match self {
+            ArchiveFilter::All => false,
+            ArchiveFilter::SymbolsOnly => {
+                is_model_file(path)
+                    || ["kicad_mod", "mod"]
+                        .iter()
+                        .any(|ext| has_extension(path, ext))
+            }
+            ArchiveFilter::FootprintsOnly => ["kicad_sym", "lib", "dcm"]
+                .iter()
+                .any(|ext| has_extension(path, ext)),
         }
-        Err(ImportError::InvalidSource(format!(
-            "expected directory or .zip: {}",
-            path.display()
-        )))
     }
 }
 
-fn extract_zip(zip_path: &Path, dest: &Path) -> Result<(), ImportError> {
-    let file = fs::File::open(zip_path)?;
-    let mut archive = ZipArchive::new(file)?;
+fn extract_zip(
+    reader: impl Read + Seek,
+    dest: &Path,
+    filter: ArchiveFilter,
+) -> Result<(), ImportError> {
+    let mut archive = ZipArchive::new(reader)?;
     for i in 0..archive.len() {
         let mut entry = archive.by_index(i)?;
         let out_path = match entry.enclosed_name() {
@@ -211,7 +2024,12 @@ fn extract_zip(zip_path: &Path, dest: &Path) -> Result<(), ImportError> {
             None => continue,
         };
         if entry.is_dir() {
-            fs::create_dir_all(&out_path)?;
+            if !filter.skips_dirs() {
+                fs::create_dir_all(&out_path)?;
+            }
+            continue;
+        }
+        if filter.skips(&out_path) {
             continue;
         }
         if let Some(parent) = out_path.parent() {
@@ -223,6 +2041,41 @@ fn extract_zip(zip_path: &Path, dest: &Path) -> Result<(), ImportError> {
     Ok(())
 }
 
+fn extract_tar(reader: impl Read, dest: &Path, filter: ArchiveFilter) -> Result<(), ImportError> {
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let kind = entry.header().entry_type();
+        if !(kind.is_file() || kind.is_dir()) {
+            continue;
+        }
+        if (kind.is_dir() && filter.skips_dirs()) || (kind.is_file() && filter.skips(&entry.path()?))
+        {
+            continue;
+        }
+        entry.unpack_in(dest)?;
+    }
+    Ok(())
+}
+
+fn extract_7z(path: &Path, dest: &Path, filter: ArchiveFilter) -> Result<(), ImportError> {
+    sevenz_rust::decompress_file_with_extract_fn(path, dest, |entry, reader, out_path| {
+        let enclosed = Path::new(entry.name())
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+        let skipped = if entry.is_directory() {
+            filter.skips_dirs()
+        } else {
+            filter.skips(Path::new(entry.name()))
+        };
+        if !enclosed || skipped {
+            return Ok(true);
+        }
+        sevenz_rust::default_entry_extract_fn(entry, reader, out_path)
+    })?;
+    Ok(())
+}
+
 fn find_files(root: &Path, extension: &str) -> Result<Vec<PathBuf>, ImportError> {
     let mut out = Vec::new();
     for entry in WalkDir::new(root) {
@@ -238,6 +2091,19 @@ fn find_files(root: &Path, extension: &str) -> Result<Vec<PathBuf>, ImportError>
     Ok(out)
 }
 
+fn copy_tree(from: &Path, to: &Path) -> Result<(), ImportError> {
+    for entry in WalkDir::new(from).min_depth(1) {
+        let entry = entry?;
+        let target = to.join(entry.path().strip_prefix(from).unwrap_or(entry.path()));
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target)?;
+        } else if entry.file_type().is_file() {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
 fn find_step_files(root: &Path) -> Result<Vec<PathBuf>, ImportError> {
     let mut out = Vec::new();
     for entry in WalkDir::new(root) {
@@ -253,6 +2119,12 @@ fn find_step_files(root: &Path) -> Result<Vec<PathBuf>, ImportError> {
     Ok(out)
 }
 
+fn is_model_file(path: &Path) -> bool {
+    ["step", "stp", "wrl"]
+        .iter()
+        .any(|ext| has_extension(path, ext))
+}
+
 fn has_extension(path: &Path, ext: &str) -> bool {
     path.extension()
         .and_then(|value| value.to_str())
@@ -260,8 +2132,38 @@ fn has_extension(path: &Path, ext: &str) -> bool {
         .unwrap_or(false)
 }
 
-fn is_zip(path: &Path) -> bool {
-    has_extension(path, "zip")
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ArchiveKind {
+    Zip,
+    Tar,
+    TarGz,
+    SevenZ,
+}
+
+impl ArchiveKind {
+    fn detect(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_string_lossy().to_ascii_lowercase();
+        if name.ends_with(".zip") {
+            Some(ArchiveKind::Zip)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(ArchiveKind::TarGz)
+        } else if name.ends_with(".tar") {
+            Some(ArchiveKind::Tar)
+        } else if name.ends_with(".7z") {
+            Some(ArchiveKind::SevenZ)
+        } else {
+            None
+        }
+    }
+
+    fn extract(self, path: &Path, dest: &Path, filter: ArchiveFilter) -> Result<(), ImportError> {
+        match self {
+            ArchiveKind::Zip => extract_zip(fs::File::open(path)?, dest, filter),
+            ArchiveKind::Tar => extract_tar(fs::File::open(path)?, dest, filter),
+            ArchiveKind::TarGz => extract_tar(GzDecoder::new(fs::File::open(path)?), dest, filter),
+            ArchiveKind::SevenZ => extract_7z(path, dest, filter),
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -295,45 +2197,84 @@ fn associate_footprints(
     symbols: Vec<Symbol>,
     footprints: &[FootprintInfo],
     footprint_lib_name: &str,
-) -> Result<Vec<Symbol>, ImportError> {
+    archive_libs: Option<&BTreeSet<String>>,
+    hints: &HashMap<String, String>,
+    threshold: f64,
+) -> Result<(Vec<Symbol>, Vec<SymbolAssociation>), ImportError> {
     let mut out = Vec::with_capacity(symbols.len());
+    let mut associations = Vec::with_capacity(symbols.len());
     let mut footprints_by_name = HashMap::new();
     for footprint in footprints {
         footprints_by_name.insert(footprint.name.as_str(), footprint);
     }
 
     for mut symbol in symbols {
-        let footprint_name =
-            select_footprint_for_symbol(&symbol, &footprints_by_name, footprints.len())?;
+        if let Some(archive_libs) = archive_libs
+            && let Some(value) = symbol.property_value("Footprint")
+            && let Some((library, _)) = value.trim().split_once(':')
+            && !archive_libs.contains(library)
+        {
+            associations.push(SymbolAssociation {
+                symbol: symbol.name().to_string(),
+                footprint: Some(value.trim().to_string()),
+                rule: Some(AssociationRule::ExternalLibrary),
+                confidence: 1.0,
+                needs_review: false,
+            });
+            out.push(symbol);
+            continue;
+        }
+        let hint = hints.get(&symbol.name().to_lowercase()).map(String::as_str);
+        let (footprint_name, rule, confidence) =
+            select_footprint_for_symbol(&symbol, &footprints_by_name, footprints.len(), hint)?;
         let value = format!("{}:{}", footprint_lib_name, footprint_name);
         symbol.set_or_add_property("Footprint", &value);
+        associations.push(SymbolAssociation {
+            symbol: symbol.name().to_string(),
+            footprint: Some(value),
+            rule: Some(rule),
+            confidence,
+            needs_review: confidence < threshold,
+        });
         out.push(symbol);
     }
-    Ok(out)
+    Ok((out, associations))
 }
 
 fn select_footprint_for_symbol(
     symbol: &Symbol,
     footprints_by_name: &HashMap<&str, &FootprintInfo>,
     footprint_count: usize,
-) -> Result<String, ImportError> {
-    if let Some(value) = symbol.property_value("Footprint") {
-        let trimmed = value.trim();
-        if !trimmed.is_empty() {
-            if let Some(name) = footprint_name_from_value(trimmed) {
-                if footprints_by_name.contains_key(name) {
-                    return Ok(name.to_string());
-                }
-            }
-        }
+    hint: Option<&str>,
+) -> Result<(String, AssociationRule, f64), ImportError> {
+    let explicit = symbol
+        .property_value("Footprint")
+        .and_then(|value| footprint_name_from_value(value.trim()).map(str::to_string));
+    if let Some(name) = &explicit
+        && footprints_by_name.contains_key(name.as_str())
+    {
+        return Ok((name.clone(), AssociationRule::ExplicitProperty, 1.0));
     }
-    if footprint_count == 1 {
-        if let Some((name, _)) = footprints_by_name.iter().next() {
-            return Ok((*name).to_string());
-        }
+    if let Some(hint) = hint
+        && footprints_by_name.contains_key(hint)
+    {
+        return Ok((hint.to_string(), AssociationRule::VendorConvention, 1.0));
+    }
+    if footprint_count == 1
+        && let Some((name, _)) = footprints_by_name.iter().next()
+    {
+        let confidence = if explicit.is_some() { 0.5 } else { 0.9 };
+        return Ok((
+            (*name).to_string(),
+            AssociationRule::SingleFootprint,
+            confidence,
+        ));
     }
     if footprints_by_name.contains_key(symbol.name()) {
-        return Ok(symbol.name().to_string());
+        return Ok((symbol.name().to_string(), AssociationRule::NameMatch, 1.0));
+    }
+    if let Some((name, score)) = fuzzy_footprint_match(symbol.name(), footprints_by_name) {
+        return Ok((name, AssociationRule::Fuzzy, score));
     }
     Err(ImportError::Association(format!(
         "unable to choose footprint for symbol {}",
@@ -341,6 +2282,68 @@ fn select_footprint_for_symbol(
     )))
 }
 
+fn fuzzy_footprint_match(
+    symbol_name: &str,
+    footprints_by_name: &HashMap<&str, &FootprintInfo>,
+) -> Option<(String, f64)> {
+    let mut best: Option<(&str, f64)> = None;
+    let mut tied = false;
+    for name in footprints_by_name.keys() {
+        let score = name_similarity(symbol_name, name);
+        if score < FUZZY_MATCH_FLOOR {
+            continue;
+        }
+        match best {
+            Some((_, best_score)) if score < best_score => {}
+            Some((_, best_score)) if score == best_score => tied = true,
+            _ => {
+                best = Some((name, score));
+                tied = false;
+            }
+        }
+    }
+    if tied {
+        return None;
+    }
+    best.map(|(name, score)| (name.to_string(), score))
+}
+
+fn name_similarity(a: &str, b: &str) -> f64 {
+    let a = normalize_name(a);
+    let b = normalize_name(b);
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    if a == b {
+        return 0.95;
+    }
+    let longest = a.len().max(b.len()) as f64;
+    1.0 - levenshtein(&a, &b) as f64 / longest
+}
+
+fn normalize_name(name: &str) -> Vec<char> {
+    name.chars()
+        .filter(|ch| ch.is_ascii_alphanumeric())
+        .map(|ch| ch.to_ascii_lowercase())
+        .collect()
+}
+
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            current[j + 1] = (previous[j] + cost)
+                .min(previous[j + 1] + 1)
+                .min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
 fn footprint_name_from_value(value: &str) -> Option<&str> {
     if value.is_empty() {
         return None;
@@ -351,7 +2354,7 @@ fn footprint_name_from_value(value: &str) -> Option<&str> {
     Some(value)
 }
 
-fn footprint_lib_name(path: &Path) -> Result<String, ImportError> {
+pub fn footprint_lib_name(path: &Path) -> Result<String, ImportError> {
     let name = path
         .file_name()
         .and_then(|value| value.to_str())
@@ -367,37 +2370,472 @@ fn footprint_lib_name(path: &Path) -> Result<String, ImportError> {
     Ok(name.to_string())
 }
 
+fn associate_models<'a>(
+    footprints: &[FootprintInfo],
+    step_files: &'a [PathBuf],
+) -> Vec<Option<&'a Path>> {
+    footprints
+        .iter()
+        .map(|footprint| {
+            let referenced = fs::read_to_string(&footprint.path)
+                .ok()
+                .and_then(|content| parse_one(&content).ok())
+                .and_then(|sexp| model_reference(&sexp).map(str::to_string));
+            if let Some(reference) = referenced {
+                let file_name = reference.rsplit(['/', '\\']).next().unwrap_or_default();
+                if let Some(step) = nearest_step(footprint, step_files, |step| {
+                    step.file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(|name| name.eq_ignore_ascii_case(file_name))
+                }) {
+                    return Some(step);
+                }
+            }
+            let wanted = normalize_name(&footprint.name);
+            if let Some(step) = nearest_step(footprint, step_files, |step| {
+                step.file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .is_some_and(|stem| normalize_name(stem) == wanted)
+            }) {
+                return Some(step);
+            }
+            if footprints.len() == 1 && step_files.len() == 1 {
+                return Some(step_files[0].as_path());
+            }
+            None
+        })
+        .collect()
+}
+
+fn nearest_step<'a>(
+    footprint: &FootprintInfo,
+    step_files: &'a [PathBuf],
+    matches: impl Fn(&Path) -> bool,
+) -> Option<&'a Path> {
+    step_files
+        .iter()
+        .filter(|step| matches(step))
+        .min_by_key(|step| {
+            let shared = step
+                .components()
+                .zip(footprint.path.components())
+                .take_while(|(a, b)| a == b)
+                .count();
+            std::cmp::Reverse(shared)
+        })
+        .map(PathBuf::as_path)
+}
+
+fn apply_model_overrides<'a>(
+    models: &mut [Option<&'a Path>],
+    footprints: &[FootprintInfo],
+    overrides: &'a [ModelOverride],
+) -> Result<(), ImportError> {
+    for model in overrides {
+        let index = match model.footprint() {
+            Some(name) => footprints.iter().position(|footprint| footprint.name == name),
+            None if footprints.len() == 1 => Some(0),
+            None => {
+                let stem = model
+                    .path()
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .map(normalize_name)
+                    .unwrap_or_default();
+                footprints
+                    .iter()
+                    .position(|footprint| normalize_name(&footprint.name) == stem)
+            }
+        };
+        let index = index.ok_or_else(|| {
+            ImportError::Association(format!(
+                "no imported footprint for model {}, use FOOTPRINT=PATH",
+                model.path().display()
+            ))
+        })?;
+        models[index] = Some(model.path());
+    }
+    Ok(())
+}
+
+fn apply_symbol_overrides(
+    symbols: &mut [Symbol],
+    footprints: &[FootprintInfo],
+    overrides: &[SymbolOverride],
+) -> Result<(), ImportError> {
+    for symbol_override in overrides {
+        let index = match symbol_override.symbol() {
+            Some(name) => symbols
+                .iter()
+                .position(|symbol| symbol.name() == name)
+                .ok_or_else(|| {
+                    ImportError::InvalidSource(format!("no symbol named {} in source", name))
+                })?,
+            None if symbols.len() == 1 => 0,
+            None => {
+                return Err(ImportError::InvalidSource(format!(
+                    "source has {} symbols, name the one to override",
+                    symbols.len()
+                )));
+            }
+        };
+        let symbol = &mut symbols[index];
+        if let Some(footprint) = symbol_override.footprint() {
+            if !footprints.iter().any(|info| info.name == footprint) {
+                return Err(ImportError::Association(format!(
+                    "no footprint named {} in source for {}",
+                    footprint,
+                    symbol.name()
+                )));
+            }
+            symbol.set_or_add_property("Footprint", footprint);
+        }
+        if let Some(rename) = symbol_override.rename() {
+            symbol.rename(rename);
+        }
+    }
+    Ok(())
+}
+
+pub fn model_reference(footprint: &Sexp) -> Option<&str> {
+    let Sexp::List(items) = footprint else {
+        return None;
+    };
+    items.iter().find_map(|item| match item {
+        Sexp::List(model) if is_model(model) => match model.get(1) {
+            Some(Sexp::Atom(path)) => Some(path.value()),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+fn is_model(items: &[Sexp]) -> bool {
+    matches!(items.first(), Some(Sexp::Atom(head)) if head.value() == "model")
+}
+
+fn set_model_path(footprint: &mut Sexp, model_path: &str) {
+    let Sexp::List(items) = footprint else {
+        return;
+    };
+    let path = Sexp::Atom(Atom::new_quoted(model_path));
+    for item in items.iter_mut() {
+        if let Sexp::List(model) = item
+            && is_model(model)
+        {
+            if model.len() > 1 {
+                model[1] = path;
+            } else {
+                model.push(path);
+            }
+            return;
+        }
+    }
+    let xyz = |value: &str| {
+        Sexp::List(vec![
+            Sexp::Atom(Atom::new("xyz")),
+            Sexp::Atom(Atom::new(value)),
+            Sexp::Atom(Atom::new(value)),
+            Sexp::Atom(Atom::new(value)),
+        ])
+    };
+    items.push(Sexp::List(vec![
+        Sexp::Atom(Atom::new("model")),
+        path,
+        Sexp::List(vec![Sexp::Atom(Atom::new("offset")), xyz("0")]),
+        Sexp::List(vec![Sexp::Atom(Atom::new("scale")), xyz("1")]),
+        Sexp::List(vec![Sexp::Atom(Atom::new("rotate")), xyz("0")]),
+    ]));
+}
+
+pub fn model_path(step_dir: &Path, file_name: &str) -> String {
+    let path = if step_dir.is_absolute() {
+        step_dir.join(file_name).display().to_string()
+    } else {
+        let mut path = String::from("${KIPRJMOD}");
+        for component in step_dir.components() {
+            path.push('/');
+            path.push_str(&component.as_os_str().to_string_lossy());
+        }
+        path.push('/');
+        path.push_str(file_name);
+        path
+    };
+    path.replace('\\', "/")
+}
+
+fn step_file_names(
+    step_files: &[PathBuf],
+    footprints: &[FootprintInfo],
+    models: &[Option<&Path>],
+    naming: StepNaming,
+    layout: StepLayout,
+) -> Result<HashMap<PathBuf, String>, ImportError> {
+    let mut owners: HashMap<&Path, &str> = HashMap::new();
+    for (footprint, model) in footprints.iter().zip(models) {
+        if let Some(step) = model {
+            owners.entry(step).or_insert(footprint.name.as_str());
+        }
+    }
+    let mut names = HashMap::new();
+    if naming == StepNaming::Footprint {
+        for (footprint, model) in footprints.iter().zip(models) {
+            if let Some(step) = model {
+                let name = format!(
+                    "{}.{}",
+                    sanitize_file_stem(&footprint.name),
+                    step_extension(step)
+                );
+                names.entry(step.to_path_buf()).or_insert(name);
+            }
+        }
+    }
+    for step in step_files {
+        if names.contains_key(step) {
+            continue;
+        }
+        let file_name = step
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| ImportError::InvalidSource("invalid step path".to_string()))?;
+        let name = match naming {
+            StepNaming::Original => file_name.to_string(),
+            StepNaming::Sanitized | StepNaming::Footprint => {
+                let stem = step
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .unwrap_or(file_name);
+                format!("{}.{}", sanitize_file_stem(stem), step_extension(step))
+            }
+        };
+        names.insert(step.clone(), name);
+    }
+    if layout == StepLayout::Component {
+        for step in step_files {
+            let component = match owners.get(step.as_path()) {
+                Some(owner) => sanitize_file_stem(owner),
+                None => sanitize_file_stem(
+                    step.file_stem()
+                        .and_then(|stem| stem.to_str())
+                        .unwrap_or_default(),
+                ),
+            };
+            if let Some(name) = names.get_mut(step) {
+                *name = format!("{}/{}", component, name);
+            }
+        }
+    }
+    Ok(names)
+}
+
+fn resolve_step_collisions(
+    step_files: &[PathBuf],
+    contents: &HashMap<PathBuf, PathBuf>,
+    names: &mut HashMap<PathBuf, String>,
+    dest_dir: &Path,
+    resumed: &HashSet<&str>,
+    jobs: usize,
+    warnings: &mut Vec<String>,
+) -> Result<HashSet<PathBuf>, ImportError> {
+    let hashes = parallel_map(step_files, jobs, |step| -> Result<_, ImportError> {
+        let Some(name) = names.get(step) else {
+            return Ok(None);
+        };
+        let hash = sha256_file(contents.get(step).unwrap_or(step))?;
+        let dest = dest_dir.join(name);
+        let dest_hash = if dest.is_file() {
+            Some(sha256_file(&dest)?)
+        } else {
+            None
+        };
+        Ok(Some((hash, dest_hash)))
+    });
+    let mut claimed: HashMap<String, String> = HashMap::new();
+    let mut completed = HashSet::new();
+    for (step, hashes) in step_files.iter().zip(hashes) {
+        let (Some(name), Some((hash, dest_hash))) = (names.get(step).cloned(), hashes?) else {
+            continue;
+        };
+        if resumed.contains(name.as_str()) && dest_hash.as_ref() == Some(&hash) {
+            completed.insert(step.clone());
+        }
+        let dest_hash = dest_hash.filter(|_| !resumed.contains(name.as_str()));
+        let key = name.to_ascii_lowercase();
+        let existing = claimed.get(&key).cloned().or(dest_hash);
+        let name = match existing {
+            Some(existing) if existing != hash => {
+                let renamed = hashed_file_name(&name, &hash);
+                warnings.push(format!(
+                    "step file {} differs from an existing model of the same name, copied as {}",
+                    name, renamed
+                ));
+                renamed
+            }
+            _ => name,
+        };
+        claimed.insert(name.to_ascii_lowercase(), hash);
+        names.insert(step.clone(), name);
+    }
+    Ok(completed)
+}
+
+fn hashed_file_name(name: &str, hash: &str) -> String {
+    let suffix = &hash[..8];
+    match name.rsplit_once('.') {
+        Some((stem, extension)) => format!("{}-{}.{}", stem, suffix, extension),
+        None => format!("{}-{}", name, suffix),
+    }
+}
+
+fn step_extension(step: &Path) -> String {
+    step.extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_else(|| "step".to_string())
+}
+
+fn sanitize_file_stem(stem: &str) -> String {
+    let mut out = String::new();
+    for ch in stem.chars() {
+        if ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.') {
+            out.push(ch);
+        } else if !out.ends_with('_') {
+            out.push('_');
+        }
+    }
+    let trimmed = out.trim_matches(|ch| ch == '_' || ch == '.');
+    if trimmed.is_empty() {
+        "model".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+pub fn relative_model_path(step_dir: &Path, name: &str) -> String {
+    step_dir.join(name).to_string_lossy().replace('\\', "/")
+}
+
 fn copy_footprints(
     footprints: &[FootprintInfo],
-    dest_lib: &Path,
+    models: &[Option<&Path>],
+    step_names: &HashMap<PathBuf, String>,
+    config: &ImportConfig,
+    pads_fixed: &mut Vec<(String, PadProblem)>,
+    warnings: &mut Vec<String>,
 ) -> Result<usize, ImportError> {
-    fs::create_dir_all(dest_lib)?;
+    let dest_lib = config.output_path(config.footprint_lib());
+    fs::create_dir_all(&dest_lib)?;
     let mut count = 0;
-    for footprint in footprints {
-        let file_name = footprint
-            .path
-            .file_name()
-            .ok_or_else(|| ImportError::InvalidSource("invalid footprint path".to_string()))?;
-        let dest_path = dest_lib.join(file_name);
-        fs::copy(&footprint.path, &dest_path)?;
+    for (footprint, model) in footprints.iter().zip(models) {
+        let dest_path = dest_lib.join(format!("{}.kicad_mod", footprint.name));
+        let step_name = model.and_then(|step| step_names.get(step));
         count += 1;
+        let parsed = match config.pad_policy() {
+            PadPolicy::Off if step_name.is_none() => None,
+            _ => Some(parse_one(&fs::read_to_string(&footprint.path)?)),
+        };
+        let mut sexp = match parsed {
+            Some(Ok(sexp)) => sexp,
+            Some(Err(err)) if step_name.is_some() => return Err(err.into()),
+            _ => {
+                fs::copy(&footprint.path, &dest_path)?;
+                continue;
+            }
+        };
+        let mut changed = false;
+        if let Some(step_name) = step_name {
+            let path = match config.model_store() {
+                Some(store) => format!("${{{}}}/{}", store.variable(), step_name),
+                None => model_path(config.step_dir(), step_name),
+            };
+            set_model_path(&mut sexp, &path);
+            changed = true;
+        }
+        match config.pad_policy() {
+            PadPolicy::Warn => {
+                for problem in check_pads(&sexp) {
+                    warnings.push(format!("{}: {}", footprint.name, problem));
+                }
+            }
+            PadPolicy::Fix => {
+                let fixed = fix_pads(&mut sexp);
+                changed |= !fixed.is_empty();
+                pads_fixed.extend(
+                    fixed
+                        .into_iter()
+                        .map(|problem| (footprint.name.clone(), problem)),
+                );
+            }
+            PadPolicy::Off => {}
+        }
+        if changed {
+            fs::write(&dest_path, sexp.to_string_pretty())?;
+        } else {
+            fs::copy(&footprint.path, &dest_path)?;
+        }
     }
     Ok(count)
 }
 
-fn copy_steps(step_files: &[PathBuf], dest_dir: &Path) -> Result<usize, ImportError> {
+fn copy_steps(
+    step_files: &[PathBuf],
+    completed: &HashSet<PathBuf>,
+    contents: &HashMap<PathBuf, PathBuf>,
+    step_names: &HashMap<PathBuf, String>,
+    dest_dir: &Path,
+    jobs: usize,
+) -> Result<(usize, u64), ImportError> {
+    let step_files: Vec<PathBuf> = step_files
+        .iter()
+        .filter(|step| !completed.contains(*step))
+        .cloned()
+        .collect();
     if step_files.is_empty() {
-        return Ok(0);
+        return Ok((0, 0));
     }
     fs::create_dir_all(dest_dir)?;
-    let mut count = 0;
-    for step in step_files {
-        let file_name = step
-            .file_name()
+    let copied = parallel_map(&step_files, jobs, |step| -> Result<u64, ImportError> {
+        let file_name = step_names
+            .get(step)
             .ok_or_else(|| ImportError::InvalidSource("invalid step path".to_string()))?;
         let dest_path = dest_dir.join(file_name);
-        fs::copy(step, dest_path)?;
-        count += 1;
+        if let Some(parent) = dest_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        Ok(fs::copy(contents.get(step).unwrap_or(step), dest_path)?)
+    });
+    let mut bytes = 0;
+    for size in copied {
+        bytes += size?;
     }
-    Ok(count)
+    Ok((step_files.len(), bytes))
+}
+
+pub fn parallel_map<T: Sync, R: Send>(items: &[T], jobs: usize, f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    let jobs = jobs.clamp(1, items.len().max(1));
+    if jobs == 1 {
+        return items.iter().map(f).collect();
+    }
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new((0..items.len()).map(|_| None).collect::<Vec<_>>());
+    thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| {
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(item) = items.get(index) else {
+                        break;
+                    };
+                    let result = f(item);
+                    results.lock().unwrap_or_else(PoisonError::into_inner)[index] = Some(result);
+                }
+            });
+        }
+    });
+    results
+        .into_inner()
+        .unwrap_or_else(PoisonError::into_inner)
+        .into_iter()
+        .flatten()
+        .collect()
 }
@@ -0,0 +1,111 @@
+use crate::ledger::STATE_DIR;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const JOURNAL_FILE: &str = "import-journal.toml";
+
+#[derive(Debug)]
+pub enum JournalError {
+    Io(io::Error),
+    Parse(toml::de::Error),
+    Serialize(toml::ser::Error),
+}
+
+impl fmt::Display for JournalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JournalError::Io(err) => write!(f, "io error: {}", err),
+            JournalError::Parse(err) => write!(f, "import journal parse error: {}", err),
+            JournalError::Serialize(err) => write!(f, "import journal write error: {}", err),
+        }
+    }
+}
+
+impl Error for JournalError {}
+
+impl From<io::Error> for JournalError {
+    fn from(value: io::Error) -> Self {
+        JournalError::Io(value)
+    }
+}
+
+impl From<toml::de::Error> for JournalError {
+    fn from(value: toml::de::Error) -> Self {
+        JournalError::Parse(value)
+    }
+}
+
+impl From<toml::ser::Error> for JournalError {
+    fn from(value: toml::ser::Error) -> Self {
+        JournalError::Serialize(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportJournal {
+    args: toml::Value,
+    #[serde(default)]
+    symbols_added: Option<usize>,
+    #[serde(default)]
+    step_files: Vec<String>,
+}
+
+impl ImportJournal {
+    pub fn new(args: toml::Value) -> Self {
+        Self {
+            args,
+            symbols_added: None,
+            step_files: Vec::new(),
+        }
+    }
+
+    pub fn path(project_root: &Path) -> PathBuf {
+        project_root.join(STATE_DIR).join(JOURNAL_FILE)
+    }
+
+    pub fn load(path: &Path) -> Result<Option<Self>, JournalError> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(toml::from_str(&fs::read_to_string(path)?)?))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), JournalError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub fn remove(path: &Path) -> Result<(), JournalError> {
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    pub fn args(&self) -> &toml::Value {
+        &self.args
+    }
+
+    pub fn symbols_added(&self) -> Option<usize> {
+        self.symbols_added
+    }
+
+    pub fn step_files(&self) -> &[String] {
+        &self.step_files
+    }
+
+    pub fn set_symbols_added(&mut self, count: usize) {
+        self.symbols_added = Some(count);
+    }
+
+    pub fn set_step_files(&mut self, names: Vec<String>) {
+        self.step_files = names;
+    }
+}
@@ -1,5 +1,13 @@
+use std::collections::HashSet;
 use std::error::Error;
 use std::fmt;
+use std::str::FromStr;
+
+pub const SCHEMATIC_PRECISION: usize = 4;
+pub const BOARD_PRECISION: usize = 6;
+pub const STANDARD_PROPERTIES: [&str; 4] = ["Reference", "Value", "Footprint", "Datasheet"];
+const MAX_DEPTH: usize = 256;
+const MAX_ATOM_LEN: usize = 16 * 1024 * 1024;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Atom {
@@ -22,18 +30,58 @@ impl Atom {
         }
     }
 
+    pub fn number(value: f64) -> Self {
+        Self::new(format_number(value, BOARD_PRECISION))
+    }
+
     pub fn value(&self) -> &str {
         &self.value
     }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        if self.quoted {
+            return None;
+        }
+        self.value.parse().ok().filter(|value: &f64| value.is_finite())
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        if self.quoted {
+            return None;
+        }
+        self.value.parse().ok()
+    }
+}
+
+pub fn format_number(value: f64, precision: usize) -> String {
+    let text = format!("{:.*}", precision, value);
+    let text = if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &text
+    };
+    if text == "-0" {
+        "0".to_string()
+    } else {
+        text.to_string()
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Sexp {
     Atom(Atom),
     List(Vec<Sexp>),
+    Comment(String),
 }
 
 impl Sexp {
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Sexp::Atom(atom) => atom.as_f64(),
+            Sexp::List(_) | Sexp::Comment(_) => None,
+        }
+    }
+
     pub fn to_string_pretty(&self) -> String {
         self.to_string_pretty_with_indent("\t")
     }
@@ -48,38 +96,164 @@ impl Sexp {
     fn write_pretty(&self, out: &mut String, indent: usize, indent_str: &str) {
         match self {
             Sexp::Atom(atom) => out.push_str(&render_atom(atom)),
+            Sexp::Comment(text) => out.push_str(text),
             Sexp::List(items) => {
-                out.push('(');
-                if items.is_empty() {
-                    out.push(')');
-                    return;
-                }
-                if items.iter().all(|item| matches!(item, Sexp::Atom(_))) {
-                    for (idx, item) in items.iter().enumerate() {
-                        if idx > 0 {
-                            out.push(' ');
-                        }
-                        item.write_pretty(out, indent, indent_str);
-                    }
-                    out.push(')');
-                    return;
-                }
-                items[0].write_pretty(out, indent, indent_str);
-                for item in &items[1..] {
-                    out.push('\n');
-                    for _ in 0..indent + 1 {
-                        out.push_str(indent_str);
-                    }
-                    item.write_pretty(out, indent + 1, indent_str);
-                }
-                out.push('\n');
-                for _ in 0..indent {
-                    out.push_str(indent_str);
-                }
-                out.push(')');
+                write_list(items.iter(), out, indent, indent_str, Sexp::write_pretty)
             }
         }
     }
+
+    fn write_symbol(&self, out: &mut String, indent: usize, indent_str: &str) {
+        match self {
+            Sexp::List(items) if symbol_name(self).is_some() => write_list(
+                canonical_symbol_items(items).into_iter(),
+                out,
+                indent,
+                indent_str,
+                Sexp::write_symbol,
+            ),
+            _ => self.write_pretty(out, indent, indent_str),
+        }
+    }
+}
+
+fn write_list<'a>(
+    mut items: impl Iterator<Item = &'a Sexp> + Clone,
+    out: &mut String,
+    indent: usize,
+    indent_str: &str,
+    write_item: fn(&Sexp, &mut String, usize, &str),
+) {
+    out.push('(');
+    if items.clone().all(|item| matches!(item, Sexp::Atom(_))) {
+        for (idx, item) in items.enumerate() {
+            if idx > 0 {
+                out.push(' ');
+            }
+            item.write_pretty(out, indent, indent_str);
+        }
+        out.push(')');
+        return;
+    }
+    if let Some(head) = items.next() {
+        head.write_pretty(out, indent, indent_str);
+    }
+    for item in items {
+        out.push('\n');
+        for _ in 0..indent + 1 {
+            out.push_str(indent_str);
+        }
+        write_item(item, out, indent + 1, indent_str);
+    }
+    out.push('\n');
+    for _ in 0..indent {
+        out.push_str(indent_str);
+    }
+    out.push(')');
+}
+
+const SYMBOL_HEADER: &[&str] = &[
+    "extends",
+    "power",
+    "unit_name",
+    "pin_numbers",
+    "pin_names",
+    "exclude_from_sim",
+    "in_bom",
+    "on_board",
+];
+const SYMBOL_TRAILER: &[&str] = &["embedded_fonts", "embedded_files"];
+const MANDATORY_PROPERTIES: &[&str] = &[
+    "Reference",
+    "Value",
+    "Footprint",
+    "Datasheet",
+    "Description",
+];
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum SymbolItemKey {
+    Header(usize),
+    Property(usize),
+    Graphic,
+    Unit(Vec<NaturalChunk>),
+    Pin(Vec<NaturalChunk>),
+    Trailer,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum NaturalChunk {
+    Number(u64),
+    Text(String),
+}
+
+fn canonical_symbol_items(items: &[Sexp]) -> Vec<&Sexp> {
+    let split = items.len().min(2);
+    let mut groups = Vec::new();
+    let mut pending = Vec::new();
+    for item in &items[split..] {
+        pending.push(item);
+        if !matches!(item, Sexp::Comment(_)) {
+            groups.push((symbol_item_key(item), std::mem::take(&mut pending)));
+        }
+    }
+    groups.sort_by(|a, b| a.0.cmp(&b.0));
+    items[..split]
+        .iter()
+        .chain(groups.into_iter().flat_map(|(_, group)| group))
+        .chain(pending)
+        .collect()
+}
+
+fn symbol_item_key(item: &Sexp) -> SymbolItemKey {
+    let Sexp::List(items) = item else {
+        return SymbolItemKey::Graphic;
+    };
+    let head = items.first().and_then(atom_value).unwrap_or_default();
+    let arg = || items.get(1).and_then(atom_value).unwrap_or_default();
+    if let Some(index) = SYMBOL_HEADER.iter().position(|name| *name == head) {
+        return SymbolItemKey::Header(index);
+    }
+    match head {
+        "property" => SymbolItemKey::Property(
+            MANDATORY_PROPERTIES
+                .iter()
+                .position(|name| *name == arg())
+                .unwrap_or(MANDATORY_PROPERTIES.len()),
+        ),
+        "symbol" => SymbolItemKey::Unit(natural_key(arg())),
+        "pin" => SymbolItemKey::Pin(natural_key(
+            items
+                .iter()
+                .find_map(|field| match field {
+                    Sexp::List(field) if field.first().and_then(atom_value) == Some("number") => {
+                        field.get(1).and_then(atom_value)
+                    }
+                    _ => None,
+                })
+                .unwrap_or_default(),
+        )),
+        _ if SYMBOL_TRAILER.contains(&head) => SymbolItemKey::Trailer,
+        _ => SymbolItemKey::Graphic,
+    }
+}
+
+fn natural_key(text: &str) -> Vec<NaturalChunk> {
+    let mut chunks = Vec::new();
+    let mut rest = text;
+    while let Some(ch) = rest.chars().next() {
+        let digits = ch.is_ascii_digit();
+        let end = rest
+            .find(|next: char| next.is_ascii_digit() != digits)
+            .unwrap_or(rest.len());
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push(match chunk.parse() {
+            Ok(number) if digits => NaturalChunk::Number(number),
+            _ => NaturalChunk::Text(chunk.to_string()),
+        });
+        rest = tail;
+    }
+    chunks
 }
 
 #[derive(Clone, Debug)]
@@ -133,11 +307,12 @@ impl Symbol {
             _ => return false,
         };
         for item in list.iter_mut() {
-            if let Some(items) = property_items_mut(item, name) {
-                if items.len() >= 3 {
-                    items[2] = Sexp::Atom(Atom::new(value));
-                    return true;
-                }
+            if let Some(items) = property_items_mut(item, name)
+                && items.len() >= 3
+            {
+                items[1] = Sexp::Atom(Atom::new_quoted(canonical_property_name(name)));
+                items[2] = Sexp::Atom(Atom::new(value));
+                return true;
             }
         }
         false
@@ -155,6 +330,7 @@ impl Symbol {
             Sexp::List(items) if is_property_list(items) => Some(items.clone()),
             _ => None,
         }) {
+            let name = canonical_property_name(name);
             let mut new_items = template;
             if new_items.len() >= 2 {
                 new_items[1] = Sexp::Atom(Atom::new_quoted(name));
@@ -171,51 +347,369 @@ impl Symbol {
         }
         list.push(Sexp::List(vec![
             Sexp::Atom(Atom::new("property")),
-            Sexp::Atom(Atom::new_quoted(name)),
+            Sexp::Atom(Atom::new_quoted(canonical_property_name(name))),
             Sexp::Atom(Atom::new(value)),
         ]));
     }
 
+    pub fn set_or_add_hidden_property(&mut self, name: &str, value: &str) {
+        if self.set_property_value(name, value) {
+            return;
+        }
+        let list = match &mut self.sexp {
+            Sexp::List(items) => items,
+            _ => return,
+        };
+        list.push(Sexp::List(vec![
+            Sexp::Atom(Atom::new("property")),
+            Sexp::Atom(Atom::new_quoted(canonical_property_name(name))),
+            Sexp::Atom(Atom::new_quoted(value)),
+            Sexp::List(vec![
+                Sexp::Atom(Atom::new("at")),
+                Sexp::Atom(Atom::new("0")),
+                Sexp::Atom(Atom::new("0")),
+                Sexp::Atom(Atom::new("0")),
+            ]),
+            Sexp::List(vec![
+                Sexp::Atom(Atom::new("effects")),
+                Sexp::List(vec![
+                    Sexp::Atom(Atom::new("font")),
+                    Sexp::List(vec![
+                        Sexp::Atom(Atom::new("size")),
+                        Sexp::Atom(Atom::new("1.27")),
+                        Sexp::Atom(Atom::new("1.27")),
+                    ]),
+                ]),
+                Sexp::Atom(Atom::new("hide")),
+            ]),
+        ]));
+    }
+
+    pub fn remove_property(&mut self, name: &str) -> bool {
+        let list = match &mut self.sexp {
+            Sexp::List(items) => items,
+            _ => return false,
+        };
+        let before = list.len();
+        list.retain(|item| property_value(item, name).is_none());
+        list.len() != before
+    }
+
+    pub fn keep_properties_from(&mut self, previous: &Symbol, skip_prefix: &str) -> Vec<String> {
+        let Sexp::List(previous_items) = &previous.sexp else {
+            return Vec::new();
+        };
+        let mut kept = Vec::new();
+        for item in previous_items {
+            let Sexp::List(items) = item else {
+                continue;
+            };
+            let Some(name) = items
+                .get(1)
+                .filter(|_| items.len() >= 3 && is_property_list(items))
+                .and_then(atom_value)
+            else {
+                continue;
+            };
+            if name.starts_with(skip_prefix) || self.property_value(name).is_some() {
+                continue;
+            }
+            if let Sexp::List(list) = &mut self.sexp {
+                list.push(item.clone());
+                kept.push(name.to_string());
+            }
+        }
+        kept
+    }
+
+    pub fn properties(&self) -> Vec<(String, String)> {
+        let list = match &self.sexp {
+            Sexp::List(items) => items,
+            _ => return Vec::new(),
+        };
+        list.iter()
+            .filter_map(|item| match item {
+                Sexp::List(items) if items.len() >= 3 && is_property_list(items) => Some((
+                    atom_value(&items[1])?.to_string(),
+                    atom_value(&items[2])?.to_string(),
+                )),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn pin_count(&self) -> usize {
+        count_lists(&self.sexp, "pin")
+    }
+
+    pub fn map_pin_text(&mut self, mut map: impl FnMut(&str, &str) -> Option<String>) -> usize {
+        map_pin_text(&mut self.sexp, &mut map)
+    }
+
+    pub fn extends(&self) -> Option<&str> {
+        let Sexp::List(items) = &self.sexp else {
+            return None;
+        };
+        items.iter().find_map(|item| match item {
+            Sexp::List(extends) if extends.first().and_then(atom_value) == Some("extends") => {
+                extends.get(1).and_then(atom_value)
+            }
+            _ => None,
+        })
+    }
+
+    pub fn rename(&mut self, new_name: &str) {
+        let Sexp::List(items) = &mut self.sexp else {
+            return;
+        };
+        let old_prefix = format!("{}_", self.name);
+        for item in items.iter_mut().skip(2) {
+            if let Sexp::List(unit) = item
+                && unit.len() >= 2
+                && atom_value(&unit[0]) == Some("symbol")
+                && let Some(suffix) = atom_value(&unit[1])
+                    .and_then(|unit_name| unit_name.strip_prefix(&old_prefix))
+                    .filter(|suffix| is_unit_suffix(suffix))
+            {
+                unit[1] = Sexp::Atom(Atom::new_quoted(format!("{}_{}", new_name, suffix)));
+            }
+        }
+        if items.len() >= 2 {
+            items[1] = Sexp::Atom(Atom::new_quoted(new_name));
+        }
+        if self.property_value("Value").as_deref() == Some(self.name.as_str()) {
+            self.set_property_value("Value", new_name);
+        }
+        self.name = new_name.to_string();
+    }
+
     pub fn into_sexp(self) -> Sexp {
         self.sexp
     }
 }
 
+fn is_unit_suffix(suffix: &str) -> bool {
+    suffix
+        .split_once('_')
+        .is_some_and(|(unit, style)| {
+            !unit.is_empty()
+                && !style.is_empty()
+                && unit.chars().all(|ch| ch.is_ascii_digit())
+                && style.chars().all(|ch| ch.is_ascii_digit())
+        })
+}
+
+fn extends_items_mut(sexp: &mut Sexp) -> Option<&mut Vec<Sexp>> {
+    let Sexp::List(items) = sexp else {
+        return None;
+    };
+    items.iter_mut().find_map(|item| match item {
+        Sexp::List(extends) if extends.len() >= 2 && atom_value(&extends[0]) == Some("extends") => {
+            Some(extends)
+        }
+        _ => None,
+    })
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum AddPolicy {
     ErrorOnConflict,
     ReplaceExisting,
     SkipExisting,
+    RelinkExisting,
+    MergeProperties,
+}
+
+impl AddPolicy {
+    pub fn all() -> &'static [AddPolicy] {
+        &[
+            AddPolicy::ErrorOnConflict,
+            AddPolicy::ReplaceExisting,
+            AddPolicy::SkipExisting,
+            AddPolicy::RelinkExisting,
+            AddPolicy::MergeProperties,
+        ]
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AddPolicy::ErrorOnConflict => "error",
+            AddPolicy::ReplaceExisting => "replace",
+            AddPolicy::SkipExisting => "skip",
+            AddPolicy::RelinkExisting => "relink",
+            AddPolicy::MergeProperties => "merge",
+        }
+    }
+}
+
+impl fmt::Display for AddPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for AddPolicy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        AddPolicy::all()
+            .iter()
+            .copied()
+            .find(|policy| policy.name().eq_ignore_ascii_case(value.trim()))
+            .ok_or_else(|| format!("unknown existing symbol policy: {}", value))
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct KicadSymbolLib {
-    root: Sexp,
+    head: Sexp,
+    items: Vec<LibItem>,
+    warnings: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
+enum LibItem {
+    Symbol(Symbol),
+    Other(Sexp),
 }
 
 impl KicadSymbolLib {
     pub fn parse(input: &str) -> Result<Self, KicadSymError> {
         let mut parser = Parser::new(input);
-        let mut items = parser.parse_all()?;
-        if items.len() != 1 {
-            return Err(KicadSymError::new(
-                "expected a single top-level S-expression for library",
-            ));
+        let items = parser.parse_all()?;
+        let mut root: Option<Sexp> = None;
+        let mut rejected = None;
+        let mut warnings = Vec::new();
+        for item in items {
+            if let Err(err) = ensure_root(&item) {
+                let head = match &item {
+                    Sexp::List(items) => items.first().and_then(atom_value),
+                    Sexp::Atom(atom) => Some(atom.value()),
+                    Sexp::Comment(_) => None,
+                };
+                warnings.push(format!(
+                    "ignored stray top-level expression: {}",
+                    head.unwrap_or("()")
+                ));
+                rejected.get_or_insert(err);
+                continue;
+            }
+            match &mut root {
+                None => root = Some(item),
+                Some(root) => {
+                    warnings.push("merged a second kicad_symbol_lib expression".to_string());
+                    merge_roots(root, item, &mut warnings)?;
+                }
+            }
         }
-        let root = items.remove(0);
-        ensure_root(&root)?;
-        Ok(Self { root })
+        let root = root.ok_or_else(|| {
+            rejected.unwrap_or_else(|| KicadSymError::new("expected a kicad_symbol_lib expression"))
+        })?;
+        let Sexp::List(root_items) = root else {
+            return Err(KicadSymError::new("library root must be a list expression"));
+        };
+        let mut root_items = root_items.into_iter();
+        let head = root_items
+            .next()
+            .ok_or_else(|| KicadSymError::new("library root list is empty"))?;
+        let mut items = Vec::new();
+        for item in root_items {
+            if symbol_name(&item).is_some() {
+                items.push(LibItem::Symbol(Symbol::from_sexp(item)?));
+            } else {
+                items.push(LibItem::Other(item));
+            }
+        }
+        Ok(Self {
+            head,
+            items,
+            warnings,
+        })
+    }
+
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
     }
 
     pub fn symbols(&self) -> Result<Vec<Symbol>, KicadSymError> {
-        let items = root_items(&self.root)?;
-        let mut out = Vec::new();
-        for item in items.iter().skip(1) {
-            if symbol_name(item).is_some() {
-                out.push(Symbol::from_sexp(item.clone())?);
+        Ok(self.symbols_iter().cloned().collect())
+    }
+
+    pub fn symbols_iter(&self) -> impl Iterator<Item = &Symbol> {
+        self.items.iter().filter_map(|item| match item {
+            LibItem::Symbol(symbol) => Some(symbol),
+            LibItem::Other(_) => None,
+        })
+    }
+
+    pub fn symbol(&self, name: &str) -> Option<&Symbol> {
+        self.symbols_iter().find(|symbol| symbol.name() == name)
+    }
+
+    pub fn symbol_mut(&mut self, name: &str) -> Option<&mut Symbol> {
+        self.items.iter_mut().find_map(|item| match item {
+            LibItem::Symbol(symbol) if symbol.name() == name => Some(symbol),
+            _ => None,
+        })
+    }
+
+    pub fn with_symbol_mut<R>(
+        &mut self,
+        name: &str,
+        edit: impl FnOnce(&mut Symbol) -> R,
+    ) -> Option<R> {
+        self.symbol_mut(name).map(edit)
+    }
+
+    pub fn remove_symbol(&mut self, name: &str) -> Option<Symbol> {
+        let idx = self.items.iter().position(|item| {
+            matches!(item, LibItem::Symbol(symbol) if symbol.name() == name)
+        })?;
+        match self.items.remove(idx) {
+            LibItem::Symbol(symbol) => Some(symbol),
+            LibItem::Other(_) => None,
+        }
+    }
+
+    pub fn delete_symbol(&mut self, name: &str) -> Result<Symbol, KicadSymError> {
+        let derived: Vec<&str> = self
+            .symbols_iter()
+            .filter(|symbol| symbol.extends() == Some(name))
+            .map(Symbol::name)
+            .collect();
+        if !derived.is_empty() {
+            return Err(KicadSymError::new(format!(
+                "{} is extended by {}",
+                name,
+                derived.join(", ")
+            )));
+        }
+        self.remove_symbol(name)
+            .ok_or_else(|| KicadSymError::new(format!("symbol not found: {}", name)))
+    }
+
+    pub fn rename_symbol(&mut self, old_name: &str, new_name: &str) -> Result<(), KicadSymError> {
+        if old_name == new_name {
+            return Ok(());
+        }
+        if self.symbol(new_name).is_some() {
+            return Err(KicadSymError::new(format!(
+                "symbol already exists: {}",
+                new_name
+            )));
+        }
+        self.symbol_mut(old_name)
+            .ok_or_else(|| KicadSymError::new(format!("symbol not found: {}", old_name)))?
+            .rename(new_name);
+        for item in &mut self.items {
+            if let LibItem::Symbol(symbol) = item
+                && let Some(extends) = extends_items_mut(&mut symbol.sexp)
+                && atom_value(&extends[1]) == Some(old_name)
+            {
+                extends[1] = Sexp::Atom(Atom::new_quoted(new_name));
             }
         }
-        Ok(out)
+        Ok(())
     }
 
     pub fn add_symbol(
@@ -223,35 +717,65 @@ impl KicadSymbolLib {
         symbol: Symbol,
         policy: AddPolicy,
     ) -> Result<(), KicadSymError> {
-        ensure_root(&self.root)?;
-        let items = root_items_mut(&mut self.root)?;
         let name = symbol.name().to_string();
-        let mut existing = None;
-        for (idx, item) in items.iter().enumerate().skip(1) {
-            if symbol_name(item) == Some(name.as_str()) {
-                existing = Some(idx);
-                break;
-            }
-        }
-        match (existing, policy) {
-            (Some(_), AddPolicy::SkipExisting) => Ok(()),
-            (Some(_), AddPolicy::ErrorOnConflict) => Err(KicadSymError::new(format!(
+        let Some(existing) = self.symbol_mut(&name) else {
+            self.items.push(LibItem::Symbol(symbol));
+            return Ok(());
+        };
+        match policy {
+            AddPolicy::SkipExisting => Ok(()),
+            AddPolicy::ErrorOnConflict => Err(KicadSymError::new(format!(
                 "symbol already exists: {}",
                 name
             ))),
-            (Some(idx), AddPolicy::ReplaceExisting) => {
-                items[idx] = symbol.into_sexp();
+            AddPolicy::ReplaceExisting => {
+                *existing = symbol;
+                Ok(())
+            }
+            AddPolicy::RelinkExisting => {
+                if let Some(footprint) = symbol.property_value("Footprint") {
+                    existing.set_or_add_property("Footprint", &footprint);
+                }
                 Ok(())
             }
-            (None, _) => {
-                items.push(symbol.into_sexp());
+            AddPolicy::MergeProperties => {
+                for (name, value) in symbol.properties() {
+                    if !value.is_empty() {
+                        existing.set_or_add_property(&name, &value);
+                    }
+                }
                 Ok(())
             }
         }
     }
 
     pub fn to_string_pretty(&self) -> String {
-        self.root.to_string_pretty_with_indent("\t")
+        let indent_str = "\t";
+        let mut out = String::from("(");
+        self.head.write_pretty(&mut out, 0, indent_str);
+        let items: Vec<&Sexp> = self
+            .items
+            .iter()
+            .map(|item| match item {
+                LibItem::Symbol(symbol) => &symbol.sexp,
+                LibItem::Other(sexp) => sexp,
+            })
+            .collect();
+        if items.iter().all(|item| matches!(item, Sexp::Atom(_))) {
+            for item in items {
+                out.push(' ');
+                item.write_pretty(&mut out, 0, indent_str);
+            }
+            out.push_str(")\n");
+            return out;
+        }
+        for item in items {
+            out.push('\n');
+            out.push_str(indent_str);
+            item.write_symbol(&mut out, 1, indent_str);
+        }
+        out.push_str("\n)\n");
+        out
     }
 }
 
@@ -270,6 +794,101 @@ pub fn parse_one(input: &str) -> Result<Sexp, KicadSymError> {
     Ok(items.remove(0))
 }
 
+pub struct SymbolStream<'a> {
+    parser: Parser<'a>,
+    in_root: bool,
+    roots: usize,
+    seen: HashSet<String>,
+    done: bool,
+}
+
+impl<'a> SymbolStream<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self {
+            parser: Parser::new(input),
+            in_root: false,
+            roots: 0,
+            seen: HashSet::new(),
+            done: false,
+        }
+    }
+
+    fn enter_root(&mut self) -> Result<bool, KicadSymError> {
+        loop {
+            self.parser.skip_ws_and_comments();
+            match self.parser.peek() {
+                None => return Ok(false),
+                Some('(') => {
+                    self.parser.expect('(')?;
+                    self.parser.depth = 1;
+                    self.parser.skip_ws_and_comments();
+                    let head = match self.parser.peek() {
+                        Some('(') | Some(')') | None => None,
+                        Some(_) => Some(self.parser.parse_sexp()?),
+                    };
+                    if head.as_ref().and_then(atom_value) == Some("kicad_symbol_lib") {
+                        self.roots += 1;
+                        return Ok(true);
+                    }
+                    self.parser.parse_list_items()?;
+                    self.parser.depth = 0;
+                }
+                Some(_) => {
+                    self.parser.parse_sexp()?;
+                }
+            }
+        }
+    }
+
+    fn next_symbol(&mut self) -> Result<Option<Symbol>, KicadSymError> {
+        loop {
+            if !self.in_root {
+                if self.enter_root()? {
+                    self.in_root = true;
+                } else if self.roots == 0 {
+                    return Err(KicadSymError::new("expected a kicad_symbol_lib expression"));
+                } else {
+                    return Ok(None);
+                }
+            }
+            self.parser.skip_ws_and_comments();
+            match self.parser.peek() {
+                Some(')') => {
+                    self.parser.next();
+                    self.parser.depth = 0;
+                    self.in_root = false;
+                }
+                None => return Err(self.parser.error("unterminated list")),
+                Some(_) => {
+                    let item = self.parser.parse_sexp()?;
+                    let Some(name) = symbol_name(&item) else {
+                        continue;
+                    };
+                    if !self.seen.insert(name.to_string()) && self.roots > 1 {
+                        continue;
+                    }
+                    return Symbol::from_sexp(item).map(Some);
+                }
+            }
+        }
+    }
+}
+
+impl Iterator for SymbolStream<'_> {
+    type Item = Result<Symbol, KicadSymError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let next = self.next_symbol().transpose();
+        if !matches!(next, Some(Ok(_))) {
+            self.done = true;
+        }
+        next
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct KicadSymError {
     message: String,
@@ -293,6 +912,18 @@ impl KicadSymError {
             column: Some(column),
         }
     }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    pub fn column(&self) -> Option<usize> {
+        self.column
+    }
 }
 
 impl fmt::Display for KicadSymError {
@@ -308,18 +939,29 @@ impl fmt::Display for KicadSymError {
 
 impl Error for KicadSymError {}
 
-struct Parser {
-    chars: Vec<char>,
+fn attach_comments(items: &mut Vec<Sexp>, comments: impl IntoIterator<Item = Sexp>) {
+    let at = items
+        .iter()
+        .skip(1)
+        .position(|item| matches!(item, Sexp::List(_)))
+        .map_or(items.len(), |idx| idx + 1);
+    items.splice(at..at, comments);
+}
+
+struct Parser<'a> {
+    input: &'a str,
     pos: usize,
+    depth: usize,
     line: usize,
     column: usize,
 }
 
-impl Parser {
-    fn new(input: &str) -> Self {
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
         Self {
-            chars: input.chars().collect(),
+            input,
             pos: 0,
+            depth: 0,
             line: 1,
             column: 1,
         }
@@ -327,12 +969,24 @@ impl Parser {
 
     fn parse_all(&mut self) -> Result<Vec<Sexp>, KicadSymError> {
         let mut items = Vec::new();
+        let mut pending = Vec::new();
         loop {
-            self.skip_ws_and_comments();
+            pending.extend(self.take_comments());
             if self.peek().is_none() {
                 break;
             }
-            items.push(self.parse_sexp()?);
+            let mut item = self.parse_sexp()?;
+            if let Sexp::List(list) = &mut item {
+                attach_comments(list, pending.drain(..));
+            }
+            items.push(item);
+        }
+        let last_list = items
+            .iter_mut()
+            .rev()
+            .find(|item| matches!(item, Sexp::List(_)));
+        if let Some(Sexp::List(list)) = last_list {
+            list.extend(pending);
         }
         Ok(items)
     }
@@ -349,19 +1003,37 @@ impl Parser {
     }
 
     fn parse_list(&mut self) -> Result<Sexp, KicadSymError> {
+        if self.depth >= MAX_DEPTH {
+            return Err(self.error(format!("lists nested deeper than {}", MAX_DEPTH)));
+        }
         self.expect('(')?;
+        self.depth += 1;
+        let list = self.parse_list_items();
+        self.depth -= 1;
+        list
+    }
+
+    fn parse_list_items(&mut self) -> Result<Sexp, KicadSymError> {
         let mut items = Vec::new();
+        let mut pending = Vec::new();
         loop {
-            self.skip_ws_and_comments();
+            pending.extend(self.take_comments());
             match self.peek() {
                 Some(')') => {
                     self.next();
                     break;
                 }
                 None => return Err(self.error("unterminated list")),
-                _ => items.push(self.parse_sexp()?),
+                _ => {
+                    let item = self.parse_sexp()?;
+                    if matches!(item, Sexp::List(_)) {
+                        items.append(&mut pending);
+                    }
+                    items.push(item);
+                }
             }
         }
+        items.append(&mut pending);
         Ok(Sexp::List(items))
     }
 
@@ -373,6 +1045,7 @@ impl Parser {
             }
             self.next();
             value.push(ch);
+            self.check_atom_len(&value)?;
         }
         if value.is_empty() {
             return Err(self.error("expected atom"));
@@ -400,10 +1073,31 @@ impl Parser {
                 }
                 _ => value.push(ch),
             }
+            self.check_atom_len(&value)?;
         }
         Ok(Sexp::Atom(Atom::new_quoted(value)))
     }
 
+    fn take_comments(&mut self) -> Vec<Sexp> {
+        let mut comments = Vec::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(';') | Some('#') => {
+                    let mut text = String::new();
+                    while let Some(ch) = self.next() {
+                        if ch == '\n' {
+                            break;
+                        }
+                        text.push(ch);
+                    }
+                    comments.push(Sexp::Comment(text.trim_end().to_string()));
+                }
+                _ => return comments,
+            }
+        }
+    }
+
     fn skip_ws_and_comments(&mut self) {
         loop {
             self.skip_whitespace();
@@ -439,12 +1133,12 @@ impl Parser {
     }
 
     fn peek(&self) -> Option<char> {
-        self.chars.get(self.pos).copied()
+        self.input[self.pos..].chars().next()
     }
 
     fn next(&mut self) -> Option<char> {
         let ch = self.peek()?;
-        self.pos += 1;
+        self.pos += ch.len_utf8();
         if ch == '\n' {
             self.line += 1;
             self.column = 1;
@@ -454,6 +1148,13 @@ impl Parser {
         Some(ch)
     }
 
+    fn check_atom_len(&self, value: &str) -> Result<(), KicadSymError> {
+        if value.len() > MAX_ATOM_LEN {
+            return Err(self.error(format!("atom longer than {} bytes", MAX_ATOM_LEN)));
+        }
+        Ok(())
+    }
+
     fn error(&self, message: impl Into<String>) -> KicadSymError {
         KicadSymError::with_pos(message, self.line, self.column)
     }
@@ -477,6 +1178,34 @@ fn root_items_mut(sexp: &mut Sexp) -> Result<&mut Vec<Sexp>, KicadSymError> {
     }
 }
 
+fn merge_roots(
+    root: &mut Sexp,
+    other: Sexp,
+    warnings: &mut Vec<String>,
+) -> Result<(), KicadSymError> {
+    let Sexp::List(extra) = other else {
+        return Ok(());
+    };
+    let items = root_items_mut(root)?;
+    for item in extra.into_iter().skip(1) {
+        let Some(name) = symbol_name(&item) else {
+            continue;
+        };
+        if items
+            .iter()
+            .any(|existing| symbol_name(existing) == Some(name))
+        {
+            warnings.push(format!(
+                "duplicate symbol {} in merged library, keeping the first",
+                name
+            ));
+            continue;
+        }
+        items.push(item);
+    }
+    Ok(())
+}
+
 fn ensure_root(sexp: &Sexp) -> Result<(), KicadSymError> {
     let items = root_items(sexp)?;
     if items.is_empty() {
@@ -511,6 +1240,41 @@ fn atom_value(sexp: &Sexp) -> Option<&str> {
     }
 }
 
+fn count_lists(sexp: &Sexp, head: &str) -> usize {
+    let Sexp::List(items) = sexp else {
+        return 0;
+    };
+    if items.first().and_then(atom_value) == Some(head) {
+        return 1;
+    }
+    items.iter().map(|item| count_lists(item, head)).sum()
+}
+
+fn map_pin_text(sexp: &mut Sexp, map: &mut impl FnMut(&str, &str) -> Option<String>) -> usize {
+    let Sexp::List(items) = sexp else {
+        return 0;
+    };
+    if items.first().and_then(atom_value) != Some("pin") {
+        return items.iter_mut().map(|item| map_pin_text(item, map)).sum();
+    }
+    let mut changed = 0;
+    for item in items.iter_mut() {
+        let Sexp::List(field) = item else {
+            continue;
+        };
+        let Some(kind @ ("name" | "number")) = field.first().and_then(atom_value) else {
+            continue;
+        };
+        if let Some(text) = field.get(1).and_then(atom_value)
+            && let Some(cleaned) = map(kind, text).filter(|cleaned| cleaned != text)
+        {
+            field[1] = Sexp::Atom(Atom::new_quoted(cleaned));
+            changed += 1;
+        }
+    }
+    changed
+}
+
 fn property_value<'a>(sexp: &'a Sexp, name: &str) -> Option<&'a str> {
     let items = match sexp {
         Sexp::List(items) => items,
@@ -522,7 +1286,7 @@ fn property_value<'a>(sexp: &'a Sexp, name: &str) -> Option<&'a str> {
     if atom_value(&items[0]) != Some("property") {
         return None;
     }
-    if atom_value(&items[1]) != Some(name) {
+    if !atom_value(&items[1]).is_some_and(|key| property_name_matches(key, name)) {
         return None;
     }
     atom_value(&items[2])
@@ -539,12 +1303,32 @@ fn property_items_mut<'a>(sexp: &'a mut Sexp, name: &str) -> Option<&'a mut Vec<
     if !is_property_list(items) {
         return None;
     }
-    if atom_value(&items[1]) != Some(name) {
+    if !atom_value(&items[1]).is_some_and(|key| property_name_matches(key, name)) {
         return None;
     }
     Some(items)
 }
 
+pub fn canonical_property_name(name: &str) -> &str {
+    let bare = name
+        .get(..3)
+        .filter(|prefix| prefix.eq_ignore_ascii_case("ki_"))
+        .map_or(name, |_| &name[3..]);
+    STANDARD_PROPERTIES
+        .iter()
+        .find(|standard| standard.eq_ignore_ascii_case(bare))
+        .copied()
+        .unwrap_or(name)
+}
+
+fn property_name_matches(key: &str, name: &str) -> bool {
+    if key == name {
+        return true;
+    }
+    let canonical = canonical_property_name(name);
+    STANDARD_PROPERTIES.contains(&canonical) && canonical_property_name(key) == canonical
+}
+
 fn is_property_list(items: &[Sexp]) -> bool {
     if items.is_empty() {
         return false;
@@ -586,6 +1370,101 @@ fn escape_atom(value: &str) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn rejects_hostile_input_without_panicking() {
+        let deep = "(".repeat(100_000) + &")".repeat(100_000);
+        let err = parse_one(&deep).unwrap_err();
+        assert!(err.to_string().contains("nested deeper than 256"));
+        assert!(parse_one(&format!("{}a{}", "(".repeat(200), ")".repeat(200))).is_ok());
+
+        for input in ["(a \"b\\", "(a \"b", "(a", ")", "(a))", "\"\\"] {
+            assert!(parse_sexps(input).is_err(), "{:?}", input);
+        }
+        let huge = format!("(a \"{}\")", "x".repeat(MAX_ATOM_LEN + 1));
+        assert!(parse_one(&huge).unwrap_err().to_string().contains("atom longer than"));
+    }
+
+    #[test]
+    fn streams_the_same_symbols_as_a_full_parse() {
+        let input = "; header\n(kicad_symbol_lib (version 20231120) (generator kci)\n  # note\n  (symbol \"A\" (property \"Value\" \"Ä\"))\n  (symbol \"B\" (extends \"A\")))\n(stray (symbol \"X\"))\n(kicad_symbol_lib (symbol \"A\") (symbol \"C\"))\n";
+        let parsed: Vec<Symbol> = KicadSymbolLib::parse(input).unwrap().symbols().unwrap();
+        let streamed: Vec<Symbol> = SymbolStream::new(input).collect::<Result<_, _>>().unwrap();
+        let names: Vec<&str> = streamed.iter().map(Symbol::name).collect();
+        assert_eq!(names, ["A", "B", "C"]);
+        assert_eq!(
+            streamed.iter().map(|symbol| &symbol.sexp).collect::<Vec<_>>(),
+            parsed.iter().map(|symbol| &symbol.sexp).collect::<Vec<_>>()
+        );
+
+        assert!(SymbolStream::new("(footprint \"A\")").next().unwrap().is_err());
+        let mut truncated = SymbolStream::new("(kicad_symbol_lib (symbol \"A\") (symbol");
+        assert!(truncated.next().unwrap().is_ok());
+        assert!(truncated.next().unwrap().is_err());
+        assert!(truncated.next().is_none());
+    }
+
+    #[test]
+    fn writes_symbols_in_canonical_order() {
+        let input = "(kicad_symbol_lib (version 20231120) (symbol \"U\" \
+            (symbol \"U_1_1\" (pin input line (number \"10\")) (pin input line (number \"2\")) \
+             (rectangle (start 0 0) (end 1 1))) \
+            (property \"MPN\" \"X\") (symbol \"U_0_1\") (in_bom yes) \
+            (property \"Value\" \"U\") (pin_names (offset 0)) (property \"Reference\" \"U\")))";
+        let out = KicadSymbolLib::parse(input).unwrap().to_string_pretty();
+        let order = [
+            "(pin_names",
+            "(in_bom",
+            "\"Reference\"",
+            "\"Value\"",
+            "\"MPN\"",
+            "\"U_0_1\"",
+            "\"U_1_1\"",
+            "(rectangle",
+            "\"2\"",
+            "\"10\"",
+        ];
+        let positions: Vec<usize> = order.iter().map(|text| out.find(text).unwrap()).collect();
+        assert!(positions.windows(2).all(|pair| pair[0] < pair[1]), "{}", out);
+        assert_eq!(KicadSymbolLib::parse(&out).unwrap().to_string_pretty(), out);
+    }
+
+    #[test]
+    fn round_trips_comments() {
+        let input = "; vendor library\n(kicad_symbol_lib (version 20231120)\n  # keep in sync with datasheet rev C\n  (symbol \"A\" ; unit A\n    (property \"Value\" \"A\")))\n; end\n";
+        let lib = KicadSymbolLib::parse(input).unwrap();
+        assert_eq!(lib.symbol("A").unwrap().property_value("Value").as_deref(), Some("A"));
+        let out = lib.to_string_pretty();
+        assert!(out.contains("\t; vendor library\n"));
+        assert!(out.contains("\t# keep in sync with datasheet rev C\n"));
+        assert!(out.contains("\t\t; unit A\n"));
+        assert!(out.contains("\t; end\n)"));
+        assert_eq!(KicadSymbolLib::parse(&out).unwrap().to_string_pretty(), out);
+    }
+
+    #[test]
+    fn formats_numbers_like_kicad() {
+        assert_eq!(format_number(0.254, BOARD_PRECISION), "0.254");
+        assert_eq!(format_number(0.1 + 0.2, BOARD_PRECISION), "0.3");
+        assert_eq!(format_number(2.0, SCHEMATIC_PRECISION), "2");
+        assert_eq!(format_number(-0.00001, SCHEMATIC_PRECISION), "0");
+        assert_eq!(format_number(-1.27, SCHEMATIC_PRECISION), "-1.27");
+        assert_eq!(format_number(1.23456789, BOARD_PRECISION), "1.234568");
+        assert_eq!(Atom::number(-2.54).value(), "-2.54");
+
+        let sexp = parse_one("(at 1.5 -2 90 \"3\")").unwrap();
+        let Sexp::List(items) = sexp else {
+            panic!("expected a list");
+        };
+        assert_eq!(items[1].as_f64(), Some(1.5));
+        assert_eq!(items[3].as_f64(), Some(90.0));
+        assert_eq!(items[0].as_f64(), None);
+        assert_eq!(items[4].as_f64(), None);
+        let Sexp::Atom(angle) = &items[3] else {
+            panic!("expected an atom");
+        };
+        assert_eq!(angle.as_i64(), Some(90));
+    }
+
     #[test]
     fn parse_symbols_from_library() {
         let input = "(kicad_symbol_lib (version 20231120) (symbol \"A\") (symbol \"B\"))";
@@ -620,6 +1499,43 @@ mod tests {
         assert!(!out.contains("new"));
     }
 
+    #[test]
+    fn add_symbol_relinks_existing_footprint() {
+        let input = "(kicad_symbol_lib (version 20231120) \
+                     (symbol \"A\" (property \"Value\" \"mine\") (property \"Footprint\" \"Old:FP\")))";
+        let mut lib = KicadSymbolLib::parse(input).unwrap();
+        let symbol = Symbol::parse(
+            "(symbol \"A\" (property \"Value\" \"vendor\") (property \"Footprint\" \"New:FP\"))",
+        )
+        .unwrap();
+        lib.add_symbol(symbol, AddPolicy::RelinkExisting).unwrap();
+        let symbol = lib.symbols().unwrap().remove(0);
+        assert_eq!(symbol.property_value("Value").unwrap(), "mine");
+        assert_eq!(symbol.property_value("Footprint").unwrap(), "New:FP");
+        assert_eq!("Relink".parse::<AddPolicy>(), Ok(AddPolicy::RelinkExisting));
+    }
+
+    #[test]
+    fn add_symbol_merges_non_empty_properties() {
+        let input = "(kicad_symbol_lib (version 20231120) \
+                     (symbol \"A\" (property \"Value\" \"A\") (property \"Datasheet\" \"old.pdf\") \
+                     (symbol \"A_0_1\" (rectangle (start -5 5) (end 5 -5)))))";
+        let mut lib = KicadSymbolLib::parse(input).unwrap();
+        let symbol = Symbol::parse(
+            "(symbol \"A\" (property \"Value\" \"\") (property \"Datasheet\" \"new.pdf\") \
+             (property \"MPN\" \"A-123\") (symbol \"A_0_1\" (circle (center 0 0) (radius 1))))",
+        )
+        .unwrap();
+        lib.add_symbol(symbol, AddPolicy::MergeProperties).unwrap();
+        let symbol = lib.symbols().unwrap().remove(0);
+        assert_eq!(symbol.property_value("Value").unwrap(), "A");
+        assert_eq!(symbol.property_value("Datasheet").unwrap(), "new.pdf");
+        assert_eq!(symbol.property_value("MPN").unwrap(), "A-123");
+        let out = lib.to_string_pretty();
+        assert!(out.contains("rectangle"));
+        assert!(!out.contains("circle"));
+    }
+
     #[test]
     fn add_symbol_errors_on_conflict() {
         let input = "(kicad_symbol_lib (version 20231120) (symbol \"A\"))";
@@ -646,6 +1562,90 @@ mod tests {
         assert_eq!(names, vec!["A", "B"]);
     }
 
+    #[test]
+    fn borrowed_symbols_edit_in_place() {
+        let input = "(kicad_symbol_lib (version 20231120) (symbol \"A\" (property \"Value\" \"A\")) (symbol \"B\"))";
+        let mut lib = KicadSymbolLib::parse(input).unwrap();
+        let names: Vec<&str> = lib.symbols_iter().map(Symbol::name).collect();
+        assert_eq!(names, ["A", "B"]);
+        assert!(lib.symbol("C").is_none());
+        lib.symbol_mut("A").unwrap().set_property_value("Value", "A2");
+        assert_eq!(
+            lib.symbol("A").unwrap().property_value("Value").as_deref(),
+            Some("A2")
+        );
+        let expected = parse_one(input)
+            .unwrap()
+            .to_string_pretty()
+            .replace("\"A\")", "A2)");
+        assert_eq!(lib.to_string_pretty(), expected);
+
+        let edited = lib.with_symbol_mut("B", |symbol| {
+            symbol.set_or_add_property("Value", "B2");
+            symbol.name().to_string()
+        });
+        assert_eq!(edited.as_deref(), Some("B"));
+        assert_eq!(
+            lib.symbol("B").unwrap().property_value("Value").as_deref(),
+            Some("B2")
+        );
+        assert!(lib.with_symbol_mut("C", |_| ()).is_none());
+    }
+
+    #[test]
+    fn removes_and_renames_symbols() {
+        let input = "(kicad_symbol_lib (version 20231120) (symbol \"OPA\" (property \"Value\" \"OPA\") (symbol \"OPA_0_1\") (symbol \"OPA_1_1\") (symbol \"OPA_extra\")) (symbol \"OPA2\" (extends \"OPA\")) (symbol \"OLD\"))";
+        let mut lib = KicadSymbolLib::parse(input).unwrap();
+        assert_eq!(lib.remove_symbol("OLD").unwrap().name(), "OLD");
+        assert!(lib.remove_symbol("OLD").is_none());
+
+        assert!(lib.delete_symbol("OPA").is_err());
+        assert!(lib.delete_symbol("OLD").is_err());
+
+        assert!(lib.rename_symbol("OPA", "OPA2").is_err());
+        assert!(lib.rename_symbol("MISSING", "X").is_err());
+        lib.rename_symbol("OPA", "TLV").unwrap();
+        let out = lib.to_string_pretty();
+        assert!(out.contains("\"TLV_0_1\""));
+        assert!(out.contains("\"TLV_1_1\""));
+        assert!(out.contains("\"OPA_extra\""));
+        assert!(out.contains("(extends \"TLV\")"));
+        assert!(!out.contains("OLD"));
+        let symbol = lib.symbol("TLV").unwrap();
+        assert_eq!(symbol.property_value("Value").as_deref(), Some("TLV"));
+    }
+
+    #[test]
+    fn merges_concatenated_libraries() {
+        let input = "(kicad_symbol_lib (version 20231120) (symbol \"A\"))\n\
+                     (kicad_symbol_lib (version 20211014) (symbol \"B\") (symbol \"A\"))\n\
+                     (generator kicad_symbol_editor)";
+        let lib = KicadSymbolLib::parse(input).unwrap();
+        let names: Vec<_> = lib
+            .symbols()
+            .unwrap()
+            .into_iter()
+            .map(|sym| sym.name().to_string())
+            .collect();
+        assert_eq!(names, vec!["A", "B"]);
+        assert_eq!(
+            lib.warnings(),
+            [
+                "merged a second kicad_symbol_lib expression",
+                "duplicate symbol A in merged library, keeping the first",
+                "ignored stray top-level expression: generator",
+            ]
+        );
+        assert!(KicadSymbolLib::parse("(kicad_sch (version 20231120))").is_err());
+        assert!(KicadSymbolLib::parse("").is_err());
+        assert!(
+            KicadSymbolLib::parse("(kicad_symbol_lib (symbol \"A\"))")
+                .unwrap()
+                .warnings()
+                .is_empty()
+        );
+    }
+
     #[test]
     fn parses_comments_and_quoted_names() {
         let input = "(kicad_symbol_lib\n; comment\n(symbol \"LM 2907-8\")\n# comment\n)";
@@ -669,6 +1669,49 @@ mod tests {
         assert_eq!(symbol.property_value("Footprint").unwrap(), "Lib:FP");
     }
 
+    #[test]
+    fn standard_properties_match_case_insensitively() {
+        let mut symbol = Symbol::parse(
+            "(symbol \"A\" (property \"footprint\" \"Old:FP\") (property \"ki_datasheet\" \"a.pdf\") (property \"mpn\" \"X\"))",
+        )
+        .unwrap();
+        assert_eq!(symbol.property_value("Footprint").unwrap(), "Old:FP");
+        assert_eq!(symbol.property_value("FOOTPRINT").unwrap(), "Old:FP");
+        assert_eq!(symbol.property_value("Datasheet").unwrap(), "a.pdf");
+        assert!(symbol.property_value("MPN").is_none());
+
+        symbol.set_or_add_property("Footprint", "Lib:FP");
+        symbol.set_property_value("datasheet", "b.pdf");
+        symbol.set_or_add_property("value", "A");
+        assert_eq!(
+            symbol.properties(),
+            vec![
+                ("Footprint".to_string(), "Lib:FP".to_string()),
+                ("Datasheet".to_string(), "b.pdf".to_string()),
+                ("mpn".to_string(), "X".to_string()),
+                ("Value".to_string(), "A".to_string()),
+            ]
+        );
+        assert!(symbol.remove_property("KI_FOOTPRINT"));
+        assert!(symbol.property_value("Footprint").is_none());
+        assert_eq!(canonical_property_name("ki_keywords"), "ki_keywords");
+    }
+
+    #[test]
+    fn keeps_properties_missing_from_the_replacement() {
+        let previous = Symbol::parse(
+            "(symbol \"A\" (property \"Value\" \"A\") (property \"Tolerance\" \"1%\" (at 0 0 0)) (property \"kci_tag:status\" \"approved\"))",
+        )
+        .unwrap();
+        let mut symbol =
+            Symbol::parse("(symbol \"A\" (property \"value\" \"B\") (property \"MPN\" \"X\"))").unwrap();
+        assert_eq!(symbol.keep_properties_from(&previous, "kci_tag:"), ["Tolerance"]);
+        assert_eq!(symbol.property_value("Value").unwrap(), "B");
+        assert_eq!(symbol.property_value("Tolerance").unwrap(), "1%");
+        assert!(symbol.property_value("kci_tag:status").is_none());
+        assert!(symbol.into_sexp().to_string_pretty().contains("(at 0 0 0)"));
+    }
+
     #[test]
     fn set_or_add_property_inserts_when_missing() {
         let mut symbol = Symbol::parse("(symbol \"A\")").unwrap();
@@ -676,4 +1719,23 @@ mod tests {
         symbol.set_or_add_property("Footprint", "Lib:FP");
         assert_eq!(symbol.property_value("Footprint").unwrap(), "Lib:FP");
     }
+
+    #[test]
+    fn hidden_properties_can_be_added_and_removed() {
+        let mut symbol = Symbol::parse("(symbol \"A\" (property \"Value\" \"A\"))").unwrap();
+        symbol.set_or_add_hidden_property("kci_tag:status", "approved");
+        let out = symbol.clone().into_sexp().to_string_pretty();
+        assert!(out.contains("\"kci_tag:status\"\n\t\t\"approved\""));
+        assert!(out.contains("\t\t\thide\n"));
+        assert_eq!(
+            symbol.properties(),
+            vec![
+                ("Value".to_string(), "A".to_string()),
+                ("kci_tag:status".to_string(), "approved".to_string()),
+            ]
+        );
+        assert!(symbol.remove_property("kci_tag:status"));
+        assert!(!symbol.remove_property("kci_tag:status"));
+        assert!(symbol.property_value("kci_tag:status").is_none());
+    }
 }
@@ -1,13 +1,18 @@
 use crate::importer::ImportConfig;
 use crate::kicad_sym::{parse_one, Atom, Sexp};
+use crate::sexp_record::SexpRecord;
 use std::error::Error;
 use std::fmt;
 use std::fs;
 use std::io;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
 
-#[derive(Debug, Clone, Copy)]
-enum TableKind {
+const DEFAULT_KICAD_VERSION: u32 = 8;
+const TOOL_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableKind {
     Symbol,
     Footprint,
 }
@@ -19,6 +24,13 @@ impl TableKind {
             TableKind::Footprint => "fp_lib_table",
         }
     }
+
+    pub fn file_name(self) -> &'static str {
+        match self {
+            TableKind::Symbol => "sym-lib-table",
+            TableKind::Footprint => "fp-lib-table",
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -51,38 +63,426 @@ pub fn ensure_project_tables(
     config: &ImportConfig,
 ) -> Result<(), TableError> {
     ensure_table(
-        &project_root.join("sym-lib-table"),
+        &project_root.join(TableKind::Symbol.file_name()),
         TableKind::Symbol,
         project_root,
         config.symbol_lib(),
+        config,
     )?;
     ensure_table(
-        &project_root.join("fp-lib-table"),
+        &project_root.join(TableKind::Footprint.file_name()),
         TableKind::Footprint,
         project_root,
         config.footprint_lib(),
+        config,
     )?;
     Ok(())
 }
 
+pub fn ensure_symbol_table(
+    project_root: &Path,
+    config: &ImportConfig,
+) -> Result<(), TableError> {
+    ensure_table(
+        &project_root.join(TableKind::Symbol.file_name()),
+        TableKind::Symbol,
+        project_root,
+        config.symbol_lib(),
+        config,
+    )
+}
+
+pub fn ensure_existing_project_tables(
+    project_root: &Path,
+    config: &ImportConfig,
+) -> Result<(), TableError> {
+    for (kind, lib_path) in [
+        (TableKind::Symbol, config.symbol_lib()),
+        (TableKind::Footprint, config.footprint_lib()),
+    ] {
+        if !project_root.join(lib_path).exists() {
+            continue;
+        }
+        ensure_table(
+            &project_root.join(kind.file_name()),
+            kind,
+            project_root,
+            lib_path,
+            config,
+        )?;
+    }
+    Ok(())
+}
+
+pub fn table_version_for(kicad_major: u32) -> u32 {
+    if kicad_major >= 9 { 8 } else { 7 }
+}
+
+fn table_version(config: &ImportConfig) -> u32 {
+    table_version_for(config.kicad_version().unwrap_or(DEFAULT_KICAD_VERSION))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TableIssue {
+    DuplicateEntry { name: String },
+    MissingLibrary { name: String, uri: String },
+    WrongType { name: String, found: String },
+    AbsolutePath { name: String, uri: String },
+}
+
+impl fmt::Display for TableIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TableIssue::DuplicateEntry { name } => write!(f, "duplicate entry {}", name),
+            TableIssue::MissingLibrary { name, uri } => {
+                write!(f, "entry {} points at missing library {}", name, uri)
+            }
+            TableIssue::WrongType { name, found } => {
+                write!(f, "entry {} has type {} instead of KiCad", name, found)
+            }
+            TableIssue::AbsolutePath { name, uri } => {
+                write!(f, "entry {} uses absolute path {}", name, uri)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TableFinding {
+    table: PathBuf,
+    issue: TableIssue,
+    fixed: bool,
+}
+
+impl TableFinding {
+    pub fn table(&self) -> &Path {
+        &self.table
+    }
+
+    pub fn issue(&self) -> &TableIssue {
+        &self.issue
+    }
+
+    pub fn fixed(&self) -> bool {
+        self.fixed
+    }
+}
+
+pub fn diagnose_project_tables(
+    project_root: &Path,
+    fix: bool,
+) -> Result<Vec<TableFinding>, TableError> {
+    let mut findings = Vec::new();
+    for kind in [TableKind::Symbol, TableKind::Footprint] {
+        let table_path = project_root.join(kind.file_name());
+        if !table_path.exists() {
+            continue;
+        }
+        let content = fs::read_to_string(&table_path)?;
+        let mut table = parse_table(&content, kind)?;
+        let issues = repair_table(&mut table, kind, project_root, fix);
+        if fix && !issues.is_empty() {
+            fs::write(&table_path, table.to_string_pretty_with_indent("  "))?;
+        }
+        findings.extend(issues.into_iter().map(|issue| TableFinding {
+            table: table_path.clone(),
+            issue,
+            fixed: fix,
+        }));
+    }
+    Ok(findings)
+}
+
+fn repair_table(
+    table: &mut Sexp,
+    kind: TableKind,
+    project_root: &Path,
+    fix: bool,
+) -> Vec<TableIssue> {
+    let mut issues = Vec::new();
+    let items = match list_items_mut(table) {
+        Ok(items) => items,
+        Err(_) => return issues,
+    };
+    let mut seen = Vec::new();
+    let mut remove = Vec::new();
+    for (idx, item) in items.iter_mut().enumerate().skip(1) {
+        let name = match lib_name(item) {
+            Some(name) => name.to_string(),
+            None => continue,
+        };
+        let uri = lib_child_value(item, "uri").unwrap_or_default().to_string();
+        if let Some(path) = resolve_uri(&uri, project_root)
+            && !path.exists()
+        {
+            issues.push(TableIssue::MissingLibrary {
+                name,
+                uri: uri.clone(),
+            });
+            remove.push(idx);
+            continue;
+        }
+        if seen.contains(&name) {
+            issues.push(TableIssue::DuplicateEntry { name });
+            remove.push(idx);
+            continue;
+        }
+        seen.push(name.clone());
+
+        let lib_type = lib_child_value(item, "type")
+            .unwrap_or_default()
+            .to_string();
+        if lib_type != "KiCad" && uri_is_kicad_format(&uri, kind) {
+            issues.push(TableIssue::WrongType {
+                name: name.clone(),
+                found: lib_type,
+            });
+            if fix && let Ok(lib_items) = list_items_mut(item) {
+                set_child_value(lib_items, "type", "KiCad");
+            }
+        }
+
+        let uri_path = Path::new(&uri);
+        if uri_path.is_absolute() && uri_path.starts_with(project_root) {
+            issues.push(TableIssue::AbsolutePath {
+                name,
+                uri: uri.clone(),
+            });
+            if fix && let Ok(lib_items) = list_items_mut(item) {
+                set_child_value(lib_items, "uri", &make_uri(uri_path, project_root));
+            }
+        }
+    }
+    if fix {
+        for idx in remove.into_iter().rev() {
+            items.remove(idx);
+        }
+    }
+    issues
+}
+
+fn resolve_uri(uri: &str, project_root: &Path) -> Option<PathBuf> {
+    if let Some(rest) = uri.strip_prefix("${KIPRJMOD}") {
+        let rest = rest.trim_start_matches(['/', '\\']);
+        return Some(project_root.join(rest));
+    }
+    if uri.contains("${") || uri.contains("://") || uri.is_empty() {
+        return None;
+    }
+    let path = Path::new(uri);
+    if path.is_absolute() {
+        Some(path.to_path_buf())
+    } else {
+        Some(project_root.join(path))
+    }
+}
+
+fn uri_is_kicad_format(uri: &str, kind: TableKind) -> bool {
+    let uri = uri.trim_end_matches(['/', '\\']);
+    match kind {
+        TableKind::Symbol => uri.ends_with(".kicad_sym"),
+        TableKind::Footprint => uri.ends_with(".pretty"),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShadowedLib {
+    kind: TableKind,
+    name: String,
+    global_uri: String,
+}
+
+impl ShadowedLib {
+    pub fn kind(&self) -> TableKind {
+        self.kind
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn global_uri(&self) -> &str {
+        &self.global_uri
+    }
+}
+
+impl fmt::Display for ShadowedLib {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "project library {} shadows global {} entry ({})",
+            self.name,
+            self.kind.file_name(),
+            self.global_uri
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableEntry {
+    kind: TableKind,
+    name: String,
+    uri: String,
+    path: Option<PathBuf>,
+}
+
+impl TableEntry {
+    pub fn kind(&self) -> TableKind {
+        self.kind
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+}
+
+impl fmt::Display for TableEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} -> {}", self.kind.file_name(), self.name, self.uri)
+    }
+}
+
+pub fn project_table_entries(
+    project_root: &Path,
+    kind: TableKind,
+) -> Result<Vec<TableEntry>, TableError> {
+    let table_path = project_root.join(kind.file_name());
+    if !table_path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&table_path)?;
+    let table = parse_table(&content, kind)?;
+    let items = match &table {
+        Sexp::List(items) => items,
+        _ => return Ok(Vec::new()),
+    };
+    Ok(items
+        .iter()
+        .filter_map(|item| {
+            let entry = LibEntry::from_sexp(item).ok()?;
+            let uri = entry.uri.unwrap_or_default();
+            Some(TableEntry {
+                kind,
+                name: entry.name,
+                path: resolve_uri(&uri, project_root),
+                uri,
+            })
+        })
+        .collect())
+}
+
+pub fn kicad_global_config_dir() -> Option<PathBuf> {
+    let base = if let Some(dir) = std::env::var_os("KICAD_CONFIG_HOME") {
+        PathBuf::from(dir)
+    } else if cfg!(target_os = "windows") {
+        PathBuf::from(std::env::var_os("APPDATA")?).join("kicad")
+    } else if cfg!(target_os = "macos") {
+        PathBuf::from(std::env::var_os("HOME")?)
+            .join("Library")
+            .join("Preferences")
+            .join("kicad")
+    } else if let Some(dir) = std::env::var_os("XDG_CONFIG_HOME") {
+        PathBuf::from(dir).join("kicad")
+    } else {
+        PathBuf::from(std::env::var_os("HOME")?)
+            .join(".config")
+            .join("kicad")
+    };
+    Some(latest_version_dir(&base).unwrap_or(base))
+}
+
+fn latest_version_dir(base: &Path) -> Option<PathBuf> {
+    let mut versions = Vec::new();
+    for entry in fs::read_dir(base).ok()?.flatten() {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let version = path
+            .file_name()
+            .and_then(|value| value.to_str())
+            .and_then(parse_version);
+        if let Some(version) = version {
+            versions.push((version, path));
+        }
+    }
+    versions.sort();
+    versions.pop().map(|(_, path)| path)
+}
+
+fn parse_version(value: &str) -> Option<(u32, u32)> {
+    let (major, minor) = value.split_once('.')?;
+    Some((major.parse().ok()?, minor.parse().ok()?))
+}
+
+pub fn find_shadowed_global_libs(
+    global_dir: &Path,
+    config: &ImportConfig,
+) -> Result<Vec<ShadowedLib>, TableError> {
+    let mut out = Vec::new();
+    for (kind, lib_path) in [
+        (TableKind::Symbol, config.symbol_lib()),
+        (TableKind::Footprint, config.footprint_lib()),
+    ] {
+        let table_path = global_dir.join(kind.file_name());
+        if !table_path.exists() {
+            continue;
+        }
+        let name = lib_name_from_path(kind, lib_path)?;
+        let content = fs::read_to_string(&table_path)?;
+        let table = parse_table(&content, kind)?;
+        let items = match &table {
+            Sexp::List(items) => items,
+            _ => continue,
+        };
+        if let Some(entry) = items
+            .iter()
+            .filter_map(|item| LibEntry::from_sexp(item).ok())
+            .find(|entry| entry.name == name)
+        {
+            out.push(ShadowedLib {
+                kind,
+                name,
+                global_uri: entry.uri.unwrap_or_default(),
+            });
+        }
+    }
+    Ok(out)
+}
+
 fn ensure_table(
     table_path: &Path,
     kind: TableKind,
     project_root: &Path,
     lib_path: &Path,
+    config: &ImportConfig,
 ) -> Result<(), TableError> {
     let lib_name = lib_name_from_path(kind, lib_path)?;
     let uri = make_uri(lib_path, project_root);
+    let version = table_version(config);
+    let date = today();
+    let fields = LibFields {
+        options: expand_lib_template(config.lib_options().unwrap_or_default(), &lib_name, &date),
+        descr: expand_lib_template(config.lib_descr().unwrap_or_default(), &lib_name, &date),
+    };
 
     let mut table = if table_path.exists() {
         let content = fs::read_to_string(table_path)?;
         parse_table(&content, kind)?
     } else {
-        default_table(kind)
+        default_table(kind, version)
     };
 
-    ensure_version(&mut table)?;
-    ensure_lib_entry(&mut table, &lib_name, &uri);
+    ensure_version(&mut table, version)?;
+    ensure_lib_entry(&mut table, &lib_name, &uri, &fields);
 
     let output = table.to_string_pretty_with_indent("  ");
     fs::write(table_path, output)?;
@@ -100,76 +500,136 @@ fn parse_table(input: &str, kind: TableKind) -> Result<Sexp, TableError> {
     Ok(sexp)
 }
 
-fn default_table(kind: TableKind) -> Sexp {
+fn default_table(kind: TableKind, version: u32) -> Sexp {
     Sexp::List(vec![
         Sexp::Atom(Atom::new(kind.root_name())),
         Sexp::List(vec![
             Sexp::Atom(Atom::new("version")),
-            Sexp::Atom(Atom::new("7")),
+            Sexp::Atom(Atom::new(version.to_string())),
         ]),
     ])
 }
 
-fn ensure_version(table: &mut Sexp) -> Result<(), TableError> {
+fn ensure_version(table: &mut Sexp, version: u32) -> Result<(), TableError> {
     let items = list_items_mut(table)?;
     for item in items.iter_mut().skip(1) {
-        if let Ok(list) = list_items_mut(item) {
-            if list.len() >= 2 && atom_value(&list[0]) == Some("version") {
-                return Ok(());
-            }
+        if let Ok(list) = list_items_mut(item)
+            && list.len() >= 2
+            && atom_value(&list[0]) == Some("version")
+        {
+            return Ok(());
         }
     }
     items.insert(
         1,
         Sexp::List(vec![
             Sexp::Atom(Atom::new("version")),
-            Sexp::Atom(Atom::new("7")),
+            Sexp::Atom(Atom::new(version.to_string())),
         ]),
     );
     Ok(())
 }
 
-fn ensure_lib_entry(table: &mut Sexp, name: &str, uri: &str) {
+struct LibFields {
+    options: String,
+    descr: String,
+}
+
+crate::sexp_record! {
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct LibEntry = "lib" {
+        ;
+        name: String = "name",
+        lib_type: Option<String> = opt "type",
+        uri: Option<String> = opt "uri",
+        options: Option<String> = opt "options",
+        descr: Option<String> = opt "descr",
+    }
+}
+
+fn expand_lib_template(template: &str, lib_name: &str, date: &str) -> String {
+    template
+        .replace("${DATE}", date)
+        .replace("${TOOL_VERSION}", TOOL_VERSION)
+        .replace("${LIB_NAME}", lib_name)
+}
+
+fn today() -> String {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() / 86_400)
+        .unwrap_or_default() as i64;
+    let (year, month, day) = civil_from_days(days);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+fn ensure_lib_entry(table: &mut Sexp, name: &str, uri: &str, fields: &LibFields) {
     let items = match list_items_mut(table) {
         Ok(items) => items,
         Err(_) => return,
     };
     for item in items.iter_mut() {
         if lib_name(item) == Some(name) {
-            update_lib(item, name, uri);
+            update_lib(item, name, uri, fields);
             return;
         }
     }
-    items.push(build_lib_entry(name, uri));
+    items.push(build_lib_entry(name, uri, fields));
 }
 
-fn build_lib_entry(name: &str, uri: &str) -> Sexp {
-    Sexp::List(vec![
-        Sexp::Atom(Atom::new("lib")),
-        Sexp::List(vec![
-            Sexp::Atom(Atom::new("name")),
-            Sexp::Atom(Atom::new_quoted(name)),
-        ]),
-        Sexp::List(vec![
-            Sexp::Atom(Atom::new("type")),
-            Sexp::Atom(Atom::new_quoted("KiCad")),
-        ]),
-        Sexp::List(vec![
-            Sexp::Atom(Atom::new("uri")),
-            Sexp::Atom(Atom::new_quoted(uri)),
-        ]),
-        Sexp::List(vec![
-            Sexp::Atom(Atom::new("options")),
-            Sexp::Atom(Atom::new_quoted("")),
-        ]),
-        Sexp::List(vec![
-            Sexp::Atom(Atom::new("descr")),
-            Sexp::Atom(Atom::new_quoted("")),
-        ]),
-    ])
+pub fn remove_project_lib(
+    project_root: &Path,
+    kind: TableKind,
+    name: &str,
+) -> Result<bool, TableError> {
+    let table_path = project_root.join(kind.file_name());
+    if !table_path.exists() {
+        return Ok(false);
+    }
+    let content = fs::read_to_string(&table_path)?;
+    let mut table = parse_table(&content, kind)?;
+    if !remove_lib_entry(&mut table, name) {
+        return Ok(false);
+    }
+    fs::write(&table_path, table.to_string_pretty_with_indent("  "))?;
+    Ok(true)
 }
 
-fn update_lib(sexp: &mut Sexp, name: &str, uri: &str) {
+fn remove_lib_entry(table: &mut Sexp, name: &str) -> bool {
+    let items = match list_items_mut(table) {
+        Ok(items) => items,
+        Err(_) => return false,
+    };
+    let before = items.len();
+    items.retain(|item| lib_name(item) != Some(name));
+    items.len() != before
+}
+
+fn build_lib_entry(name: &str, uri: &str, fields: &LibFields) -> Sexp {
+    LibEntry {
+        name: name.to_string(),
+        lib_type: Some("KiCad".to_string()),
+        uri: Some(uri.to_string()),
+        options: Some(fields.options.clone()),
+        descr: Some(fields.descr.clone()),
+    }
+    .to_sexp()
+}
+
+fn update_lib(sexp: &mut Sexp, name: &str, uri: &str, fields: &LibFields) {
     let items = match list_items_mut(sexp) {
         Ok(items) => items,
         Err(_) => return,
@@ -177,8 +637,92 @@ fn update_lib(sexp: &mut Sexp, name: &str, uri: &str) {
     set_child_value(items, "name", name);
     set_child_value(items, "type", "KiCad");
     set_child_value(items, "uri", uri);
-    set_child_value(items, "options", "");
-    set_child_value(items, "descr", "");
+    ensure_child_value(items, "options", &fields.options);
+    ensure_child_value(items, "descr", &fields.descr);
+}
+
+fn ensure_child_value(items: &mut Vec<Sexp>, key: &str, value: &str) {
+    let existing = items.iter_mut().skip(1).find_map(|item| match item {
+        Sexp::List(list) if list.first().and_then(atom_value) == Some(key) => Some(list),
+        _ => None,
+    });
+    if let Some(list) = existing {
+        if !value.is_empty() && list.get(1).and_then(atom_value).is_none_or(str::is_empty) {
+            list.truncate(1);
+            list.push(Sexp::Atom(Atom::new_quoted(value)));
+        }
+    } else {
+        items.push(Sexp::List(vec![
+            Sexp::Atom(Atom::new(key)),
+            Sexp::Atom(Atom::new_quoted(value)),
+        ]));
+    }
+}
+
+pub fn set_project_lib_disabled(
+    project_root: &Path,
+    kind: TableKind,
+    name: &str,
+    disabled: bool,
+) -> Result<bool, TableError> {
+    let table_path = project_root.join(kind.file_name());
+    if !table_path.exists() {
+        return Ok(false);
+    }
+    let content = fs::read_to_string(&table_path)?;
+    let mut table = parse_table(&content, kind)?;
+    let items = list_items_mut(&mut table)?;
+    let entry = match items.iter_mut().find(|item| lib_name(item) == Some(name)) {
+        Some(entry) => entry,
+        None => return Ok(false),
+    };
+    set_lib_flag(list_items_mut(entry)?, "disabled", disabled);
+    fs::write(&table_path, table.to_string_pretty_with_indent("  "))?;
+    Ok(true)
+}
+
+pub fn rename_project_lib(
+    project_root: &Path,
+    kind: TableKind,
+    old_name: &str,
+    new_name: &str,
+    new_path: &Path,
+) -> Result<bool, TableError> {
+    let table_path = project_root.join(kind.file_name());
+    if !table_path.exists() {
+        return Ok(false);
+    }
+    let content = fs::read_to_string(&table_path)?;
+    let mut table = parse_table(&content, kind)?;
+    let items = list_items_mut(&mut table)?;
+    if old_name != new_name && items.iter().any(|item| lib_name(item) == Some(new_name)) {
+        return Err(TableError::Invalid(format!(
+            "{} already has a library named {}",
+            kind.file_name(),
+            new_name
+        )));
+    }
+    let entry = match items.iter_mut().find(|item| lib_name(item) == Some(old_name)) {
+        Some(entry) => entry,
+        None => return Ok(false),
+    };
+    let entry_items = list_items_mut(entry)?;
+    set_child_value(entry_items, "name", new_name);
+    set_child_value(entry_items, "uri", &make_uri(new_path, project_root));
+    fs::write(&table_path, table.to_string_pretty_with_indent("  "))?;
+    Ok(true)
+}
+
+fn set_lib_flag(items: &mut Vec<Sexp>, flag: &str, enabled: bool) {
+    let is_flag = |item: &Sexp| {
+        matches!(item, Sexp::List(list) if list.first().and_then(atom_value) == Some(flag))
+    };
+    let present = items.iter().any(is_flag);
+    if enabled && !present {
+        items.push(Sexp::List(vec![Sexp::Atom(Atom::new(flag))]));
+    } else if !enabled {
+        items.retain(|item| !is_flag(item));
+    }
 }
 
 fn set_child_value(items: &mut Vec<Sexp>, key: &str, value: &str) {
@@ -199,18 +743,23 @@ fn set_child_value(items: &mut Vec<Sexp>, key: &str, value: &str) {
 }
 
 fn lib_name(sexp: &Sexp) -> Option<&str> {
+    lib_child_value(sexp, "name")
+}
+
+fn lib_child_value<'a>(sexp: &'a Sexp, key: &str) -> Option<&'a str> {
     let items = match sexp {
         Sexp::List(items) => items,
         _ => return None,
     };
-    if atom_value(&items[0]) != Some("lib") {
+    if items.first().and_then(atom_value) != Some("lib") {
         return None;
     }
     for item in items.iter().skip(1) {
-        if let Sexp::List(list) = item {
-            if list.len() >= 2 && atom_value(&list[0]) == Some("name") {
-                return atom_value(&list[1]);
-            }
+        if let Sexp::List(list) = item
+            && list.len() >= 2
+            && atom_value(&list[0]) == Some(key)
+        {
+            return atom_value(&list[1]);
         }
     }
     None
@@ -241,7 +790,7 @@ fn atom_value(sexp: &Sexp) -> Option<&str> {
     }
 }
 
-fn lib_name_from_path(kind: TableKind, path: &Path) -> Result<String, TableError> {
+pub fn lib_name_from_path(kind: TableKind, path: &Path) -> Result<String, TableError> {
     let name = match kind {
         TableKind::Symbol => path
             .file_stem()
@@ -282,7 +831,6 @@ fn make_uri(path: &Path, project_root: &Path) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::PathBuf;
     use tempfile::tempdir;
 
     #[test]
@@ -322,4 +870,277 @@ mod tests {
         let sym = fs::read_to_string(table_path).unwrap();
         assert!(sym.contains("${KIPRJMOD}/project_symbols.kicad_sym"));
     }
+
+    #[test]
+    fn doctor_reports_and_fixes_broken_entries() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("good.kicad_sym"), "").unwrap();
+        let absolute = dir.path().join("good.kicad_sym");
+        let table_path = dir.path().join("sym-lib-table");
+        fs::write(
+            &table_path,
+            format!(
+                "(sym_lib_table (version 7)\
+                 (lib (name \"good\")(type \"Legacy\")(uri \"{}\")(options \"\")(descr \"\"))\
+                 (lib (name \"good\")(type \"KiCad\")(uri \"${{KIPRJMOD}}/good.kicad_sym\")(options \"\")(descr \"\"))\
+                 (lib (name \"gone\")(type \"KiCad\")(uri \"${{KIPRJMOD}}/gone.kicad_sym\")(options \"\")(descr \"\"))\
+                 (lib (name \"std\")(type \"KiCad\")(uri \"${{KICAD8_SYMBOL_DIR}}/Device.kicad_sym\")(options \"\")(descr \"\")))",
+                absolute.display()
+            ),
+        )
+        .unwrap();
+
+        let findings = diagnose_project_tables(dir.path(), false).unwrap();
+        let issues: Vec<_> = findings
+            .iter()
+            .map(|finding| finding.issue().clone())
+            .collect();
+        assert_eq!(issues.len(), 4);
+        assert!(issues.contains(&TableIssue::DuplicateEntry {
+            name: "good".to_string()
+        }));
+        assert!(issues.iter().any(
+            |issue| matches!(issue, TableIssue::MissingLibrary { name, .. } if name == "gone")
+        ));
+        assert!(
+            issues
+                .iter()
+                .any(|issue| matches!(issue, TableIssue::WrongType { .. }))
+        );
+        assert!(
+            issues
+                .iter()
+                .any(|issue| matches!(issue, TableIssue::AbsolutePath { .. }))
+        );
+
+        let findings = diagnose_project_tables(dir.path(), true).unwrap();
+        assert!(findings.iter().all(|finding| finding.fixed()));
+        let repaired = fs::read_to_string(&table_path).unwrap();
+        assert_eq!(repaired.matches("(name \"good\")").count(), 1);
+        assert!(!repaired.contains("gone"));
+        assert!(repaired.contains("${KIPRJMOD}/good.kicad_sym"));
+        assert!(repaired.contains("KICAD8_SYMBOL_DIR"));
+        assert!(!repaired.contains("Legacy"));
+        assert!(diagnose_project_tables(dir.path(), false).unwrap().is_empty());
+    }
+
+    #[test]
+    fn doctor_keeps_the_valid_entry_of_a_duplicate_nickname() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("Parts.pretty")).unwrap();
+        let table_path = dir.path().join("fp-lib-table");
+        fs::write(
+            &table_path,
+            "(fp_lib_table (version 7)\
+             (lib (name \"Parts\")(type \"KiCad\")(uri \"${KIPRJMOD}/Old.pretty\")(options \"\")(descr \"\"))\
+             (lib (name \"Parts\")(type \"KiCad\")(uri \"${KIPRJMOD}/Parts.pretty\")(options \"\")(descr \"\")))",
+        )
+        .unwrap();
+
+        let findings = diagnose_project_tables(dir.path(), true).unwrap();
+        assert_eq!(findings.len(), 1);
+        assert!(matches!(
+            findings[0].issue(),
+            TableIssue::MissingLibrary { uri, .. } if uri.ends_with("Old.pretty")
+        ));
+        let entries = project_table_entries(dir.path(), TableKind::Footprint).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].uri(), "${KIPRJMOD}/Parts.pretty");
+    }
+
+    #[test]
+    fn removes_lib_entry_by_name() {
+        let dir = tempdir().unwrap();
+        let table_path = dir.path().join("fp-lib-table");
+        fs::write(
+            &table_path,
+            "(fp_lib_table (version 7) (lib (name \"keep\")(type \"KiCad\")(uri \"a.pretty\")) (lib (name \"drop\")(type \"KiCad\")(uri \"b.pretty\")))",
+        )
+        .unwrap();
+        assert!(remove_project_lib(dir.path(), TableKind::Footprint, "drop").unwrap());
+        assert!(!remove_project_lib(dir.path(), TableKind::Footprint, "drop").unwrap());
+        assert!(!remove_project_lib(dir.path(), TableKind::Symbol, "keep").unwrap());
+        let table = fs::read_to_string(table_path).unwrap();
+        assert!(table.contains("keep"));
+        assert!(!table.contains("drop"));
+    }
+
+    #[test]
+    fn update_preserves_unknown_fields() {
+        let dir = tempdir().unwrap();
+        let table_path = dir.path().join("sym-lib-table");
+        fs::write(
+            &table_path,
+            "(sym_lib_table (version 7) (lib (name \"project_symbols\")(type \"KiCad\")(uri \"${KIPRJMOD}/old.kicad_sym\")(options \"\")(descr \"Hand written\")(hidden)))",
+        )
+        .unwrap();
+        let config = ImportConfig::new(
+            PathBuf::from("project_symbols.kicad_sym"),
+            PathBuf::from("project_footprints.pretty"),
+            PathBuf::from("project_3d"),
+        );
+        ensure_project_tables(dir.path(), &config).unwrap();
+        let sym = fs::read_to_string(&table_path).unwrap();
+        assert!(sym.contains("${KIPRJMOD}/project_symbols.kicad_sym"));
+        assert!(sym.contains("\"Hand written\""));
+        assert!(sym.contains("(hidden)"));
+    }
+
+    #[test]
+    fn lib_entries_use_descr_and_options_templates() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(19_782), (2024, 2, 29));
+        let dir = tempdir().unwrap();
+        let table_path = dir.path().join("sym-lib-table");
+        fs::write(
+            &table_path,
+            "(sym_lib_table (version 7) (lib (name \"project_symbols\")(type \"KiCad\")(uri \"${KIPRJMOD}/old.kicad_sym\")(options \"\")(descr \"Hand written\")))",
+        )
+        .unwrap();
+        let config = ImportConfig::new(
+            PathBuf::from("project_symbols.kicad_sym"),
+            PathBuf::from("project_footprints.pretty"),
+            PathBuf::from("project_3d"),
+        )
+        .with_lib_descr(Some("${LIB_NAME} by kci ${TOOL_VERSION} on ${DATE}".to_string()))
+        .with_lib_options(Some("pinned".to_string()));
+        ensure_project_tables(dir.path(), &config).unwrap();
+
+        let sym = fs::read_to_string(&table_path).unwrap();
+        assert!(sym.contains("(descr \"Hand written\")"), "{}", sym);
+        assert!(sym.contains("(options \"pinned\")"), "{}", sym);
+        let fp = fs::read_to_string(dir.path().join("fp-lib-table")).unwrap();
+        let expected = format!(
+            "(descr \"project_footprints by kci {} on {}\")",
+            TOOL_VERSION,
+            today()
+        );
+        assert!(fp.contains(&expected), "{}", fp);
+    }
+
+    #[test]
+    fn update_preserves_comments() {
+        let dir = tempdir().unwrap();
+        let table_path = dir.path().join("sym-lib-table");
+        fs::write(
+            &table_path,
+            "# managed by hand, see wiki\n(sym_lib_table (version 7)\n  ; vendor parts\n  (lib (name \"project_symbols\")(type \"KiCad\")(uri \"${KIPRJMOD}/old.kicad_sym\")(options \"\")(descr \"\")))\n",
+        )
+        .unwrap();
+        let config = ImportConfig::new(
+            PathBuf::from("project_symbols.kicad_sym"),
+            PathBuf::from("project_footprints.pretty"),
+            PathBuf::from("project_3d"),
+        );
+        ensure_project_tables(dir.path(), &config).unwrap();
+        let sym = fs::read_to_string(&table_path).unwrap();
+        assert!(sym.contains("${KIPRJMOD}/project_symbols.kicad_sym"));
+        assert!(sym.contains("# managed by hand, see wiki\n"));
+        assert!(sym.contains("; vendor parts\n"));
+    }
+
+    #[test]
+    fn toggles_disabled_flag() {
+        let dir = tempdir().unwrap();
+        let table_path = dir.path().join("sym-lib-table");
+        fs::write(
+            &table_path,
+            "(sym_lib_table (version 7) (lib (name \"lib\")(type \"KiCad\")(uri \"lib.kicad_sym\")))",
+        )
+        .unwrap();
+        assert!(set_project_lib_disabled(dir.path(), TableKind::Symbol, "lib", true).unwrap());
+        assert!(set_project_lib_disabled(dir.path(), TableKind::Symbol, "lib", true).unwrap());
+        let sym = fs::read_to_string(&table_path).unwrap();
+        assert_eq!(sym.matches("(disabled)").count(), 1);
+        assert!(set_project_lib_disabled(dir.path(), TableKind::Symbol, "lib", false).unwrap());
+        assert!(!fs::read_to_string(&table_path).unwrap().contains("disabled"));
+        assert!(!set_project_lib_disabled(dir.path(), TableKind::Symbol, "other", true).unwrap());
+    }
+
+    #[test]
+    fn reads_and_writes_lib_entries_as_records() {
+        let fields = LibFields {
+            options: String::new(),
+            descr: "vendor parts".to_string(),
+        };
+        let built = build_lib_entry("lib", "${KIPRJMOD}/lib.kicad_sym", &fields);
+        assert_eq!(
+            built.to_string_pretty(),
+            parse_one(
+                "(lib (name \"lib\") (type \"KiCad\") (uri \"${KIPRJMOD}/lib.kicad_sym\") \
+                 (options \"\") (descr \"vendor parts\"))"
+            )
+            .unwrap()
+            .to_string_pretty()
+        );
+        let entry = LibEntry::from_sexp(&built).unwrap();
+        assert_eq!(entry.descr.as_deref(), Some("vendor parts"));
+
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("fp-lib-table"),
+            "(fp_lib_table (version 7) (lib (name \"Parts\")(uri \"Parts.pretty\")(disabled)) (lib (type \"KiCad\")))",
+        )
+        .unwrap();
+        let entries = project_table_entries(dir.path(), TableKind::Footprint).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name(), "Parts");
+        assert_eq!(entries[0].path(), Some(dir.path().join("Parts.pretty").as_path()));
+    }
+
+    #[test]
+    fn detects_shadowed_global_libraries() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("sym-lib-table"),
+            "(sym_lib_table (version 7) (lib (name \"Device\")(type \"KiCad\")(uri \"${KICAD8_SYMBOL_DIR}/Device.kicad_sym\")))",
+        )
+        .unwrap();
+        let config = ImportConfig::new(
+            PathBuf::from("Device.kicad_sym"),
+            PathBuf::from("project_footprints.pretty"),
+            PathBuf::from("project_3d"),
+        );
+        let shadowed = find_shadowed_global_libs(dir.path(), &config).unwrap();
+        assert_eq!(shadowed.len(), 1);
+        assert_eq!(shadowed[0].kind(), TableKind::Symbol);
+        assert_eq!(shadowed[0].name(), "Device");
+        assert_eq!(
+            shadowed[0].global_uri(),
+            "${KICAD8_SYMBOL_DIR}/Device.kicad_sym"
+        );
+    }
+
+    #[test]
+    fn picks_latest_versioned_config_dir() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("7.0")).unwrap();
+        fs::create_dir_all(dir.path().join("10.0")).unwrap();
+        fs::create_dir_all(dir.path().join("8.0")).unwrap();
+        assert_eq!(
+            latest_version_dir(dir.path()).unwrap(),
+            dir.path().join("10.0")
+        );
+    }
+
+    #[test]
+    fn table_version_follows_kicad_release() {
+        let dir = tempdir().unwrap();
+        let config = ImportConfig::new(
+            PathBuf::from("project_symbols.kicad_sym"),
+            PathBuf::from("project_footprints.pretty"),
+            PathBuf::from("project_3d"),
+        )
+        .with_kicad_version(Some(9));
+        ensure_project_tables(dir.path(), &config).unwrap();
+        let sym = fs::read_to_string(dir.path().join("sym-lib-table")).unwrap();
+        assert!(sym.contains("(version 8)"));
+
+        let table_path = dir.path().join("fp-lib-table");
+        fs::write(&table_path, "(fp_lib_table (version 6))").unwrap();
+        ensure_project_tables(dir.path(), &config).unwrap();
+        let fp = fs::read_to_string(table_path).unwrap();
+        assert!(fp.contains("(version 6)"));
+        assert!(!fp.contains("(version 8)"));
+    }
 }
@@ -0,0 +1,168 @@
+use crate::signing::Provenance;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const STATE_DIR: &str = ".kci";
+const LEDGER_FILE: &str = "ledger.toml";
+
+#[derive(Debug)]
+pub enum LedgerError {
+    Io(io::Error),
+    Parse(toml::de::Error),
+    Write(toml::ser::Error),
+}
+
+impl fmt::Display for LedgerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LedgerError::Io(err) => write!(f, "io error: {}", err),
+            LedgerError::Parse(err) => write!(f, "ledger parse error: {}", err),
+            LedgerError::Write(err) => write!(f, "ledger write error: {}", err),
+        }
+    }
+}
+
+impl Error for LedgerError {}
+
+impl From<io::Error> for LedgerError {
+    fn from(value: io::Error) -> Self {
+        LedgerError::Io(value)
+    }
+}
+
+impl From<toml::de::Error> for LedgerError {
+    fn from(value: toml::de::Error) -> Self {
+        LedgerError::Parse(value)
+    }
+}
+
+impl From<toml::ser::Error> for LedgerError {
+    fn from(value: toml::ser::Error) -> Self {
+        LedgerError::Write(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FootprintRecord {
+    name: String,
+    #[serde(default)]
+    models: Vec<String>,
+}
+
+impl FootprintRecord {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn models(&self) -> &[String] {
+        &self.models
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Ledger {
+    #[serde(default)]
+    provenance: Option<Provenance>,
+    #[serde(default, rename = "footprint")]
+    footprints: Vec<FootprintRecord>,
+}
+
+impl Ledger {
+    pub fn path(project_root: &Path) -> PathBuf {
+        project_root.join(STATE_DIR).join(LEDGER_FILE)
+    }
+
+    pub fn load(project_root: &Path) -> Result<Self, LedgerError> {
+        let path = Self::path(project_root);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let raw = fs::read_to_string(path)?;
+        Ok(toml::from_str(&raw)?)
+    }
+
+    pub fn save(&self, project_root: &Path) -> Result<(), LedgerError> {
+        let path = Self::path(project_root);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn set_provenance(&mut self, provenance: Provenance) {
+        self.provenance = Some(provenance);
+    }
+
+    pub fn provenance(&self) -> Option<&Provenance> {
+        self.provenance.as_ref()
+    }
+
+    pub fn footprints(&self) -> &[FootprintRecord] {
+        &self.footprints
+    }
+
+    pub fn record_footprint(&mut self, name: &str, models: Vec<String>) {
+        let record = FootprintRecord {
+            name: name.to_string(),
+            models,
+        };
+        match self.footprints.iter_mut().find(|entry| entry.name == name) {
+            Some(entry) => *entry = record,
+            None => self.footprints.push(record),
+        }
+    }
+
+    pub fn remove_footprint(&mut self, name: &str) -> Option<FootprintRecord> {
+        let idx = self
+            .footprints
+            .iter()
+            .position(|entry| entry.name == name)?;
+        Some(self.footprints.remove(idx))
+    }
+
+    pub fn models_for(&self, footprint: &str) -> &[String] {
+        self.footprints
+            .iter()
+            .find(|entry| entry.name == footprint)
+            .map(|entry| entry.models.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn footprints_using(&self, model: &str) -> Vec<&str> {
+        self.footprints
+            .iter()
+            .filter(|entry| entry.models.iter().any(|path| path == model))
+            .map(|entry| entry.name.as_str())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn records_replace_and_round_trip() {
+        let dir = tempdir().unwrap();
+        let mut ledger = Ledger::default();
+        ledger.record_footprint("SOIC-8", vec!["3d/old.step".to_string()]);
+        ledger.record_footprint("SOIC-8", vec!["3d/soic8.step".to_string()]);
+        ledger.record_footprint("SOT-23", vec!["3d/soic8.step".to_string()]);
+        ledger.save(dir.path()).unwrap();
+
+        let loaded = Ledger::load(dir.path()).unwrap();
+        assert_eq!(loaded.footprints().len(), 2);
+        assert_eq!(loaded.models_for("SOIC-8"), ["3d/soic8.step".to_string()]);
+        assert_eq!(
+            loaded.footprints_using("3d/soic8.step"),
+            vec!["SOIC-8", "SOT-23"]
+        );
+        assert!(loaded.models_for("missing").is_empty());
+    }
+}
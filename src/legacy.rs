@@ -0,0 +1,1072 @@
+use crate::kicad_sym::{
+    AddPolicy, KicadSymError, KicadSymbolLib, SCHEMATIC_PRECISION, Symbol, format_number,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+const SYMBOL_HEADER: &str = "EESchema-LIBRARY";
+const FOOTPRINT_HEADER: &str = "PCBNEW-LibModule";
+const MM_PER_MIL: f64 = 0.0254;
+const MM_PER_DECIMIL: f64 = 0.00254;
+
+#[derive(Debug)]
+pub enum LegacyError {
+    Io(io::Error),
+    Symbol(KicadSymError),
+    Invalid(String),
+}
+
+impl fmt::Display for LegacyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LegacyError::Io(err) => write!(f, "io error: {}", err),
+            LegacyError::Symbol(err) => write!(f, "legacy symbol conversion error: {}", err),
+            LegacyError::Invalid(msg) => write!(f, "invalid legacy KiCad library: {}", msg),
+        }
+    }
+}
+
+impl Error for LegacyError {}
+
+impl From<io::Error> for LegacyError {
+    fn from(value: io::Error) -> Self {
+        LegacyError::Io(value)
+    }
+}
+
+impl From<KicadSymError> for LegacyError {
+    fn from(value: KicadSymError) -> Self {
+        LegacyError::Symbol(value)
+    }
+}
+
+type Footprint = (String, String);
+
+pub fn is_symbol_library(path: &Path) -> bool {
+    has_header(path, "lib", SYMBOL_HEADER)
+}
+
+pub fn is_footprint_library(path: &Path) -> bool {
+    has_header(path, "mod", FOOTPRINT_HEADER)
+}
+
+pub fn find_symbol_libraries(root: &Path) -> Vec<PathBuf> {
+    find_libraries(root, is_symbol_library)
+}
+
+pub fn find_footprint_libraries(root: &Path) -> Vec<PathBuf> {
+    find_libraries(root, is_footprint_library)
+}
+
+fn find_libraries(root: &Path, check: fn(&Path) -> bool) -> Vec<PathBuf> {
+    WalkDir::new(root)
+        .into_iter()
+        .flatten()
+        .filter(|entry| entry.file_type().is_file() && check(entry.path()))
+        .map(|entry| entry.path().to_path_buf())
+        .collect()
+}
+
+fn has_header(path: &Path, extension: &str, header: &str) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(extension))
+        && fs::read(path).is_ok_and(|bytes| {
+            String::from_utf8_lossy(&bytes[..bytes.len().min(64)])
+                .trim_start_matches('\u{feff}')
+                .starts_with(header)
+        })
+}
+
+pub fn convert_symbol_library(path: &Path) -> Result<(KicadSymbolLib, Vec<String>), LegacyError> {
+    let content = fs::read_to_string(path)?;
+    if !content.trim_start().starts_with(SYMBOL_HEADER) {
+        return Err(LegacyError::Invalid(format!(
+            "{} is not an EESchema library",
+            path.display()
+        )));
+    }
+    let docs = fs::read_to_string(path.with_extension("dcm"))
+        .map(|content| parse_docs(&content))
+        .unwrap_or_default();
+
+    let mut lib = KicadSymbolLib::parse("(kicad_symbol_lib (version 20231120) (generator kci))")?;
+    let mut warnings = Vec::new();
+    let mut lines = content.lines();
+    while let Some(line) = lines.next() {
+        let tokens = tokenize(line);
+        if tokens.first().map(String::as_str) != Some("DEF") {
+            continue;
+        }
+        let mut body = Vec::new();
+        for line in lines.by_ref() {
+            if line.trim() == "ENDDEF" {
+                break;
+            }
+            body.push(line);
+        }
+        let (symbols, skipped) = legacy_symbol(&tokens, &body, &docs)
+            .ok_or_else(|| LegacyError::Invalid(format!("malformed definition: {}", line)))?;
+        for symbol in symbols {
+            let symbol = Symbol::parse(&symbol)?;
+            if lib.symbol(symbol.name()).is_some() {
+                warnings.push(format!(
+                    "duplicate symbol {}, keeping the first",
+                    symbol.name()
+                ));
+                continue;
+            }
+            lib.add_symbol(symbol, AddPolicy::ErrorOnConflict)?;
+        }
+        warnings.extend(skipped);
+    }
+    Ok((lib, warnings))
+}
+
+pub fn convert_footprint_library(
+    path: &Path,
+) -> Result<(Vec<Footprint>, Vec<String>), LegacyError> {
+    let content = fs::read_to_string(path)?;
+    if !content.trim_start().starts_with(FOOTPRINT_HEADER) {
+        return Err(LegacyError::Invalid(format!(
+            "{} is not a PCBNEW module library",
+            path.display()
+        )));
+    }
+    let scale = if content
+        .lines()
+        .take_while(|line| !line.starts_with("$MODULE"))
+        .any(|line| tokenize(line) == ["Units", "mm"])
+    {
+        1.0
+    } else {
+        MM_PER_DECIMIL
+    };
+
+    let mut footprints = Vec::new();
+    let mut warnings = Vec::new();
+    let mut lines = content.lines();
+    while let Some(line) = lines.next() {
+        let Some(name) = line.strip_prefix("$MODULE ") else {
+            continue;
+        };
+        let mut body = Vec::new();
+        for line in lines.by_ref() {
+            if line.starts_with("$EndMODULE") {
+                break;
+            }
+            body.push(line);
+        }
+        let name = name.trim().to_string();
+        footprints.push((
+            name.clone(),
+            legacy_footprint(&name, &body, scale, &mut warnings),
+        ));
+    }
+    Ok((footprints, warnings))
+}
+
+struct Doc {
+    description: Option<String>,
+    keywords: Option<String>,
+    datasheet: Option<String>,
+}
+
+fn parse_docs(content: &str) -> HashMap<String, Doc> {
+    let mut docs = HashMap::new();
+    let mut current: Option<(String, Doc)> = None;
+    for line in content.lines() {
+        if let Some(name) = line.strip_prefix("$CMP ") {
+            current = Some((
+                name.trim().to_string(),
+                Doc {
+                    description: None,
+                    keywords: None,
+                    datasheet: None,
+                },
+            ));
+        } else if line.starts_with("$ENDCMP") {
+            if let Some((name, doc)) = current.take() {
+                docs.insert(name, doc);
+            }
+        } else if let Some((_, doc)) = current.as_mut() {
+            let text = line.get(2..).map(|text| text.trim().to_string());
+            match line.get(..2) {
+                Some("D ") => doc.description = text,
+                Some("K ") => doc.keywords = text,
+                Some("F ") => doc.datasheet = text,
+                _ => {}
+            }
+        }
+    }
+    docs
+}
+
+fn legacy_symbol(
+    def: &[String],
+    body: &[&str],
+    docs: &HashMap<String, Doc>,
+) -> Option<(Vec<String>, Vec<String>)> {
+    let name = def.get(1)?.trim_start_matches('~').to_string();
+    let pin_offset: f64 = def.get(4)?.parse().ok()?;
+    let show_numbers = def.get(5).map(String::as_str) != Some("N");
+    let show_names = def.get(6).map(String::as_str) != Some("N");
+    let power = def.get(9).map(String::as_str) == Some("P");
+
+    let mut properties: Vec<(String, Vec<String>)> = Vec::new();
+    let mut aliases = Vec::new();
+    let mut filters = Vec::new();
+    let mut units: BTreeMap<(i64, i64), Vec<String>> = BTreeMap::new();
+    let mut skipped = Vec::new();
+    let mut in_filters = false;
+    for line in body {
+        let tokens = tokenize(line);
+        let Some(kind) = tokens.first().map(String::as_str) else {
+            continue;
+        };
+        if in_filters {
+            if kind == "$ENDFPLIST" {
+                in_filters = false;
+            } else {
+                filters.extend(tokens.iter().cloned());
+            }
+            continue;
+        }
+        match kind {
+            "$FPLIST" => in_filters = true,
+            "ALIAS" => aliases.extend(tokens[1..].iter().cloned()),
+            "DRAW" | "ENDDRAW" => {}
+            _ if kind.starts_with('F') && kind[1..].parse::<usize>().is_ok() => {
+                let index: usize = kind[1..].parse().ok()?;
+                let key = match index {
+                    0 => "Reference".to_string(),
+                    1 => "Value".to_string(),
+                    2 => "Footprint".to_string(),
+                    3 => "Datasheet".to_string(),
+                    _ => match tokens.get(9) {
+                        Some(key) => key.clone(),
+                        None => continue,
+                    },
+                };
+                properties.push((key, tokens));
+            }
+            _ => {
+                let unit = |idx: usize| -> (i64, i64) {
+                    let unit = tokens.get(idx).and_then(|v| v.parse().ok()).unwrap_or(0);
+                    let convert = tokens
+                        .get(idx + 1)
+                        .and_then(|v| v.parse().ok())
+                        .filter(|convert| *convert > 0)
+                        .unwrap_or(1);
+                    (unit, convert)
+                };
+                let (key, item) = match kind {
+                    "S" => (unit(5), rectangle(&tokens)),
+                    "P" => (unit(2), polyline(&tokens)),
+                    "C" => (unit(4), circle(&tokens)),
+                    "A" => (unit(6), arc(&tokens)),
+                    "T" => (unit(6), text(&tokens)),
+                    "X" => (unit(9), pin(&tokens)),
+                    _ => {
+                        skipped.push(format!("{}: skipped unsupported drawing {}", name, kind));
+                        continue;
+                    }
+                };
+                match item {
+                    Some(item) => units.entry(key).or_default().push(item),
+                    None => skipped.push(format!("{}: skipped malformed {} line", name, kind)),
+                }
+            }
+        }
+    }
+
+    let doc = docs.get(&name);
+    let datasheet = doc
+        .and_then(|doc| doc.datasheet.as_deref())
+        .filter(|url| !url.is_empty());
+    let mut extra = Vec::new();
+    if let Some(doc) = doc {
+        if let Some(description) = &doc.description {
+            extra.push(("Description", description.clone()));
+        }
+        if let Some(keywords) = &doc.keywords {
+            extra.push(("ki_keywords", keywords.clone()));
+        }
+    }
+    if !filters.is_empty() {
+        extra.push(("ki_fp_filters", filters.join(" ")));
+    }
+
+    let render = |value_override: Option<&str>| {
+        properties
+            .iter()
+            .map(|(key, tokens)| {
+                let value = match key.as_str() {
+                    "Value" => value_override,
+                    "Datasheet" if matches!(tokens.get(1).map(String::as_str), Some("" | "~")) => {
+                        datasheet
+                    }
+                    _ => None,
+                };
+                field(key, value, tokens)
+            })
+            .collect::<Option<Vec<_>>>()
+    };
+
+    let mut out = format!("(symbol {}", quote(&name));
+    if power {
+        out.push_str(" (power)");
+    }
+    if !show_numbers {
+        out.push_str(" (pin_numbers hide)");
+    }
+    out.push_str(&format!(" (pin_names (offset {})", mm(pin_offset)));
+    if !show_names {
+        out.push_str(" hide");
+    }
+    out.push_str(") (in_bom yes) (on_board yes)");
+    for property in render(None)? {
+        out.push(' ');
+        out.push_str(&property);
+    }
+    for (key, value) in &extra {
+        out.push_str(&format!(
+            " (property {} {} (at 0 0 0) (effects (font (size 1.27 1.27)) hide))",
+            quote(key),
+            quote(value)
+        ));
+    }
+    for ((unit, convert), items) in units {
+        out.push_str(&format!(
+            " (symbol {} {})",
+            quote(&format!("{}_{}_{}", name, unit, convert)),
+            items.join(" ")
+        ));
+    }
+    out.push(')');
+
+    let mut symbols = vec![out];
+    for alias in aliases {
+        let mut derived = format!("(symbol {} (extends {})", quote(&alias), quote(&name));
+        for property in render(Some(&alias))? {
+            derived.push(' ');
+            derived.push_str(&property);
+        }
+        derived.push(')');
+        symbols.push(derived);
+    }
+    Some((symbols, skipped))
+}
+
+fn field(key: &str, value: Option<&str>, tokens: &[String]) -> Option<String> {
+    let value = value.or(tokens.get(1).map(String::as_str))?;
+    let (x, y) = (coord(tokens.get(2)?)?, coord(tokens.get(3)?)?);
+    let size = coord(tokens.get(4)?)?;
+    let angle = if tokens.get(5).map(String::as_str) == Some("V") {
+        90
+    } else {
+        0
+    };
+    let hidden = tokens.get(6).map(String::as_str) == Some("I");
+    let mut justify = Vec::new();
+    match tokens.get(7).map(String::as_str) {
+        Some("L") => justify.push("left"),
+        Some("R") => justify.push("right"),
+        _ => {}
+    }
+    match tokens.get(8).and_then(|style| style.chars().next()) {
+        Some('T') => justify.push("top"),
+        Some('B') => justify.push("bottom"),
+        _ => {}
+    }
+    let justify = if justify.is_empty() {
+        String::new()
+    } else {
+        format!(" (justify {})", justify.join(" "))
+    };
+    Some(format!(
+        "(property {} {} (at {} {} {}) (effects (font (size {} {})){}{}))",
+        quote(key),
+        quote(value),
+        mm(x),
+        mm(y),
+        angle,
+        mm(size),
+        mm(size),
+        justify,
+        if hidden { " hide" } else { "" }
+    ))
+}
+
+fn rectangle(tokens: &[String]) -> Option<String> {
+    Some(format!(
+        "(rectangle (start {} {}) (end {} {}) {} {})",
+        mm(coord(tokens.get(1)?)?),
+        mm(coord(tokens.get(2)?)?),
+        mm(coord(tokens.get(3)?)?),
+        mm(coord(tokens.get(4)?)?),
+        stroke(tokens.get(7)),
+        fill(tokens.get(8))
+    ))
+}
+
+fn polyline(tokens: &[String]) -> Option<String> {
+    let count: usize = tokens.get(1)?.parse().ok()?;
+    let points = (0..count)
+        .map(|idx| {
+            Some(format!(
+                "(xy {} {})",
+                mm(coord(tokens.get(5 + idx * 2)?)?),
+                mm(coord(tokens.get(6 + idx * 2)?)?)
+            ))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(format!(
+        "(polyline (pts {}) {} {})",
+        points.join(" "),
+        stroke(tokens.get(4)),
+        fill(tokens.get(5 + count * 2))
+    ))
+}
+
+fn circle(tokens: &[String]) -> Option<String> {
+    Some(format!(
+        "(circle (center {} {}) (radius {}) {} {})",
+        mm(coord(tokens.get(1)?)?),
+        mm(coord(tokens.get(2)?)?),
+        mm(coord(tokens.get(3)?)?),
+        stroke(tokens.get(6)),
+        fill(tokens.get(7))
+    ))
+}
+
+fn arc(tokens: &[String]) -> Option<String> {
+    let (cx, cy, radius) = (
+        coord(tokens.get(1)?)?,
+        coord(tokens.get(2)?)?,
+        coord(tokens.get(3)?)?,
+    );
+    let start: f64 = tokens.get(4)?.parse::<f64>().ok()? / 10.0;
+    let end: f64 = tokens.get(5)?.parse::<f64>().ok()? / 10.0;
+    let mut mid = (start + end) / 2.0;
+    if (end - start).abs() > 180.0 {
+        mid += 180.0;
+    }
+    let at = |degrees: f64| {
+        let radians = degrees.to_radians();
+        format!(
+            "{} {}",
+            mm(cx + radius * radians.cos()),
+            mm(cy + radius * radians.sin())
+        )
+    };
+    Some(format!(
+        "(arc (start {}) (mid {}) (end {}) {} {})",
+        at(start),
+        at(mid),
+        at(end),
+        stroke(tokens.get(8)),
+        fill(tokens.get(9))
+    ))
+}
+
+fn text(tokens: &[String]) -> Option<String> {
+    let angle = tokens.get(1)?.parse::<f64>().ok()? / 10.0;
+    let size = coord(tokens.get(4)?)?;
+    Some(format!(
+        "(text {} (at {} {} {}) (effects (font (size {} {})){}))",
+        quote(&tokens.get(8)?.replace('~', " ")),
+        mm(coord(tokens.get(2)?)?),
+        mm(coord(tokens.get(3)?)?),
+        angle,
+        mm(size),
+        mm(size),
+        if tokens.get(5).map(String::as_str) == Some("1") {
+            " hide"
+        } else {
+            ""
+        }
+    ))
+}
+
+fn pin(tokens: &[String]) -> Option<String> {
+    let angle = match tokens.get(6)?.as_str() {
+        "U" => 90,
+        "L" => 180,
+        "D" => 270,
+        _ => 0,
+    };
+    let electric = match tokens.get(11).map(String::as_str) {
+        Some("I") => "input",
+        Some("O") => "output",
+        Some("B") => "bidirectional",
+        Some("T") => "tri_state",
+        Some("U") => "unspecified",
+        Some("W") => "power_in",
+        Some("w") => "power_out",
+        Some("C") => "open_collector",
+        Some("E") => "open_emitter",
+        Some("N") => "no_connect",
+        _ => "passive",
+    };
+    let shape_flags = tokens.get(12).map(String::as_str).unwrap_or_default();
+    let hidden = shape_flags.contains('N');
+    let shape = match shape_flags.replace('N', "").as_str() {
+        "I" => "inverted",
+        "C" => "clock",
+        "IC" | "CI" => "inverted_clock",
+        "L" => "input_low",
+        "CL" => "clock_low",
+        "V" => "output_low",
+        "F" => "edge_clock_high",
+        "X" => "non_logic",
+        _ => "line",
+    };
+    let label = |value: &str| {
+        if value == "~" {
+            String::new()
+        } else {
+            value.to_string()
+        }
+    };
+    Some(format!(
+        "(pin {} {} (at {} {} {}) (length {}){} (name {} (effects (font (size {} {})))) (number {} (effects (font (size {} {})))))",
+        electric,
+        shape,
+        mm(coord(tokens.get(3)?)?),
+        mm(coord(tokens.get(4)?)?),
+        angle,
+        mm(coord(tokens.get(5)?)?),
+        if hidden { " hide" } else { "" },
+        quote(&label(tokens.get(1)?)),
+        mm(coord(tokens.get(8)?)?),
+        mm(coord(tokens.get(8)?)?),
+        quote(&label(tokens.get(2)?)),
+        mm(coord(tokens.get(7)?)?),
+        mm(coord(tokens.get(7)?)?)
+    ))
+}
+
+fn stroke(width: Option<&String>) -> String {
+    let width = width.and_then(|width| coord(width)).unwrap_or(0.0);
+    format!("(stroke (width {}) (type default))", mm(width))
+}
+
+fn fill(kind: Option<&String>) -> &'static str {
+    match kind.map(String::as_str) {
+        Some("F") => "(fill (type outline))",
+        Some("f") => "(fill (type background))",
+        _ => "(fill (type none))",
+    }
+}
+
+fn coord(value: &str) -> Option<f64> {
+    value.parse::<f64>().ok().map(|mils| mils * MM_PER_MIL)
+}
+
+fn legacy_footprint(name: &str, body: &[&str], scale: f64, warnings: &mut Vec<String>) -> String {
+    let length = |value: Option<&String>| {
+        value
+            .and_then(|value| value.parse::<f64>().ok())
+            .map(|value| value * scale)
+            .unwrap_or(0.0)
+    };
+    let point = |tokens: &[String], idx: usize| {
+        format!(
+            "{} {}",
+            mm(length(tokens.get(idx))),
+            mm(length(tokens.get(idx + 1)))
+        )
+    };
+
+    let mut layer = "F.Cu";
+    let mut header = Vec::new();
+    let mut smd = false;
+    let mut through_hole = false;
+    let mut items = Vec::new();
+    let mut lines = body.iter();
+    while let Some(line) = lines.next() {
+        let tokens = tokenize(line);
+        let Some(kind) = tokens.first().map(String::as_str) else {
+            continue;
+        };
+        let rest = line.get(3..).unwrap_or_default().trim();
+        match kind {
+            "Po" if tokens.get(4).map(String::as_str) == Some("0") => layer = "B.Cu",
+            "Cd" => header.push(format!("(descr {})", quote(rest))),
+            "Kw" => header.push(format!("(tags {})", quote(rest))),
+            "At" => smd |= tokens.get(1).map(String::as_str) == Some("SMD"),
+            _ if kind.starts_with('T') && kind[1..].parse::<usize>().is_ok() => {
+                let role = match kind {
+                    "T0" => "reference",
+                    "T1" => "value",
+                    _ => "user",
+                };
+                let text = if role == "reference" {
+                    "REF**".to_string()
+                } else {
+                    tokens.get(11).cloned().unwrap_or_default()
+                };
+                let angle = tokens
+                    .get(5)
+                    .and_then(|angle| angle.parse::<f64>().ok())
+                    .unwrap_or(0.0)
+                    / 10.0;
+                let rotation = if angle != 0.0 {
+                    format!(" {}", angle)
+                } else {
+                    String::new()
+                };
+                items.push(format!(
+                    "(fp_text {} {} (at {}{}) (layer {}){} (effects (font (size {} {}) (thickness {}))))",
+                    role,
+                    quote(&text),
+                    point(&tokens, 1),
+                    rotation,
+                    quote(board_layer(tokens.get(9)).unwrap_or("F.SilkS")),
+                    if tokens.get(8).map(String::as_str) == Some("I") {
+                        " hide"
+                    } else {
+                        ""
+                    },
+                    mm(length(tokens.get(4))),
+                    mm(length(tokens.get(3))),
+                    mm(length(tokens.get(6)))
+                ));
+            }
+            "DS" | "DC" | "DA" => {
+                let (width, layer_idx) = if kind == "DA" { (6, 7) } else { (5, 6) };
+                let Some(layer) = board_layer(tokens.get(layer_idx)) else {
+                    warnings.push(format!("{}: skipped {} on an unknown layer", name, kind));
+                    continue;
+                };
+                let stroke = format!(
+                    "(stroke (width {}) (type solid)) (layer {})",
+                    mm(length(tokens.get(width))),
+                    quote(layer)
+                );
+                items.push(match kind {
+                    "DS" => format!(
+                        "(fp_line (start {}) (end {}) {})",
+                        point(&tokens, 1),
+                        point(&tokens, 3),
+                        stroke
+                    ),
+                    "DC" => format!(
+                        "(fp_circle (center {}) (end {}) {} (fill none))",
+                        point(&tokens, 1),
+                        point(&tokens, 3),
+                        stroke
+                    ),
+                    _ => {
+                        let (cx, cy) = (length(tokens.get(1)), length(tokens.get(2)));
+                        let (sx, sy) = (length(tokens.get(3)) - cx, length(tokens.get(4)) - cy);
+                        let angle = tokens
+                            .get(5)
+                            .and_then(|angle| angle.parse::<f64>().ok())
+                            .unwrap_or(0.0)
+                            / 10.0;
+                        let rotate = |degrees: f64| {
+                            let (sin, cos) = degrees.to_radians().sin_cos();
+                            format!(
+                                "{} {}",
+                                mm(cx + sx * cos - sy * sin),
+                                mm(cy + sx * sin + sy * cos)
+                            )
+                        };
+                        format!(
+                            "(fp_arc (start {}) (mid {}) (end {}) {})",
+                            rotate(0.0),
+                            rotate(angle / 2.0),
+                            rotate(angle),
+                            stroke
+                        )
+                    }
+                });
+            }
+            "DP" => {
+                let count: usize = tokens
+                    .get(5)
+                    .and_then(|count| count.parse().ok())
+                    .unwrap_or(0);
+                let points: Vec<String> = lines
+                    .by_ref()
+                    .take(count)
+                    .map(|line| format!("(xy {})", point(&tokenize(line), 1)))
+                    .collect();
+                let Some(layer) = board_layer(tokens.get(7)) else {
+                    warnings.push(format!("{}: skipped polygon on an unknown layer", name));
+                    continue;
+                };
+                items.push(format!(
+                    "(fp_poly (pts {}) (stroke (width {}) (type solid)) (fill solid) (layer {}))",
+                    points.join(" "),
+                    mm(length(tokens.get(6))),
+                    quote(layer)
+                ));
+            }
+            "$PAD" => {
+                let pad: Vec<Vec<String>> = lines
+                    .by_ref()
+                    .take_while(|line| !line.starts_with("$EndPAD"))
+                    .map(|line| tokenize(line))
+                    .collect();
+                match legacy_pad(&pad, &length) {
+                    Some((pad, plated)) => {
+                        through_hole |= plated;
+                        items.push(pad);
+                    }
+                    None => warnings.push(format!("{}: skipped malformed pad", name)),
+                }
+            }
+            "$SHAPE3D" => {
+                for line in lines.by_ref() {
+                    if line.starts_with("$EndSHAPE3D") {
+                        break;
+                    }
+                    let tokens = tokenize(line);
+                    if tokens.first().map(String::as_str) == Some("Na")
+                        && let Some(model) = tokens.get(1)
+                    {
+                        items.push(format!("(model {})", quote(model)));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let attr = if through_hole {
+        "through_hole"
+    } else if smd {
+        "smd"
+    } else {
+        "through_hole"
+    };
+    let mut out = format!(
+        "(footprint {} (version 20221018) (generator kci) (layer {})",
+        quote(name),
+        quote(layer)
+    );
+    for item in header {
+        out.push(' ');
+        out.push_str(&item);
+    }
+    out.push_str(&format!(" (attr {})", attr));
+    for item in items {
+        out.push(' ');
+        out.push_str(&item);
+    }
+    out.push(')');
+    out
+}
+
+fn legacy_pad(
+    pad: &[Vec<String>],
+    length: &dyn Fn(Option<&String>) -> f64,
+) -> Option<(String, bool)> {
+    let line = |key: &str| {
+        pad.iter()
+            .find(|tokens| tokens.first().map(String::as_str) == Some(key))
+    };
+    let shape_line = line("Sh")?;
+    let position = line("Po")?;
+    let number = shape_line.get(1)?;
+    let shape = match shape_line.get(2)?.as_str() {
+        "C" => "circle",
+        "O" => "oval",
+        _ => "rect",
+    };
+    let size = format!(
+        "(size {} {})",
+        mm(length(shape_line.get(3))),
+        mm(length(shape_line.get(4)))
+    );
+    let angle = shape_line
+        .get(7)
+        .and_then(|angle| angle.parse::<f64>().ok())
+        .unwrap_or(0.0)
+        / 10.0;
+    let rotation = if angle != 0.0 {
+        format!(" {}", angle)
+    } else {
+        String::new()
+    };
+    let at = format!(
+        "(at {} {}{})",
+        mm(length(position.get(1))),
+        mm(length(position.get(2))),
+        rotation
+    );
+    let attribute = line("At");
+    let kind = match attribute
+        .and_then(|tokens| tokens.get(1))
+        .map(String::as_str)
+    {
+        Some("SMD") => "smd",
+        Some("CONN") => "connect",
+        Some("HOLE") => "np_thru_hole",
+        _ => "thru_hole",
+    };
+    let mask = attribute
+        .and_then(|tokens| tokens.get(3))
+        .and_then(|mask| u32::from_str_radix(mask, 16).ok())
+        .unwrap_or(if kind == "smd" {
+            0x0088_8000
+        } else {
+            0x00C0_8001
+        });
+    let layers = pad_layers(mask);
+
+    let drill = match line("Dr") {
+        Some(tokens) if kind != "smd" && kind != "connect" => {
+            let (ox, oy) = (length(tokens.get(2)), length(tokens.get(3)));
+            let offset = if ox != 0.0 || oy != 0.0 {
+                format!(" (offset {} {})", mm(ox), mm(oy))
+            } else {
+                String::new()
+            };
+            if tokens.get(4).map(String::as_str) == Some("O") {
+                format!(
+                    " (drill oval {} {}{})",
+                    mm(length(tokens.get(5))),
+                    mm(length(tokens.get(6))),
+                    offset
+                )
+            } else {
+                format!(" (drill {}{})", mm(length(tokens.get(1))), offset)
+            }
+        }
+        _ => String::new(),
+    };
+    Some((
+        format!(
+            "(pad {} {} {} {} {}{} (layers {}))",
+            quote(number),
+            kind,
+            shape,
+            at,
+            size,
+            drill,
+            layers
+                .iter()
+                .map(|layer| quote(layer))
+                .collect::<Vec<_>>()
+                .join(" ")
+        ),
+        kind == "thru_hole",
+    ))
+}
+
+fn pad_layers(mask: u32) -> Vec<&'static str> {
+    let has = |bit: u32| mask & (1 << bit) != 0;
+    let mut layers = Vec::new();
+    match (has(15), has(0)) {
+        (true, true) => layers.push("*.Cu"),
+        (true, false) => layers.push("F.Cu"),
+        (false, true) => layers.push("B.Cu"),
+        _ => {}
+    }
+    for (front, back, both, front_name, back_name) in [
+        (19, 18, None, "F.Paste", "B.Paste"),
+        (23, 22, Some("*.Mask"), "F.Mask", "B.Mask"),
+    ] {
+        match (has(front), has(back), both) {
+            (true, true, Some(both)) => layers.push(both),
+            (true, true, None) => layers.extend([front_name, back_name]),
+            (true, false, _) => layers.push(front_name),
+            (false, true, _) => layers.push(back_name),
+            _ => {}
+        }
+    }
+    layers
+}
+
+fn board_layer(id: Option<&String>) -> Option<&'static str> {
+    match id?.as_str() {
+        "0" => Some("B.Cu"),
+        "15" => Some("F.Cu"),
+        "16" => Some("B.Adhes"),
+        "17" => Some("F.Adhes"),
+        "18" => Some("B.Paste"),
+        "19" => Some("F.Paste"),
+        "20" => Some("B.SilkS"),
+        "21" => Some("F.SilkS"),
+        "22" => Some("B.Mask"),
+        "23" => Some("F.Mask"),
+        "24" => Some("Dwgs.User"),
+        "25" => Some("Cmts.User"),
+        "26" => Some("Eco1.User"),
+        "27" => Some("Eco2.User"),
+        "28" => Some("Edge.Cuts"),
+        _ => None,
+    }
+}
+
+fn tokenize(line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut chars = line.trim().chars().peekable();
+    while let Some(&ch) = chars.peek() {
+        if ch.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let mut token = String::new();
+        if ch == '"' {
+            chars.next();
+            while let Some(ch) = chars.next() {
+                match ch {
+                    '\\' => token.extend(chars.next()),
+                    '"' => break,
+                    _ => token.push(ch),
+                }
+            }
+        } else {
+            while let Some(&ch) = chars.peek() {
+                if ch.is_whitespace() {
+                    break;
+                }
+                token.push(ch);
+                chars.next();
+            }
+        }
+        tokens.push(token);
+    }
+    tokens
+}
+
+fn mm(value: f64) -> String {
+    format_number(value, SCHEMATIC_PRECISION)
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn converts_legacy_symbols_with_docs_and_aliases() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("timers.lib");
+        fs::write(
+            &path,
+            r#"EESchema-LIBRARY Version 2.4
+#encoding utf-8
+DEF NE555 U 0 40 Y Y 1 F N
+F0 "U" -400 350 50 H V L CNN
+F1 "NE555" 0 350 50 H V L CNN
+F2 "Package_DIP:DIP-8_W7.62mm" 0 0 50 H I C CNN
+F3 "" 0 0 50 H I C CNN
+F4 "TI" 0 -100 50 H I C CNN "Manufacturer"
+ALIAS LM555
+$FPLIST
+ DIP*W7.62mm*
+$ENDFPLIST
+DRAW
+S -400 -300 400 300 0 1 10 f
+X GND 1 0 -400 100 U 50 50 1 1 W
+X TR 2 -500 100 100 R 50 50 1 1 I
+B 4 0 1 0 0 0 1 1 2 2 3 3 N
+ENDDRAW
+ENDDEF
+"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("timers.dcm"),
+            "EESchema-DOCLIB  Version 2.0\n$CMP NE555\nD Precision timer\nK timer\nF http://www.ti.com/lit/ds/symlink/ne555.pdf\n$ENDCMP\n",
+        )
+        .unwrap();
+        assert!(is_symbol_library(&path));
+
+        let (lib, warnings) = convert_symbol_library(&path).unwrap();
+        assert_eq!(warnings, ["NE555: skipped unsupported drawing B"]);
+        let symbol = lib.symbol("NE555").unwrap();
+        assert_eq!(
+            symbol.property_value("Footprint").as_deref(),
+            Some("Package_DIP:DIP-8_W7.62mm")
+        );
+        assert_eq!(
+            symbol.property_value("Datasheet").as_deref(),
+            Some("http://www.ti.com/lit/ds/symlink/ne555.pdf")
+        );
+        assert_eq!(
+            symbol.property_value("Description").as_deref(),
+            Some("Precision timer")
+        );
+        assert_eq!(symbol.property_value("Manufacturer").as_deref(), Some("TI"));
+        assert_eq!(
+            lib.symbol("LM555")
+                .unwrap()
+                .property_value("Value")
+                .as_deref(),
+            Some("LM555")
+        );
+        let text = lib.to_string_pretty();
+        assert!(text.contains("(extends \"NE555\")"));
+        assert!(text.contains("\"NE555_0_1\""));
+        assert!(text.contains("(at 0 -10.16 90)"));
+        assert!(text.contains("(start -10.16 -7.62)"));
+    }
+
+    #[test]
+    fn converts_legacy_footprints() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("dip.mod");
+        fs::write(
+            &path,
+            r#"PCBNEW-LibModule-V1  01/01/2015 00:00:00
+# encoding utf-8
+Units mm
+$INDEX
+DIP-8
+$EndINDEX
+$MODULE DIP-8
+Po 0 0 0 15 00000000 00000000 ~~
+Li DIP-8
+Cd 8-lead DIP
+Kw DIP THT
+T0 0 -5 1 1 0 0.15 N V 21 N "U1"
+T1 0 5 1 1 0 0.15 N V 21 N "DIP-8"
+DS -5 -3 5 -3 0.15 21
+DA 0 -3 -1 -3 -1800 0.15 21
+$PAD
+Sh "1" R 1.6 1.6 0 0 0
+Dr 0.8 0 0
+At STD N 00E0FFFF
+Ne 0 ""
+Po -3.81 -3.81
+$EndPAD
+$SHAPE3D
+Na "Housings_DIP.3dshapes/DIP-8.wrl"
+$EndSHAPE3D
+$EndMODULE DIP-8
+$EndLIBRARY
+"#,
+        )
+        .unwrap();
+        assert!(is_footprint_library(&path));
+
+        let (footprints, warnings) = convert_footprint_library(&path).unwrap();
+        assert!(warnings.is_empty());
+        let [(name, content)] = footprints.as_slice() else {
+            panic!("expected one footprint");
+        };
+        assert_eq!(name, "DIP-8");
+        assert!(content.contains("(descr \"8-lead DIP\")"));
+        assert!(content.contains("(attr through_hole)"));
+        assert!(content.contains(
+            "(pad \"1\" thru_hole rect (at -3.81 -3.81) (size 1.6 1.6) (drill 0.8) (layers \"*.Cu\" \"*.Mask\"))"
+        ));
+        assert!(content.contains("(fp_arc (start -1 -3) (mid 0 -2) (end 1 -3)"));
+        assert!(content.contains("(fp_text value \"DIP-8\""));
+    }
+}
@@ -0,0 +1,268 @@
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+
+pub const LFS_WARN_BYTES: u64 = 1024 * 1024;
+const LFS_ATTRIBUTES: &str = "filter=lfs diff=lfs merge=lfs -text";
+
+#[derive(Debug)]
+pub enum LfsError {
+    Io(io::Error),
+    Git(String),
+}
+
+impl fmt::Display for LfsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LfsError::Io(err) => write!(f, "io error: {}", err),
+            LfsError::Git(msg) => write!(f, "git failed: {}", msg),
+        }
+    }
+}
+
+impl Error for LfsError {}
+
+impl From<io::Error> for LfsError {
+    fn from(value: io::Error) -> Self {
+        LfsError::Io(value)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LfsPolicy {
+    Off,
+    Warn,
+    Track,
+}
+
+impl LfsPolicy {
+    pub fn all() -> &'static [LfsPolicy] {
+        &[LfsPolicy::Off, LfsPolicy::Warn, LfsPolicy::Track]
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            LfsPolicy::Off => "off",
+            LfsPolicy::Warn => "warn",
+            LfsPolicy::Track => "track",
+        }
+    }
+}
+
+impl fmt::Display for LfsPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for LfsPolicy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        LfsPolicy::all()
+            .iter()
+            .copied()
+            .find(|policy| policy.name().eq_ignore_ascii_case(value.trim()))
+            .ok_or_else(|| format!("unknown lfs policy: {}", value))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LfsFinding {
+    path: PathBuf,
+    size: u64,
+}
+
+impl LfsFinding {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+}
+
+impl fmt::Display for LfsFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({:.1} MB) is not tracked by Git LFS",
+            self.path.display(),
+            self.size as f64 / (1024.0 * 1024.0)
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LfsReport {
+    untracked: Vec<LfsFinding>,
+    added_patterns: Vec<String>,
+}
+
+impl LfsReport {
+    pub fn untracked(&self) -> &[LfsFinding] {
+        &self.untracked
+    }
+
+    pub fn added_patterns(&self) -> &[String] {
+        &self.added_patterns
+    }
+}
+
+pub fn check_lfs(
+    project_root: &Path,
+    files: &[PathBuf],
+    policy: LfsPolicy,
+) -> Result<LfsReport, LfsError> {
+    let mut report = LfsReport::default();
+    if policy == LfsPolicy::Off || files.is_empty() {
+        return Ok(report);
+    }
+    let repo_root = match repo_root(project_root) {
+        Some(root) => root,
+        None => return Ok(report),
+    };
+    let files: Vec<PathBuf> = files
+        .iter()
+        .filter(|file| {
+            fs::canonicalize(project_root.join(file)).is_ok_and(|path| path.starts_with(&repo_root))
+        })
+        .cloned()
+        .collect();
+    if files.is_empty() {
+        return Ok(report);
+    }
+    let untracked = untracked_by_lfs(project_root, &files)?;
+    match policy {
+        LfsPolicy::Off => {}
+        LfsPolicy::Warn => {
+            for path in untracked {
+                let size = fs::metadata(project_root.join(&path))?.len();
+                if size >= LFS_WARN_BYTES {
+                    report.untracked.push(LfsFinding { path, size });
+                }
+            }
+        }
+        LfsPolicy::Track => {
+            let patterns: BTreeSet<String> = untracked
+                .iter()
+                .filter_map(|path| path.extension())
+                .map(|ext| format!("*.{}", ext.to_string_lossy()))
+                .collect();
+            report.added_patterns = add_lfs_patterns(&repo_root, patterns)?;
+        }
+    }
+    Ok(report)
+}
+
+fn repo_root(project_root: &Path) -> Option<PathBuf> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--show-toplevel"])
+        .current_dir(project_root)
+        .stdin(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let root = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    fs::canonicalize(root).ok()
+}
+
+fn untracked_by_lfs(project_root: &Path, files: &[PathBuf]) -> Result<Vec<PathBuf>, LfsError> {
+    let output = Command::new("git")
+        .args(["check-attr", "filter", "--"])
+        .args(files)
+        .current_dir(project_root)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|err| LfsError::Git(err.to_string()))?;
+    if !output.status.success() {
+        return Err(LfsError::Git(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut untracked = Vec::new();
+    for line in stdout.lines() {
+        if let Some((path, value)) = line.rsplit_once(": filter: ")
+            && value != "lfs"
+        {
+            untracked.push(PathBuf::from(path));
+        }
+    }
+    Ok(untracked)
+}
+
+fn add_lfs_patterns(repo_root: &Path, patterns: BTreeSet<String>) -> Result<Vec<String>, LfsError> {
+    let path = repo_root.join(".gitattributes");
+    let mut content = if path.exists() {
+        fs::read_to_string(&path)?
+    } else {
+        String::new()
+    };
+    let existing: BTreeSet<&str> = content
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .collect();
+    let added: Vec<String> = patterns
+        .into_iter()
+        .filter(|pattern| !existing.contains(pattern.as_str()))
+        .collect();
+    if added.is_empty() {
+        return Ok(added);
+    }
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    for pattern in &added {
+        content.push_str(&format!("{} {}\n", pattern, LFS_ATTRIBUTES));
+    }
+    fs::write(path, content)?;
+    Ok(added)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn warns_then_tracks_large_models() {
+        let dir = tempdir().unwrap();
+        let init = Command::new("git")
+            .arg("init")
+            .arg("-q")
+            .current_dir(dir.path())
+            .output();
+        if !init.is_ok_and(|output| output.status.success()) {
+            return;
+        }
+        fs::create_dir_all(dir.path().join("3d")).unwrap();
+        fs::write(
+            dir.path().join("3d/big.step"),
+            vec![b'x'; LFS_WARN_BYTES as usize],
+        )
+        .unwrap();
+        fs::write(dir.path().join("3d/small.step"), "ISO-10303-21;").unwrap();
+        let files = vec![PathBuf::from("3d/big.step"), PathBuf::from("3d/small.step")];
+
+        let report = check_lfs(dir.path(), &files, LfsPolicy::Warn).unwrap();
+        assert_eq!(report.untracked().len(), 1);
+        assert_eq!(report.untracked()[0].path(), Path::new("3d/big.step"));
+
+        let report = check_lfs(dir.path(), &files, LfsPolicy::Track).unwrap();
+        assert_eq!(report.added_patterns(), ["*.step".to_string()]);
+        let attributes = fs::read_to_string(dir.path().join(".gitattributes")).unwrap();
+        assert_eq!(attributes, "*.step filter=lfs diff=lfs merge=lfs -text\n");
+
+        let report = check_lfs(dir.path(), &files, LfsPolicy::Warn).unwrap();
+        assert!(report.untracked().is_empty());
+    }
+}
@@ -1,4 +1,45 @@
 pub mod kicad_sym;
+pub mod altium;
+pub mod annotate;
+pub mod batch;
 pub mod cli;
+pub mod consolidate;
+pub mod descriptions;
+pub mod easyeda;
+pub mod enrich;
+pub mod export;
+pub mod fetch;
+pub mod generate;
+pub mod ignore_rules;
 pub mod importer;
+pub mod journal;
 pub mod kicad_table;
+pub mod legacy;
+pub mod ledger;
+pub mod lifecycle;
+pub mod lfs;
+pub mod lookup;
+pub mod lsp;
+pub mod manifest;
+pub mod mapped;
+pub mod merge;
+pub mod model_store;
+pub mod naming;
+pub mod pad_checks;
+pub mod pin_text;
+pub mod remote;
+pub mod remove;
+pub mod rename;
+pub mod review;
+pub mod sexp_record;
+pub mod shell;
+pub mod signing;
+pub mod simplify;
+pub mod staging;
+pub mod stats;
+pub mod symbol_cache;
+pub mod tags;
+pub mod validate;
+pub mod value_rules;
+pub mod vendor;
+pub mod watch;
@@ -0,0 +1,434 @@
+use crate::kicad_sym::{Atom, KicadSymError, KicadSymbolLib, Sexp, parse_sexps};
+use crate::lookup::{count_lib_ids, schematic_files};
+use crate::mapped::MappedFile;
+use crate::tags::{Tag, TagError, TagStore, Tags, load_tags, update_tags};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+pub const LIFECYCLE_TAG: &str = "lifecycle";
+pub const REPLACEMENT_TAG: &str = "replacement";
+
+#[derive(Debug)]
+pub enum LifecycleError {
+    Io(io::Error),
+    Walkdir(walkdir::Error),
+    Symbol(KicadSymError),
+    Tag(TagError),
+}
+
+impl fmt::Display for LifecycleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LifecycleError::Io(err) => write!(f, "io error: {}", err),
+            LifecycleError::Walkdir(err) => write!(f, "walk error: {}", err),
+            LifecycleError::Symbol(err) => write!(f, "schematic parse error: {}", err),
+            LifecycleError::Tag(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Error for LifecycleError {}
+
+impl From<io::Error> for LifecycleError {
+    fn from(value: io::Error) -> Self {
+        LifecycleError::Io(value)
+    }
+}
+
+impl From<walkdir::Error> for LifecycleError {
+    fn from(value: walkdir::Error) -> Self {
+        LifecycleError::Walkdir(value)
+    }
+}
+
+impl From<KicadSymError> for LifecycleError {
+    fn from(value: KicadSymError) -> Self {
+        LifecycleError::Symbol(value)
+    }
+}
+
+impl From<TagError> for LifecycleError {
+    fn from(value: TagError) -> Self {
+        LifecycleError::Tag(value)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Lifecycle {
+    Draft,
+    Reviewed,
+    Approved,
+    Deprecated,
+}
+
+impl Lifecycle {
+    pub fn all() -> &'static [Lifecycle] {
+        &[
+            Lifecycle::Draft,
+            Lifecycle::Reviewed,
+            Lifecycle::Approved,
+            Lifecycle::Deprecated,
+        ]
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Lifecycle::Draft => "draft",
+            Lifecycle::Reviewed => "reviewed",
+            Lifecycle::Approved => "approved",
+            Lifecycle::Deprecated => "deprecated",
+        }
+    }
+
+    pub fn of(tags: &Tags) -> Lifecycle {
+        tags.get(LIFECYCLE_TAG)
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(Lifecycle::Draft)
+    }
+
+    pub fn tag(self) -> Tag {
+        Tag::new(LIFECYCLE_TAG, self.name())
+    }
+}
+
+impl fmt::Display for Lifecycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Lifecycle {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Lifecycle::all()
+            .iter()
+            .copied()
+            .find(|state| state.name().eq_ignore_ascii_case(value.trim()))
+            .ok_or_else(|| format!("unknown lifecycle state: {}", value))
+    }
+}
+
+pub fn set_lifecycle(
+    store: TagStore,
+    symbol_lib: &Path,
+    project_root: &Path,
+    symbols: &[String],
+    state: Lifecycle,
+) -> Result<(), LifecycleError> {
+    for symbol in symbols {
+        update_tags(store, symbol_lib, project_root, symbol, &[state.tag()], &[])?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnapprovedUse {
+    schematic: PathBuf,
+    symbol: String,
+    state: Lifecycle,
+}
+
+impl UnapprovedUse {
+    pub fn schematic(&self) -> &Path {
+        &self.schematic
+    }
+
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn state(&self) -> Lifecycle {
+        self.state
+    }
+}
+
+impl fmt::Display for UnapprovedUse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} is {}",
+            self.schematic.display(),
+            self.symbol,
+            self.state
+        )
+    }
+}
+
+pub fn find_unapproved_uses(
+    store: TagStore,
+    symbol_lib: &Path,
+    project_root: &Path,
+) -> Result<Vec<UnapprovedUse>, LifecycleError> {
+    let nickname = library_nickname(symbol_lib);
+    let tags = load_tags(store, symbol_lib, project_root)?;
+    let mut found = Vec::new();
+    for path in schematic_files(project_root)? {
+        let file = MappedFile::open(&path)?;
+        let mut lib_ids = BTreeMap::new();
+        for sexp in parse_sexps(file.text()?)? {
+            count_lib_ids(&sexp, &mut lib_ids);
+        }
+        let schematic = path
+            .strip_prefix(project_root)
+            .unwrap_or(&path)
+            .to_path_buf();
+        for lib_id in lib_ids.into_keys() {
+            let Some(symbol) = lib_id
+                .split_once(':')
+                .filter(|(lib, _)| *lib == nickname)
+                .map(|(_, symbol)| symbol)
+            else {
+                continue;
+            };
+            let state = tags
+                .get(symbol)
+                .map(Lifecycle::of)
+                .unwrap_or(Lifecycle::Draft);
+            if state != Lifecycle::Approved {
+                found.push(UnapprovedUse {
+                    schematic: schematic.clone(),
+                    symbol: symbol.to_string(),
+                    state,
+                });
+            }
+        }
+    }
+    Ok(found)
+}
+
+pub fn deprecate(
+    store: TagStore,
+    symbol_lib: &Path,
+    project_root: &Path,
+    symbol: &str,
+    replacement: Option<&str>,
+) -> Result<(), LifecycleError> {
+    let mut set = vec![Lifecycle::Deprecated.tag()];
+    let mut remove = Vec::new();
+    match replacement {
+        Some(replacement) => {
+            if !load_tags(store, symbol_lib, project_root)?.contains_key(replacement) {
+                return Err(TagError::MissingSymbol(replacement.to_string()).into());
+            }
+            set.push(Tag::new(REPLACEMENT_TAG, replacement));
+        }
+        None => remove.push(REPLACEMENT_TAG.to_string()),
+    }
+    update_tags(store, symbol_lib, project_root, symbol, &set, &remove)?;
+    Ok(())
+}
+
+pub fn deprecation_notice(symbol: &str, tags: &Tags) -> Option<String> {
+    if Lifecycle::of(tags) != Lifecycle::Deprecated {
+        return None;
+    }
+    Some(match tags.get(REPLACEMENT_TAG) {
+        Some(replacement) => format!("{} is deprecated, use {} instead", symbol, replacement),
+        None => format!("{} is deprecated", symbol),
+    })
+}
+
+pub fn substitute_references(
+    symbol_lib: &Path,
+    project_root: &Path,
+    symbol: &str,
+    replacement: &str,
+    write: bool,
+) -> Result<Vec<(PathBuf, usize)>, LifecycleError> {
+    let nickname = library_nickname(symbol_lib);
+    let old_id = format!("{}:{}", nickname, symbol);
+    let new_id = format!("{}:{}", nickname, replacement);
+    let mut cached = None;
+    let mut changed = Vec::new();
+    for path in schematic_files(project_root)? {
+        let content = fs::read_to_string(&path)?;
+        let mut sexps = parse_sexps(&content)?;
+        let count: usize = sexps
+            .iter_mut()
+            .map(|sexp| replace_lib_ids(sexp, &old_id, &new_id))
+            .sum();
+        if count == 0 {
+            continue;
+        }
+        if write {
+            if cached.is_none() {
+                cached = Some(cached_symbol(symbol_lib, replacement, &new_id)?);
+            }
+            if let Some(cached) = &cached {
+                for sexp in sexps.iter_mut() {
+                    update_lib_symbols(sexp, &old_id, &new_id, cached);
+                }
+            }
+            let out: String = sexps.iter().map(Sexp::to_string_pretty).collect();
+            fs::write(&path, out)?;
+        }
+        let schematic = path
+            .strip_prefix(project_root)
+            .unwrap_or(&path)
+            .to_path_buf();
+        changed.push((schematic, count));
+    }
+    Ok(changed)
+}
+
+fn library_nickname(symbol_lib: &Path) -> String {
+    symbol_lib
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn cached_symbol(symbol_lib: &Path, name: &str, lib_id: &str) -> Result<Sexp, LifecycleError> {
+    let content = fs::read_to_string(symbol_lib)?;
+    let symbol = KicadSymbolLib::parse(&content)?
+        .symbol(name)
+        .cloned()
+        .ok_or_else(|| TagError::MissingSymbol(name.to_string()))?;
+    let mut sexp = symbol.into_sexp();
+    if let Sexp::List(items) = &mut sexp
+        && items.len() >= 2
+    {
+        items[1] = Sexp::Atom(Atom::new_quoted(lib_id));
+    }
+    Ok(sexp)
+}
+
+fn replace_lib_ids(sexp: &mut Sexp, old_id: &str, new_id: &str) -> usize {
+    let Sexp::List(items) = sexp else {
+        return 0;
+    };
+    if let [Sexp::Atom(head), Sexp::Atom(value)] = items.as_slice()
+        && head.value() == "lib_id"
+    {
+        if value.value() != old_id {
+            return 0;
+        }
+        items[1] = Sexp::Atom(Atom::new_quoted(new_id));
+        return 1;
+    }
+    items
+        .iter_mut()
+        .map(|item| replace_lib_ids(item, old_id, new_id))
+        .sum()
+}
+
+fn update_lib_symbols(sexp: &mut Sexp, old_id: &str, new_id: &str, cached: &Sexp) {
+    let Sexp::List(items) = sexp else {
+        return;
+    };
+    for item in items.iter_mut() {
+        let Sexp::List(entries) = item else {
+            continue;
+        };
+        if !matches!(entries.first(), Some(Sexp::Atom(head)) if head.value() == "lib_symbols") {
+            continue;
+        }
+        entries.retain(|entry| cached_name(entry) != Some(old_id));
+        if !entries
+            .iter()
+            .any(|entry| cached_name(entry) == Some(new_id))
+        {
+            entries.push(cached.clone());
+        }
+    }
+}
+
+fn cached_name(sexp: &Sexp) -> Option<&str> {
+    match sexp {
+        Sexp::List(items) => match items.as_slice() {
+            [Sexp::Atom(head), Sexp::Atom(name), ..] if head.value() == "symbol" => {
+                Some(name.value())
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn flags_unapproved_symbols_placed_in_schematics() {
+        let dir = tempdir().unwrap();
+        let symbol_lib = dir.path().join("parts.kicad_sym");
+        fs::write(
+            &symbol_lib,
+            "(kicad_symbol_lib (version 20231120) (symbol \"U1\") (symbol \"U2\") (symbol \"U3\"))",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("board.kicad_sch"),
+            "(kicad_sch (lib_symbols (symbol \"parts:U1\")) \
+             (symbol (lib_id \"parts:U1\")) (symbol (lib_id \"parts:U2\")) \
+             (symbol (lib_id \"Device:R\")))",
+        )
+        .unwrap();
+        set_lifecycle(
+            TagStore::Sidecar,
+            &symbol_lib,
+            dir.path(),
+            &["U1".to_string()],
+            Lifecycle::Approved,
+        )
+        .unwrap();
+        set_lifecycle(
+            TagStore::Sidecar,
+            &symbol_lib,
+            dir.path(),
+            &["U3".to_string()],
+            Lifecycle::Deprecated,
+        )
+        .unwrap();
+
+        let found = find_unapproved_uses(TagStore::Sidecar, &symbol_lib, dir.path()).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].symbol(), "U2");
+        assert_eq!(found[0].state(), Lifecycle::Draft);
+        assert_eq!(found[0].to_string(), "board.kicad_sch: U2 is draft");
+    }
+
+    #[test]
+    fn substitutes_deprecated_symbol_references() {
+        let dir = tempdir().unwrap();
+        let symbol_lib = dir.path().join("parts.kicad_sym");
+        fs::write(
+            &symbol_lib,
+            "(kicad_symbol_lib (version 20231120) (symbol \"U1\") (symbol \"U2\" (property \"Value\" \"U2\")))",
+        )
+        .unwrap();
+        let schematic = dir.path().join("board.kicad_sch");
+        fs::write(
+            &schematic,
+            "(kicad_sch (lib_symbols (symbol \"parts:U1\")) \
+             (symbol (lib_id \"parts:U1\")) (symbol (lib_id \"parts:U1\")))",
+        )
+        .unwrap();
+        deprecate(TagStore::Sidecar, &symbol_lib, dir.path(), "U1", Some("U2")).unwrap();
+        let tags = load_tags(TagStore::Sidecar, &symbol_lib, dir.path()).unwrap();
+        assert_eq!(
+            deprecation_notice("U1", &tags["U1"]).unwrap(),
+            "U1 is deprecated, use U2 instead"
+        );
+        assert!(deprecation_notice("U2", &tags["U2"]).is_none());
+        assert!(deprecate(TagStore::Sidecar, &symbol_lib, dir.path(), "U1", Some("U9")).is_err());
+
+        let preview = substitute_references(&symbol_lib, dir.path(), "U1", "U2", false).unwrap();
+        assert_eq!(preview, vec![(PathBuf::from("board.kicad_sch"), 2)]);
+        assert!(fs::read_to_string(&schematic).unwrap().contains("parts:U1"));
+
+        substitute_references(&symbol_lib, dir.path(), "U1", "U2", true).unwrap();
+        let content = fs::read_to_string(&schematic).unwrap();
+        assert!(!content.contains("parts:U1"));
+        assert!(content.contains("(lib_id \"parts:U2\")"));
+        assert!(content.contains("\"parts:U2\"\n\t\t\t(property \"Value\" \"U2\")"));
+    }
+}
@@ -0,0 +1,786 @@
+use crate::importer::ImportConfig;
+use crate::kicad_sym::{KicadSymError, Sexp, SymbolStream, parse_one, parse_sexps};
+use crate::kicad_table::{
+    TableEntry, TableError, TableKind, lib_name_from_path, project_table_entries,
+};
+use crate::ledger::STATE_DIR;
+use crate::mapped::MappedFile;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::UNIX_EPOCH;
+use walkdir::WalkDir;
+
+const MODEL_EXTENSIONS: &[&str] = &["step", "stp", "wrl"];
+const INDEX_FILE: &str = "index.toml";
+
+#[derive(Debug)]
+pub enum LookupError {
+    Io(io::Error),
+    Walkdir(walkdir::Error),
+    Write(toml::ser::Error),
+    Symbol(KicadSymError),
+    Table(TableError),
+}
+
+impl fmt::Display for LookupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LookupError::Io(err) => write!(f, "io error: {}", err),
+            LookupError::Walkdir(err) => write!(f, "walk error: {}", err),
+            LookupError::Write(err) => write!(f, "index write error: {}", err),
+            LookupError::Symbol(err) => write!(f, "symbol parse error: {}", err),
+            LookupError::Table(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Error for LookupError {}
+
+impl From<io::Error> for LookupError {
+    fn from(value: io::Error) -> Self {
+        LookupError::Io(value)
+    }
+}
+
+impl From<walkdir::Error> for LookupError {
+    fn from(value: walkdir::Error) -> Self {
+        LookupError::Walkdir(value)
+    }
+}
+
+impl From<toml::ser::Error> for LookupError {
+    fn from(value: toml::ser::Error) -> Self {
+        LookupError::Write(value)
+    }
+}
+
+impl From<KicadSymError> for LookupError {
+    fn from(value: KicadSymError) -> Self {
+        LookupError::Symbol(value)
+    }
+}
+
+impl From<TableError> for LookupError {
+    fn from(value: TableError) -> Self {
+        LookupError::Table(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LookupTarget {
+    Footprint {
+        library: Option<String>,
+        name: String,
+    },
+    Model(String),
+}
+
+impl FromStr for LookupTarget {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        let file_name = value.rsplit(['/', '\\']).next().unwrap_or(value);
+        if file_name.is_empty() {
+            return Err(format!("expected a footprint or model name: {}", value));
+        }
+        let extension = Path::new(file_name)
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_ascii_lowercase());
+        if extension
+            .as_deref()
+            .is_some_and(|ext| MODEL_EXTENSIONS.contains(&ext))
+        {
+            return Ok(LookupTarget::Model(file_name.to_string()));
+        }
+        let name = file_name.strip_suffix(".kicad_mod").unwrap_or(file_name);
+        Ok(match name.split_once(':') {
+            Some((library, name)) => LookupTarget::Footprint {
+                library: Some(library.to_string()),
+                name: name.to_string(),
+            },
+            None => LookupTarget::Footprint {
+                library: None,
+                name: name.to_string(),
+            },
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexedFootprint {
+    library: String,
+    name: String,
+    path: PathBuf,
+    models: Vec<String>,
+}
+
+impl IndexedFootprint {
+    pub fn library(&self) -> &str {
+        &self.library
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn models(&self) -> &[String] {
+        &self.models
+    }
+
+    pub fn lib_id(&self) -> String {
+        format!("{}:{}", self.library, self.name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexedSymbol {
+    library: String,
+    name: String,
+    footprint: Option<String>,
+}
+
+impl IndexedSymbol {
+    pub fn library(&self) -> &str {
+        &self.library
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn footprint(&self) -> Option<&str> {
+        self.footprint.as_deref()
+    }
+
+    pub fn lib_id(&self) -> String {
+        format!("{}:{}", self.library, self.name)
+    }
+}
+
+impl fmt::Display for IndexedSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{} -> {}",
+            self.library,
+            self.name,
+            self.footprint.as_deref().unwrap_or_default()
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Usage {
+    schematic: PathBuf,
+    lib_id: String,
+    count: usize,
+}
+
+impl Usage {
+    pub fn schematic(&self) -> &Path {
+        &self.schematic
+    }
+
+    pub fn lib_id(&self) -> &str {
+        &self.lib_id
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+impl fmt::Display for Usage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} ({} placed)",
+            self.schematic.display(),
+            self.lib_id,
+            self.count
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct CachedFile<T> {
+    size: u64,
+    modified: u64,
+    entries: Vec<T>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct IndexCache {
+    #[serde(default)]
+    footprints: BTreeMap<String, CachedFile<IndexedFootprint>>,
+    #[serde(default)]
+    symbols: BTreeMap<String, CachedFile<IndexedSymbol>>,
+    #[serde(default)]
+    schematics: BTreeMap<String, CachedFile<Usage>>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LibraryIndex {
+    footprints: Vec<IndexedFootprint>,
+    symbols: Vec<IndexedSymbol>,
+    usages: Vec<Usage>,
+    tables: Vec<TableEntry>,
+    files: usize,
+    reparsed: usize,
+}
+
+impl LibraryIndex {
+    pub fn path(project_root: &Path) -> PathBuf {
+        project_root.join(STATE_DIR).join(INDEX_FILE)
+    }
+
+    pub fn build(project_root: &Path, config: &ImportConfig) -> Result<Self, LookupError> {
+        Ok(Self::scan(project_root, config, &IndexCache::default())?.0)
+    }
+
+    pub fn refresh(project_root: &Path, config: &ImportConfig) -> Result<Self, LookupError> {
+        let path = Self::path(project_root);
+        let previous: IndexCache = fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| toml::from_str(&raw).ok())
+            .unwrap_or_default();
+        let (index, cache) = Self::scan(project_root, config, &previous)?;
+        if cache != previous {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&path, toml::to_string_pretty(&cache)?)?;
+        }
+        Ok(index)
+    }
+
+    pub fn clear(project_root: &Path) -> Result<(), LookupError> {
+        let path = Self::path(project_root);
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    fn scan(
+        project_root: &Path,
+        config: &ImportConfig,
+        previous: &IndexCache,
+    ) -> Result<(Self, IndexCache), LookupError> {
+        let mut index = LibraryIndex::default();
+        let mut cache = IndexCache::default();
+        for kind in [TableKind::Symbol, TableKind::Footprint] {
+            index
+                .tables
+                .extend(project_table_entries(project_root, kind)?);
+        }
+
+        let mut footprint_libs = Vec::new();
+        let mut symbol_libs = Vec::new();
+        for entry in &index.tables {
+            let Some(path) = entry.path() else {
+                continue;
+            };
+            match entry.kind() {
+                TableKind::Footprint => {
+                    footprint_libs.push((entry.name().to_string(), path.to_path_buf()))
+                }
+                TableKind::Symbol => {
+                    symbol_libs.push((entry.name().to_string(), path.to_path_buf()))
+                }
+            }
+        }
+        add_configured_lib(
+            &mut footprint_libs,
+            project_root,
+            config.footprint_lib(),
+            TableKind::Footprint,
+        );
+        add_configured_lib(
+            &mut symbol_libs,
+            project_root,
+            config.symbol_lib(),
+            TableKind::Symbol,
+        );
+
+        for (library, dir) in footprint_libs {
+            if !dir.is_dir() {
+                continue;
+            }
+            let mut paths: Vec<PathBuf> = fs::read_dir(&dir)?
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("kicad_mod"))
+                .collect();
+            paths.sort();
+            for path in paths {
+                let footprints = index.refresh_file(
+                    project_root,
+                    &path,
+                    &previous.footprints,
+                    &mut cache.footprints,
+                    |relative| {
+                        let name = path
+                            .file_stem()
+                            .map(|stem| stem.to_string_lossy().to_string())
+                            .unwrap_or_default();
+                        let models = fs::read_to_string(&path)
+                            .ok()
+                            .and_then(|content| parse_one(&content).ok())
+                            .map(|sexp| model_paths(&sexp))
+                            .unwrap_or_default();
+                        Ok(vec![IndexedFootprint {
+                            library: library.clone(),
+                            name,
+                            path: relative.to_path_buf(),
+                            models,
+                        }])
+                    },
+                )?;
+                index
+                    .footprints
+                    .extend(footprints.into_iter().map(|footprint| IndexedFootprint {
+                        library: library.clone(),
+                        ..footprint
+                    }));
+            }
+        }
+
+        for (library, path) in symbol_libs {
+            if !path.is_file() {
+                continue;
+            }
+            let symbols = index.refresh_file(
+                project_root,
+                &path,
+                &previous.symbols,
+                &mut cache.symbols,
+                |_| {
+                    let file = MappedFile::open(&path)?;
+                    SymbolStream::new(file.text()?)
+                        .map(|symbol| {
+                            let symbol = symbol?;
+                            Ok(IndexedSymbol {
+                                library: library.clone(),
+                                name: symbol.name().to_string(),
+                                footprint: symbol
+                                    .property_value("Footprint")
+                                    .filter(|value| !value.is_empty()),
+                            })
+                        })
+                        .collect()
+                },
+            )?;
+            index
+                .symbols
+                .extend(symbols.into_iter().map(|symbol| IndexedSymbol {
+                    library: library.clone(),
+                    ..symbol
+                }));
+        }
+
+        for path in schematic_files(project_root)? {
+            let usages = index.refresh_file(
+                project_root,
+                &path,
+                &previous.schematics,
+                &mut cache.schematics,
+                |relative| {
+                    let file = MappedFile::open(&path)?;
+                    let mut counts = BTreeMap::new();
+                    for sexp in parse_sexps(file.text()?)? {
+                        count_lib_ids(&sexp, &mut counts);
+                    }
+                    Ok(counts
+                        .into_iter()
+                        .map(|(lib_id, count)| Usage {
+                            schematic: relative.to_path_buf(),
+                            lib_id,
+                            count,
+                        })
+                        .collect())
+                },
+            )?;
+            index.usages.extend(usages);
+        }
+        Ok((index, cache))
+    }
+
+    fn refresh_file<T: Clone>(
+        &mut self,
+        project_root: &Path,
+        path: &Path,
+        previous: &BTreeMap<String, CachedFile<T>>,
+        current: &mut BTreeMap<String, CachedFile<T>>,
+        parse: impl FnOnce(&Path) -> Result<Vec<T>, LookupError>,
+    ) -> Result<Vec<T>, LookupError> {
+        let relative = path.strip_prefix(project_root).unwrap_or(path);
+        let key = relative.to_string_lossy().replace('\\', "/");
+        let metadata = fs::metadata(path)?;
+        let size = metadata.len();
+        let modified = metadata
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos() as u64)
+            .unwrap_or_default();
+        self.files += 1;
+        let entries = match previous.get(&key) {
+            Some(cached) if cached.size == size && cached.modified == modified => {
+                cached.entries.clone()
+            }
+            _ => {
+                self.reparsed += 1;
+                parse(relative)?
+            }
+        };
+        current.insert(
+            key,
+            CachedFile {
+                size,
+                modified,
+                entries: entries.clone(),
+            },
+        );
+        Ok(entries)
+    }
+
+    pub fn footprints(&self) -> &[IndexedFootprint] {
+        &self.footprints
+    }
+
+    pub fn symbols(&self) -> &[IndexedSymbol] {
+        &self.symbols
+    }
+
+    pub fn usages(&self) -> &[Usage] {
+        &self.usages
+    }
+
+    pub fn files(&self) -> usize {
+        self.files
+    }
+
+    pub fn reparsed(&self) -> usize {
+        self.reparsed
+    }
+
+    pub fn usages_of(&self, symbol: &str) -> Vec<Usage> {
+        self.usages
+            .iter()
+            .filter(|usage| match symbol.split_once(':') {
+                Some(_) => usage.lib_id == symbol,
+                None => usage
+                    .lib_id
+                    .split_once(':')
+                    .is_some_and(|(_, name)| name == symbol),
+            })
+            .cloned()
+            .collect()
+    }
+
+    pub fn lookup(&self, target: &LookupTarget) -> LookupReport {
+        let footprints: Vec<IndexedFootprint> = self
+            .footprints
+            .iter()
+            .filter(|footprint| match target {
+                LookupTarget::Footprint { library, name } => {
+                    footprint.name == *name
+                        && library
+                            .as_ref()
+                            .is_none_or(|library| footprint.library == *library)
+                }
+                LookupTarget::Model(file_name) => footprint.models.iter().any(|model| {
+                    model
+                        .rsplit(['/', '\\'])
+                        .next()
+                        .is_some_and(|name| name.eq_ignore_ascii_case(file_name))
+                }),
+            })
+            .cloned()
+            .collect();
+        let mut lib_ids: BTreeSet<String> =
+            footprints.iter().map(IndexedFootprint::lib_id).collect();
+        if let LookupTarget::Footprint {
+            library: Some(library),
+            name,
+        } = target
+        {
+            lib_ids.insert(format!("{}:{}", library, name));
+        }
+        let symbols: Vec<IndexedSymbol> = self
+            .symbols
+            .iter()
+            .filter(|symbol| match (&symbol.footprint, target) {
+                (
+                    Some(footprint),
+                    LookupTarget::Footprint {
+                        library: None,
+                        name,
+                    },
+                ) => {
+                    lib_ids.contains(footprint)
+                        || footprint.rsplit_once(':').is_some_and(|(_, fp)| fp == name)
+                }
+                (Some(footprint), _) => lib_ids.contains(footprint),
+                (None, _) => false,
+            })
+            .cloned()
+            .collect();
+        let tables = self
+            .tables
+            .iter()
+            .filter(|entry| match entry.kind() {
+                TableKind::Footprint => footprints
+                    .iter()
+                    .any(|footprint| footprint.library == entry.name()),
+                TableKind::Symbol => symbols.iter().any(|symbol| symbol.library == entry.name()),
+            })
+            .cloned()
+            .collect();
+        let symbol_ids: BTreeSet<String> = symbols.iter().map(IndexedSymbol::lib_id).collect();
+        let usages = self
+            .usages
+            .iter()
+            .filter(|usage| symbol_ids.contains(&usage.lib_id))
+            .cloned()
+            .collect();
+        LookupReport {
+            footprints,
+            symbols,
+            usages,
+            tables,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LookupReport {
+    footprints: Vec<IndexedFootprint>,
+    symbols: Vec<IndexedSymbol>,
+    usages: Vec<Usage>,
+    tables: Vec<TableEntry>,
+}
+
+impl LookupReport {
+    pub fn footprints(&self) -> &[IndexedFootprint] {
+        &self.footprints
+    }
+
+    pub fn symbols(&self) -> &[IndexedSymbol] {
+        &self.symbols
+    }
+
+    pub fn usages(&self) -> &[Usage] {
+        &self.usages
+    }
+
+    pub fn tables(&self) -> &[TableEntry] {
+        &self.tables
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.footprints.is_empty() && self.symbols.is_empty()
+    }
+}
+
+fn add_configured_lib(
+    libs: &mut Vec<(String, PathBuf)>,
+    project_root: &Path,
+    lib_path: &Path,
+    kind: TableKind,
+) {
+    let path = project_root.join(lib_path);
+    let canonical = fs::canonicalize(&path).ok();
+    if canonical.is_none()
+        || libs
+            .iter()
+            .any(|(_, existing)| fs::canonicalize(existing).ok() == canonical)
+    {
+        return;
+    }
+    if let Ok(name) = lib_name_from_path(kind, lib_path) {
+        libs.push((name, path));
+    }
+}
+
+pub fn schematic_files(project_root: &Path) -> Result<Vec<PathBuf>, walkdir::Error> {
+    let entries = WalkDir::new(project_root)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.depth() == 0 || !entry.file_name().to_string_lossy().starts_with('.')
+        });
+    let mut files = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        if entry.file_type().is_file()
+            && entry.path().extension().and_then(|ext| ext.to_str()) == Some("kicad_sch")
+        {
+            files.push(entry.into_path());
+        }
+    }
+    Ok(files)
+}
+
+pub fn count_lib_ids(sexp: &Sexp, counts: &mut BTreeMap<String, usize>) {
+    let Sexp::List(items) = sexp else {
+        return;
+    };
+    if let [Sexp::Atom(head), Sexp::Atom(value)] = items.as_slice()
+        && head.value() == "lib_id"
+    {
+        *counts.entry(value.value().to_string()).or_default() += 1;
+        return;
+    }
+    for item in items {
+        count_lib_ids(item, counts);
+    }
+}
+
+fn model_paths(sexp: &Sexp) -> Vec<String> {
+    let Sexp::List(items) = sexp else {
+        return Vec::new();
+    };
+    items
+        .iter()
+        .filter_map(|item| match item {
+            Sexp::List(model) => match model.as_slice() {
+                [Sexp::Atom(head), Sexp::Atom(path), ..] if head.value() == "model" => {
+                    Some(path.value().to_string())
+                }
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn finds_symbols_and_tables_for_footprints_and_models() {
+        let dir = tempdir().unwrap();
+        let footprints = dir.path().join("Parts.pretty");
+        fs::create_dir_all(&footprints).unwrap();
+        fs::write(
+            footprints.join("SOIC-8.kicad_mod"),
+            "(footprint \"SOIC-8\" (model \"${KIPRJMOD}/3d/soic8.step\" (offset (xyz 0 0 0))))",
+        )
+        .unwrap();
+        fs::write(
+            footprints.join("SOT-23.kicad_mod"),
+            "(footprint \"SOT-23\")",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("Parts.kicad_sym"),
+            "(kicad_symbol_lib (version 20231120) \
+             (symbol \"NE555\" (property \"Footprint\" \"Parts:SOIC-8\")) \
+             (symbol \"LM358\" (property \"Footprint\" \"Parts:SOIC-8\")) \
+             (symbol \"BC847\" (property \"Footprint\" \"Parts:SOT-23\")))",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("fp-lib-table"),
+            "(fp_lib_table (version 7) (lib (name \"Parts\") (type \"KiCad\") (uri \"${KIPRJMOD}/Parts.pretty\") (options \"\") (descr \"\")))",
+        )
+        .unwrap();
+        let config = ImportConfig::new(
+            PathBuf::from("Parts.kicad_sym"),
+            PathBuf::from("Parts.pretty"),
+            PathBuf::from("3d"),
+        );
+        let index = LibraryIndex::build(dir.path(), &config).unwrap();
+        assert_eq!(index.footprints().len(), 2);
+        assert_eq!(index.symbols().len(), 3);
+
+        let report = index.lookup(&"SOIC8.STEP".parse().unwrap());
+        assert_eq!(report.footprints().len(), 1);
+        assert_eq!(report.footprints()[0].lib_id(), "Parts:SOIC-8");
+        let symbols: Vec<&str> = report.symbols().iter().map(IndexedSymbol::name).collect();
+        assert_eq!(symbols, vec!["NE555", "LM358"]);
+        assert_eq!(report.tables().len(), 1);
+        assert_eq!(
+            report.tables()[0].to_string(),
+            "fp-lib-table: Parts -> ${KIPRJMOD}/Parts.pretty"
+        );
+
+        let report = index.lookup(&"Parts:SOT-23".parse().unwrap());
+        assert_eq!(
+            report.symbols()[0].to_string(),
+            "Parts:BC847 -> Parts:SOT-23"
+        );
+        assert!(
+            index
+                .lookup(&"QFN-16.kicad_mod".parse().unwrap())
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn refreshes_only_changed_files() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("Parts.pretty")).unwrap();
+        fs::write(
+            dir.path().join("Parts.pretty/SOIC-8.kicad_mod"),
+            "(footprint \"SOIC-8\")",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("Parts.kicad_sym"),
+            "(kicad_symbol_lib (version 20231120) (symbol \"NE555\" (property \"Footprint\" \"Parts:SOIC-8\")))",
+        )
+        .unwrap();
+        let schematic = dir.path().join("main.kicad_sch");
+        fs::write(
+            &schematic,
+            "(kicad_sch (symbol (lib_id \"Parts:NE555\")) (symbol (lib_id \"Parts:NE555\")))",
+        )
+        .unwrap();
+        let config = ImportConfig::new(
+            PathBuf::from("Parts.kicad_sym"),
+            PathBuf::from("Parts.pretty"),
+            PathBuf::from("3d"),
+        );
+
+        let index = LibraryIndex::refresh(dir.path(), &config).unwrap();
+        assert_eq!((index.files(), index.reparsed()), (3, 3));
+        assert!(LibraryIndex::path(dir.path()).exists());
+        let report = index.lookup(&"SOIC-8".parse().unwrap());
+        assert_eq!(
+            report.usages()[0].to_string(),
+            "main.kicad_sch: Parts:NE555 (2 placed)"
+        );
+
+        let index = LibraryIndex::refresh(dir.path(), &config).unwrap();
+        assert_eq!(index.reparsed(), 0);
+        assert_eq!(index.usages_of("NE555").len(), 1);
+
+        fs::write(
+            &schematic,
+            "(kicad_sch (symbol (lib_id \"Parts:NE555\")) (symbol (lib_id \"Device:R\")))",
+        )
+        .unwrap();
+        let index = LibraryIndex::refresh(dir.path(), &config).unwrap();
+        assert_eq!(index.reparsed(), 1);
+        assert_eq!(index.usages_of("Parts:NE555")[0].count(), 1);
+        assert_eq!(index.usages_of("Device:R").len(), 1);
+
+        LibraryIndex::clear(dir.path()).unwrap();
+        let index = LibraryIndex::refresh(dir.path(), &config).unwrap();
+        assert_eq!(index.reparsed(), 3);
+    }
+}
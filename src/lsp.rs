@@ -0,0 +1,457 @@
+use crate::annotate::FootprintSummary;
+use crate::kicad_sym::{
+    KicadSymError, KicadSymbolLib, STANDARD_PROPERTIES, parse_one, parse_sexps,
+};
+use serde_json::{Value, json};
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+const METHOD_NOT_FOUND: i64 = -32601;
+const SEVERITY_ERROR: u8 = 1;
+const SEVERITY_WARNING: u8 = 2;
+
+#[derive(Debug)]
+pub enum LspError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Protocol(String),
+}
+
+impl fmt::Display for LspError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LspError::Io(err) => write!(f, "io error: {}", err),
+            LspError::Json(err) => write!(f, "invalid message: {}", err),
+            LspError::Protocol(msg) => write!(f, "protocol error: {}", msg),
+        }
+    }
+}
+
+impl Error for LspError {}
+
+impl From<io::Error> for LspError {
+    fn from(value: io::Error) -> Self {
+        LspError::Io(value)
+    }
+}
+
+impl From<serde_json::Error> for LspError {
+    fn from(value: serde_json::Error) -> Self {
+        LspError::Json(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    line: usize,
+    character: usize,
+    severity: u8,
+    message: String,
+}
+
+impl Diagnostic {
+    fn error(text: &str, line: usize, column: usize, message: impl Into<String>) -> Self {
+        Self::at(text, line, column, SEVERITY_ERROR, message)
+    }
+
+    fn warning(text: &str, offset: Option<usize>, message: impl Into<String>) -> Self {
+        let before = &text[..offset.unwrap_or_default()];
+        let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+        let line = before.matches('\n').count();
+        let column = before[line_start..].chars().count();
+        Self::at(text, line, column, SEVERITY_WARNING, message)
+    }
+
+    fn at(
+        text: &str,
+        line: usize,
+        column: usize,
+        severity: u8,
+        message: impl Into<String>,
+    ) -> Self {
+        let character = text
+            .lines()
+            .nth(line)
+            .map(|line_text| line_text.chars().take(column).map(char::len_utf16).sum())
+            .unwrap_or(0);
+        Self {
+            line,
+            character,
+            severity,
+            message: message.into(),
+        }
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn character(&self) -> usize {
+        self.character
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == SEVERITY_ERROR
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    fn to_json(&self) -> Value {
+        let position = json!({"line": self.line, "character": self.character});
+        json!({
+            "range": {"start": position, "end": position},
+            "severity": self.severity,
+            "source": "kci",
+            "message": self.message,
+        })
+    }
+}
+
+pub fn diagnostics(uri: &str, text: &str) -> Vec<Diagnostic> {
+    let result = match file_kind(uri) {
+        FileKind::SymbolLibrary => symbol_library_diagnostics(text),
+        FileKind::Footprint => footprint_diagnostics(text),
+        FileKind::Other => parse_sexps(text).map(|_| Vec::new()),
+    };
+    result.unwrap_or_else(|err| vec![parse_error(text, &err)])
+}
+
+pub fn format_document(uri: &str, text: &str) -> Option<String> {
+    let formatted = match file_kind(uri) {
+        FileKind::SymbolLibrary => KicadSymbolLib::parse(text).ok()?.to_string_pretty(),
+        FileKind::Footprint | FileKind::Other => parse_one(text).ok()?.to_string_pretty(),
+    };
+    (formatted != text).then_some(formatted)
+}
+
+pub fn serve(input: impl BufRead, mut output: impl Write) -> Result<(), LspError> {
+    let mut server = Server {
+        input,
+        documents: HashMap::new(),
+    };
+    while let Some(message) = server.read_message()? {
+        let method = message
+            .get("method")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        if method == "exit" {
+            return Ok(());
+        }
+        let replies = server.handle(method, &params);
+        if let Some(id) = message.get("id") {
+            let reply = match replies.response {
+                Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+                Err(error) => json!({"jsonrpc": "2.0", "id": id, "error": error}),
+            };
+            write_message(&mut output, &reply)?;
+        }
+        for notification in replies.notifications {
+            write_message(&mut output, &notification)?;
+        }
+    }
+    Ok(())
+}
+
+enum FileKind {
+    SymbolLibrary,
+    Footprint,
+    Other,
+}
+
+fn file_kind(uri: &str) -> FileKind {
+    let lower = uri.to_ascii_lowercase();
+    if lower.ends_with(".kicad_sym") {
+        FileKind::SymbolLibrary
+    } else if lower.ends_with(".kicad_mod") {
+        FileKind::Footprint
+    } else {
+        FileKind::Other
+    }
+}
+
+fn parse_error(text: &str, err: &KicadSymError) -> Diagnostic {
+    let line = err.line().unwrap_or(1).saturating_sub(1);
+    let column = err.column().unwrap_or(1).saturating_sub(1);
+    Diagnostic::error(text, line, column, err.message())
+}
+
+fn symbol_library_diagnostics(text: &str) -> Result<Vec<Diagnostic>, KicadSymError> {
+    let library = KicadSymbolLib::parse(text)?;
+    let mut out: Vec<Diagnostic> = library
+        .warnings()
+        .iter()
+        .map(|warning| Diagnostic::warning(text, None, warning.as_str()))
+        .collect();
+    let names: HashSet<&str> = library.symbols_iter().map(|symbol| symbol.name()).collect();
+    let mut seen = HashSet::new();
+    for symbol in library.symbols_iter() {
+        let offset = text.find(&format!("(symbol \"{}\"", symbol.name()));
+        if !seen.insert(symbol.name()) {
+            out.push(Diagnostic::warning(
+                text,
+                offset,
+                format!("duplicate symbol {}", symbol.name()),
+            ));
+        }
+        if let Some(parent) = symbol.extends() {
+            if !names.contains(parent) {
+                out.push(Diagnostic::warning(
+                    text,
+                    offset,
+                    format!("{} extends unknown symbol {}", symbol.name(), parent),
+                ));
+            }
+            continue;
+        }
+        for property in STANDARD_PROPERTIES.iter().take(2) {
+            if symbol.property_value(property).is_none() {
+                out.push(Diagnostic::warning(
+                    text,
+                    offset,
+                    format!("{} has no {} property", symbol.name(), property),
+                ));
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn footprint_diagnostics(text: &str) -> Result<Vec<Diagnostic>, KicadSymError> {
+    let footprint = FootprintSummary::parse("", text)?;
+    let mut out = Vec::new();
+    if footprint.pads() == 0 {
+        out.push(Diagnostic::warning(text, None, "footprint has no pads"));
+    }
+    for model in footprint.models() {
+        let lower = model.to_ascii_lowercase();
+        if ![".step", ".stp", ".wrl"]
+            .iter()
+            .any(|extension| lower.ends_with(extension))
+        {
+            out.push(Diagnostic::warning(
+                text,
+                text.find(model.as_str()),
+                format!("3D model is not a STEP or VRML file: {}", model),
+            ));
+        }
+    }
+    Ok(out)
+}
+
+struct Replies {
+    response: Result<Value, Value>,
+    notifications: Vec<Value>,
+}
+
+struct Server<R> {
+    input: R,
+    documents: HashMap<String, String>,
+}
+
+impl<R: BufRead> Server<R> {
+    fn read_message(&mut self) -> Result<Option<Value>, LspError> {
+        let mut length = None;
+        loop {
+            let mut header = String::new();
+            if self.input.read_line(&mut header)? == 0 {
+                return Ok(None);
+            }
+            let header = header.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':')
+                && name.eq_ignore_ascii_case("content-length")
+            {
+                length = Some(value.trim().parse::<usize>().map_err(|_| {
+                    LspError::Protocol(format!("invalid Content-Length: {}", value.trim()))
+                })?);
+            }
+        }
+        let length =
+            length.ok_or_else(|| LspError::Protocol("missing Content-Length".to_string()))?;
+        let mut body = vec![0; length];
+        self.input.read_exact(&mut body)?;
+        Ok(Some(serde_json::from_slice(&body)?))
+    }
+
+    fn handle(&mut self, method: &str, params: &Value) -> Replies {
+        let uri = params
+            .pointer("/textDocument/uri")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let mut notifications = Vec::new();
+        let response = match method {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    "textDocumentSync": 1,
+                    "documentFormattingProvider": true,
+                },
+                "serverInfo": {"name": "kci", "version": env!("CARGO_PKG_VERSION")},
+            })),
+            "textDocument/didOpen" | "textDocument/didChange" | "textDocument/didSave" => {
+                let text = params
+                    .pointer("/textDocument/text")
+                    .or_else(|| {
+                        params
+                            .get("contentChanges")
+                            .and_then(Value::as_array)
+                            .and_then(|changes| changes.last())
+                            .and_then(|change| change.get("text"))
+                    })
+                    .and_then(Value::as_str);
+                if let Some(text) = text {
+                    self.documents.insert(uri.clone(), text.to_string());
+                }
+                if let Some(text) = self.documents.get(&uri) {
+                    notifications.push(publish(&uri, &diagnostics(&uri, text)));
+                }
+                Ok(Value::Null)
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+                notifications.push(publish(&uri, &[]));
+                Ok(Value::Null)
+            }
+            "textDocument/formatting" => Ok(self
+                .documents
+                .get(&uri)
+                .and_then(|text| {
+                    let formatted = format_document(&uri, text)?;
+                    let end = json!({"line": text.lines().count() + 1, "character": 0});
+                    Some(json!([{
+                        "range": {"start": {"line": 0, "character": 0}, "end": end},
+                        "newText": formatted,
+                    }]))
+                })
+                .unwrap_or(Value::Null)),
+            "initialized" | "shutdown" | "$/cancelRequest" | "$/setTrace" => Ok(Value::Null),
+            other => Err(json!({
+                "code": METHOD_NOT_FOUND,
+                "message": format!("unsupported method: {}", other),
+            })),
+        };
+        Replies {
+            response,
+            notifications,
+        }
+    }
+}
+
+fn publish(uri: &str, diagnostics: &[Diagnostic]) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": {
+            "uri": uri,
+            "diagnostics": diagnostics.iter().map(Diagnostic::to_json).collect::<Vec<_>>(),
+        },
+    })
+}
+
+fn write_message(output: &mut impl Write, message: &Value) -> Result<(), LspError> {
+    let body = serde_json::to_string(message)?;
+    write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    output.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(message: Value) -> String {
+        let body = message.to_string();
+        format!("Content-Length: {}\r\n\r\n{}", body.len(), body)
+    }
+
+    fn read_all(mut output: &[u8]) -> Vec<Value> {
+        let mut server = Server {
+            input: &mut output,
+            documents: HashMap::new(),
+        };
+        std::iter::from_fn(|| server.read_message().unwrap()).collect()
+    }
+
+    #[test]
+    fn reports_parse_errors_and_validation_findings() {
+        let broken = diagnostics(
+            "file:///lib.kicad_sym",
+            "(kicad_symbol_lib\n  (symbol \"A\"",
+        );
+        assert_eq!(broken.len(), 1);
+        assert!(broken[0].is_error());
+        assert_eq!(broken[0].line(), 1);
+
+        let text = "(kicad_symbol_lib\n\t(symbol \"R\" (property \"Reference\" \"R\"))\n\t(symbol \"R2\" (extends \"Q\")))\n";
+        let found = diagnostics("file:///lib.kicad_sym", text);
+        let messages: Vec<&str> = found.iter().map(Diagnostic::message).collect();
+        assert_eq!(
+            messages,
+            ["R has no Value property", "R2 extends unknown symbol Q"]
+        );
+        assert_eq!((found[0].line(), found[0].character()), (1, 1));
+        assert_eq!(found[1].line(), 2);
+
+        let footprint = diagnostics(
+            "file:///Fp.pretty/X.kicad_mod",
+            "(footprint \"X\" (model \"x.igs\"))",
+        );
+        assert_eq!(footprint.len(), 2);
+        assert!(format_document("file:///x.kicad_mod", "(footprint \"X\"\n)").is_some());
+        assert!(format_document("file:///x.kicad_mod", "(footprint \"X\"").is_none());
+    }
+
+    #[test]
+    fn serves_diagnostics_and_formatting_over_stdio() {
+        let uri = "file:///parts.kicad_sym";
+        let text = "(kicad_symbol_lib (symbol \"A\" (property \"Reference\" \"U\") (property \"Value\" \"A\")))";
+        let input = [
+            frame(json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}})),
+            frame(json!({"jsonrpc": "2.0", "method": "textDocument/didOpen",
+                "params": {"textDocument": {"uri": uri, "languageId": "kicad", "version": 1, "text": text}}})),
+            frame(json!({"jsonrpc": "2.0", "method": "textDocument/didChange",
+                "params": {"textDocument": {"uri": uri, "version": 2}, "contentChanges": [{"text": "(kicad_symbol_lib"}]}})),
+            frame(json!({"jsonrpc": "2.0", "method": "textDocument/didChange",
+                "params": {"textDocument": {"uri": uri, "version": 3}, "contentChanges": [{"text": text}]}})),
+            frame(json!({"jsonrpc": "2.0", "id": 2, "method": "textDocument/formatting",
+                "params": {"textDocument": {"uri": uri}, "options": {"tabSize": 4, "insertSpaces": false}}})),
+            frame(json!({"jsonrpc": "2.0", "id": 3, "method": "textDocument/hover", "params": {}})),
+            frame(json!({"jsonrpc": "2.0", "method": "exit"})),
+        ]
+        .concat();
+        let mut output = Vec::new();
+        serve(input.as_bytes(), &mut output).unwrap();
+        let replies = read_all(&output);
+
+        assert_eq!(replies.len(), 6);
+        assert_eq!(
+            replies[0]["result"]["capabilities"]["documentFormattingProvider"],
+            true
+        );
+        assert_eq!(
+            replies[1]["params"]["diagnostics"]
+                .as_array()
+                .unwrap()
+                .len(),
+            0
+        );
+        assert_eq!(replies[2]["params"]["diagnostics"][0]["severity"], 1);
+        assert_eq!(
+            replies[3]["params"]["diagnostics"]
+                .as_array()
+                .unwrap()
+                .len(),
+            0
+        );
+        let formatted = replies[4]["result"][0]["newText"].as_str().unwrap();
+        assert!(formatted.starts_with("(kicad_symbol_lib\n\t(symbol\n\t\t\"A\"\n"));
+        assert_eq!(replies[5]["error"]["code"], METHOD_NOT_FOUND);
+    }
+}
@@ -0,0 +1,276 @@
+use crate::importer::ImportConfig;
+use crate::kicad_table::TableKind;
+use crate::ledger::STATE_DIR;
+use crate::signing::Provenance;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+const MANIFEST_FILE: &str = "lock.toml";
+
+#[derive(Debug)]
+pub enum ManifestError {
+    Io(io::Error),
+    Walkdir(walkdir::Error),
+    Parse(toml::de::Error),
+    Write(toml::ser::Error),
+    Missing(PathBuf),
+}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ManifestError::Io(err) => write!(f, "io error: {}", err),
+            ManifestError::Walkdir(err) => write!(f, "walk error: {}", err),
+            ManifestError::Parse(err) => write!(f, "manifest parse error: {}", err),
+            ManifestError::Write(err) => write!(f, "manifest write error: {}", err),
+            ManifestError::Missing(path) => write!(
+                f,
+                "no checksum manifest at {}, run `kci freeze` first",
+                path.display()
+            ),
+        }
+    }
+}
+
+impl Error for ManifestError {}
+
+impl From<io::Error> for ManifestError {
+    fn from(value: io::Error) -> Self {
+        ManifestError::Io(value)
+    }
+}
+
+impl From<walkdir::Error> for ManifestError {
+    fn from(value: walkdir::Error) -> Self {
+        ManifestError::Walkdir(value)
+    }
+}
+
+impl From<toml::de::Error> for ManifestError {
+    fn from(value: toml::de::Error) -> Self {
+        ManifestError::Parse(value)
+    }
+}
+
+impl From<toml::ser::Error> for ManifestError {
+    fn from(value: toml::ser::Error) -> Self {
+        ManifestError::Write(value)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChangeKind {
+    Modified,
+    Missing,
+    Added,
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChangeKind::Modified => write!(f, "modified"),
+            ChangeKind::Missing => write!(f, "missing"),
+            ChangeKind::Added => write!(f, "added"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestChange {
+    path: String,
+    kind: ChangeKind,
+}
+
+impl ManifestChange {
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn kind(&self) -> ChangeKind {
+        self.kind
+    }
+}
+
+impl fmt::Display for ManifestChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.kind, self.path)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    #[serde(default)]
+    provenance: Option<Provenance>,
+    #[serde(default)]
+    files: BTreeMap<String, String>,
+}
+
+impl Manifest {
+    pub fn path(project_root: &Path) -> PathBuf {
+        project_root.join(STATE_DIR).join(MANIFEST_FILE)
+    }
+
+    pub fn compute(project_root: &Path, config: &ImportConfig) -> Result<Self, ManifestError> {
+        let mut files = BTreeMap::new();
+        let roots = [
+            config.symbol_lib(),
+            config.footprint_lib(),
+            config.step_dir(),
+            Path::new(TableKind::Symbol.file_name()),
+            Path::new(TableKind::Footprint.file_name()),
+        ];
+        for root in roots {
+            let root = project_root.join(root);
+            if !root.exists() {
+                continue;
+            }
+            for entry in WalkDir::new(&root) {
+                let entry = entry?;
+                if !entry.file_type().is_file() {
+                    continue;
+                }
+                let path = entry.path();
+                let key = path
+                    .strip_prefix(project_root)
+                    .unwrap_or(path)
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                files.insert(key, sha256_file(path)?);
+            }
+        }
+        Ok(Self {
+            provenance: None,
+            files,
+        })
+    }
+
+    pub fn load(project_root: &Path) -> Result<Self, ManifestError> {
+        let path = Self::path(project_root);
+        if !path.exists() {
+            return Err(ManifestError::Missing(path));
+        }
+        let raw = fs::read_to_string(path)?;
+        Ok(toml::from_str(&raw)?)
+    }
+
+    pub fn save(&self, project_root: &Path) -> Result<(), ManifestError> {
+        let path = Self::path(project_root);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn set_provenance(&mut self, provenance: Provenance) {
+        self.provenance = Some(provenance);
+    }
+
+    pub fn provenance(&self) -> Option<&Provenance> {
+        self.provenance.as_ref()
+    }
+
+    pub fn files(&self) -> &BTreeMap<String, String> {
+        &self.files
+    }
+
+    pub fn diff(&self, current: &Manifest) -> Vec<ManifestChange> {
+        let mut changes = Vec::new();
+        for (path, hash) in &self.files {
+            let kind = match current.files.get(path) {
+                Some(current_hash) if current_hash == hash => continue,
+                Some(_) => ChangeKind::Modified,
+                None => ChangeKind::Missing,
+            };
+            changes.push(ManifestChange {
+                path: path.clone(),
+                kind,
+            });
+        }
+        for path in current.files.keys() {
+            if !self.files.contains_key(path) {
+                changes.push(ManifestChange {
+                    path: path.clone(),
+                    kind: ChangeKind::Added,
+                });
+            }
+        }
+        changes
+    }
+}
+
+pub fn sha256_file(path: &Path) -> io::Result<String> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn detects_modified_missing_and_added_files() {
+        let dir = tempdir().unwrap();
+        let config = ImportConfig::new(
+            PathBuf::from("lib.kicad_sym"),
+            PathBuf::from("Lib.pretty"),
+            PathBuf::from("3d"),
+        );
+        fs::write(dir.path().join("lib.kicad_sym"), "(kicad_symbol_lib)").unwrap();
+        fs::create_dir_all(dir.path().join("Lib.pretty")).unwrap();
+        fs::write(
+            dir.path().join("Lib.pretty/A.kicad_mod"),
+            "(footprint \"A\")",
+        )
+        .unwrap();
+
+        let frozen = Manifest::compute(dir.path(), &config).unwrap();
+        frozen.save(dir.path()).unwrap();
+        assert_eq!(Manifest::load(dir.path()).unwrap(), frozen);
+        assert_eq!(
+            frozen.files()["lib.kicad_sym"],
+            "cde9cc46a4a1b642e4a2a52d792a1a84d3189606964671bee0840c97c5f48f6f"
+        );
+
+        fs::write(
+            dir.path().join("lib.kicad_sym"),
+            "(kicad_symbol_lib tampered)",
+        )
+        .unwrap();
+        fs::remove_file(dir.path().join("Lib.pretty/A.kicad_mod")).unwrap();
+        fs::write(
+            dir.path().join("Lib.pretty/B.kicad_mod"),
+            "(footprint \"B\")",
+        )
+        .unwrap();
+
+        let current = Manifest::compute(dir.path(), &config).unwrap();
+        let changes: Vec<String> = frozen
+            .diff(&current)
+            .iter()
+            .map(|change| change.to_string())
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                "missing: Lib.pretty/A.kicad_mod",
+                "modified: lib.kicad_sym",
+                "added: Lib.pretty/B.kicad_mod",
+            ]
+        );
+    }
+}
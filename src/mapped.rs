@@ -0,0 +1,70 @@
+use memmap2::Mmap;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+pub const MAP_THRESHOLD: u64 = 1024 * 1024;
+
+pub enum MappedFile {
+    Mapped(Mmap),
+    Read(Vec<u8>),
+}
+
+impl MappedFile {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let mut file = File::open(path)?;
+        let len = file.metadata()?.len();
+        if len < MAP_THRESHOLD {
+            let mut bytes = Vec::with_capacity(len as usize);
+            file.read_to_end(&mut bytes)?;
+            return Ok(MappedFile::Read(bytes));
+        }
+        // SAFETY: the map is read-only and lives no longer than one command. As with any mmap,
+        // truncating the file while it is mapped is not protected against.
+        let map = unsafe { Mmap::map(&file)? };
+        Ok(MappedFile::Mapped(map))
+    }
+
+    pub fn is_mapped(&self) -> bool {
+        matches!(self, MappedFile::Mapped(_))
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        match self {
+            MappedFile::Mapped(map) => map,
+            MappedFile::Read(bytes) => bytes,
+        }
+    }
+
+    pub fn text(&self) -> io::Result<&str> {
+        std::str::from_utf8(self.bytes())
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn maps_only_large_files() {
+        let dir = tempdir().unwrap();
+        let small = dir.path().join("small.kicad_sym");
+        std::fs::write(&small, "(kicad_symbol_lib)").unwrap();
+        let file = MappedFile::open(&small).unwrap();
+        assert!(!file.is_mapped());
+        assert_eq!(file.text().unwrap(), "(kicad_symbol_lib)");
+
+        let large = dir.path().join("large.kicad_sym");
+        let content = format!("(kicad_symbol_lib {})", "x".repeat(MAP_THRESHOLD as usize));
+        std::fs::write(&large, &content).unwrap();
+        let file = MappedFile::open(&large).unwrap();
+        assert!(file.is_mapped());
+        assert_eq!(file.text().unwrap(), content);
+
+        std::fs::write(&small, [0xff, 0xfe]).unwrap();
+        let err = MappedFile::open(&small).unwrap().text().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,255 @@
+use crate::kicad_sym::{AddPolicy, KicadSymError, KicadSymbolLib};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+#[derive(Debug)]
+pub enum MergeError {
+    Io(io::Error),
+    Symbol(KicadSymError),
+    UnknownSymbol(String),
+    Conflicts(Vec<String>),
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeError::Io(err) => write!(f, "io error: {}", err),
+            MergeError::Symbol(err) => write!(f, "cannot merge symbols: {}", err),
+            MergeError::UnknownSymbol(name) => {
+                write!(f, "policy given for {}, which is not in the library", name)
+            }
+            MergeError::Conflicts(names) => write!(
+                f,
+                "symbols already in the project library: {} (pick a policy with --existing or --policy)",
+                names.join(", ")
+            ),
+        }
+    }
+}
+
+impl Error for MergeError {}
+
+impl From<io::Error> for MergeError {
+    fn from(value: io::Error) -> Self {
+        MergeError::Io(value)
+    }
+}
+
+impl From<KicadSymError> for MergeError {
+    fn from(value: KicadSymError) -> Self {
+        MergeError::Symbol(value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeOutcome {
+    Added,
+    Unchanged,
+    Replaced,
+    Skipped,
+    Relinked,
+    Merged,
+}
+
+impl MergeOutcome {
+    pub fn all() -> &'static [MergeOutcome] {
+        &[
+            MergeOutcome::Added,
+            MergeOutcome::Unchanged,
+            MergeOutcome::Replaced,
+            MergeOutcome::Skipped,
+            MergeOutcome::Relinked,
+            MergeOutcome::Merged,
+        ]
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MergeOutcome::Added => "added",
+            MergeOutcome::Unchanged => "unchanged",
+            MergeOutcome::Replaced => "replaced",
+            MergeOutcome::Skipped => "skipped",
+            MergeOutcome::Relinked => "relinked",
+            MergeOutcome::Merged => "merged",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergedSymbol {
+    name: String,
+    outcome: MergeOutcome,
+}
+
+impl MergedSymbol {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn outcome(&self) -> MergeOutcome {
+        self.outcome
+    }
+}
+
+impl fmt::Display for MergedSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.outcome.name(), self.name)
+    }
+}
+
+pub fn merge_symbol_lib(
+    target: &Path,
+    source: &Path,
+    default: AddPolicy,
+    policies: &HashMap<String, AddPolicy>,
+) -> Result<Vec<MergedSymbol>, MergeError> {
+    let source_lib = KicadSymbolLib::parse(&fs::read_to_string(source)?)?;
+    if let Some(name) = policies
+        .keys()
+        .find(|name| source_lib.symbol(name).is_none())
+    {
+        return Err(MergeError::UnknownSymbol(name.clone()));
+    }
+    let mut target_lib = if target.exists() {
+        KicadSymbolLib::parse(&fs::read_to_string(target)?)?
+    } else {
+        KicadSymbolLib::parse("(kicad_symbol_lib (version 20231120) (generator kci))")?
+    };
+
+    let mut merged = Vec::new();
+    let mut conflicts = Vec::new();
+    for symbol in source_lib.symbols_iter() {
+        let policy = policies.get(symbol.name()).copied().unwrap_or(default);
+        let outcome = match target_lib.symbol(symbol.name()) {
+            None => MergeOutcome::Added,
+            Some(existing) if existing.clone().into_sexp() == symbol.clone().into_sexp() => {
+                MergeOutcome::Unchanged
+            }
+            Some(_) => match policy {
+                AddPolicy::ReplaceExisting => MergeOutcome::Replaced,
+                AddPolicy::SkipExisting => MergeOutcome::Skipped,
+                AddPolicy::RelinkExisting => MergeOutcome::Relinked,
+                AddPolicy::MergeProperties => MergeOutcome::Merged,
+                AddPolicy::ErrorOnConflict => {
+                    conflicts.push(symbol.name().to_string());
+                    continue;
+                }
+            },
+        };
+        merged.push((symbol, policy, outcome));
+    }
+    if !conflicts.is_empty() {
+        return Err(MergeError::Conflicts(conflicts));
+    }
+
+    for (symbol, policy, outcome) in merged.iter_mut() {
+        if *outcome == MergeOutcome::Unchanged {
+            continue;
+        }
+        let before = target_lib
+            .symbol(symbol.name())
+            .map(|existing| existing.clone().into_sexp());
+        target_lib.add_symbol((*symbol).clone(), *policy)?;
+        let after = target_lib
+            .symbol(symbol.name())
+            .map(|existing| existing.clone().into_sexp());
+        if before == after && matches!(outcome, MergeOutcome::Merged | MergeOutcome::Relinked) {
+            *outcome = MergeOutcome::Unchanged;
+        }
+    }
+    if let Some(parent) = target.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(target, target_lib.to_string_pretty())?;
+    Ok(merged
+        .into_iter()
+        .map(|(symbol, _, outcome)| MergedSymbol {
+            name: symbol.name().to_string(),
+            outcome,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn merges_with_per_symbol_policies() {
+        let dir = tempdir().unwrap();
+        let target = dir.path().join("project.kicad_sym");
+        let source = dir.path().join("other.kicad_sym");
+        fs::write(
+            &target,
+            r#"(kicad_symbol_lib
+                (symbol "NE555" (property "Value" "NE555"))
+                (symbol "LM358" (property "Value" "LM358"))
+                (symbol "TL072" (property "Value" "TL072")))"#,
+        )
+        .unwrap();
+        fs::write(
+            &source,
+            r#"(kicad_symbol_lib
+                (symbol "NE555" (property "Value" "NE555"))
+                (symbol "LM358" (property "Value" "LM358") (property "MPN" "LM358DR"))
+                (symbol "TL072" (property "Value" "TL072CP"))
+                (symbol "OPA2134" (property "Value" "OPA2134")))"#,
+        )
+        .unwrap();
+
+        let err = merge_symbol_lib(
+            &target,
+            &source,
+            AddPolicy::ErrorOnConflict,
+            &HashMap::new(),
+        )
+        .unwrap_err();
+        assert!(matches!(&err, MergeError::Conflicts(names) if names == &["LM358", "TL072"]));
+
+        let policies = HashMap::from([("TL072".to_string(), AddPolicy::SkipExisting)]);
+        let merged =
+            merge_symbol_lib(&target, &source, AddPolicy::MergeProperties, &policies).unwrap();
+        let outcomes: Vec<String> = merged.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            outcomes,
+            [
+                "unchanged NE555",
+                "merged LM358",
+                "skipped TL072",
+                "added OPA2134"
+            ]
+        );
+        let lib = KicadSymbolLib::parse(&fs::read_to_string(&target).unwrap()).unwrap();
+        assert_eq!(
+            lib.symbol("LM358")
+                .unwrap()
+                .property_value("MPN")
+                .as_deref(),
+            Some("LM358DR")
+        );
+        assert_eq!(
+            lib.symbol("TL072")
+                .unwrap()
+                .property_value("Value")
+                .as_deref(),
+            Some("TL072")
+        );
+        assert!(lib.symbol("OPA2134").is_some());
+        let merged =
+            merge_symbol_lib(&target, &source, AddPolicy::MergeProperties, &policies).unwrap();
+        assert_eq!(merged[1].outcome(), MergeOutcome::Unchanged);
+
+        let policies = HashMap::from([("LM741".to_string(), AddPolicy::SkipExisting)]);
+        assert!(matches!(
+            merge_symbol_lib(&target, &source, AddPolicy::ReplaceExisting, &policies),
+            Err(MergeError::UnknownSymbol(name)) if name == "LM741"
+        ));
+    }
+}
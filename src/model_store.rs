@@ -0,0 +1,139 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+pub const DEFAULT_MODEL_VARIABLE: &str = "KCI_3DMODELS";
+const S3_SCHEME: &str = "s3://";
+
+#[derive(Debug)]
+pub enum ModelStoreError {
+    Io(io::Error),
+    Invalid(String),
+    Tool(String),
+}
+
+impl fmt::Display for ModelStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModelStoreError::Io(err) => write!(f, "io error: {}", err),
+            ModelStoreError::Invalid(msg) => write!(f, "invalid model store: {}", msg),
+            ModelStoreError::Tool(msg) => write!(f, "aws failed: {}", msg),
+        }
+    }
+}
+
+impl Error for ModelStoreError {}
+
+impl From<io::Error> for ModelStoreError {
+    fn from(value: io::Error) -> Self {
+        ModelStoreError::Io(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelStore {
+    url: String,
+    endpoint: Option<String>,
+    variable: String,
+}
+
+impl ModelStore {
+    pub fn new(url: &str) -> Result<Self, ModelStoreError> {
+        let bucket = url.strip_prefix(S3_SCHEME).unwrap_or_default();
+        if bucket.trim_matches('/').is_empty() {
+            return Err(ModelStoreError::Invalid(format!(
+                "expected {}BUCKET[/PREFIX]: {}",
+                S3_SCHEME, url
+            )));
+        }
+        Ok(Self {
+            url: url.trim_end_matches('/').to_string(),
+            endpoint: None,
+            variable: DEFAULT_MODEL_VARIABLE.to_string(),
+        })
+    }
+
+    pub fn with_endpoint(mut self, endpoint: Option<String>) -> Self {
+        self.endpoint = endpoint;
+        self
+    }
+
+    pub fn with_variable(mut self, variable: Option<String>) -> Self {
+        if let Some(variable) = variable {
+            self.variable = variable;
+        }
+        self
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn endpoint(&self) -> Option<&str> {
+        self.endpoint.as_deref()
+    }
+
+    pub fn variable(&self) -> &str {
+        &self.variable
+    }
+
+    pub fn object_url(&self, name: &str) -> String {
+        format!("{}/{}", self.url, name.trim_start_matches('/'))
+    }
+
+    pub fn upload(&self, local: &Path, name: &str) -> Result<(), ModelStoreError> {
+        let mut command = self.aws();
+        command.arg(local).arg(self.object_url(name));
+        run_aws(&mut command)
+    }
+
+    pub fn download(&self, name: &str, dest: &Path) -> Result<(), ModelStoreError> {
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut command = self.aws();
+        command.arg(self.object_url(name)).arg(dest);
+        run_aws(&mut command)
+    }
+
+    fn aws(&self) -> Command {
+        let mut command = Command::new("aws");
+        if let Some(endpoint) = &self.endpoint {
+            command.arg("--endpoint-url").arg(endpoint);
+        }
+        command.args(["s3", "cp", "--only-show-errors"]);
+        command
+    }
+}
+
+fn run_aws(command: &mut Command) -> Result<(), ModelStoreError> {
+    let output = command
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|err| ModelStoreError::Tool(err.to_string()))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    Err(ModelStoreError::Tool(
+        String::from_utf8_lossy(&output.stderr).trim().to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_object_urls() {
+        let store = ModelStore::new("s3://models/kicad/").unwrap();
+        assert_eq!(store.variable(), DEFAULT_MODEL_VARIABLE);
+        assert_eq!(
+            store.object_url("SOIC-8/soic8.step"),
+            "s3://models/kicad/SOIC-8/soic8.step"
+        );
+        assert!(ModelStore::new("models/kicad").is_err());
+        assert!(ModelStore::new("s3://").is_err());
+    }
+}
@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+
+pub const SYMBOL_CATEGORY: &str = "symbols";
+pub const FOOTPRINT_CATEGORY: &str = "footprints";
+pub const MODEL_CATEGORY: &str = "3d";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NamingError {
+    UnknownPlaceholder {
+        template: String,
+        placeholder: String,
+    },
+    Unclosed(String),
+    Empty(String),
+}
+
+impl fmt::Display for NamingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NamingError::UnknownPlaceholder {
+                template,
+                placeholder,
+            } => write!(
+                f,
+                "unknown placeholder {{{}}} in library naming template {:?}, use {{project}} or {{category}}",
+                placeholder, template
+            ),
+            NamingError::Unclosed(template) => {
+                write!(f, "unclosed {{ in library naming template {:?}", template)
+            }
+            NamingError::Empty(template) => {
+                write!(
+                    f,
+                    "library naming template {:?} expands to nothing",
+                    template
+                )
+            }
+        }
+    }
+}
+
+impl Error for NamingError {}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LibraryNaming {
+    #[serde(default)]
+    symbol_lib: Option<String>,
+    #[serde(default)]
+    footprint_lib: Option<String>,
+    #[serde(default)]
+    step_dir: Option<String>,
+}
+
+impl LibraryNaming {
+    pub fn with_symbol_lib(mut self, template: Option<&str>) -> Self {
+        self.symbol_lib = template.map(str::to_string);
+        self
+    }
+
+    pub fn with_footprint_lib(mut self, template: Option<&str>) -> Self {
+        self.footprint_lib = template.map(str::to_string);
+        self
+    }
+
+    pub fn with_step_dir(mut self, template: Option<&str>) -> Self {
+        self.step_dir = template.map(str::to_string);
+        self
+    }
+
+    pub fn symbol_lib(&self, project: &str) -> Result<Option<PathBuf>, NamingError> {
+        expand_template(self.symbol_lib.as_deref(), project, SYMBOL_CATEGORY)
+    }
+
+    pub fn footprint_lib(&self, project: &str) -> Result<Option<PathBuf>, NamingError> {
+        expand_template(self.footprint_lib.as_deref(), project, FOOTPRINT_CATEGORY)
+    }
+
+    pub fn step_dir(&self, project: &str) -> Result<Option<PathBuf>, NamingError> {
+        expand_template(self.step_dir.as_deref(), project, MODEL_CATEGORY)
+    }
+}
+
+fn expand_template(
+    template: Option<&str>,
+    project: &str,
+    category: &str,
+) -> Result<Option<PathBuf>, NamingError> {
+    let Some(template) = template else {
+        return Ok(None);
+    };
+    let mut out = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('}') else {
+            return Err(NamingError::Unclosed(template.to_string()));
+        };
+        match &rest[start + 1..start + end] {
+            "project" => out.push_str(project),
+            "category" => out.push_str(category),
+            placeholder => {
+                return Err(NamingError::UnknownPlaceholder {
+                    template: template.to_string(),
+                    placeholder: placeholder.to_string(),
+                });
+            }
+        }
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+    if out.trim().is_empty() {
+        return Err(NamingError::Empty(template.to_string()));
+    }
+    Ok(Some(PathBuf::from(out)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_project_and_category() {
+        let naming = LibraryNaming::default()
+            .with_symbol_lib(Some("libs/{project}/{category}.kicad_sym"))
+            .with_step_dir(Some("libs/{project}/{category}"));
+        assert_eq!(
+            naming.symbol_lib("board").unwrap(),
+            Some(PathBuf::from("libs/board/symbols.kicad_sym"))
+        );
+        assert_eq!(naming.footprint_lib("board").unwrap(), None);
+        assert_eq!(
+            naming.step_dir("board").unwrap(),
+            Some(PathBuf::from("libs/board/3d"))
+        );
+
+        let naming = LibraryNaming::default().with_symbol_lib(Some("{vendor}.kicad_sym"));
+        assert!(matches!(
+            naming.symbol_lib("board"),
+            Err(NamingError::UnknownPlaceholder { placeholder, .. }) if placeholder == "vendor"
+        ));
+        let naming = LibraryNaming::default().with_footprint_lib(Some("{project.pretty"));
+        assert!(matches!(
+            naming.footprint_lib("board"),
+            Err(NamingError::Unclosed(_))
+        ));
+    }
+}
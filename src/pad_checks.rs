@@ -0,0 +1,348 @@
+use crate::kicad_sym::{Atom, BOARD_PRECISION, Sexp, format_number};
+use std::fmt;
+
+const PASTE_MARGIN: &str = "solder_paste_margin";
+const PASTE_RATIO: &str = "solder_paste_margin_ratio";
+const MASK_MARGIN: &str = "solder_mask_margin";
+const SIZE_TOLERANCE_MM: f64 = 1e-6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PadPolicy {
+    Warn,
+    Fix,
+    Off,
+}
+
+impl PadPolicy {
+    pub fn all() -> &'static [PadPolicy] {
+        &[PadPolicy::Warn, PadPolicy::Fix, PadPolicy::Off]
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            PadPolicy::Warn => "warn",
+            PadPolicy::Fix => "fix",
+            PadPolicy::Off => "off",
+        }
+    }
+}
+
+impl fmt::Display for PadPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl std::str::FromStr for PadPolicy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        PadPolicy::all()
+            .iter()
+            .copied()
+            .find(|policy| policy.name().eq_ignore_ascii_case(value.trim()))
+            .ok_or_else(|| format!("unknown pad check policy: {}", value))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PadProblem {
+    NoPaste { pad: String },
+    MaskDefined { pad: String, margin: String },
+}
+
+impl PadProblem {
+    pub fn pad(&self) -> &str {
+        match self {
+            PadProblem::NoPaste { pad } | PadProblem::MaskDefined { pad, .. } => pad,
+        }
+    }
+}
+
+impl fmt::Display for PadProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PadProblem::NoPaste { pad } => write!(f, "SMD pad {} has no solder paste", pad),
+            PadProblem::MaskDefined { pad, margin } => write!(
+                f,
+                "pad {} is solder-mask defined (mask margin {} mm)",
+                pad, margin
+            ),
+        }
+    }
+}
+
+pub fn check_pads(footprint: &Sexp) -> Vec<PadProblem> {
+    pad_problems(footprint)
+        .into_iter()
+        .map(|(_, problem)| problem)
+        .collect()
+}
+
+pub fn fix_pads(footprint: &mut Sexp) -> Vec<PadProblem> {
+    let problems = pad_problems(footprint);
+    let Sexp::List(items) = footprint else {
+        return Vec::new();
+    };
+    let defaults = Margins::read(items, Margins::default());
+    let mut clear_paste = false;
+    let mut clear_mask = false;
+    for (index, problem) in &problems {
+        let Some(Sexp::List(fields)) = items.get_mut(*index) else {
+            continue;
+        };
+        match problem {
+            PadProblem::NoPaste { .. } => {
+                add_paste_layer(fields);
+                remove_negative(fields, &[PASTE_MARGIN, PASTE_RATIO]);
+                clear_paste |= !has_paste(fields, Margins::read(fields, defaults));
+            }
+            PadProblem::MaskDefined { .. } => {
+                remove_negative(fields, &[MASK_MARGIN]);
+                clear_mask |= defaults.mask < 0.0;
+            }
+        }
+    }
+    if clear_paste {
+        remove_negative(items, &[PASTE_MARGIN, PASTE_RATIO]);
+    }
+    if clear_mask {
+        remove_negative(items, &[MASK_MARGIN]);
+    }
+    problems.into_iter().map(|(_, problem)| problem).collect()
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Margins {
+    paste: f64,
+    paste_ratio: f64,
+    mask: f64,
+}
+
+impl Margins {
+    fn read(fields: &[Sexp], defaults: Margins) -> Margins {
+        let value = |key: &str, default: f64| match field(fields, key)
+            .and_then(|field| field.get(1))
+            .and_then(Sexp::as_f64)
+        {
+            Some(value) if value != 0.0 => value,
+            _ => default,
+        };
+        Margins {
+            paste: value(PASTE_MARGIN, defaults.paste),
+            paste_ratio: value(PASTE_RATIO, defaults.paste_ratio),
+            mask: value(MASK_MARGIN, defaults.mask),
+        }
+    }
+}
+
+fn pad_problems(footprint: &Sexp) -> Vec<(usize, PadProblem)> {
+    let Sexp::List(items) = footprint else {
+        return Vec::new();
+    };
+    let defaults = Margins::read(items, Margins::default());
+    let bga = items
+        .get(1)
+        .and_then(atom)
+        .is_some_and(|name| name.to_ascii_uppercase().contains("BGA"));
+    let apertures: Vec<[f64; 2]> = items
+        .iter()
+        .filter_map(pad_fields)
+        .filter(|fields| copper_side(fields).is_none() && layers(fields).any(is_paste_layer))
+        .filter_map(|fields| pair(fields, "at"))
+        .collect();
+
+    let mut problems = Vec::new();
+    for (index, item) in items.iter().enumerate() {
+        let Some(fields) = pad_fields(item) else {
+            continue;
+        };
+        let Some(number) = fields
+            .get(1)
+            .and_then(atom)
+            .filter(|number| !number.is_empty())
+        else {
+            continue;
+        };
+        if copper_side(fields).is_none() {
+            continue;
+        }
+        let margins = Margins::read(fields, defaults);
+        if fields.get(2).and_then(atom) == Some("smd")
+            && !has_paste(fields, margins)
+            && !covered(fields, &apertures)
+        {
+            problems.push((
+                index,
+                PadProblem::NoPaste {
+                    pad: number.to_string(),
+                },
+            ));
+        }
+        if !bga && margins.mask < 0.0 && layers(fields).any(|layer| layer.ends_with(".Mask")) {
+            problems.push((
+                index,
+                PadProblem::MaskDefined {
+                    pad: number.to_string(),
+                    margin: format_number(margins.mask, BOARD_PRECISION),
+                },
+            ));
+        }
+    }
+    problems
+}
+
+fn has_paste(fields: &[Sexp], margins: Margins) -> bool {
+    if !layers(fields).any(is_paste_layer) {
+        return false;
+    }
+    pair(fields, "size").is_none_or(|size| {
+        size.iter()
+            .all(|&dim| dim + 2.0 * (margins.paste + margins.paste_ratio * dim) > SIZE_TOLERANCE_MM)
+    })
+}
+
+fn covered(fields: &[Sexp], apertures: &[[f64; 2]]) -> bool {
+    let (Some(at), Some(size)) = (pair(fields, "at"), pair(fields, "size")) else {
+        return false;
+    };
+    let reach = size[0].max(size[1]) / 2.0;
+    apertures
+        .iter()
+        .any(|point| (point[0] - at[0]).abs() <= reach && (point[1] - at[1]).abs() <= reach)
+}
+
+fn add_paste_layer(fields: &mut [Sexp]) {
+    if layers(fields).any(is_paste_layer) {
+        return;
+    }
+    let Some(paste) = copper_side(fields) else {
+        return;
+    };
+    if let Some(Sexp::List(layers)) = fields
+        .iter_mut()
+        .find(|field| field_name(field) == Some("layers"))
+    {
+        layers.push(Sexp::Atom(Atom::new_quoted(paste)));
+    }
+}
+
+fn remove_negative(fields: &mut Vec<Sexp>, keys: &[&str]) {
+    fields.retain(|field| {
+        !(field_name(field).is_some_and(|name| keys.contains(&name))
+            && matches!(field, Sexp::List(items)
+                if items.get(1).and_then(Sexp::as_f64).is_some_and(|value| value < 0.0)))
+    });
+}
+
+fn copper_side(fields: &[Sexp]) -> Option<&'static str> {
+    layers(fields).find_map(|layer| match layer {
+        "F.Cu" | "*.Cu" => Some("F.Paste"),
+        "B.Cu" => Some("B.Paste"),
+        _ => None,
+    })
+}
+
+fn is_paste_layer(layer: &str) -> bool {
+    layer.ends_with(".Paste")
+}
+
+fn layers(fields: &[Sexp]) -> impl Iterator<Item = &str> {
+    field(fields, "layers")
+        .map(|layers| &layers[1..])
+        .unwrap_or_default()
+        .iter()
+        .filter_map(atom)
+}
+
+fn pair(fields: &[Sexp], key: &str) -> Option<[f64; 2]> {
+    let field = field(fields, key)?;
+    Some([field.get(1)?.as_f64()?, field.get(2)?.as_f64()?])
+}
+
+fn pad_fields(item: &Sexp) -> Option<&[Sexp]> {
+    match item {
+        Sexp::List(fields) if field_name(item) == Some("pad") => Some(fields),
+        _ => None,
+    }
+}
+
+fn field<'a>(fields: &'a [Sexp], key: &str) -> Option<&'a [Sexp]> {
+    fields.iter().find_map(|field| match field {
+        Sexp::List(items) if field_name(field) == Some(key) => Some(items.as_slice()),
+        _ => None,
+    })
+}
+
+fn field_name(sexp: &Sexp) -> Option<&str> {
+    match sexp {
+        Sexp::List(items) => items.first().and_then(atom),
+        _ => None,
+    }
+}
+
+fn atom(sexp: &Sexp) -> Option<&str> {
+    match sexp {
+        Sexp::Atom(atom) => Some(atom.value()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kicad_sym::parse_one;
+
+    #[test]
+    fn flags_and_fixes_paste_and_mask_defects() {
+        let mut footprint = parse_one(
+            r#"(footprint "QFN-8-1EP"
+                (solder_mask_margin -0.02)
+                (pad "1" smd rect (at -1 -0.75) (size 0.6 0.3) (layers "F.Cu" "F.Paste" "F.Mask"))
+                (pad "2" smd rect (at -1 0.75) (size 0.6 0.3) (layers "F.Cu" "F.Mask"))
+                (pad "3" smd rect (at 1 -0.75) (size 0.6 0.3) (layers "F.Cu" "F.Paste" "F.Mask")
+                    (solder_paste_margin_ratio -0.5))
+                (pad "9" smd rect (at 0 0) (size 1.6 1.6) (layers "F.Cu" "F.Mask"))
+                (pad "" smd rect (at -0.4 -0.4) (size 0.6 0.6) (layers "F.Paste"))
+                (pad "" smd circle (at 2 2) (size 1 1) (layers "F.Cu" "F.Mask")))"#,
+        )
+        .unwrap();
+        let problems: Vec<String> = check_pads(&footprint)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            problems,
+            [
+                "pad 1 is solder-mask defined (mask margin -0.02 mm)",
+                "SMD pad 2 has no solder paste",
+                "pad 2 is solder-mask defined (mask margin -0.02 mm)",
+                "SMD pad 3 has no solder paste",
+                "pad 3 is solder-mask defined (mask margin -0.02 mm)",
+                "pad 9 is solder-mask defined (mask margin -0.02 mm)",
+            ]
+        );
+
+        assert_eq!(fix_pads(&mut footprint).len(), 6);
+        assert!(check_pads(&footprint).is_empty());
+        let text = footprint.to_string_pretty();
+        assert!(!text.contains("solder_mask_margin"), "{}", text);
+        assert!(!text.contains("solder_paste_margin_ratio"), "{}", text);
+        assert_eq!(text.matches("\"F.Paste\"").count(), 4, "{}", text);
+
+        let mut bga = parse_one(
+            r#"(footprint "BGA-4"
+                (pad "A1" smd circle (at 0 0) (size 0.3 0.3) (layers "F.Cu" "F.Paste" "F.Mask")
+                    (solder_mask_margin -0.05)))"#,
+        )
+        .unwrap();
+        assert!(check_pads(&bga).is_empty());
+        assert!(fix_pads(&mut bga).is_empty());
+        assert!(
+            "Fix"
+                .parse::<PadPolicy>()
+                .is_ok_and(|policy| policy == PadPolicy::Fix)
+        );
+        assert!("strict".parse::<PadPolicy>().is_err());
+    }
+}
@@ -0,0 +1,126 @@
+use crate::kicad_sym::Symbol;
+
+const SEPARATORS: &[char] = &['/', '|', ',', ';'];
+
+pub fn clean_pin_text(symbol: &mut Symbol) -> usize {
+    symbol.map_pin_text(|kind, text| match kind {
+        "name" => Some(clean_pin_name(text)),
+        _ => Some(collapse_whitespace(text)),
+    })
+}
+
+pub fn clean_pin_name(name: &str) -> String {
+    let name = collapse_whitespace(name);
+    if name.is_empty() || name == "~" {
+        return name;
+    }
+    normalize_separators(&fix_overlines(&name))
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn fix_overlines(name: &str) -> String {
+    let mut out = String::new();
+    let mut chars = name.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch != '~' {
+            out.push(ch);
+            continue;
+        }
+        while chars.next_if(|next| next.is_whitespace()).is_some() {}
+        let braced = chars.next_if_eq(&'{').is_some();
+        let mut text = String::new();
+        let mut depth = 0;
+        while let Some(&next) = chars.peek() {
+            if braced {
+                chars.next();
+                match next {
+                    '{' => depth += 1,
+                    '}' if depth == 0 => break,
+                    '}' => depth -= 1,
+                    _ => {}
+                }
+            } else if next == '~' {
+                chars.next();
+                break;
+            } else if next.is_whitespace() || SEPARATORS.contains(&next) {
+                break;
+            } else {
+                chars.next();
+            }
+            text.push(next);
+        }
+        let text = text.trim();
+        if !text.is_empty() {
+            out.push_str("~{");
+            out.push_str(text);
+            out.push('}');
+        }
+    }
+    out
+}
+
+fn normalize_separators(name: &str) -> String {
+    let mut out = String::new();
+    let mut depth = 0usize;
+    let mut chars = name.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        if depth > 0 || !SEPARATORS.contains(&ch) {
+            out.push(ch);
+            continue;
+        }
+        let trimmed = out.trim_end().len();
+        out.truncate(trimmed);
+        while chars
+            .next_if(|next| next.is_whitespace() || SEPARATORS.contains(next))
+            .is_some()
+        {}
+        if !out.is_empty() && chars.peek().is_some() {
+            out.push('/');
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cleans_names_and_numbers() {
+        for (raw, clean) in [
+            (" RESET ", "RESET"),
+            ("~RESET", "~{RESET}"),
+            ("~{RESET}", "~{RESET}"),
+            ("~{ RESET", "~{RESET}"),
+            ("~RESET~/IO", "~{RESET}/IO"),
+            ("~WR/~RD", "~{WR}/~{RD}"),
+            ("~{CS_{1}}", "~{CS_{1}}"),
+            ("PA0 / ADC1_IN0 | TIM2_CH1", "PA0/ADC1_IN0/TIM2_CH1"),
+            ("VDD,,VDDA,", "VDD/VDDA"),
+            ("~", "~"),
+            ("EN  1", "EN 1"),
+        ] {
+            assert_eq!(clean_pin_name(raw), clean, "{:?}", raw);
+        }
+
+        let mut symbol = Symbol::parse(
+            "(symbol \"U1\" (symbol \"U1_1_1\" \
+             (pin input line (name \"~RST \" (effects)) (number \" 1\" (effects))) \
+             (pin input line (name \"A/B\") (number \"2\"))))",
+        )
+        .unwrap();
+        assert_eq!(clean_pin_text(&mut symbol), 2);
+        let text = symbol.into_sexp().to_string_pretty();
+        assert!(text.contains("\"~{RST}\""), "{}", text);
+        assert!(text.contains("\"1\"") && !text.contains("\" 1\""), "{}", text);
+        assert!(text.contains("\"A/B\""), "{}", text);
+    }
+}
@@ -0,0 +1,285 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+
+const SSH_SCHEME: &str = "ssh://";
+
+#[derive(Debug)]
+pub enum RemoteError {
+    Io(io::Error),
+    Invalid(String),
+    Tool { tool: &'static str, message: String },
+}
+
+impl fmt::Display for RemoteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RemoteError::Io(err) => write!(f, "io error: {}", err),
+            RemoteError::Invalid(msg) => write!(f, "invalid remote target: {}", msg),
+            RemoteError::Tool { tool, message } => write!(f, "{} failed: {}", tool, message),
+        }
+    }
+}
+
+impl Error for RemoteError {}
+
+impl From<io::Error> for RemoteError {
+    fn from(value: io::Error) -> Self {
+        RemoteError::Io(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteTarget {
+    host: String,
+    port: Option<u16>,
+    path: PathBuf,
+}
+
+impl RemoteTarget {
+    pub fn from_path(path: &Path) -> Option<Result<Self, RemoteError>> {
+        let value = path.to_str()?;
+        if !value.starts_with(SSH_SCHEME) {
+            return None;
+        }
+        Some(value.parse())
+    }
+
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    pub fn port(&self) -> Option<u16> {
+        self.port
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn exists(&self) -> Result<bool, RemoteError> {
+        let output = self
+            .remote_shell("test -e", &self.path)
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|err| tool_error("ssh", err.to_string()))?;
+        match output.status.code() {
+            Some(0) => Ok(true),
+            Some(1) => Ok(false),
+            _ => Err(tool_error(
+                "ssh",
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            )),
+        }
+    }
+
+    pub fn fetch(&self, dest: &Path) -> Result<bool, RemoteError> {
+        if !self.exists()? {
+            return Ok(false);
+        }
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut command = self.scp();
+        command
+            .arg("-r")
+            .arg("--")
+            .arg(self.remote_spec(&self.path))
+            .arg(dest);
+        run_tool("scp", &mut command)?;
+        Ok(true)
+    }
+
+    pub fn push(&self, local: &Path, remote_path: &Path) -> Result<(), RemoteError> {
+        if let Some(parent) = remote_path.parent() {
+            run_tool("ssh", &mut self.remote_shell("mkdir -p", parent))?;
+        }
+        let mut command = self.scp();
+        command
+            .arg("--")
+            .arg(local)
+            .arg(self.remote_spec(remote_path));
+        run_tool("scp", &mut command)
+    }
+
+    fn ssh(&self) -> Command {
+        let mut command = Command::new("ssh");
+        if let Some(port) = self.port {
+            command.arg("-p").arg(port.to_string());
+        }
+        command.arg("--").arg(&self.host);
+        command
+    }
+
+    fn remote_shell(&self, command: &str, path: &Path) -> Command {
+        let mut ssh = self.ssh();
+        ssh.arg(format!("{} {}", command, shell_quote(path)));
+        ssh
+    }
+
+    fn scp(&self) -> Command {
+        let mut command = Command::new("scp");
+        command.args(["-q", "-O"]);
+        if let Some(port) = self.port {
+            command.arg("-P").arg(port.to_string());
+        }
+        command
+    }
+
+    fn remote_spec(&self, path: &Path) -> String {
+        format!("{}:{}", self.host, shell_quote(path))
+    }
+}
+
+impl fmt::Display for RemoteTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.port {
+            Some(port) => write!(
+                f,
+                "{}{}:{}{}",
+                SSH_SCHEME,
+                self.host,
+                port,
+                self.path.display()
+            ),
+            None => write!(f, "{}{}{}", SSH_SCHEME, self.host, self.path.display()),
+        }
+    }
+}
+
+impl FromStr for RemoteTarget {
+    type Err = RemoteError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let rest = value
+            .strip_prefix(SSH_SCHEME)
+            .ok_or_else(|| RemoteError::Invalid(format!("expected {}: {}", SSH_SCHEME, value)))?;
+        let (authority, path) = rest
+            .find('/')
+            .map(|idx| rest.split_at(idx))
+            .ok_or_else(|| RemoteError::Invalid(format!("missing remote path: {}", value)))?;
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => {
+                let port = port
+                    .parse::<u16>()
+                    .map_err(|_| RemoteError::Invalid(format!("invalid port: {}", value)))?;
+                (host, Some(port))
+            }
+            None => (authority, None),
+        };
+        if host.is_empty() || host.ends_with('@') {
+            return Err(RemoteError::Invalid(format!("missing host: {}", value)));
+        }
+        if host.starts_with('-') {
+            return Err(RemoteError::Invalid(format!("invalid host: {}", value)));
+        }
+        if path.len() <= 1 {
+            return Err(RemoteError::Invalid(format!(
+                "missing remote path: {}",
+                value
+            )));
+        }
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            path: PathBuf::from(path),
+        })
+    }
+}
+
+pub fn run_sync_command(command: &str, project_root: &Path) -> Result<(), RemoteError> {
+    let mut process = if cfg!(windows) {
+        let mut process = Command::new("cmd");
+        process.arg("/C").arg(command);
+        process
+    } else {
+        let mut process = Command::new("sh");
+        process.arg("-c").arg(command);
+        process
+    };
+    process.current_dir(project_root);
+    run_tool("sync command", &mut process)
+}
+
+fn run_tool(tool: &'static str, command: &mut Command) -> Result<(), RemoteError> {
+    let output = command
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|err| tool_error(tool, err.to_string()))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    Err(tool_error(
+        tool,
+        String::from_utf8_lossy(&output.stderr).trim().to_string(),
+    ))
+}
+
+fn tool_error(tool: &'static str, message: String) -> RemoteError {
+    RemoteError::Tool { tool, message }
+}
+
+fn shell_quote(path: &Path) -> String {
+    format!("'{}'", path.to_string_lossy().replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ssh_targets() {
+        let target = RemoteTarget::from_path(Path::new("ssh://lib@server:2222/srv/kicad/3d"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(target.host(), "lib@server");
+        assert_eq!(target.port(), Some(2222));
+        assert_eq!(target.path(), Path::new("/srv/kicad/3d"));
+        assert_eq!(target.to_string(), "ssh://lib@server:2222/srv/kicad/3d");
+
+        let target: RemoteTarget = "ssh://server/srv/lib.kicad_sym".parse().unwrap();
+        assert_eq!(target.port(), None);
+        assert!(RemoteTarget::from_path(Path::new("lib.kicad_sym")).is_none());
+        assert!("ssh://server".parse::<RemoteTarget>().is_err());
+        assert!("ssh://server:ssh/srv".parse::<RemoteTarget>().is_err());
+        assert!(
+            "ssh://-oProxyCommand=sh/srv"
+                .parse::<RemoteTarget>()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn quotes_remote_paths_for_the_remote_shell() {
+        let target: RemoteTarget = "ssh://server/srv/My Lib.pretty".parse().unwrap();
+        let path = Path::new("/srv/My Lib.pretty/R_$HOME's 0603.kicad_mod");
+        let command = target.remote_shell("test -e", path);
+        let args: Vec<_> = command.get_args().collect();
+        assert_eq!(args[..2], ["--", "server"]);
+        let remote = args[2].to_str().unwrap();
+        let output = Command::new("sh")
+            .arg("-c")
+            .arg(remote.replacen("test -e", "printf %s", 1))
+            .output()
+            .unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            path.to_string_lossy()
+        );
+
+        let spec = target.remote_spec(path);
+        let quoted = spec.strip_prefix("server:").unwrap();
+        let output = Command::new("sh")
+            .arg("-c")
+            .arg(format!("printf %s {}", quoted))
+            .output()
+            .unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            path.to_string_lossy()
+        );
+    }
+}
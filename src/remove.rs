@@ -0,0 +1,274 @@
+use crate::importer::{ImportConfig, model_reference};
+use crate::kicad_sym::{KicadSymError, KicadSymbolLib, parse_one, parse_sexps};
+use crate::kicad_table::{TableError, TableKind, lib_name_from_path};
+use crate::lookup::{count_lib_ids, schematic_files};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug)]
+pub enum RemoveError {
+    Io(io::Error),
+    Walkdir(walkdir::Error),
+    Symbol(KicadSymError),
+    Table(TableError),
+}
+
+impl fmt::Display for RemoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RemoveError::Io(err) => write!(f, "io error: {}", err),
+            RemoveError::Walkdir(err) => write!(f, "walk error: {}", err),
+            RemoveError::Symbol(err) => write!(f, "cannot remove symbol: {}", err),
+            RemoveError::Table(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Error for RemoveError {}
+
+impl From<io::Error> for RemoveError {
+    fn from(value: io::Error) -> Self {
+        RemoveError::Io(value)
+    }
+}
+
+impl From<walkdir::Error> for RemoveError {
+    fn from(value: walkdir::Error) -> Self {
+        RemoveError::Walkdir(value)
+    }
+}
+
+impl From<KicadSymError> for RemoveError {
+    fn from(value: KicadSymError) -> Self {
+        RemoveError::Symbol(value)
+    }
+}
+
+impl From<TableError> for RemoveError {
+    fn from(value: TableError) -> Self {
+        RemoveError::Table(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Removal {
+    symbol: String,
+    footprint: Option<String>,
+    removed_footprint: bool,
+    removed_models: Vec<PathBuf>,
+    kept: Vec<String>,
+    placed: usize,
+}
+
+impl Removal {
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn footprint(&self) -> Option<&str> {
+        self.footprint.as_deref()
+    }
+
+    pub fn removed_footprint(&self) -> bool {
+        self.removed_footprint
+    }
+
+    pub fn removed_models(&self) -> &[PathBuf] {
+        &self.removed_models
+    }
+
+    pub fn kept(&self) -> &[String] {
+        &self.kept
+    }
+
+    pub fn placed(&self) -> usize {
+        self.placed
+    }
+}
+
+pub fn remove_symbol(
+    project_root: &Path,
+    config: &ImportConfig,
+    name: &str,
+    with_footprint: bool,
+) -> Result<Removal, RemoveError> {
+    let symbol_lib = project_root.join(config.symbol_lib());
+    let mut lib = KicadSymbolLib::parse(&fs::read_to_string(&symbol_lib)?)?;
+    let symbol = lib.delete_symbol(name)?;
+    fs::write(&symbol_lib, lib.to_string_pretty())?;
+
+    let mut removal = Removal {
+        symbol: name.to_string(),
+        footprint: symbol
+            .property_value("Footprint")
+            .filter(|value| !value.is_empty()),
+        removed_footprint: false,
+        removed_models: Vec::new(),
+        kept: Vec::new(),
+        placed: placed_count(project_root, config, name)?,
+    };
+    let Some(footprint) = removal.footprint.clone().filter(|_| with_footprint) else {
+        return Ok(removal);
+    };
+    let footprint_lib = lib_name_from_path(TableKind::Footprint, config.footprint_lib())?;
+    let Some(footprint_name) = footprint
+        .strip_prefix(&format!("{}:", footprint_lib))
+        .map(str::to_string)
+    else {
+        removal
+            .kept
+            .push(format!("{} is not in {}", footprint, footprint_lib));
+        return Ok(removal);
+    };
+    let users: Vec<&str> = lib
+        .symbols_iter()
+        .filter(|other| other.property_value("Footprint").as_deref() == Some(footprint.as_str()))
+        .map(|other| other.name())
+        .collect();
+    if !users.is_empty() {
+        removal.kept.push(format!(
+            "{} is still used by {}",
+            footprint,
+            users.join(", ")
+        ));
+        return Ok(removal);
+    }
+
+    let footprint_dir = project_root.join(config.footprint_lib());
+    let footprint_path = footprint_dir.join(format!("{}.kicad_mod", footprint_name));
+    if !footprint_path.exists() {
+        return Ok(removal);
+    }
+    let model =
+        model_reference(&parse_one(&fs::read_to_string(&footprint_path)?)?).map(str::to_string);
+    fs::remove_file(&footprint_path)?;
+    removal.removed_footprint = true;
+
+    let Some(model) = model else {
+        return Ok(removal);
+    };
+    let Some(model_path) = model
+        .strip_prefix("${KIPRJMOD}/")
+        .map(|relative| project_root.join(relative))
+    else {
+        return Ok(removal);
+    };
+    let mut model_users = Vec::new();
+    for entry in fs::read_dir(&footprint_dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) == Some("kicad_mod")
+            && model_reference(&parse_one(&fs::read_to_string(&path)?)?) == Some(model.as_str())
+            && let Some(stem) = path.file_stem()
+        {
+            model_users.push(stem.to_string_lossy().to_string());
+        }
+    }
+    if !model_users.is_empty() {
+        removal.kept.push(format!(
+            "{} is still used by {}",
+            model,
+            model_users.join(", ")
+        ));
+    } else if model_path.exists() {
+        fs::remove_file(&model_path)?;
+        removal.removed_models.push(
+            model_path
+                .strip_prefix(project_root)
+                .unwrap_or(&model_path)
+                .to_path_buf(),
+        );
+    }
+    Ok(removal)
+}
+
+fn placed_count(
+    project_root: &Path,
+    config: &ImportConfig,
+    name: &str,
+) -> Result<usize, RemoveError> {
+    let lib_id = format!(
+        "{}:{}",
+        lib_name_from_path(TableKind::Symbol, config.symbol_lib())?,
+        name
+    );
+    let mut counts = BTreeMap::new();
+    for path in schematic_files(project_root)? {
+        for sexp in parse_sexps(&fs::read_to_string(path)?)? {
+            count_lib_ids(&sexp, &mut counts);
+        }
+    }
+    Ok(counts.get(&lib_id).copied().unwrap_or(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn removes_symbol_and_unshared_assets() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let config = ImportConfig::new(
+            PathBuf::from("lib.kicad_sym"),
+            PathBuf::from("lib.pretty"),
+            PathBuf::from("lib_3d"),
+        );
+        fs::write(
+            root.join("lib.kicad_sym"),
+            r#"(kicad_symbol_lib
+                (symbol "NE555" (property "Footprint" "lib:DIP-8"))
+                (symbol "LM358" (property "Footprint" "lib:SOIC-8"))
+                (symbol "TL072" (property "Footprint" "lib:SOIC-8")))"#,
+        )
+        .unwrap();
+        fs::create_dir_all(root.join("lib.pretty")).unwrap();
+        fs::create_dir_all(root.join("lib_3d")).unwrap();
+        for (footprint, model) in [("DIP-8", "DIP-8"), ("SOIC-8", "SOIC-8"), ("SO-8", "SOIC-8")] {
+            fs::write(
+                root.join(format!("lib.pretty/{}.kicad_mod", footprint)),
+                format!(
+                    "(footprint \"{}\" (model \"${{KIPRJMOD}}/lib_3d/{}.step\"))",
+                    footprint, model
+                ),
+            )
+            .unwrap();
+            fs::write(root.join(format!("lib_3d/{}.step", model)), "ISO-10303-21;").unwrap();
+        }
+        fs::write(
+            root.join("board.kicad_sch"),
+            r#"(kicad_sch (symbol (lib_id "lib:NE555")))"#,
+        )
+        .unwrap();
+
+        let removal = remove_symbol(root, &config, "NE555", true).unwrap();
+        assert!(removal.removed_footprint());
+        assert_eq!(
+            removal.removed_models(),
+            [PathBuf::from("lib_3d/DIP-8.step")]
+        );
+        assert_eq!(removal.placed(), 1);
+        assert!(!root.join("lib.pretty/DIP-8.kicad_mod").exists());
+
+        let removal = remove_symbol(root, &config, "LM358", true).unwrap();
+        assert!(!removal.removed_footprint());
+        assert_eq!(removal.kept(), ["lib:SOIC-8 is still used by TL072"]);
+
+        let removal = remove_symbol(root, &config, "TL072", true).unwrap();
+        assert!(removal.removed_footprint());
+        assert!(removal.removed_models().is_empty());
+        assert!(root.join("lib_3d/SOIC-8.step").exists());
+
+        assert!(matches!(
+            remove_symbol(root, &config, "NE555", false),
+            Err(RemoveError::Symbol(_))
+        ));
+        let lib = KicadSymbolLib::parse(&fs::read_to_string(root.join("lib.kicad_sym")).unwrap())
+            .unwrap();
+        assert_eq!(lib.symbols_iter().count(), 0);
+    }
+}
@@ -0,0 +1,703 @@
+use crate::importer::{ImportConfig, model_path, relative_model_path};
+use crate::kicad_sym::{Atom, KicadSymError, KicadSymbolLib, Sexp, parse_one, parse_sexps};
+use crate::kicad_table::{
+    TableError, TableKind, lib_name_from_path, project_table_entries, rename_project_lib,
+};
+use crate::ledger::Ledger;
+use crate::lookup::{LibraryIndex, count_lib_ids, schematic_files};
+use crate::naming::{LibraryNaming, NamingError};
+use crate::review::ReviewQueue;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+const SYMBOL_LIB_SUFFIX: &str = "_symbols.kicad_sym";
+const FOOTPRINT_LIB_SUFFIX: &str = "_footprints.pretty";
+const STEP_DIR_SUFFIX: &str = "_step";
+const PROJECT_FILE_EXTENSIONS: &[&str] = &["kicad_sch", "kicad_pcb"];
+
+#[derive(Debug)]
+pub enum RenameError {
+    Io(io::Error),
+    Walkdir(walkdir::Error),
+    Symbol(KicadSymError),
+    Table(TableError),
+    State(String),
+    Invalid(String),
+}
+
+impl fmt::Display for RenameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenameError::Io(err) => write!(f, "io error: {}", err),
+            RenameError::Walkdir(err) => write!(f, "walk error: {}", err),
+            RenameError::Symbol(err) => write!(f, "parse error: {}", err),
+            RenameError::Table(err) => write!(f, "{}", err),
+            RenameError::State(msg) => write!(f, "project state error: {}", msg),
+            RenameError::Invalid(msg) => write!(f, "cannot rename: {}", msg),
+        }
+    }
+}
+
+impl Error for RenameError {}
+
+impl From<io::Error> for RenameError {
+    fn from(value: io::Error) -> Self {
+        RenameError::Io(value)
+    }
+}
+
+impl From<walkdir::Error> for RenameError {
+    fn from(value: walkdir::Error) -> Self {
+        RenameError::Walkdir(value)
+    }
+}
+
+impl From<KicadSymError> for RenameError {
+    fn from(value: KicadSymError) -> Self {
+        RenameError::Symbol(value)
+    }
+}
+
+impl From<TableError> for RenameError {
+    fn from(value: TableError) -> Self {
+        RenameError::Table(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectRename {
+    symbol_lib: PathBuf,
+    footprint_lib: PathBuf,
+    step_dir: PathBuf,
+    moved: Vec<(PathBuf, PathBuf)>,
+    updated: Vec<PathBuf>,
+}
+
+impl ProjectRename {
+    pub fn symbol_lib(&self) -> &Path {
+        &self.symbol_lib
+    }
+
+    pub fn footprint_lib(&self) -> &Path {
+        &self.footprint_lib
+    }
+
+    pub fn step_dir(&self) -> &Path {
+        &self.step_dir
+    }
+
+    pub fn moved(&self) -> &[(PathBuf, PathBuf)] {
+        &self.moved
+    }
+
+    pub fn updated(&self) -> &[PathBuf] {
+        &self.updated
+    }
+}
+
+pub fn rename_project(
+    project_root: &Path,
+    config: &ImportConfig,
+    naming: &LibraryNaming,
+    new_name: &str,
+) -> Result<ProjectRename, RenameError> {
+    if new_name.is_empty() || new_name.contains(['/', '\\', ':']) {
+        return Err(RenameError::Invalid(format!(
+            "{:?} is not a project name",
+            new_name
+        )));
+    }
+    let invalid = |err: NamingError| RenameError::Invalid(err.to_string());
+    let symbol_lib = naming
+        .symbol_lib(new_name)
+        .map_err(invalid)?
+        .unwrap_or_else(|| renamed(config.symbol_lib(), SYMBOL_LIB_SUFFIX, new_name));
+    let footprint_lib = naming
+        .footprint_lib(new_name)
+        .map_err(invalid)?
+        .unwrap_or_else(|| renamed(config.footprint_lib(), FOOTPRINT_LIB_SUFFIX, new_name));
+    let step_dir = if config.model_store().is_some() {
+        config.step_dir().to_path_buf()
+    } else {
+        naming
+            .step_dir(new_name)
+            .map_err(invalid)?
+            .unwrap_or_else(|| renamed(config.step_dir(), STEP_DIR_SUFFIX, new_name))
+    };
+    let moves: Vec<(&Path, &Path)> = [
+        (config.symbol_lib(), symbol_lib.as_path()),
+        (config.footprint_lib(), footprint_lib.as_path()),
+        (config.step_dir(), step_dir.as_path()),
+    ]
+    .into_iter()
+    .filter(|(old, new)| old != new)
+    .collect();
+    if moves.is_empty() {
+        return Err(RenameError::Invalid(format!(
+            "the libraries are already named after {}, or don't follow the \
+             <project>{} naming scheme",
+            new_name, SYMBOL_LIB_SUFFIX
+        )));
+    }
+    for (_, new) in &moves {
+        if project_root.join(new).exists() {
+            return Err(RenameError::Invalid(format!(
+                "{} already exists",
+                new.display()
+            )));
+        }
+    }
+
+    let mut prefixes = Vec::new();
+    let nickname = |kind, path: &Path| lib_name_from_path(kind, path);
+    let old_symbol_nick = nickname(TableKind::Symbol, config.symbol_lib())?;
+    let new_symbol_nick = nickname(TableKind::Symbol, &symbol_lib)?;
+    let old_footprint_nick = nickname(TableKind::Footprint, config.footprint_lib())?;
+    let new_footprint_nick = nickname(TableKind::Footprint, &footprint_lib)?;
+    if old_symbol_nick != new_symbol_nick {
+        prefixes.push((
+            format!("{}:", old_symbol_nick),
+            format!("{}:", new_symbol_nick),
+        ));
+    }
+    if old_footprint_nick != new_footprint_nick {
+        prefixes.push((
+            format!("{}:", old_footprint_nick),
+            format!("{}:", new_footprint_nick),
+        ));
+    }
+    if config.step_dir() != step_dir {
+        prefixes.push((model_path(config.step_dir(), ""), model_path(&step_dir, "")));
+        prefixes.push((
+            relative_model_path(config.step_dir(), ""),
+            relative_model_path(&step_dir, ""),
+        ));
+    }
+
+    let mut moved = Vec::new();
+    for (old, new) in moves {
+        let from = project_root.join(old);
+        if from.exists() {
+            let to = project_root.join(new);
+            if let Some(parent) = to.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::rename(&from, to)?;
+            moved.push((old.to_path_buf(), new.to_path_buf()));
+        }
+    }
+
+    let mut updated = Vec::new();
+    let symbol_path = project_root.join(&symbol_lib);
+    if symbol_path.exists()
+        && rewrite_symbol_footprints(&symbol_path, &|value| rewrite(value, &prefixes))?
+    {
+        updated.push(symbol_lib.clone());
+    }
+    let footprint_path = project_root.join(&footprint_lib);
+    if footprint_path.is_dir() {
+        for entry in fs::read_dir(&footprint_path)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) == Some("kicad_mod")
+                && rewrite_references(&path, &|value| rewrite(value, &prefixes))?
+            {
+                updated.push(footprint_lib.join(path.file_name().unwrap_or_default()));
+            }
+        }
+    }
+    for path in project_files(project_root)? {
+        if rewrite_references(&path, &|value| rewrite(value, &prefixes))? {
+            updated.push(
+                path.strip_prefix(project_root)
+                    .unwrap_or(&path)
+                    .to_path_buf(),
+            );
+        }
+    }
+
+    let tables = [
+        (
+            TableKind::Symbol,
+            &old_symbol_nick,
+            &new_symbol_nick,
+            config.symbol_lib(),
+            &symbol_lib,
+        ),
+        (
+            TableKind::Footprint,
+            &old_footprint_nick,
+            &new_footprint_nick,
+            config.footprint_lib(),
+            &footprint_lib,
+        ),
+    ];
+    for (kind, old_nick, new_nick, old_path, path) in tables {
+        if (old_nick != new_nick || old_path != path)
+            && rename_project_lib(project_root, kind, old_nick, new_nick, path)?
+        {
+            updated.push(PathBuf::from(kind.file_name()));
+        }
+    }
+
+    for state in [Ledger::path(project_root), ReviewQueue::path(project_root)] {
+        if state.exists() && rewrite_state_file(&state, &prefixes)? {
+            updated.push(
+                state
+                    .strip_prefix(project_root)
+                    .unwrap_or(&state)
+                    .to_path_buf(),
+            );
+        }
+    }
+    let index = LibraryIndex::path(project_root);
+    if index.exists() {
+        fs::remove_file(index)?;
+    }
+
+    Ok(ProjectRename {
+        symbol_lib,
+        footprint_lib,
+        step_dir,
+        moved,
+        updated,
+    })
+}
+
+pub fn rename_symbol(
+    project_root: &Path,
+    config: &ImportConfig,
+    old_name: &str,
+    new_name: &str,
+) -> Result<usize, RenameError> {
+    if new_name.is_empty() || new_name.contains(':') {
+        return Err(RenameError::Invalid(format!(
+            "{:?} is not a symbol name",
+            new_name
+        )));
+    }
+    let symbol_lib = project_root.join(config.symbol_lib());
+    let mut lib = KicadSymbolLib::parse(&fs::read_to_string(&symbol_lib)?)?;
+    lib.rename_symbol(old_name, new_name)
+        .map_err(|err| RenameError::Invalid(err.message().to_string()))?;
+    fs::write(&symbol_lib, lib.to_string_pretty())?;
+
+    let queue_error = |err| {
+        RenameError::State(format!(
+            "{}: {}",
+            ReviewQueue::path(project_root).display(),
+            err
+        ))
+    };
+    let mut queue = ReviewQueue::load(project_root).map_err(queue_error)?;
+    if queue.rename(old_name, new_name) {
+        queue.save(project_root).map_err(queue_error)?;
+    }
+
+    let lib_id = format!(
+        "{}:{}",
+        lib_name_from_path(TableKind::Symbol, config.symbol_lib())?,
+        old_name
+    );
+    let mut counts = BTreeMap::new();
+    for path in schematic_files(project_root)? {
+        for sexp in parse_sexps(&fs::read_to_string(path)?)? {
+            count_lib_ids(&sexp, &mut counts);
+        }
+    }
+    Ok(counts.get(&lib_id).copied().unwrap_or(0))
+}
+
+pub fn rename_footprint(
+    project_root: &Path,
+    config: &ImportConfig,
+    old_name: &str,
+    new_name: &str,
+) -> Result<Vec<PathBuf>, RenameError> {
+    if new_name.is_empty() || new_name.contains(['/', '\\', ':']) {
+        return Err(RenameError::Invalid(format!(
+            "{:?} is not a footprint name",
+            new_name
+        )));
+    }
+    let footprint_dir = project_root.join(config.footprint_lib());
+    let old_path = footprint_dir.join(format!("{}.kicad_mod", old_name));
+    let new_path = footprint_dir.join(format!("{}.kicad_mod", new_name));
+    if !old_path.is_file() {
+        return Err(RenameError::Invalid(format!(
+            "{} has no footprint named {}",
+            config.footprint_lib().display(),
+            old_name
+        )));
+    }
+    if new_path.exists() {
+        return Err(RenameError::Invalid(format!(
+            "{} already exists",
+            new_path.display()
+        )));
+    }
+    let mut footprint = parse_one(&fs::read_to_string(&old_path)?)?;
+    set_footprint_name(&mut footprint, new_name);
+    fs::write(&new_path, footprint.to_string_pretty())?;
+    fs::remove_file(&old_path)?;
+
+    let lib = lib_name_from_path(TableKind::Footprint, config.footprint_lib())?;
+    let old_id = format!("{}:{}", lib, old_name);
+    let new_id = format!("{}:{}", lib, new_name);
+    let rewrite = |value: &str| (value == old_id).then(|| new_id.clone());
+    let mut symbol_libs = vec![project_root.join(config.symbol_lib())];
+    for entry in project_table_entries(project_root, TableKind::Symbol)? {
+        if let Some(path) = entry.path()
+            && !symbol_libs.iter().any(|known| known == path)
+        {
+            symbol_libs.push(path.to_path_buf());
+        }
+    }
+    let mut updated = Vec::new();
+    for path in symbol_libs {
+        if path.is_file() && rewrite_symbol_footprints(&path, &rewrite)? {
+            updated.push(
+                path.strip_prefix(project_root)
+                    .unwrap_or(&path)
+                    .to_path_buf(),
+            );
+        }
+    }
+    for path in project_files(project_root)? {
+        if rewrite_references(&path, &rewrite)? {
+            updated.push(
+                path.strip_prefix(project_root)
+                    .unwrap_or(&path)
+                    .to_path_buf(),
+            );
+        }
+    }
+    let index = LibraryIndex::path(project_root);
+    if index.exists() {
+        fs::remove_file(index)?;
+    }
+    Ok(updated)
+}
+
+pub fn set_footprint_name(footprint: &mut Sexp, name: &str) {
+    if let Sexp::List(items) = footprint
+        && items.len() >= 2
+    {
+        items[1] = Sexp::Atom(Atom::new_quoted(name));
+    }
+}
+
+fn renamed(path: &Path, suffix: &str, new_name: &str) -> PathBuf {
+    match path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.strip_suffix(suffix))
+    {
+        Some(old_name) if !old_name.is_empty() => {
+            path.with_file_name(format!("{}{}", new_name, suffix))
+        }
+        _ => path.to_path_buf(),
+    }
+}
+
+fn rewrite(value: &str, prefixes: &[(String, String)]) -> Option<String> {
+    prefixes.iter().find_map(|(old, new)| {
+        value
+            .strip_prefix(old.as_str())
+            .map(|rest| format!("{}{}", new, rest))
+    })
+}
+
+pub fn rewrite_symbol_footprints(
+    path: &Path,
+    rewrite: &dyn Fn(&str) -> Option<String>,
+) -> Result<bool, RenameError> {
+    let mut lib = KicadSymbolLib::parse(&fs::read_to_string(path)?)?;
+    let names: Vec<String> = lib
+        .symbols_iter()
+        .map(|symbol| symbol.name().to_string())
+        .collect();
+    let mut changed = false;
+    for name in names {
+        let Some(symbol) = lib.symbol_mut(&name) else {
+            continue;
+        };
+        if let Some(footprint) = symbol
+            .property_value("Footprint")
+            .and_then(|value| rewrite(&value))
+        {
+            symbol.set_property_value("Footprint", &footprint);
+            changed = true;
+        }
+    }
+    if changed {
+        fs::write(path, lib.to_string_pretty())?;
+    }
+    Ok(changed)
+}
+
+pub fn rewrite_references(
+    path: &Path,
+    rewrite: &dyn Fn(&str) -> Option<String>,
+) -> Result<bool, RenameError> {
+    let mut sexps = parse_sexps(&fs::read_to_string(path)?)?;
+    let count: usize = sexps
+        .iter_mut()
+        .map(|sexp| rewrite_atoms(sexp, rewrite))
+        .sum();
+    if count > 0 {
+        let out: String = sexps.iter().map(Sexp::to_string_pretty).collect();
+        fs::write(path, out)?;
+    }
+    Ok(count > 0)
+}
+
+fn rewrite_atoms(sexp: &mut Sexp, rewrite: &dyn Fn(&str) -> Option<String>) -> usize {
+    match sexp {
+        Sexp::Atom(atom) => match rewrite(atom.value()) {
+            Some(value) => {
+                *atom = Atom::new_quoted(value);
+                1
+            }
+            None => 0,
+        },
+        Sexp::List(items) => items
+            .iter_mut()
+            .map(|item| rewrite_atoms(item, rewrite))
+            .sum(),
+        Sexp::Comment(_) => 0,
+    }
+}
+
+fn rewrite_state_file(path: &Path, prefixes: &[(String, String)]) -> Result<bool, RenameError> {
+    let mut value: toml::Value = toml::from_str(&fs::read_to_string(path)?)
+        .map_err(|err| RenameError::State(format!("{}: {}", path.display(), err)))?;
+    if rewrite_strings(&mut value, prefixes) == 0 {
+        return Ok(false);
+    }
+    let out = toml::to_string_pretty(&value)
+        .map_err(|err| RenameError::State(format!("{}: {}", path.display(), err)))?;
+    fs::write(path, out)?;
+    Ok(true)
+}
+
+fn rewrite_strings(value: &mut toml::Value, prefixes: &[(String, String)]) -> usize {
+    match value {
+        toml::Value::String(text) => match rewrite(text, prefixes) {
+            Some(renamed) => {
+                *text = renamed;
+                1
+            }
+            None => 0,
+        },
+        toml::Value::Array(items) => items
+            .iter_mut()
+            .map(|item| rewrite_strings(item, prefixes))
+            .sum(),
+        toml::Value::Table(table) => table
+            .iter_mut()
+            .map(|(_, item)| rewrite_strings(item, prefixes))
+            .sum(),
+        _ => 0,
+    }
+}
+
+pub fn project_files(project_root: &Path) -> Result<Vec<PathBuf>, walkdir::Error> {
+    let entries = WalkDir::new(project_root)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.depth() == 0 || !entry.file_name().to_string_lossy().starts_with('.')
+        });
+    let mut files = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        let extension = entry.path().extension().and_then(|ext| ext.to_str());
+        if entry.file_type().is_file()
+            && extension.is_some_and(|ext| PROJECT_FILE_EXTENSIONS.contains(&ext))
+        {
+            files.push(entry.into_path());
+        }
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kicad_table::ensure_project_tables;
+    use crate::review::ReviewItem;
+    use tempfile::tempdir;
+
+    #[test]
+    fn renames_libraries_and_their_references() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let config = ImportConfig::new(
+            PathBuf::from("old_symbols.kicad_sym"),
+            PathBuf::from("old_footprints.pretty"),
+            PathBuf::from("old_step"),
+        );
+        fs::write(
+            root.join("old_symbols.kicad_sym"),
+            r#"(kicad_symbol_lib (symbol "NE555"
+                (property "Footprint" "old_footprints:DIP-8")))"#,
+        )
+        .unwrap();
+        fs::create_dir(root.join("old_footprints.pretty")).unwrap();
+        fs::write(
+            root.join("old_footprints.pretty/DIP-8.kicad_mod"),
+            r#"(footprint "DIP-8" (model "${KIPRJMOD}/old_step/DIP-8.step"))"#,
+        )
+        .unwrap();
+        fs::create_dir(root.join("old_step")).unwrap();
+        fs::write(root.join("old_step/DIP-8.step"), "ISO-10303-21;").unwrap();
+        fs::write(
+            root.join("board.kicad_sch"),
+            r#"(kicad_sch (lib_symbols (symbol "old_symbols:NE555"))
+                (symbol (lib_id "old_symbols:NE555")
+                    (property "Footprint" "old_footprints:DIP-8")))"#,
+        )
+        .unwrap();
+        ensure_project_tables(root, &config).unwrap();
+        let mut ledger = Ledger::default();
+        ledger.record_footprint("DIP-8", vec!["old_step/DIP-8.step".to_string()]);
+        ledger.save(root).unwrap();
+
+        let rename = rename_project(root, &config, &LibraryNaming::default(), "board").unwrap();
+        assert_eq!(rename.symbol_lib(), Path::new("board_symbols.kicad_sym"));
+        assert_eq!(rename.moved().len(), 3);
+        assert!(root.join("board_step/DIP-8.step").exists());
+        assert!(!root.join("old_footprints.pretty").exists());
+
+        let symbols = fs::read_to_string(root.join("board_symbols.kicad_sym")).unwrap();
+        assert!(symbols.contains("board_footprints:DIP-8"));
+        assert!(!symbols.contains("old_"));
+        let footprint =
+            fs::read_to_string(root.join("board_footprints.pretty/DIP-8.kicad_mod")).unwrap();
+        assert!(footprint.contains("${KIPRJMOD}/board_step/DIP-8.step"));
+        let schematic = fs::read_to_string(root.join("board.kicad_sch")).unwrap();
+        assert!(!schematic.contains("old_"));
+        assert!(schematic.contains("\"board_symbols:NE555\""));
+        let table = fs::read_to_string(root.join("fp-lib-table")).unwrap();
+        assert!(table.contains("\"board_footprints\""));
+        assert!(table.contains("${KIPRJMOD}/board_footprints.pretty"));
+        assert_eq!(
+            Ledger::load(root).unwrap().models_for("DIP-8"),
+            ["board_step/DIP-8.step"]
+        );
+
+        assert!(matches!(
+            rename_project(root, &config, &LibraryNaming::default(), "board"),
+            Err(RenameError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn renames_a_footprint_and_its_references() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let config = ImportConfig::new(
+            PathBuf::from("lib.kicad_sym"),
+            PathBuf::from("lib.pretty"),
+            PathBuf::from("lib_3d"),
+        );
+        fs::write(
+            root.join("lib.kicad_sym"),
+            r#"(kicad_symbol_lib
+                (symbol "NE555" (property "Footprint" "lib:DIP-8"))
+                (symbol "LM358" (property "Footprint" "lib:DIP-8_W10"))
+                (symbol "TL072" (property "Footprint" "other:DIP-8")))"#,
+        )
+        .unwrap();
+        fs::create_dir(root.join("lib.pretty")).unwrap();
+        fs::write(
+            root.join("lib.pretty/DIP-8.kicad_mod"),
+            r#"(footprint "DIP-8")"#,
+        )
+        .unwrap();
+        fs::write(
+            root.join("board.kicad_pcb"),
+            r#"(kicad_pcb (footprint "lib:DIP-8" (layer "F.Cu")))"#,
+        )
+        .unwrap();
+
+        let updated = rename_footprint(root, &config, "DIP-8", "DIP-8_W7.62mm").unwrap();
+        assert_eq!(
+            updated,
+            [
+                PathBuf::from("lib.kicad_sym"),
+                PathBuf::from("board.kicad_pcb")
+            ]
+        );
+        assert!(!root.join("lib.pretty/DIP-8.kicad_mod").exists());
+        let footprint =
+            fs::read_to_string(root.join("lib.pretty/DIP-8_W7.62mm.kicad_mod")).unwrap();
+        assert!(footprint.contains("\"DIP-8_W7.62mm\""));
+        let lib = KicadSymbolLib::parse(&fs::read_to_string(root.join("lib.kicad_sym")).unwrap())
+            .unwrap();
+        let footprint_of = |name: &str| lib.symbol(name).unwrap().property_value("Footprint");
+        assert_eq!(footprint_of("NE555").as_deref(), Some("lib:DIP-8_W7.62mm"));
+        assert_eq!(footprint_of("LM358").as_deref(), Some("lib:DIP-8_W10"));
+        assert_eq!(footprint_of("TL072").as_deref(), Some("other:DIP-8"));
+        let pcb = fs::read_to_string(root.join("board.kicad_pcb")).unwrap();
+        assert!(pcb.contains("\"lib:DIP-8_W7.62mm\""));
+
+        assert!(matches!(
+            rename_footprint(root, &config, "DIP-8", "SOIC-8"),
+            Err(RenameError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn renames_a_symbol_and_counts_placed_instances() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let config = ImportConfig::new(
+            PathBuf::from("lib.kicad_sym"),
+            PathBuf::from("lib.pretty"),
+            PathBuf::from("lib_3d"),
+        );
+        fs::write(
+            root.join("lib.kicad_sym"),
+            r#"(kicad_symbol_lib
+                (symbol "NE555" (property "Value" "NE555")
+                    (symbol "NE555_0_1") (symbol "NE555_1_1"))
+                (symbol "LM358"))"#,
+        )
+        .unwrap();
+        fs::write(
+            root.join("board.kicad_sch"),
+            r#"(kicad_sch (symbol (lib_id "lib:NE555")) (symbol (lib_id "lib:NE555")))"#,
+        )
+        .unwrap();
+
+        let mut queue = ReviewQueue::default();
+        queue.push(ReviewItem::new("NE555", None, "no footprint assigned"));
+        queue.save(root).unwrap();
+
+        assert_eq!(rename_symbol(root, &config, "NE555", "NE555P").unwrap(), 2);
+        let queue = ReviewQueue::load(root).unwrap();
+        assert_eq!(queue.items()[0].symbol(), "NE555P");
+        let lib = KicadSymbolLib::parse(&fs::read_to_string(root.join("lib.kicad_sym")).unwrap())
+            .unwrap();
+        let symbol = lib.symbol("NE555P").unwrap();
+        assert_eq!(symbol.property_value("Value").as_deref(), Some("NE555P"));
+        let out = lib.to_string_pretty();
+        assert!(out.contains("\"NE555P_0_1\""));
+        assert!(out.contains("\"NE555P_1_1\""));
+
+        assert!(matches!(
+            rename_symbol(root, &config, "NE555P", "LM358"),
+            Err(RenameError::Invalid(_))
+        ));
+        assert!(matches!(
+            rename_symbol(root, &config, "NE555P", "lib:X"),
+            Err(RenameError::Invalid(_))
+        ));
+    }
+}
@@ -0,0 +1,496 @@
+use crate::importer::{ImportConfig, ImportError, ImportReport, footprint_lib_name};
+use crate::kicad_sym::{AddPolicy, KicadSymError, KicadSymbolLib, Symbol};
+use crate::ledger::STATE_DIR;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+const REVIEW_FILE: &str = "review.toml";
+const REVIEW_PROPERTIES: &[&str] = &["Value", "MPN", "Description"];
+
+#[derive(Debug)]
+pub enum ReviewError {
+    Io(io::Error),
+    Parse(toml::de::Error),
+    Write(toml::ser::Error),
+    Symbol(KicadSymError),
+    Import(ImportError),
+    MissingSymbol(String),
+}
+
+impl fmt::Display for ReviewError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReviewError::Io(err) => write!(f, "io error: {}", err),
+            ReviewError::Parse(err) => write!(f, "review queue parse error: {}", err),
+            ReviewError::Write(err) => write!(f, "review queue write error: {}", err),
+            ReviewError::Symbol(err) => write!(f, "symbol parse error: {}", err),
+            ReviewError::Import(err) => write!(f, "{}", err),
+            ReviewError::MissingSymbol(name) => {
+                write!(f, "symbol not found in library: {}", name)
+            }
+        }
+    }
+}
+
+impl Error for ReviewError {}
+
+impl From<io::Error> for ReviewError {
+    fn from(value: io::Error) -> Self {
+        ReviewError::Io(value)
+    }
+}
+
+impl From<toml::de::Error> for ReviewError {
+    fn from(value: toml::de::Error) -> Self {
+        ReviewError::Parse(value)
+    }
+}
+
+impl From<toml::ser::Error> for ReviewError {
+    fn from(value: toml::ser::Error) -> Self {
+        ReviewError::Write(value)
+    }
+}
+
+impl From<KicadSymError> for ReviewError {
+    fn from(value: KicadSymError) -> Self {
+        ReviewError::Symbol(value)
+    }
+}
+
+impl From<ImportError> for ReviewError {
+    fn from(value: ImportError) -> Self {
+        ReviewError::Import(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReviewItem {
+    symbol: String,
+    #[serde(default)]
+    footprint: Option<String>,
+    reason: String,
+}
+
+impl ReviewItem {
+    pub fn new(symbol: &str, footprint: Option<&str>, reason: &str) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            footprint: footprint.map(str::to_string),
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn footprint(&self) -> Option<&str> {
+        self.footprint.as_deref()
+    }
+
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
+
+impl fmt::Display for ReviewItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.footprint {
+            Some(footprint) => write!(f, "{} ({}): {}", self.symbol, footprint, self.reason),
+            None => write!(f, "{}: {}", self.symbol, self.reason),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReviewQueue {
+    #[serde(default, rename = "item")]
+    items: Vec<ReviewItem>,
+}
+
+impl ReviewQueue {
+    pub fn path(project_root: &Path) -> PathBuf {
+        project_root.join(STATE_DIR).join(REVIEW_FILE)
+    }
+
+    pub fn load(project_root: &Path) -> Result<Self, ReviewError> {
+        let path = Self::path(project_root);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let raw = fs::read_to_string(path)?;
+        Ok(toml::from_str(&raw)?)
+    }
+
+    pub fn save(&self, project_root: &Path) -> Result<(), ReviewError> {
+        let path = Self::path(project_root);
+        if self.items.is_empty() {
+            if path.exists() {
+                fs::remove_file(path)?;
+            }
+            return Ok(());
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn items(&self) -> &[ReviewItem] {
+        &self.items
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn push(&mut self, item: ReviewItem) {
+        self.resolve(&item.symbol);
+        self.items.push(item);
+    }
+
+    pub fn resolve(&mut self, symbol: &str) -> bool {
+        let before = self.items.len();
+        self.items.retain(|item| item.symbol != symbol);
+        self.items.len() != before
+    }
+
+    pub fn rename(&mut self, old_symbol: &str, new_symbol: &str) -> bool {
+        let mut renamed = false;
+        for item in self
+            .items
+            .iter_mut()
+            .filter(|item| item.symbol == old_symbol)
+        {
+            item.symbol = new_symbol.to_string();
+            renamed = true;
+        }
+        renamed
+    }
+
+    pub fn update_from_report(&mut self, report: &ImportReport) -> usize {
+        let mut queued = 0;
+        for association in report.associations() {
+            let reason = match association.rule() {
+                None => "no footprint assigned".to_string(),
+                Some(rule) if association.needs_review() => format!(
+                    "low-confidence {} match ({:.0}%)",
+                    rule,
+                    association.confidence() * 100.0
+                ),
+                Some(_) => {
+                    self.resolve(association.symbol());
+                    continue;
+                }
+            };
+            self.push(ReviewItem::new(
+                association.symbol(),
+                association.footprint(),
+                &reason,
+            ));
+            queued += 1;
+        }
+        queued
+    }
+}
+
+pub fn list_footprints(footprint_lib: &Path) -> Result<Vec<String>, ReviewError> {
+    let mut names = Vec::new();
+    if !footprint_lib.is_dir() {
+        return Ok(names);
+    }
+    for entry in fs::read_dir(footprint_lib)? {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) == Some("kicad_mod")
+            && let Some(stem) = path.file_stem().and_then(|stem| stem.to_str())
+        {
+            names.push(stem.to_string());
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+pub fn load_symbol(symbol_lib: &Path, name: &str) -> Result<Symbol, ReviewError> {
+    let content = fs::read_to_string(symbol_lib)?;
+    let lib = KicadSymbolLib::parse(&content)?;
+    lib.symbol(name)
+        .cloned()
+        .ok_or_else(|| ReviewError::MissingSymbol(name.to_string()))
+}
+
+pub fn store_symbol(symbol_lib: &Path, symbol: Symbol) -> Result<(), ReviewError> {
+    let content = fs::read_to_string(symbol_lib)?;
+    let mut lib = KicadSymbolLib::parse(&content)?;
+    lib.add_symbol(symbol, AddPolicy::ReplaceExisting)?;
+    fs::write(symbol_lib, lib.to_string_pretty())?;
+    Ok(())
+}
+
+pub fn review_interactive<R: BufRead, W: Write>(
+    queue: &mut ReviewQueue,
+    config: &ImportConfig,
+    input: &mut R,
+    output: &mut W,
+) -> Result<usize, ReviewError> {
+    let lib_name = footprint_lib_name(config.footprint_lib())?;
+    let footprints = list_footprints(config.footprint_lib())?;
+    let items = queue.items().to_vec();
+    let mut lib = KicadSymbolLib::parse(&fs::read_to_string(config.symbol_lib())?)?;
+    let mut resolved = 0;
+    for (idx, item) in items.iter().enumerate() {
+        writeln!(output, "[{}/{}] {}", idx + 1, items.len(), item)?;
+        if lib.symbol(item.symbol()).is_none() {
+            writeln!(
+                output,
+                "  warning: {} is no longer in the library; dropping it from the queue",
+                item.symbol()
+            )?;
+            queue.resolve(item.symbol());
+            continue;
+        }
+        let finished = lib
+            .with_symbol_mut(item.symbol(), |symbol| -> Result<bool, ReviewError> {
+                for (number, name) in footprints.iter().enumerate() {
+                    writeln!(output, "  {}) {}", number + 1, name)?;
+                }
+                let current = symbol.property_value("Footprint").unwrap_or_default();
+                loop {
+                    match prompt(input, output, "footprint", &current)? {
+                        Answer::Eof => return Ok(false),
+                        Answer::Value(value) => {
+                            match resolve_footprint_choice(&value, &footprints, &lib_name) {
+                                Some(choice) => symbol.set_or_add_property("Footprint", &choice),
+                                None => {
+                                    writeln!(
+                                        output,
+                                        "  no footprint numbered {}; pick 1-{} or type a name",
+                                        value,
+                                        footprints.len()
+                                    )?;
+                                    continue;
+                                }
+                            }
+                        }
+                        Answer::Keep => {}
+                    }
+                    break;
+                }
+
+                for property in REVIEW_PROPERTIES {
+                    let current = symbol.property_value(property).unwrap_or_default();
+                    if !is_blank(&current) {
+                        continue;
+                    }
+                    match prompt(input, output, property, &current)? {
+                        Answer::Eof => return Ok(false),
+                        Answer::Value(value) => symbol.set_or_add_property(property, &value),
+                        Answer::Keep => {}
+                    }
+                }
+                let datasheet = symbol.property_value("Datasheet").unwrap_or_default();
+                match prompt(input, output, "datasheet", &datasheet)? {
+                    Answer::Eof => return Ok(false),
+                    Answer::Value(value) => symbol.set_or_add_property("Datasheet", &value),
+                    Answer::Keep => {}
+                }
+                Ok(true)
+            })
+            .ok_or_else(|| ReviewError::MissingSymbol(item.symbol().to_string()))??;
+        fs::write(config.symbol_lib(), lib.to_string_pretty())?;
+        if !finished {
+            break;
+        }
+
+        match prompt(input, output, "mark resolved? [y/N/q]", "")? {
+            Answer::Value(value) if value.eq_ignore_ascii_case("y") => {
+                queue.resolve(item.symbol());
+                resolved += 1;
+            }
+            Answer::Value(value) if value.eq_ignore_ascii_case("q") => break,
+            Answer::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(resolved)
+}
+
+enum Answer {
+    Keep,
+    Value(String),
+    Eof,
+}
+
+fn prompt<R: BufRead, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    label: &str,
+    current: &str,
+) -> Result<Answer, ReviewError> {
+    if current.is_empty() {
+        write!(output, "  {}: ", label)?;
+    } else {
+        write!(output, "  {} [{}]: ", label, current)?;
+    }
+    output.flush()?;
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+        writeln!(output)?;
+        return Ok(Answer::Eof);
+    }
+    let line = line.trim();
+    if line.is_empty() {
+        Ok(Answer::Keep)
+    } else {
+        Ok(Answer::Value(line.to_string()))
+    }
+}
+
+fn resolve_footprint_choice(answer: &str, footprints: &[String], lib_name: &str) -> Option<String> {
+    if let Ok(number) = answer.parse::<usize>() {
+        return footprints
+            .get(number.checked_sub(1)?)
+            .map(|name| format!("{}:{}", lib_name, name));
+    }
+    if answer.contains(':') {
+        return Some(answer.to_string());
+    }
+    Some(format!("{}:{}", lib_name, answer))
+}
+
+fn is_blank(value: &str) -> bool {
+    let value = value.trim();
+    value.is_empty() || value == "~"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn queue_round_trips_and_removes_empty_file() {
+        let dir = tempdir().unwrap();
+        let mut queue = ReviewQueue::default();
+        queue.push(ReviewItem::new("A", Some("Lib:A"), "low confidence"));
+        queue.push(ReviewItem::new("A", None, "no footprint assigned"));
+        queue.save(dir.path()).unwrap();
+
+        let mut loaded = ReviewQueue::load(dir.path()).unwrap();
+        assert_eq!(loaded.items().len(), 1);
+        assert_eq!(loaded.items()[0].reason(), "no footprint assigned");
+
+        assert!(loaded.resolve("A"));
+        loaded.save(dir.path()).unwrap();
+        assert!(!ReviewQueue::path(dir.path()).exists());
+    }
+
+    #[test]
+    fn interactive_review_updates_symbol_and_resolves() {
+        let dir = tempdir().unwrap();
+        let symbol_lib = dir.path().join("lib.kicad_sym");
+        let footprint_lib = dir.path().join("Lib.pretty");
+        fs::create_dir_all(&footprint_lib).unwrap();
+        fs::write(
+            footprint_lib.join("SOIC-8.kicad_mod"),
+            "(footprint \"SOIC-8\")",
+        )
+        .unwrap();
+        fs::write(
+            &symbol_lib,
+            "(kicad_symbol_lib (version 20231120) (symbol \"U1\" (property \"Value\" \"U1\") (property \"Footprint\" \"\") (property \"Datasheet\" \"~\")))",
+        )
+        .unwrap();
+        let config = ImportConfig::new(symbol_lib.clone(), footprint_lib, dir.path().join("3d"));
+        let mut queue = ReviewQueue::default();
+        queue.push(ReviewItem::new("U1", None, "no footprint assigned"));
+
+        let mut input = io::Cursor::new("1\nNE555\n\nhttps://example.com/ne555.pdf\ny\n");
+        let mut output = Vec::new();
+        let resolved = review_interactive(&mut queue, &config, &mut input, &mut output).unwrap();
+
+        assert_eq!(resolved, 1);
+        assert!(queue.is_empty());
+        let symbol = load_symbol(&symbol_lib, "U1").unwrap();
+        assert_eq!(symbol.property_value("Footprint").unwrap(), "Lib:SOIC-8");
+        assert_eq!(symbol.property_value("MPN").unwrap(), "NE555");
+        assert_eq!(
+            symbol.property_value("Datasheet").unwrap(),
+            "https://example.com/ne555.pdf"
+        );
+    }
+
+    #[test]
+    fn interactive_review_drops_stale_items_and_reprompts_bad_choices() {
+        let dir = tempdir().unwrap();
+        let symbol_lib = dir.path().join("lib.kicad_sym");
+        let footprint_lib = dir.path().join("Lib.pretty");
+        fs::create_dir_all(&footprint_lib).unwrap();
+        fs::write(
+            footprint_lib.join("SOIC-8.kicad_mod"),
+            "(footprint \"SOIC-8\")",
+        )
+        .unwrap();
+        fs::write(
+            &symbol_lib,
+            "(kicad_symbol_lib (version 20231120) (symbol \"U1\" (property \"Value\" \"U1\") (property \"Footprint\" \"\") (property \"MPN\" \"NE555\") (property \"Description\" \"timer\")))",
+        )
+        .unwrap();
+        let config = ImportConfig::new(symbol_lib.clone(), footprint_lib, dir.path().join("3d"));
+        let mut queue = ReviewQueue::default();
+        queue.push(ReviewItem::new("Removed", None, "no footprint assigned"));
+        queue.push(ReviewItem::new("U1", None, "no footprint assigned"));
+
+        let mut input = io::Cursor::new("7\n1\n\ny\n");
+        let mut output = Vec::new();
+        let resolved = review_interactive(&mut queue, &config, &mut input, &mut output).unwrap();
+
+        assert_eq!(resolved, 1);
+        assert!(queue.is_empty());
+        let output = String::from_utf8(output).unwrap();
+        assert!(
+            output.contains("Removed is no longer in the library"),
+            "{}",
+            output
+        );
+        assert!(
+            output.contains("no footprint numbered 7; pick 1-1"),
+            "{}",
+            output
+        );
+        let symbol = load_symbol(&symbol_lib, "U1").unwrap();
+        assert_eq!(symbol.property_value("Footprint").unwrap(), "Lib:SOIC-8");
+    }
+
+    #[test]
+    fn interactive_review_stops_at_end_of_input() {
+        let dir = tempdir().unwrap();
+        let symbol_lib = dir.path().join("lib.kicad_sym");
+        let footprint_lib = dir.path().join("Lib.pretty");
+        fs::create_dir_all(&footprint_lib).unwrap();
+        fs::write(
+            &symbol_lib,
+            "(kicad_symbol_lib (version 20231120) (symbol \"U1\" (property \"Footprint\" \"\")) (symbol \"U2\" (property \"Footprint\" \"\")))",
+        )
+        .unwrap();
+        let config = ImportConfig::new(symbol_lib, footprint_lib, dir.path().join("3d"));
+        let mut queue = ReviewQueue::default();
+        queue.push(ReviewItem::new("U1", None, "no footprint assigned"));
+        queue.push(ReviewItem::new("U2", None, "no footprint assigned"));
+
+        for script in ["", "\n\n\n\n\n\n\n"] {
+            let mut input = io::Cursor::new(script);
+            let mut output = Vec::new();
+            let resolved =
+                review_interactive(&mut queue, &config, &mut input, &mut output).unwrap();
+            assert_eq!(resolved, 0);
+            assert_eq!(queue.items().len(), 2);
+        }
+    }
+}
@@ -0,0 +1,374 @@
+use crate::kicad_sym::{Atom, KicadSymError, Sexp, parse_one};
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum SexpError {
+    Parse(KicadSymError),
+    Missing(String),
+    Invalid(String),
+}
+
+impl fmt::Display for SexpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SexpError::Parse(err) => write!(f, "{}", err),
+            SexpError::Missing(what) => write!(f, "missing {}", what),
+            SexpError::Invalid(msg) => write!(f, "invalid S-expression: {}", msg),
+        }
+    }
+}
+
+impl Error for SexpError {}
+
+impl From<KicadSymError> for SexpError {
+    fn from(value: KicadSymError) -> Self {
+        SexpError::Parse(value)
+    }
+}
+
+impl SexpError {
+    pub fn context(self, key: &str) -> Self {
+        match self {
+            SexpError::Missing(what) => SexpError::Missing(format!("{} in ({})", what, key)),
+            SexpError::Invalid(msg) => SexpError::Invalid(format!("({}): {}", key, msg)),
+            other => other,
+        }
+    }
+}
+
+pub trait FromSexp: Sized {
+    fn from_items(items: &[Sexp]) -> Result<Self, SexpError>;
+}
+
+pub trait ToSexp {
+    fn to_items(&self) -> Vec<Sexp>;
+}
+
+pub trait SexpRecord: FromSexp + ToSexp {
+    const HEAD: &'static str;
+
+    fn from_sexp(sexp: &Sexp) -> Result<Self, SexpError> {
+        match sexp {
+            Sexp::List(items) if head(items) == Some(Self::HEAD) => {
+                Self::from_items(&items[1..]).map_err(|err| err.context(Self::HEAD))
+            }
+            _ => Err(SexpError::Invalid(format!(
+                "expected a list like ({} ...)",
+                Self::HEAD
+            ))),
+        }
+    }
+
+    fn to_sexp(&self) -> Sexp {
+        let mut items = vec![Sexp::Atom(Atom::new(Self::HEAD))];
+        items.extend(self.to_items());
+        Sexp::List(items)
+    }
+
+    fn parse(input: &str) -> Result<Self, SexpError> {
+        Self::from_sexp(&parse_one(input)?)
+    }
+}
+
+pub fn head(items: &[Sexp]) -> Option<&str> {
+    match items.first() {
+        Some(Sexp::Atom(atom)) => Some(atom.value()),
+        _ => None,
+    }
+}
+
+pub fn children<'a>(items: &'a [Sexp], key: &str) -> Vec<&'a [Sexp]> {
+    items
+        .iter()
+        .filter_map(|item| match item {
+            Sexp::List(list) if head(list) == Some(key) => Some(&list[1..]),
+            _ => None,
+        })
+        .collect()
+}
+
+pub fn keyed(key: &str, items: Vec<Sexp>) -> Sexp {
+    let mut list = vec![Sexp::Atom(Atom::new(key))];
+    list.extend(items);
+    Sexp::List(list)
+}
+
+pub fn decode_required<T: FromSexp>(items: &[Sexp], key: &str) -> Result<T, SexpError> {
+    match children(items, key).as_slice() {
+        [] => Err(SexpError::Missing(format!("({})", key))),
+        [tail] => T::from_items(tail).map_err(|err| err.context(key)),
+        _ => Err(SexpError::Invalid(format!(
+            "({}) given more than once",
+            key
+        ))),
+    }
+}
+
+pub fn decode_optional<T: FromSexp>(items: &[Sexp], key: &str) -> Result<Option<T>, SexpError> {
+    match children(items, key).as_slice() {
+        [] => Ok(None),
+        [tail] => T::from_items(tail)
+            .map(Some)
+            .map_err(|err| err.context(key)),
+        _ => Err(SexpError::Invalid(format!(
+            "({}) given more than once",
+            key
+        ))),
+    }
+}
+
+pub fn decode_many<T: FromSexp>(items: &[Sexp], key: &str) -> Result<Vec<T>, SexpError> {
+    children(items, key)
+        .into_iter()
+        .map(|tail| T::from_items(tail).map_err(|err| err.context(key)))
+        .collect()
+}
+
+pub fn decode_positional<T: FromSexp>(
+    items: &[Sexp],
+    index: usize,
+    name: &str,
+) -> Result<T, SexpError> {
+    match items.get(index) {
+        Some(item @ Sexp::Atom(_)) => T::from_items(std::slice::from_ref(item)),
+        _ => Err(SexpError::Missing(name.to_string())),
+    }
+}
+
+fn single_atom<'a>(items: &'a [Sexp], what: &str) -> Result<&'a str, SexpError> {
+    match items {
+        [Sexp::Atom(atom)] => Ok(atom.value()),
+        [] => Err(SexpError::Missing(what.to_string())),
+        _ => Err(SexpError::Invalid(format!("expected a single {}", what))),
+    }
+}
+
+impl FromSexp for String {
+    fn from_items(items: &[Sexp]) -> Result<Self, SexpError> {
+        single_atom(items, "string").map(str::to_string)
+    }
+}
+
+impl ToSexp for String {
+    fn to_items(&self) -> Vec<Sexp> {
+        vec![Sexp::Atom(Atom::new_quoted(self.clone()))]
+    }
+}
+
+impl FromSexp for bool {
+    fn from_items(items: &[Sexp]) -> Result<Self, SexpError> {
+        match single_atom(items, "yes/no")? {
+            "yes" => Ok(true),
+            "no" => Ok(false),
+            other => Err(SexpError::Invalid(format!(
+                "expected yes or no, got {}",
+                other
+            ))),
+        }
+    }
+}
+
+impl ToSexp for bool {
+    fn to_items(&self) -> Vec<Sexp> {
+        vec![Sexp::Atom(Atom::new(if *self { "yes" } else { "no" }))]
+    }
+}
+
+macro_rules! number_sexp {
+    ($($ty:ty => $atom:expr),*) => {$(
+        impl FromSexp for $ty {
+            fn from_items(items: &[Sexp]) -> Result<Self, SexpError> {
+                let value = single_atom(items, "number")?;
+                value.parse().map_err(|_| {
+                    SexpError::Invalid(format!("expected a number, got {}", value))
+                })
+            }
+        }
+
+        impl ToSexp for $ty {
+            fn to_items(&self) -> Vec<Sexp> {
+                vec![Sexp::Atom($atom(*self))]
+            }
+        }
+    )*};
+}
+
+number_sexp!(
+    f64 => Atom::number,
+    i64 => |value: i64| Atom::new(value.to_string()),
+    u32 => |value: u32| Atom::new(value.to_string())
+);
+
+impl<T: FromSexp> FromSexp for Vec<T> {
+    fn from_items(items: &[Sexp]) -> Result<Self, SexpError> {
+        items
+            .iter()
+            .filter(|item| !matches!(item, Sexp::Comment(_)))
+            .map(|item| T::from_items(std::slice::from_ref(item)))
+            .collect()
+    }
+}
+
+impl<T: ToSexp> ToSexp for Vec<T> {
+    fn to_items(&self) -> Vec<Sexp> {
+        self.iter().flat_map(ToSexp::to_items).collect()
+    }
+}
+
+#[macro_export]
+macro_rules! sexp_record {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident $(= $head:literal)? {
+            $($pvis:vis $pfield:ident: $pty:ty),* $(,)?
+            $(; $($kvis:vis $kfield:ident: $kty:ty = $($mode:ident)? $key:literal),* $(,)?)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $($pvis $pfield: $pty,)*
+            $($($kvis $kfield: $kty,)*)?
+        }
+
+        impl $crate::sexp_record::FromSexp for $name {
+            #[allow(unused_variables, unused_mut, unused_assignments)]
+            fn from_items(
+                items: &[$crate::kicad_sym::Sexp],
+            ) -> Result<Self, $crate::sexp_record::SexpError> {
+                let mut index = 0;
+                $(
+                    let $pfield = $crate::sexp_record::decode_positional(
+                        items,
+                        index,
+                        stringify!($pfield),
+                    )?;
+                    index += 1;
+                )*
+                $($(
+                    let $kfield = $crate::sexp_record!(@decode items, $key, $($mode)?)?;
+                )*)?
+                Ok(Self {
+                    $($pfield,)*
+                    $($($kfield,)*)?
+                })
+            }
+        }
+
+        impl $crate::sexp_record::ToSexp for $name {
+            fn to_items(&self) -> Vec<$crate::kicad_sym::Sexp> {
+                let mut out = Vec::new();
+                $(out.extend($crate::sexp_record::ToSexp::to_items(&self.$pfield));)*
+                $($(
+                    $crate::sexp_record!(@encode out, self.$kfield, $key, $($mode)?);
+                )*)?
+                out
+            }
+        }
+
+        $(
+            impl $crate::sexp_record::SexpRecord for $name {
+                const HEAD: &'static str = $head;
+            }
+        )?
+    };
+    (@decode $items:ident, $key:literal, ) => {
+        $crate::sexp_record::decode_required($items, $key)
+    };
+    (@decode $items:ident, $key:literal, opt) => {
+        $crate::sexp_record::decode_optional($items, $key)
+    };
+    (@decode $items:ident, $key:literal, many) => {
+        $crate::sexp_record::decode_many($items, $key)
+    };
+    (@encode $out:ident, $value:expr, $key:literal, ) => {
+        $out.push($crate::sexp_record::keyed(
+            $key,
+            $crate::sexp_record::ToSexp::to_items(&$value),
+        ))
+    };
+    (@encode $out:ident, $value:expr, $key:literal, opt) => {
+        if let Some(value) = &$value {
+            $out.push($crate::sexp_record::keyed(
+                $key,
+                $crate::sexp_record::ToSexp::to_items(value),
+            ));
+        }
+    };
+    (@encode $out:ident, $value:expr, $key:literal, many) => {
+        for value in &$value {
+            $out.push($crate::sexp_record::keyed(
+                $key,
+                $crate::sexp_record::ToSexp::to_items(value),
+            ));
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    crate::sexp_record! {
+        #[derive(Debug, Clone, PartialEq)]
+        struct Point {
+            x: f64,
+            y: f64,
+        }
+    }
+
+    crate::sexp_record! {
+        #[derive(Debug, Clone, PartialEq)]
+        struct Pad {
+            number: String,
+            kind: String;
+            at: Point = "at",
+            layers: Vec<String> = "layers",
+            drill: Option<f64> = opt "drill",
+        }
+    }
+
+    crate::sexp_record! {
+        #[derive(Debug, Clone, PartialEq)]
+        struct Footprint = "footprint" {
+            name: String;
+            layer: String = "layer",
+            pads: Vec<Pad> = many "pad",
+        }
+    }
+
+    #[test]
+    fn maps_records_to_and_from_sexps() {
+        let footprint = Footprint::parse(
+            r#"(footprint "R_0603" (layer "F.Cu") (attr smd)
+                (pad "1" smd (at -0.8 0) (layers "F.Cu" "F.Mask"))
+                (pad "2" thru_hole (at 0.8 0) (layers "*.Cu") (drill 0.4)))"#,
+        )
+        .unwrap();
+        assert_eq!(footprint.name, "R_0603");
+        assert_eq!(footprint.pads.len(), 2);
+        assert_eq!(footprint.pads[0].at, Point { x: -0.8, y: 0.0 });
+        assert_eq!(footprint.pads[0].layers, ["F.Cu", "F.Mask"]);
+        assert_eq!(footprint.pads[0].drill, None);
+        assert_eq!(footprint.pads[1].drill, Some(0.4));
+
+        let sexp = footprint.to_sexp();
+        assert_eq!(Footprint::from_sexp(&sexp).unwrap(), footprint);
+        let text = sexp.to_string_pretty();
+        assert!(text.contains("(at -0.8 0)"));
+        assert!(text.contains("(drill 0.4)"));
+        let mut moved = footprint.clone();
+        moved.pads[0].at.x += 0.1 + 0.2;
+        assert!(moved.to_sexp().to_string_pretty().contains("(at -0.5 0)"));
+
+        let err = Footprint::parse(r#"(footprint "R" (pad "1" smd (layers "F.Cu")))"#).unwrap_err();
+        assert_eq!(err.to_string(), "missing (layer) in (footprint)");
+        let err =
+            Footprint::parse(r#"(footprint "R" (layer "F.Cu") (pad "1" smd (at x 0) (layers)))"#)
+                .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid S-expression: (footprint): (pad): (at): expected a number, got x"
+        );
+    }
+}
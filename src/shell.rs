@@ -0,0 +1,253 @@
+use crate::annotate::SymbolSummary;
+use crate::kicad_sym::{KicadSymError, KicadSymbolLib};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+pub const SHELL_HELP: &str = "commands:
+  list                  list the symbols in the project library
+  search <text>         find symbols whose name or properties contain <text>
+  show <symbol>         show a symbol's pins and properties
+  import <source>...    import archives or folders into the project
+  help                  show this help
+  exit                  leave the shell";
+
+#[derive(Debug)]
+pub enum ShellError {
+    Io(io::Error),
+    Symbol(KicadSymError),
+    UnknownSymbol(String),
+    Usage(String),
+}
+
+impl fmt::Display for ShellError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShellError::Io(err) => write!(f, "io error: {}", err),
+            ShellError::Symbol(err) => write!(f, "symbol library error: {}", err),
+            ShellError::UnknownSymbol(name) => write!(f, "no symbol named {}", name),
+            ShellError::Usage(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl Error for ShellError {}
+
+impl From<io::Error> for ShellError {
+    fn from(value: io::Error) -> Self {
+        ShellError::Io(value)
+    }
+}
+
+impl From<KicadSymError> for ShellError {
+    fn from(value: KicadSymError) -> Self {
+        ShellError::Symbol(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShellCommand {
+    List,
+    Search(String),
+    Show(String),
+    Import(Vec<PathBuf>),
+    Help,
+    Exit,
+    Empty,
+}
+
+impl ShellCommand {
+    pub fn parse(line: &str) -> Result<Self, ShellError> {
+        let words = split_words(line)?;
+        let Some((command, args)) = words.split_first() else {
+            return Ok(ShellCommand::Empty);
+        };
+        let usage = |text: &str| ShellError::Usage(format!("usage: {}", text));
+        match command.as_str() {
+            "list" | "ls" if args.is_empty() => Ok(ShellCommand::List),
+            "list" | "ls" => Err(usage("list")),
+            "search" if !args.is_empty() => Ok(ShellCommand::Search(args.join(" "))),
+            "search" => Err(usage("search <text>")),
+            "show" if args.len() == 1 => Ok(ShellCommand::Show(args[0].clone())),
+            "show" => Err(usage("show <symbol>")),
+            "import" if !args.is_empty() => Ok(ShellCommand::Import(
+                args.iter().map(|arg| expand_home(arg)).collect(),
+            )),
+            "import" => Err(usage("import <source>...")),
+            "help" | "?" => Ok(ShellCommand::Help),
+            "exit" | "quit" => Ok(ShellCommand::Exit),
+            other => Err(ShellError::Usage(format!(
+                "unknown command: {} (try `help`)",
+                other
+            ))),
+        }
+    }
+}
+
+pub struct Session {
+    symbol_lib: PathBuf,
+    library: Option<(Option<SystemTime>, KicadSymbolLib)>,
+    loads: usize,
+}
+
+impl Session {
+    pub fn new(symbol_lib: &Path) -> Self {
+        Self {
+            symbol_lib: symbol_lib.to_path_buf(),
+            library: None,
+            loads: 0,
+        }
+    }
+
+    pub fn loads(&self) -> usize {
+        self.loads
+    }
+
+    pub fn library(&mut self) -> Result<Option<&KicadSymbolLib>, ShellError> {
+        if !self.symbol_lib.exists() {
+            self.library = None;
+            return Ok(None);
+        }
+        let modified = fs::metadata(&self.symbol_lib)?.modified().ok();
+        let stale = match &self.library {
+            Some((loaded, _)) => modified.is_none() || *loaded != modified,
+            None => true,
+        };
+        if stale {
+            let content = fs::read_to_string(&self.symbol_lib)?;
+            self.library = Some((modified, KicadSymbolLib::parse(&content)?));
+            self.loads += 1;
+        }
+        Ok(self.library.as_ref().map(|(_, library)| library))
+    }
+
+    pub fn list(&mut self) -> Result<Vec<String>, ShellError> {
+        Ok(self
+            .library()?
+            .map(|library| {
+                library
+                    .symbols_iter()
+                    .map(|symbol| symbol.name().to_string())
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    pub fn search(&mut self, text: &str) -> Result<Vec<String>, ShellError> {
+        let needle = text.to_lowercase();
+        let Some(library) = self.library()? else {
+            return Ok(Vec::new());
+        };
+        Ok(library
+            .symbols_iter()
+            .filter(|symbol| {
+                symbol.name().to_lowercase().contains(&needle)
+                    || symbol
+                        .properties()
+                        .iter()
+                        .any(|(_, value)| value.to_lowercase().contains(&needle))
+            })
+            .map(|symbol| symbol.name().to_string())
+            .collect())
+    }
+
+    pub fn show(&mut self, name: &str) -> Result<SymbolSummary, ShellError> {
+        self.library()?
+            .and_then(|library| library.symbol(name))
+            .map(SymbolSummary::from_symbol)
+            .ok_or_else(|| ShellError::UnknownSymbol(name.to_string()))
+    }
+}
+
+fn split_words(line: &str) -> Result<Vec<String>, ShellError> {
+    let mut words = Vec::new();
+    let mut current: Option<String> = None;
+    let mut quote = None;
+    for ch in line.chars() {
+        match (quote, ch) {
+            (Some(open), ch) if ch == open => quote = None,
+            (Some(_), ch) => current.get_or_insert_with(String::new).push(ch),
+            (None, '"' | '\'') => {
+                quote = Some(ch);
+                current.get_or_insert_with(String::new);
+            }
+            (None, ch) if ch.is_whitespace() => words.extend(current.take()),
+            (None, ch) => current.get_or_insert_with(String::new).push(ch),
+        }
+    }
+    if quote.is_some() {
+        return Err(ShellError::Usage("unterminated quote".to_string()));
+    }
+    words.extend(current);
+    Ok(words)
+}
+
+fn expand_home(arg: &str) -> PathBuf {
+    match (arg.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => PathBuf::from(arg),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::tempdir;
+
+    #[test]
+    fn parses_shell_commands() {
+        assert_eq!(ShellCommand::parse("  ").unwrap(), ShellCommand::Empty);
+        assert_eq!(ShellCommand::parse("ls").unwrap(), ShellCommand::List);
+        assert_eq!(
+            ShellCommand::parse("search lm358 dual").unwrap(),
+            ShellCommand::Search("lm358 dual".to_string())
+        );
+        assert_eq!(
+            ShellCommand::parse("import \"my parts.zip\" 'b.zip'").unwrap(),
+            ShellCommand::Import(vec![PathBuf::from("my parts.zip"), PathBuf::from("b.zip")])
+        );
+        assert!(matches!(
+            ShellCommand::parse("show"),
+            Err(ShellError::Usage(_))
+        ));
+        assert!(matches!(
+            ShellCommand::parse("import \"a.zip"),
+            Err(ShellError::Usage(_))
+        ));
+        assert!(ShellCommand::parse("frobnicate").is_err());
+    }
+
+    #[test]
+    fn session_parses_the_library_once_until_it_changes() {
+        let dir = tempdir().unwrap();
+        let symbol_lib = dir.path().join("lib.kicad_sym");
+        let mut session = Session::new(&symbol_lib);
+        assert!(session.list().unwrap().is_empty());
+
+        fs::write(
+            &symbol_lib,
+            r#"(kicad_symbol_lib (symbol "LM358" (property "Value" "LM358")
+                (property "Description" "Dual op-amp")) (symbol "NE555"))"#,
+        )
+        .unwrap();
+        assert_eq!(session.list().unwrap(), ["LM358", "NE555"]);
+        assert_eq!(session.search("OP-AMP").unwrap(), ["LM358"]);
+        assert_eq!(session.show("NE555").unwrap().name(), "NE555");
+        assert!(matches!(
+            session.show("TL072"),
+            Err(ShellError::UnknownSymbol(_))
+        ));
+        assert_eq!(session.loads(), 1);
+
+        fs::write(&symbol_lib, r#"(kicad_symbol_lib (symbol "TL072"))"#).unwrap();
+        let file = fs::File::options().write(true).open(&symbol_lib).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(10))
+            .unwrap();
+        assert_eq!(session.list().unwrap(), ["TL072"]);
+        assert_eq!(session.loads(), 2);
+    }
+}
@@ -0,0 +1,324 @@
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SSH_NAMESPACE: &str = "kci";
+
+#[derive(Debug)]
+pub enum SigningError {
+    Io(io::Error),
+    Tool { tool: &'static str, message: String },
+    Unsigned(PathBuf),
+}
+
+impl fmt::Display for SigningError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SigningError::Io(err) => write!(f, "io error: {}", err),
+            SigningError::Tool { tool, message } => write!(f, "{} failed: {}", tool, message),
+            SigningError::Unsigned(path) => {
+                write!(f, "missing signature for {}", path.display())
+            }
+        }
+    }
+}
+
+impl Error for SigningError {}
+
+impl From<io::Error> for SigningError {
+    fn from(value: io::Error) -> Self {
+        SigningError::Io(value)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SigningFormat {
+    Ssh,
+    Minisign,
+}
+
+impl SigningFormat {
+    pub fn all() -> &'static [SigningFormat] {
+        &[SigningFormat::Ssh, SigningFormat::Minisign]
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SigningFormat::Ssh => "ssh",
+            SigningFormat::Minisign => "minisign",
+        }
+    }
+
+    fn tool(self) -> &'static str {
+        match self {
+            SigningFormat::Ssh => "ssh-keygen",
+            SigningFormat::Minisign => "minisign",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            SigningFormat::Ssh => "sig",
+            SigningFormat::Minisign => "minisig",
+        }
+    }
+}
+
+impl fmt::Display for SigningFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for SigningFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        SigningFormat::all()
+            .iter()
+            .copied()
+            .find(|format| format.name().eq_ignore_ascii_case(value.trim()))
+            .ok_or_else(|| format!("unknown signing format: {}", value))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SigningConfig {
+    format: SigningFormat,
+    key: PathBuf,
+    verify_key: Option<PathBuf>,
+    identity: Option<String>,
+}
+
+impl SigningConfig {
+    pub fn new(format: SigningFormat, key: PathBuf) -> Self {
+        Self {
+            format,
+            key,
+            verify_key: None,
+            identity: None,
+        }
+    }
+
+    pub fn with_verify_key(mut self, verify_key: Option<PathBuf>) -> Self {
+        self.verify_key = verify_key;
+        self
+    }
+
+    pub fn with_identity(mut self, identity: Option<String>) -> Self {
+        self.identity = identity;
+        self
+    }
+
+    pub fn format(&self) -> SigningFormat {
+        self.format
+    }
+
+    pub fn key(&self) -> &Path {
+        &self.key
+    }
+
+    pub fn verify_key(&self) -> Option<&Path> {
+        self.verify_key.as_deref()
+    }
+
+    pub fn identity(&self) -> Option<&str> {
+        self.identity.as_deref()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+    tool: String,
+    operator: String,
+    timestamp: u64,
+}
+
+impl Provenance {
+    pub fn current(operator: Option<&str>) -> Self {
+        let operator = operator
+            .map(str::to_string)
+            .or_else(|| std::env::var("USER").ok())
+            .or_else(|| std::env::var("USERNAME").ok())
+            .unwrap_or_else(|| "unknown".to_string());
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+        Self {
+            tool: format!("kci {}", env!("CARGO_PKG_VERSION")),
+            operator,
+            timestamp,
+        }
+    }
+
+    pub fn tool(&self) -> &str {
+        &self.tool
+    }
+
+    pub fn operator(&self) -> &str {
+        &self.operator
+    }
+
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+}
+
+pub fn signature_path(path: &Path, format: SigningFormat) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".");
+    name.push(format.extension());
+    PathBuf::from(name)
+}
+
+pub fn sign_file(path: &Path, config: &SigningConfig) -> Result<PathBuf, SigningError> {
+    let signature = signature_path(path, config.format);
+    if signature.exists() {
+        fs::remove_file(&signature)?;
+    }
+    let mut command = Command::new(config.format.tool());
+    match config.format {
+        SigningFormat::Ssh => {
+            command
+                .args(["-Y", "sign", "-n", SSH_NAMESPACE, "-f"])
+                .arg(&config.key)
+                .arg(path);
+        }
+        SigningFormat::Minisign => {
+            command
+                .arg("-S")
+                .arg("-s")
+                .arg(&config.key)
+                .arg("-m")
+                .arg(path)
+                .arg("-x")
+                .arg(&signature);
+        }
+    }
+    run_tool(config.format, command.stdin(Stdio::null()))?;
+    Ok(signature)
+}
+
+pub fn verify_file(path: &Path, config: &SigningConfig) -> Result<(), SigningError> {
+    let signature = signature_path(path, config.format);
+    if !signature.exists() {
+        return Err(SigningError::Unsigned(path.to_path_buf()));
+    }
+    let verify_key = config
+        .verify_key
+        .as_ref()
+        .ok_or_else(|| SigningError::Tool {
+            tool: config.format.tool(),
+            message: "no verify key configured".to_string(),
+        })?;
+    let mut command = Command::new(config.format.tool());
+    match config.format {
+        SigningFormat::Ssh => {
+            let identity = config
+                .identity
+                .as_deref()
+                .ok_or_else(|| SigningError::Tool {
+                    tool: config.format.tool(),
+                    message: "no signer identity configured".to_string(),
+                })?;
+            command
+                .args(["-Y", "verify", "-n", SSH_NAMESPACE, "-f"])
+                .arg(verify_key)
+                .arg("-I")
+                .arg(identity)
+                .arg("-s")
+                .arg(&signature)
+                .stdin(fs::File::open(path)?);
+        }
+        SigningFormat::Minisign => {
+            command
+                .arg("-V")
+                .arg("-p")
+                .arg(verify_key)
+                .arg("-m")
+                .arg(path)
+                .arg("-x")
+                .arg(&signature)
+                .stdin(Stdio::null());
+        }
+    }
+    run_tool(config.format, &mut command)
+}
+
+fn run_tool(format: SigningFormat, command: &mut Command) -> Result<(), SigningError> {
+    let output = command.output().map_err(|err| SigningError::Tool {
+        tool: format.tool(),
+        message: err.to_string(),
+    })?;
+    if output.status.success() {
+        return Ok(());
+    }
+    Err(SigningError::Tool {
+        tool: format.tool(),
+        message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn signature_path_appends_extension() {
+        assert_eq!(
+            signature_path(Path::new(".kci/lock.toml"), SigningFormat::Ssh),
+            Path::new(".kci/lock.toml.sig")
+        );
+        assert_eq!(
+            signature_path(Path::new("ledger.toml"), SigningFormat::Minisign),
+            Path::new("ledger.toml.minisig")
+        );
+    }
+
+    #[test]
+    fn ssh_sign_and_verify_round_trip() {
+        let dir = tempdir().unwrap();
+        let key = dir.path().join("id_ed25519");
+        let generated = Command::new("ssh-keygen")
+            .args([
+                "-q",
+                "-t",
+                "ed25519",
+                "-N",
+                "",
+                "-C",
+                "ci@example.com",
+                "-f",
+            ])
+            .arg(&key)
+            .output();
+        if !generated.is_ok_and(|output| output.status.success()) {
+            return;
+        }
+        let public = fs::read_to_string(dir.path().join("id_ed25519.pub")).unwrap();
+        let allowed = dir.path().join("allowed_signers");
+        fs::write(&allowed, format!("ci@example.com {}", public)).unwrap();
+
+        let file = dir.path().join("lock.toml");
+        fs::write(&file, "[files]\n").unwrap();
+        let config = SigningConfig::new(SigningFormat::Ssh, key)
+            .with_verify_key(Some(allowed))
+            .with_identity(Some("ci@example.com".to_string()));
+        sign_file(&file, &config).unwrap();
+        verify_file(&file, &config).unwrap();
+
+        fs::write(&file, "[files]\ntampered = true\n").unwrap();
+        assert!(matches!(
+            verify_file(&file, &config),
+            Err(SigningError::Tool { .. })
+        ));
+    }
+}
@@ -0,0 +1,173 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+const INPUT_PLACEHOLDER: &str = "{input}";
+const OUTPUT_PLACEHOLDER: &str = "{output}";
+
+#[derive(Debug)]
+pub enum SimplifyError {
+    Io(io::Error),
+    Invalid(String),
+    Tool(String),
+}
+
+impl fmt::Display for SimplifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SimplifyError::Io(err) => write!(f, "io error: {}", err),
+            SimplifyError::Invalid(msg) => write!(f, "invalid simplify command: {}", msg),
+            SimplifyError::Tool(msg) => write!(f, "simplify command failed: {}", msg),
+        }
+    }
+}
+
+impl Error for SimplifyError {}
+
+impl From<io::Error> for SimplifyError {
+    fn from(value: io::Error) -> Self {
+        SimplifyError::Io(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimplifyCommand {
+    template: String,
+}
+
+impl SimplifyCommand {
+    pub fn new(template: &str) -> Result<Self, SimplifyError> {
+        for placeholder in [INPUT_PLACEHOLDER, OUTPUT_PLACEHOLDER] {
+            if !template.contains(placeholder) {
+                return Err(SimplifyError::Invalid(format!(
+                    "missing {} in: {}",
+                    placeholder, template
+                )));
+            }
+        }
+        Ok(Self {
+            template: template.to_string(),
+        })
+    }
+
+    pub fn template(&self) -> &str {
+        &self.template
+    }
+
+    pub fn run(&self, input: &Path, output: &Path) -> Result<Simplification, SimplifyError> {
+        if let Some(parent) = output.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let line = self
+            .template
+            .replace(INPUT_PLACEHOLDER, &shell_quote(input))
+            .replace(OUTPUT_PLACEHOLDER, &shell_quote(output));
+        let mut process = if cfg!(windows) {
+            let mut process = Command::new("cmd");
+            process.arg("/C").arg(line);
+            process
+        } else {
+            let mut process = Command::new("sh");
+            process.arg("-c").arg(line);
+            process
+        };
+        let result = process
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|err| SimplifyError::Tool(err.to_string()))?;
+        if !result.status.success() {
+            return Err(SimplifyError::Tool(
+                String::from_utf8_lossy(&result.stderr).trim().to_string(),
+            ));
+        }
+        let reduced_size = match fs::metadata(output) {
+            Ok(metadata) if metadata.len() > 0 => metadata.len(),
+            _ => {
+                return Err(SimplifyError::Tool(format!(
+                    "no output written for {}",
+                    input.display()
+                )));
+            }
+        };
+        Ok(Simplification {
+            name: input
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            original_size: fs::metadata(input)?.len(),
+            reduced_size,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Simplification {
+    name: String,
+    original_size: u64,
+    reduced_size: u64,
+}
+
+impl Simplification {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn original_size(&self) -> u64 {
+        self.original_size
+    }
+
+    pub fn reduced_size(&self) -> u64 {
+        self.reduced_size
+    }
+}
+
+impl fmt::Display for Simplification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} simplified from {:.1} MB to {:.1} MB",
+            self.name,
+            self.original_size as f64 / (1024.0 * 1024.0),
+            self.reduced_size as f64 / (1024.0 * 1024.0)
+        )
+    }
+}
+
+fn shell_quote(path: &Path) -> String {
+    let value = path.to_string_lossy();
+    if cfg!(windows) {
+        format!("\"{}\"", value)
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn runs_command_and_records_sizes() {
+        assert!(SimplifyCommand::new("simplify {input}").is_err());
+        if cfg!(windows) {
+            return;
+        }
+        let dir = tempdir().unwrap();
+        let input = dir.path().join("it's big.step");
+        fs::write(&input, vec![b'x'; 4096]).unwrap();
+        let output = dir.path().join("out").join("it's big.step");
+        let command = SimplifyCommand::new("head -c 1024 {input} > {output}").unwrap();
+        let simplification = command.run(&input, &output).unwrap();
+        assert_eq!(simplification.name(), "it's big.step");
+        assert_eq!(simplification.original_size(), 4096);
+        assert_eq!(simplification.reduced_size(), 1024);
+
+        let failing = SimplifyCommand::new("true {input} {output}").unwrap();
+        let missing = dir.path().join("missing.step");
+        assert!(failing.run(&input, &missing).is_err());
+    }
+}
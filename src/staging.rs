@@ -0,0 +1,450 @@
+use crate::importer::{ImportConfig, new_temp_dir};
+use crate::kicad_sym::{KicadSymError, KicadSymbolLib, Sexp, Symbol};
+use crate::kicad_table::TableKind;
+use crate::ledger::Ledger;
+use crate::tags::sidecar_path;
+use similar::TextDiff;
+use std::collections::{BTreeSet, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use walkdir::WalkDir;
+
+#[derive(Debug)]
+pub enum StagingError {
+    Io(io::Error),
+    Walkdir(walkdir::Error),
+    Binary(PathBuf),
+    Symbol(KicadSymError),
+}
+
+impl fmt::Display for StagingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StagingError::Io(err) => write!(f, "io error: {}", err),
+            StagingError::Walkdir(err) => write!(f, "walk error: {}", err),
+            StagingError::Binary(path) => {
+                write!(f, "cannot write binary file to a patch: {}", path.display())
+            }
+            StagingError::Symbol(err) => write!(f, "cannot compare symbols: {}", err),
+        }
+    }
+}
+
+impl Error for StagingError {}
+
+impl From<io::Error> for StagingError {
+    fn from(value: io::Error) -> Self {
+        StagingError::Io(value)
+    }
+}
+
+impl From<walkdir::Error> for StagingError {
+    fn from(value: walkdir::Error) -> Self {
+        StagingError::Walkdir(value)
+    }
+}
+
+impl From<KicadSymError> for StagingError {
+    fn from(value: KicadSymError) -> Self {
+        StagingError::Symbol(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SymbolChange {
+    Added(String),
+    Replaced {
+        name: String,
+        properties: Vec<PropertyChange>,
+        body_changed: bool,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertyChange {
+    name: String,
+    before: Option<String>,
+    after: Option<String>,
+}
+
+impl PropertyChange {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn before(&self) -> Option<&str> {
+        self.before.as_deref()
+    }
+
+    pub fn after(&self) -> Option<&str> {
+        self.after.as_deref()
+    }
+}
+
+impl fmt::Display for PropertyChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.before, &self.after) {
+            (Some(before), Some(after)) => write!(f, "{}: {:?} -> {:?}", self.name, before, after),
+            (None, Some(after)) => write!(f, "+ {}: {:?}", self.name, after),
+            (Some(before), None) => write!(f, "- {}: {:?}", self.name, before),
+            (None, None) => write!(f, "{}", self.name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    path: PathBuf,
+    before: Option<Vec<u8>>,
+    after: Vec<u8>,
+}
+
+impl FileChange {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn is_new(&self) -> bool {
+        self.before.is_none()
+    }
+
+    pub fn shows_diff(&self) -> bool {
+        let name = self
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        name == TableKind::Symbol.file_name()
+            || name == TableKind::Footprint.file_name()
+            || name.ends_with(".kicad_sym")
+    }
+
+    pub fn is_text(&self) -> bool {
+        std::str::from_utf8(&self.after).is_ok()
+            && self
+                .before
+                .as_deref()
+                .is_none_or(|before| std::str::from_utf8(before).is_ok())
+    }
+
+    pub fn unified_diff(&self) -> String {
+        let before = self
+            .before
+            .as_deref()
+            .map(String::from_utf8_lossy)
+            .unwrap_or_default();
+        let after = String::from_utf8_lossy(&self.after);
+        let path = display_path(&self.path);
+        let old_header = if self.is_new() {
+            "/dev/null".to_string()
+        } else {
+            format!("a/{}", path)
+        };
+        TextDiff::from_lines(before.as_ref(), after.as_ref())
+            .unified_diff()
+            .header(&old_header, &format!("b/{}", path))
+            .to_string()
+    }
+
+    pub fn symbol_changes(&self) -> Result<Vec<SymbolChange>, StagingError> {
+        let before = match self.before.as_deref() {
+            Some(before) => canonical_lib(&String::from_utf8_lossy(before))?,
+            None => KicadSymbolLib::parse("(kicad_symbol_lib)")?,
+        };
+        let after = KicadSymbolLib::parse(&String::from_utf8_lossy(&self.after))?;
+        let mut changes = Vec::new();
+        for symbol in after.symbols_iter() {
+            let Some(previous) = before.symbol(symbol.name()) else {
+                changes.push(SymbolChange::Added(symbol.name().to_string()));
+                continue;
+            };
+            let properties = property_changes(previous, symbol);
+            let body_changed = without_properties(previous) != without_properties(symbol);
+            if !properties.is_empty() || body_changed {
+                changes.push(SymbolChange::Replaced {
+                    name: symbol.name().to_string(),
+                    properties,
+                    body_changed,
+                });
+            }
+        }
+        Ok(changes)
+    }
+
+    pub fn git_diff(&self) -> Result<String, StagingError> {
+        if !self.is_text() {
+            return Err(StagingError::Binary(self.path.clone()));
+        }
+        let path = display_path(&self.path);
+        let mut out = format!("diff --git a/{} b/{}\n", path, path);
+        if self.is_new() {
+            out.push_str("new file mode 100644\n");
+        }
+        out.push_str(&self.unified_diff());
+        Ok(out)
+    }
+}
+
+pub struct Staging {
+    dir: TempDir,
+    config: ImportConfig,
+    project_root: PathBuf,
+    sources: HashMap<PathBuf, PathBuf>,
+}
+
+impl Staging {
+    pub fn new(project_root: &Path, config: &ImportConfig) -> Result<Self, StagingError> {
+        Self::with_sources(project_root, config, HashMap::new())
+    }
+
+    pub fn with_sources(
+        project_root: &Path,
+        config: &ImportConfig,
+        sources: HashMap<PathBuf, PathBuf>,
+    ) -> Result<Self, StagingError> {
+        let dir = new_temp_dir(config.temp_dir())?;
+        let config = config
+            .clone()
+            .with_output_root(Some(dir.path().to_path_buf()));
+        let mut staging = Self {
+            dir,
+            config,
+            project_root: project_root.to_path_buf(),
+            sources,
+        };
+        for path in staging.tracked_paths() {
+            let real = staging.real_path(&path);
+            let staged = staging.config.output_path(&path);
+            if real.is_file() {
+                if let Some(parent) = staged.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::copy(&real, &staged)?;
+            } else if real.is_dir() {
+                fs::create_dir_all(&staged)?;
+                staging.config = staging.config.clone().with_existing_path(&path, real);
+            }
+        }
+        Ok(staging)
+    }
+
+    pub fn root(&self) -> &Path {
+        self.dir.path()
+    }
+
+    pub fn config(&self) -> &ImportConfig {
+        &self.config
+    }
+
+    pub fn changes(&self) -> Result<Vec<FileChange>, StagingError> {
+        let mut changes = Vec::new();
+        for path in self.tracked_paths() {
+            let staged = self.config.output_path(&path);
+            if !staged.exists() {
+                continue;
+            }
+            let real = self.real_path(&path);
+            for entry in WalkDir::new(&staged).sort_by_file_name() {
+                let entry = entry?;
+                if !entry.file_type().is_file() {
+                    continue;
+                }
+                let relative = entry.path().strip_prefix(&staged).unwrap_or(entry.path());
+                let (real_file, display) = if relative.as_os_str().is_empty() {
+                    (real.clone(), path.clone())
+                } else {
+                    (real.join(relative), path.join(relative))
+                };
+                let display = display
+                    .strip_prefix(&self.project_root)
+                    .map(Path::to_path_buf)
+                    .unwrap_or(display);
+                let after = fs::read(entry.path())?;
+                let before = if real_file.is_file() {
+                    Some(fs::read(&real_file)?)
+                } else {
+                    None
+                };
+                if before.as_ref() == Some(&after) {
+                    continue;
+                }
+                changes.push(FileChange {
+                    path: display,
+                    before,
+                    after,
+                });
+            }
+        }
+        Ok(changes)
+    }
+
+    pub fn staged_path(&self, path: &Path) -> PathBuf {
+        self.config.output_path(path)
+    }
+
+    pub fn file_change(&self, path: &Path) -> Result<Option<FileChange>, StagingError> {
+        let staged = self.config.output_path(path);
+        if !staged.is_file() {
+            return Ok(None);
+        }
+        let real = self.real_path(path);
+        let after = fs::read(&staged)?;
+        let before = if real.is_file() {
+            Some(fs::read(&real)?)
+        } else {
+            None
+        };
+        if before.as_ref() == Some(&after) {
+            return Ok(None);
+        }
+        Ok(Some(FileChange {
+            path: path.to_path_buf(),
+            before,
+            after,
+        }))
+    }
+
+    fn real_path(&self, path: &Path) -> PathBuf {
+        self.sources
+            .get(path)
+            .cloned()
+            .unwrap_or_else(|| self.project_root.join(path))
+    }
+
+    fn tracked_paths(&self) -> Vec<PathBuf> {
+        vec![
+            self.config.symbol_lib().to_path_buf(),
+            self.config.footprint_lib().to_path_buf(),
+            self.config.step_dir().to_path_buf(),
+            PathBuf::from(TableKind::Symbol.file_name()),
+            PathBuf::from(TableKind::Footprint.file_name()),
+            Ledger::path(Path::new("")),
+            sidecar_path(Path::new("")),
+        ]
+    }
+}
+
+fn canonical_lib(text: &str) -> Result<KicadSymbolLib, KicadSymError> {
+    KicadSymbolLib::parse(&KicadSymbolLib::parse(text)?.to_string_pretty())
+}
+
+fn property_changes(before: &Symbol, after: &Symbol) -> Vec<PropertyChange> {
+    let before: HashMap<String, String> = before.properties().into_iter().collect();
+    let after: HashMap<String, String> = after.properties().into_iter().collect();
+    let names: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    names
+        .into_iter()
+        .filter(|name| before.get(*name) != after.get(*name))
+        .map(|name| PropertyChange {
+            name: name.clone(),
+            before: before.get(name).cloned(),
+            after: after.get(name).cloned(),
+        })
+        .collect()
+}
+
+fn without_properties(symbol: &Symbol) -> Sexp {
+    let mut symbol = symbol.clone();
+    for (name, _) in symbol.properties() {
+        symbol.remove_property(&name);
+    }
+    symbol.into_sexp()
+}
+
+fn display_path(path: &Path) -> String {
+    path.to_string_lossy()
+        .replace('\\', "/")
+        .trim_start_matches("./")
+        .trim_start_matches('/')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_staged_changes_without_touching_project() {
+        let project = tempfile::tempdir().unwrap();
+        fs::write(
+            project.path().join("lib.kicad_sym"),
+            "(kicad_symbol_lib\n)\n",
+        )
+        .unwrap();
+        let config = ImportConfig::new(
+            PathBuf::from("lib.kicad_sym"),
+            PathBuf::from("Lib.pretty"),
+            PathBuf::from("3d"),
+        );
+        let staging = Staging::new(project.path(), &config).unwrap();
+        let staged_lib = staging.config().output_path(config.symbol_lib());
+        assert!(staged_lib.starts_with(staging.root()));
+        fs::write(&staged_lib, "(kicad_symbol_lib\n\t(symbol \"A\")\n)\n").unwrap();
+        let staged_footprint = staging
+            .config()
+            .output_path(&config.footprint_lib().join("A.kicad_mod"));
+        fs::create_dir_all(staged_footprint.parent().unwrap()).unwrap();
+        fs::write(&staged_footprint, "(footprint \"A\")\n").unwrap();
+
+        let changes = staging.changes().unwrap();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].path(), Path::new("lib.kicad_sym"));
+        assert!(changes[0].shows_diff());
+        assert!(changes[0].unified_diff().contains("+\t(symbol \"A\")"));
+        assert!(changes[1].is_new());
+        assert!(!changes[1].shows_diff());
+        assert_eq!(
+            changes[1].git_diff().unwrap(),
+            "diff --git a/Lib.pretty/A.kicad_mod b/Lib.pretty/A.kicad_mod\n\
+             new file mode 100644\n\
+             --- /dev/null\n\
+             +++ b/Lib.pretty/A.kicad_mod\n\
+             @@ -0,0 +1 @@\n\
+             +(footprint \"A\")\n"
+        );
+        assert_eq!(
+            fs::read_to_string(project.path().join("lib.kicad_sym")).unwrap(),
+            "(kicad_symbol_lib\n)\n"
+        );
+    }
+
+    #[test]
+    fn compares_symbols_by_property() {
+        let change = FileChange {
+            path: PathBuf::from("lib.kicad_sym"),
+            before: Some(
+                b"(kicad_symbol_lib (symbol \"A\" (property \"Value\" \"A\") (property \"Old\" \"x\") \
+                  (pin input line (number \"2\")) (pin input line (number \"1\"))) \
+                  (symbol \"B\" (pin input line (number \"1\"))))"
+                    .to_vec(),
+            ),
+            after: b"(kicad_symbol_lib (symbol \"A\" (property \"Value\" \"A1\") \
+                     (pin input line (number \"1\")) (pin input line (number \"2\"))) \
+                     (symbol \"B\" (pin output line (number \"1\"))) (symbol \"C\"))"
+                .to_vec(),
+        };
+        let changes = change.symbol_changes().unwrap();
+        assert_eq!(changes.len(), 3);
+        let SymbolChange::Replaced {
+            name,
+            properties,
+            body_changed,
+        } = &changes[0]
+        else {
+            panic!("unexpected change: {:?}", changes[0]);
+        };
+        assert_eq!(name, "A");
+        assert!(!body_changed);
+        let properties: Vec<String> = properties.iter().map(ToString::to_string).collect();
+        assert_eq!(properties, ["- Old: \"x\"", "Value: \"A\" -> \"A1\""]);
+        assert!(matches!(
+            &changes[1],
+            SymbolChange::Replaced { name, body_changed: true, .. } if name == "B"
+        ));
+        assert_eq!(changes[2], SymbolChange::Added("C".to_string()));
+    }
+}
@@ -0,0 +1,224 @@
+use crate::importer::ImportReport;
+use crate::ledger::STATE_DIR;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use walkdir::WalkDir;
+
+const STATS_FILE: &str = "stats.jsonl";
+
+#[derive(Debug)]
+pub enum StatsError {
+    Io(io::Error),
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for StatsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatsError::Io(err) => write!(f, "io error: {}", err),
+            StatsError::Json(err) => write!(f, "import stats error: {}", err),
+        }
+    }
+}
+
+impl Error for StatsError {}
+
+impl From<io::Error> for StatsError {
+    fn from(value: io::Error) -> Self {
+        StatsError::Io(value)
+    }
+}
+
+impl From<serde_json::Error> for StatsError {
+    fn from(value: serde_json::Error) -> Self {
+        StatsError::Json(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StageTime {
+    stage: String,
+    ms: f64,
+}
+
+impl StageTime {
+    pub fn stage(&self) -> &str {
+        &self.stage
+    }
+
+    pub fn ms(&self) -> f64 {
+        self.ms
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportStats {
+    timestamp: u64,
+    sources: Vec<String>,
+    source_bytes: u64,
+    total_ms: f64,
+    stages: Vec<StageTime>,
+    symbols: usize,
+    footprints: usize,
+    step_files: usize,
+    step_bytes: u64,
+    fetches: usize,
+    fetch_cache_hits: usize,
+}
+
+impl ImportStats {
+    pub fn new(report: &ImportReport, sources: &[PathBuf], total: Duration) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+        Self {
+            timestamp,
+            sources: sources
+                .iter()
+                .map(|source| source.display().to_string())
+                .collect(),
+            source_bytes: sources.iter().map(|source| size_on_disk(source)).sum(),
+            total_ms: milliseconds(total),
+            stages: report
+                .stage_times()
+                .iter()
+                .map(|(stage, time)| StageTime {
+                    stage: stage.to_string(),
+                    ms: milliseconds(*time),
+                })
+                .collect(),
+            symbols: report.symbols_added(),
+            footprints: report.footprints_added(),
+            step_files: report.step_files_added(),
+            step_bytes: report.step_bytes_copied(),
+            fetches: 0,
+            fetch_cache_hits: 0,
+        }
+    }
+
+    pub fn with_fetches(mut self, fetches: usize, cache_hits: usize) -> Self {
+        self.fetches = fetches;
+        self.fetch_cache_hits = cache_hits;
+        self
+    }
+
+    pub fn path(project_root: &Path) -> PathBuf {
+        project_root.join(STATE_DIR).join(STATS_FILE)
+    }
+
+    pub fn load_all(project_root: &Path) -> Result<Vec<Self>, StatsError> {
+        let path = Self::path(project_root);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        fs::read_to_string(path)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str(line).map_err(StatsError::from))
+            .collect()
+    }
+
+    pub fn append(&self, project_root: &Path) -> Result<(), StatsError> {
+        let path = Self::path(project_root);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut line = serde_json::to_string(self)?;
+        line.push('\n');
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?
+            .write_all(line.as_bytes())?;
+        Ok(())
+    }
+
+    pub fn stages(&self) -> &[StageTime] {
+        &self.stages
+    }
+
+    pub fn total_ms(&self) -> f64 {
+        self.total_ms
+    }
+
+    pub fn source_bytes(&self) -> u64 {
+        self.source_bytes
+    }
+
+    pub fn cache_hit_rate(&self) -> Option<f64> {
+        (self.fetches > 0).then(|| self.fetch_cache_hits as f64 / self.fetches as f64)
+    }
+}
+
+fn milliseconds(time: Duration) -> f64 {
+    (time.as_secs_f64() * 1_000_000.0).round() / 1000.0
+}
+
+fn size_on_disk(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::importer::{ImportConfig, import_source};
+    use crate::kicad_sym::AddPolicy;
+    use tempfile::tempdir;
+
+    #[test]
+    fn appends_one_record_per_import() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let source = root.join("vendor");
+        fs::create_dir_all(source.join("LM358.pretty")).unwrap();
+        fs::write(
+            source.join("LM358.kicad_sym"),
+            r#"(kicad_symbol_lib (symbol "LM358" (property "Footprint" "SOIC-8")))"#,
+        )
+        .unwrap();
+        fs::write(
+            source.join("LM358.pretty/SOIC-8.kicad_mod"),
+            r#"(footprint "SOIC-8")"#,
+        )
+        .unwrap();
+        let config = ImportConfig::new(
+            root.join("lib.kicad_sym"),
+            root.join("lib.pretty"),
+            root.join("lib_3d"),
+        );
+
+        for _ in 0..2 {
+            let report = import_source(&source, &config, AddPolicy::ReplaceExisting).unwrap();
+            ImportStats::new(
+                &report,
+                std::slice::from_ref(&source),
+                Duration::from_millis(12),
+            )
+            .with_fetches(4, 3)
+            .append(root)
+            .unwrap();
+        }
+
+        let records = ImportStats::load_all(root).unwrap();
+        assert_eq!(records.len(), 2);
+        let stats = &records[0];
+        assert_eq!(stats.total_ms(), 12.0);
+        assert!(stats.source_bytes() > 0);
+        assert_eq!(stats.cache_hit_rate(), Some(0.75));
+        let stages: Vec<&str> = stats.stages().iter().map(StageTime::stage).collect();
+        assert_eq!(stages.first(), Some(&"extract"));
+        assert_eq!(stages.last(), Some(&"copy models"));
+    }
+}
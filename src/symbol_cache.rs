@@ -0,0 +1,198 @@
+use crate::fetch::CACHE_DIR;
+use crate::kicad_sym::{KicadSymError, SymbolStream};
+use crate::ledger::STATE_DIR;
+use crate::mapped::MappedFile;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const SYMBOL_CACHE_FILE: &str = "symbols.bin";
+const SYMBOL_CACHE_VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub enum SymbolCacheError {
+    Io(io::Error),
+    Symbol(KicadSymError),
+    Encode(bincode::Error),
+}
+
+impl fmt::Display for SymbolCacheError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SymbolCacheError::Io(err) => write!(f, "io error: {}", err),
+            SymbolCacheError::Symbol(err) => write!(f, "symbol parse error: {}", err),
+            SymbolCacheError::Encode(err) => write!(f, "symbol cache write error: {}", err),
+        }
+    }
+}
+
+impl Error for SymbolCacheError {}
+
+impl From<io::Error> for SymbolCacheError {
+    fn from(value: io::Error) -> Self {
+        SymbolCacheError::Io(value)
+    }
+}
+
+impl From<KicadSymError> for SymbolCacheError {
+    fn from(value: KicadSymError) -> Self {
+        SymbolCacheError::Symbol(value)
+    }
+}
+
+impl From<bincode::Error> for SymbolCacheError {
+    fn from(value: bincode::Error) -> Self {
+        SymbolCacheError::Encode(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedSymbol {
+    name: String,
+    properties: Vec<(String, String)>,
+}
+
+impl CachedSymbol {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn properties(&self) -> &[(String, String)] {
+        &self.properties
+    }
+
+    pub fn property_value(&self, name: &str) -> Option<&str> {
+        self.properties
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct CachedLibrary {
+    hash: [u8; 32],
+    symbols: Vec<CachedSymbol>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct CacheFile {
+    version: u32,
+    libraries: BTreeMap<String, CachedLibrary>,
+}
+
+#[derive(Debug)]
+pub struct SymbolCache {
+    path: PathBuf,
+    file: CacheFile,
+    dirty: bool,
+    parsed: usize,
+}
+
+impl SymbolCache {
+    pub fn path(project_root: &Path) -> PathBuf {
+        project_root
+            .join(STATE_DIR)
+            .join(CACHE_DIR)
+            .join(SYMBOL_CACHE_FILE)
+    }
+
+    pub fn load(project_root: &Path) -> Self {
+        let path = Self::path(project_root);
+        let file = fs::read(&path)
+            .ok()
+            .and_then(|bytes| bincode::deserialize::<CacheFile>(&bytes).ok())
+            .filter(|file| file.version == SYMBOL_CACHE_VERSION)
+            .unwrap_or(CacheFile {
+                version: SYMBOL_CACHE_VERSION,
+                libraries: BTreeMap::new(),
+            });
+        Self {
+            path,
+            file,
+            dirty: false,
+            parsed: 0,
+        }
+    }
+
+    pub fn parsed(&self) -> usize {
+        self.parsed
+    }
+
+    pub fn symbols(&mut self, symbol_lib: &Path) -> Result<&[CachedSymbol], SymbolCacheError> {
+        let key = symbol_lib.to_string_lossy().replace('\\', "/");
+        let mapped = MappedFile::open(symbol_lib)?;
+        let hash: [u8; 32] = Sha256::digest(mapped.bytes()).into();
+        let fresh = self
+            .file
+            .libraries
+            .get(&key)
+            .is_some_and(|library| library.hash == hash);
+        if !fresh {
+            let symbols = SymbolStream::new(mapped.text()?)
+                .map(|symbol| {
+                    let symbol = symbol?;
+                    Ok(CachedSymbol {
+                        name: symbol.name().to_string(),
+                        properties: symbol.properties(),
+                    })
+                })
+                .collect::<Result<Vec<_>, KicadSymError>>()?;
+            self.file
+                .libraries
+                .insert(key.clone(), CachedLibrary { hash, symbols });
+            self.dirty = true;
+            self.parsed += 1;
+        }
+        Ok(&self.file.libraries[&key].symbols)
+    }
+
+    pub fn save(&mut self) -> Result<(), SymbolCacheError> {
+        if !self.dirty {
+            return Ok(());
+        }
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, bincode::serialize(&self.file)?)?;
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn reparses_a_library_only_when_its_content_changes() {
+        let dir = tempdir().unwrap();
+        let symbol_lib = dir.path().join("lib.kicad_sym");
+        fs::write(
+            &symbol_lib,
+            r#"(kicad_symbol_lib (symbol "LM358" (property "Value" "LM358")) (symbol "NE555"))"#,
+        )
+        .unwrap();
+
+        let mut cache = SymbolCache::load(dir.path());
+        let symbols = cache.symbols(&symbol_lib).unwrap();
+        assert_eq!(symbols.len(), 2);
+        assert_eq!(symbols[0].property_value("Value"), Some("LM358"));
+        cache.save().unwrap();
+        assert!(SymbolCache::path(dir.path()).exists());
+
+        let mut cache = SymbolCache::load(dir.path());
+        assert_eq!(cache.symbols(&symbol_lib).unwrap()[1].name(), "NE555");
+        assert_eq!(cache.parsed(), 0);
+
+        fs::write(&symbol_lib, r#"(kicad_symbol_lib (symbol "TL072"))"#).unwrap();
+        assert_eq!(cache.symbols(&symbol_lib).unwrap()[0].name(), "TL072");
+        assert_eq!(cache.parsed(), 1);
+    }
+}
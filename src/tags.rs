@@ -0,0 +1,341 @@
+use crate::kicad_sym::{KicadSymError, KicadSymbolLib, Symbol};
+use crate::ledger::STATE_DIR;
+use crate::symbol_cache::{SymbolCache, SymbolCacheError};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+pub const TAG_PROPERTY_PREFIX: &str = "kci_tag:";
+const TAGS_FILE: &str = "tags.toml";
+
+pub type Tags = BTreeMap<String, String>;
+
+#[derive(Debug)]
+pub enum TagError {
+    Io(io::Error),
+    Parse(toml::de::Error),
+    Write(toml::ser::Error),
+    Symbol(KicadSymError),
+    Cache(SymbolCacheError),
+    MissingSymbol(String),
+}
+
+impl fmt::Display for TagError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TagError::Io(err) => write!(f, "io error: {}", err),
+            TagError::Parse(err) => write!(f, "tag index parse error: {}", err),
+            TagError::Write(err) => write!(f, "tag index write error: {}", err),
+            TagError::Symbol(err) => write!(f, "symbol parse error: {}", err),
+            TagError::Cache(err) => write!(f, "{}", err),
+            TagError::MissingSymbol(name) => write!(f, "symbol not found in library: {}", name),
+        }
+    }
+}
+
+impl Error for TagError {}
+
+impl From<io::Error> for TagError {
+    fn from(value: io::Error) -> Self {
+        TagError::Io(value)
+    }
+}
+
+impl From<toml::de::Error> for TagError {
+    fn from(value: toml::de::Error) -> Self {
+        TagError::Parse(value)
+    }
+}
+
+impl From<toml::ser::Error> for TagError {
+    fn from(value: toml::ser::Error) -> Self {
+        TagError::Write(value)
+    }
+}
+
+impl From<KicadSymError> for TagError {
+    fn from(value: KicadSymError) -> Self {
+        TagError::Symbol(value)
+    }
+}
+
+impl From<SymbolCacheError> for TagError {
+    fn from(value: SymbolCacheError) -> Self {
+        TagError::Cache(value)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TagStore {
+    Property,
+    Sidecar,
+}
+
+impl TagStore {
+    pub fn all() -> &'static [TagStore] {
+        &[TagStore::Property, TagStore::Sidecar]
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TagStore::Property => "property",
+            TagStore::Sidecar => "sidecar",
+        }
+    }
+}
+
+impl fmt::Display for TagStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for TagStore {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        TagStore::all()
+            .iter()
+            .copied()
+            .find(|store| store.name().eq_ignore_ascii_case(value.trim()))
+            .ok_or_else(|| format!("unknown tag store: {}", value))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tag {
+    key: String,
+    value: String,
+}
+
+impl Tag {
+    pub fn new(key: &str, value: &str) -> Self {
+        Self {
+            key: key.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn matches(&self, tags: &Tags) -> bool {
+        tags.get(&self.key).is_some_and(|value| *value == self.value)
+    }
+}
+
+impl fmt::Display for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.key, self.value)
+    }
+}
+
+impl FromStr for Tag {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (key, tag_value) = value
+            .split_once('=')
+            .ok_or_else(|| format!("expected KEY=VALUE: {}", value))?;
+        let key = key.trim();
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return Err(format!("invalid tag key: {}", value));
+        }
+        Ok(Self {
+            key: key.to_string(),
+            value: tag_value.trim().to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct TagIndex {
+    #[serde(default)]
+    symbols: BTreeMap<String, Tags>,
+}
+
+impl TagIndex {
+    fn path(project_root: &Path) -> PathBuf {
+        project_root.join(STATE_DIR).join(TAGS_FILE)
+    }
+
+    fn load(project_root: &Path) -> Result<Self, TagError> {
+        let path = Self::path(project_root);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let raw = fs::read_to_string(path)?;
+        Ok(toml::from_str(&raw)?)
+    }
+
+    fn save(&self, project_root: &Path) -> Result<(), TagError> {
+        let path = Self::path(project_root);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+pub fn sidecar_path(project_root: &Path) -> PathBuf {
+    TagIndex::path(project_root)
+}
+
+pub fn load_tags(
+    store: TagStore,
+    symbol_lib: &Path,
+    project_root: &Path,
+) -> Result<BTreeMap<String, Tags>, TagError> {
+    let index = match store {
+        TagStore::Property => TagIndex::default(),
+        TagStore::Sidecar => TagIndex::load(project_root)?,
+    };
+    let mut cache = SymbolCache::load(project_root);
+    let tags = cache
+        .symbols(symbol_lib)?
+        .iter()
+        .map(|symbol| {
+            let tags = match store {
+                TagStore::Property => tags_from_properties(symbol.properties()),
+                TagStore::Sidecar => index
+                    .symbols
+                    .get(symbol.name())
+                    .cloned()
+                    .unwrap_or_default(),
+            };
+            (symbol.name().to_string(), tags)
+        })
+        .collect();
+    cache.save()?;
+    Ok(tags)
+}
+
+pub fn update_tags(
+    store: TagStore,
+    symbol_lib: &Path,
+    project_root: &Path,
+    symbol_name: &str,
+    set: &[Tag],
+    remove: &[String],
+) -> Result<Tags, TagError> {
+    let mut lib = load_library(symbol_lib)?;
+    let symbol = lib
+        .symbol_mut(symbol_name)
+        .ok_or_else(|| TagError::MissingSymbol(symbol_name.to_string()))?;
+    match store {
+        TagStore::Property => {
+            for key in remove {
+                symbol.remove_property(&format!("{}{}", TAG_PROPERTY_PREFIX, key));
+            }
+            for tag in set {
+                symbol.set_or_add_hidden_property(
+                    &format!("{}{}", TAG_PROPERTY_PREFIX, tag.key),
+                    &tag.value,
+                );
+            }
+            let tags = property_tags(symbol);
+            if !set.is_empty() || !remove.is_empty() {
+                fs::write(symbol_lib, lib.to_string_pretty())?;
+            }
+            Ok(tags)
+        }
+        TagStore::Sidecar => {
+            let mut index = TagIndex::load(project_root)?;
+            let tags = index.symbols.entry(symbol_name.to_string()).or_default();
+            for key in remove {
+                tags.remove(key);
+            }
+            for tag in set {
+                tags.insert(tag.key.clone(), tag.value.clone());
+            }
+            let tags = tags.clone();
+            if tags.is_empty() {
+                index.symbols.remove(symbol_name);
+            }
+            if !set.is_empty() || !remove.is_empty() {
+                index.save(project_root)?;
+            }
+            Ok(tags)
+        }
+    }
+}
+
+fn property_tags(symbol: &Symbol) -> Tags {
+    tags_from_properties(&symbol.properties())
+}
+
+fn tags_from_properties(properties: &[(String, String)]) -> Tags {
+    properties
+        .iter()
+        .filter_map(|(name, value)| {
+            name.strip_prefix(TAG_PROPERTY_PREFIX)
+                .map(|key| (key.to_string(), value.clone()))
+        })
+        .collect()
+}
+
+fn load_library(symbol_lib: &Path) -> Result<KicadSymbolLib, TagError> {
+    let content = fs::read_to_string(symbol_lib)?;
+    Ok(KicadSymbolLib::parse(&content)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn tags_round_trip_in_both_stores() {
+        let dir = tempdir().unwrap();
+        let symbol_lib = dir.path().join("lib.kicad_sym");
+        fs::write(
+            &symbol_lib,
+            "(kicad_symbol_lib (version 20231120) (symbol \"U1\" (property \"Value\" \"U1\")) (symbol \"U2\"))",
+        )
+        .unwrap();
+        let status: Tag = "status=approved".parse().unwrap();
+        assert!("approved".parse::<Tag>().is_err());
+
+        for store in TagStore::all().iter().copied() {
+            let tags = update_tags(
+                store,
+                &symbol_lib,
+                dir.path(),
+                "U1",
+                &[status.clone(), "stage=proto".parse().unwrap()],
+                &[],
+            )
+            .unwrap();
+            assert_eq!(tags.len(), 2);
+            let tags =
+                update_tags(store, &symbol_lib, dir.path(), "U1", &[], &["stage".to_string()])
+                    .unwrap();
+            assert_eq!(tags.len(), 1);
+
+            let all = load_tags(store, &symbol_lib, dir.path()).unwrap();
+            assert!(status.matches(&all["U1"]));
+            assert!(all["U2"].is_empty());
+        }
+        assert!(TagIndex::path(dir.path()).exists());
+        assert!(
+            fs::read_to_string(&symbol_lib)
+                .unwrap()
+                .contains("kci_tag:status")
+        );
+        assert!(matches!(
+            update_tags(TagStore::Sidecar, &symbol_lib, dir.path(), "U3", &[], &[]),
+            Err(TagError::MissingSymbol(_))
+        ));
+    }
+}
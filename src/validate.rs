@@ -0,0 +1,540 @@
+use crate::importer::{ImportConfig, model_reference};
+use crate::kicad_sym::{Sexp, SymbolStream, parse_one};
+use crate::kicad_table::{
+    TableError, TableIssue, TableKind, diagnose_project_tables, lib_name_from_path,
+    project_table_entries,
+};
+use crate::mapped::MappedFile;
+use crate::pad_checks::{PadPolicy, PadProblem, check_pads};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const MIL_MM: f64 = 0.0254;
+const METRIC_GRID_MM: f64 = 0.05;
+const GRID_TOLERANCE_MM: f64 = 1e-4;
+const PITCH_TOLERANCE_MM: f64 = 0.01;
+const STANDARD_PITCHES_MM: &[f64] = &[
+    0.3, 0.35, 0.4, 0.5, 0.635, 0.65, 0.75, 0.8, 1.0, 1.25, 1.27, 1.5, 2.0, 2.5, 2.54, 3.0, 3.5,
+    3.81, 3.96, 4.2, 5.0, 5.08, 7.5, 7.62, 10.0, 10.16,
+];
+
+#[derive(Debug)]
+pub enum ValidateError {
+    Io(io::Error),
+    Table(TableError),
+}
+
+impl fmt::Display for ValidateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidateError::Io(err) => write!(f, "io error: {}", err),
+            ValidateError::Table(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Error for ValidateError {}
+
+impl From<io::Error> for ValidateError {
+    fn from(value: io::Error) -> Self {
+        ValidateError::Io(value)
+    }
+}
+
+impl From<TableError> for ValidateError {
+    fn from(value: TableError) -> Self {
+        ValidateError::Table(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LibraryProblem {
+    Unparsable { path: PathBuf, message: String },
+    DuplicateSymbol { name: String, count: usize },
+    DuplicateFootprint { name: String, files: Vec<String> },
+    MissingFootprint { symbol: String, footprint: String },
+    MissingModel { footprint: String, model: String },
+    MixedUnits { footprint: String },
+    SuspiciousPitch { footprint: String, pitch: String },
+    Pad { footprint: String, problem: PadProblem },
+    Table { table: String, issue: TableIssue },
+}
+
+impl fmt::Display for LibraryProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LibraryProblem::Unparsable { path, message } => {
+                write!(f, "{}: cannot be parsed: {}", path.display(), message)
+            }
+            LibraryProblem::DuplicateSymbol { name, count } => {
+                write!(f, "symbol {} is defined {} times", name, count)
+            }
+            LibraryProblem::DuplicateFootprint { name, files } => {
+                write!(f, "footprint name {} is used by {}", name, files.join(", "))
+            }
+            LibraryProblem::MissingFootprint { symbol, footprint } => {
+                write!(f, "{}: footprint {} does not exist", symbol, footprint)
+            }
+            LibraryProblem::MissingModel { footprint, model } => {
+                write!(f, "{}: 3D model {} does not exist", footprint, model)
+            }
+            LibraryProblem::MixedUnits { footprint } => write!(
+                f,
+                "{}: pads are on an imperial grid but the metric courtyard doesn't cover them; check the unit conversion",
+                footprint
+            ),
+            LibraryProblem::SuspiciousPitch { footprint, pitch } => {
+                write!(
+                    f,
+                    "{}: pad pitch {} mm doesn't match a standard package",
+                    footprint, pitch
+                )
+            }
+            LibraryProblem::Pad { footprint, problem } => write!(f, "{}: {}", footprint, problem),
+            LibraryProblem::Table { table, issue } => write!(f, "{}: {}", table, issue),
+        }
+    }
+}
+
+pub fn validate_libraries(
+    project_root: &Path,
+    config: &ImportConfig,
+) -> Result<Vec<LibraryProblem>, ValidateError> {
+    let mut problems = Vec::new();
+    let relative = |path: &Path| {
+        path.strip_prefix(project_root)
+            .unwrap_or(path)
+            .to_path_buf()
+    };
+
+    let mut footprint_dirs = HashMap::new();
+    footprint_dirs.insert(
+        lib_name_from_path(TableKind::Footprint, config.footprint_lib())?,
+        project_root.join(config.footprint_lib()),
+    );
+    let mut tables_parsed = true;
+    for kind in [TableKind::Symbol, TableKind::Footprint] {
+        match project_table_entries(project_root, kind) {
+            Ok(entries) if kind == TableKind::Footprint => {
+                for entry in entries {
+                    if let Some(path) = entry.path() {
+                        footprint_dirs
+                            .entry(entry.name().to_string())
+                            .or_insert_with(|| path.to_path_buf());
+                    }
+                }
+            }
+            Ok(_) => {}
+            Err(TableError::Parse(message)) => {
+                tables_parsed = false;
+                problems.push(LibraryProblem::Unparsable {
+                    path: PathBuf::from(kind.file_name()),
+                    message,
+                });
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+    if tables_parsed {
+        for finding in diagnose_project_tables(project_root, false)? {
+            problems.push(LibraryProblem::Table {
+                table: relative(finding.table()).display().to_string(),
+                issue: finding.issue().clone(),
+            });
+        }
+    }
+
+    let symbol_lib = project_root.join(config.symbol_lib());
+    if symbol_lib.is_file() {
+        let file = MappedFile::open(&symbol_lib)?;
+        let mut counts = BTreeMap::new();
+        let mut missing = Vec::new();
+        let mut parse_error = None;
+        for symbol in SymbolStream::new(file.text()?) {
+            let symbol = match symbol {
+                Ok(symbol) => symbol,
+                Err(err) => {
+                    parse_error = Some(err);
+                    break;
+                }
+            };
+            *counts.entry(symbol.name().to_string()).or_insert(0) += 1;
+            let Some(footprint) = symbol.property_value("Footprint") else {
+                continue;
+            };
+            let Some((nick, name)) = footprint.split_once(':') else {
+                continue;
+            };
+            if let Some(dir) = footprint_dirs.get(nick)
+                && !dir.join(format!("{}.kicad_mod", name)).is_file()
+            {
+                missing.push(LibraryProblem::MissingFootprint {
+                    symbol: symbol.name().to_string(),
+                    footprint,
+                });
+            }
+        }
+        match parse_error {
+            Some(err) => problems.push(LibraryProblem::Unparsable {
+                path: relative(&symbol_lib),
+                message: err.to_string(),
+            }),
+            None => {
+                for (name, count) in counts {
+                    if count > 1 {
+                        problems.push(LibraryProblem::DuplicateSymbol { name, count });
+                    }
+                }
+                problems.extend(missing);
+            }
+        }
+    }
+
+    let footprint_dir = project_root.join(config.footprint_lib());
+    if footprint_dir.is_dir() {
+        let mut paths: Vec<PathBuf> = fs::read_dir(&footprint_dir)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<_, _>>()?;
+        paths.sort();
+        let mut names: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for path in paths {
+            if path.extension().and_then(|ext| ext.to_str()) != Some("kicad_mod") {
+                continue;
+            }
+            let file_name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let file = MappedFile::open(&path)?;
+            let footprint = match parse_one(file.text()?) {
+                Ok(footprint) => footprint,
+                Err(err) => {
+                    problems.push(LibraryProblem::Unparsable {
+                        path: relative(&path),
+                        message: err.to_string(),
+                    });
+                    continue;
+                }
+            };
+            if let Some(name) = footprint_name(&footprint) {
+                names.entry(name).or_default().push(file_name.clone());
+            }
+            if let Some(model) = model_reference(&footprint)
+                && let Some(model_path) = local_model_path(project_root, model)
+                && !model_path.exists()
+            {
+                problems.push(LibraryProblem::MissingModel {
+                    footprint: file_name.clone(),
+                    model: model.to_string(),
+                });
+            }
+            problems.extend(unit_problems(&file_name, &footprint));
+            if config.pad_policy() != PadPolicy::Off {
+                problems.extend(check_pads(&footprint).into_iter().map(|problem| {
+                    LibraryProblem::Pad {
+                        footprint: file_name.clone(),
+                        problem,
+                    }
+                }));
+            }
+        }
+        for (name, files) in names {
+            if files.len() > 1 {
+                problems.push(LibraryProblem::DuplicateFootprint { name, files });
+            }
+        }
+    }
+    Ok(problems)
+}
+
+fn footprint_name(footprint: &Sexp) -> Option<String> {
+    match footprint {
+        Sexp::List(items) => match items.get(1) {
+            Some(Sexp::Atom(atom)) => Some(atom.value().to_string()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Grid {
+    Imperial,
+    Metric,
+}
+
+fn unit_problems(file_name: &str, footprint: &Sexp) -> Vec<LibraryProblem> {
+    let mut problems = Vec::new();
+    let pads = points(footprint, |head, _| head == "pad", &["at"]);
+    let courtyard = points(
+        footprint,
+        |head, layer| {
+            head.starts_with("fp_") && layer.is_some_and(|layer| layer.ends_with(".CrtYd"))
+        },
+        &["start", "mid", "end", "center", "xy"],
+    );
+    if grid(&pads) == Some(Grid::Imperial)
+        && grid(&courtyard) == Some(Grid::Metric)
+        && !covers(&courtyard, &pads)
+    {
+        problems.push(LibraryProblem::MixedUnits {
+            footprint: file_name.to_string(),
+        });
+    }
+    let mut pitches = BTreeSet::new();
+    for (axis, other) in [(0, 1), (1, 0)] {
+        let mut lines: BTreeMap<i64, Vec<f64>> = BTreeMap::new();
+        for pad in &pads {
+            lines
+                .entry((pad[other] * 1000.0).round() as i64)
+                .or_default()
+                .push(pad[axis]);
+        }
+        for mut line in lines.into_values() {
+            line.sort_by(f64::total_cmp);
+            line.dedup_by(|a, b| (*a - *b).abs() < GRID_TOLERANCE_MM);
+            if line.len() < 3 {
+                continue;
+            }
+            let pitch = line[1] - line[0];
+            if line
+                .windows(2)
+                .all(|pair| (pair[1] - pair[0] - pitch).abs() < PITCH_TOLERANCE_MM)
+                && !STANDARD_PITCHES_MM
+                    .iter()
+                    .any(|standard| (standard - pitch).abs() < PITCH_TOLERANCE_MM)
+            {
+                pitches.insert(format!("{:.3}", pitch));
+            }
+        }
+    }
+    for pitch in pitches {
+        problems.push(LibraryProblem::SuspiciousPitch {
+            footprint: file_name.to_string(),
+            pitch,
+        });
+    }
+    problems
+}
+
+fn points(
+    footprint: &Sexp,
+    select: impl Fn(&str, Option<&str>) -> bool,
+    keys: &[&str],
+) -> Vec<[f64; 2]> {
+    let Sexp::List(items) = footprint else {
+        return Vec::new();
+    };
+    let mut points = Vec::new();
+    for item in items {
+        let Sexp::List(fields) = item else {
+            continue;
+        };
+        let Some(Sexp::Atom(head)) = fields.first() else {
+            continue;
+        };
+        let layer = fields.iter().find_map(|field| match field {
+            Sexp::List(layer) if layer.first().and_then(atom) == Some("layer") => {
+                layer.get(1).and_then(atom)
+            }
+            _ => None,
+        });
+        if select(head.value(), layer) {
+            collect_points(fields, keys, &mut points);
+        }
+    }
+    points
+}
+
+fn collect_points(fields: &[Sexp], keys: &[&str], points: &mut Vec<[f64; 2]>) {
+    for field in fields {
+        let Sexp::List(field) = field else {
+            continue;
+        };
+        match field.first().and_then(atom) {
+            Some("pts") => collect_points(&field[1..], keys, points),
+            Some(key) if keys.contains(&key) => {
+                if let (Some(x), Some(y)) = (
+                    field.get(1).and_then(Sexp::as_f64),
+                    field.get(2).and_then(Sexp::as_f64),
+                ) {
+                    points.push([x, y]);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn atom(sexp: &Sexp) -> Option<&str> {
+    match sexp {
+        Sexp::Atom(atom) => Some(atom.value()),
+        _ => None,
+    }
+}
+
+fn grid(points: &[[f64; 2]]) -> Option<Grid> {
+    let on_grid =
+        |value: f64, step: f64| ((value / step).round() * step - value).abs() < GRID_TOLERANCE_MM;
+    let mut imperial = false;
+    let mut metric = false;
+    for value in points.iter().flatten().copied() {
+        if value.abs() < GRID_TOLERANCE_MM {
+            continue;
+        }
+        match (on_grid(value, MIL_MM), on_grid(value, METRIC_GRID_MM)) {
+            (true, false) => imperial = true,
+            (false, true) => metric = true,
+            (true, true) => {}
+            (false, false) => return None,
+        }
+    }
+    match (imperial, metric) {
+        (true, false) => Some(Grid::Imperial),
+        (false, true) => Some(Grid::Metric),
+        _ => None,
+    }
+}
+
+fn covers(outline: &[[f64; 2]], points: &[[f64; 2]]) -> bool {
+    let min = |axis: usize| {
+        outline
+            .iter()
+            .map(|point| point[axis])
+            .fold(f64::MAX, f64::min)
+    };
+    let max = |axis: usize| {
+        outline
+            .iter()
+            .map(|point| point[axis])
+            .fold(f64::MIN, f64::max)
+    };
+    points
+        .iter()
+        .all(|point| (0..2).all(|axis| point[axis] >= min(axis) && point[axis] <= max(axis)))
+}
+
+fn local_model_path(project_root: &Path, model: &str) -> Option<PathBuf> {
+    if let Some(relative) = model.strip_prefix("${KIPRJMOD}/") {
+        return Some(project_root.join(relative));
+    }
+    if model.contains('$') {
+        return None;
+    }
+    Some(project_root.join(model))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kicad_table::ensure_project_tables;
+    use tempfile::tempdir;
+
+    #[test]
+    fn reports_broken_references_duplicates_and_bad_files() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let config = ImportConfig::new(
+            PathBuf::from("lib.kicad_sym"),
+            PathBuf::from("lib.pretty"),
+            PathBuf::from("lib_3d"),
+        );
+        ensure_project_tables(root, &config).unwrap();
+        fs::write(
+            root.join("lib.kicad_sym"),
+            r#"(kicad_symbol_lib
+                (symbol "NE555" (property "Footprint" "lib:DIP-8"))
+                (symbol "LM358" (property "Footprint" "lib:SOIC-8"))
+                (symbol "LM358" (property "Footprint" "Package_SO:SOIC-8"))
+                (symbol "TL072" (property "Footprint" "")))"#,
+        )
+        .unwrap();
+        fs::create_dir_all(root.join("lib.pretty")).unwrap();
+        fs::create_dir_all(root.join("lib_3d")).unwrap();
+        fs::write(root.join("lib_3d/DIP-8.step"), "ISO-10303-21;").unwrap();
+        let footprints = [
+            (
+                "DIP-8",
+                r#"(footprint "DIP-8" (model "${KIPRJMOD}/lib_3d/DIP-8.step"))"#,
+            ),
+            (
+                "DIP-8_copy",
+                r#"(footprint "DIP-8" (model "${KIPRJMOD}/lib_3d/gone.step"))"#,
+            ),
+            ("broken", r#"(footprint "broken""#),
+        ];
+        for (file, content) in footprints {
+            fs::write(root.join(format!("lib.pretty/{}.kicad_mod", file)), content).unwrap();
+        }
+
+        let problems: Vec<String> = validate_libraries(root, &config)
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(problems.len(), 5, "{:?}", problems);
+        assert!(problems.contains(&"symbol LM358 is defined 2 times".to_string()));
+        assert!(problems.contains(&"LM358: footprint lib:SOIC-8 does not exist".to_string()));
+        assert!(
+            problems.contains(
+                &"DIP-8_copy.kicad_mod: 3D model ${KIPRJMOD}/lib_3d/gone.step does not exist"
+                    .to_string()
+            )
+        );
+        assert!(problems.contains(
+            &"footprint name DIP-8 is used by DIP-8.kicad_mod, DIP-8_copy.kicad_mod".to_string()
+        ));
+        assert!(problems.iter().any(|problem| {
+            problem.starts_with(&format!(
+                "{}: cannot be parsed",
+                Path::new("lib.pretty/broken.kicad_mod").display()
+            ))
+        }));
+
+        fs::write(root.join("fp-lib-table"), "(fp_lib_table (lib").unwrap();
+        let problems = validate_libraries(root, &config).unwrap();
+        assert!(problems.iter().any(|problem| matches!(
+            problem,
+            LibraryProblem::Unparsable { path, .. } if path == Path::new("fp-lib-table")
+        )));
+    }
+
+    #[test]
+    fn flags_mixed_units_and_odd_pitches() {
+        let dip = |courtyard: f64| {
+            parse_one(&format!(
+                "(footprint \"DIP-4\" \
+                 (pad \"1\" thru_hole circle (at 0 0)) (pad \"2\" thru_hole circle (at 0 2.54)) \
+                 (pad \"3\" thru_hole circle (at 7.62 2.54)) (pad \"4\" thru_hole circle (at 7.62 0)) \
+                 (fp_rect (start -1.1 -1.1) (end {} 3.65) (layer \"F.CrtYd\")))",
+                courtyard
+            ))
+            .unwrap()
+        };
+        assert!(unit_problems("DIP-4.kicad_mod", &dip(8.7)).is_empty());
+        assert_eq!(
+            unit_problems("DIP-4.kicad_mod", &dip(0.35)),
+            [LibraryProblem::MixedUnits {
+                footprint: "DIP-4.kicad_mod".to_string()
+            }]
+        );
+
+        let soic = parse_one(
+            "(footprint \"SOIC-3\" (pad \"1\" smd rect (at -2.5 -1.3)) \
+             (pad \"2\" smd rect (at -2.5 0)) (pad \"3\" smd rect (at -2.5 1.3)) \
+             (pad \"4\" smd rect (at 2.5 0)))",
+        )
+        .unwrap();
+        assert_eq!(
+            unit_problems("SOIC-3.kicad_mod", &soic)
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            ["SOIC-3.kicad_mod: pad pitch 1.300 mm doesn't match a standard package"]
+        );
+    }
+}
@@ -0,0 +1,345 @@
+use crate::kicad_sym::Symbol;
+use std::fmt;
+use std::str::FromStr;
+
+const PART_NUMBER_PROPERTIES: &[&str] = &[
+    "MPN",
+    "Manufacturer_Part_Number",
+    "Manufacturer Part Number",
+    "MFR_PN",
+    "Part Number",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValueDecoder {
+    MurataGrm,
+    SamsungCl,
+    YageoRc,
+    VishayCrcw,
+}
+
+impl ValueDecoder {
+    pub fn all() -> &'static [ValueDecoder] {
+        &[
+            ValueDecoder::MurataGrm,
+            ValueDecoder::SamsungCl,
+            ValueDecoder::YageoRc,
+            ValueDecoder::VishayCrcw,
+        ]
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ValueDecoder::MurataGrm => "murata-grm",
+            ValueDecoder::SamsungCl => "samsung-cl",
+            ValueDecoder::YageoRc => "yageo-rc",
+            ValueDecoder::VishayCrcw => "vishay-crcw",
+        }
+    }
+
+    pub fn decode(self, part_number: &str) -> Option<String> {
+        let pn = part_number.trim().to_ascii_uppercase();
+        match self {
+            ValueDecoder::MurataGrm => decode_murata_grm(&pn),
+            ValueDecoder::SamsungCl => decode_samsung_cl(&pn),
+            ValueDecoder::YageoRc => decode_yageo_rc(&pn),
+            ValueDecoder::VishayCrcw => decode_vishay_crcw(&pn),
+        }
+    }
+}
+
+impl fmt::Display for ValueDecoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for ValueDecoder {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        ValueDecoder::all()
+            .iter()
+            .copied()
+            .find(|decoder| decoder.name().eq_ignore_ascii_case(value.trim()))
+            .ok_or_else(|| format!("unknown value decoder: {}", value))
+    }
+}
+
+pub fn decode_part_number(part_number: &str, decoders: &[ValueDecoder]) -> Option<String> {
+    decoders
+        .iter()
+        .find_map(|decoder| decoder.decode(part_number))
+}
+
+pub fn apply_value_rules(symbol: &mut Symbol, decoders: &[ValueDecoder]) -> bool {
+    if decoders.is_empty() {
+        return false;
+    }
+    let current = symbol.property_value("Value").unwrap_or_default();
+    let part_numbers: Vec<String> = PART_NUMBER_PROPERTIES
+        .iter()
+        .filter_map(|name| symbol.property_value(name))
+        .collect();
+
+    let placeholder =
+        current.trim().is_empty() || current == symbol.name() || part_numbers.contains(&current);
+    if !placeholder && decode_part_number(&current, decoders).is_none() {
+        return false;
+    }
+
+    let mut candidates = part_numbers;
+    candidates.push(current);
+    candidates.push(symbol.name().to_string());
+    for candidate in candidates {
+        if candidate.trim().is_empty() {
+            continue;
+        }
+        if let Some(value) = decode_part_number(&candidate, decoders) {
+            if symbol.property_value("MPN").is_none() {
+                symbol.set_or_add_property("MPN", candidate.trim());
+            }
+            symbol.set_or_add_property("Value", &value);
+            return true;
+        }
+    }
+    false
+}
+
+fn decode_murata_grm(pn: &str) -> Option<String> {
+    let rest = pn.strip_prefix("GRM")?;
+    if rest.len() < 11 || !rest.is_ascii() {
+        return None;
+    }
+    let dielectric = match &rest[3..5] {
+        "5C" => "C0G",
+        "R6" => "X5R",
+        "R7" => "X7R",
+        "C6" => "X5S",
+        "C7" => "X7S",
+        "C8" => "X6S",
+        "D7" => "X7T",
+        "E7" => "X7U",
+        "L8" => "X8L",
+        "F5" => "Y5V",
+        _ => return None,
+    };
+    let voltage = murata_voltage(&rest[5..7])?;
+    let capacitance = capacitance_from_code(&rest[7..10])?;
+    Some(format!("{} {} {}", capacitance, voltage, dielectric))
+}
+
+fn murata_voltage(code: &str) -> Option<&'static str> {
+    Some(match code {
+        "0E" => "2.5V",
+        "0G" => "4V",
+        "0J" => "6.3V",
+        "1A" => "10V",
+        "1C" => "16V",
+        "1E" => "25V",
+        "YA" | "1V" => "35V",
+        "1H" => "50V",
+        "2A" => "100V",
+        "2D" => "200V",
+        "2E" => "250V",
+        "2W" => "450V",
+        "2J" => "630V",
+        _ => return None,
+    })
+}
+
+fn decode_samsung_cl(pn: &str) -> Option<String> {
+    let rest = pn.strip_prefix("CL")?;
+    if rest.len() < 8 || !rest.is_ascii() {
+        return None;
+    }
+    if !rest[..2].chars().all(|ch| ch.is_ascii_digit()) {
+        return None;
+    }
+    let dielectric = match &rest[2..3] {
+        "A" => "X5R",
+        "B" => "X7R",
+        "C" => "C0G",
+        "F" => "Y5V",
+        "X" => "X6S",
+        "Y" => "X7S",
+        "Z" => "X7T",
+        _ => return None,
+    };
+    let capacitance = capacitance_from_code(&rest[3..6])?;
+    let voltage = match &rest[7..8] {
+        "S" => "2.5V",
+        "R" => "4V",
+        "Q" => "6.3V",
+        "P" => "10V",
+        "O" => "16V",
+        "A" => "25V",
+        "L" => "35V",
+        "B" => "50V",
+        "C" => "100V",
+        "D" => "200V",
+        "E" => "250V",
+        "G" => "500V",
+        _ => return None,
+    };
+    Some(format!("{} {} {}", capacitance, voltage, dielectric))
+}
+
+fn decode_yageo_rc(pn: &str) -> Option<String> {
+    let rest = pn.strip_prefix("RC")?;
+    if rest.len() < 6 || !rest.is_ascii() || !rest[..4].chars().all(|ch| ch.is_ascii_digit()) {
+        return None;
+    }
+    let tolerance = resistor_tolerance(&rest[4..5])?;
+    let (_, code) = rest.split_once('-')?;
+    if code.len() < 3 {
+        return None;
+    }
+    let code = code[2..].strip_suffix('L').unwrap_or(&code[2..]);
+    let resistance = resistance_from_code(code)?;
+    Some(format!("{} {}", resistance, tolerance))
+}
+
+fn decode_vishay_crcw(pn: &str) -> Option<String> {
+    let rest = pn.strip_prefix("CRCW")?;
+    if rest.len() < 9 || !rest.is_ascii() || !rest[..4].chars().all(|ch| ch.is_ascii_digit()) {
+        return None;
+    }
+    let resistance = resistance_from_code(&rest[4..8])?;
+    let tolerance = resistor_tolerance(&rest[8..9])?;
+    Some(format!("{} {}", resistance, tolerance))
+}
+
+fn resistor_tolerance(code: &str) -> Option<&'static str> {
+    Some(match code {
+        "B" => "0.1%",
+        "C" => "0.25%",
+        "D" => "0.5%",
+        "F" => "1%",
+        "G" => "2%",
+        "J" => "5%",
+        "K" => "10%",
+        _ => return None,
+    })
+}
+
+fn capacitance_from_code(code: &str) -> Option<String> {
+    let picofarads = if let Some(idx) = code.find('R') {
+        let (whole, frac) = (&code[..idx], &code[idx + 1..]);
+        format!("{}.{}", whole, frac).parse::<f64>().ok()?
+    } else {
+        if code.len() != 3 || !code.chars().all(|ch| ch.is_ascii_digit()) {
+            return None;
+        }
+        let digits: f64 = code[..2].parse().ok()?;
+        let exponent: i32 = code[2..].parse().ok()?;
+        digits * 10f64.powi(exponent)
+    };
+    Some(format_with_prefix(picofarads * 1e-12, "F"))
+}
+
+fn resistance_from_code(code: &str) -> Option<String> {
+    if code.is_empty() {
+        return None;
+    }
+    let mut multiplier = None;
+    let mut digits = String::new();
+    for ch in code.chars() {
+        match ch {
+            '0'..='9' => digits.push(ch),
+            'R' | 'K' | 'M' | 'G' if multiplier.is_none() => {
+                multiplier = Some(match ch {
+                    'R' => 1.0,
+                    'K' => 1e3,
+                    'M' => 1e6,
+                    _ => 1e9,
+                });
+                digits.push('.');
+            }
+            _ => return None,
+        }
+    }
+    let multiplier = multiplier?;
+    let ohms = digits.trim_end_matches('.').parse::<f64>().ok()? * multiplier;
+    Some(format_with_prefix(ohms, ""))
+}
+
+fn format_with_prefix(value: f64, unit: &str) -> String {
+    const PREFIXES: &[(f64, &str)] = &[
+        (1e9, "G"),
+        (1e6, "M"),
+        (1e3, "k"),
+        (1.0, ""),
+        (1e-3, "m"),
+        (1e-6, "u"),
+        (1e-9, "n"),
+        (1e-12, "p"),
+    ];
+    if value == 0.0 {
+        return format!("0{}", unit);
+    }
+    for (scale, prefix) in PREFIXES {
+        if value >= scale * 0.999_999 {
+            let scaled = value / scale;
+            let rounded = (scaled * 1000.0).round() / 1000.0;
+            return format!("{}{}{}", rounded, prefix, unit);
+        }
+    }
+    let rounded = (value / 1e-12 * 1000.0).round() / 1000.0;
+    format!("{}p{}", rounded, unit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_murata_grm_capacitor() {
+        assert_eq!(
+            ValueDecoder::MurataGrm
+                .decode("GRM155R71C104KA88D")
+                .unwrap(),
+            "100nF 16V X7R"
+        );
+        assert_eq!(
+            ValueDecoder::MurataGrm
+                .decode("GRM1555C1H1R0CA01D")
+                .unwrap(),
+            "1pF 50V C0G"
+        );
+    }
+
+    #[test]
+    fn decodes_resistor_families() {
+        assert_eq!(
+            ValueDecoder::YageoRc.decode("RC0402FR-0710KL").unwrap(),
+            "10k 1%"
+        );
+        assert_eq!(
+            ValueDecoder::VishayCrcw.decode("CRCW04024K70JNED").unwrap(),
+            "4.7k 5%"
+        );
+        assert!(ValueDecoder::YageoRc.decode("GRM155R71C104KA88D").is_none());
+    }
+
+    #[test]
+    fn apply_replaces_part_number_value_and_keeps_mpn() {
+        let mut symbol = Symbol::parse(
+            "(symbol \"GRM155R71C104KA88D\" (property \"Value\" \"GRM155R71C104KA88D\"))",
+        )
+        .unwrap();
+        assert!(apply_value_rules(&mut symbol, ValueDecoder::all()));
+        assert_eq!(symbol.property_value("Value").unwrap(), "100nF 16V X7R");
+        assert_eq!(symbol.property_value("MPN").unwrap(), "GRM155R71C104KA88D");
+    }
+
+    #[test]
+    fn apply_keeps_hand_written_value() {
+        let mut symbol = Symbol::parse(
+            "(symbol \"C1\" (property \"Value\" \"decoupling\") (property \"MPN\" \"GRM155R71C104KA88D\"))",
+        )
+        .unwrap();
+        assert!(!apply_value_rules(&mut symbol, ValueDecoder::all()));
+        assert_eq!(symbol.property_value("Value").unwrap(), "decoupling");
+    }
+}
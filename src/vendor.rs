@@ -0,0 +1,87 @@
+use std::fmt;
+use std::path::Path;
+use walkdir::WalkDir;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Vendor {
+    SnapEda,
+    UltraLibrarian,
+    SamacSys,
+    KicadProject,
+    Unknown,
+}
+
+impl Vendor {
+    pub fn name(self) -> &'static str {
+        match self {
+            Vendor::SnapEda => "SnapEDA",
+            Vendor::UltraLibrarian => "Ultra Librarian",
+            Vendor::SamacSys => "SamacSys / Component Search Engine",
+            Vendor::KicadProject => "KiCad project",
+            Vendor::Unknown => "unknown",
+        }
+    }
+}
+
+impl fmt::Display for Vendor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+pub fn detect_vendor(root: &Path) -> Vendor {
+    let mut names = Vec::new();
+    for entry in WalkDir::new(root).min_depth(1).into_iter().flatten() {
+        if let Ok(relative) = entry.path().strip_prefix(root) {
+            names.push(relative.to_string_lossy().to_ascii_lowercase());
+        }
+    }
+    let any = |check: &dyn Fn(&str) -> bool| names.iter().any(|name| check(name));
+
+    if any(&|name| name.ends_with(".kicad_pro") || name.ends_with(".kicad_sch")) {
+        return Vendor::KicadProject;
+    }
+
+    if any(&|name| name.contains("snapeda") || name.ends_with("how-to-import.htm")) {
+        return Vendor::SnapEda;
+    }
+    if any(&|name| {
+        name.contains("ultralibrarian")
+            || name.contains("ultra_librarian")
+            || file_name(name).starts_with("kicadv")
+    }) {
+        return Vendor::UltraLibrarian;
+    }
+    if any(&|name| name.contains("samacsys") || name.ends_with(".epw"))
+        || any(&|name| file_name(name).starts_with("lib_") && !name.contains('.'))
+    {
+        return Vendor::SamacSys;
+    }
+    Vendor::Unknown
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit(['/', '\\']).next().unwrap_or(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn detects_vendor_from_marker_files() {
+        let dir = tempdir().unwrap();
+        assert_eq!(detect_vendor(dir.path()), Vendor::Unknown);
+
+        fs::create_dir_all(dir.path().join("LIB_NE555").join("KiCad")).unwrap();
+        assert_eq!(detect_vendor(dir.path()), Vendor::SamacSys);
+
+        fs::write(dir.path().join("how-to-import.htm"), "").unwrap();
+        assert_eq!(detect_vendor(dir.path()), Vendor::SnapEda);
+
+        fs::write(dir.path().join("board.kicad_sch"), "").unwrap();
+        assert_eq!(detect_vendor(dir.path()), Vendor::KicadProject);
+    }
+}
@@ -0,0 +1,176 @@
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant, SystemTime};
+
+pub const DEFAULT_SETTLE_TIME: Duration = Duration::from_secs(1);
+const ARCHIVE_SUFFIXES: &[&str] = &[".zip", ".7z", ".tar", ".tar.gz", ".tgz"];
+
+#[derive(Debug)]
+pub enum WatchError {
+    Io(io::Error),
+    Notify(notify::Error),
+    Closed,
+}
+
+impl fmt::Display for WatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WatchError::Io(err) => write!(f, "io error: {}", err),
+            WatchError::Notify(err) => write!(f, "watch error: {}", err),
+            WatchError::Closed => write!(f, "watch error: event channel closed"),
+        }
+    }
+}
+
+impl Error for WatchError {}
+
+impl From<io::Error> for WatchError {
+    fn from(value: io::Error) -> Self {
+        WatchError::Io(value)
+    }
+}
+
+impl From<notify::Error> for WatchError {
+    fn from(value: notify::Error) -> Self {
+        WatchError::Notify(value)
+    }
+}
+
+pub struct FolderWatch {
+    dir: PathBuf,
+    _watcher: RecommendedWatcher,
+    events: Receiver<notify::Result<Event>>,
+    pending: HashMap<PathBuf, (u64, Instant)>,
+    imported: HashMap<PathBuf, Option<SystemTime>>,
+    settle_time: Duration,
+}
+
+impl FolderWatch {
+    pub fn new(dir: &Path) -> Result<Self, WatchError> {
+        if !dir.is_dir() {
+            return Err(WatchError::Io(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} is not a directory", dir.display()),
+            )));
+        }
+        let (sender, events) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender)?;
+        watcher.watch(dir, RecursiveMode::NonRecursive)?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            _watcher: watcher,
+            events,
+            pending: HashMap::new(),
+            imported: HashMap::new(),
+            settle_time: DEFAULT_SETTLE_TIME,
+        })
+    }
+
+    pub fn with_settle_time(mut self, settle_time: Duration) -> Self {
+        self.settle_time = settle_time;
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn wait(&mut self, timeout: Duration) -> Result<Vec<PathBuf>, WatchError> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let event = match self.events.recv_timeout(remaining) {
+                Ok(event) => event?,
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return Err(WatchError::Closed),
+            };
+            if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                continue;
+            }
+            for path in event.paths {
+                if is_component_archive(&path) {
+                    let len = fs::metadata(&path).map(|meta| meta.len()).unwrap_or(0);
+                    self.pending.insert(path, (len, Instant::now()));
+                }
+            }
+        }
+        Ok(self.settled())
+    }
+
+    fn settled(&mut self) -> Vec<PathBuf> {
+        let mut ready = Vec::new();
+        let now = Instant::now();
+        self.pending.retain(|path, (len, changed)| {
+            let Ok(meta) = fs::metadata(path) else {
+                return false;
+            };
+            if meta.len() != *len {
+                *len = meta.len();
+                *changed = now;
+                return true;
+            }
+            if *len == 0 || now.duration_since(*changed) < self.settle_time {
+                return true;
+            }
+            let modified = meta.modified().ok();
+            if self.imported.get(path) != Some(&modified) {
+                self.imported.insert(path.clone(), modified);
+                ready.push(path.clone());
+            }
+            false
+        });
+        ready.sort();
+        ready
+    }
+}
+
+pub fn is_component_archive(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    let name = name.to_ascii_lowercase();
+    !name.starts_with('.') && ARCHIVE_SUFFIXES.iter().any(|suffix| name.ends_with(suffix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn recognises_component_archives() {
+        assert!(is_component_archive(Path::new("/dl/LM358.zip")));
+        assert!(is_component_archive(Path::new("ul_NE555.TAR.GZ")));
+        assert!(!is_component_archive(Path::new("LM358.zip.crdownload")));
+        assert!(!is_component_archive(Path::new(".~lock.zip")));
+        assert!(!is_component_archive(Path::new("notes.txt")));
+    }
+
+    #[test]
+    fn reports_new_archives_once_they_settle() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("old.zip"), b"PK").unwrap();
+        let mut watch = FolderWatch::new(dir.path())
+            .unwrap()
+            .with_settle_time(Duration::ZERO);
+
+        fs::write(dir.path().join("notes.txt"), b"hello").unwrap();
+        let partial = dir.path().join("LM358.zip.part");
+        fs::write(&partial, b"PK\x03\x04").unwrap();
+        fs::rename(&partial, dir.path().join("LM358.zip")).unwrap();
+
+        let start = Instant::now();
+        let mut found = Vec::new();
+        while found.is_empty() && start.elapsed() < Duration::from_secs(5) {
+            found = watch.wait(Duration::from_millis(100)).unwrap();
+        }
+        assert_eq!(found, [dir.path().join("LM358.zip")]);
+        assert!(watch.wait(Duration::from_millis(100)).unwrap().is_empty());
+    }
+}
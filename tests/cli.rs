@@ -1,5 +1,14 @@
 use clap::Parser;
-use kicad_component_importer::cli::{Cli, Command};
+use kicad_component_importer::cli::{
+    CacheAction, CacheArgs, Cli, Command, ConfigAction, FetchArgs, GenerateTarget, TableAction,
+    TableSelection, WatchArgs,
+};
+use std::io::{Cursor, Write};
+use std::path::PathBuf;
+use std::process::Stdio;
+use tempfile::tempdir;
+use zip::write::FileOptions;
+use zip::ZipWriter;
 
 #[test]
 fn parse_import_command() {
@@ -17,7 +26,8 @@ fn parse_import_command() {
     .unwrap();
     match cli.command {
         Command::Import(args) => {
-            assert_eq!(args.source.to_string_lossy(), "source.zip");
+            assert_eq!(args.sources.len(), 1);
+            assert_eq!(args.sources[0].to_string_lossy(), "source.zip");
             assert_eq!(
                 args.symbol_lib.unwrap().to_string_lossy(),
                 "sym.kicad_sym"
@@ -28,5 +38,718 @@ fn parse_import_command() {
             );
             assert_eq!(args.step_dir.unwrap().to_string_lossy(), "steps");
         }
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn import_reads_zip_from_stdin() {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    zip.start_file("lib.kicad_sym", FileOptions::default()).unwrap();
+    zip.write_all(
+        b"(kicad_symbol_lib (version 20231120) (symbol \"PartA\" (property \"Footprint\" \"Old:SOIC-8\")))",
+    )
+    .unwrap();
+    zip.start_file("SOIC-8.kicad_mod", FileOptions::default()).unwrap();
+    zip.write_all(b"(footprint \"SOIC-8\")").unwrap();
+    let archive = zip.finish().unwrap().into_inner();
+
+    let dir = tempdir().unwrap();
+    let mut child = std::process::Command::new(env!("CARGO_BIN_EXE_kicad-component-importer"))
+        .args([
+            "import",
+            "-",
+            "--symbol-lib",
+            "parts.kicad_sym",
+            "--footprint-lib",
+            "parts.pretty",
+        ])
+        .current_dir(dir.path())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(&archive).unwrap();
+    assert!(child.wait().unwrap().success());
+    let symbols = std::fs::read_to_string(dir.path().join("parts.kicad_sym")).unwrap();
+    assert!(symbols.contains("PartA"));
+    assert!(dir.path().join("parts.pretty/SOIC-8.kicad_mod").exists());
+
+    let cli = Cli::try_parse_from(["kci", "import", "-", "-"]).unwrap();
+    let Command::Import(args) = cli.command else {
+        panic!("expected import command");
+    };
+    assert!(
+        kicad_component_importer::cli::resolve_import(*args, dir.path())
+            .unwrap_err()
+            .to_string()
+            .contains("only be given once")
+    );
+}
+
+#[test]
+fn import_rebuilds_library_from_manifest() {
+    let dir = tempdir().unwrap();
+    let vendor = dir.path().join("vendor");
+    for (name, symbol, footprint) in [("timer", "NE555P", "DIP-8"), ("opamp", "LM358", "SOIC-8")] {
+        let source = vendor.join(name);
+        std::fs::create_dir_all(&source).unwrap();
+        std::fs::write(
+            source.join("lib.kicad_sym"),
+            format!(
+                "(kicad_symbol_lib (version 20231120) (symbol \"{}\" (property \"Footprint\" \"Old:{}\")))",
+                symbol, footprint
+            ),
+        )
+        .unwrap();
+        std::fs::write(
+            source.join(format!("{}.kicad_mod", footprint)),
+            format!("(footprint \"{}\")", footprint),
+        )
+        .unwrap();
+    }
+    std::fs::write(
+        vendor.join("parts.toml"),
+        "[[part]]\npath = \"timer\"\nrename = \"NE555\"\n\n[[part]]\npath = \"opamp\"\n",
+    )
+    .unwrap();
+
+    let status = std::process::Command::new(env!("CARGO_BIN_EXE_kicad-component-importer"))
+        .args([
+            "import",
+            "--manifest",
+            "vendor/parts.toml",
+            "--symbol-lib",
+            "parts.kicad_sym",
+            "--footprint-lib",
+            "parts.pretty",
+        ])
+        .current_dir(dir.path())
+        .stdout(Stdio::null())
+        .status()
+        .unwrap();
+    assert!(status.success());
+    let symbols = std::fs::read_to_string(dir.path().join("parts.kicad_sym")).unwrap();
+    assert!(symbols.contains("\"NE555\""));
+    assert!(!symbols.contains("NE555P"));
+    assert!(symbols.contains("\"LM358\""));
+    assert!(dir.path().join("parts.pretty/SOIC-8.kicad_mod").exists());
+
+    let cli = Cli::try_parse_from(["kci", "import", "--manifest", "parts.toml"]).unwrap();
+    match cli.command {
+        Command::Import(args) => assert_eq!(args.manifest, Some(PathBuf::from("parts.toml"))),
+        other => panic!("unexpected command: {:?}", other),
+    }
+    assert!(Cli::try_parse_from(["kci", "import", "a.zip", "--manifest", "parts.toml"]).is_err());
+}
+
+#[test]
+fn parse_generate_passive_command() {
+    let cli = Cli::try_parse_from(["kci", "generate", "passive", "R", "10k", "0402"]).unwrap();
+    match cli.command {
+        Command::Generate(args) => match args.target {
+            GenerateTarget::Passive(args) => {
+                assert_eq!(args.kind, "R");
+                assert_eq!(args.value, "10k");
+                assert_eq!(args.size, "0402");
+            }
+            other => panic!("unexpected target: {:?}", other),
+        },
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn parse_generate_connector_requires_pins_or_csv() {
+    assert!(Cli::try_parse_from(["kci", "generate", "connector"]).is_err());
+    let cli = Cli::try_parse_from([
+        "kci", "generate", "connector", "--pins", "6", "--pitch", "2.0", "--rows", "2",
+    ])
+    .unwrap();
+    match cli.command {
+        Command::Generate(args) => match args.target {
+            GenerateTarget::Connector(args) => {
+                assert_eq!(args.pins, Some(6));
+                assert_eq!(args.pitch, 2.0);
+                assert_eq!(args.rows, 2);
+            }
+            other => panic!("unexpected target: {:?}", other),
+        },
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn parse_table_remove_command() {
+    let cli =
+        Cli::try_parse_from(["kci", "table", "remove", "old_symbols", "--table", "symbol"]).unwrap();
+    match cli.command {
+        Command::Table(args) => match args.action {
+            TableAction::Remove(args) => {
+                assert_eq!(args.name, "old_symbols");
+                assert_eq!(args.table, TableSelection::Symbol);
+            }
+            other => panic!("unexpected action: {:?}", other),
+        },
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn parse_review_list_command() {
+    let cli = Cli::try_parse_from(["kci", "review", "--list"]).unwrap();
+    match cli.command {
+        Command::Review(args) => assert!(args.list),
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn parse_verify_archive_command() {
+    let cli = Cli::try_parse_from(["kci", "verify-archive", "vendor.zip"]).unwrap();
+    match cli.command {
+        Command::VerifyArchive(args) => assert_eq!(args.source.to_string_lossy(), "vendor.zip"),
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn parse_cache_clear_command() {
+    let cli = Cli::try_parse_from(["kci", "cache", "clear"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Command::Cache(CacheArgs {
+            action: CacheAction::Clear
+        })
+    ));
+}
+
+#[test]
+fn parse_shell_command() {
+    let cli = Cli::try_parse_from(["kci", "shell"]).unwrap();
+    assert!(matches!(cli.command, Command::Shell));
+}
+
+#[test]
+fn parse_lsp_command() {
+    let cli = Cli::try_parse_from(["kci", "lsp"]).unwrap();
+    assert!(matches!(cli.command, Command::Lsp));
+}
+
+#[test]
+fn parse_annotate_source_command() {
+    let cli =
+        Cli::try_parse_from(["kci", "annotate-source", "vendor.zip", "-o", "parts.md"]).unwrap();
+    match cli.command {
+        Command::AnnotateSource(args) => {
+            assert_eq!(args.source.to_string_lossy(), "vendor.zip");
+            assert_eq!(args.output.unwrap().to_string_lossy(), "parts.md");
+        }
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn parse_freeze_and_verify_commands() {
+    let cli = Cli::try_parse_from(["kci", "freeze"]).unwrap();
+    assert!(matches!(cli.command, Command::Freeze));
+    let cli = Cli::try_parse_from(["kci", "verify"]).unwrap();
+    assert!(matches!(cli.command, Command::Verify));
+}
+
+#[test]
+fn parse_import_dry_run_flag() {
+    let cli = Cli::try_parse_from(["kci", "import", "source.zip", "--dry-run"]).unwrap();
+    match cli.command {
+        Command::Import(args) => assert!(args.dry_run),
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn parse_import_emit_patch_option() {
+    let cli =
+        Cli::try_parse_from(["kci", "import", "source.zip", "--emit-patch", "out.patch"]).unwrap();
+    match cli.command {
+        Command::Import(args) => {
+            assert_eq!(args.emit_patch.unwrap().to_string_lossy(), "out.patch");
+            assert!(!args.dry_run);
+        }
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn parse_import_enrich_flag() {
+    let cli = Cli::try_parse_from(["kci", "import", "source.zip", "--enrich"]).unwrap();
+    match cli.command {
+        Command::Import(args) => assert!(args.enrich),
+        other => panic!("unexpected command: {:?}", other),
+    }
+    assert!(Cli::try_parse_from(["kci", "import", "source.zip", "--enrich", "--dry-run"]).is_err());
+}
+
+#[test]
+fn parse_import_project_option() {
+    let cli =
+        Cli::try_parse_from(["kci", "import", "source.zip", "--project", "hardware"]).unwrap();
+    match cli.command {
+        Command::Import(args) => assert_eq!(args.project, Some(PathBuf::from("hardware"))),
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn parse_import_kicad_project_option() {
+    let cli =
+        Cli::try_parse_from(["kci", "import", "source.zip", "--kicad-project", "rev_b"]).unwrap();
+    match cli.command {
+        Command::Import(args) => assert_eq!(args.kicad_project.as_deref(), Some("rev_b")),
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn parse_import_stats_flag() {
+    let cli = Cli::try_parse_from(["kci", "import", "source.zip", "--stats"]).unwrap();
+    match cli.command {
+        Command::Import(args) => assert!(args.stats),
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn parse_import_temp_dir_option() {
+    let cli =
+        Cli::try_parse_from(["kci", "import", "source.zip", "--temp-dir", "/scratch/kci"]).unwrap();
+    match cli.command {
+        Command::Import(args) => assert_eq!(args.temp_dir, Some(PathBuf::from("/scratch/kci"))),
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn parse_import_resume_without_sources() {
+    let cli = Cli::try_parse_from(["kci", "import", "--resume"]).unwrap();
+    match cli.command {
+        Command::Import(args) => {
+            assert!(args.resume);
+            assert!(args.sources.is_empty());
+        }
+        other => panic!("unexpected command: {:?}", other),
+    }
+    assert!(Cli::try_parse_from(["kci", "import", "source.zip", "--resume"]).is_err());
+}
+
+#[test]
+fn parse_fetch_models_command() {
+    let cli = Cli::try_parse_from(["kci", "fetch-models"]).unwrap();
+    assert!(matches!(cli.command, Command::FetchModels));
+}
+
+#[test]
+fn parse_import_lfs_option() {
+    let cli = Cli::try_parse_from(["kci", "import", "source.zip", "--lfs", "track"]).unwrap();
+    match cli.command {
+        Command::Import(args) => assert_eq!(args.lfs.as_deref(), Some("track")),
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn parse_import_size_budget_options() {
+    let cli = Cli::try_parse_from([
+        "kci",
+        "import",
+        "source.zip",
+        "--max-model-size",
+        "5",
+        "--oversize",
+        "skip",
+    ])
+    .unwrap();
+    match cli.command {
+        Command::Import(args) => {
+            assert_eq!(args.max_model_size, Some(5.0));
+            assert_eq!(args.oversize.as_deref(), Some("skip"));
+        }
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn parse_import_pad_checks() {
+    let cli = Cli::try_parse_from(["kci", "import", "source.zip", "--pad-checks", "fix"]).unwrap();
+    match cli.command {
+        Command::Import(args) => assert_eq!(args.pad_checks.as_deref(), Some("fix")),
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn parse_tag_and_list_commands() {
+    let cli = Cli::try_parse_from([
+        "kci",
+        "tag",
+        "NE555",
+        "status=approved",
+        "--remove",
+        "stage",
+    ])
+    .unwrap();
+    match cli.command {
+        Command::Tag(args) => {
+            assert_eq!(args.symbol, "NE555");
+            assert_eq!(args.tags, vec!["status=approved".to_string()]);
+            assert_eq!(args.remove, vec!["stage".to_string()]);
+        }
+        other => panic!("unexpected command: {:?}", other),
+    }
+
+    let cli = Cli::try_parse_from(["kci", "list", "--tag", "status=approved"]).unwrap();
+    match cli.command {
+        Command::List(args) => assert_eq!(args.tags, vec!["status=approved".to_string()]),
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn parse_approve_and_validate_commands() {
+    let cli = Cli::try_parse_from(["kci", "approve", "NE555", "LM358"]).unwrap();
+    match cli.command {
+        Command::Approve(args) => {
+            assert_eq!(args.symbols, vec!["NE555".to_string(), "LM358".to_string()]);
+            assert!(args.state.is_none());
+        }
+        other => panic!("unexpected command: {:?}", other),
+    }
+    assert!(Cli::try_parse_from(["kci", "approve"]).is_err());
+
+    let cli = Cli::try_parse_from(["kci", "validate"]).unwrap();
+    assert!(matches!(cli.command, Command::Validate));
+}
+
+#[test]
+fn parse_deprecate_command() {
+    let cli = Cli::try_parse_from([
+        "kci",
+        "deprecate",
+        "NE555",
+        "--replacement",
+        "TLC555",
+        "--substitute",
+    ])
+    .unwrap();
+    match cli.command {
+        Command::Deprecate(args) => {
+            assert_eq!(args.symbol, "NE555");
+            assert_eq!(args.replacement.as_deref(), Some("TLC555"));
+            assert!(args.substitute);
+        }
+        other => panic!("unexpected command: {:?}", other),
+    }
+    assert!(Cli::try_parse_from(["kci", "deprecate", "NE555", "--substitute"]).is_err());
+}
+
+#[test]
+fn parse_where_command() {
+    let cli = Cli::try_parse_from(["kci", "where", "soic8.step"]).unwrap();
+    match cli.command {
+        Command::Where(args) => assert_eq!(args.target, "soic8.step"),
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn parse_usages_and_index_commands() {
+    let cli = Cli::try_parse_from(["kci", "usages", "project_symbols:NE555"]).unwrap();
+    match cli.command {
+        Command::Usages(args) => assert_eq!(args.symbol, "project_symbols:NE555"),
+        other => panic!("unexpected command: {:?}", other),
+    }
+    let cli = Cli::try_parse_from(["kci", "index", "--rebuild"]).unwrap();
+    match cli.command {
+        Command::Index(args) => assert!(args.rebuild),
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn parse_import_from_kicad_plugin_flag() {
+    let cli = Cli::try_parse_from(["kci", "import", "archive.zip", "--from-kicad-plugin"]).unwrap();
+    match cli.command {
+        Command::Import(args) => assert!(args.from_kicad_plugin),
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn parse_import_symbols_only_flag() {
+    let cli = Cli::try_parse_from(["kci", "import", "archive.zip", "--symbols-only"]).unwrap();
+    match cli.command {
+        Command::Import(args) => {
+            assert!(args.symbols_only);
+            assert!(!args.allow_partial);
+        }
+        other => panic!("unexpected command: {:?}", other),
+    }
+    let cli =
+        Cli::try_parse_from(["kci", "import", "archive.zip", "--allow-missing-footprints"]).unwrap();
+    match cli.command {
+        Command::Import(args) => {
+            assert!(args.allow_partial);
+            assert!(!args.symbols_only);
+        }
+        other => panic!("unexpected command: {:?}", other),
+    }
+    assert!(Cli::try_parse_from([
+        "kci",
+        "import",
+        "archive.zip",
+        "--symbols-only",
+        "--footprints-only"
+    ])
+    .is_err());
+}
+
+#[test]
+fn parse_import_existing_option() {
+    let cli = Cli::try_parse_from(["kci", "import", "source.zip", "--existing", "relink"]).unwrap();
+    match cli.command {
+        Command::Import(args) => assert_eq!(args.existing.as_deref(), Some("relink")),
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn parse_import_lcsc_without_sources() {
+    let cli = Cli::try_parse_from(["kci", "import", "--lcsc", "C7593", "--lcsc", "c2040"]).unwrap();
+    match cli.command {
+        Command::Import(args) => {
+            assert!(args.sources.is_empty());
+            assert_eq!(args.lcsc, vec!["C7593".to_string(), "c2040".to_string()]);
+        }
+        other => panic!("unexpected command: {:?}", other),
+    }
+    assert!(Cli::try_parse_from(["kci", "import"]).is_err());
+}
+
+#[test]
+fn parse_fetch_digikey_command() {
+    let cli = Cli::try_parse_from([
+        "kci",
+        "fetch",
+        "--digikey",
+        "296-1411-5-ND",
+        "--digikey-client-id",
+        "id",
+        "--digikey-client-secret",
+        "secret",
+    ])
+    .unwrap();
+    match cli.command {
+        Command::Fetch(FetchArgs {
+            digikey,
+            digikey_client_id,
+            digikey_client_secret,
+            mouser,
+            ..
+        }) => {
+            assert_eq!(digikey, ["296-1411-5-ND"]);
+            assert!(mouser.is_empty());
+            assert_eq!(digikey_client_id.as_deref(), Some("id"));
+            assert_eq!(digikey_client_secret.as_deref(), Some("secret"));
+        }
+        other => panic!("unexpected command: {:?}", other),
+    }
+    assert!(Cli::try_parse_from(["kci", "fetch"]).is_err());
+    let missing_secret = ["kci", "fetch", "--digikey", "X", "--digikey-client-id", "id"];
+    assert!(Cli::try_parse_from(missing_secret).is_err());
+}
+
+#[test]
+fn parse_fetch_mouser_command() {
+    let cli = Cli::try_parse_from([
+        "kci",
+        "fetch",
+        "--mouser",
+        "595-NE555P",
+        "--mouser",
+        "512-LM358N",
+        "--mouser-api-key",
+        "key",
+    ])
+    .unwrap();
+    match cli.command {
+        Command::Fetch(FetchArgs {
+            digikey,
+            mouser,
+            mouser_api_key,
+            ..
+        }) => {
+            assert!(digikey.is_empty());
+            assert_eq!(mouser, ["595-NE555P", "512-LM358N"]);
+            assert_eq!(mouser_api_key.as_deref(), Some("key"));
+        }
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn parse_watch_command() {
+    let cli = Cli::try_parse_from(["kci", "watch", "/home/me/Downloads"]).unwrap();
+    match cli.command {
+        Command::Watch(WatchArgs { dir }) => assert_eq!(dir, PathBuf::from("/home/me/Downloads")),
+        other => panic!("unexpected command: {:?}", other),
+    }
+    assert!(Cli::try_parse_from(["kci", "watch"]).is_err());
+}
+
+#[test]
+fn parse_rename_project_command() {
+    let cli = Cli::try_parse_from(["kci", "rename-project", "board"]).unwrap();
+    match cli.command {
+        Command::RenameProject(args) => assert_eq!(args.name.as_deref(), Some("board")),
+        other => panic!("unexpected command: {:?}", other),
+    }
+    let cli = Cli::try_parse_from(["kci", "rename-project"]).unwrap();
+    assert!(matches!(cli.command, Command::RenameProject(args) if args.name.is_none()));
+}
+
+#[test]
+fn parse_consolidate_command() {
+    let cli = Cli::try_parse_from([
+        "kci",
+        "consolidate",
+        "vendor_a",
+        "vendor_b",
+        "--into",
+        "parts.pretty",
+    ])
+    .unwrap();
+    match cli.command {
+        Command::Consolidate(args) => {
+            assert_eq!(args.libs, ["vendor_a", "vendor_b"]);
+            assert_eq!(args.into, Some(PathBuf::from("parts.pretty")));
+        }
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn parse_remove_command() {
+    let cli = Cli::try_parse_from(["kci", "remove", "NE555", "--with-footprint"]).unwrap();
+    match cli.command {
+        Command::Remove(args) => {
+            assert_eq!(args.symbol, "NE555");
+            assert!(args.with_footprint);
+        }
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn parse_rename_command() {
+    let cli = Cli::try_parse_from(["kci", "rename", "NE555", "NE555P"]).unwrap();
+    match cli.command {
+        Command::Rename(args) => {
+            assert_eq!(args.old, "NE555");
+            assert_eq!(args.new, "NE555P");
+        }
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn parse_rename_footprint_command() {
+    let cli = Cli::try_parse_from(["kci", "rename-footprint", "DIP-8", "DIP-8_W7.62mm"]).unwrap();
+    match cli.command {
+        Command::RenameFootprint(args) => {
+            assert_eq!(args.old, "DIP-8");
+            assert_eq!(args.new, "DIP-8_W7.62mm");
+        }
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn parse_init_command() {
+    let cli = Cli::try_parse_from(["kci", "init", "--name", "acme"]).unwrap();
+    match cli.command {
+        Command::Init(args) => assert_eq!(args.name.as_deref(), Some("acme")),
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn parse_config_set_command() {
+    let cli = Cli::try_parse_from(["kci", "config", "set", "step_dir", "models3d"]).unwrap();
+    match cli.command {
+        Command::Config(args) => match args.action {
+            ConfigAction::Set(args) => {
+                assert_eq!(args.key, "step_dir");
+                assert_eq!(args.value, "models3d");
+            }
+            other => panic!("unexpected action: {:?}", other),
+        },
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn parse_search_command() {
+    let cli = Cli::try_parse_from(["kci", "search", "op-amp"]).unwrap();
+    match cli.command {
+        Command::Search(args) => assert_eq!(args.text, "op-amp"),
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn parse_export_command() {
+    let cli = Cli::try_parse_from(["kci", "export", "NE555", "-o", "part.zip"]).unwrap();
+    match cli.command {
+        Command::Export(args) => {
+            assert_eq!(args.symbol, "NE555");
+            assert_eq!(args.output, Some(PathBuf::from("part.zip")));
+        }
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn parse_diff_command() {
+    let cli = Cli::try_parse_from(["kci", "diff", "part.zip", "--existing", "merge"]).unwrap();
+    match cli.command {
+        Command::Diff(args) => {
+            assert_eq!(args.sources, [PathBuf::from("part.zip")]);
+            assert_eq!(args.existing.as_deref(), Some("merge"));
+        }
+        other => panic!("unexpected command: {:?}", other),
+    }
+    assert!(Cli::try_parse_from(["kci", "diff"]).is_err());
+}
+
+#[test]
+fn parse_merge_command() {
+    let cli = Cli::try_parse_from([
+        "kci",
+        "merge",
+        "other.kicad_sym",
+        "--existing",
+        "skip",
+        "--policy",
+        "NE555=replace",
+        "--policy",
+        "LM358=merge",
+    ])
+    .unwrap();
+    match cli.command {
+        Command::Merge(args) => {
+            assert_eq!(args.lib, PathBuf::from("other.kicad_sym"));
+            assert_eq!(args.existing.as_deref(), Some("skip"));
+            assert_eq!(args.policies, ["NE555=replace", "LM358=merge"]);
+        }
+        other => panic!("unexpected command: {:?}", other),
     }
 }
@@ -1,5 +1,15 @@
-use kicad_component_importer::importer::{import_source, ImportConfig, ImportError};
+use kicad_component_importer::importer::{
+    import_source, import_sources, inspect_source, AssociationRule, ImportConfig, ImportError,
+    ModelOverride, OversizePolicy, StepLayout, StepNaming, SymbolOverride,
+};
+use kicad_component_importer::journal::ImportJournal;
 use kicad_component_importer::kicad_sym::{AddPolicy, KicadSymbolLib};
+use kicad_component_importer::manifest::sha256_file;
+use kicad_component_importer::model_store::ModelStore;
+use kicad_component_importer::pad_checks::PadPolicy;
+use kicad_component_importer::simplify::SimplifyCommand;
+use kicad_component_importer::staging::Staging;
+use kicad_component_importer::value_rules::ValueDecoder;
 use std::fs;
 use std::io::Write;
 use std::path::Path;
@@ -82,6 +92,143 @@ fn import_zip_updates_library_prefix() {
     assert_eq!(footprint_value, "Dest:MyFootprint");
 }
 
+#[test]
+fn import_extracts_tar_archives() {
+    let temp = tempdir().unwrap();
+    let files: [(&str, &[u8]); 2] = [
+        (
+            "Symbols/lib.kicad_sym",
+            b"(kicad_symbol_lib (version 20231120) (symbol \"PartA\" (property \"Footprint\" \"Old:MyFootprint\")))",
+        ),
+        (
+            "Footprints.pretty/MyFootprint.kicad_mod",
+            b"(footprint \"MyFootprint\")",
+        ),
+    ];
+    let tar_path = temp.path().join("source.tar");
+    let mut tar = tar::Builder::new(fs::File::create(&tar_path).unwrap());
+    for (name, content) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        tar.append_data(&mut header, name, content).unwrap();
+    }
+    tar.finish().unwrap();
+    drop(tar);
+    let tgz_path = temp.path().join("source.tgz");
+    let mut encoder = flate2::write::GzEncoder::new(
+        fs::File::create(&tgz_path).unwrap(),
+        flate2::Compression::default(),
+    );
+    encoder.write_all(&fs::read(&tar_path).unwrap()).unwrap();
+    encoder.finish().unwrap();
+
+    for (idx, source) in [tar_path, tgz_path].iter().enumerate() {
+        let dest_sym = temp.path().join(format!("dest{}.kicad_sym", idx));
+        let config = ImportConfig::new(
+            dest_sym.clone(),
+            temp.path().join("Dest.pretty"),
+            temp.path().join("steps"),
+        );
+        let report = import_source(source, &config, AddPolicy::ReplaceExisting).unwrap();
+        assert_eq!(report.symbols_added(), 1);
+        assert_eq!(read_symbol_footprint(&dest_sym), "Dest:MyFootprint");
+    }
+}
+
+#[test]
+fn import_reads_standalone_symbol_library() {
+    let temp = tempdir().unwrap();
+    let source = temp.path().join("colleague.kicad_sym");
+    fs::write(
+        &source,
+        "(kicad_symbol_lib (version 20231120) (symbol \"PartA\" (property \"Value\" \"PartA\")) (symbol \"PartB\" (property \"Value\" \"PartB\")))",
+    )
+    .unwrap();
+    let dest_sym = temp.path().join("dest.kicad_sym");
+    let config = ImportConfig::new(
+        dest_sym.clone(),
+        temp.path().join("Dest.pretty"),
+        temp.path().join("steps"),
+    );
+
+    let report = import_source(&source, &config, AddPolicy::ReplaceExisting).unwrap();
+    assert_eq!(report.symbols_added(), 2);
+    let lib = KicadSymbolLib::parse(&fs::read_to_string(&dest_sym).unwrap()).unwrap();
+    assert!(lib.symbol("PartA").is_some());
+    assert!(lib.symbol("PartB").is_some());
+    assert!(source.exists());
+}
+
+#[test]
+fn import_keeps_hand_edited_properties_on_replace() {
+    let temp = tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(&source).unwrap();
+    write_symbol_lib(&source.join("lib.kicad_sym"), "PartA", "Old:MyFootprint");
+    write_footprint(&source.join("MyFootprint.kicad_mod"), "MyFootprint");
+
+    for keep in [true, false] {
+        let dest_sym = temp.path().join(format!("dest_{}.kicad_sym", keep));
+        fs::write(
+            &dest_sym,
+            "(kicad_symbol_lib (version 20231120) (symbol \"PartA\" (property \"Footprint\" \"Dest:Other\") (property \"Tolerance\" \"1%\") (property \"Notes\" \"checked\")))",
+        )
+        .unwrap();
+        let config = ImportConfig::new(
+            dest_sym.clone(),
+            temp.path().join("Dest.pretty"),
+            temp.path().join("steps"),
+        )
+        .with_keep_local_properties(keep);
+        let report = import_source(&source, &config, AddPolicy::ReplaceExisting).unwrap();
+
+        let lib = KicadSymbolLib::parse(&fs::read_to_string(&dest_sym).unwrap()).unwrap();
+        let symbol = lib.symbol("PartA").unwrap();
+        assert_eq!(symbol.property_value("Footprint").unwrap(), "Dest:MyFootprint");
+        if keep {
+            assert_eq!(symbol.property_value("Tolerance").unwrap(), "1%");
+            assert_eq!(symbol.property_value("Notes").unwrap(), "checked");
+            assert_eq!(
+                report.kept_properties(),
+                [("PartA".to_string(), vec!["Tolerance".to_string(), "Notes".to_string()])]
+            );
+        } else {
+            assert!(symbol.property_value("Tolerance").is_none());
+            assert!(report.kept_properties().is_empty());
+        }
+    }
+}
+
+#[test]
+fn import_extracts_7z_archives() {
+    let temp = tempdir().unwrap();
+    let staging = temp.path().join("staging");
+    fs::create_dir_all(staging.join("Symbols")).unwrap();
+    write_symbol_lib(
+        &staging.join("Symbols").join("lib.kicad_sym"),
+        "PartA",
+        "Old:MyFootprint",
+    );
+    write_footprint(
+        &staging.join("Footprints.pretty").join("MyFootprint.kicad_mod"),
+        "MyFootprint",
+    );
+    let archive = temp.path().join("source.7z");
+    sevenz_rust::compress_to_path(&staging, &archive).unwrap();
+
+    let dest_sym = temp.path().join("dest.kicad_sym");
+    let config = ImportConfig::new(
+        dest_sym.clone(),
+        temp.path().join("Dest.pretty"),
+        temp.path().join("steps"),
+    );
+    let report = import_source(&archive, &config, AddPolicy::ReplaceExisting).unwrap();
+    assert_eq!(report.symbols_added(), 1);
+    assert_eq!(read_symbol_footprint(&dest_sym), "Dest:MyFootprint");
+}
+
 #[test]
 fn import_errors_on_ambiguous_footprints() {
     let temp = tempdir().unwrap();
@@ -105,3 +252,1504 @@ fn import_errors_on_ambiguous_footprints() {
         other => panic!("unexpected error: {:?}", other),
     }
 }
+
+#[test]
+fn import_derives_value_from_part_number() {
+    let temp = tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(&source).unwrap();
+    fs::write(
+        source.join("lib.kicad_sym"),
+        "(kicad_symbol_lib (version 20231120) (symbol \"GRM155R71C104KA88D\" (property \"Value\" \"GRM155R71C104KA88D\") (property \"Footprint\" \"\")))",
+    )
+    .unwrap();
+    write_footprint(
+        &source.join("Footprints.pretty").join("C_0402.kicad_mod"),
+        "C_0402",
+    );
+
+    let dest_sym = temp.path().join("dest.kicad_sym");
+    let config = ImportConfig::new(
+        dest_sym.clone(),
+        temp.path().join("Dest.pretty"),
+        temp.path().join("steps"),
+    )
+    .with_value_decoders(ValueDecoder::all().to_vec());
+
+    let report = import_source(&source, &config, AddPolicy::ReplaceExisting).unwrap();
+    assert_eq!(report.values_derived(), 1);
+    let lib = KicadSymbolLib::parse(&fs::read_to_string(&dest_sym).unwrap()).unwrap();
+    let symbol = lib.symbols().unwrap().remove(0);
+    assert_eq!(symbol.property_value("Value").unwrap(), "100nF 16V X7R");
+    assert_eq!(symbol.property_value("MPN").unwrap(), "GRM155R71C104KA88D");
+}
+
+#[test]
+fn import_symbols_only_requires_allow_partial() {
+    let temp = tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(&source).unwrap();
+    write_symbol_lib(&source.join("lib.kicad_sym"), "PartA", "Vendor:PartA_FP");
+
+    let dest_sym = temp.path().join("dest.kicad_sym");
+    let dest_fp = temp.path().join("Dest.pretty");
+    let config = ImportConfig::new(dest_sym.clone(), dest_fp.clone(), temp.path().join("steps"));
+
+    let err = import_source(&source, &config, AddPolicy::ReplaceExisting).unwrap_err();
+    assert!(matches!(err, ImportError::MissingFootprints));
+
+    let config = config.with_allow_partial(true);
+    let report = import_source(&source, &config, AddPolicy::ReplaceExisting).unwrap();
+    assert_eq!(report.symbols_added(), 1);
+    assert_eq!(report.footprints_added(), 0);
+    assert_eq!(report.warnings().len(), 1);
+    assert_eq!(read_symbol_footprint(&dest_sym), "Vendor:PartA_FP");
+    assert!(!dest_fp.exists());
+}
+
+#[test]
+fn import_allow_partial_keeps_footprints_that_exist() {
+    let temp = tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(&source).unwrap();
+    write_symbol_lib(&source.join("lib.kicad_sym"), "PartA", "Vendor:PartA_FP");
+
+    let dest_sym = temp.path().join("dest.kicad_sym");
+    let dest_fp = temp.path().join("Dest.pretty");
+    let dest_steps = temp.path().join("steps");
+    let config = ImportConfig::new(dest_sym.clone(), dest_fp.clone(), dest_steps.clone())
+        .with_allow_partial(true);
+    let report = import_source(&source, &config, AddPolicy::ReplaceExisting).unwrap();
+    assert_eq!(report.symbols_added(), 1);
+    assert_eq!(report.footprints_added(), 0);
+    assert_eq!(report.warnings().len(), 1);
+    assert!(!dest_fp.exists());
+
+    write_footprint(&source.join("PartA_FP.kicad_mod"), "PartA_FP");
+    fs::write(source.join("PartA_FP.step"), "ISO-10303-21;").unwrap();
+    let report = import_source(&source, &config, AddPolicy::ReplaceExisting).unwrap();
+    assert_eq!(report.symbols_added(), 1);
+    assert_eq!(report.footprints_added(), 1);
+    assert_eq!(report.step_files_added(), 1);
+    assert!(report.warnings().is_empty());
+    assert!(dest_fp.join("PartA_FP.kicad_mod").exists());
+    assert!(dest_steps.join("PartA_FP.step").exists());
+}
+
+#[test]
+fn import_models_only_source_with_allow_partial() {
+    let temp = tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(&source).unwrap();
+    fs::write(source.join("PartA.step"), "ISO-10303-21;").unwrap();
+
+    let dest_sym = temp.path().join("dest.kicad_sym");
+    let dest_fp = temp.path().join("Dest.pretty");
+    let dest_steps = temp.path().join("steps");
+    let config = ImportConfig::new(dest_sym.clone(), dest_fp.clone(), dest_steps.clone());
+
+    let err = import_source(&source, &config, AddPolicy::ReplaceExisting).unwrap_err();
+    assert!(matches!(err, ImportError::MissingSymbols));
+
+    for config in [
+        config.clone().with_allow_partial(true),
+        config.clone().with_footprints_only(true),
+    ] {
+        let report = import_source(&source, &config, AddPolicy::ReplaceExisting).unwrap();
+        assert_eq!(report.symbols_added(), 0);
+        assert_eq!(report.footprints_added(), 0);
+        assert_eq!(report.step_files_added(), 1);
+        assert_eq!(report.warnings().len(), 1);
+        assert!(dest_steps.join("PartA.step").exists());
+        assert!(!dest_sym.exists());
+        assert!(!dest_fp.exists());
+        fs::remove_dir_all(&dest_steps).unwrap();
+    }
+}
+
+#[test]
+fn import_footprints_only_skips_symbol_stage() {
+    let temp = tempdir().unwrap();
+    let source = temp.path().join("source");
+    write_footprint(&source.join("Fp.pretty").join("MyFootprint.kicad_mod"), "MyFootprint");
+    fs::write(source.join("MyFootprint.step"), "ISO-10303-21;").unwrap();
+
+    let dest_sym = temp.path().join("dest.kicad_sym");
+    let dest_fp = temp.path().join("Dest.pretty");
+    let dest_steps = temp.path().join("steps");
+    let config = ImportConfig::new(dest_sym.clone(), dest_fp.clone(), dest_steps.clone());
+
+    let err = import_source(&source, &config, AddPolicy::ReplaceExisting).unwrap_err();
+    assert!(matches!(err, ImportError::MissingSymbols));
+
+    let config = config.with_allow_partial(true);
+    let report = import_source(&source, &config, AddPolicy::ReplaceExisting).unwrap();
+    assert_eq!(report.symbols_added(), 0);
+    assert_eq!(report.footprints_added(), 1);
+    assert_eq!(report.step_files_added(), 1);
+    assert_eq!(report.warnings().len(), 1);
+    assert!(dest_fp.join("MyFootprint.kicad_mod").exists());
+    assert!(dest_steps.join("MyFootprint.step").exists());
+    assert!(!dest_sym.exists());
+}
+
+#[test]
+fn import_symbols_only_mode_ignores_footprints() {
+    let temp = tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(&source).unwrap();
+    write_symbol_lib(&source.join("lib.kicad_sym"), "Conn_01x04", "Later:Conn");
+
+    let dest_sym = temp.path().join("dest.kicad_sym");
+    let dest_fp = temp.path().join("Dest.pretty");
+    let dest_steps = temp.path().join("steps");
+    let config = ImportConfig::new(dest_sym.clone(), dest_fp.clone(), dest_steps.clone())
+        .with_symbols_only(true);
+    let report = import_source(&source, &config, AddPolicy::ReplaceExisting).unwrap();
+    assert_eq!(report.symbols_added(), 1);
+    assert!(report.warnings().is_empty());
+    assert_eq!(read_symbol_footprint(&dest_sym), "Later:Conn");
+
+    write_footprint(&source.join("Conn.kicad_mod"), "Conn");
+    fs::write(source.join("Conn.step"), "ISO-10303-21;").unwrap();
+    let report = import_source(&source, &config, AddPolicy::ReplaceExisting).unwrap();
+    assert_eq!(report.footprints_added(), 0);
+    assert_eq!(report.step_files_added(), 0);
+    assert_eq!(read_symbol_footprint(&dest_sym), "Later:Conn");
+    assert!(!dest_fp.exists());
+    assert!(!dest_steps.exists());
+}
+
+#[test]
+fn import_footprints_only_mode_ignores_symbols() {
+    let temp = tempdir().unwrap();
+    let source = temp.path().join("source");
+    write_footprint(&source.join("MyFootprint.kicad_mod"), "MyFootprint");
+
+    let dest_sym = temp.path().join("dest.kicad_sym");
+    let dest_fp = temp.path().join("Dest.pretty");
+    let config = ImportConfig::new(dest_sym.clone(), dest_fp.clone(), temp.path().join("steps"))
+        .with_footprints_only(true);
+    let report = import_source(&source, &config, AddPolicy::ReplaceExisting).unwrap();
+    assert_eq!(report.footprints_added(), 1);
+    assert!(report.warnings().is_empty());
+    assert!(dest_fp.join("MyFootprint.kicad_mod").exists());
+
+    write_symbol_lib(&source.join("lib.kicad_sym"), "PartA", "Vendor:MyFootprint");
+    let report = import_source(&source, &config, AddPolicy::ReplaceExisting).unwrap();
+    assert_eq!(report.symbols_added(), 0);
+    assert!(!dest_sym.exists());
+
+    let empty = temp.path().join("empty");
+    fs::create_dir_all(&empty).unwrap();
+    let err = import_source(&empty, &config, AddPolicy::ReplaceExisting).unwrap_err();
+    assert!(matches!(err, ImportError::MissingFootprints));
+}
+
+#[test]
+fn import_from_kicad_plugin_maps_archived_project_libraries() {
+    let temp = tempdir().unwrap();
+    let project = temp.path().join("archive").join("Board");
+    fs::create_dir_all(project.join("lib")).unwrap();
+    fs::create_dir_all(project.join("old")).unwrap();
+    fs::write(project.join("Board.kicad_pro"), "{}").unwrap();
+    fs::write(
+        project.join("sym-lib-table"),
+        "(sym_lib_table (version 7) \
+         (lib (name \"Board\") (type \"KiCad\") (uri \"${KIPRJMOD}/lib/Board.kicad_sym\") (options \"\") (descr \"\")) \
+         (lib (name \"Device\") (type \"KiCad\") (uri \"${KICAD8_SYMBOL_DIR}/Device.kicad_sym\") (options \"\") (descr \"\")))",
+    )
+    .unwrap();
+    fs::write(
+        project.join("fp-lib-table"),
+        "(fp_lib_table (version 7) (lib (name \"BoardFp\") (type \"KiCad\") (uri \"${KIPRJMOD}/lib/BoardFp.pretty\") (options \"\") (descr \"\")))",
+    )
+    .unwrap();
+    fs::write(
+        project.join("lib").join("Board.kicad_sym"),
+        "(kicad_symbol_lib (version 20231120) \
+         (symbol \"PartA\" (property \"Footprint\" \"BoardFp:PartA_FP\")) \
+         (symbol \"R_10k\" (property \"Footprint\" \"Resistor_SMD:R_0402_1005Metric\")))",
+    )
+    .unwrap();
+    write_footprint(
+        &project.join("lib").join("BoardFp.pretty").join("PartA_FP.kicad_mod"),
+        "PartA_FP",
+    );
+    write_symbol_lib(&project.join("old").join("stale.kicad_sym"), "Stale", "");
+
+    let dest_sym = temp.path().join("dest.kicad_sym");
+    let config = ImportConfig::new(
+        dest_sym.clone(),
+        temp.path().join("Dest.pretty"),
+        temp.path().join("steps"),
+    )
+    .with_from_kicad_plugin(true);
+    let report = import_source(&temp.path().join("archive"), &config, AddPolicy::ReplaceExisting)
+        .unwrap();
+    assert_eq!(report.symbols_added(), 2);
+    assert_eq!(report.footprints_added(), 1);
+    let associations = report.associations();
+    assert_eq!(associations[0].footprint(), Some("Dest:PartA_FP"));
+    assert_eq!(associations[0].rule(), Some(AssociationRule::ExplicitProperty));
+    assert_eq!(associations[1].footprint(), Some("Resistor_SMD:R_0402_1005Metric"));
+    assert_eq!(associations[1].rule(), Some(AssociationRule::ExternalLibrary));
+
+    let loose = temp.path().join("loose");
+    fs::create_dir_all(&loose).unwrap();
+    write_symbol_lib(&loose.join("lib.kicad_sym"), "PartA", "");
+    let err = import_source(&loose, &config, AddPolicy::ReplaceExisting).unwrap_err();
+    assert!(matches!(err, ImportError::InvalidSource(_)));
+}
+
+#[test]
+fn import_detects_zipped_kicad_project() {
+    let temp = tempdir().unwrap();
+    let zip_path = temp.path().join("other_board.zip");
+    let file = fs::File::create(&zip_path).unwrap();
+    let mut zip = ZipWriter::new(file);
+    let options = FileOptions::default();
+    let entries: [(&str, &str); 5] = [
+        ("other_board/other_board.kicad_sch", "(kicad_sch (version 20231120))"),
+        (
+            "other_board/sym-lib-table",
+            "(sym_lib_table (version 7) (lib (name \"Board\") (type \"KiCad\") (uri \"${KIPRJMOD}/Board.kicad_sym\") (options \"\") (descr \"\")))",
+        ),
+        (
+            "other_board/Board.kicad_sym",
+            "(kicad_symbol_lib (version 20231120) (symbol \"PartA\" (property \"Footprint\" \"Board:PartA_FP\")))",
+        ),
+        (
+            "other_board/scratch.kicad_sym",
+            "(kicad_symbol_lib (version 20231120) (symbol \"Scratch\"))",
+        ),
+        ("other_board/Board.pretty/PartA_FP.kicad_mod", "(footprint \"PartA_FP\")"),
+    ];
+    for (name, content) in entries {
+        zip.start_file(name, options).unwrap();
+        zip.write_all(content.as_bytes()).unwrap();
+    }
+    zip.finish().unwrap();
+
+    let inspection = inspect_source(&zip_path).unwrap();
+    assert_eq!(inspection.vendor().name(), "KiCad project");
+    assert_eq!(inspection.symbols(), ["PartA".to_string()]);
+    assert!(inspection.footprints().is_empty());
+
+    let config = ImportConfig::new(
+        temp.path().join("dest.kicad_sym"),
+        temp.path().join("Dest.pretty"),
+        temp.path().join("steps"),
+    )
+    .with_allow_partial(true);
+    let report = import_source(&zip_path, &config, AddPolicy::ReplaceExisting).unwrap();
+    assert_eq!(report.symbols_added(), 1);
+    assert_eq!(report.footprints_added(), 0);
+    assert!(report.warnings()[0].contains("is a KiCad project"));
+}
+
+#[test]
+fn import_picks_files_from_snapeda_layout() {
+    let temp = tempdir().unwrap();
+    let zip_path = temp.path().join("snapeda_NE555DR.zip");
+    let file = fs::File::create(&zip_path).unwrap();
+    let mut zip = ZipWriter::new(file);
+    let options = FileOptions::default();
+    let entries: [(&str, &[u8]); 6] = [
+        ("NE555DR/how-to-import.htm", b"<html></html>"),
+        (
+            "NE555DR/KiCad/NE555DR.kicad_sym",
+            b"(kicad_symbol_lib (version 20231120) (symbol \"NE555DR\" (property \"Footprint\" \"SOIC127P600X175-8N\")))",
+        ),
+        (
+            "NE555DR/KiCad/SOIC127P600X175-8N.KICAD_MOD",
+            b"(footprint \"SOIC127P600X175-8N\")",
+        ),
+        (
+            "NE555DR/kicad_v5/SOIC127P600X175-8N.kicad_mod",
+            b"(module \"SOIC127P600X175-8N\")",
+        ),
+        ("NE555DR/3D/NE555DR.STEP", b"ISO-10303-21;"),
+        ("__MACOSX/NE555DR/KiCad/._NE555DR.kicad_sym", &[0, 5, 22, 7, 0]),
+    ];
+    for (name, content) in entries {
+        zip.start_file(name, options).unwrap();
+        zip.write_all(content).unwrap();
+    }
+    zip.finish().unwrap();
+
+    let inspection = inspect_source(&zip_path).unwrap();
+    assert_eq!(inspection.vendor().name(), "SnapEDA");
+    assert_eq!(inspection.symbol_files().len(), 1);
+    assert_eq!(inspection.footprint_files().len(), 1);
+
+    let dest_fp = temp.path().join("Dest.pretty");
+    let dest_steps = temp.path().join("steps");
+    let config = ImportConfig::new(
+        temp.path().join("dest.kicad_sym"),
+        dest_fp.clone(),
+        dest_steps.clone(),
+    );
+    let report = import_source(&zip_path, &config, AddPolicy::ReplaceExisting).unwrap();
+    assert_eq!(report.symbols_added(), 1);
+    assert_eq!(report.footprints_added(), 1);
+    assert_eq!(report.models_associated(), 1);
+    assert_eq!(
+        report.associations()[0].footprint(),
+        Some("Dest:SOIC127P600X175-8N")
+    );
+    assert!(
+        fs::read_to_string(dest_fp.join("SOIC127P600X175-8N.kicad_mod"))
+            .unwrap()
+            .starts_with("(footprint")
+    );
+    assert!(dest_steps.join("NE555DR.STEP").exists());
+}
+
+#[test]
+fn import_maps_samacsys_symbols_by_lib_folder() {
+    let temp = tempdir().unwrap();
+    let zip_path = temp.path().join("samacsys_parts.zip");
+    let file = fs::File::create(&zip_path).unwrap();
+    let mut zip = ZipWriter::new(file);
+    let options = FileOptions::default();
+    let entries: [(&str, &[u8]); 3] = [
+        (
+            "LIB_NE555/KiCad/SamacSys_Parts.kicad_sym",
+            b"(kicad_symbol_lib (version 20231120) (symbol \"NE555\" (property \"Footprint\" \"\")) (symbol \"LM358\" (property \"Footprint\" \"\")))",
+        ),
+        ("LIB_NE555/KiCad/SOIC127P600X175-8N.kicad_mod", b"(footprint \"SOIC127P600X175-8N\")"),
+        ("LIB_LM358/KiCad/SOP65P640X120-8N.kicad_mod", b"(footprint \"SOP65P640X120-8N\")"),
+    ];
+    for (name, content) in entries {
+        zip.start_file(name, options).unwrap();
+        zip.write_all(content).unwrap();
+    }
+    zip.finish().unwrap();
+
+    let inspection = inspect_source(&zip_path).unwrap();
+    assert_eq!(inspection.vendor().name(), "SamacSys / Component Search Engine");
+    assert_eq!(inspection.problems(), ["no 3D models found".to_string()]);
+
+    let config = ImportConfig::new(
+        temp.path().join("dest.kicad_sym"),
+        temp.path().join("Dest.pretty"),
+        temp.path().join("steps"),
+    );
+    let report = import_source(&zip_path, &config, AddPolicy::ReplaceExisting).unwrap();
+    assert_eq!(report.symbols_added(), 2);
+    assert_eq!(report.footprints_added(), 2);
+    let footprint_of = |symbol: &str| {
+        report
+            .associations()
+            .iter()
+            .find(|association| association.symbol() == symbol)
+            .and_then(|association| association.footprint())
+            .map(str::to_string)
+    };
+    assert_eq!(footprint_of("NE555").as_deref(), Some("Dest:SOIC127P600X175-8N"));
+    assert_eq!(footprint_of("LM358").as_deref(), Some("Dest:SOP65P640X120-8N"));
+}
+
+#[test]
+fn import_converts_easyeda_component_json() {
+    let temp = tempdir().unwrap();
+    let json_path = temp.path().join("C7593.json");
+    fs::write(
+        &json_path,
+        r#"{"success": true, "result": {
+            "title": "NE555DR",
+            "dataStr": {
+                "head": {"x": "400", "y": "300", "c_para": {"pre": "U?", "name": "NE555DR"}},
+                "shape": [
+                    "R~380~280~~~40~40~#880000~1~0~none~gge1~0",
+                    "P~show~1~1~370~290~180~gge2~0^^370~290^^M 370 290 h 10~#880000^^1~383~294~0~TRIG~start~~~#0000FF^^1~375~289~0~1~end~~~#0000FF"
+                ]
+            },
+            "packageDetail": {
+                "title": "SOIC-8_L4.9-W3.9-P1.27",
+                "dataStr": {
+                    "head": {"x": "4000", "y": "3000", "uuid_3d": "abc123"},
+                    "shape": ["PAD~RECT~3985~3000~6~2~1~~1~0~~0~gge3~0~~Y~0"]
+                }
+            },
+            "lcsc": {"number": "C7593"}
+        }}"#,
+    )
+    .unwrap();
+    fs::write(temp.path().join("abc123.step"), "ISO-10303-21;").unwrap();
+
+    let inspection = inspect_source(&json_path).unwrap();
+    assert_eq!(inspection.symbols(), ["NE555DR".to_string()]);
+    assert!(inspection.problems().is_empty());
+
+    let dest_steps = temp.path().join("steps");
+    let config = ImportConfig::new(
+        temp.path().join("dest.kicad_sym"),
+        temp.path().join("Dest.pretty"),
+        dest_steps.clone(),
+    );
+    let report = import_source(&json_path, &config, AddPolicy::ReplaceExisting).unwrap();
+    assert_eq!(report.symbols_added(), 1);
+    assert_eq!(report.footprints_added(), 1);
+    assert_eq!(report.models_associated(), 1);
+    assert_eq!(report.associations()[0].rule(), Some(AssociationRule::ExplicitProperty));
+    assert_eq!(
+        report.associations()[0].footprint(),
+        Some("Dest:SOIC-8_L4.9-W3.9-P1.27")
+    );
+    assert!(dest_steps.join("SOIC-8_L4.9-W3.9-P1.27.step").exists());
+    let lib = KicadSymbolLib::parse(&fs::read_to_string(temp.path().join("dest.kicad_sym")).unwrap())
+        .unwrap();
+    assert_eq!(
+        lib.symbol("NE555DR").unwrap().property_value("LCSC").as_deref(),
+        Some("C7593")
+    );
+}
+
+#[test]
+fn import_converts_altium_libraries() {
+    let temp = tempdir().unwrap();
+    let source = temp.path().join("altium");
+    fs::create_dir_all(&source).unwrap();
+    fs::write(
+        source.join("NE555.SchLib"),
+        [
+            "|HEADER=Protel for Windows - Schematic Library Editor Ascii File Version 5.0",
+            "|RECORD=1|LIBREFERENCE=NE555|PARTCOUNT=2",
+            "|RECORD=34|OWNERPARTID=-1|TEXT=U?",
+            "|RECORD=14|OWNERPARTID=1|LOCATION.X=0|LOCATION.Y=-20|CORNER.X=40|CORNER.Y=10",
+            "|RECORD=2|OWNERPARTID=1|LOCATION.X=0|LOCATION.Y=0|PINLENGTH=20|PINCONGLOMERATE=2|ELECTRICAL=0|NAME=TRIG|DESIGNATOR=2",
+            "|RECORD=45|MODELNAME=SOIC8|MODELTYPE=PCBLIB|ISCURRENT=T",
+        ]
+        .join("\r\n"),
+    )
+    .unwrap();
+
+    let subrecord = |bytes: &[u8]| {
+        let mut out = (bytes.len() as u32).to_le_bytes().to_vec();
+        out.extend(bytes);
+        out
+    };
+    let mut data = subrecord(b"\x05SOIC8");
+    data.push(2);
+    data.extend(subrecord(b"\x011"));
+    for _ in 0..3 {
+        data.extend(subrecord(&[0]));
+    }
+    let mut pad = vec![1u8];
+    pad.extend([0; 12]);
+    for value in [-106_000i32, 75_000, 60_000, 24_000, 0, 0, 0, 0, 0] {
+        pad.extend(value.to_le_bytes());
+    }
+    pad.extend([2, 2, 2]);
+    pad.extend(0f64.to_le_bytes());
+    pad.extend([1, 0, 0]);
+    data.extend(subrecord(&pad));
+    data.extend(subrecord(&[]));
+    data.push(4);
+    let mut track = vec![33u8];
+    track.extend([0; 12]);
+    for value in [-80_000i32, 100_000, 80_000, 100_000, 6_000] {
+        track.extend(value.to_le_bytes());
+    }
+    data.extend(subrecord(&track));
+    let mut pcblib = cfb::create(source.join("NE555.PcbLib")).unwrap();
+    pcblib.create_storage("/SOIC8").unwrap();
+    pcblib
+        .create_stream("/SOIC8/Data")
+        .unwrap()
+        .write_all(&data)
+        .unwrap();
+    pcblib.flush().unwrap();
+    drop(pcblib);
+    fs::write(source.join("SOIC8.step"), "ISO-10303-21;").unwrap();
+
+    let inspection = inspect_source(&source).unwrap();
+    assert_eq!(inspection.symbols(), ["NE555".to_string()]);
+    assert!(inspection.problems().is_empty());
+
+    let dest_fp = temp.path().join("Dest.pretty");
+    let config = ImportConfig::new(
+        temp.path().join("dest.kicad_sym"),
+        dest_fp.clone(),
+        temp.path().join("steps"),
+    );
+    let report = import_source(&source, &config, AddPolicy::ReplaceExisting).unwrap();
+    assert_eq!(report.symbols_added(), 1);
+    assert_eq!(report.footprints_added(), 1);
+    assert_eq!(report.models_associated(), 1);
+    assert_eq!(report.associations()[0].footprint(), Some("Dest:SOIC8"));
+    let footprint = fs::read_to_string(dest_fp.join("SOIC8.kicad_mod")).unwrap();
+    assert!(footprint.contains("(at -2.6924 -1.905)"));
+    assert!(footprint.contains("(size 1.524 0.6096)"));
+    assert!(footprint.contains("(start -2.032 -2.54)"));
+    let lib = KicadSymbolLib::parse(&fs::read_to_string(temp.path().join("dest.kicad_sym")).unwrap())
+        .unwrap();
+    assert_eq!(
+        lib.symbol("NE555").unwrap().property_value("Reference").as_deref(),
+        Some("U")
+    );
+}
+
+#[test]
+fn import_upgrades_legacy_kicad_libraries() {
+    let temp = tempdir().unwrap();
+    let source = temp.path().join("legacy");
+    fs::create_dir_all(&source).unwrap();
+    fs::write(
+        source.join("NE555.lib"),
+        "EESchema-LIBRARY Version 2.4\n\
+         DEF NE555 U 0 40 Y Y 1 F N\n\
+         F0 \"U\" -400 350 50 H V L CNN\n\
+         F1 \"NE555\" 0 350 50 H V L CNN\n\
+         F2 \"Package_DIP:DIP-8\" 0 0 50 H I C CNN\n\
+         DRAW\n\
+         S -400 -300 400 300 0 1 10 f\n\
+         X TR 2 -500 100 100 R 50 50 1 1 I\n\
+         ENDDRAW\n\
+         ENDDEF\n\
+         #End Library\n",
+    )
+    .unwrap();
+    fs::write(
+        source.join("NE555.mod"),
+        "PCBNEW-LibModule-V1\n\
+         $MODULE DIP-8\n\
+         Po 0 0 0 15 00000000 00000000 ~~\n\
+         $PAD\n\
+         Sh \"1\" R 600 600 0 0 0\n\
+         Dr 320 0 0\n\
+         At STD N 00E0FFFF\n\
+         Po -1500 -1500\n\
+         $EndPAD\n\
+         $EndMODULE DIP-8\n\
+         $EndLIBRARY\n",
+    )
+    .unwrap();
+    fs::write(source.join("DIP-8.step"), "ISO-10303-21;").unwrap();
+
+    let inspection = inspect_source(&source).unwrap();
+    assert_eq!(inspection.symbols(), ["NE555".to_string()]);
+    assert!(inspection.problems().is_empty());
+
+    let dest_fp = temp.path().join("Dest.pretty");
+    let config = ImportConfig::new(
+        temp.path().join("dest.kicad_sym"),
+        dest_fp.clone(),
+        temp.path().join("steps"),
+    );
+    let report = import_source(&source, &config, AddPolicy::ReplaceExisting).unwrap();
+    assert_eq!(report.symbols_added(), 1);
+    assert_eq!(report.footprints_added(), 1);
+    assert_eq!(report.models_associated(), 1);
+    assert_eq!(report.associations()[0].footprint(), Some("Dest:DIP-8"));
+    let footprint = fs::read_to_string(dest_fp.join("DIP-8.kicad_mod")).unwrap();
+    assert!(footprint.contains("(at -3.81 -3.81)"));
+    assert!(footprint.contains("(drill 0.8128)"));
+    assert!(!source.join("NE555.kicad_sym").exists());
+}
+
+#[test]
+fn import_takes_modern_files_from_ultra_librarian_export() {
+    let temp = tempdir().unwrap();
+    let zip_path = temp.path().join("ul_NE555.zip");
+    let file = fs::File::create(&zip_path).unwrap();
+    let mut zip = ZipWriter::new(file);
+    let options = FileOptions::default();
+    let entries: [(&str, &str); 6] = [
+        ("KiCAD/NE555.lib", "EESchema-LIBRARY Version 2.3"),
+        ("KiCAD/footprints.pretty/SOIC8.kicad_mod", "(module SOIC8 (layer F.Cu))"),
+        (
+            "KiCADv6/NE555.kicad_sym",
+            "(kicad_symbol_lib (version 20211014) (symbol \"NE555\" (property \"Footprint\" \"SOIC8\")))",
+        ),
+        ("KiCADv6/footprints.pretty/SOIC8.kicad_mod", "(footprint \"SOIC8\")"),
+        ("KiCADv6/footprints.pretty/SOIC8-M.kicad_mod", "(footprint \"SOIC8-M\")"),
+        ("STEP/SOIC8.step", "ISO-10303-21;"),
+    ];
+    for (name, content) in entries {
+        zip.start_file(name, options).unwrap();
+        zip.write_all(content.as_bytes()).unwrap();
+    }
+    zip.finish().unwrap();
+
+    let inspection = inspect_source(&zip_path).unwrap();
+    assert_eq!(inspection.vendor().name(), "Ultra Librarian");
+    assert_eq!(inspection.footprint_files().len(), 2);
+
+    let dest_fp = temp.path().join("Dest.pretty");
+    let config = ImportConfig::new(
+        temp.path().join("dest.kicad_sym"),
+        dest_fp.clone(),
+        temp.path().join("steps"),
+    );
+    let report = import_source(&zip_path, &config, AddPolicy::ReplaceExisting).unwrap();
+    assert_eq!(report.symbols_added(), 1);
+    assert_eq!(report.footprints_added(), 2);
+    assert_eq!(report.associations()[0].footprint(), Some("Dest:SOIC8"));
+    assert!(
+        fs::read_to_string(dest_fp.join("SOIC8.kicad_mod"))
+            .unwrap()
+            .starts_with("(footprint")
+    );
+}
+
+#[test]
+fn import_merges_concatenated_symbol_libraries() {
+    let temp = tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(&source).unwrap();
+    fs::write(
+        source.join("lib.kicad_sym"),
+        "(kicad_symbol_lib (version 20231120) (symbol \"A\" (property \"Footprint\" \"X:A\")))\n\
+         (kicad_symbol_lib (version 20231120) (symbol \"B\" (property \"Footprint\" \"X:B\")))",
+    )
+    .unwrap();
+    write_footprint(&source.join("A.kicad_mod"), "A");
+    write_footprint(&source.join("B.kicad_mod"), "B");
+
+    let config = ImportConfig::new(
+        temp.path().join("dest.kicad_sym"),
+        temp.path().join("Dest.pretty"),
+        temp.path().join("steps"),
+    );
+    let report = import_source(&source, &config, AddPolicy::ReplaceExisting).unwrap();
+    assert_eq!(report.symbols_added(), 2);
+    assert_eq!(
+        report.warnings(),
+        ["lib.kicad_sym: merged a second kicad_symbol_lib expression".to_string()]
+    );
+}
+
+#[test]
+fn import_relink_keeps_symbol_but_moves_footprint() {
+    let temp = tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(&source).unwrap();
+    write_symbol_lib(&source.join("lib.kicad_sym"), "PartA", "Vendor:PartA_FP");
+    write_footprint(&source.join("PartA_FP.kicad_mod"), "PartA_FP");
+
+    let dest_sym = temp.path().join("dest.kicad_sym");
+    let steps = temp.path().join("steps");
+    let config = ImportConfig::new(dest_sym.clone(), temp.path().join("Old.pretty"), steps.clone());
+    import_source(&source, &config, AddPolicy::ReplaceExisting).unwrap();
+    let mut lib = KicadSymbolLib::parse(&fs::read_to_string(&dest_sym).unwrap()).unwrap();
+    let mut symbol = lib.symbols().unwrap().remove(0);
+    symbol.set_or_add_property("Note", "mine");
+    lib.add_symbol(symbol, AddPolicy::ReplaceExisting).unwrap();
+    fs::write(&dest_sym, lib.to_string_pretty()).unwrap();
+
+    let config = ImportConfig::new(dest_sym.clone(), temp.path().join("New.pretty"), steps);
+    import_source(&source, &config, AddPolicy::RelinkExisting).unwrap();
+    let lib = KicadSymbolLib::parse(&fs::read_to_string(&dest_sym).unwrap()).unwrap();
+    let symbol = lib.symbols().unwrap().remove(0);
+    assert_eq!(symbol.property_value("Note").unwrap(), "mine");
+    assert_eq!(symbol.property_value("Footprint").unwrap(), "New:PartA_FP");
+}
+
+#[test]
+fn import_reports_association_rule_per_symbol() {
+    let temp = tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(&source).unwrap();
+    fs::write(
+        source.join("lib.kicad_sym"),
+        "(kicad_symbol_lib (version 20231120) (symbol \"PartA\" (property \"Footprint\" \"Vendor:B\")) (symbol \"A\" (property \"Footprint\" \"\")))",
+    )
+    .unwrap();
+    write_footprint(&source.join("Fp.pretty").join("A.kicad_mod"), "A");
+    write_footprint(&source.join("Fp.pretty").join("B.kicad_mod"), "B");
+
+    let config = ImportConfig::new(
+        temp.path().join("dest.kicad_sym"),
+        temp.path().join("Dest.pretty"),
+        temp.path().join("steps"),
+    );
+    let report = import_source(&source, &config, AddPolicy::ReplaceExisting).unwrap();
+    let associations = report.associations();
+    assert_eq!(associations.len(), 2);
+    assert_eq!(associations[0].symbol(), "PartA");
+    assert_eq!(associations[0].footprint(), Some("Dest:B"));
+    assert_eq!(associations[0].rule(), Some(AssociationRule::ExplicitProperty));
+    assert_eq!(associations[1].symbol(), "A");
+    assert_eq!(associations[1].rule(), Some(AssociationRule::NameMatch));
+    assert_eq!(associations[1].to_string(), "A -> Dest:A (name match, 100%)");
+}
+
+#[test]
+fn import_flags_low_confidence_fuzzy_match_for_review() {
+    let temp = tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(&source).unwrap();
+    write_symbol_lib(&source.join("lib.kicad_sym"), "LM7805", "");
+    write_footprint(&source.join("Fp.pretty").join("LM7805_TO220.kicad_mod"), "LM7805_TO220");
+    write_footprint(&source.join("Fp.pretty").join("Other.kicad_mod"), "Other");
+
+    let config = ImportConfig::new(
+        temp.path().join("dest.kicad_sym"),
+        temp.path().join("Dest.pretty"),
+        temp.path().join("steps"),
+    );
+    let report = import_source(&source, &config, AddPolicy::ReplaceExisting).unwrap();
+    let association = &report.associations()[0];
+    assert_eq!(association.rule(), Some(AssociationRule::Fuzzy));
+    assert_eq!(association.footprint(), Some("Dest:LM7805_TO220"));
+    assert!(association.needs_review());
+    assert_eq!(report.needs_review().count(), 1);
+
+    let config = config.with_association_threshold(0.5);
+    let report = import_source(&source, &config, AddPolicy::ReplaceExisting).unwrap();
+    assert_eq!(report.needs_review().count(), 0);
+}
+
+#[test]
+fn import_sources_attaches_step_from_other_archive() {
+    let temp = tempdir().unwrap();
+    let vendor_a = temp.path().join("vendor_a");
+    fs::create_dir_all(&vendor_a).unwrap();
+    write_symbol_lib(&vendor_a.join("lib.kicad_sym"), "PartA", "Vendor:SOIC-8");
+    fs::write(
+        vendor_a.join("SOIC-8.kicad_mod"),
+        "(footprint \"SOIC-8\" (layer \"F.Cu\") (model \"C:/vendor/models/soic8_rev3.step\" (offset (xyz 0 0 1))))",
+    )
+    .unwrap();
+    let vendor_b = temp.path().join("vendor_b");
+    fs::create_dir_all(&vendor_b).unwrap();
+    fs::write(vendor_b.join("soic8_rev3.step"), "ISO-10303-21;").unwrap();
+
+    let dest_fp = temp.path().join("Dest.pretty");
+    let dest_steps = temp.path().join("steps");
+    let config = ImportConfig::new(
+        temp.path().join("dest.kicad_sym"),
+        dest_fp.clone(),
+        dest_steps.clone(),
+    );
+    let report =
+        import_sources(&[vendor_a, vendor_b], &config, AddPolicy::ReplaceExisting).unwrap();
+    assert_eq!(report.symbols_added(), 1);
+    assert_eq!(report.step_files_added(), 1);
+    assert_eq!(report.models_associated(), 1);
+
+    let footprint = fs::read_to_string(dest_fp.join("SOIC-8.kicad_mod")).unwrap();
+    let model = dest_steps.join("soic8_rev3.step").display().to_string();
+    assert!(footprint.contains(&model.replace('\\', "/")));
+    assert!(footprint.contains("(xyz 0 0 1)"));
+}
+
+#[test]
+fn import_model_override_replaces_archive_model() {
+    let temp = tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(&source).unwrap();
+    write_symbol_lib(&source.join("lib.kicad_sym"), "QFN-16", "");
+    write_footprint(&source.join("Fp.pretty").join("QFN-16.kicad_mod"), "QFN-16");
+    write_footprint(&source.join("Fp.pretty").join("SOT-23.kicad_mod"), "SOT-23");
+    fs::write(source.join("QFN-16.step"), "ISO-10303-21;").unwrap();
+    let custom = temp.path().join("better.step");
+    fs::write(&custom, "ISO-10303-21;").unwrap();
+
+    let dest_fp = temp.path().join("Dest.pretty");
+    let dest_steps = temp.path().join("steps");
+    let config = ImportConfig::new(
+        temp.path().join("dest.kicad_sym"),
+        dest_fp.clone(),
+        dest_steps.clone(),
+    );
+
+    let ambiguous: ModelOverride = custom.display().to_string().parse().unwrap();
+    let err = import_source(
+        &source,
+        &config.clone().with_model_overrides(vec![ambiguous]),
+        AddPolicy::ReplaceExisting,
+    )
+    .unwrap_err();
+    assert!(matches!(err, ImportError::Association(_)));
+
+    let targeted: ModelOverride = format!("QFN-16={}", custom.display()).parse().unwrap();
+    let config = config.with_model_overrides(vec![targeted]);
+    let report = import_source(&source, &config, AddPolicy::ReplaceExisting).unwrap();
+    assert_eq!(report.models_associated(), 1);
+    assert_eq!(report.step_files_added(), 2);
+
+    let footprint = fs::read_to_string(dest_fp.join("QFN-16.kicad_mod")).unwrap();
+    assert!(footprint.contains("better.step"));
+    assert!(!footprint.contains("QFN-16.step"));
+    let other = fs::read_to_string(dest_fp.join("SOT-23.kicad_mod")).unwrap();
+    assert!(!other.contains("model"));
+}
+
+#[test]
+fn model_override_keeps_equals_signs_in_paths() {
+    let temp = tempdir().unwrap();
+    let existing = temp.path().join("R=0603.step");
+    fs::write(&existing, "ISO-10303-21;").unwrap();
+
+    let model: ModelOverride = existing.display().to_string().parse().unwrap();
+    assert_eq!(model.footprint(), None);
+    assert_eq!(model.path(), existing);
+
+    let model: ModelOverride = "models/a=b.step".parse().unwrap();
+    assert_eq!(model.footprint(), None);
+    assert_eq!(model.path(), Path::new("models/a=b.step"));
+
+    let model: ModelOverride = "QFN-16=models/a=b.step".parse().unwrap();
+    assert_eq!(model.footprint(), Some("QFN-16"));
+    assert_eq!(model.path(), Path::new("models/a=b.step"));
+    assert!("=models/a.step".parse::<ModelOverride>().is_err());
+}
+
+#[test]
+fn import_renames_steps_after_footprint() {
+    let temp = tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(&source).unwrap();
+    write_symbol_lib(&source.join("lib.kicad_sym"), "PartA", "");
+    write_footprint(&source.join("Fp.pretty").join("SOIC-8.kicad_mod"), "SOIC-8");
+    fs::write(source.join("SOIC 8 (rev C) 2023-01.STEP"), "ISO-10303-21;").unwrap();
+
+    let dest_fp = temp.path().join("Dest.pretty");
+    let dest_steps = temp.path().join("steps");
+    let config = ImportConfig::new(
+        temp.path().join("dest.kicad_sym"),
+        dest_fp.clone(),
+        dest_steps.clone(),
+    );
+
+    let sanitized = config.clone().with_step_naming(StepNaming::Sanitized);
+    import_source(&source, &sanitized, AddPolicy::ReplaceExisting).unwrap();
+    assert!(dest_steps.join("SOIC_8_rev_C_2023-01.step").exists());
+
+    let config = config.with_step_naming(StepNaming::Footprint);
+    import_source(&source, &config, AddPolicy::ReplaceExisting).unwrap();
+    assert!(dest_steps.join("SOIC-8.step").exists());
+    let footprint = fs::read_to_string(dest_fp.join("SOIC-8.kicad_mod")).unwrap();
+    assert!(footprint.contains("/SOIC-8.step\""));
+}
+
+#[test]
+fn import_keeps_same_named_steps_from_different_parts() {
+    let temp = tempdir().unwrap();
+    let mut sources = Vec::new();
+    for (part, content) in [("PartA", "model-a"), ("PartB", "model-b")] {
+        let source = temp.path().join(part);
+        fs::create_dir_all(&source).unwrap();
+        write_symbol_lib(&source.join("lib.kicad_sym"), part, &format!("Vendor:{}", part));
+        fs::write(
+            source.join(format!("{}.kicad_mod", part)),
+            format!("(footprint \"{}\" (model \"3d/model.step\"))", part),
+        )
+        .unwrap();
+        fs::write(source.join("model.step"), content).unwrap();
+        sources.push(source);
+    }
+
+    let dest_fp = temp.path().join("Dest.pretty");
+    let dest_steps = temp.path().join("steps");
+    let config = ImportConfig::new(
+        temp.path().join("dest.kicad_sym"),
+        dest_fp.clone(),
+        dest_steps.clone(),
+    );
+    let report = import_sources(&sources, &config, AddPolicy::ReplaceExisting).unwrap();
+    assert_eq!(report.models_associated(), 2);
+    assert_eq!(report.warnings().len(), 1);
+
+    let steps: Vec<_> = fs::read_dir(&dest_steps).unwrap().collect();
+    assert_eq!(steps.len(), 2);
+    for part in ["PartA", "PartB"] {
+        let footprint = fs::read_to_string(dest_fp.join(format!("{}.kicad_mod", part))).unwrap();
+        let model = footprint
+            .split('"')
+            .find(|value| value.ends_with(".step"))
+            .unwrap();
+        let file_name = model.rsplit('/').next().unwrap();
+        let expected = if part == "PartA" { "model-a" } else { "model-b" };
+        assert_eq!(fs::read_to_string(dest_steps.join(file_name)).unwrap(), expected);
+    }
+
+    import_sources(&sources, &config, AddPolicy::ReplaceExisting).unwrap();
+    assert_eq!(fs::read_dir(&dest_steps).unwrap().count(), 2);
+}
+
+#[test]
+fn import_copies_steps_in_parallel() {
+    let temp = tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(&source).unwrap();
+    write_symbol_lib(&source.join("lib.kicad_sym"), "PartA", "Vendor:FP-0");
+    let mut expected_bytes = 0;
+    for index in 0..10 {
+        let name = format!("FP-{}", index);
+        write_footprint(&source.join("Fp.pretty").join(format!("{}.kicad_mod", name)), &name);
+        let content = format!("ISO-10303-21; model {}", index);
+        expected_bytes += content.len() as u64;
+        fs::write(source.join(format!("{}.step", name)), content).unwrap();
+    }
+
+    let dest_steps = temp.path().join("steps");
+    fs::create_dir_all(&dest_steps).unwrap();
+    fs::write(dest_steps.join("FP-3.step"), "ISO-10303-21; older model").unwrap();
+    let config = ImportConfig::new(
+        temp.path().join("dest.kicad_sym"),
+        temp.path().join("Dest.pretty"),
+        dest_steps.clone(),
+    )
+    .with_copy_jobs(3);
+    assert_eq!(config.copy_jobs(), 3);
+    let report = import_source(&source, &config, AddPolicy::ReplaceExisting).unwrap();
+
+    assert_eq!(report.step_files_added(), 10);
+    assert_eq!(report.step_bytes_copied(), expected_bytes);
+    assert_eq!(fs::read_dir(&dest_steps).unwrap().count(), 11);
+    assert_eq!(
+        fs::read_to_string(dest_steps.join("FP-3.step")).unwrap(),
+        "ISO-10303-21; older model"
+    );
+    for index in 0..10 {
+        let footprint = fs::read_to_string(
+            temp.path()
+                .join("Dest.pretty")
+                .join(format!("FP-{}.kicad_mod", index)),
+        )
+        .unwrap();
+        let model = footprint
+            .split('"')
+            .find(|value| value.ends_with(".step"))
+            .unwrap();
+        let file_name = model.rsplit('/').next().unwrap();
+        assert_eq!(
+            fs::read_to_string(dest_steps.join(file_name)).unwrap(),
+            format!("ISO-10303-21; model {}", index)
+        );
+    }
+    assert_eq!(config.with_copy_jobs(0).copy_jobs(), 1);
+}
+
+#[test]
+fn import_applies_symbol_overrides() {
+    let temp = tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(&source).unwrap();
+    write_symbol_lib(&source.join("lib.kicad_sym"), "NE555P", "Vendor:DIP-8");
+    write_footprint(&source.join("Fp.pretty").join("DIP-8.kicad_mod"), "DIP-8");
+    write_footprint(&source.join("Fp.pretty").join("SOIC-8.kicad_mod"), "SOIC-8");
+    let config = ImportConfig::new(
+        temp.path().join("dest.kicad_sym"),
+        temp.path().join("Dest.pretty"),
+        temp.path().join("steps"),
+    );
+
+    let overrides = vec![SymbolOverride::new(None)
+        .with_rename(Some("NE555_TI"))
+        .with_footprint(Some("SOIC-8"))];
+    let report = import_source(
+        &source,
+        &config.clone().with_symbol_overrides(overrides),
+        AddPolicy::ReplaceExisting,
+    )
+    .unwrap();
+    assert_eq!(report.associations()[0].symbol(), "NE555_TI");
+    let lib =
+        KicadSymbolLib::parse(&fs::read_to_string(temp.path().join("dest.kicad_sym")).unwrap())
+            .unwrap();
+    assert!(lib.symbol("NE555P").is_none());
+    assert_eq!(
+        lib.symbol("NE555_TI").unwrap().property_value("Footprint").as_deref(),
+        Some("Dest:SOIC-8")
+    );
+
+    let missing = vec![SymbolOverride::new(None).with_footprint(Some("QFN-16"))];
+    assert!(matches!(
+        import_source(
+            &source,
+            &config.clone().with_symbol_overrides(missing),
+            AddPolicy::ReplaceExisting
+        ),
+        Err(ImportError::Association(_))
+    ));
+    let unknown = vec![SymbolOverride::new(Some("LM358")).with_rename(Some("X"))];
+    assert!(matches!(
+        import_source(
+            &source,
+            &config.with_symbol_overrides(unknown),
+            AddPolicy::ReplaceExisting
+        ),
+        Err(ImportError::InvalidSource(_))
+    ));
+}
+
+#[test]
+fn import_stores_models_per_component() {
+    let temp = tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(&source).unwrap();
+    write_symbol_lib(&source.join("lib.kicad_sym"), "PartA", "");
+    write_footprint(&source.join("Fp.pretty").join("SOIC-8.kicad_mod"), "SOIC-8");
+    fs::write(source.join("SOIC-8.step"), "ISO-10303-21;").unwrap();
+    fs::write(source.join("spare part.step"), "ISO-10303-21; spare").unwrap();
+
+    let dest_fp = temp.path().join("Dest.pretty");
+    let dest_steps = temp.path().join("steps");
+    let config = ImportConfig::new(
+        temp.path().join("dest.kicad_sym"),
+        dest_fp.clone(),
+        dest_steps.clone(),
+    )
+    .with_step_layout(StepLayout::Component);
+    let report = import_source(&source, &config, AddPolicy::ReplaceExisting).unwrap();
+    let expected_model = dest_steps.join("SOIC-8").join("SOIC-8.step");
+    assert_eq!(
+        report.footprint_models(),
+        [(
+            "SOIC-8".to_string(),
+            Some(expected_model.to_string_lossy().replace('\\', "/"))
+        )]
+    );
+
+    assert!(dest_steps.join("SOIC-8").join("SOIC-8.step").exists());
+    assert!(dest_steps.join("spare_part").join("spare part.step").exists());
+    let footprint = fs::read_to_string(dest_fp.join("SOIC-8.kicad_mod")).unwrap();
+    assert!(footprint.contains("/SOIC-8/SOIC-8.step\""));
+}
+
+#[test]
+fn import_writes_model_store_variable_paths() {
+    let temp = tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(&source).unwrap();
+    write_symbol_lib(&source.join("lib.kicad_sym"), "PartA", "");
+    write_footprint(&source.join("Fp.pretty").join("SOIC-8.kicad_mod"), "SOIC-8");
+    fs::write(source.join("SOIC-8.step"), "ISO-10303-21;").unwrap();
+
+    let dest_fp = temp.path().join("Dest.pretty");
+    let store = ModelStore::new("s3://models/kicad")
+        .unwrap()
+        .with_variable(Some("COMPANY_3D".to_string()));
+    let config = ImportConfig::new(
+        temp.path().join("dest.kicad_sym"),
+        dest_fp.clone(),
+        temp.path().join("steps"),
+    )
+    .with_model_store(Some(store));
+    let report = import_source(&source, &config, AddPolicy::ReplaceExisting).unwrap();
+    assert_eq!(report.copied_steps(), ["SOIC-8.step".to_string()]);
+    let footprint = fs::read_to_string(dest_fp.join("SOIC-8.kicad_mod")).unwrap();
+    assert!(footprint.contains("\"${COMPANY_3D}/SOIC-8.step\""));
+}
+
+#[test]
+fn import_skips_models_over_size_budget() {
+    let temp = tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(&source).unwrap();
+    write_symbol_lib(&source.join("lib.kicad_sym"), "PartA", "");
+    write_footprint(&source.join("Fp.pretty").join("SOIC-8.kicad_mod"), "SOIC-8");
+    fs::write(source.join("SOIC-8.step"), vec![b'x'; 2 * 1024 * 1024]).unwrap();
+
+    let dest_steps = temp.path().join("steps");
+    let config = ImportConfig::new(
+        temp.path().join("dest.kicad_sym"),
+        temp.path().join("Dest.pretty"),
+        dest_steps.clone(),
+    )
+    .with_model_size_limit_mb(1.0);
+    let report = import_source(&source, &config, AddPolicy::ReplaceExisting).unwrap();
+    assert_eq!(report.step_files_added(), 1);
+    assert!(report
+        .warnings()
+        .iter()
+        .any(|warning| warning.contains("SOIC-8.step is 2.0 MB, above the 1 MB budget")));
+
+    fs::remove_dir_all(&dest_steps).unwrap();
+    let config = config.with_oversize_policy(OversizePolicy::Skip);
+    let report = import_source(&source, &config, AddPolicy::ReplaceExisting).unwrap();
+    assert_eq!(report.step_files_added(), 0);
+    assert_eq!(report.models_associated(), 0);
+    assert!(report
+        .warnings()
+        .iter()
+        .any(|warning| warning.starts_with("skipped model SOIC-8.step")));
+    assert!(!dest_steps.join("SOIC-8.step").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn import_simplifies_models_over_size_budget() {
+    let temp = tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(&source).unwrap();
+    write_symbol_lib(&source.join("lib.kicad_sym"), "PartA", "");
+    write_footprint(&source.join("Fp.pretty").join("SOIC-8.kicad_mod"), "SOIC-8");
+    fs::write(source.join("SOIC-8.step"), vec![b'x'; 2 * 1024 * 1024]).unwrap();
+
+    let dest_steps = temp.path().join("steps");
+    let command = SimplifyCommand::new("head -c 1000 {input} > {output}").unwrap();
+    let config = ImportConfig::new(
+        temp.path().join("dest.kicad_sym"),
+        temp.path().join("Dest.pretty"),
+        dest_steps.clone(),
+    )
+    .with_model_size_limit_mb(1.0)
+    .with_oversize_policy(OversizePolicy::Simplify)
+    .with_simplify_command(Some(command));
+    let report = import_source(&source, &config, AddPolicy::ReplaceExisting).unwrap();
+    assert_eq!(report.models_associated(), 1);
+    assert!(report.warnings().is_empty());
+    assert_eq!(report.simplifications().len(), 1);
+    assert_eq!(report.simplifications()[0].original_size(), 2 * 1024 * 1024);
+    assert_eq!(report.simplifications()[0].reduced_size(), 1000);
+    assert_eq!(fs::metadata(dest_steps.join("SOIC-8.step")).unwrap().len(), 1000);
+
+    let report = import_source(&source, &config, AddPolicy::ReplaceExisting).unwrap();
+    assert!(report.warnings().is_empty());
+}
+
+#[test]
+fn inspect_source_predicts_problems_without_writing() {
+    let temp = tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(&source).unwrap();
+    write_symbol_lib(&source.join("lib.kicad_sym"), "PartA", "");
+    write_footprint(&source.join("Fp.pretty").join("A.kicad_mod"), "A");
+    write_footprint(&source.join("Fp.pretty").join("B.kicad_mod"), "B");
+    fs::write(source.join("readme.txt"), "hello").unwrap();
+
+    let inspection = inspect_source(&source).unwrap();
+    assert_eq!(inspection.symbols(), ["PartA".to_string()]);
+    assert_eq!(inspection.footprints().len(), 2);
+    assert_eq!(inspection.other_files(), [Path::new("readme.txt").to_path_buf()]);
+    assert!(inspection
+        .problems()
+        .iter()
+        .any(|problem| problem == "no footprint can be chosen for symbol PartA"));
+    assert!(inspection
+        .problems()
+        .iter()
+        .any(|problem| problem == "no 3D models found"));
+    assert_eq!(fs::read_dir(temp.path()).unwrap().count(), 1);
+}
+
+#[test]
+fn import_into_staging_leaves_project_untouched() {
+    let temp = tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(&source).unwrap();
+    write_symbol_lib(&source.join("lib.kicad_sym"), "PartA", "");
+    write_footprint(
+        &source.join("Footprints.pretty").join("MyFootprint.kicad_mod"),
+        "MyFootprint",
+    );
+    let project = temp.path().join("project");
+    fs::create_dir_all(&project).unwrap();
+    let dest_sym = project.join("dest.kicad_sym");
+    fs::write(&dest_sym, "(kicad_symbol_lib (version 20231120))").unwrap();
+    let config = ImportConfig::new(
+        dest_sym.clone(),
+        project.join("Dest.pretty"),
+        project.join("steps"),
+    );
+
+    let staging = Staging::new(&project, &config).unwrap();
+    let report = import_source(&source, staging.config(), AddPolicy::ReplaceExisting).unwrap();
+    assert_eq!(report.symbols_added(), 1);
+
+    let changes = staging.changes().unwrap();
+    assert_eq!(changes.len(), 2);
+    assert!(changes[0].shows_diff());
+    let diff = changes[0].unified_diff();
+    assert!(diff.starts_with("--- a/"));
+    assert!(diff.contains("\"PartA\""));
+    assert!(changes[1].is_new());
+    assert!(changes[1].path().ends_with("Dest.pretty/MyFootprint.kicad_mod"));
+    assert_eq!(
+        fs::read_to_string(&dest_sym).unwrap(),
+        "(kicad_symbol_lib (version 20231120))"
+    );
+    assert!(!project.join("Dest.pretty").exists());
+}
+
+#[test]
+fn staging_reads_existing_models_in_place() {
+    let temp = tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(&source).unwrap();
+    write_symbol_lib(&source.join("lib.kicad_sym"), "PartA", "");
+    write_footprint(&source.join("Part.kicad_mod"), "Part");
+    fs::write(source.join("Part.step"), "new model").unwrap();
+    let project = temp.path().join("project");
+    fs::create_dir_all(project.join("steps")).unwrap();
+    fs::write(project.join("steps").join("Part.step"), "old model").unwrap();
+    fs::write(project.join("steps").join("Other.step"), "unrelated").unwrap();
+    let config = ImportConfig::new(
+        project.join("dest.kicad_sym"),
+        project.join("Dest.pretty"),
+        project.join("steps"),
+    );
+
+    let staging = Staging::new(&project, &config).unwrap();
+    let staged_steps = staging.staged_path(config.step_dir());
+    assert_eq!(fs::read_dir(&staged_steps).unwrap().count(), 0);
+    let report = import_source(&source, staging.config(), AddPolicy::ReplaceExisting).unwrap();
+    assert!(
+        report
+            .warnings()
+            .iter()
+            .any(|warning| warning.contains("differs from an existing model"))
+    );
+    assert!(!staged_steps.join("Other.step").exists());
+    let steps: Vec<_> = staging
+        .changes()
+        .unwrap()
+        .into_iter()
+        .filter(|change| change.path().starts_with("steps"))
+        .collect();
+    assert_eq!(steps.len(), 1);
+    assert!(steps[0].is_new());
+    let hash = sha256_file(&source.join("Part.step")).unwrap();
+    assert_eq!(
+        steps[0].path().file_name().unwrap(),
+        format!("Part-{}.step", &hash[..8]).as_str()
+    );
+}
+
+#[test]
+fn import_extracts_into_configured_temp_dir_and_cleans_up() {
+    let temp = tempdir().unwrap();
+    let scratch = temp.path().join("scratch");
+    let zip_path = temp.path().join("source.zip");
+    let mut zip = ZipWriter::new(fs::File::create(&zip_path).unwrap());
+    zip.start_file("lib.kicad_sym", FileOptions::default()).unwrap();
+    zip.write_all(b"(kicad_symbol_lib (version 20231120) (symbol \"PartA\"))")
+        .unwrap();
+    zip.finish().unwrap();
+    let config = ImportConfig::new(
+        temp.path().join("dest.kicad_sym"),
+        temp.path().join("Dest.pretty"),
+        temp.path().join("steps"),
+    )
+    .with_temp_dir(Some(scratch.clone()));
+
+    let result = import_source(&zip_path, &config, AddPolicy::ReplaceExisting);
+    assert!(matches!(result, Err(ImportError::MissingFootprints)));
+    assert!(scratch.is_dir());
+    assert_eq!(fs::read_dir(&scratch).unwrap().count(), 0);
+
+    let report = import_source(
+        &zip_path,
+        &config.with_allow_partial(true),
+        AddPolicy::ReplaceExisting,
+    )
+    .unwrap();
+    assert_eq!(report.symbols_added(), 1);
+    assert_eq!(fs::read_dir(&scratch).unwrap().count(), 0);
+}
+
+#[test]
+fn symbols_only_import_never_reads_model_entries() {
+    let temp = tempdir().unwrap();
+    let zip_path = temp.path().join("source.zip");
+    let mut zip = ZipWriter::new(fs::File::create(&zip_path).unwrap());
+    let stored = FileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    zip.start_file("KiCad/lib.kicad_sym", stored).unwrap();
+    zip.write_all(b"(kicad_symbol_lib (version 20231120) (symbol \"PartA\"))")
+        .unwrap();
+    zip.start_file("3D/PartA.step", stored).unwrap();
+    zip.write_all(b"ISO-10303-21; model body").unwrap();
+    zip.finish().unwrap();
+    let mut bytes = fs::read(&zip_path).unwrap();
+    let body = bytes
+        .windows(10)
+        .position(|window| window == b"model body")
+        .unwrap();
+    bytes[body] = b'M';
+    fs::write(&zip_path, bytes).unwrap();
+    let config = ImportConfig::new(
+        temp.path().join("dest.kicad_sym"),
+        temp.path().join("Dest.pretty"),
+        temp.path().join("steps"),
+    );
+
+    let full = import_source(
+        &zip_path,
+        &config.clone().with_allow_partial(true),
+        AddPolicy::ReplaceExisting,
+    );
+    assert!(matches!(full, Err(ImportError::Io(_))));
+    let report = import_source(
+        &zip_path,
+        &config.with_symbols_only(true),
+        AddPolicy::ReplaceExisting,
+    )
+    .unwrap();
+    assert_eq!(report.symbols_added(), 1);
+    assert_eq!(report.step_files_added(), 0);
+}
+
+#[test]
+fn footprints_only_import_never_reads_symbol_entries() {
+    let temp = tempdir().unwrap();
+    let zip_path = temp.path().join("source.zip");
+    let mut zip = ZipWriter::new(fs::File::create(&zip_path).unwrap());
+    let stored = FileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    zip.start_file("KiCad/lib.kicad_sym", stored).unwrap();
+    zip.write_all(b"(kicad_symbol_lib (version 20231120) (symbol \"symbol body\"))")
+        .unwrap();
+    zip.start_file("KiCad/PartA.kicad_mod", stored).unwrap();
+    zip.write_all(b"(footprint \"PartA\" (model \"3D/PartA.step\"))")
+        .unwrap();
+    zip.start_file("3D/PartA.step", stored).unwrap();
+    zip.write_all(b"ISO-10303-21;").unwrap();
+    zip.finish().unwrap();
+    let mut bytes = fs::read(&zip_path).unwrap();
+    let body = bytes
+        .windows(11)
+        .position(|window| window == b"symbol body")
+        .unwrap();
+    bytes[body] = b'S';
+    fs::write(&zip_path, bytes).unwrap();
+    let config = ImportConfig::new(
+        temp.path().join("dest.kicad_sym"),
+        temp.path().join("Dest.pretty"),
+        temp.path().join("steps"),
+    );
+
+    let full = import_source(&zip_path, &config, AddPolicy::ReplaceExisting);
+    assert!(matches!(full, Err(ImportError::Io(_))));
+    let report = import_source(
+        &zip_path,
+        &config.with_footprints_only(true),
+        AddPolicy::ReplaceExisting,
+    )
+    .unwrap();
+    assert_eq!(report.symbols_added(), 0);
+    assert_eq!(report.footprints_added(), 1);
+    assert_eq!(report.step_files_added(), 1);
+    assert!(!temp.path().join("dest.kicad_sym").exists());
+}
+
+#[test]
+fn resumed_import_finishes_interrupted_copies() {
+    let temp = tempdir().unwrap();
+    let source = temp.path().join("vendor");
+    fs::create_dir_all(&source).unwrap();
+    fs::write(
+        source.join("lib.kicad_sym"),
+        "(kicad_symbol_lib (version 20231120) \
+         (symbol \"PartA\" (property \"Footprint\" \"Vendor:PartA\")) \
+         (symbol \"PartB\" (property \"Footprint\" \"Vendor:PartB\")))",
+    )
+    .unwrap();
+    for (part, step) in [("PartA", "a.step"), ("PartB", "b.step")] {
+        fs::write(
+            source.join(format!("{}.kicad_mod", part)),
+            format!("(footprint \"{}\" (model \"3d/{}\"))", part, step),
+        )
+        .unwrap();
+        fs::write(source.join(step), format!("model {}", part)).unwrap();
+    }
+
+    let dest_sym = temp.path().join("dest.kicad_sym");
+    let dest_steps = temp.path().join("steps");
+    let config = ImportConfig::new(
+        dest_sym.clone(),
+        temp.path().join("Dest.pretty"),
+        dest_steps.clone(),
+    );
+    import_source(&source, &config, AddPolicy::ErrorOnConflict).unwrap();
+    let symbols = fs::read_to_string(&dest_sym).unwrap();
+    fs::write(dest_steps.join("b.step"), "mod").unwrap();
+
+    let mut journal = ImportJournal::new(toml::Value::Table(Default::default()));
+    journal.set_symbols_added(2);
+    journal.set_step_files(vec!["a.step".to_string(), "b.step".to_string()]);
+    let report = import_source(
+        &source,
+        &config.with_journal(Some(journal)),
+        AddPolicy::ErrorOnConflict,
+    )
+    .unwrap();
+    assert_eq!(report.symbols_added(), 2);
+    assert_eq!(report.step_files_added(), 1);
+    assert!(report.warnings().is_empty(), "{:?}", report.warnings());
+    assert_eq!(fs::read_to_string(&dest_sym).unwrap(), symbols);
+    assert_eq!(fs::read_dir(&dest_steps).unwrap().count(), 2);
+    assert_eq!(
+        fs::read_to_string(dest_steps.join("b.step")).unwrap(),
+        "model PartB"
+    );
+}
+
+#[test]
+fn import_dir_skips_gitignored_files() {
+    let temp = tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(source.join("build")).unwrap();
+    fs::write(source.join(".gitignore"), "build/\n").unwrap();
+    fs::write(source.join(".kciignore"), "scratch.kicad_mod\n").unwrap();
+    write_symbol_lib(&source.join("lib.kicad_sym"), "PartA", "");
+    write_symbol_lib(&source.join("build").join("old.kicad_sym"), "Stale", "");
+    write_footprint(&source.join("build").join("Stale.kicad_mod"), "Stale");
+    write_footprint(&source.join("scratch.kicad_mod"), "Scratch");
+    write_footprint(&source.join("Parts.pretty").join("MyFootprint.kicad_mod"), "MyFootprint");
+
+    let dest_sym = temp.path().join("dest.kicad_sym");
+    let dest_fp = temp.path().join("Dest.pretty");
+    let config = ImportConfig::new(dest_sym.clone(), dest_fp.clone(), temp.path().join("steps"));
+
+    let report = import_source(&source, &config, AddPolicy::ErrorOnConflict).unwrap();
+    assert_eq!(report.symbols_added(), 1);
+    assert_eq!(report.footprints_added(), 1);
+    assert!(report.warnings().iter().any(|warning| warning.contains("skipped 3 file(s)")));
+    assert_eq!(read_symbol_footprint(&dest_sym), "Dest:MyFootprint");
+    assert!(!dest_fp.join("Stale.kicad_mod").exists());
+    assert!(!dest_fp.join("Scratch.kicad_mod").exists());
+}
+
+#[test]
+fn import_checks_and_fixes_paste_and_mask() {
+    let temp = tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(&source).unwrap();
+    write_symbol_lib(&source.join("lib.kicad_sym"), "PartA", "SOT-23");
+    let footprint = r#"(footprint "SOT-23"
+        (pad "1" smd rect (at -1 0.95) (size 0.9 0.8) (layers "F.Cu" "F.Mask"))
+        (pad "2" smd rect (at -1 -0.95) (size 0.9 0.8) (layers "F.Cu" "F.Paste" "F.Mask"))
+        (pad "3" smd rect (at 1 0) (size 0.9 0.8) (layers "F.Cu" "F.Paste" "F.Mask")
+            (solder_mask_margin -0.05)))"#;
+    fs::write(source.join("SOT-23.kicad_mod"), footprint).unwrap();
+
+    let dest_fp = temp.path().join("Dest.pretty");
+    let config = ImportConfig::new(
+        temp.path().join("dest.kicad_sym"),
+        dest_fp.clone(),
+        temp.path().join("steps"),
+    );
+    let report = import_source(&source, &config, AddPolicy::ReplaceExisting).unwrap();
+    let warnings: Vec<&String> = report
+        .warnings()
+        .iter()
+        .filter(|warning| warning.starts_with("SOT-23: "))
+        .collect();
+    assert_eq!(warnings.len(), 2, "{:?}", report.warnings());
+    assert!(report.pads_fixed().is_empty());
+    assert_eq!(fs::read_to_string(dest_fp.join("SOT-23.kicad_mod")).unwrap(), footprint);
+
+    let config = config.with_pad_policy(PadPolicy::Fix);
+    let report = import_source(&source, &config, AddPolicy::ReplaceExisting).unwrap();
+    let fixed: Vec<String> = report
+        .pads_fixed()
+        .iter()
+        .map(|(footprint, problem)| format!("{}: {}", footprint, problem))
+        .collect();
+    assert_eq!(
+        fixed,
+        [
+            "SOT-23: SMD pad 1 has no solder paste",
+            "SOT-23: pad 3 is solder-mask defined (mask margin -0.05 mm)"
+        ]
+    );
+    let content = fs::read_to_string(dest_fp.join("SOT-23.kicad_mod")).unwrap();
+    assert_eq!(content.matches("\"F.Paste\"").count(), 3, "{}", content);
+    assert!(!content.contains("solder_mask_margin"), "{}", content);
+}